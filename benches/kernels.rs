@@ -0,0 +1,249 @@
+//! Timings of the hot numerical loops.
+//!
+//! ```text
+//! cargo bench --no-default-features --bench kernels
+//! cargo bench --no-default-features --bench kernels -- --save main.txt
+//! cargo bench --no-default-features --bench kernels -- --baseline main.txt --tolerance 0.1
+//! ```
+//!
+//! Each case reports the median time per call over several samples. With
+//! `--save` the medians are written to a file; with `--baseline` they are
+//! compared with a saved file, and the run fails if any case is slower than
+//! the baseline by more than `--tolerance` (a fraction, default 0.1).
+//! `--filter` runs only the cases whose name contains the given text.
+
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use rustflow::kernels::{axpy, sum, window_sums};
+use rustflow::reach_routing::muskingum::muskingum_cascade_rs;
+use rustflow::runoff::unit_hydrograph::convolve;
+
+const SAMPLES: usize = 15;
+const SAMPLE_TIME: Duration = Duration::from_millis(40);
+
+struct Options {
+    filter: Option<String>,
+    save: Option<String>,
+    baseline: Option<String>,
+    tolerance: f64,
+}
+
+fn options() -> Result<Options, String> {
+    let mut options = Options {
+        filter: None,
+        save: None,
+        baseline: None,
+        tolerance: 0.1,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("`{arg}` needs a value"));
+        match arg.as_str() {
+            "--filter" => options.filter = Some(value()?),
+            "--save" => options.save = Some(value()?),
+            "--baseline" => options.baseline = Some(value()?),
+            "--tolerance" => {
+                options.tolerance = value()?
+                    .parse()
+                    .map_err(|_| "`--tolerance` must be a number".to_string())?
+            }
+            // Passed by `cargo bench`.
+            "--bench" => {}
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+    }
+    Ok(options)
+}
+
+/// Median nanoseconds per call of `f`.
+fn time(mut f: impl FnMut()) -> f64 {
+    // Calibrate the number of calls per sample.
+    let mut calls = 1u32;
+    loop {
+        let start = Instant::now();
+        for _ in 0..calls {
+            f();
+        }
+        if start.elapsed() >= SAMPLE_TIME / 4 || calls >= 1 << 24 {
+            break;
+        }
+        calls *= 2;
+    }
+    let calls = calls * 4;
+    let mut samples: Vec<f64> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..calls {
+                f();
+            }
+            start.elapsed().as_nanos() as f64 / f64::from(calls)
+        })
+        .collect();
+    samples.sort_by(f64::total_cmp);
+    samples[SAMPLES / 2]
+}
+
+/// Deterministic, rain-like test series: mostly zeros with bursts.
+fn series(n: usize, seed: u64) -> Vec<f64> {
+    let mut state = seed;
+    (0..n)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let u = (state >> 11) as f64 / (1u64 << 53) as f64;
+            if u < 0.7 {
+                0.0
+            } else {
+                u * 0.01
+            }
+        })
+        .collect()
+}
+
+/// A named benchmark case.
+type Case = (&'static str, Box<dyn FnMut()>);
+
+fn cases() -> Vec<Case> {
+    let hourly_year = series(8760, 1);
+    let daily_century = series(36525, 2);
+    let ordinates: Vec<f64> = (0..240)
+        .map(|k| (k as f64 / 24.0) * (-(k as f64) / 24.0).exp())
+        .collect();
+    let short_ordinates = ordinates[..24].to_vec();
+    let flows: Vec<f64> = daily_century.iter().map(|p| 5.0 + 1e3 * p).collect();
+    let mut y = vec![0.0; 4096];
+    let x = series(4096, 3);
+
+    let excess_short = hourly_year.clone();
+    let flows_30 = flows.clone();
+    let flows_routed = flows.clone();
+    vec![
+        (
+            "axpy_4096",
+            Box::new(move || axpy(black_box(&mut y), 0.5, black_box(&x))),
+        ),
+        (
+            "sum_36525",
+            Box::new(move || {
+                black_box(sum(black_box(&flows)));
+            }),
+        ),
+        (
+            "convolve_8760x240",
+            Box::new(move || {
+                black_box(convolve(black_box(&hourly_year), black_box(&ordinates)));
+            }),
+        ),
+        (
+            "convolve_8760x24",
+            Box::new(move || {
+                black_box(convolve(
+                    black_box(&excess_short),
+                    black_box(&short_ordinates),
+                ));
+            }),
+        ),
+        (
+            "moving_mean_30d_36525",
+            Box::new(move || {
+                black_box(window_sums(black_box(&flows_30), 30));
+            }),
+        ),
+        (
+            "muskingum_36525x3",
+            Box::new(move || {
+                black_box(muskingum_cascade_rs(
+                    black_box(&flows_routed),
+                    86400.0,
+                    2.0 * 86400.0,
+                    0.2,
+                    3,
+                    None,
+                ));
+            }),
+        ),
+    ]
+}
+
+fn read_baseline(path: &str) -> Result<BTreeMap<String, f64>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (name, ns) = line
+                .split_once(char::is_whitespace)
+                .ok_or(format!("{path}: malformed line `{line}`"))?;
+            let ns = ns
+                .trim()
+                .parse()
+                .map_err(|_| format!("{path}: malformed line `{line}`"))?;
+            Ok((name.to_string(), ns))
+        })
+        .collect()
+}
+
+fn main() -> ExitCode {
+    let options = match options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let baseline = match options.baseline.as_deref().map(read_baseline).transpose() {
+        Ok(baseline) => baseline,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut results = Vec::new();
+    let mut regressions = 0;
+    for (name, f) in cases() {
+        if options
+            .filter
+            .as_ref()
+            .is_some_and(|filter| !name.contains(filter.as_str()))
+        {
+            continue;
+        }
+        let ns = time(f);
+        let comparison = match baseline.as_ref().and_then(|b| b.get(name)) {
+            Some(&old) => {
+                let change = ns / old - 1.0;
+                let regressed = change > options.tolerance;
+                regressions += usize::from(regressed);
+                format!(
+                    "  {:+.1}% vs baseline{}",
+                    100.0 * change,
+                    if regressed { "  REGRESSION" } else { "" }
+                )
+            }
+            None => String::new(),
+        };
+        println!("{name:<24} {ns:>14.1} ns{comparison}");
+        results.push((name, ns));
+    }
+    if let Some(path) = &options.save {
+        let text: String = results
+            .iter()
+            .map(|(name, ns)| format!("{name} {ns:.1}\n"))
+            .collect();
+        if let Err(e) = std::fs::write(path, text) {
+            eprintln!("error: {path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    }
+    if regressions > 0 {
+        eprintln!(
+            "{regressions} case(s) slower than the baseline by more than {:.0}%",
+            100.0 * options.tolerance
+        );
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
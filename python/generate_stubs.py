@@ -0,0 +1,358 @@
+"""
+Generates type stubs for the compiled `rustflow.rustflow` extension module.
+
+Run after building the extension, e.g. with `maturin develop`:
+
+    python python/generate_stubs.py          # write the stubs
+    python python/generate_stubs.py --check  # fail if they are out of date
+
+The stubs are written to `python/rustflow/rustflow/`, one file per
+extension submodule, where type checkers find them in place of the
+compiled module. Signatures come from the `__text_signature__` that pyo3
+gives every function and method. Types come from the Google-style
+docstrings of the Rust classes (`Args:` and `Returns:` sections, and the
+leading `type:` of property docstrings) and, for functions, from the
+annotations of the Python wrapper of the same name. Anything else is
+`Any`.
+"""
+
+import argparse
+import ast
+import collections.abc
+import datetime
+import inspect
+import os
+import re
+import sys
+import types
+import typing
+from pathlib import Path
+
+import rustflow.rustflow as native
+
+PACKAGE = Path(__file__).parent / "rustflow"
+OUTPUT = PACKAGE / "rustflow"
+
+HEADER = """\
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+"""
+
+# Names the header makes available to annotations.
+NAMESPACE = {
+    "Any": typing.Any,
+    "Callable": collections.abc.Callable,
+    "Literal": typing.Literal,
+    "Mapping": collections.abc.Mapping,
+    "Optional": typing.Optional,
+    "PathLike": os.PathLike,
+    "Sequence": collections.abc.Sequence,
+    "Union": typing.Union,
+    "datetime": datetime.datetime,
+    "timedelta": datetime.timedelta,
+}
+
+ARG = re.compile(r"^\s+(\*{0,2}\w+) \((.+)\):")
+
+
+def submodules(module: types.ModuleType) -> dict[str, types.ModuleType]:
+    return {
+        name: value
+        for name, value in sorted(vars(module).items())
+        if isinstance(value, types.ModuleType)
+    }
+
+
+def classes(module: types.ModuleType) -> dict[str, type]:
+    return {
+        name: value
+        for name, value in sorted(vars(module).items())
+        if isinstance(value, type) and not name.startswith("_")
+    }
+
+
+def valid(annotation: str, namespace: dict) -> bool:
+    """Whether `annotation` evaluates with the names available to a stub."""
+    try:
+        eval(annotation, dict(namespace))
+    except Exception:
+        return False
+    return True
+
+
+def section(doc: str, title: str) -> list[str]:
+    """Lines of a Google-style docstring section, without the heading."""
+    lines = inspect.cleandoc(doc or "").splitlines()
+    try:
+        start = lines.index(f"{title}:") + 1
+    except ValueError:
+        return []
+    body = []
+    for line in lines[start:]:
+        if line and not line.startswith(" "):
+            break
+        body.append(line)
+    return body
+
+
+def documented_arguments(doc: str) -> dict[str, str]:
+    arguments = {}
+    for line in section(doc, "Args"):
+        match = ARG.match(line)
+        if match:
+            name, kind = match.groups()
+            arguments[name.lstrip("*")] = kind.removesuffix(", optional")
+    return arguments
+
+
+def documented_return(doc: str) -> str | None:
+    for line in section(doc, "Returns"):
+        if line.strip():
+            kind, _, _ = line.strip().partition(": ")
+            return kind
+    return None
+
+
+def documented_property(doc: str) -> str | None:
+    kind, separator, _ = inspect.cleandoc(doc or "").partition(": ")
+    return kind if separator and "\n" not in kind else None
+
+
+class Wrappers:
+    """Annotations of the Python wrapper functions, keyed by function name,
+    with module-level type aliases substituted."""
+
+    def __init__(self, package: Path):
+        self.functions: dict[str, dict[str, str]] = {}
+        for path in sorted(package.rglob("*.py")):
+            tree = ast.parse(path.read_text())
+            aliases = {
+                node.targets[0].id: node.value
+                for node in tree.body
+                if isinstance(node, ast.Assign)
+                and len(node.targets) == 1
+                and isinstance(node.targets[0], ast.Name)
+                and isinstance(node.value, ast.Subscript)
+            }
+            for node in tree.body:
+                if isinstance(node, ast.FunctionDef) and not node.name.startswith("_"):
+                    arguments = node.args.posonlyargs + node.args.args + node.args.kwonlyargs
+                    self.functions.setdefault(
+                        node.name,
+                        {
+                            argument.arg: ast.unparse(Expand(aliases).visit(argument.annotation))
+                            for argument in arguments
+                            if argument.annotation is not None
+                        },
+                    )
+
+    def arguments(self, name: str) -> dict[str, str]:
+        return self.functions.get(name, {})
+
+
+class Expand(ast.NodeTransformer):
+    def __init__(self, aliases: dict[str, ast.expr]):
+        self.aliases = aliases
+
+    def visit_Name(self, node: ast.Name) -> ast.expr:
+        return self.aliases.get(node.id, node)
+
+
+def allows_none(annotation: str, namespace: dict) -> bool:
+    value = eval(annotation, dict(namespace))
+    return value is None or type(None) in typing.get_args(value)
+
+
+def default(value) -> str:
+    """Default as written in a stub: literals as they are, others `...`."""
+    if value is None or isinstance(value, (bool, int, float, str)):
+        return repr(value)
+    return "..."
+
+
+def parameters(
+    signature: inspect.Signature, kinds: dict[str, str], namespace: dict, skip_first: bool
+) -> str:
+    out = []
+    previous = None
+    for i, parameter in enumerate(signature.parameters.values()):
+        if skip_first and i == 0:
+            continue
+        if (
+            previous == inspect.Parameter.POSITIONAL_ONLY
+            and parameter.kind != inspect.Parameter.POSITIONAL_ONLY
+        ):
+            out.append("/")
+        if parameter.kind == inspect.Parameter.KEYWORD_ONLY and previous not in (
+            inspect.Parameter.KEYWORD_ONLY,
+            inspect.Parameter.VAR_POSITIONAL,
+        ):
+            out.append("*")
+        previous = parameter.kind
+        kind = kinds.get(parameter.name)
+        if kind is None or not valid(kind, namespace):
+            kind = "Any"
+        if parameter.default is None and not allows_none(kind, namespace) and kind != "Any":
+            kind = f"Optional[{kind}]"
+        prefix = {
+            inspect.Parameter.VAR_POSITIONAL: "*",
+            inspect.Parameter.VAR_KEYWORD: "**",
+        }.get(parameter.kind, "")
+        text = f"{prefix}{parameter.name}: {kind}"
+        if parameter.default is not inspect.Parameter.empty:
+            text += f" = {default(parameter.default)}"
+        out.append(text)
+    if previous == inspect.Parameter.POSITIONAL_ONLY:
+        out.append("/")
+    return ", ".join(out)
+
+
+def signature_of(function) -> inspect.Signature | None:
+    try:
+        return inspect.signature(function)
+    except (TypeError, ValueError):
+        return None
+
+
+def docstring(doc: str | None, indent: str) -> list[str]:
+    if not doc:
+        return []
+    lines = inspect.cleandoc(doc).replace("\\", "\\\\").replace('"""', '\\"\\"\\"').splitlines()
+    if len(lines) == 1:
+        return [f'{indent}"""{lines[0]}"""']
+    return [f'{indent}"""'] + [f"{indent}{line}".rstrip() for line in lines] + [f'{indent}"""']
+
+
+def function_stub(name: str, function, kinds: dict[str, str], namespace: dict) -> list[str]:
+    signature = signature_of(function)
+    returns = documented_return(function.__doc__)
+    if returns is None or not valid(returns, namespace):
+        returns = "Any"
+    kinds = {**kinds, **documented_arguments(function.__doc__)}
+    arguments = "*args: Any, **kwargs: Any" if signature is None else parameters(
+        signature, kinds, namespace, skip_first=False
+    )
+    body = docstring(function.__doc__, "    ")
+    return [f"def {name}({arguments}) -> {returns}:"] + (body or ["    ..."])
+
+
+def method_stub(name: str, owner: type, namespace: dict) -> list[str]:
+    raw = inspect.getattr_static(owner, name)
+    function = getattr(owner, name)
+    # Slot wrappers carry CPython's generic docstrings.
+    doc = function.__doc__ if name not in ("__len__", "__call__") else None
+    signature = signature_of(function)
+    kinds = documented_arguments(doc)
+    if isinstance(raw, staticmethod):
+        decorator, first = ["    @staticmethod"], None
+    elif isinstance(raw, classmethod) or inspect.ismethod(function):
+        decorator, first = ["    @classmethod"], "cls"
+    else:
+        decorator, first = [], "self"
+    if signature is None:
+        arguments = "*args: Any, **kwargs: Any"
+    else:
+        arguments = parameters(signature, kinds, namespace, skip_first=first == "self")
+    arguments = ", ".join(a for a in (first, arguments) if a)
+    returns = "int" if name == "__len__" else documented_return(doc)
+    if returns is None or not valid(returns, namespace):
+        returns = "Any"
+    body = docstring(doc, "        ")
+    return decorator + [f"    def {name}({arguments}) -> {returns}:"] + (body or ["        ..."])
+
+
+def class_stub(name: str, cls: type, namespace: dict) -> list[str]:
+    if issubclass(cls, BaseException):
+        return [f"class {name}({cls.__base__.__name__}): ..."]
+    lines = [f"class {name}:"] + docstring(cls.__doc__, "    ")
+    if cls.__text_signature__ is not None:
+        arguments = parameters(
+            inspect.signature(cls), documented_arguments(cls.__doc__), namespace, skip_first=False
+        )
+        arguments = ", ".join(a for a in ("self", arguments) if a)
+        lines.append(f"    def __init__({arguments}) -> None: ...")
+    # pyo3 does not keep definition order, so members are sorted.
+    for member, raw in sorted(vars(cls).items()):
+        if member.startswith("_") and member not in ("__len__", "__call__"):
+            continue
+        if isinstance(raw, types.GetSetDescriptorType):
+            kind = documented_property(raw.__doc__)
+            if kind is None or not valid(kind, namespace):
+                kind = "Any"
+            body = docstring(raw.__doc__, "        ")
+            lines += ["    @property", f"    def {member}(self) -> {kind}:"]
+            lines += body or ["        ..."]
+        else:
+            lines += method_stub(member, cls, namespace)
+    return lines
+
+
+def module_stub(module: types.ModuleType, wrappers: Wrappers, namespace: dict) -> str:
+    body = []
+    # Base classes first and aliases last, so every name is defined before
+    # it is used.
+    for name, cls in sorted(
+        classes(module).items(),
+        key=lambda item: (item[1].__name__ != item[0], len(item[1].__mro__)),
+    ):
+        if cls.__name__ != name:
+            body += ["", f"{name} = {cls.__name__}", ""]
+        else:
+            body += ["", *class_stub(name, cls, namespace), ""]
+    for name, function in sorted(vars(module).items()):
+        if name.startswith("_") or not callable(function) or isinstance(function, type):
+            continue
+        body += ["", *function_stub(name, function, wrappers.arguments(name), namespace), ""]
+    # Classes of other submodules named in this one's signatures.
+    signatures = "\n".join(line for line in body if line.lstrip().startswith("def "))
+    lines = [HEADER]
+    for other, submodule in submodules(native).items():
+        used = [
+            name
+            for name, cls in classes(submodule).items()
+            if submodule is not module
+            and not issubclass(cls, BaseException)
+            and re.search(rf"\b{name}\b", signatures)
+        ]
+        if used:
+            lines.append(f"from .{other} import {', '.join(used)}")
+    if module is native:
+        lines += [f"from . import {name} as {name}" for name in submodules(native)]
+    return "\n".join(lines + body).rstrip() + "\n"
+
+
+def stubs() -> dict[Path, str]:
+    wrappers = Wrappers(PACKAGE)
+    namespace = dict(NAMESPACE)
+    for submodule in submodules(native).values():
+        namespace.update(classes(submodule))
+    files = {OUTPUT / "__init__.pyi": module_stub(native, wrappers, namespace)}
+    for name, submodule in submodules(native).items():
+        files[OUTPUT / f"{name}.pyi"] = module_stub(submodule, wrappers, namespace)
+    return files
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__.split("\n\n")[0].strip())
+    parser.add_argument(
+        "--check", action="store_true", help="fail if the stubs are out of date"
+    )
+    args = parser.parse_args()
+    files = stubs()
+    stale = [path for path, text in files.items() if not path.exists() or path.read_text() != text]
+    if args.check:
+        for path in stale:
+            print(f"{path} is out of date; run python/generate_stubs.py", file=sys.stderr)
+        return 1 if stale else 0
+    OUTPUT.mkdir(exist_ok=True)
+    for path in stale:
+        path.write_text(files[path])
+        print(f"wrote {path}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
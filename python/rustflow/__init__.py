@@ -1 +1,19 @@
+from .rustflow import (
+    ConvergenceError,
+    DataError,
+    InvalidParameterError,
+    MassBalanceError,
+    ParameterError,
+    RustflowError,
+    reset_logging,
+)
 
+__all__ = [
+    "ConvergenceError",
+    "DataError",
+    "InvalidParameterError",
+    "MassBalanceError",
+    "ParameterError",
+    "RustflowError",
+    "reset_logging",
+]
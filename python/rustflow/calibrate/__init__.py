@@ -0,0 +1,401 @@
+from datetime import timedelta
+from typing import Callable, Literal, Mapping, Optional, Sequence, Union
+
+from ..rustflow import calibrate
+
+CrossValidationResult = calibrate.CrossValidationResult
+GlueResult = calibrate.GlueResult
+ParetoFront = calibrate.ParetoFront
+
+from . import gradient, sensitivity
+
+__all__ = [
+    "CrossValidationResult",
+    "GlueResult",
+    "ParetoFront",
+    "cross_validate",
+    "glue",
+    "gradient",
+    "kge",
+    "log_nse",
+    "nse",
+    "nsga2",
+    "rmse",
+    "sensitivity",
+]
+
+
+def nse(simulated: Sequence[float], observed: Sequence[float]) -> float:
+    """
+    Nash-Sutcliffe efficiency of a simulated series (1 is a perfect fit).
+
+    Pairs in which either value is `nan` are skipped.
+
+    Raises:
+        ValueError: If the series differ in length or fewer than two valid
+            pairs remain.
+    """
+    return calibrate.nse(simulated, observed)
+
+
+def kge(simulated: Sequence[float], observed: Sequence[float]) -> float:
+    """
+    Kling-Gupta efficiency (Gupta et al., 2009) of a simulated series
+    (1 is a perfect fit).
+
+    Pairs in which either value is `nan` are skipped.
+
+    Raises:
+        ValueError: If the series differ in length or fewer than two valid
+            pairs remain.
+    """
+    return calibrate.kge(simulated, observed)
+
+
+def log_nse(simulated: Sequence[float], observed: Sequence[float]) -> float:
+    """
+    Nash-Sutcliffe efficiency of the logarithms of the flows, which weights
+    the fit of low flows (1 is a perfect fit).
+
+    An offset of 1% of the mean observed flow is added before taking
+    logarithms so that zero flows stay finite; negative flows count as
+    zero. Pairs in which either value is `nan` are skipped.
+
+    Raises:
+        ValueError: If the series differ in length or fewer than two valid
+            pairs remain.
+    """
+    return calibrate.log_nse(simulated, observed)
+
+
+def rmse(simulated: Sequence[float], observed: Sequence[float]) -> float:
+    """
+    Root-mean-square error, in the units of the series.
+
+    Pairs in which either value is `nan` are skipped.
+
+    Raises:
+        ValueError: If the series differ in length or fewer than two valid
+            pairs remain.
+    """
+    return calibrate.rmse(simulated, observed)
+
+
+def glue(
+    config: dict,
+    parameters: Mapping[str, tuple[float, float]],
+    inflows: Mapping[str, Sequence[float]],
+    observed: Sequence[float],
+    node: str,
+    time_step: timedelta,
+    samples: int = 1000,
+    likelihood: Literal["nse", "kge", "log_nse"] = "nse",
+    threshold: float = 0.5,
+    bounds: tuple[float, float] = (0.05, 0.95),
+    seed: Optional[int] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+) -> GlueResult:
+    """
+    Generalised Likelihood Uncertainty Estimation (GLUE) for a routing
+    network.
+
+    Parameter sets are drawn by Latin hypercube sampling and the network is
+    run for every set in parallel in Rust. Sets whose likelihood reaches
+    `threshold` are behavioural; prediction bounds are quantiles of their
+    simulations weighted by `likelihood - threshold`.
+
+    Args:
+        config (dict): Network configuration, as for `rustflow.network.Network`.
+        parameters (Mapping[str, tuple[float, float]]): Sampling range of each
+            calibrated parameter, keyed by `"node_id.field"`, e.g.
+            `{"reach.k": (3600.0, 14400.0), "reach.x": (0.0, 0.5)}`. Values are
+            in the units of the configuration.
+        inflows (Mapping[str, Sequence[float]]): External inflow and rainfall
+            series, as for `Network.run`.
+        observed (Sequence[float]): Observed outflow of `node`; `nan` marks
+            missing observations.
+        node (str): Node whose outflow is compared with `observed`.
+        time_step (timedelta): Interval between consecutive values.
+        samples (int, optional): Number of parameter sets. Defaults to 1000.
+        likelihood (Literal["nse", "kge", "log_nse"], optional): Likelihood
+            measure. Defaults to `"nse"`.
+        threshold (float, optional): Minimum likelihood of a behavioural
+            set. Defaults to 0.5.
+        bounds (tuple[float, float], optional): Probabilities of the lower and
+            upper prediction bounds. Defaults to `(0.05, 0.95)`.
+        seed (Optional[int], optional): Seed for reproducible sampling.
+        progress_callback (Optional[Callable[[int, int], None]], optional):
+            Called with the completed and total number of model runs as the
+            analysis proceeds, at most 20 times a second. Raising an
+            exception from it cancels the analysis; Ctrl-C also interrupts
+            it. Defaults to None.
+
+    Returns:
+        GlueResult: `samples`, `likelihood`, `behavioral` indices,
+            `behavioral_parameters` and the `lower`, `median` and `upper`
+            prediction bounds. Bounds are `nan` when no set is behavioural.
+
+    Raises:
+        ValueError: If a parameter path or `node` does not exist in the
+            network, or the configuration is invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.calibrate import glue
+
+        config = {"nodes": [{"id": "reach", "type": "muskingum", "k": 7200.0,
+                             "x": 0.2, "inflow": "upstream"}]}
+        result = glue(
+            config,
+            {"reach.k": (1800.0, 21600.0), "reach.x": (0.0, 0.5)},
+            {"upstream": inflow},
+            observed_outflow,
+            node="reach",
+            time_step=timedelta(hours=1),
+            samples=5000,
+            seed=42,
+        )
+        print(result.behavioral_parameters["reach.k"])
+        ```
+    """
+    return calibrate.glue(
+        config,
+        dict(parameters),
+        dict(inflows),
+        observed,
+        node,
+        time_step,
+        samples,
+        likelihood,
+        threshold,
+        tuple(bounds),
+        seed,
+        progress_callback,
+    )
+
+
+def nsga2(
+    config: dict,
+    parameters: Mapping[str, tuple[float, float]],
+    inflows: Mapping[str, Sequence[float]],
+    observed: Sequence[float],
+    node: str,
+    time_step: timedelta,
+    objectives: Sequence[Literal["nse", "kge", "log_nse"]] = ("nse", "log_nse"),
+    population: int = 100,
+    generations: int = 50,
+    crossover: float = 0.9,
+    seed: Optional[int] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+) -> ParetoFront:
+    """
+    Multi-objective calibration of a routing network with NSGA-II (Deb et
+    al., 2002).
+
+    A single objective hides trade-offs, such as fitting flood peaks
+    against fitting low flows. NSGA-II instead returns the Pareto front:
+    the parameter sets that no other set beats on every objective. The
+    first population is drawn by Latin hypercube sampling. Each generation
+    breeds `population` offspring by tournament selection, simulated
+    binary crossover and polynomial mutation, and keeps the best half of
+    parents and offspring by non-domination rank and crowding distance.
+    All model runs of a generation run in parallel in Rust.
+
+    Args:
+        config (dict): Network configuration, as for `rustflow.network.Network`.
+        parameters (Mapping[str, tuple[float, float]]): Search range of each
+            calibrated parameter, keyed by `"node_id.field"`.
+        inflows (Mapping[str, Sequence[float]]): External inflow and rainfall
+            series, as for `Network.run`.
+        observed (Sequence[float]): Observed outflow of `node`; `nan` marks
+            missing observations.
+        node (str): Node whose outflow is compared with `observed`.
+        time_step (timedelta): Interval between consecutive values.
+        objectives (Sequence[Literal["nse", "kge", "log_nse"]], optional):
+            Measures to maximise. Defaults to `("nse", "log_nse")`, which
+            trades high flows against low flows.
+        population (int, optional): Number of parameter sets kept in each
+            generation, at least 4. Defaults to 100.
+        generations (int, optional): Number of generations. The analysis
+            makes `population × (generations + 1)` model runs. Defaults to
+            50.
+        crossover (float, optional): Probability that a pair of parents is
+            crossed. Defaults to 0.9.
+        seed (Optional[int], optional): Seed for a reproducible search.
+        progress_callback (Optional[Callable[[int, int], None]], optional):
+            Called with the completed and total number of model runs, as
+            for `glue`. Defaults to None.
+
+    Returns:
+        ParetoFront: The non-dominated `sets` and their `scores`, sorted by
+            the first objective from best to worst. They are also available
+            as `parameter_sets` and `objective_values` dicts.
+
+    Raises:
+        ValueError: If a parameter path or `node` does not exist in the
+            network, an objective is unknown or `population` is below 4.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.calibrate import nsga2
+
+        front = nsga2(
+            config,
+            {"reach.k": (1800.0, 21600.0), "reach.x": (0.0, 0.5)},
+            {"upstream": inflow},
+            observed_outflow,
+            node="reach",
+            time_step=timedelta(hours=1),
+            objectives=["nse", "log_nse"],
+            seed=42,
+        )
+        for values, scores in zip(front.sets, front.scores):
+            print(values, scores)
+        ```
+    """
+    return calibrate.nsga2(
+        config,
+        dict(parameters),
+        dict(inflows),
+        observed,
+        node,
+        time_step,
+        list(objectives),
+        population,
+        generations,
+        crossover,
+        seed,
+        progress_callback,
+    )
+
+
+def cross_validate(
+    config: dict,
+    parameters: Mapping[str, tuple[float, float]],
+    inflows: Mapping[str, Sequence[float]],
+    observed: Sequence[float],
+    node: str,
+    time_step: timedelta,
+    scheme: Literal["split_sample", "k_fold", "rolling"] = "split_sample",
+    fraction: float = 0.5,
+    folds: int = 5,
+    calibration_window: Optional[Union[int, timedelta]] = None,
+    validation_window: Optional[Union[int, timedelta]] = None,
+    step: Optional[Union[int, timedelta]] = None,
+    warm_up: Union[int, timedelta] = 0,
+    samples: int = 1000,
+    likelihood: Literal["nse", "kge", "log_nse"] = "nse",
+    seed: Optional[int] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+) -> CrossValidationResult:
+    """
+    Temporal cross-validation of a network calibration.
+
+    The record is split into calibration and validation periods:
+
+    - `"split_sample"`: Klemeš's split-sample test. The first `fraction` of
+      the record calibrates and the rest validates, then the other way
+      round.
+    - `"k_fold"`: `folds` contiguous blocks. Each block is validated in turn
+      while the others calibrate.
+    - `"rolling"`: calibration windows of `calibration_window`, each
+      validated on the following `validation_window`, advanced by `step`.
+
+    Parameter sets are drawn by Latin hypercube sampling. The network is
+    run once per set over the whole record, in parallel in Rust, so every
+    period starts from a warmed-up state. Each split keeps the set with the
+    best likelihood over its calibration periods and scores that set over
+    its validation period. The drop from calibration to validation score
+    shows how well the calibration transfers to unseen data.
+
+    Args:
+        config (dict): Network configuration, as for `rustflow.network.Network`.
+        parameters (Mapping[str, tuple[float, float]]): Sampling range of each
+            calibrated parameter, keyed by `"node_id.field"`.
+        inflows (Mapping[str, Sequence[float]]): External inflow and rainfall
+            series, as for `Network.run`.
+        observed (Sequence[float]): Observed outflow of `node`; `nan` marks
+            missing observations.
+        node (str): Node whose outflow is compared with `observed`.
+        time_step (timedelta): Interval between consecutive values.
+        scheme (Literal["split_sample", "k_fold", "rolling"], optional): How
+            the record is split. Defaults to `"split_sample"`.
+        fraction (float, optional): Calibration share of the record for
+            `"split_sample"`. Defaults to 0.5.
+        folds (int, optional): Number of blocks for `"k_fold"`. Defaults
+            to 5.
+        calibration_window (Optional[Union[int, timedelta]], optional):
+            Length of each calibration window for `"rolling"`, in steps or
+            as a duration.
+        validation_window (Optional[Union[int, timedelta]], optional):
+            Length of each validation window for `"rolling"`.
+        step (Optional[Union[int, timedelta]], optional): Advance between
+            rolling windows. Defaults to `validation_window`.
+        warm_up (Union[int, timedelta], optional): Start of the record left
+            out of every period. Defaults to 0.
+        samples (int, optional): Number of parameter sets. Defaults to 1000.
+        likelihood (Literal["nse", "kge", "log_nse"], optional): Measure
+            used to calibrate and validate. Defaults to `"nse"`.
+        seed (Optional[int], optional): Seed for reproducible sampling.
+        progress_callback (Optional[Callable[[int, int], None]], optional):
+            Called with the completed and total number of model runs, as
+            for `glue`. Defaults to None.
+
+    Returns:
+        CrossValidationResult: Per-split `folds` with periods, calibrated
+            parameters and scores; `calibration_scores`,
+            `validation_scores` and `degradation`; and a `summary` of their
+            means and the largest degradation.
+
+    Raises:
+        ValueError: If a parameter path or `node` does not exist in the
+            network, the scheme is unknown or the record is too short for
+            it.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.calibrate import cross_validate
+
+        result = cross_validate(
+            config,
+            {"reach.k": (1800.0, 21600.0), "reach.x": (0.0, 0.5)},
+            {"upstream": inflow},
+            observed_outflow,
+            node="reach",
+            time_step=timedelta(hours=1),
+            scheme="k_fold",
+            folds=4,
+            warm_up=timedelta(days=7),
+            seed=1,
+        )
+        print(result.summary["mean_degradation"])
+        ```
+    """
+
+    def steps(length: Optional[Union[int, timedelta]]) -> Optional[int]:
+        if isinstance(length, timedelta):
+            return round(length / time_step)
+        return length
+
+    return calibrate.cross_validate(
+        config,
+        dict(parameters),
+        dict(inflows),
+        observed,
+        node,
+        time_step,
+        scheme,
+        fraction,
+        folds,
+        steps(calibration_window),
+        steps(validation_window),
+        steps(step),
+        steps(warm_up),
+        samples,
+        likelihood,
+        seed,
+        progress_callback,
+    )
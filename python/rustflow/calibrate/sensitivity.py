@@ -0,0 +1,151 @@
+from datetime import timedelta
+from typing import Callable, Literal, Mapping, Optional, Sequence
+
+from ..rustflow import calibrate
+
+Objective = Literal["peak", "volume", "mean", "nse", "kge", "log_nse"]
+
+
+def morris(
+    config: dict,
+    parameters: Mapping[str, tuple[float, float]],
+    inflows: Mapping[str, Sequence[float]],
+    node: str,
+    time_step: timedelta,
+    objective: Objective = "peak",
+    observed: Optional[Sequence[float]] = None,
+    trajectories: int = 20,
+    levels: int = 4,
+    seed: Optional[int] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+) -> dict[str, dict[str, float]]:
+    """
+    Morris elementary-effects screening of network parameters.
+
+    Runs `trajectories × (len(parameters) + 1)` simulations in parallel in
+    Rust. Effects are expressed per unit of normalised parameter range, so
+    they are comparable across parameters.
+
+    Args:
+        config (dict): Network configuration, as for `rustflow.network.Network`.
+        parameters (Mapping[str, tuple[float, float]]): Range of each
+            parameter, keyed by `"node_id.field"`.
+        inflows (Mapping[str, Sequence[float]]): External inflow and rainfall
+            series, as for `Network.run`.
+        node (str): Node whose outflow is evaluated.
+        time_step (timedelta): Interval between consecutive values.
+        objective (Objective, optional): Scalar output: `"peak"` outflow,
+            `"volume"` (flow × seconds), `"mean"` outflow, or the `"nse"`,
+            `"kge"` or `"log_nse"` fit to `observed`. Defaults to `"peak"`.
+        observed (Optional[Sequence[float]], optional): Observed outflow,
+            required for the fit objectives.
+        trajectories (int, optional): Number of trajectories. Defaults to 20.
+        levels (int, optional): Even number of grid levels. Defaults to 4.
+        seed (Optional[int], optional): Seed for reproducible sampling.
+        progress_callback (Optional[Callable[[int, int], None]], optional):
+            Called with the completed and total number of model runs as the
+            analysis proceeds, at most 20 times a second. Raising an
+            exception from it cancels the analysis; Ctrl-C also interrupts
+            it. Defaults to None.
+
+    Returns:
+        dict[str, dict[str, float]]: For each parameter, `mu` (mean effect),
+            `mu_star` (mean absolute effect, the usual importance ranking) and
+            `sigma` (spread, indicating non-linearity or interactions).
+
+    Raises:
+        ValueError: If a parameter path or `node` does not exist, `levels`
+            is odd, or a fit objective is requested without `observed`.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.calibrate.sensitivity import morris
+
+        indices = morris(
+            config,
+            {"reach.k": (1800.0, 21600.0), "reach.x": (0.0, 0.5)},
+            {"upstream": inflow},
+            node="reach",
+            time_step=timedelta(hours=1),
+            seed=1,
+        )
+        ranking = sorted(indices, key=lambda p: indices[p]["mu_star"], reverse=True)
+        ```
+    """
+    return calibrate.morris(
+        config,
+        dict(parameters),
+        dict(inflows),
+        node,
+        time_step,
+        objective,
+        observed,
+        trajectories,
+        levels,
+        seed,
+        progress_callback,
+    )
+
+
+def sobol(
+    config: dict,
+    parameters: Mapping[str, tuple[float, float]],
+    inflows: Mapping[str, Sequence[float]],
+    node: str,
+    time_step: timedelta,
+    objective: Objective = "peak",
+    observed: Optional[Sequence[float]] = None,
+    samples: int = 1024,
+    seed: Optional[int] = None,
+    progress_callback: Optional[Callable[[int, int], None]] = None,
+) -> dict[str, dict[str, float]]:
+    """
+    Sobol variance-based sensitivity indices of network parameters.
+
+    Uses the Saltelli (2010) first-order and Jansen total-effect estimators,
+    which cost `samples × (len(parameters) + 2)` simulations, run in
+    parallel in Rust.
+
+    Args:
+        config (dict): Network configuration, as for `rustflow.network.Network`.
+        parameters (Mapping[str, tuple[float, float]]): Range of each
+            parameter, keyed by `"node_id.field"`. Parameters are sampled
+            uniformly within their ranges.
+        inflows (Mapping[str, Sequence[float]]): External inflow and rainfall
+            series, as for `Network.run`.
+        node (str): Node whose outflow is evaluated.
+        time_step (timedelta): Interval between consecutive values.
+        objective (Objective, optional): Scalar output, as for `morris`.
+            Defaults to `"peak"`.
+        observed (Optional[Sequence[float]], optional): Observed outflow,
+            required for the fit objectives.
+        samples (int, optional): Number of base samples. Defaults to 1024.
+        seed (Optional[int], optional): Seed for reproducible sampling.
+        progress_callback (Optional[Callable[[int, int], None]], optional):
+            Called with the completed and total number of model runs as the
+            analysis proceeds, at most 20 times a second. Raising an
+            exception from it cancels the analysis; Ctrl-C also interrupts
+            it. Defaults to None.
+
+    Returns:
+        dict[str, dict[str, float]]: For each parameter, the `first`-order
+            index (share of output variance explained by the parameter alone)
+            and the `total` index (including interactions).
+
+    Raises:
+        ValueError: If a parameter path or `node` does not exist, or a fit
+            objective is requested without `observed`.
+    """
+    return calibrate.sobol(
+        config,
+        dict(parameters),
+        dict(inflows),
+        node,
+        time_step,
+        objective,
+        observed,
+        samples,
+        seed,
+        progress_callback,
+    )
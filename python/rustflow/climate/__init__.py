@@ -0,0 +1,111 @@
+from datetime import datetime, timedelta
+from typing import Literal, Optional
+
+from ..rustflow import climate
+
+
+def delta_change(
+    series: list[float],
+    factors: list[float],
+    start: Optional[datetime] = None,
+    time_step: Optional[timedelta] = None,
+    kind: Literal["additive", "multiplicative"] = "multiplicative",
+) -> list[float]:
+    """
+    Perturbs a historical forcing series with delta-change factors.
+
+    A single factor applies to the whole record. Twelve factors are monthly
+    and are selected by the calendar month of each value, which needs the
+    series `start` and `time_step`. Multiplicative factors suit
+    precipitation and flow; additive changes suit temperature.
+
+    Args:
+        series (list[float]): Historical series. Any iterable of numbers or
+            Arrow array is accepted.
+        factors (list[float]): One factor, or twelve for January to December.
+        start (datetime, optional): Timestamp of the first value; required
+            for monthly factors.
+        time_step (timedelta, optional): Interval between values; required
+            for monthly factors.
+        kind (Literal["additive", "multiplicative"], optional): How the
+            factors apply. Defaults to `"multiplicative"`.
+
+    Returns:
+        list[float]: The perturbed series.
+
+    Raises:
+        ValueError: If the number of factors is not 1 or 12, or monthly
+            factors are given without `start` and `time_step`.
+
+    Example:
+        ```python
+        from datetime import datetime, timedelta
+        from rustflow.climate import delta_change
+
+        future = delta_change(
+            hourly_rain,
+            [1.15, 1.12, 1.08, 1.02, 0.97, 0.90, 0.88, 0.90, 0.98, 1.05, 1.10, 1.14],
+            start=datetime(1990, 1, 1),
+            time_step=timedelta(hours=1),
+        )
+        ```
+    """
+    return climate.delta_change(series, factors, start, time_step, kind)
+
+
+def quantile_mapping(
+    series: list[float],
+    observed: list[float],
+    modelled: list[float],
+    method: Literal["empirical", "quantile_delta"] = "empirical",
+    kind: Literal["additive", "multiplicative"] = "multiplicative",
+) -> list[float]:
+    """
+    Bias-corrects a climate model series by quantile mapping.
+
+    `observed` and `modelled` cover a common reference period and define
+    empirical distributions with Hazen plotting positions.
+
+    - `"empirical"`: each value is located in the modelled distribution and
+      replaced by the observed value at the same probability.
+    - `"quantile_delta"`: quantile delta mapping (Cannon et al., 2015). Each
+      value is located in the series' own distribution, and its change from
+      the modelled quantile is applied to the observed quantile. This keeps
+      the model's projected change in every quantile.
+
+    Values beyond the reference range keep the correction of the nearest
+    reference quantile. Multiplicative corrections suit precipitation;
+    where the modelled quantile is zero, the observed quantile is used.
+    Non-finite values pass through unchanged.
+
+    Args:
+        series (list[float]): Model series to correct, e.g. a future
+            projection.
+        observed (list[float]): Observations over the reference period.
+        modelled (list[float]): Model output over the reference period.
+        method (Literal["empirical", "quantile_delta"], optional): Mapping
+            variant. Defaults to `"empirical"`.
+        kind (Literal["additive", "multiplicative"], optional): Whether the
+            corrections are differences or ratios. Defaults to
+            `"multiplicative"`.
+
+    Returns:
+        list[float]: The corrected series.
+
+    Raises:
+        ValueError: If a reference series has fewer than two finite values,
+            or the method or kind is unknown.
+
+    Example:
+        ```python
+        from rustflow.climate import quantile_mapping
+
+        corrected = quantile_mapping(
+            model_future,
+            observed=gauge_1981_2010,
+            modelled=model_1981_2010,
+            method="quantile_delta",
+        )
+        ```
+    """
+    return climate.quantile_mapping(series, observed, modelled, method, kind)
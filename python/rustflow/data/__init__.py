@@ -0,0 +1,3 @@
+from . import usgs
+
+__all__ = ["usgs"]
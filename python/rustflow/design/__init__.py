@@ -0,0 +1,152 @@
+from datetime import timedelta
+from typing import Any, Literal, Optional
+
+from ..rustflow import design
+
+
+def pmf(
+    rainfall: list[float],
+    time_step: timedelta,
+    area: float,
+    elevation: list[float],
+    storage: list[float],
+    outflow: list[float],
+    initial_elevation: float,
+    crest_elevation: float,
+    loss: Literal["curve_number", "initial_constant"] = "curve_number",
+    curve_number: Optional[float] = None,
+    initial_abstraction_ratio: float = 0.2,
+    initial_loss: float = 0.0,
+    loss_rate: float = 0.0,
+    lag: Optional[timedelta] = None,
+    unit_hydrograph: Optional[list[float]] = None,
+    time_area: Optional[list[float]] = None,
+    storage_coefficient: Optional[timedelta] = None,
+    peaking_factor: float = 1.0,
+    baseflow: float = 0.0,
+    freeboard: float = 0.0,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, Any]:
+    """
+    Runs a probable maximum flood (PMF) analysis for a dam in one call.
+
+    The steps are:
+
+    1. Losses reduce the PMP hyetograph to rainfall excess, by the NRCS
+       curve number method or an initial loss plus a constant rate.
+    2. A unit hydrograph transforms the excess into direct runoff. It is
+       the NRCS dimensionless unit hydrograph for a given lag, user
+       ordinates, or the Clark unit hydrograph of a time–area histogram
+       and storage coefficient.
+    3. The unit hydrograph is first compressed in time by
+       `peaking_factor`, which divides its time to peak by the factor and
+       keeps its volume.
+    4. Baseflow is added, and the inflow is routed through the reservoir
+       by the level-pool method.
+    5. The maximum pool is compared with the dam crest.
+
+    The spillway is adequate when the freeboard at the maximum pool is at
+    least `freeboard`. The run covers the storm plus the length of the unit
+    hydrograph. Append zero rainfall to follow the recession further.
+
+    Args:
+        rainfall (list[float]): Incremental PMP depths per time step (mm or
+            in, per `units`).
+        time_step (timedelta): Interval between rainfall values, also used
+            for routing.
+        area (float): Drainage area (km² or mi²).
+        elevation (list[float]): Pool elevations of the reservoir table (m
+            or ft), strictly increasing.
+        storage (list[float]): Storage at each elevation (m³ or ft³).
+        outflow (list[float]): Total spillway and outlet discharge at each
+            elevation (m³/s or cfs).
+        initial_elevation (float): Starting pool elevation.
+        crest_elevation (float): Top of dam elevation.
+        loss (Literal["curve_number", "initial_constant"], optional): Loss
+            method. Defaults to `"curve_number"`.
+        curve_number (float, optional): NRCS curve number; required with
+            `loss="curve_number"`.
+        initial_abstraction_ratio (float, optional): `Ia / S` of the curve
+            number method. Defaults to 0.2.
+        initial_loss (float, optional): Initial loss (mm or in) of the
+            `initial_constant` method. Defaults to 0.
+        loss_rate (float, optional): Constant loss rate (mm/h or in/h) of
+            the `initial_constant` method. Defaults to 0.
+        lag (timedelta, optional): Basin lag for the NRCS unit hydrograph.
+        unit_hydrograph (list[float], optional): Unit hydrograph ordinates
+            at `time_step` (m³/s per mm or cfs per inch of excess), used
+            instead of `lag`.
+        time_area (list[float], optional): Drainage area (km² or mi²)
+            reaching the outlet within each time step, as returned by
+            `rustflow.terrain.FlowGrid.travel_time`, for a Clark unit
+            hydrograph instead of `lag`.
+        storage_coefficient (timedelta, optional): Clark storage
+            coefficient; required with `time_area`.
+        peaking_factor (float, optional): Ratio by which the unit
+            hydrograph's time to peak is shortened. Defaults to 1 (none).
+        baseflow (float, optional): Constant baseflow (m³/s or cfs).
+            Defaults to 0.
+        freeboard (float, optional): Required freeboard below the crest (m
+            or ft). Defaults to 0.
+        units (Literal["si", "us"], optional): Unit system of all inputs
+            and outputs. Defaults to `"si"`.
+
+    Returns:
+        dict: An audit report. `summary` holds the total rainfall, loss and
+            excess, the peak inflow and outflow and the maximum pool with
+            their times from the start, the `freeboard`, and whether the
+            dam is `overtopped` and the spillway `adequate`. The series
+            `rainfall`, `loss`, `excess`, `unit_hydrograph` (after the
+            peaking adjustment), `inflow`, `outflow` and `elevation` are
+            also returned.
+
+    Raises:
+        ValueError: If the loss or unit hydrograph options are missing or
+            conflicting, the reservoir table is invalid, or a parameter is
+            out of range.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.design import pmf
+
+        report = pmf(
+            pmp_increments,
+            timedelta(hours=1),
+            area=350.0,
+            elevation=[100.0, 105.0, 110.0, 115.0],
+            storage=[0.0, 2.0e7, 5.0e7, 9.0e7],
+            outflow=[0.0, 150.0, 900.0, 2500.0],
+            initial_elevation=105.0,
+            crest_elevation=114.0,
+            curve_number=85.0,
+            lag=timedelta(hours=4),
+            peaking_factor=1.33,
+            freeboard=0.5,
+        )
+        print(report["summary"]["adequate"])
+        ```
+    """
+    return design.pmf(
+        rainfall,
+        time_step,
+        area,
+        elevation,
+        storage,
+        outflow,
+        initial_elevation,
+        crest_elevation,
+        loss,
+        curve_number,
+        initial_abstraction_ratio,
+        initial_loss,
+        loss_rate,
+        lag,
+        unit_hydrograph,
+        time_area,
+        storage_coefficient,
+        peaking_factor,
+        baseflow,
+        freeboard,
+        units,
+    )
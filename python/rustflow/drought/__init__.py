@@ -0,0 +1,104 @@
+from typing import Literal, Union
+
+from ..rustflow import drought
+
+Series = Union[list[float], dict[str, list[float]]]
+Indices = Union[dict[int, list[float]], dict[str, dict[int, list[float]]]]
+
+
+def _by_name(series: Series) -> tuple[dict[str, list[float]], bool]:
+    if isinstance(series, dict):
+        return series, False
+    return {"": series}, True
+
+
+def spi(
+    precipitation: Series,
+    windows: list[int] = [1, 3, 6, 12],
+    seasons: int = 12,
+    distribution: Literal["gamma", "lognormal"] = "gamma",
+) -> Indices:
+    """
+    Computes the Standardized Precipitation Index (McKee et al., 1993).
+
+    Precipitation is summed over trailing windows of `windows` steps. A
+    distribution is fitted separately to the sums of each season, i.e. of
+    every `seasons`-th step, so each calendar month gets its own fit for
+    monthly data. Each sum is then mapped to a standard normal deviate.
+    Zero sums are handled with a mixed distribution. Steps before a window
+    fills, windows with missing (NaN) values and seasons with fewer than
+    three non-zero sums give NaN.
+
+    Args:
+        precipitation (list[float] | dict[str, list[float]]): A
+            precipitation series, or series keyed by station or grid cell,
+            which are processed in parallel.
+        windows (list[int], optional): Accumulation windows in steps.
+            Defaults to `[1, 3, 6, 12]`.
+        seasons (int, optional): Number of seasons in the cycle; 12 for
+            monthly data, 1 for a single fit. Defaults to 12.
+        distribution (Literal["gamma", "lognormal"], optional):
+            Distribution of the non-zero sums. Defaults to `"gamma"`.
+
+    Returns:
+        dict: The index series keyed by window, or for a dict input, keyed
+            by name and then by window.
+
+    Raises:
+        ValueError: If a window or `seasons` is zero, or the distribution
+            is unknown.
+
+    Example:
+        ```python
+        from rustflow.drought import spi
+
+        result = spi({"station_a": monthly_a, "station_b": monthly_b})
+        spi_3 = result["station_a"][3]
+        ```
+    """
+    named, single = _by_name(precipitation)
+    result = drought.spi(named, windows, seasons, distribution)
+    return result[""] if single else result
+
+
+def ssi(
+    flows: Series,
+    windows: list[int] = [1, 3, 6, 12],
+    seasons: int = 12,
+    distribution: Literal["gamma", "lognormal"] = "lognormal",
+) -> Indices:
+    """
+    Computes the Standardized Streamflow Index.
+
+    This is the streamflow analogue of `spi`. Flows are summed over trailing
+    windows, a distribution is fitted per season, and each sum is mapped to
+    a standard normal deviate.
+
+    Args:
+        flows (list[float] | dict[str, list[float]]): A flow series, or
+            series keyed by gauge, which are processed in parallel.
+        windows (list[int], optional): Accumulation windows in steps.
+            Defaults to `[1, 3, 6, 12]`.
+        seasons (int, optional): Number of seasons in the cycle; 12 for
+            monthly data, 1 for a single fit. Defaults to 12.
+        distribution (Literal["gamma", "lognormal"], optional):
+            Distribution of the non-zero sums. Defaults to `"lognormal"`.
+
+    Returns:
+        dict: The index series keyed by window, or for a dict input, keyed
+            by name and then by window.
+
+    Raises:
+        ValueError: If a window or `seasons` is zero, or the distribution
+            is unknown.
+
+    Example:
+        ```python
+        from rustflow.drought import ssi
+
+        ssi_6 = ssi(monthly_flows, windows=[6])[6]
+        ```
+    """
+    named, single = _by_name(flows)
+    result = drought.ssi(named, windows, seasons, distribution)
+    return result[""] if single else result
@@ -0,0 +1,106 @@
+from typing import Any
+
+from ..rustflow import economics
+
+
+def event_damage(
+    peaks: dict[str, float],
+    curves: dict[str, tuple[list[float], list[float]]],
+) -> dict[str, float]:
+    """
+    Evaluates stage–damage or flow–damage curves at the peaks of one event.
+
+    Damage is zero below the first curve value and held at the last damage
+    above the last value. A `nan` peak gives zero damage.
+
+    Args:
+        peaks (dict[str, float]): Peak stage or flow of the event at each
+            damage reach, keyed by name (e.g. network node id).
+        curves (dict[str, tuple[list[float], list[float]]]): Damage curve of
+            each reach in `peaks`: strictly increasing stages or flows and
+            the non-decreasing damage at each.
+
+    Returns:
+        dict[str, float]: Damage at each reach.
+
+    Raises:
+        ValueError: If a reach has no curve or a curve is invalid.
+
+    Example:
+        ```python
+        from rustflow.economics import event_damage
+
+        damage = event_damage(
+            {"town": max(result.stage["town"])},
+            {"town": ([101.0, 102.0, 103.5], [0.0, 2.0e6, 9.0e6])},
+        )
+        ```
+    """
+    return economics.event_damage(peaks, curves)
+
+
+def expected_annual_damage(
+    probabilities: list[float],
+    peaks: dict[str, list[float]],
+    curves: dict[str, tuple[list[float], list[float]]],
+) -> dict[str, Any]:
+    """
+    Computes event damages and expected annual damage (EAD) over a set of
+    frequency events.
+
+    Each event's routed peak at each damage reach is converted to damage
+    with the reach's curve (see `event_damage`). The EAD integrates damage
+    over annual exceedance probability by the trapezoidal rule between the
+    most and least frequent events. Add a frequent zero-damage event and a
+    rare event to bound the curve beyond the simulated ones.
+
+    Args:
+        probabilities (list[float]): Annual exceedance probability of each
+            event, all different and within (0, 1].
+        peaks (dict[str, list[float]]): Peak stage or flow of each event
+            at each damage reach, keyed by name.
+        curves (dict[str, tuple[list[float], list[float]]]): Damage curve of
+            each reach in `peaks`.
+
+    Returns:
+        dict: `event_damage` (damage of each event, keyed by reach),
+            `total_event_damage` (summed over reaches), `expected_annual_damage`
+            (keyed by reach) and `total_expected_annual_damage`.
+
+    Raises:
+        ValueError: If a reach has no curve, a curve is invalid, the peak
+            counts differ from the events, or a probability is invalid.
+
+    Example:
+        ```python
+        from rustflow.economics import expected_annual_damage
+
+        result = expected_annual_damage(
+            probabilities=[0.5, 0.1, 0.02, 0.01, 0.002],
+            peaks={"town": [100.8, 101.9, 102.6, 103.0, 103.8]},
+            curves={"town": ([101.0, 102.0, 103.5], [0.0, 2.0e6, 9.0e6])},
+        )
+        ead = result["total_expected_annual_damage"]
+        ```
+    """
+    return economics.expected_annual_damage(probabilities, peaks, curves)
+
+
+def integrate_damage(probabilities: list[float], damages: list[float]) -> float:
+    """
+    Integrates expected annual damage from event damages.
+
+    Args:
+        probabilities (list[float]): Annual exceedance probability of each
+            event, all different and within (0, 1].
+        damages (list[float]): Damage of each event.
+
+    Returns:
+        float: Trapezoidal integral of damage over exceedance probability
+            between the most and least frequent events.
+
+    Raises:
+        ValueError: If fewer than two events are given, the lengths differ,
+            or a probability is invalid.
+    """
+    return economics.integrate_damage(probabilities, damages)
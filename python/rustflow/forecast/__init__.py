@@ -0,0 +1,112 @@
+from datetime import timedelta
+from typing import Optional, Sequence
+
+from ..rustflow import forecast
+
+__all__ = ["ar1_error_correction", "enkf_muskingum"]
+
+
+def ar1_error_correction(
+    simulated: Sequence[float],
+    observed: Sequence[float],
+    phi: Optional[float] = None,
+) -> tuple[list[float], float]:
+    """
+    Updates a simulated series with an AR(1) model of its errors.
+
+    At each step the latest error `observed - simulated` seen at an earlier
+    step is added to the simulation, decayed by `phi` per step since it was
+    observed. Only past observations are used, so the result is what a
+    real-time forecaster would have issued; for a forecast, extend
+    `observed` with `nan` over the forecast horizon and the correction
+    decays towards the raw simulation.
+
+    Args:
+        simulated (Sequence[float]): Routed flows.
+        observed (Sequence[float]): Observed flows aligned with `simulated`;
+            `nan` marks missing observations.
+        phi (Optional[float], optional): Error autocorrelation. If omitted it
+            is fitted as the lag-1 autocorrelation of the observed errors.
+
+    Returns:
+        tuple[list[float], float]: The corrected series and the `phi` used.
+
+    Raises:
+        ValueError: If the series differ in length, or `phi` is omitted and
+            there are no two consecutive observations.
+    """
+    return forecast.ar1_error_correction(simulated, observed, phi)
+
+
+def enkf_muskingum(
+    inflow: Sequence[float],
+    observed: Sequence[float],
+    k: timedelta,
+    x: float,
+    time_step: timedelta,
+    sub_reaches: int = 1,
+    members: int = 100,
+    inflow_error: float = 0.2,
+    observation_error: float = 0.05,
+    seed: Optional[int] = None,
+) -> dict[str, list[float]]:
+    """
+    Routes an inflow through a Muskingum reach while assimilating observed
+    outflows with an ensemble Kalman filter.
+
+    Each member routes the inflow with a multiplicative log-normal error.
+    Whenever an observation is available, the sub-reach outflows of all
+    members are updated toward it, so the routing state, not just the
+    output, follows the observations. Steps with `nan` observations (e.g.
+    the forecast horizon) are propagated without an update.
+
+    Args:
+        inflow (Sequence[float]): Upstream inflow (observed, then forecast).
+        observed (Sequence[float]): Observed outflow aligned with `inflow`;
+            `nan` where unavailable.
+        k (timedelta): Storage time constant of the reach.
+        x (float): Muskingum weighting factor.
+        time_step (timedelta): Interval between consecutive values.
+        sub_reaches (int, optional): Number of sub-reaches (state dimension).
+            Defaults to 1.
+        members (int, optional): Ensemble size. Defaults to 100.
+        inflow_error (float, optional): Log-space standard deviation of the
+            inflow error. Defaults to 0.2.
+        observation_error (float, optional): Observation error standard
+            deviation as a fraction of the observed flow. Defaults to 0.05.
+        seed (Optional[int], optional): Seed for reproducible perturbations.
+
+    Returns:
+        dict[str, list[float]]: Ensemble `mean` outflow and its 5 % (`lower`)
+            and 95 % (`upper`) quantiles at each step.
+
+    Raises:
+        ValueError: If the series differ in length or `members` is below 2.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        import math
+        from rustflow.forecast import enkf_muskingum
+
+        # observations up to now, then a 12-step forecast horizon
+        observed = observed_flows + [math.nan] * 12
+        result = enkf_muskingum(
+            inflow, observed, timedelta(hours=4), 0.2, timedelta(hours=1),
+            sub_reaches=3, seed=7,
+        )
+        forecast = result["mean"][-12:]
+        ```
+    """
+    return forecast.enkf_muskingum(
+        inflow,
+        observed,
+        k,
+        x,
+        time_step,
+        sub_reaches,
+        members,
+        inflow_error,
+        observation_error,
+        seed,
+    )
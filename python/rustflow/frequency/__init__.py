@@ -0,0 +1,326 @@
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Union
+
+from ..rustflow import frequency
+
+IdfCurve = frequency.IdfCurve
+
+PlottingPosition = Literal["weibull", "gringorten", "cunnane", "hazen"]
+
+
+def plotting_positions(
+    values: list[float],
+    method: PlottingPosition = "weibull",
+) -> dict[str, list[float]]:
+    """
+    Computes plotting positions of a sample, e.g. annual maximum flows.
+
+    The value of rank `i` (1 for the largest) in a sample of `n` plots at
+    exceedance probability `(i - a) / (n + 1 - 2a)`, with `a = 0` for
+    Weibull, 0.44 for Gringorten, 0.4 for Cunnane and 0.5 for Hazen. NaN
+    values are ignored.
+
+    Args:
+        values (list[float]): Sample values.
+        method (Literal["weibull", "gringorten", "cunnane", "hazen"],
+            optional): Plotting-position formula. Defaults to `"weibull"`.
+
+    Returns:
+        dict[str, list[float]]: The sample `value`s in descending order,
+            with their `exceedance` probability and `return_period` (in
+            sampling intervals, e.g. years).
+
+    Raises:
+        ValueError: If the method is unknown or the sample has no values.
+
+    Example:
+        ```python
+        from rustflow.frequency import plotting_positions
+
+        peaks = [412.0, 388.0, 905.0, 251.0, 660.0, 530.0]
+        points = plotting_positions(peaks, method="gringorten")
+        # Overlay points["return_period"] vs points["value"] on a fitted curve.
+        ```
+    """
+    return frequency.plotting_positions(values, method)
+
+
+def empirical_cdf(values: list[float], points: list[float]) -> list[float]:
+    """
+    Evaluates the empirical cumulative distribution function of a sample.
+
+    Args:
+        values (list[float]): Sample values; NaNs are ignored.
+        points (list[float]): Values at which to evaluate the function.
+
+    Returns:
+        list[float]: The fraction of the sample less than or equal to each
+            point.
+
+    Example:
+        ```python
+        from rustflow.frequency import empirical_cdf
+
+        empirical_cdf([3.0, 1.0, 2.0, 4.0], [0.5, 2.0, 5.0])  # [0.0, 0.5, 1.0]
+        ```
+    """
+    return frequency.empirical_cdf(values, points)
+
+
+def empirical_quantiles(
+    values: list[float],
+    return_periods: list[float],
+    method: PlottingPosition = "weibull",
+) -> list[float]:
+    """
+    Interpolates values with given return periods from the empirical
+    frequency curve of a sample.
+
+    The curve joins the plotting positions linearly in the logarithm of the
+    return period and is extrapolated from its end segments beyond the
+    plotted range.
+
+    Args:
+        values (list[float]): Sample values, e.g. annual maximum flows.
+        return_periods (list[float]): Return periods (in sampling
+            intervals, e.g. years), each greater than 1.
+        method (Literal["weibull", "gringorten", "cunnane", "hazen"],
+            optional): Plotting-position formula. Defaults to `"weibull"`.
+
+    Returns:
+        list[float]: The value for each return period.
+
+    Raises:
+        ValueError: If a return period is not greater than 1, the method is
+            unknown or the sample has no values.
+
+    Example:
+        ```python
+        from rustflow.frequency import empirical_quantiles
+
+        peaks = [412.0, 388.0, 905.0, 251.0, 660.0, 530.0]
+        q2, q5 = empirical_quantiles(peaks, [2.0, 5.0])
+        ```
+    """
+    return frequency.empirical_quantiles(values, return_periods, method)
+
+
+def empirical_return_periods(
+    values: list[float],
+    magnitudes: list[float],
+    method: PlottingPosition = "weibull",
+) -> list[float]:
+    """
+    Interpolates the return periods of given magnitudes from the empirical
+    frequency curve of a sample.
+
+    This is the inverse of `empirical_quantiles`.
+
+    Args:
+        values (list[float]): Sample values, e.g. annual maximum flows.
+        magnitudes (list[float]): Values whose return periods are wanted.
+        method (Literal["weibull", "gringorten", "cunnane", "hazen"],
+            optional): Plotting-position formula. Defaults to `"weibull"`.
+
+    Returns:
+        list[float]: The return period (in sampling intervals) of each
+            magnitude.
+
+    Raises:
+        ValueError: If the method is unknown or the sample has no values.
+
+    Example:
+        ```python
+        from rustflow.frequency import empirical_return_periods
+
+        peaks = [412.0, 388.0, 905.0, 251.0, 660.0, 530.0]
+        (t,) = empirical_return_periods(peaks, [700.0])
+        ```
+    """
+    return frequency.empirical_return_periods(values, magnitudes, method)
+
+
+def idf_fit(
+    durations: list[timedelta],
+    annual_maxima: list[list[float]],
+    method: Literal["gev", "sherman"] = "gev",
+    distribution: Literal["gev", "gumbel"] = "gev",
+) -> IdfCurve:
+    """
+    Fits an intensity–duration–frequency (IDF) relationship to annual
+    maximum rainfall depths at several durations.
+
+    The depths are converted to intensities, and a GEV or Gumbel
+    distribution is fitted by L-moments for each duration. With `"gev"`,
+    the curve keeps these distributions and interpolates linearly in log
+    intensity and log duration between them. With `"sherman"`, it is
+    summarised by the generalized Sherman formula `i = k T^m / (d + b)^n`.
+    That formula is fitted by least squares in log space to the
+    per-duration quantiles for return periods of 2 to 200 years.
+
+    Args:
+        durations (list[timedelta]): Distinct accumulation durations.
+        annual_maxima (list[list[float]]): Annual maximum depths for each
+            duration, in any depth unit. NaN values are ignored.
+        method (Literal["gev", "sherman"], optional): Form of the curve.
+            Defaults to `"gev"`.
+        distribution (Literal["gev", "gumbel"], optional): Distribution
+            fitted to each duration. Defaults to `"gev"`.
+
+    Returns:
+        IdfCurve: Callable as `curve(duration, return_period)` for the
+            intensity in depth units per hour. It also has `intensity` and
+            `depth` methods and exposes `method` and `parameters` (Sherman
+            `b` in hours).
+
+    Raises:
+        ValueError: If the durations are not distinct and positive, a
+            duration has fewer than three maxima or no spread, or a
+            requested return period is not above 1.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.frequency import idf_fit
+
+        curve = idf_fit(
+            [timedelta(hours=1), timedelta(hours=6), timedelta(hours=24)],
+            [maxima_1h, maxima_6h, maxima_24h],
+            method="sherman",
+        )
+        i100 = curve(timedelta(hours=3), 100.0)
+        ```
+    """
+    return frequency.idf_fit(
+        durations,
+        annual_maxima,
+        method,
+        distribution,
+    )
+
+
+def low_flow(
+    flows: list[float],
+    start: datetime,
+    n_days: int = 7,
+    return_periods: list[float] = [10.0],
+    distribution: Literal["lp3", "weibull"] = "lp3",
+    year_start_month: int = 4,
+) -> dict[str, Any]:
+    """
+    Computes low-flow frequency statistics such as 7Q10 from daily flows.
+
+    The `n_days` moving average is taken over the daily series and its
+    minimum found for each climatic year, which starts on the first of
+    `year_start_month` and is named by the calendar year in which it ends.
+    Years with missing (NaN) or absent days are skipped. A log-Pearson type
+    III (method of moments on base-10 logarithms) or two-parameter Weibull
+    (maximum likelihood) distribution is fitted to the annual minima, with
+    the USGS conditional probability adjustment for years of zero flow.
+
+    Args:
+        flows (list[float]): Daily mean flows.
+        start (datetime): Date of the first value.
+        n_days (int, optional): Averaging period in days. Defaults to 7.
+        return_periods (list[float], optional): Return periods in years,
+            each greater than 1. Defaults to `[10.0]`.
+        distribution (Literal["lp3", "weibull"], optional): Distribution
+            fitted to the annual minima. Defaults to `"lp3"`.
+        year_start_month (int, optional): First month of the climatic year.
+            Defaults to 4 (April).
+
+    Returns:
+        dict[str, Any]: The climatic `years` and their `annual_minima`, the
+            `statistics` keyed by name (e.g. `"7Q10"`) and the fitted
+            `parameters`: `mean`, `std` and `skew` of the logarithms for
+            log-Pearson III or `shape` and `scale` for Weibull, and the
+            `nonzero_fraction` of years.
+
+    Raises:
+        ValueError: If fewer than three complete years have non-zero low
+            flows, or a parameter is out of range.
+
+    Example:
+        ```python
+        from datetime import datetime, timedelta
+        from rustflow.frequency import low_flow
+
+        result = low_flow(daily_flows, datetime(1980, 4, 1))
+        q7_10 = result["statistics"]["7Q10"]
+        ```
+    """
+    return frequency.low_flow(
+        flows,
+        start,
+        n_days,
+        return_periods,
+        distribution,
+        year_start_month,
+    )
+
+
+def regression_peaks(
+    equations: Union[str, PathLike, dict[str, Any]],
+    characteristics: dict[str, float],
+    confidence: float = 0.9,
+) -> dict[str, dict[str, Union[float, bool]]]:
+    """
+    Estimates peak-flow statistics at an ungauged site from regional
+    regression equations.
+
+    Equations follow the form published for USGS StreamStats: a transformed
+    statistic (usually `log10(Q)`) is `intercept + sum(coefficient *
+    transform(characteristic + offset))`. Prediction intervals follow
+    Tasker and Driver (1988), with the variance of prediction equal to the
+    model error variance plus the sampling variance `x U x'` from the
+    equation's `covariance` matrix `U` (intercept first), or its
+    `average_sampling_variance` when no matrix is given.
+
+    Args:
+        equations (str | PathLike | dict): A `.toml` or `.json` file, or a
+            dict, holding either one equation or an `equations` list. Each
+            equation has a `statistic` name, an `intercept` and a
+            `predictors` list of `name`, `coefficient` and optional
+            `transform` (`"log10"` by default, `"ln"` or `"none"`),
+            `offset`, `min` and `max`; and optional `response` (`"log10"`
+            by default), `model_variance`, `covariance`,
+            `average_sampling_variance` and `degrees_of_freedom` (Student's
+            t intervals; normal intervals if omitted).
+        characteristics (dict[str, float]): Basin characteristics by name,
+            in the units of the equations.
+        confidence (float, optional): Coverage of the two-sided prediction
+            interval. Defaults to 0.9.
+
+    Returns:
+        dict[str, dict[str, float | bool]]: For each statistic, the
+            `estimate`, the interval bounds `lower` and `upper`, the
+            `standard_error` of prediction in transformed units and whether
+            it is `extrapolated` beyond the range of a characteristic.
+
+    Raises:
+        ValueError: If an equation is malformed or a characteristic is
+            missing or cannot be transformed.
+
+    Example:
+        ```python
+        from rustflow.frequency import regression_peaks
+
+        equation = {
+            "statistic": "Q100",
+            "intercept": 2.35,
+            "predictors": [
+                {"name": "drainage_area", "coefficient": 0.62, "min": 1, "max": 2500},
+                {"name": "precipitation", "coefficient": 1.1},
+            ],
+            "model_variance": 0.042,
+            "average_sampling_variance": 0.004,
+            "degrees_of_freedom": 85,
+        }
+        peaks = regression_peaks(
+            equation, {"drainage_area": 120.0, "precipitation": 38.0}
+        )
+        q100 = peaks["Q100"]["estimate"]
+        ```
+    """
+    return frequency.regression_peaks(equations, characteristics, confidence)
@@ -0,0 +1,268 @@
+"""
+Pure-function kernels for embedding rustflow in differentiable pipelines.
+
+The functions take plain C-contiguous `float32` or `float64` arrays (NumPy
+arrays or any buffer) and return NumPy arrays of the same dtype, with no
+Python objects in between and no state. A 1-D inflow is one series; a 2-D
+inflow of shape `(batch, steps)` is a batch routed in parallel, with `k` and
+`x` either shared or given per series. Time steps and `k` are in seconds.
+
+`muskingum_vjp` gives the vector–Jacobian product needed by reverse-mode
+automatic differentiation, so `torch_muskingum` and `jax_muskingum` wrap
+the kernel as a custom operator that gradients flow through, for hybrid
+models that learn routing parameters alongside a neural network.
+"""
+
+from typing import Any, Union
+
+from ..rustflow import functional
+
+__all__ = ["jax_muskingum", "muskingum", "muskingum_vjp", "torch_muskingum"]
+
+Parameter = Union[float, Any]
+
+
+def muskingum(
+    inflow: Any, k: Parameter, x: Parameter, dt: float, sub_reaches: int = 1
+) -> Any:
+    """
+    Routes one series or a batch of series through Muskingum reaches.
+
+    Each series starts from its first inflow. The result has the shape and
+    dtype of `inflow`; `float32` inputs are routed in `float64` and stored
+    in `float32`.
+
+    Args:
+        inflow (Any): C-contiguous `float32` or `float64` array of shape
+            `(steps,)` or `(batch, steps)`.
+        k (Parameter): Storage constant of each reach (s), a number or a
+            1-D array of one value per series.
+        x (Parameter): Weighting factor, a number or one value per series.
+        dt (float): Time step (s).
+        sub_reaches (int, optional): Number of sub-reaches. Defaults to 1.
+
+    Returns:
+        Any: Outflow array, or a memoryview when NumPy is not installed.
+
+    Raises:
+        ValueError: If the array is not 1-D or 2-D, a parameter has the
+            wrong length, or a parameter is invalid.
+        TypeError: If `inflow` is not a `float32` or `float64` array.
+
+    Example:
+        ```python
+        import numpy as np
+        from rustflow.functional import muskingum
+
+        inflow = np.full((4, 48), 10.0, dtype=np.float32)
+        inflow[:, 5] = 80.0
+        outflow = muskingum(inflow, np.array([3600.0, 7200.0, 10800.0, 14400.0]), 0.2, 3600.0)
+        ```
+    """
+    return functional.muskingum(inflow, k, x, dt, sub_reaches)
+
+
+def muskingum_vjp(
+    inflow: Any,
+    k: Parameter,
+    x: Parameter,
+    dt: float,
+    grad_outflow: Any,
+    sub_reaches: int = 1,
+) -> tuple[Any, Any, Any]:
+    """
+    Vector–Jacobian product of `muskingum`.
+
+    Given the gradient of a scalar loss with respect to the outflow, returns
+    its gradients with respect to the inflow, `k` and `x`. Routing is
+    linear in the inflow, so the inflow gradient is exact (the adjoint
+    recursion run backwards in time), as are the parameter gradients.
+
+    Args:
+        inflow (Any): Inflow array, as for `muskingum`.
+        k (Parameter): Storage constant (s), as for `muskingum`.
+        x (Parameter): Weighting factor, as for `muskingum`.
+        dt (float): Time step (s).
+        grad_outflow (Any): Gradient with respect to the outflow, with the
+            shape and dtype of `inflow`.
+        sub_reaches (int, optional): Number of sub-reaches. Defaults to 1.
+
+    Returns:
+        tuple[Any, Any, Any]: Gradients with respect to the inflow (shape of
+            `inflow`), and to `k` (per second) and `x`, each with one value
+            per series, all in the dtype of `inflow`.
+
+    Raises:
+        ValueError: If the shapes or dtypes differ or a parameter is
+            invalid.
+
+    Example:
+        ```python
+        import numpy as np
+        from rustflow.functional import muskingum_vjp
+
+        grad_inflow, grad_k, grad_x = muskingum_vjp(
+            inflow, 7200.0, 0.2, 3600.0, np.ones_like(inflow)
+        )
+        ```
+    """
+    return functional.muskingum_vjp(inflow, k, x, dt, grad_outflow, sub_reaches)
+
+
+def _reduce_to(grad: Any, like: Any) -> Any:
+    """Sums a per-series gradient for a parameter shared by the batch."""
+    return grad.sum().reshape(like.shape) if like.numel() == 1 else grad.reshape(like.shape)
+
+
+def torch_muskingum(inflow: Any, k: Any, x: Any, dt: float, sub_reaches: int = 1) -> Any:
+    """
+    Muskingum routing as a PyTorch operator with gradients.
+
+    The forward and backward passes run the Rust kernels on CPU copies of
+    the tensors; results return to the device and dtype of `inflow`.
+    Gradients flow to any of `inflow`, `k` and `x` that require them, so a
+    network can predict inflows or routing parameters and be trained
+    through the routing.
+
+    Args:
+        inflow (torch.Tensor): `float32` or `float64` tensor of shape
+            `(steps,)` or `(batch, steps)`.
+        k (torch.Tensor): Storage constant (s), a scalar tensor or one value
+            per series.
+        x (torch.Tensor): Weighting factor, a scalar tensor or one value per
+            series.
+        dt (float): Time step (s).
+        sub_reaches (int, optional): Number of sub-reaches. Defaults to 1.
+
+    Returns:
+        torch.Tensor: Outflow with the shape, dtype and device of `inflow`.
+
+    Raises:
+        ImportError: If PyTorch is not installed.
+
+    Example:
+        ```python
+        import torch
+        from rustflow.functional import torch_muskingum
+
+        k = torch.tensor(7200.0, dtype=torch.float64, requires_grad=True)
+        outflow = torch_muskingum(inflow, k, torch.tensor(0.2, dtype=torch.float64), 3600.0)
+        ((outflow - observed) ** 2).sum().backward()
+        print(k.grad)
+        ```
+    """
+    import numpy as np
+    import torch
+
+    class Muskingum(torch.autograd.Function):
+        @staticmethod
+        def forward(ctx, inflow, k, x):
+            ctx.save_for_backward(inflow, k, x)
+            q = np.ascontiguousarray(inflow.detach().cpu().numpy())
+            out = muskingum(
+                q,
+                k.detach().cpu().double().numpy().ravel(),
+                x.detach().cpu().double().numpy().ravel(),
+                dt,
+                sub_reaches,
+            )
+            return torch.from_numpy(np.array(out)).to(inflow.device)
+
+        @staticmethod
+        def backward(ctx, grad_outflow):
+            inflow, k, x = ctx.saved_tensors
+            q = np.ascontiguousarray(inflow.detach().cpu().numpy())
+            g = np.ascontiguousarray(grad_outflow.detach().cpu().numpy().astype(q.dtype))
+            grad_q, grad_k, grad_x = muskingum_vjp(
+                q,
+                k.detach().cpu().double().numpy().ravel(),
+                x.detach().cpu().double().numpy().ravel(),
+                dt,
+                g,
+                sub_reaches,
+            )
+            as_tensor = lambda a, like: torch.from_numpy(np.array(a)).to(like.device, like.dtype)
+            return (
+                as_tensor(grad_q, inflow),
+                _reduce_to(as_tensor(grad_k, k), k),
+                _reduce_to(as_tensor(grad_x, x), x),
+            )
+
+    return Muskingum.apply(inflow, k, x)
+
+
+def jax_muskingum(inflow: Any, k: Any, x: Any, dt: float, sub_reaches: int = 1) -> Any:
+    """
+    Muskingum routing as a JAX operator with a custom vector–Jacobian
+    product.
+
+    The kernels are called through `jax.pure_callback`, so the operator can
+    be used inside `jax.jit` and differentiated with `jax.grad`; it runs on
+    the host. Shared `k` and `x` receive the summed gradient of the batch.
+
+    Args:
+        inflow (jax.Array): `float32` or `float64` array of shape `(steps,)`
+            or `(batch, steps)`.
+        k (jax.Array): Storage constant (s), a scalar or one value per
+            series.
+        x (jax.Array): Weighting factor, a scalar or one value per series.
+        dt (float): Time step (s).
+        sub_reaches (int, optional): Number of sub-reaches. Defaults to 1.
+
+    Returns:
+        jax.Array: Outflow with the shape and dtype of `inflow`.
+
+    Raises:
+        ImportError: If JAX is not installed.
+
+    Example:
+        ```python
+        import jax
+        import jax.numpy as jnp
+        from rustflow.functional import jax_muskingum
+
+        loss = lambda k: jnp.sum((jax_muskingum(inflow, k, 0.2, 3600.0) - observed) ** 2)
+        print(jax.grad(loss)(7200.0))
+        ```
+    """
+    import jax
+    import jax.numpy as jnp
+    import numpy as np
+
+    def host_forward(q, k, x):
+        q = np.ascontiguousarray(q)
+        return np.asarray(muskingum(q, np.ravel(k), np.ravel(x), dt, sub_reaches))
+
+    def host_vjp(q, k, x, g):
+        q = np.ascontiguousarray(q)
+        g = np.ascontiguousarray(g, dtype=q.dtype)
+        return tuple(
+            np.asarray(a)
+            for a in muskingum_vjp(q, np.ravel(k), np.ravel(x), dt, g, sub_reaches)
+        )
+
+    @jax.custom_vjp
+    def route(q, k, x):
+        shape = jax.ShapeDtypeStruct(q.shape, q.dtype)
+        return jax.pure_callback(host_forward, shape, q, k, x)
+
+    def route_forward(q, k, x):
+        return route(q, k, x), (q, k, x)
+
+    def route_backward(residuals, g):
+        q, k, x = residuals
+        batch = q.shape[0] if q.ndim == 2 else 1
+        shapes = (
+            jax.ShapeDtypeStruct(q.shape, q.dtype),
+            jax.ShapeDtypeStruct((batch,), q.dtype),
+            jax.ShapeDtypeStruct((batch,), q.dtype),
+        )
+        grad_q, grad_k, grad_x = jax.pure_callback(host_vjp, shapes, q, k, x, g)
+        reduce = lambda grad, like: (
+            jnp.sum(grad).reshape(like.shape) if like.size == 1 else grad.reshape(like.shape)
+        ).astype(like.dtype)
+        return grad_q, reduce(grad_k, k), reduce(grad_x, x)
+
+    route.defvjp(route_forward, route_backward)
+    inflow = jnp.asarray(inflow)
+    return route(inflow, jnp.asarray(k, dtype=inflow.dtype), jnp.asarray(x, dtype=inflow.dtype))
@@ -0,0 +1,94 @@
+from datetime import timedelta
+from typing import Literal, Optional
+
+from ..rustflow import groundwater
+
+
+def simulate(
+    recharge: list[float],
+    time_step: timedelta,
+    area: float,
+    specific_yield: float,
+    k: Optional[timedelta] = None,
+    coefficient: Optional[float] = None,
+    exponent: float = 1.0,
+    bottom: float = 0.0,
+    initial_head: Optional[float] = None,
+    stream_level: Optional[list[float]] = None,
+    conductance: Optional[float] = None,
+    stream_bed: Optional[float] = None,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, list[float]]:
+    """
+    Simulates an unconfined aquifer draining to a stream as baseflow.
+
+    Recharge fills a groundwater store that drains either as a linear
+    reservoir (`q = S / k`) or a nonlinear one (`q = coefficient * S **
+    exponent`, with `q` in mm/h or in/h and the stored depth `S` in mm or
+    in). With a `stream_level` series and a streambed `conductance`, the
+    aquifer also exchanges water with the stream following the MODFLOW
+    river package: `C * (h - stream_level)` while the water table `h` is
+    above `stream_bed`, and `C * (stream_bed - stream_level)` below it.
+
+    Args:
+        recharge (list[float]): Recharge rate per time step (mm/h or in/h,
+            per `units`).
+        time_step (timedelta): Interval between consecutive values.
+        area (float): Aquifer plan area (ha or acres).
+        specific_yield (float): Drainable porosity, in (0, 1].
+        k (timedelta, optional): Recession constant of a linear reservoir.
+        coefficient (float, optional): Coefficient of a nonlinear
+            reservoir; give either `k` or `coefficient`.
+        exponent (float, optional): Exponent of a nonlinear reservoir.
+            Defaults to 1.
+        bottom (float, optional): Elevation of the drainage base (m or ft).
+            Defaults to 0.
+        initial_head (float, optional): Initial water table elevation (m or
+            ft). Defaults to `bottom`.
+        stream_level (list[float], optional): Stream water surface
+            elevation per time step (m or ft).
+        conductance (float, optional): Streambed conductance (m²/s or
+            ft²/s); required with `stream_level`.
+        stream_bed (float, optional): Streambed elevation (m or ft).
+            Defaults to `bottom`.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            results. Defaults to `"si"`.
+
+    Returns:
+        dict[str, list[float]]: `baseflow` and `exchange` (flow to the
+            stream, negative when the stream loses water) in m³/s or cfs,
+            `head` (water table elevation) and `storage` (drainable volume
+            in m³ or ft³) at the end of each step.
+
+    Raises:
+        ValueError: If neither or both of `k` and `coefficient` are given,
+            or a parameter is out of range.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.groundwater import simulate
+
+        recharge = [0.5] * 48 + [0.0] * 500  # mm/h
+        result = simulate(
+            recharge, timedelta(hours=1), area=500.0, specific_yield=0.15,
+            k=timedelta(days=20),
+        )
+        baseflow = result["baseflow"]
+        ```
+    """
+    return groundwater.simulate(
+        recharge,
+        time_step,
+        area,
+        specific_yield,
+        k,
+        coefficient,
+        exponent,
+        bottom,
+        initial_head,
+        stream_level,
+        conductance,
+        stream_bed,
+        units,
+    )
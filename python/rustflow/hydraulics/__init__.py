@@ -0,0 +1,387 @@
+from datetime import datetime
+from typing import Any, Literal, Optional, Union
+
+from ..rustflow import hydraulics
+
+Culvert = hydraulics.Culvert
+
+Units = Literal["si", "us"]
+
+
+def manning_discharge(
+    depth: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    units: Units = "si",
+) -> float:
+    """
+    Computes the normal-flow discharge of a trapezoidal channel using
+    Manning's equation.
+
+    Inputs are converted to SI internally, so the same roughness `n` is used
+    regardless of the unit system (the 1.486 factor of the US customary form
+    is applied implicitly).
+
+    Args:
+        depth (float): Flow depth (m or ft).
+        bottom_width (float): Channel bottom width (m or ft). Use 0 for a
+            triangular channel.
+        side_slope (float): Bank slope as horizontal run per unit rise
+            (z in zH:1V). Use 0 for a rectangular channel.
+        n (float): Manning's roughness coefficient.
+        slope (float): Longitudinal bed slope (dimensionless).
+        units (Literal["si", "us"], optional): Unit system of the length
+            inputs and the returned discharge. `"si"` uses metres and m³/s,
+            `"us"` uses feet and cfs. Defaults to `"si"`.
+
+    Returns:
+        float: Discharge in m³/s (`units="si"`) or cfs (`units="us"`).
+
+    Raises:
+        ValueError: If `units` is not recognised or the channel geometry,
+            `n` or `slope` is non-physical.
+
+    Example:
+        ```python
+        from rustflow.hydraulics import manning_discharge
+
+        q = manning_discharge(1.5, 10.0, 2.0, 0.035, 0.001, units="us")
+        ```
+    """
+    return hydraulics.manning_discharge(
+        depth, bottom_width, side_slope, n, slope, units
+    )
+
+
+def normal_depth(
+    discharge: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    units: Units = "si",
+) -> float:
+    """
+    Computes the normal depth of a trapezoidal channel for a given discharge
+    by inverting Manning's equation.
+
+    Args:
+        discharge (float): Discharge (m³/s or cfs).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Bank slope as horizontal run per unit rise.
+        n (float): Manning's roughness coefficient.
+        slope (float): Longitudinal bed slope (dimensionless).
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            the returned depth. Defaults to `"si"`.
+
+    Returns:
+        float: Normal depth in m (`units="si"`) or ft (`units="us"`).
+
+    Raises:
+        ValueError: If `units` is not recognised or the channel geometry,
+            `n` or `slope` is non-physical.
+    """
+    return hydraulics.normal_depth(discharge, bottom_width, side_slope, n, slope, units)
+
+
+def weir_flow(
+    upstream: float,
+    downstream: float,
+    crest: float,
+    length: float,
+    coefficient: Optional[float] = None,
+    units: Units = "si",
+) -> float:
+    """
+    Computes the flow over a weir between two water levels.
+
+    The free-flow discharge `C L H^1.5` is reduced for submergence by the
+    Villemonte (1947) factor `(1 - (H2 / H1)^1.5)^0.385`, where `H1` and
+    `H2` are the heads above the crest on the high and low sides.
+
+    Args:
+        upstream (float): Water level on the upstream side (m or ft).
+        downstream (float): Water level on the downstream side (m or ft).
+        crest (float): Crest elevation (m or ft).
+        length (float): Crest length (m or ft).
+        coefficient (float, optional): Weir coefficient `C`, in m^0.5/s
+            (`units="si"`) or ft^0.5/s (`units="us"`). Defaults to the
+            broad-crested 1.7 m^0.5/s (3.08 ft^0.5/s).
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            the returned discharge. Defaults to `"si"`.
+
+    Returns:
+        float: Discharge in m³/s or cfs, negative when the downstream level
+            is the higher.
+
+    Raises:
+        ValueError: If `units` is not recognised or `length` or
+            `coefficient` is not positive.
+
+    Example:
+        ```python
+        from rustflow.hydraulics import weir_flow
+
+        q = weir_flow(103.0, 101.5, 100.0, 20.0, coefficient=3.0, units="us")
+        ```
+    """
+    return hydraulics.weir_flow(upstream, downstream, crest, length, coefficient, units)
+
+
+def travel_time(
+    discharges: list[float],
+    length: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    units: Units = "si",
+) -> dict[str, Any]:
+    """
+    Computes the kinematic wave celerity and travel time of a uniform
+    trapezoidal reach over a range of discharges.
+
+    At each discharge the normal depth is found from Manning's equation and
+    the wave celerity is `dQ/dA` of the normal-flow rating (5/3 of the mean
+    velocity in a wide channel). The wave travel time (length over
+    celerity) is the Muskingum `K` of the reach, so the table can drive
+    variable-K routing. The water travel time (length over mean velocity)
+    is the time for a spill or tracer to arrive downstream.
+
+    Args:
+        discharges (list[float]): Discharges (m³/s or cfs). Any iterable of
+            numbers or Arrow array is accepted.
+        length (float): Reach length (m or ft).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Bank slope as horizontal run per unit rise.
+        n (float): Manning's roughness coefficient.
+        slope (float): Longitudinal bed slope (dimensionless).
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            the returned depths and speeds. Defaults to `"si"`.
+
+    Returns:
+        dict: `discharge`, normal `depth`, mean `velocity`, wave `celerity`
+            (m/s or ft/s), and `travel_time` and `water_travel_time`
+            (list[timedelta]) at each discharge.
+
+    Raises:
+        ValueError: If `units` is not recognised, the channel geometry, `n`
+            or `slope` is non-physical, or `length` or a discharge is not
+            positive.
+
+    Example:
+        ```python
+        from rustflow.hydraulics import travel_time
+
+        table = travel_time([10, 50, 200], 12_000, 20.0, 2.0, 0.035, 0.0005)
+        k = table["travel_time"]
+        ```
+    """
+    return hydraulics.travel_time(
+        discharges, length, bottom_width, side_slope, n, slope, units
+    )
+
+
+def bridge_backwater(
+    discharges: list[float],
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    opening_width: float,
+    low_chord: float,
+    length: float,
+    pier_width: float = 0.0,
+    pier_drag: float = 2.0,
+    abutment_slope: float = 0.0,
+    contraction: float = 0.3,
+    expansion: float = 0.5,
+    tailwater: Optional[list[float]] = None,
+    method: Literal["energy", "momentum", "highest"] = "highest",
+    units: Units = "si",
+) -> dict[str, Any]:
+    """
+    Computes the backwater raised by a bridge opening across a trapezoidal
+    channel, after the HEC-RAS low-flow and pressure-flow bridge methods.
+
+    From the tailwater depth below the bridge, the energy method balances
+    energy through the opening with expansion, friction and contraction
+    losses, and the momentum method balances momentum with the force on the
+    abutments and piers and the pier drag. Where no subcritical depth
+    balances, the opening chokes at critical depth. Once the upstream
+    energy reaches the low chord the opening flows as a sluice gate, or as
+    an orifice when the tailwater also submerges it. Flow over the deck is
+    not modelled.
+
+    Args:
+        discharges (list[float]): Discharges (m³/s or cfs).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Channel bank slope, horizontal per unit rise.
+        n (float): Manning's roughness of the channel and opening.
+        slope (float): Channel bed slope.
+        opening_width (float): Bottom width between the abutments.
+        low_chord (float): Height of the low chord above the bed.
+        length (float): Length of the opening along the flow.
+        pier_width (float, optional): Total width of the piers. Defaults to
+            0.
+        pier_drag (float, optional): Pier drag coefficient, 1.2 for
+            circular, 1.33 for semicircular and 2.0 for square piers.
+            Defaults to 2.0.
+        abutment_slope (float, optional): Abutment slope, horizontal per
+            unit rise. Defaults to 0 (vertical).
+        contraction (float, optional): Contraction loss coefficient.
+            Defaults to 0.3.
+        expansion (float, optional): Expansion loss coefficient. Defaults
+            to 0.5.
+        tailwater (list[float], optional): Tailwater depth at each
+            discharge. Defaults to normal depth in the channel.
+        method (Literal["energy", "momentum", "highest"], optional):
+            Low-flow method; `"highest"` takes the higher upstream level of
+            the two. Defaults to `"highest"`.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            outputs. Defaults to `"si"`.
+
+    Returns:
+        dict: `discharge`, `upstream_depth` (at the upstream face),
+            `opening_depth`, `downstream_depth`, `backwater` (rise over
+            the unobstructed water surface) and `flow` (`"free"`,
+            `"choked"`, `"sluice"` or `"orifice"`) at each discharge.
+
+    Raises:
+        ValueError: If the channel or bridge geometry is non-physical, the
+            method is unknown or `tailwater` does not match `discharges`.
+
+    Example:
+        ```python
+        from rustflow.hydraulics import bridge_backwater
+
+        table = bridge_backwater(
+            [20, 50, 100], 20.0, 2.0, 0.035, 0.001, 12.0, 3.0, 10.0,
+            pier_width=1.5,
+        )
+        ```
+    """
+    return hydraulics.bridge_backwater(
+        discharges,
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        opening_width,
+        low_chord,
+        length,
+        pier_width,
+        pier_drag,
+        abutment_slope,
+        contraction,
+        expansion,
+        tailwater,
+        method,
+        units,
+    )
+
+
+def rate_stage(
+    stage: list[float],
+    times: list[datetime],
+    rating_stage: Optional[list[float]] = None,
+    rating_discharge: Optional[list[float]] = None,
+    coefficient: Optional[float] = None,
+    offset: float = 0.0,
+    exponent: Optional[float] = None,
+    shifts: Optional[
+        list[tuple[datetime, Union[float, list[tuple[float, float]]]]]
+    ] = None,
+    fall: Optional[list[float]] = None,
+    reference_fall: Optional[Union[float, list[tuple[float, float]]]] = None,
+    fall_exponent: float = 0.5,
+    ice: Optional[list[tuple[datetime, datetime, float]]] = None,
+) -> dict[str, Any]:
+    """
+    Converts a stage record to discharge through a rating curve, with shift,
+    backwater and ice adjustments.
+
+    The open-water rating is a stage–discharge table or the power law
+    `Q = coefficient (h - offset)^exponent`. Three adjustments can be
+    applied (Rantz et al., 1982):
+
+    - Shifts are stage corrections added to the gauge height before it
+      enters the rating. Each dated shift is constant or varies with stage
+      (a variable-shift diagram). Shifts are prorated linearly in time
+      between their dates and held before the first and after the last.
+    - A stage–fall rating for variable backwater multiplies the rated
+      discharge by `(F / Fr)^N`. `F` is the measured fall to an auxiliary
+      gauge and `Fr` the reference fall of the rating, constant or varying
+      with stage.
+    - During ice periods, the open-water discharge is multiplied by an ice
+      coefficient.
+
+    Stages, falls and discharges are in the units of the rating.
+
+    Args:
+        stage (list[float]): Gauge heights. Any iterable of numbers or Arrow
+            array is accepted.
+        times (list[datetime]): Time of each gauge height.
+        rating_stage (list[float], optional): Stages of a rating table,
+            strictly increasing.
+        rating_discharge (list[float], optional): Discharge at each rating
+            stage.
+        coefficient (float, optional): Power-law rating coefficient, used
+            instead of a table.
+        offset (float, optional): Gauge height of zero flow of the power
+            law. Defaults to 0.
+        exponent (float, optional): Power-law rating exponent.
+        shifts (list[tuple[datetime, float | list[tuple[float, float]]]],
+            optional): Shifts in order of time, each a constant or
+            `(stage, shift)` pairs, in effect from its date.
+        fall (list[float], optional): Measured fall to the auxiliary gauge
+            at each time; requires `reference_fall`.
+        reference_fall (float | list[tuple[float, float]], optional):
+            Reference fall of a stage–fall rating, constant or as
+            `(stage, fall)` pairs.
+        fall_exponent (float, optional): Exponent `N` of the fall ratio.
+            Defaults to 0.5.
+        ice (list[tuple[datetime, datetime, float]], optional): Start, end
+            and ice coefficient of each ice-affected period.
+
+    Returns:
+        dict: `discharge`, with the `shift`, `fall_factor` and
+            `ice_coefficient` applied at each time.
+
+    Raises:
+        ValueError: If the rating is not given as a table or a power law,
+            the series lengths differ, a fall record and reference fall are
+            not given together, or a table is invalid.
+
+    Example:
+        ```python
+        from datetime import datetime
+        from rustflow.hydraulics import rate_stage
+
+        record = rate_stage(
+            stage,
+            times,
+            coefficient=12.5,
+            offset=0.4,
+            exponent=1.6,
+            shifts=[(datetime(2024, 3, 1), 0.0), (datetime(2024, 6, 1), -0.05)],
+            ice=[(datetime(2024, 12, 10), datetime(2025, 3, 5), 0.7)],
+        )
+        ```
+    """
+    return hydraulics.rate_stage(
+        stage,
+        times,
+        rating_stage,
+        rating_discharge,
+        coefficient,
+        offset,
+        exponent,
+        shifts,
+        fall,
+        reference_fall,
+        fall_exponent,
+        ice,
+    )
@@ -0,0 +1,323 @@
+from datetime import datetime, timedelta
+from typing import Any, Literal, Optional
+
+from ..rustflow import hydrograph
+
+
+def iha(
+    flows: list[float],
+    start: datetime,
+    impact_year: Optional[int] = None,
+    statistic: Literal["parametric", "nonparametric"] = "nonparametric",
+    year_start_month: int = 10,
+) -> dict[str, Any]:
+    """
+    Computes the Indicators of Hydrologic Alteration (IHA) of daily flows.
+
+    The 33 IHA parameters (Richter et al., 1996) are computed for every
+    complete year: monthly flows (`"january"` ... `"december"`); annual
+    minimum and maximum 1-, 3-, 7-, 30- and 90-day means (`"min_7_day"`,
+    `"max_1_day"`, ...), `"zero_flow_days"` and `"base_flow_index"` (7-day
+    minimum over the annual mean); the day of year of the annual minimum
+    and maximum (`"date_of_min"`, `"date_of_max"`); the count and mean
+    duration of low and high pulses, below the 25th and above the 75th
+    percentile of the (pre-impact) daily flows; and the mean `"rise_rate"`
+    and `"fall_rate"` and the number of `"reversals"`. Years with missing
+    (NaN) or absent days are skipped.
+
+    With an `impact_year`, years named before it form the pre-impact period.
+    The periods are compared by the relative `deviation` of their central
+    tendency and by the Range of Variability Approach (Richter et al.,
+    1997): the `alteration` of each parameter is the observed minus the
+    expected number of post-impact years within the pre-impact middle
+    range (33rd–67th percentiles, or mean ± one standard deviation), over
+    the expected number.
+
+    Args:
+        flows (list[float]): Daily mean flows.
+        start (datetime): Date of the first value.
+        impact_year (int, optional): First year of the post-impact period.
+        statistic (Literal["parametric", "nonparametric"], optional): Use
+            means and coefficients of variation, or medians and
+            coefficients of dispersion. Defaults to `"nonparametric"`.
+        year_start_month (int, optional): First month of the year, which
+            is named by the calendar year in which it ends. Defaults to 10
+            (water years starting in October).
+
+    Returns:
+        dict[str, Any]: The `years` and the per-year `parameters` keyed by
+            name. Without an `impact_year`, a `summary` of each parameter's
+            `central` tendency and `dispersion`; with one, `pre` and `post`
+            summaries and the `deviation` and `alteration` of each
+            parameter.
+
+    Raises:
+        ValueError: If a period has fewer than two complete years, or a
+            parameter is out of range.
+
+    Example:
+        ```python
+        from datetime import datetime
+        from rustflow.hydrograph import iha
+
+        result = iha(daily_flows, datetime(1950, 10, 1), impact_year=1975)
+        change = result["deviation"]["max_1_day"]
+        ```
+    """
+    return hydrograph.iha(flows, start, impact_year, statistic, year_start_month)
+
+
+def scale_to_peak(
+    flows: list[float],
+    peak: float,
+    baseflow: float = 0.0,
+) -> list[float]:
+    """
+    Scales a hydrograph so that its peak equals a target.
+
+    The flow above `baseflow` is multiplied by a single factor, which keeps
+    the shape and timing.
+
+    Args:
+        flows (list[float]): Pattern hydrograph. Any iterable of
+            numbers or Arrow array is accepted.
+        peak (float): Target peak flow.
+        baseflow (float, optional): Flow left unscaled. Defaults to 0.
+
+    Returns:
+        list[float]: The scaled hydrograph.
+
+    Raises:
+        ValueError: If the hydrograph does not rise above the baseflow or
+            the target peak is below it.
+    """
+    return hydrograph.scale_to_peak(flows, peak, baseflow)
+
+
+def scale_to_volume(
+    flows: list[float],
+    volume: float,
+    time_step: timedelta,
+    baseflow: float = 0.0,
+) -> list[float]:
+    """
+    Scales a hydrograph so that its volume above baseflow equals a target.
+
+    The volume is the sum of the ordinates above `baseflow` times the time
+    step, in flow units × seconds (e.g. m³ for m³/s).
+
+    Args:
+        flows (list[float]): Pattern hydrograph.
+        volume (float): Target volume above the baseflow.
+        time_step (timedelta): Interval between ordinates.
+        baseflow (float, optional): Flow left unscaled. Defaults to 0.
+
+    Returns:
+        list[float]: The scaled hydrograph.
+
+    Raises:
+        ValueError: If the hydrograph carries no volume above the baseflow
+            or the target volume is negative.
+    """
+    return hydrograph.scale_to_volume(flows, volume, time_step, baseflow)
+
+
+def balanced_hydrograph(
+    durations: list[timedelta],
+    flows: list[float],
+    time_step: timedelta,
+    pattern: Optional[list[float]] = None,
+    peak_position: float = 0.5,
+) -> list[float]:
+    """
+    Builds a balanced design hydrograph from flow-frequency quantiles at
+    several durations.
+
+    The maximum mean flow over each duration equals the given quantile,
+    e.g. the 1-day, 3-day and 7-day mean flows of the 100-year event.
+    Durations must increase, mean flows must not increase, and volumes must
+    not decrease with duration.
+
+    Without a `pattern`, nested blocks are used. The shortest duration is
+    placed at its mean flow. Each longer duration's extra volume is then
+    spread evenly over its extra steps, `peak_position` of them before the
+    peak and the rest after. The result spans the longest duration.
+
+    With a `pattern`, its nested windows of largest volume are found
+    around the peak. The ordinates added by each longer window are scaled
+    to that window's volume increment, which keeps the pattern's shape.
+    Ordinates outside the longest window are unchanged.
+
+    Args:
+        durations (list[timedelta]): Durations, whole multiples of
+            `time_step`.
+        flows (list[float]): Maximum mean flow over each duration.
+        time_step (timedelta): Interval between ordinates.
+        pattern (list[float], optional): Pattern hydrograph at `time_step`,
+            at least as long as the longest duration.
+        peak_position (float, optional): Fraction of each volume increment
+            placed before the peak, for block hydrographs. Defaults to 0.5.
+
+    Returns:
+        list[float]: The balanced hydrograph.
+
+    Raises:
+        ValueError: If durations are not whole, increasing multiples of the
+            time step, the quantiles are inconsistent, or the pattern is too
+            short.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.hydrograph import balanced_hydrograph
+
+        inflow = balanced_hydrograph(
+            durations=[timedelta(days=1), timedelta(days=3), timedelta(days=7)],
+            flows=[1200.0, 900.0, 550.0],
+            time_step=timedelta(hours=1),
+            pattern=historical_flood,
+        )
+        ```
+    """
+    return hydrograph.balanced_hydrograph(
+        durations, flows, time_step, pattern, peak_position
+    )
+
+
+def lag_correlation(
+    upstream: list[float],
+    downstream: list[float],
+    max_lag: int,
+) -> dict[str, Any]:
+    """
+    Computes the cross-correlation between an upstream and a downstream
+    series and estimates the lag between them.
+
+    The Pearson correlation of `downstream[t + lag]` with `upstream[t]` is
+    computed for every lag from `-max_lag` to `max_lag` steps. Pairs with a
+    missing (`nan`) value are skipped. The best lag approximates the
+    travel time between the gauges, e.g. as an initial Muskingum K. It can
+    also check routed flows against observations.
+
+    Args:
+        upstream (list[float]): Upstream (or routed) series. Any iterable of
+            numbers or Arrow array is accepted.
+        downstream (list[float]): Downstream (or observed) series of the
+            same length and time step.
+        max_lag (int): Largest lag, in steps.
+
+    Returns:
+        dict: `lags` (steps; positive when downstream follows upstream),
+            `correlation` at each lag, `best_lag` and `best_correlation`,
+            and `refined_lag`, the best lag refined to a fraction of a step
+            by a parabola through the peak.
+
+    Raises:
+        ValueError: If the lengths differ, `max_lag` leaves fewer than
+            three pairs, or the series have no variation.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.hydrograph import lag_correlation
+
+        result = lag_correlation(upstream_flows, downstream_flows, max_lag=48)
+        k = timedelta(hours=result["refined_lag"])
+        ```
+    """
+    return hydrograph.lag_correlation(upstream, downstream, max_lag)
+
+
+def signatures(
+    flows: list[float],
+    precipitation: Optional[list[float]] = None,
+    time_step: Optional[timedelta] = None,
+) -> dict[str, float]:
+    """
+    Computes hydrologic signatures of a flow series.
+
+    Signatures summarise how a catchment responds (Sawicz et al., 2011;
+    Addor et al., 2018). Comparing them between observed and simulated
+    flows shows which parts of the behaviour a model gets wrong, and they
+    can serve as calibration objectives.
+
+    Args:
+        flows (list[float]): Gap-free flow series, normally daily. Any
+            iterable of numbers or Arrow array is accepted.
+        precipitation (Optional[list[float]], optional): Catchment
+            precipitation over the same steps, in the same depth or volume
+            units as the flows, for the runoff ratio. `nan` values are
+            skipped. Defaults to None.
+        time_step (Optional[timedelta], optional): Interval between values.
+            Defaults to one day.
+
+    Returns:
+        dict[str, float]:
+            - `mean_flow`;
+            - `runoff_ratio`, total flow over total precipitation (`nan`
+              without precipitation);
+            - `flashiness`, the Richards–Baker index: the sum of absolute
+              step changes over the total flow;
+            - `baseflow_index`, the share of flow that is base flow, from the
+              Lyne–Hollick filter (three passes, α = 0.925);
+            - `fdc_slope`, the slope of the flow duration curve between the
+              33 % and 66 % exceedance flows, in log space (`nan` if either
+              is zero);
+            - `q5` and `q95`, the 5th and 95th percentiles of flow;
+            - `high_flow_frequency` and `high_flow_duration`, events per
+              year above nine times the median flow and their mean length
+              in days;
+            - `low_flow_frequency` and `low_flow_duration`, the same for
+              flows below a fifth of the mean flow.
+
+    Raises:
+        ValueError: If the flows have fewer than two values or contain
+            gaps, or the precipitation has a different length.
+
+    Example:
+        ```python
+        from rustflow.hydrograph import signatures
+
+        s = signatures(daily_flows, daily_precipitation)
+        print(s["baseflow_index"], s["flashiness"])
+        ```
+    """
+    return hydrograph.signatures(flows, precipitation, time_step)
+
+
+def compare_signatures(
+    observed: list[float],
+    simulated: list[float],
+    precipitation: Optional[list[float]] = None,
+    time_step: Optional[timedelta] = None,
+) -> dict[str, dict[str, float]]:
+    """
+    Compares the hydrologic signatures of simulated and observed flows.
+
+    Args:
+        observed (list[float]): Gap-free observed flows.
+        simulated (list[float]): Simulated flows over the same steps.
+        precipitation (Optional[list[float]], optional): Precipitation for
+            the runoff ratio, as for `signatures`. Defaults to None.
+        time_step (Optional[timedelta], optional): Interval between values.
+            Defaults to one day.
+
+    Returns:
+        dict[str, dict[str, float]]: `observed` and `simulated` signatures,
+            as returned by `signatures`, and the `relative_error` of each
+            simulated signature, `(simulated - observed) / |observed|`, zero
+            where the two agree exactly.
+
+    Raises:
+        ValueError: If the series differ in length or are invalid for
+            `signatures`.
+
+    Example:
+        ```python
+        from rustflow.hydrograph import compare_signatures
+
+        errors = compare_signatures(observed, simulated)["relative_error"]
+        print(errors["baseflow_index"], errors["high_flow_frequency"])
+        ```
+    """
+    return hydrograph.compare_signatures(observed, simulated, precipitation, time_step)
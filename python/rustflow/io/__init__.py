@@ -0,0 +1,490 @@
+import json
+from datetime import datetime, timedelta
+from os import PathLike, fspath
+from typing import Any, Literal, Mapping, Optional, Sequence, Union
+
+from ..rustflow import io
+
+CsvChunkReader = io.CsvChunkReader
+SeriesFile = io.SeriesFile
+
+
+def read_hydrograph_csv(
+    path: Union[str, PathLike],
+    datetime_format: Optional[str] = None,
+    time_column: Optional[str] = None,
+    columns: Optional[Union[Sequence[str], Mapping[str, str]]] = None,
+    missing_values: Optional[Sequence[str]] = None,
+    delimiter: str = ",",
+) -> tuple[list[datetime], dict[str, list[float]]]:
+    """
+    Reads a timestamped discharge CSV file.
+
+    Parsing is done in Rust and is considerably faster than `pandas.read_csv`
+    for long, high-frequency records.
+
+    Args:
+        path (str | PathLike): Path of the CSV file. The first non-empty line
+            must be a header row.
+        datetime_format (Optional[str], optional): `strftime`-style format of
+            the timestamps, supporting `%Y`, `%y`, `%m`, `%d`, `%H`, `%M`,
+            `%S` (with optional fractional seconds) and `%f`; `%y` maps
+            69–99 to 1969–1999 and 00–68 to 2000–2068. If not provided,
+            common ISO 8601 layouts are detected from the first row.
+        time_column (Optional[str], optional): Name of the timestamp column.
+            Defaults to the first column.
+        columns (Optional[Sequence[str] | Mapping[str, str]], optional):
+            Columns to read. A mapping renames CSV columns (keys) to output
+            names (values). Defaults to every non-time column.
+        missing_values (Optional[Sequence[str]], optional): Cell values to
+            treat as missing (e.g. `["NA", "-9999"]`). Empty cells are
+            always missing. Missing values are returned as `nan`.
+        delimiter (str, optional): Field separator. Defaults to `","`.
+
+    Returns:
+        tuple[list[datetime], dict[str, list[float]]]: Naive timestamps and a
+            mapping of column name to discharge values.
+
+    Raises:
+        OSError: If the file cannot be read.
+        ValueError: If a timestamp or value cannot be parsed or a requested
+            column does not exist.
+
+    Example:
+        ```python
+        from rustflow.io import read_hydrograph_csv
+
+        times, flows = read_hydrograph_csv(
+            "gage.csv",
+            datetime_format="%m/%d/%Y %H:%M",
+            columns={"Flow (cfs)": "flow"},
+            missing_values=["Eqp", "-999"],
+        )
+        ```
+    """
+    if columns is not None and not isinstance(columns, Mapping):
+        columns = {name: name for name in columns}
+
+    return io.read_hydrograph_csv(
+        path,
+        datetime_format,
+        time_column,
+        list(columns.items()) if columns is not None else None,
+        list(missing_values) if missing_values is not None else None,
+        delimiter,
+    )
+
+
+def read_hydrograph_csv_chunks(
+    path: Union[str, PathLike],
+    rows: int = 100_000,
+    datetime_format: Optional[str] = None,
+    time_column: Optional[str] = None,
+    columns: Optional[Union[Sequence[str], Mapping[str, str]]] = None,
+    missing_values: Optional[Sequence[str]] = None,
+    delimiter: str = ",",
+) -> CsvChunkReader:
+    """
+    Reads a timestamped discharge CSV file in blocks of rows.
+
+    Only one block is held in memory at a time, so records larger than
+    memory (e.g. a century of 1-minute data) can be processed block by
+    block with the stateful processors such as
+    `rustflow.timeseries.RunningStats` and `rustflow.reach.MuskingumReach`.
+
+    Args:
+        path (str | PathLike): Path of the CSV file. The first non-empty line
+            must be a header row.
+        rows (int, optional): Maximum number of rows per block. Defaults to
+            100 000.
+        datetime_format (Optional[str], optional): Timestamp format, as for
+            `read_hydrograph_csv`. Detected from the first row if omitted.
+        time_column (Optional[str], optional): Name of the timestamp column.
+            Defaults to the first column.
+        columns (Optional[Sequence[str] | Mapping[str, str]], optional):
+            Columns to read, as for `read_hydrograph_csv`.
+        missing_values (Optional[Sequence[str]], optional): Cell values to
+            treat as missing. Empty cells are always missing.
+        delimiter (str, optional): Field separator. Defaults to `","`.
+
+    Returns:
+        CsvChunkReader: An iterator of `(times, flows)` pairs, one per
+            block, in the form returned by `read_hydrograph_csv`.
+
+    Raises:
+        OSError: If the file cannot be opened or read.
+        ValueError: If `rows` is 0, the header lacks a requested column, or
+            a timestamp or value cannot be parsed (raised by the iterator
+            on the offending block).
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.io import read_hydrograph_csv_chunks
+        from rustflow.reach import MuskingumReach
+        from rustflow.timeseries import RunningStats
+
+        # One-minute steps need at least 2 K x / dt = 48 sub-reaches to keep
+        # the routing coefficients positive.
+        reach = MuskingumReach(
+            timedelta(hours=2), 0.2, timedelta(minutes=1), sub_reaches=60
+        )
+        stats = RunningStats()
+        for times, flows in read_hydrograph_csv_chunks("gage_1min.csv", rows=525_600):
+            stats.update(reach.route(flows["flow"]))
+        print(stats.mean, stats.max)
+        ```
+    """
+    if columns is not None and not isinstance(columns, Mapping):
+        columns = {name: name for name in columns}
+
+    return io.read_hydrograph_csv_chunks(
+        path,
+        rows,
+        datetime_format,
+        time_column,
+        list(columns.items()) if columns is not None else None,
+        list(missing_values) if missing_values is not None else None,
+        delimiter,
+    )
+
+
+def write_hydrograph_csv(
+    path: Union[str, PathLike],
+    times: Sequence[datetime],
+    flows: Mapping[str, Sequence[float]],
+    datetime_format: Optional[str] = None,
+    time_column: str = "time",
+    missing_value: str = "",
+    delimiter: str = ",",
+) -> None:
+    """
+    Writes discharge series to a timestamped CSV file.
+
+    Args:
+        path (str | PathLike): Path of the CSV file to create or overwrite.
+        times (Sequence[datetime]): Timestamp of each row. Time zones are
+            ignored.
+        flows (Mapping[str, Sequence[float]]): Mapping of column name to
+            values, written in mapping order.
+        datetime_format (Optional[str], optional): `strftime`-style format of
+            the timestamps. Defaults to `"%Y-%m-%d %H:%M:%S"`.
+        time_column (str, optional): Header of the timestamp column. Defaults
+            to `"time"`.
+        missing_value (str, optional): Text written for `nan` values.
+            Defaults to an empty cell.
+        delimiter (str, optional): Field separator. Defaults to `","`.
+
+    Raises:
+        OSError: If the file cannot be written.
+    """
+    return io.write_hydrograph_csv(
+        path,
+        list(times),
+        {name: list(values) for name, values in flows.items()},
+        datetime_format,
+        time_column,
+        missing_value,
+        delimiter,
+    )
+
+
+def from_arrow(batch) -> dict[str, list[float]]:
+    """
+    Reads the numeric columns of an Arrow record batch.
+
+    Column buffers are read directly through the Arrow PyCapsule interface
+    (`__arrow_c_array__`), so any Arrow producer (pyarrow, polars, nanoarrow,
+    ...) is supported without converting to Python lists first. The values
+    are copied once into Rust-owned buffers rather than shared, because the
+    numerical cores keep their inputs beyond the lifetime of the exported
+    arrays; nulls and non-`float64` columns are converted in the same pass.
+
+    Args:
+        batch: An object implementing `__arrow_c_array__` that exports a
+            struct array, such as `pyarrow.RecordBatch`. A `pyarrow.Table` is
+            accepted and combined into a single batch.
+
+    Returns:
+        dict[str, list[float]]: Mapping of column name to values. Nulls are
+            returned as `nan`.
+
+    Raises:
+        TypeError: If a column is not numeric.
+    """
+    if hasattr(batch, "combine_chunks") and hasattr(batch, "to_batches"):
+        batches = batch.combine_chunks().to_batches()
+        if not batches:
+            # A table without rows has no batches.
+            import pyarrow as pa
+
+            batches = [pa.RecordBatch.from_pylist([], schema=batch.schema)]
+        batch = batches[0]
+    return io.from_arrow(batch)
+
+
+def _table(times, columns: Mapping[str, Sequence[float]]):
+    import pyarrow as pa
+
+    table = {}
+    if times is not None:
+        times = list(times)
+        if times and isinstance(times[0], timedelta):
+            table["time"] = pa.array(times, type=pa.duration("us"))
+        else:
+            table["time"] = pa.array(times, type=pa.timestamp("us"))
+    for name, values in columns.items():
+        table[name] = pa.array(values, type=pa.float64(), from_pandas=True)
+    return pa.table(table)
+
+
+def to_arrow(times: Sequence[datetime], flows: Mapping[str, Sequence[float]]):
+    """
+    Builds a `pyarrow.Table` with a `time` column and one float64 column per
+    flow series.
+
+    Args:
+        times (Sequence[datetime]): Timestamp of each row.
+        flows (Mapping[str, Sequence[float]]): Mapping of column name to
+            values.
+
+    Returns:
+        pyarrow.Table: The assembled table.
+
+    Raises:
+        ImportError: If `pyarrow` is not installed.
+    """
+    return _table(times, flows)
+
+
+def _routing_result_to_arrow(result):
+    """Backs `RoutingResult.to_arrow`."""
+    return _table(
+        result.times,
+        {"inflow": result.inflow, "outflow": result.outflow, **result.states},
+    )
+
+
+def _network_result_to_arrow(result, times=None, variable="outflow"):
+    """Backs `NetworkResult.to_arrow`."""
+    return _table(times, getattr(result, variable))
+
+
+def read_parquet(
+    path: Union[str, PathLike], time_column: str = "time"
+) -> tuple[list[datetime], dict[str, list[float]]]:
+    """
+    Reads a Parquet file of simulation inputs or outputs.
+
+    Args:
+        path (str | PathLike): Path of the Parquet file.
+        time_column (str, optional): Name of the timestamp column. Defaults
+            to `"time"`.
+
+    Returns:
+        tuple[list[datetime], dict[str, list[float]]]: Timestamps and a
+            mapping of column name to values.
+
+    Raises:
+        ImportError: If `pyarrow` is not installed.
+    """
+    import pyarrow.parquet as pq
+
+    table = pq.read_table(path)
+    times = table.column(time_column).to_pylist()
+    return times, from_arrow(table.drop_columns([time_column]))
+
+
+def write_parquet(
+    path: Union[str, PathLike],
+    times: Sequence[datetime],
+    flows: Mapping[str, Sequence[float]],
+) -> None:
+    """
+    Writes flow series to a Parquet file with a `time` column.
+
+    Args:
+        path (str | PathLike): Path of the Parquet file to create.
+        times (Sequence[datetime]): Timestamp of each row.
+        flows (Mapping[str, Sequence[float]]): Mapping of column name to
+            values.
+
+    Raises:
+        ImportError: If `pyarrow` is not installed.
+    """
+    import pyarrow.parquet as pq
+
+    pq.write_table(to_arrow(times, flows), path)
+
+
+def write_series_file(
+    path: Union[str, PathLike],
+    series: Mapping[str, Sequence[float]],
+    start: Optional[datetime] = None,
+    time_step: Optional[timedelta] = None,
+) -> None:
+    """
+    Writes series to a binary file for fast, memory-mapped reloading.
+
+    Each series is stored as one contiguous block of little-endian
+    `float64` values after a short header, so `SeriesFile` can map the file
+    and slice any part of any series without reading the rest. This suits
+    large simulation outputs read repeatedly, e.g. in calibration loops;
+    use Parquet or NetCDF for interchange with other tools.
+
+    Args:
+        path (str | PathLike): Path of the file to create.
+        series (Mapping[str, Sequence[float]]): Series by name, all of the
+            same length.
+        start (Optional[datetime], optional): Time of the first value.
+        time_step (Optional[timedelta], optional): Interval between values.
+
+    Raises:
+        OSError: If the file cannot be written.
+        ValueError: If the series differ in length.
+
+    Example:
+        ```python
+        from rustflow.io import SeriesFile, write_series_file
+
+        write_series_file("run.rfs", result.outflow, start, time_step)
+        with SeriesFile("run.rfs") as f:
+            peak = max(f["outlet"][8760:17520])
+        ```
+    """
+    io.write_series_file(path, list(series.items()), start, time_step)
+
+
+def read_swmm_inp(path: Union[str, PathLike]) -> dict:
+    """
+    Translates an EPA SWMM 5 `.inp` file into a network configuration.
+
+    Subcatchments become nonlinear reservoir `subcatchment` nodes, conduits
+    become `kinematic_wave` reaches, storage units become level-pool
+    `reservoir` nodes and junctions, outfalls and non-conduit links become
+    pass-through `junction` nodes. The result is intended for fast screening
+    runs rather than as a replacement for SWMM's dynamic wave solver:
+
+    - every node must have a single outgoing link (no flow splits);
+    - closed shapes are replaced by a rectangle of equal full-flow area;
+    - infiltration is a constant loss on the pervious area (Horton minimum
+      rate or Green-Ampt conductivity).
+
+    Flows are computed in cfs for US `FLOW_UNITS` and m³/s for SI ones.
+    Each subcatchment reads a rainfall series (mm/h or in/h) named after its
+    rain gage.
+
+    Args:
+        path (str | PathLike): The `.inp` file.
+
+    Returns:
+        dict: A configuration accepted by `rustflow.network.Network`, with
+            `units`, `nodes` and, when `ROUTING_STEP` is set, `time_step` in
+            seconds.
+
+    Raises:
+        OSError: If the file cannot be read.
+        ValueError: If the model uses unsupported features.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.io import read_swmm_inp
+        from rustflow.network import Network
+
+        network = Network(read_swmm_inp("model.inp"))
+        result = network.run({"RG1": rain_in_per_hour}, timedelta(minutes=1))
+        ```
+    """
+    return io.read_swmm_inp(path)
+
+
+def read_geojson_network(
+    source: Union[str, PathLike, Mapping[str, Any]],
+    id_field: str = "id",
+    from_field: str = "from_node",
+    to_field: str = "to_node",
+    geographic: bool = False,
+    units: Literal["si", "us"] = "si",
+    reference_flow: Optional[float] = None,
+    tolerance: Optional[float] = None,
+) -> dict:
+    """
+    Builds a network configuration from a GeoJSON layer of reach polylines
+    and catchment polygons.
+
+    Connectivity comes from attributes: each reach names the node it leaves
+    (`from_field`) and enters (`to_field`), and each catchment names the
+    node it drains to (`to_field`). A reach drains to the reach leaving its
+    end node, and a node that no reach leaves becomes an outlet `junction`
+    named after it. Flow splits (two reaches leaving one node), cycles,
+    duplicate ids and catchments draining to unknown nodes are rejected.
+
+    Feature properties are copied into the node configuration, so element
+    parameters and `inflow` or `rainfall` series names can be attributes.
+    Reach `length` and catchment `area` (ha or acres) are measured from the
+    geometry unless given. A reach without a `type` property becomes a
+    Muskingum–Cunge reach: its `bottom_width`, `side_slope`, `n`, `slope`
+    and `reference_flow` properties give `k = L / c` and
+    `x = (1 - q0 / (B S c L)) / 2` at the normal depth of the reference
+    flow. A catchment without a `type` becomes a `subcatchment`.
+
+    Args:
+        source (str | PathLike | Mapping[str, Any]): A GeoJSON
+            FeatureCollection, as a file path, JSON text or parsed mapping.
+        id_field (str, optional): Property holding the node id; the
+            feature `id` is used when it is missing. Defaults to `"id"`.
+        from_field (str, optional): Property naming a reach's upstream
+            node. Defaults to `"from_node"`.
+        to_field (str, optional): Property naming a reach's downstream node
+            or a catchment's outlet node. Defaults to `"to_node"`.
+        geographic (bool, optional): Whether coordinates are longitude and
+            latitude in degrees. Otherwise they are planar in metres (SI) or
+            feet (US). Defaults to False.
+        units (Literal["si", "us"], optional): Unit system of the
+            configuration. Defaults to `"si"`.
+        reference_flow (float, optional): Muskingum–Cunge reference flow
+            of reaches without a `reference_flow` property.
+        tolerance (float, optional): Largest gap allowed between the end of
+            a reach and the start of the reach it drains to, in metres or
+            feet. Not checked by default.
+
+    Returns:
+        dict: A configuration accepted by `rustflow.network.Network`, with
+            `units` and `nodes`.
+
+    Raises:
+        OSError: If the file cannot be read.
+        ValueError: If a feature lacks an id or connectivity attribute, a
+            reach lacks Muskingum–Cunge properties, or the network is not a
+            valid tree.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.io import read_geojson_network
+        from rustflow.network import Network
+
+        config = read_geojson_network(
+            "rivers.geojson", from_field="FNODE", to_field="TNODE", reference_flow=50.0
+        )
+        result = Network(config).run({"upper": inflow}, timedelta(hours=1))
+        ```
+    """
+    if isinstance(source, Mapping):
+        text = json.dumps(source)
+    elif isinstance(source, str) and source.lstrip().startswith("{"):
+        text = source
+    else:
+        with open(fspath(source), encoding="utf-8") as file:
+            text = file.read()
+    return io.read_geojson_network(
+        text,
+        id_field,
+        from_field,
+        to_field,
+        geographic,
+        units,
+        reference_flow,
+        tolerance,
+    )
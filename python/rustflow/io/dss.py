@@ -0,0 +1,163 @@
+"""
+HEC-DSS time series interchange.
+
+Pathname and interval handling is native; reading and writing DSS files uses
+the optional `pydsstools` package, which wraps the HEC-DSS 7 library.
+"""
+
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import NamedTuple, Optional, Sequence, Union
+
+from ..rustflow import io
+
+
+class DssPathname(NamedTuple):
+    """The six parts of a `/A/B/C/D/E/F/` HEC-DSS pathname."""
+
+    a: str
+    b: str
+    c: str
+    d: str
+    e: str
+    f: str
+
+    def __str__(self) -> str:
+        return io.format_dss_pathname(*self)
+
+
+def parse_pathname(pathname: str) -> DssPathname:
+    """
+    Splits a HEC-DSS pathname into its parts.
+
+    Args:
+        pathname (str): Pathname such as `"/BASIN/GAGE1/FLOW//15Minute/OBS/"`.
+
+    Returns:
+        DssPathname: The upper-cased A–F parts.
+
+    Raises:
+        ValueError: If the pathname does not have six `/`-delimited parts.
+    """
+    return DssPathname(*io.parse_dss_pathname(pathname))
+
+
+def flow_pathname(
+    basin: str, location: str, time_step: timedelta, version: str, parameter="FLOW"
+) -> str:
+    """
+    Builds the pathname of a regular-interval flow record.
+
+    Args:
+        basin (str): A part (project or basin).
+        location (str): B part (gauge or node id).
+        time_step (timedelta): Record interval, mapped to the E part.
+        version (str): F part (version or scenario, e.g. `"ROUTED"`).
+        parameter (str, optional): C part. Defaults to `"FLOW"`.
+
+    Returns:
+        str: The pathname with an empty D part, as used to address a whole
+            record.
+
+    Raises:
+        ValueError: If `time_step` is not a regular HEC-DSS interval.
+    """
+    return io.format_dss_pathname(
+        basin, location, parameter, "", io.dss_interval(time_step), version
+    )
+
+
+def _open(path):
+    try:
+        from pydsstools.heclib.dss import HecDss
+    except ImportError as e:
+        raise ImportError(
+            "Reading and writing HEC-DSS files requires `pydsstools`."
+        ) from e
+    return HecDss.Open(str(path))
+
+
+def read_dss(
+    path: Union[str, PathLike],
+    pathname: str,
+    start: Optional[datetime] = None,
+    end: Optional[datetime] = None,
+) -> tuple[list[datetime], list[float]]:
+    """
+    Reads a regular-interval time series record, e.g. an observed inflow.
+
+    Args:
+        path (str | PathLike): DSS file.
+        pathname (str): Record pathname; the D part may be empty.
+        start (Optional[datetime], optional): Start of the window to read.
+        end (Optional[datetime], optional): End of the window to read.
+            `start` and `end` must be given together.
+
+    Returns:
+        tuple[list[datetime], list[float]]: Timestamps and values, with DSS
+            missing values returned as `nan`.
+
+    Raises:
+        ImportError: If `pydsstools` is not installed.
+        ValueError: If `pathname` is malformed.
+    """
+    parse_pathname(pathname)
+    window = None
+    if start is not None and end is not None:
+        fmt = "%d%b%Y %H:%M:%S"
+        window = (start.strftime(fmt).upper(), end.strftime(fmt).upper())
+    with _open(path) as fid:
+        ts = fid.read_ts(pathname, window=window, trim_missing=True)
+        times = list(ts.pytimes)
+        nodata = set(getattr(ts, "nodata", []))
+        values = [
+            float("nan") if i in nodata else float(v)
+            for i, v in enumerate(ts.values)
+        ]
+    return times, values
+
+
+def write_dss(
+    path: Union[str, PathLike],
+    pathname: str,
+    start: datetime,
+    values: Sequence[float],
+    units: str = "CFS",
+    data_type: str = "INST-VAL",
+) -> None:
+    """
+    Writes a regular-interval time series record, e.g. a routed outflow.
+
+    The record interval is taken from the E part of `pathname`, so outputs
+    can be read back by HEC-HMS and HEC-RAS without further mapping.
+
+    Args:
+        path (str | PathLike): DSS file, created if needed.
+        pathname (str): Record pathname with a regular-interval E part.
+        start (datetime): Timestamp of the first value.
+        values (Sequence[float]): Values to write; `nan` is stored as
+            missing.
+        units (str, optional): Units label. Defaults to `"CFS"`.
+        data_type (str, optional): DSS data type, e.g. `"INST-VAL"` or
+            `"PER-AVER"`. Defaults to `"INST-VAL"`.
+
+    Raises:
+        ImportError: If `pydsstools` is not installed.
+        ValueError: If `pathname` is malformed or its E part is not a
+            regular interval.
+    """
+    from pydsstools.core import TimeSeriesContainer
+
+    parts = parse_pathname(pathname)
+    io.dss_interval_seconds(parts.e)
+
+    tsc = TimeSeriesContainer()
+    tsc.pathname = str(parts)
+    tsc.startDateTime = start.strftime("%d%b%Y %H:%M:%S").upper()
+    tsc.numberValues = len(values)
+    tsc.units = units
+    tsc.type = data_type
+    tsc.interval = 1
+    tsc.values = [float(v) for v in values]
+    with _open(path) as fid:
+        fid.put_ts(tsc)
@@ -0,0 +1,6 @@
+from ..rustflow import network
+
+Network = network.Network
+NetworkResult = network.NetworkResult
+
+__all__ = ["Network", "NetworkResult"]
@@ -0,0 +1,185 @@
+"""
+Standard hydrograph plots with matplotlib, which is imported only when a
+plot is drawn (`pip install rustflow[plot]`).
+
+The result objects call into this module from their `plot` methods, e.g.
+`RoutingResult.plot()` and `NetworkResult.plot()`.
+"""
+
+from datetime import datetime, timedelta
+from typing import Any, Mapping, Optional, Sequence, Union
+
+Time = Union[datetime, timedelta, float]
+
+
+def _pyplot():
+    try:
+        import matplotlib.pyplot as plt
+    except ImportError as err:
+        raise ImportError(
+            "Plotting needs matplotlib; install it with `pip install matplotlib` "
+            "or `pip install rustflow[plot]`."
+        ) from err
+    return plt
+
+
+def _bar_width(times: Sequence[Time]) -> float:
+    """Width of a precipitation bar: the interval between the first two
+    times, in days (matplotlib's date unit) for datetimes."""
+    if len(times) < 2:
+        return 1.0
+    step = times[1] - times[0]
+    return step.total_seconds() / 86400.0 if isinstance(step, timedelta) else step
+
+
+def hydrograph(
+    times: Sequence[Time],
+    flows: Mapping[str, Sequence[float]],
+    precipitation: Optional[Sequence[float]] = None,
+    events: Optional[Sequence[tuple[Time, Time]]] = None,
+    ax=None,
+    title: Optional[str] = None,
+    flow_label: str = "Flow",
+    precipitation_label: str = "Precipitation",
+    styles: Optional[Mapping[str, Mapping[str, Any]]] = None,
+):
+    """
+    Plots a standard hydrograph.
+
+    Each series of `flows` is drawn as a line. Precipitation is drawn as
+    bars hanging from the top of the plot on a second, inverted axis. Events
+    are shaded.
+
+    Args:
+        times (Sequence[datetime | timedelta | float]): Time of each value.
+            Timedeltas are plotted in hours.
+        flows (Mapping[str, Sequence[float]]): Flow series keyed by legend
+            label, e.g. `{"inflow": ..., "outflow": ...}`.
+        precipitation (Optional[Sequence[float]], optional): Precipitation
+            depth or intensity at each time. Defaults to None.
+        events (Optional[Sequence[tuple]], optional): `(start, end)` pairs
+            of times to shade, e.g. storm events or flood periods. Defaults
+            to None.
+        ax (matplotlib.axes.Axes, optional): Axes to draw on. Defaults to
+            None, creating a new figure.
+        title (Optional[str], optional): Plot title. Defaults to None.
+        flow_label (str, optional): Flow axis label, e.g. with its units.
+            Defaults to `"Flow"`.
+        precipitation_label (str, optional): Precipitation axis label.
+            Defaults to `"Precipitation"`.
+        styles (Optional[Mapping[str, Mapping[str, Any]]], optional):
+            Keyword arguments for `Axes.plot` by series label, e.g.
+            `{"observed": {"linestyle": "none", "marker": "."}}`. Defaults
+            to None.
+
+    Returns:
+        matplotlib.axes.Axes: The flow axes; the precipitation axes, if any,
+            is its `precipitation_axes` attribute.
+
+    Raises:
+        ImportError: If matplotlib is not installed.
+
+    Example:
+        ```python
+        from rustflow.plot import hydrograph
+
+        ax = hydrograph(
+            times, {"observed": observed, "simulated": simulated},
+            precipitation=rain, events=[(storm_start, storm_end)],
+            flow_label="Flow (m³/s)", precipitation_label="Rain (mm)",
+        )
+        ax.figure.savefig("hydrograph.png")
+        ```
+    """
+    plt = _pyplot()
+    if ax is None:
+        _, ax = plt.subplots(figsize=(10, 5))
+    times = list(times)
+    hours = bool(times) and isinstance(times[0], timedelta)
+    if hours:
+        times = [t.total_seconds() / 3600.0 for t in times]
+        events = [
+            (start.total_seconds() / 3600.0, end.total_seconds() / 3600.0)
+            for start, end in events or []
+        ]
+
+    for start, end in events or []:
+        ax.axvspan(start, end, color="0.85", alpha=0.6, linewidth=0, zorder=0)
+    for label, values in flows.items():
+        ax.plot(times, list(values), label=label, **dict((styles or {}).get(label, {})))
+    ax.set_ylabel(flow_label)
+    ax.set_xlabel("Time (h)" if hours else "Time")
+    ax.set_ylim(bottom=0.0)
+    ax.grid(True, alpha=0.3)
+
+    precipitation_axes = None
+    if precipitation is not None:
+        precipitation = list(precipitation)
+        precipitation_axes = ax.twinx()
+        precipitation_axes.bar(
+            times,
+            precipitation,
+            width=_bar_width(times),
+            color="tab:blue",
+            alpha=0.4,
+            label=precipitation_label,
+        )
+        # Bars hang from the top, leaving the lower part for the flows.
+        top = max((p for p in precipitation if p == p), default=0.0)
+        precipitation_axes.set_ylim(3.0 * top if top > 0 else 1.0, 0.0)
+        precipitation_axes.set_ylabel(precipitation_label)
+    ax.precipitation_axes = precipitation_axes
+
+    if flows:
+        ax.legend(loc="center right")
+    if title is not None:
+        ax.set_title(title)
+    return ax
+
+
+def _routing_result(result, ax=None, title=None, **kwargs):
+    """Plot of a `RoutingResult`: its inflow and outflow."""
+    units = (result.parameters or {}).get("units")
+    label = {"si": "Flow (m³/s)", "us": "Flow (cfs)"}.get(units, "Flow")
+    if title is None:
+        title = f"{result.method.replace('_', ' ').capitalize()} routing"
+    kwargs.setdefault("flow_label", label)
+    return hydrograph(
+        result.times,
+        {"inflow": result.inflow, "outflow": result.outflow},
+        ax=ax,
+        title=title,
+        **kwargs,
+    )
+
+
+def _network_result(result, nodes=None, times=None, inflow=True, ax=None, **kwargs):
+    """Plot of a `NetworkResult`: the outflow, and optionally the inflow,
+    of each of `nodes` (default: the last node)."""
+    ids = list(result.node_ids)
+    if nodes is None:
+        nodes = ids[-1:]
+    elif isinstance(nodes, str):
+        nodes = [nodes]
+    missing = [n for n in nodes if n not in ids]
+    if missing:
+        raise KeyError(f"Unknown node(s): {', '.join(missing)}")
+    series = {}
+    styles = dict(kwargs.pop("styles", None) or {})
+    for node in nodes:
+        prefix = f"{node} " if len(nodes) > 1 else ""
+        if inflow:
+            series[f"{prefix}inflow"] = result.inflow[node]
+            styles.setdefault(f"{prefix}inflow", {"linestyle": "--"})
+        series[f"{prefix}outflow"] = result.outflow[node]
+    length = len(next(iter(series.values()), []))
+    if times is None:
+        times = list(range(length))
+    if len(times) != length:
+        raise ValueError(f"Got {len(times)} times for {length} steps.")
+    if len(nodes) == 1:
+        kwargs.setdefault("title", nodes[0])
+    return hydrograph(times, series, ax=ax, styles=styles, **kwargs)
+
+
+__all__ = ["hydrograph"]
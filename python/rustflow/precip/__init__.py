@@ -0,0 +1,333 @@
+from datetime import timedelta
+from collections.abc import Mapping
+from typing import Any, Literal, Optional, Union
+
+from ..rustflow import precip
+
+
+def areal_average(
+    series: dict[str, list[float]],
+    gauges: dict[str, tuple[float, float]],
+    basin: list[tuple[float, float]],
+    method: Literal["thiessen", "idw", "weights"] = "thiessen",
+    power: float = 2.0,
+    weights: Optional[dict[str, float]] = None,
+    resolution: int = 200,
+) -> dict[str, Union[list[float], dict[str, float]]]:
+    """
+    Computes basin-average rainfall from gauge series.
+
+    Thiessen and inverse-distance weights are computed by sampling the
+    basin polygon on a regular grid. Each sample point goes to its nearest
+    gauge (Thiessen) or is shared among all gauges in proportion to `1 /
+    distance ** power` (IDW). A gauge's weight is its share of the points.
+    User weights are used as given. When gauges are missing (NaN) at a
+    step, the weights are recomputed over the gauges that reported.
+
+    Args:
+        series (dict[str, list[float]]): Rainfall series keyed by gauge, of
+            equal length.
+        gauges (dict[str, tuple[float, float]]): Planar `(x, y)` location
+            of each gauge, e.g. in projected metres.
+        basin (list[tuple[float, float]]): Vertices of the basin polygon in
+            the same coordinates.
+        method (Literal["thiessen", "idw", "weights"], optional): Weighting
+            method. Defaults to `"thiessen"`.
+        power (float, optional): IDW distance exponent. Defaults to 2.
+        weights (dict[str, float], optional): Weight of each gauge; required
+            with `method="weights"`.
+        resolution (int, optional): Sample points along the longer side of
+            the basin's bounding box. Defaults to 200.
+
+    Returns:
+        dict: The basin `average` series (NaN where no gauge reported) and
+            the `weights` of each gauge when all report.
+
+    Raises:
+        ValueError: If a gauge has no location (or weight), the series
+            lengths differ, or the polygon or a parameter is invalid.
+
+    Example:
+        ```python
+        from rustflow.precip import areal_average
+
+        result = areal_average(
+            {"a": rain_a, "b": rain_b, "c": rain_c},
+            {"a": (0.0, 0.0), "b": (8000.0, 1000.0), "c": (3000.0, 9000.0)},
+            basin=[(0.0, 0.0), (10000.0, 0.0), (10000.0, 10000.0), (0.0, 10000.0)],
+        )
+        basin_rain = result["average"]
+        ```
+    """
+    return precip.areal_average(
+        series,
+        gauges,
+        basin,
+        method,
+        power,
+        weights,
+        resolution,
+    )
+
+
+def areal_reduction(
+    depths: list[float],
+    durations: list[timedelta],
+    area: float,
+    method: Literal["fsr", "tp29"] = "fsr",
+    table: Optional[dict[str, Any]] = None,
+    units: Literal["si", "us"] = "si",
+) -> list[float]:
+    """
+    Converts point design depths to basin-average depths with areal
+    reduction factors (ARFs).
+
+    `"tp29"` uses the curves of U.S. Weather Bureau Technical Paper 29 as
+    fitted by Leclerc and Schaake (1972). `"fsr"` uses the Flood Studies
+    Report relation `ARF = 1 - a t^-b` of the Flood Estimation Handbook.
+    A `table` of factors overrides `method`; it is interpolated bilinearly
+    and held constant beyond its range.
+
+    Args:
+        depths (list[float]): Point depths for the same return period, in
+            any unit.
+        durations (list[timedelta]): Duration of each depth.
+        area (float): Basin area (km² or mi² per `units`).
+        method (Literal["fsr", "tp29"], optional): Published relation.
+            Defaults to `"fsr"`.
+        table (dict, optional): Factors with increasing `areas` (km² or
+            mi²), increasing `durations` (timedeltas) and `factors`, one
+            row per duration and one column per area.
+        units (Literal["si", "us"], optional): Unit system of the areas.
+            Defaults to `"si"`.
+
+    Returns:
+        list[float]: Areal depths in the units of `depths`.
+
+    Raises:
+        ValueError: If the lengths differ, the table is malformed, or an
+            area or duration is invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.precip import areal_reduction
+
+        areal = areal_reduction(
+            [42.0, 61.0, 88.0],
+            [timedelta(hours=1), timedelta(hours=6), timedelta(hours=24)],
+            area=250.0,
+        )
+        ```
+    """
+    return precip.areal_reduction(
+        depths,
+        durations,
+        area,
+        method,
+        table,
+        units,
+    )
+
+
+def areal_reduction_factors(
+    durations: list[timedelta],
+    area: float,
+    method: Literal["fsr", "tp29"] = "fsr",
+    table: Optional[dict[str, Any]] = None,
+    units: Literal["si", "us"] = "si",
+) -> list[float]:
+    """
+    Computes areal reduction factors for a basin area.
+
+    Args:
+        durations (list[timedelta]): Storm durations.
+        area (float): Basin area (km² or mi² per `units`).
+        method (Literal["fsr", "tp29"], optional): Published relation.
+            Defaults to `"fsr"`.
+        table (dict, optional): User factors, as in `areal_reduction`.
+        units (Literal["si", "us"], optional): Unit system of the areas.
+            Defaults to `"si"`.
+
+    Returns:
+        list[float]: The factor, between 0 and 1, for each duration.
+
+    Raises:
+        ValueError: If the table is malformed or an area or duration is
+            invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.precip import areal_reduction_factors
+
+        factors = areal_reduction_factors(
+            [timedelta(hours=6), timedelta(hours=24)],
+            area=100.0,
+            method="tp29",
+            units="us",
+        )
+        ```
+    """
+    return precip.areal_reduction_factors(
+        durations,
+        area,
+        method,
+        table,
+        units,
+    )
+
+
+def depth_area_duration(
+    rainfall: list[list[float]],
+    time_step: timedelta,
+    cell_area: float,
+    durations: list[timedelta],
+    areas: list[float],
+) -> list[list[float]]:
+    """
+    Computes the depth–area–duration (DAD) table of a gridded storm.
+
+    For each duration, every window of that length is scanned. The cells
+    are ranked by their window total, and the wettest cells that make up
+    each area are averaged. The result is the largest such average over
+    all windows. Cells are not required to be contiguous.
+
+    Args:
+        rainfall (list[list[float]]): Depth per step (outer) and grid cell
+            (inner). Cells that are NaN at every step are outside the
+            domain; other NaN values count as no rain.
+        time_step (timedelta): Length of each step.
+        cell_area (float): Area of one grid cell.
+        durations (list[timedelta]): Durations, multiples of `time_step`.
+        areas (list[float]): Areas in the units of `cell_area`, rounded to
+            whole cells.
+
+    Returns:
+        list[list[float]]: Maximum average depth for each duration (outer)
+            and area (inner), in the units of `rainfall`. NaN where an area
+            exceeds the domain.
+
+    Raises:
+        ValueError: If the grid is ragged or empty, or a duration or area
+            is invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.precip import depth_area_duration
+
+        table = depth_area_duration(
+            radar_grids,
+            timedelta(hours=1),
+            cell_area=1.0,
+            durations=[timedelta(hours=6), timedelta(hours=24)],
+            areas=[10.0, 100.0, 1000.0],
+        )
+        ```
+    """
+    return precip.depth_area_duration(
+        rainfall,
+        time_step,
+        cell_area,
+        durations,
+        areas,
+    )
+
+
+def _multipolygon(geometry: Any) -> list:
+    """Normalises a GeoJSON geometry or coordinate list to MultiPolygon
+    coordinates."""
+    if isinstance(geometry, Mapping):
+        kind = geometry.get("type")
+        if kind == "Feature":
+            return _multipolygon(geometry["geometry"])
+        if kind not in ("Polygon", "MultiPolygon"):
+            raise ValueError(f"Unsupported geometry type {kind!r}.")
+        coordinates = geometry["coordinates"]
+        return [coordinates] if kind == "Polygon" else coordinates
+    depth, item = 0, geometry
+    while isinstance(item, (list, tuple)) and item:
+        depth, item = depth + 1, item[0]
+    if depth == 2:
+        return [[geometry]]
+    if depth == 3:
+        return [geometry]
+    if depth == 4:
+        return geometry
+    raise ValueError("A catchment must be a ring, polygon or multipolygon.")
+
+
+def grid_to_catchments(
+    values: Any,
+    x: list[float],
+    y: list[float],
+    catchments: Mapping[str, Any],
+    geographic: bool = False,
+) -> dict[str, dict[str, Any]]:
+    """
+    Aggregates a gridded field over catchment polygons into lumped series.
+
+    Each grid cell is weighted by the area of its overlap with the
+    catchment, found by clipping the polygon to the cell, and the lumped
+    value at each step is the weighted mean of the cells. Cells that are
+    NaN at a step are left out and the weights renormalised. Cell edges lie
+    midway between the given centres. The aggregation runs in parallel
+    without holding the GIL.
+
+    Args:
+        values (Any): A C-contiguous float32 or float64 array (NumPy array
+            or any buffer) of shape `(steps, len(y), len(x))`, or
+            `(len(y), len(x))` for a single step.
+        x (list[float]): Cell-centre x coordinates (or longitudes), strictly
+            monotonic.
+        y (list[float]): Cell-centre y coordinates (or latitudes), strictly
+            monotonic.
+        catchments (Mapping[str, Any]): Catchment geometry keyed by name: a
+            GeoJSON Polygon, MultiPolygon or Feature, or bare ring, polygon
+            or multipolygon coordinates, in the grid's coordinates. Inner
+            rings are holes.
+        geographic (bool, optional): Whether the coordinates are longitude
+            and latitude in degrees. Overlap areas are then scaled by the
+            cosine of latitude and reported in square metres. Defaults to
+            False.
+
+    Returns:
+        dict[str, dict[str, Any]]: For each catchment, its `series` (NaN at
+            steps where none of its cells has a value), the number of
+            `cells` it overlaps, and the `area` it covers on the grid.
+
+    Raises:
+        TypeError: If `values` is not a float32 or float64 buffer.
+        ValueError: If the shape does not match the coordinates, an axis is
+            not monotonic, or a geometry is invalid.
+
+    Example:
+        ```python
+        import numpy as np
+        from rustflow.precip import grid_to_catchments
+
+        forcing = grid_to_catchments(
+            np.ascontiguousarray(dataset["precip"].values, dtype=np.float32),
+            dataset["lon"].values.tolist(),
+            dataset["lat"].values.tolist(),
+            {f["properties"]["name"]: f["geometry"] for f in basins["features"]},
+            geographic=True,
+        )
+        upper_rain = forcing["upper"]["series"]
+        ```
+    """
+    names = list(catchments)
+    result = precip.grid_to_catchments(
+        values,
+        [float(v) for v in x],
+        [float(v) for v in y],
+        [_multipolygon(catchments[name]) for name in names],
+        geographic,
+    )
+    return {
+        name: {"series": series, "cells": cells, "area": area}
+        for name, series, cells, area in zip(
+            names, result["series"], result["cells"], result["area"]
+        )
+    }
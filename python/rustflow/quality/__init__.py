@@ -0,0 +1 @@
+from . import buildup_washoff, temperature, transport
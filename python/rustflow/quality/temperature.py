@@ -0,0 +1,115 @@
+from datetime import timedelta
+from typing import Literal, Optional
+
+from ..rustflow import quality
+
+
+def stream_temperature(
+    discharge: list[float],
+    inflow_temperature: list[float],
+    air_temperature: list[float],
+    relative_humidity: list[float],
+    wind_speed: list[float],
+    solar_radiation: list[float],
+    time_step: timedelta,
+    length: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    cloud_cover: Optional[list[float]] = None,
+    shade: float = 0.0,
+    method: Literal["energy_balance", "equilibrium"] = "energy_balance",
+    initial_temperature: Optional[float] = None,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, list[float]]:
+    """
+    Simulates the water temperature of a stream reach.
+
+    The reach is treated as well mixed, with its volume and surface area
+    taken from normal flow at the routed discharge. Inflow brings heat
+    advectively and the water surface exchanges heat with the atmosphere
+    through absorbed shortwave radiation (reduced by riparian `shade`),
+    atmospheric longwave radiation (Brutsaert emissivity with a cloud
+    correction), back radiation, evaporation (Edinger–Brady wind function)
+    and conduction (Bowen ratio).
+
+    With `method="energy_balance"` the full heat budget is solved
+    implicitly each step. With `method="equilibrium"` the budget is
+    linearised as `K (Te - T)` about the equilibrium temperature `Te`, at
+    which the net surface flux vanishes (Edinger et al., 1968).
+
+    Args:
+        discharge (list[float]): Flow through the reach (m³/s or cfs, per
+            `units`), e.g. routed outflows.
+        inflow_temperature (list[float]): Temperature of the inflow (°C).
+        air_temperature (list[float]): Air temperature (°C).
+        relative_humidity (list[float]): Relative humidity (0–1).
+        wind_speed (list[float]): Wind speed near the surface (m/s).
+        solar_radiation (list[float]): Incoming shortwave radiation (W/m²).
+        time_step (timedelta): Interval between consecutive values.
+        length (float): Reach length (m or ft).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Horizontal run per unit vertical rise of the
+            banks.
+        n (float): Manning's roughness coefficient.
+        slope (float): Bed slope (m/m).
+        cloud_cover (list[float], optional): Cloud cover fraction (0–1).
+            Defaults to clear sky.
+        shade (float, optional): Fraction of shortwave radiation blocked by
+            riparian vegetation or topography. Defaults to 0.
+        method (Literal["energy_balance", "equilibrium"], optional): Heat
+            budget formulation. Defaults to `"energy_balance"`.
+        initial_temperature (float, optional): Water temperature at the
+            start (°C). Defaults to the first inflow temperature.
+        units (Literal["si", "us"], optional): Unit system of the flow and
+            channel geometry; temperatures and meteorological forcing are
+            always metric. Defaults to `"si"`.
+
+    Returns:
+        dict[str, list[float]]: `temperature` of the outflow and
+            `equilibrium_temperature` (°C), and the net surface
+            `heat_flux` into the water (W/m²), for each step.
+
+    Raises:
+        ValueError: If the series differ in length or a parameter is out of
+            range.
+
+    Example:
+        ```python
+        import math
+        from datetime import timedelta
+        from rustflow.quality.temperature import stream_temperature
+
+        hours = range(72)
+        solar = [max(0.0, 800.0 * math.sin(math.pi * (h % 24 - 6) / 12))
+                 for h in hours]
+        air = [18.0 + 6.0 * math.sin(math.pi * (h % 24 - 9) / 12)
+               for h in hours]
+        result = stream_temperature(
+            [4.0] * 72, [12.0] * 72, air, [0.6] * 72, [2.0] * 72, solar,
+            timedelta(hours=1), length=5000.0, bottom_width=8.0,
+            side_slope=2.0, n=0.04, slope=0.002, shade=0.3,
+        )
+        daily_max = max(result["temperature"][48:])
+        ```
+    """
+    return quality.stream_temperature(
+        discharge,
+        inflow_temperature,
+        air_temperature,
+        relative_humidity,
+        wind_speed,
+        solar_radiation,
+        time_step,
+        length,
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        cloud_cover,
+        shade,
+        method,
+        initial_temperature,
+        units,
+    )
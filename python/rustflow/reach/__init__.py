@@ -1,8 +1,10 @@
-from datetime import timedelta
-from typing import Optional
+from datetime import datetime, timedelta
+from typing import Any, Literal, Optional, Union
 
 from ..rustflow import reach
 
+MuskingumReach = reach.MuskingumReach
+RoutingResult = reach.RoutingResult
 
 # Expose Rust functions
 def muskingum_routing(
@@ -10,9 +12,15 @@ def muskingum_routing(
     k: timedelta,
     x: float,
     time_step: timedelta,
-    sub_reaches: Optional[int] = 1,
+    sub_reaches: Union[int, Literal["auto"], None] = 1,
     initial_outflow: Optional[float] = None,
-):
+    states: bool = False,
+    result: bool = False,
+    start: Optional[datetime] = None,
+    intermediate: bool = False,
+    allow_negative_c0: bool = False,
+    sub_steps: int = 1,
+) -> Union[list[float], list[list[float]], dict[str, Any], RoutingResult]:
     """
     Performs Muskingum routing on a given inflow hydrograph.
 
@@ -24,7 +32,11 @@ def muskingum_routing(
     Args:
         inflow (list[float]): A list of inflow discharges (e.g., in cfs or cms)
             at the upstream end of the reach. The list should represent a
-            time series of flow values.
+            time series of flow values. Muskingum routing is unit-agnostic;
+            the outflow is returned in the same units as the inflow. Any
+            iterable of numbers is accepted, and Arrow arrays (e.g.
+            `pyarrow.Array`) are read directly from their buffers without a
+            Python-level conversion.
         k (timedelta): The storage time constant of the reach (e.g., in hours).
             This parameter represents the travel time through the reach.
         x (float): The weighting factor for the inflow and outflow, typically
@@ -34,21 +46,58 @@ def muskingum_routing(
             more wedge storage.
         time_step (timedelta): The time step used for the inflow hydrograph
             (e.g., in minutes or hours). This determines the time interval
-            between consecutive inflow values.
-        sub_reaches (Optional[int], optional): The number of sub-reaches to
-            divide the reach into. Dividing the reach increases accuracy at the cost of computation time. Defaults to 1.
+            between consecutive inflow values. Sub-second components are
+            preserved, so fractional-second steps (e.g. lab flume data at
+            `timedelta(milliseconds=500)`) are supported.
+        sub_reaches (Union[int, Literal["auto"], None], optional): The
+            number of sub-reaches to divide the reach into. Dividing the
+            reach increases accuracy at the cost of computation time. With
+            `"auto"`, the sub-reaches and sub-steps are chosen by
+            `muskingum_discretization` and `sub_steps` is ignored. Defaults
+            to 1.
         initial_outflow (Optional[float], optional): The initial outflow
             discharge at the start of the simulation. If not provided, it
             defaults to the first inflow value.
+        states (bool, optional): If True, also return the reach storage at
+            each step. Defaults to False.
+        result (bool, optional): If True, return a `RoutingResult` that
+            keeps the inflow, parameters, storage and diagnostics with the
+            outflow. Defaults to False.
+        start (Optional[datetime], optional): Time of the first value, used
+            for the timestamps of a `RoutingResult`. Defaults to None
+            (timestamps are offsets from the first value).
+        intermediate (bool, optional): If True, return the hydrograph after
+            each sub-reach instead of only the final outflow, e.g. to place
+            lateral inflows or follow the attenuation along the reach.
+            Defaults to False.
+        allow_negative_c0 (bool, optional): If True, route even when the
+            time step is shorter than `2 K x / sub_reaches`, for which the
+            coefficient `c0` is negative and the outflow first dips as the
+            inflow rises. Defaults to False.
+        sub_steps (int, optional): Number of sub-steps per time step, with
+            the inflow interpolated linearly in between, for reaches with a
+            storage constant shorter than the time step. Not available with
+            `intermediate`. Defaults to 1.
 
     Returns:
         list[float]: A list of outflow discharges (in the same units as the
             inflow) at the downstream end of the reach, representing the routed
             hydrograph.
+        dict: With `states=True`, `outflow` and `storage`, the reach storage
+            `K [x I + (1 - x) O]` summed over sub-reaches at each step, in
+            inflow units × seconds (m³ for inflow in m³/s).
+        list[list[float]]: With `intermediate=True`, the outflow of each
+            sub-reach, shape (sub_reaches, n_timesteps); the last row is
+            the reach outflow. Combined with `states` or `result`, it is
+            added as `sub_reach_outflow` instead.
+        RoutingResult: With `result=True`.
 
     Raises:
         TypeError: If `inflow` is not a list or a compatible iterable.
         TypeError: if `k`, `time_step` is not a timedelta
+        ParameterError: If `inflow` is empty, `time_step` is not
+            positive, `k` is negative, `sub_reaches` is less than 1, or
+            `c0` is negative without `allow_negative_c0`.
 
     Example:
         ```python
@@ -65,12 +114,481 @@ def muskingum_routing(
         ```
     """
 
-    if not isinstance(inflow, list):
-        inflow = list(inflow)
+    if sub_reaches == "auto":
+        chosen = reach.muskingum_discretization(k, x, time_step)
+        sub_reaches, sub_steps = chosen["sub_reaches"], chosen["sub_steps"]
+    return reach.muskingum_routing(
+        inflow,
+        k,
+        x,
+        time_step,
+        sub_reaches,
+        initial_outflow,
+        states,
+        result,
+        start,
+        intermediate,
+        allow_negative_c0,
+        sub_steps,
+    )
 
-    if initial_outflow is None:
-        initial_outflow = inflow[0]
 
-    return reach.muskingum_routing(
-        inflow, k, x, time_step, sub_reaches, initial_outflow
+def muskingum_discretization(
+    k: timedelta, x: float, time_step: timedelta, max_divisions: int = 100
+) -> dict[str, Any]:
+    """
+    Chooses the number of sub-reaches and sub-steps for Muskingum routing.
+
+    A Muskingum reach is accurate when each sub-step is about as long as
+    the travel time through each sub-reach (a Courant number
+    `sub_time_step / sub_reach_k` near one), and free of dips and
+    oscillations when the Courant number lies in `[2 x, 2 (1 - x)]`, so
+    that all routing coefficients are non-negative. This returns the
+    division closest to a Courant number of one, using the fewest divisions
+    among equally good choices, so `sub_reaches` need not be guessed.
+
+    Args:
+        k (timedelta): Storage time constant of the whole reach.
+        x (float): Weighting factor, between 0.0 and 0.5.
+        time_step (timedelta): Interval between consecutive inflow values.
+        max_divisions (int, optional): Largest number of sub-reaches, and
+            of sub-steps, to consider. Defaults to 100.
+
+    Returns:
+        dict[str, Any]: `sub_reaches` and `sub_steps`, the resulting
+            `sub_reach_k` and `sub_time_step` (timedeltas), the `courant`
+            number, the coefficients `c0`, `c1` and `c2`, and `accurate`,
+            whether all coefficients are non-negative. A warning is logged
+            when no division within `max_divisions` is accurate.
+
+    Raises:
+        ParameterError: If `time_step` is not positive, `k` is negative,
+            `x` is outside [0.0, 0.5] or `max_divisions` is less than 1.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reach import muskingum_discretization, muskingum_routing
+
+        chosen = muskingum_discretization(timedelta(hours=6), 0.25, timedelta(hours=1))
+        print(chosen["sub_reaches"], chosen["courant"])  # 6 1.0
+        outflow = muskingum_routing(
+            inflow, timedelta(hours=6), 0.25, timedelta(hours=1), sub_reaches="auto"
+        )
+        ```
+    """
+    return reach.muskingum_discretization(k, x, time_step, max_divisions)
+
+
+def muskingum_cunge(
+    reference_flow: float,
+    length: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, Any]:
+    """
+    Derives the Muskingum `k` and `x` of a prismatic trapezoidal reach by
+    the Muskingum-Cunge method.
+
+    The numerical diffusion of the Muskingum scheme is matched to the
+    physical diffusion of the flood wave at the reference flow `q0`:
+    `k = L / c` and `x = 0.5 (1 - q0 / (B S c L))` for the kinematic wave
+    celerity `c` and top width `B` at the normal depth of `q0`.
+
+    Args:
+        reference_flow (float): Reference flow `q0`, typically half the
+            peak inflow (m³/s or cfs, per `units`).
+        length (float): Reach length (m or ft).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Bank slope as horizontal run per unit rise.
+        n (float): Manning's roughness coefficient.
+        slope (float): Longitudinal bed slope (dimensionless).
+        units (Literal["si", "us"], optional): Unit system of the inputs.
+            Defaults to `"si"`.
+
+    Returns:
+        dict[str, Any]: `k` (timedelta) and `x` (float). `x` is negative
+            when the reach is too long for the flow; split it into
+            sub-reaches or route it with a kinematic wave.
+
+    Raises:
+        ValueError: If `units` is not recognised, the channel geometry, `n`
+            or `slope` is non-physical, or `reference_flow` or `length` is
+            not positive.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reach import muskingum_cunge, muskingum_routing
+
+        p = muskingum_cunge(5000.0, 30000.0, 150.0, 2.0, 0.035, 0.0005, units="us")
+        outflow = muskingum_routing(inflow, p["k"], p["x"], timedelta(hours=1))
+        ```
+    """
+    return reach.muskingum_cunge(
+        reference_flow, length, bottom_width, side_slope, n, slope, units
+    )
+
+
+def kinematic_wave_routing(
+    inflow: list[float],
+    time_step: timedelta,
+    length: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    segments: int = 10,
+    units: Literal["si", "us"] = "si",
+    states: bool = False,
+    result: bool = False,
+    start: Optional[datetime] = None,
+) -> Union[list[float], dict[str, Any], RoutingResult]:
+    """
+    Routes an inflow hydrograph through a trapezoidal channel with the
+    kinematic wave approximation.
+
+    The reach is split into `segments` cells solved with an implicit
+    (backward Euler) scheme, so the result is stable for any time step.
+    Flow in each cell follows Manning's equation for the channel geometry.
+    The reach starts at normal flow for the first inflow value.
+
+    Args:
+        inflow (list[float]): Upstream discharge (m³/s or cfs, per `units`).
+        time_step (timedelta): Interval between consecutive inflow values.
+        length (float): Reach length (m or ft).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Horizontal run per unit vertical rise of the
+            banks; `0` gives a rectangular channel.
+        n (float): Manning's roughness coefficient.
+        slope (float): Longitudinal bed slope (m/m).
+        segments (int, optional): Number of computational cells. Defaults
+            to 10.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            result. Defaults to `"si"`.
+        states (bool, optional): If True, also return the hydraulic state
+            of the reach at each step. Defaults to False.
+        result (bool, optional): If True, return a `RoutingResult` that
+            keeps the inflow, parameters, states and diagnostics with the
+            outflow. Defaults to False.
+        start (Optional[datetime], optional): Time of the first value, used
+            for the timestamps of a `RoutingResult`. Defaults to None.
+
+    Returns:
+        list[float]: Outflow at the downstream end, in the inflow units.
+        dict: With `states=True`, `outflow`, the volume stored in the reach
+            (`storage`, m³ or ft³), and the flow `depth` (m or ft) and mean
+            `velocity` (m/s or ft/s) at the downstream end at each step.
+        RoutingResult: With `result=True`.
+
+    Raises:
+        ValueError: If a geometric parameter is non-positive or `segments`
+            is zero.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reach import kinematic_wave_routing
+
+        inflow = [5.0, 20.0, 45.0, 30.0, 15.0, 8.0, 5.0]  # m³/s
+        outflow = kinematic_wave_routing(
+            inflow, timedelta(minutes=10), length=2000.0, bottom_width=8.0,
+            side_slope=2.0, n=0.035, slope=0.001,
+        )
+        ```
+    """
+    return reach.kinematic_wave_routing(
+        inflow,
+        time_step,
+        length,
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        segments,
+        units,
+        states,
+        result,
+        start,
+    )
+
+
+def dynamic_wave(
+    inflow: list[float],
+    downstream_stage: list[float],
+    time_step: timedelta,
+    length: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    segments: int = 20,
+    theta: float = 0.6,
+    outlet_bed: float = 0.0,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, list[float]]:
+    """
+    Routes a hydrograph through a prismatic trapezoidal channel with the
+    full Saint-Venant (dynamic wave) equations.
+
+    The equations are solved with the implicit four-point Preissmann
+    scheme. The upstream boundary is the inflow hydrograph and the
+    downstream boundary a stage hydrograph, so backwater from a lake, tide
+    or confluence reaches upstream. Muskingum and kinematic wave routing
+    cannot represent this. Each step is solved by Newton iteration. The
+    reach starts from the steady gradually varied profile for the first
+    boundary values. Flow must be subcritical. A tidal stage boundary (see
+    `tide_stage`) may reverse the flow, which is then negative.
+
+    Args:
+        inflow (list[float]): Upstream discharge (m³/s or cfs, per `units`).
+        downstream_stage (list[float]): Water-surface elevation at the
+            downstream end at each step (m or ft), on the datum of
+            `outlet_bed`.
+        time_step (timedelta): Interval between boundary values.
+        length (float): Reach length (m or ft).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Horizontal run per unit vertical rise of the
+            banks; `0` gives a rectangular channel.
+        n (float): Manning's roughness coefficient.
+        slope (float): Longitudinal bed slope (m/m).
+        segments (int, optional): Number of computational cells. Defaults
+            to 20.
+        theta (float, optional): Time weighting of the scheme, in [0.5, 1].
+            Defaults to 0.6, which damps spurious oscillations.
+        outlet_bed (float, optional): Bed elevation at the downstream end.
+            Defaults to 0, so stages are depths there.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            results. Defaults to `"si"`.
+
+    Returns:
+        dict[str, list[float]]: `outflow` at the downstream end and
+            `upstream_stage` at each step, plus the distance of each node
+            from the upstream end (`stations`), the highest water-surface
+            elevation reached there (`max_stage`) and the lowest discharge
+            (`min_discharge`), negative where the flow reversed.
+
+    Raises:
+        ValueError: If the series differ in length, a parameter is invalid,
+            a stage is below the bed, or the solution does not converge.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reach import dynamic_wave
+
+        result = dynamic_wave(
+            inflow=[20.0, 60.0, 120.0, 80.0, 40.0, 20.0] + [20.0] * 30,
+            downstream_stage=[3.0] * 36,
+            time_step=timedelta(minutes=10),
+            length=5000.0,
+            bottom_width=15.0,
+            side_slope=2.0,
+            n=0.035,
+            slope=0.0002,
+        )
+        ```
+    """
+    return reach.dynamic_wave(
+        inflow,
+        downstream_stage,
+        time_step,
+        length,
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        segments,
+        theta,
+        outlet_bed,
+        units,
+    )
+
+
+def backwater_profile(
+    discharge: list[float],
+    downstream_stage: list[float],
+    length: float,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    segments: int = 20,
+    outlet_bed: float = 0.0,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, Any]:
+    """
+    Computes steady backwater profiles in a prismatic trapezoidal channel
+    for a series of discharges and downstream stages.
+
+    Each profile is the gradually varied flow profile integrated upstream
+    from the downstream stage, so a slowly varying tide or lake level can
+    be followed quasi-steadily. A negative discharge flows upstream, as on
+    a flood tide, and the water surface then falls in that direction. Where
+    the flow would become critical, normal depth is used.
+
+    Args:
+        discharge (list[float]): Discharge through the reach (m³/s or cfs,
+            per `units`), negative for flow upstream.
+        downstream_stage (list[float]): Water-surface elevation at the
+            downstream end (m or ft), on the datum of `outlet_bed`.
+        length (float): Reach length (m or ft).
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Horizontal run per unit vertical rise of the
+            banks; `0` gives a rectangular channel.
+        n (float): Manning's roughness coefficient.
+        slope (float): Longitudinal bed slope (m/m).
+        segments (int, optional): Number of computational cells. Defaults
+            to 20.
+        outlet_bed (float, optional): Bed elevation at the downstream end.
+            Defaults to 0.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            results. Defaults to `"si"`.
+
+    Returns:
+        dict[str, Any]: The distance of each node from the upstream end
+            (`stations`), the water-surface elevation at every node for each
+            step (`stage`) and at the upstream end (`upstream_stage`).
+
+    Raises:
+        ValueError: If the series differ in length, a parameter is invalid
+            or a stage is below the bed.
+
+    Example:
+        ```python
+        from rustflow.reach import backwater_profile
+
+        result = backwater_profile(
+            discharge=[50.0, 50.0],
+            downstream_stage=[3.0, 4.0],
+            length=5000.0,
+            bottom_width=15.0,
+            side_slope=2.0,
+            n=0.035,
+            slope=0.0002,
+        )
+        ```
+    """
+    return reach.backwater_profile(
+        discharge,
+        downstream_stage,
+        length,
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        segments,
+        outlet_bed,
+        units,
+    )
+
+
+def tide_stage(
+    steps: int,
+    time_step: timedelta,
+    constituents: list[tuple[str, float, float]],
+    mean_level: float = 0.0,
+    units: Literal["si", "us"] = "si",
+) -> list[float]:
+    """
+    Generates a harmonic tide series for the downstream stage boundary of
+    `dynamic_wave` or `backwater_profile`.
+
+    The stage is the mean level plus a cosine for each constituent, with
+    the standard periods of the named constituents. Phases are lags in
+    degrees relative to the first step.
+
+    Args:
+        steps (int): Number of values.
+        time_step (timedelta): Interval between values.
+        constituents (list[tuple[str, float, float]]): `(name, amplitude,
+            phase)` of each constituent, where `name` is one of `"M2"`,
+            `"S2"`, `"N2"`, `"K2"`, `"K1"`, `"O1"`, `"P1"` or `"M4"` and
+            the amplitude is in m or ft.
+        mean_level (float, optional): Mean water level (m or ft). Defaults
+            to 0.
+        units (Literal["si", "us"], optional): Unit system of the levels.
+            Defaults to `"si"`.
+
+    Returns:
+        list[float]: Water level at each step.
+
+    Raises:
+        ValueError: If a constituent is unknown or the time step is not
+            positive.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reach import tide_stage
+
+        stage = tide_stage(
+            48, timedelta(hours=1), [("M2", 1.2, 0.0), ("S2", 0.4, 30.0)], 2.5
+        )
+        ```
+    """
+    return reach.tide_stage(steps, time_step, constituents, mean_level, units)
+
+
+def transmission_loss(
+    flow: list[float],
+    time_step: timedelta,
+    method: Literal["seepage", "lane", "fraction"],
+    rate: Optional[float] = None,
+    width: Optional[float] = None,
+    length: Optional[float] = None,
+    fraction: Optional[float] = None,
+    units: Literal["si", "us"] = "si",
+) -> tuple[list[float], list[float]]:
+    """
+    Removes channel transmission losses from a flow series.
+
+    Intended for ephemeral streams in arid regions, where a large part of
+    the flow infiltrates through the channel bed. Apply it to the routed
+    outflow of a reach; in a network, set `transmission_loss` on the reach
+    node instead.
+
+    Args:
+        flow (list[float]): Flow series (m³/s or cfs, per `units`).
+        time_step (timedelta): Interval between consecutive values.
+        method (Literal["seepage", "lane", "fraction"]): `"seepage"` removes
+            a constant seepage `rate` over a bed of `width` × `length`.
+            `"lane"` applies Lane's (1983) regression method, as in SWAT,
+            with effective bed hydraulic conductivity `rate`, treating each
+            step as a flow event; losses are relatively larger for small
+            flows. `"fraction"` removes a fixed `fraction` of the flow.
+        rate (float, optional): Seepage rate or bed hydraulic conductivity
+            (mm/h or in/h).
+        width (float, optional): Channel bed width (m or ft).
+        length (float, optional): Reach length (m or ft).
+        fraction (float, optional): Fraction of flow lost, in [0, 1].
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            results. Defaults to `"si"`.
+
+    Returns:
+        tuple[list[float], list[float]]: Remaining flow and lost flow. The
+            volume lost in a step, e.g. for groundwater recharge accounting,
+            is the lost flow times the time step.
+
+    Raises:
+        ValueError: If the parameters required by `method` are missing or
+            invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reach import transmission_loss
+
+        outflow, lost = transmission_loss(
+            [0.0, 5.0, 12.0, 6.0, 1.0], timedelta(hours=1), "lane",
+            rate=25.0, width=10.0, length=5000.0,
+        )
+        recharge_m3 = sum(lost) * 3600.0
+        ```
+    """
+    return reach.transmission_loss(
+        flow, time_step, method, rate, width, length, fraction, units
     )
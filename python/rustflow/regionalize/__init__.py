@@ -0,0 +1,129 @@
+from typing import Any, Literal, Mapping, Optional, Sequence
+
+from ..rustflow import regionalize
+
+
+def transfer_parameters(
+    target: Mapping[str, Any],
+    donors: Mapping[str, Mapping[str, Any]],
+    method: Literal["spatial_proximity", "physical_similarity"] = "spatial_proximity",
+    n_donors: int = 5,
+    power: float = 2.0,
+    geographic: bool = False,
+    attribute_weights: Optional[Mapping[str, float]] = None,
+    min_score: Optional[float] = None,
+    bounds: tuple[float, float] = (0.05, 0.95),
+) -> dict[str, Any]:
+    """
+    Estimates model parameters for an ungauged catchment from calibrated
+    donor catchments.
+
+    Donors are ranked by their distance to the target.
+    `"spatial_proximity"` uses the distance between catchment locations.
+    `"physical_similarity"` uses the Euclidean distance between catchment
+    attributes, each standardised by its spread among the donors and
+    optionally weighted. The `n_donors` nearest donors are combined with
+    weights proportional to `1 / distance ** power`. The estimate is their
+    weighted mean, and the uncertainty range is their weighted quantiles at
+    `bounds` (Oudin et al., 2008). Running the model with each donor's set
+    (`donor_parameters`) and averaging the outputs is often more robust than
+    running it once with the averaged set.
+
+    Args:
+        target (Mapping[str, Any]): The ungauged catchment: `"location"` as
+            `(x, y)` (or `(longitude, latitude)` with `geographic`) for
+            spatial proximity, and `"attributes"`, a mapping of attribute
+            name to value, for physical similarity.
+        donors (Mapping[str, Mapping[str, Any]]): Gauged catchments keyed by
+            name, each with `"parameters"` (a mapping of parameter name to
+            calibrated value), and `"location"` or `"attributes"` as for the
+            target. An optional `"score"` gives the calibration quality.
+        method (Literal["spatial_proximity", "physical_similarity"], optional):
+            How donors are ranked. Defaults to `"spatial_proximity"`.
+        n_donors (int, optional): Number of donors combined. Defaults to 5.
+        power (float, optional): Exponent of the inverse-distance weights; 0
+            weights the donors equally. Defaults to 2.
+        geographic (bool, optional): Locations are longitude and latitude in
+            degrees, and distances are great-circle distances in metres.
+            Defaults to False.
+        attribute_weights (Optional[Mapping[str, float]], optional): Relative
+            weight of each attribute in the similarity distance. Defaults to
+            equal weights.
+        min_score (Optional[float], optional): Donors without a score of at
+            least this are not used.
+        bounds (tuple[float, float], optional): Probabilities of the lower
+            and upper uncertainty bounds. Defaults to `(0.05, 0.95)`.
+
+    Returns:
+        dict: `parameters`, `lower` and `upper`, each a mapping of parameter
+            name to value; the `donors` used, nearest first, with their
+            `distances`, `weights` and `donor_parameters`.
+
+    Raises:
+        ValueError: If the donors lack the locations, attributes or
+            parameters the method needs, or no donor meets `min_score`.
+
+    Example:
+        ```python
+        from rustflow.regionalize import transfer_parameters
+
+        donors = {
+            "gauge_a": {"location": (512_000.0, 4_205_000.0),
+                        "attributes": {"area": 120.0, "slope": 0.02},
+                        "parameters": {"k": 5400.0, "x": 0.2}, "score": 0.81},
+            "gauge_b": {"location": (530_000.0, 4_190_000.0),
+                        "attributes": {"area": 340.0, "slope": 0.01},
+                        "parameters": {"k": 9000.0, "x": 0.25}, "score": 0.74},
+        }
+        target = {"location": (520_000.0, 4_200_000.0),
+                  "attributes": {"area": 200.0, "slope": 0.015}}
+        estimate = transfer_parameters(target, donors, n_donors=2)
+        print(estimate["parameters"]["k"], estimate["lower"]["k"])
+        ```
+    """
+    names = list(donors)
+    if not names:
+        raise ValueError("At least one donor is needed.")
+    parameter_names = list(donors[names[0]]["parameters"])
+    attribute_names = list(target.get("attributes", {}))
+
+    def parameters(name: str) -> list[float]:
+        values = donors[name]["parameters"]
+        missing = [p for p in parameter_names if p not in values]
+        if missing:
+            raise ValueError(f"Donor `{name}` lacks parameters {missing}.")
+        return [float(values[p]) for p in parameter_names]
+
+    def attributes(values: Mapping[str, float], name: str) -> list[float]:
+        missing = [a for a in attribute_names if a not in values]
+        if missing:
+            raise ValueError(f"Donor `{name}` lacks attributes {missing}.")
+        return [float(values[a]) for a in attribute_names]
+
+    result = regionalize.transfer_parameters(
+        target.get("location"),
+        [float(target["attributes"][a]) for a in attribute_names],
+        [donors[n].get("location") for n in names],
+        [attributes(donors[n].get("attributes", {}), n) for n in names],
+        [parameters(n) for n in names],
+        [donors[n].get("score") for n in names],
+        method,
+        n_donors,
+        power,
+        geographic,
+        None
+        if attribute_weights is None
+        else [float(attribute_weights.get(a, 1.0)) for a in attribute_names],
+        min_score,
+        tuple(bounds),
+    )
+    used: Sequence[int] = result["donors"]
+    return {
+        "parameters": dict(zip(parameter_names, result["parameters"])),
+        "lower": dict(zip(parameter_names, result["lower"])),
+        "upper": dict(zip(parameter_names, result["upper"])),
+        "donors": [names[i] for i in used],
+        "distances": result["distances"],
+        "weights": result["weights"],
+        "donor_parameters": [dict(donors[names[i]]["parameters"]) for i in used],
+    }
@@ -0,0 +1,455 @@
+from datetime import timedelta
+from typing import Any, Literal, Optional, Union
+
+from ..rustflow import reservoir
+from . import yield_analysis
+
+
+def level_pool_routing(
+    inflow: list[float],
+    elevation: list[float],
+    storage: list[float],
+    outflow: list[float],
+    time_step: timedelta,
+    initial_elevation: float,
+    area: Optional[list[float]] = None,
+    evaporation: Optional[list[float]] = None,
+    seepage: Optional[Union[float, list[float]]] = None,
+) -> list[float]:
+    """
+    Routes an inflow hydrograph through a reservoir using level-pool
+    (modified Puls) routing.
+
+    The reservoir is described by an elevation–storage–outflow table. Each
+    time step solves the storage indication equation
+    `2S2/dt + O2 + L2 = I1 + I2 + 2S1/dt - O1 - L1` by linear
+    interpolation in the table, where the losses `L` are evaporation from
+    the water surface and seepage. Losses leave the pool without passing
+    the outlet, so long routing runs do not overstate storage.
+
+    Args:
+        inflow (list[float]): Inflow discharges at each time step. Any
+            iterable of numbers or Arrow array is accepted.
+        elevation (list[float]): Pool elevations of the table rows, strictly
+            increasing.
+        storage (list[float]): Storage volume at each elevation, in flow units
+            multiplied by seconds (e.g. m³ for m³/s or ft³ for cfs).
+        outflow (list[float]): Outflow at each elevation, in the same units as
+            `inflow`.
+        time_step (timedelta): Time interval between consecutive inflow
+            values.
+        initial_elevation (float): Pool elevation at the start of the
+            simulation.
+        area (list[float], optional): Water surface area at each elevation,
+            in the length unit of the storage volumes squared. Required with
+            `evaporation`.
+        evaporation (list[float], optional): Evaporation depth per time
+            step, in the length unit of `area`. A series shorter than the
+            inflow, such as 12 monthly depths at a monthly step, is repeated.
+        seepage (float | list[float], optional): Seepage flow, constant or
+            at each elevation, in the units of `inflow`.
+
+    Returns:
+        list[float]: Outflow discharges at each time step.
+
+    Raises:
+        ValueError: If the table columns differ in length, elevations are not
+            strictly increasing, storage/outflow/area/seepage decrease with
+            elevation, or evaporation is given without `area`.
+        MassBalanceError: If the pool is drawn below the bottom of the table,
+            so the routed volumes no longer balance.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reservoir import level_pool_routing
+
+        outflow = level_pool_routing(
+            inflow=[5.0, 20.0, 45.0, 30.0, 15.0, 8.0],
+            elevation=[100.0, 101.0, 102.0, 103.0],
+            storage=[0.0, 50_000.0, 120_000.0, 200_000.0],
+            outflow=[0.0, 5.0, 20.0, 60.0],
+            time_step=timedelta(hours=1),
+            initial_elevation=100.5,
+        )
+        ```
+    """
+    return reservoir.level_pool_routing(
+        inflow,
+        elevation,
+        storage,
+        outflow,
+        time_step,
+        initial_elevation,
+        area,
+        evaporation,
+        seepage,
+    )
+
+
+def dam_breach(
+    inflow: list[float],
+    time_step: timedelta,
+    elevation: list[float],
+    storage: list[float],
+    outflow: list[float],
+    initial_elevation: float,
+    crest_elevation: float,
+    bottom_elevation: float,
+    mode: Literal["overtopping", "piping"] = "overtopping",
+    piping_elevation: Optional[float] = None,
+    method: Literal["froehlich", "macdonald", "user"] = "froehlich",
+    crest_width: Optional[float] = None,
+    upstream_slope: Optional[float] = None,
+    downstream_slope: Optional[float] = None,
+    bottom_width: Optional[float] = None,
+    side_slope: Optional[float] = None,
+    formation_time: Optional[timedelta] = None,
+    trigger_elevation: Optional[float] = None,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, Any]:
+    """
+    Generates a dam-breach outflow hydrograph.
+
+    The breach starts when the pool reaches `trigger_elevation`. The
+    default trigger is the crest for overtopping and `piping_elevation`
+    for piping. The trapezoidal breach then grows linearly to its final
+    size over the formation time. The reservoir is routed as a level pool
+    through both the spillway rating and the breach.
+
+    Breach flow uses Fread's broad-crested weir,
+    `1.7 b H^1.5 + 1.35 z H^2.5` (SI). A piping breach starts at the piping
+    elevation and flows as an orifice while the pool is above its top.
+
+    The final breach size and formation time can come from three sources:
+
+    - `"froehlich"`: the Froehlich (2008) regressions.
+    - `"macdonald"`: MacDonald and Langridge-Monopolis (1984) eroded
+      volume for earthfill dams, spread over the embankment section.
+    - `"user"`: given directly.
+
+    Args:
+        inflow (list[float]): Reservoir inflow at each time step (m³/s or
+            cfs, per `units`).
+        time_step (timedelta): Interval between inflow values.
+        elevation (list[float]): Pool elevations of the reservoir table (m
+            or ft), strictly increasing.
+        storage (list[float]): Storage at each elevation (m³ or ft³),
+            strictly increasing.
+        outflow (list[float]): Spillway and outlet discharge at each
+            elevation.
+        initial_elevation (float): Starting pool elevation.
+        crest_elevation (float): Top of dam elevation.
+        bottom_elevation (float): Elevation of the final breach bottom.
+        mode (Literal["overtopping", "piping"], optional): Failure mode.
+            Defaults to `"overtopping"`.
+        piping_elevation (float, optional): Elevation where piping starts;
+            required for piping.
+        method (Literal["froehlich", "macdonald", "user"], optional): Source
+            of the breach parameters. Defaults to `"froehlich"`.
+        crest_width (float, optional): Embankment crest width, for
+            `"macdonald"`.
+        upstream_slope (float, optional): Upstream face slope (H:V), for
+            `"macdonald"`.
+        downstream_slope (float, optional): Downstream face slope (H:V),
+            for `"macdonald"`.
+        bottom_width (float, optional): Final breach bottom width, for
+            `"user"`.
+        side_slope (float, optional): Breach side slope (H:V), for `"user"`.
+        formation_time (timedelta, optional): Breach formation time, for
+            `"user"`.
+        trigger_elevation (float, optional): Pool elevation that starts the
+            breach.
+        units (Literal["si", "us"], optional): Unit system of all inputs
+            and outputs. Defaults to `"si"`.
+
+    Returns:
+        dict: Total `outflow`, `breach_outflow` and pool `elevation` at
+            each step. Also the `initiation` time from the start and the
+            final breach `parameters`: `bottom_width`, `side_slope`,
+            `formation_time`, `height` and `volume`. The last two are
+            `None` if the breach never started.
+
+    Raises:
+        ValueError: If the tables are invalid, the breach bottom is not
+            below the crest, or a parameter required by the method or mode
+            is missing.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reservoir import dam_breach
+
+        result = dam_breach(
+            inflow,
+            timedelta(minutes=15),
+            elevation=[300.0, 310.0, 320.0, 325.0],
+            storage=[0.0, 8.0e6, 2.5e7, 3.6e7],
+            outflow=[0.0, 0.0, 400.0, 900.0],
+            initial_elevation=318.0,
+            crest_elevation=322.0,
+            bottom_elevation=302.0,
+        )
+        peak = max(result["outflow"])
+        ```
+    """
+    return reservoir.dam_breach(
+        inflow,
+        time_step,
+        elevation,
+        storage,
+        outflow,
+        initial_elevation,
+        crest_elevation,
+        bottom_elevation,
+        mode,
+        piping_elevation,
+        method,
+        crest_width,
+        upstream_slope,
+        downstream_slope,
+        bottom_width,
+        side_slope,
+        formation_time,
+        trigger_elevation,
+        units,
+    )
+
+
+def hydropower(
+    inflow: list[float],
+    time_step: timedelta,
+    elevation: list[float],
+    storage: list[float],
+    outflow: list[float],
+    initial_elevation: float,
+    tailwater_discharge: list[float],
+    tailwater_elevation: list[float],
+    turbine_capacity: float,
+    efficiency: float = 0.9,
+    efficiency_curve: Optional[tuple[list[float], list[float]]] = None,
+    power_capacity: Optional[float] = None,
+    min_head: float = 0.0,
+    loss_coefficient: float = 0.0,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, Any]:
+    """
+    Routes an inflow hydrograph through a reservoir and computes the
+    hydropower generated from its releases.
+
+    The reservoir is routed as a level pool (see `level_pool_routing`).
+    The release at each step passes through the turbines up to
+    `turbine_capacity`, and the rest spills. Power is `ρ g Q H η`. The net
+    head `H` is the pool elevation less the tailwater and the penstock
+    loss `loss_coefficient · Q²`. The tailwater is read from a rating on
+    the total release. Generation stops below `min_head` and is capped at
+    `power_capacity`, with the excess flow spilled. Energy is integrated
+    from the power at the ends of each step by the trapezoidal rule.
+
+    Args:
+        inflow (list[float]): Reservoir inflow at each time step (m³/s or
+            cfs, per `units`).
+        time_step (timedelta): Interval between inflow values.
+        elevation (list[float]): Pool elevations of the reservoir table (m
+            or ft), strictly increasing.
+        storage (list[float]): Storage at each elevation (m³ or ft³).
+        outflow (list[float]): Total release at each elevation.
+        initial_elevation (float): Starting pool elevation.
+        tailwater_discharge (list[float]): Total releases of the tailwater
+            rating, strictly increasing. A single value gives a constant
+            tailwater.
+        tailwater_elevation (list[float]): Tailwater elevation at each
+            rating discharge.
+        turbine_capacity (float): Largest turbine discharge.
+        efficiency (float, optional): Constant turbine efficiency, used
+            when `efficiency_curve` is not given. Defaults to 0.9.
+        efficiency_curve (tuple[list[float], list[float]], optional):
+            Turbine discharges and the efficiency at each, held constant
+            beyond the ends.
+        power_capacity (float, optional): Installed capacity (MW).
+        min_head (float, optional): Net head below which the units do not
+            run. Defaults to 0.
+        loss_coefficient (float, optional): Penstock loss coefficient, in
+            head per discharge squared. Defaults to 0.
+        units (Literal["si", "us"], optional): Unit system of the flows,
+            elevations and volumes. Power is always in MW and energy in
+            MWh. Defaults to `"si"`.
+
+    Returns:
+        dict: `outflow`, `turbine_flow`, `spill`, pool `elevation`, net
+            `head`, `power` (MW) and `energy` (MWh generated during each
+            step) at each step, and the run's `total_energy` (MWh).
+
+    Raises:
+        ValueError: If the reservoir or rating tables are invalid, an
+            efficiency is outside (0, 1] or a capacity is not positive.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reservoir import hydropower
+
+        result = hydropower(
+            inflow,
+            timedelta(hours=1),
+            elevation=[200.0, 210.0, 220.0, 230.0],
+            storage=[0.0, 2.0e7, 5.0e7, 9.0e7],
+            outflow=[0.0, 80.0, 150.0, 600.0],
+            initial_elevation=218.0,
+            tailwater_discharge=[0.0, 200.0, 800.0],
+            tailwater_elevation=[150.0, 152.0, 156.0],
+            turbine_capacity=160.0,
+            power_capacity=100.0,
+        )
+        energy = result["total_energy"]
+        ```
+    """
+    return reservoir.hydropower(
+        inflow,
+        time_step,
+        elevation,
+        storage,
+        outflow,
+        initial_elevation,
+        tailwater_discharge,
+        tailwater_elevation,
+        turbine_capacity,
+        efficiency,
+        efficiency_curve,
+        power_capacity,
+        min_head,
+        loss_coefficient,
+        units,
+    )
+
+
+def optimize_drawdown(
+    inflow: list[list[float]],
+    time_step: timedelta,
+    elevation: list[float],
+    storage: list[float],
+    initial_elevation: float,
+    max_release: float,
+    min_release: float = 0.0,
+    max_ramp: Optional[float] = None,
+    initial_release: Optional[float] = None,
+    min_elevation: Optional[float] = None,
+    max_elevation: Optional[float] = None,
+    target_elevation: Optional[float] = None,
+    channel_capacity: Optional[float] = None,
+    local_inflow: Optional[list[list[float]]] = None,
+    objective: Literal["peak_pool", "exceedance"] = "peak_pool",
+    release_levels: int = 51,
+    storage_levels: int = 101,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, Any]:
+    """
+    Schedules flood pool releases against an ensemble inflow forecast.
+
+    One release schedule is found for all forecast members. Releases are
+    held constant over each step, lie between `min_release` and
+    `max_release`, and change by at most `max_ramp` between steps. The
+    pool must stay between `min_elevation` and `max_elevation` in every
+    member. With a `target_elevation`, the pool under the ensemble-mean
+    inflow must be drawn down to it by the end of the forecast.
+
+    The `"peak_pool"` objective minimises the highest pool reached by any
+    member. Ties are broken by the downstream exceedance. The
+    `"exceedance"` objective minimises the ensemble-mean volume passed
+    downstream above `channel_capacity`; the downstream flow is the
+    release plus any `local_inflow`. The schedule is found by dynamic
+    programming over `release_levels` evenly spaced releases and a grid of
+    `storage_levels` cumulative release volumes.
+
+    Args:
+        inflow (list[list[float]]): Forecast members, each the mean
+            reservoir inflow over every step (m³/s or cfs, per `units`).
+            A single series is taken as a one-member forecast.
+        time_step (timedelta): Interval between forecast values.
+        elevation (list[float]): Pool elevations of the reservoir table (m
+            or ft), strictly increasing.
+        storage (list[float]): Storage at each elevation (m³ or ft³),
+            strictly increasing.
+        initial_elevation (float): Pool elevation at the forecast start.
+        max_release (float): Largest release.
+        min_release (float, optional): Smallest release. Defaults to 0.
+        max_ramp (float, optional): Largest change in release from one
+            step to the next.
+        initial_release (float, optional): Release before the forecast,
+            from which the first step is ramped.
+        min_elevation (float, optional): Lowest pool allowed. Defaults to
+            the bottom of the table.
+        max_elevation (float, optional): Highest pool allowed; required by
+            the `"exceedance"` objective.
+        target_elevation (float, optional): Pool to reach by the end of the
+            forecast under the ensemble-mean inflow.
+        channel_capacity (float, optional): Downstream flow above which
+            releases count as exceedance; required by the `"exceedance"`
+            objective.
+        local_inflow (list[list[float]], optional): Flow joining below the
+            dam, one series per forecast member.
+        objective (Literal["peak_pool", "exceedance"], optional): What the
+            schedule minimises. Defaults to `"peak_pool"`.
+        release_levels (int, optional): Number of release values tried.
+            Defaults to 51.
+        storage_levels (int, optional): Number of grid points of the
+            cumulative release at each step. Defaults to 101.
+        units (Literal["si", "us"], optional): Unit system of the flows,
+            elevations and volumes. Defaults to `"si"`.
+
+    Returns:
+        dict: The `release` at each step; per member, the pool `elevation`,
+            `storage` and `downstream` flow at the end of each step; the
+            `peak_elevation` over all members; the ensemble-mean
+            `exceedance_volume`; and the `exceedance_probability`, the
+            fraction of members above the channel capacity at each step.
+
+    Raises:
+        ValueError: If the inputs are invalid or no schedule meets the pool
+            limits and target.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reservoir import optimize_drawdown
+
+        plan = optimize_drawdown(
+            forecast_members,
+            timedelta(hours=6),
+            elevation=[200.0, 210.0, 220.0, 230.0],
+            storage=[0.0, 2.0e7, 5.0e7, 9.0e7],
+            initial_elevation=221.0,
+            max_release=400.0,
+            max_ramp=50.0,
+            initial_release=100.0,
+            max_elevation=229.0,
+            target_elevation=218.0,
+            channel_capacity=300.0,
+        )
+        releases = plan["release"]
+        ```
+    """
+    if inflow and not hasattr(inflow[0], "__len__"):
+        inflow = [inflow]
+    if local_inflow and not hasattr(local_inflow[0], "__len__"):
+        local_inflow = [local_inflow]
+    return reservoir.optimize_drawdown(
+        inflow,
+        time_step,
+        elevation,
+        storage,
+        initial_elevation,
+        max_release,
+        min_release,
+        max_ramp,
+        initial_release,
+        min_elevation,
+        max_elevation,
+        target_elevation,
+        channel_capacity,
+        local_inflow,
+        objective,
+        release_levels,
+        storage_levels,
+        units,
+    )
@@ -0,0 +1,167 @@
+from datetime import timedelta
+from typing import Optional, Sequence
+
+from ..rustflow import reservoir
+
+__all__ = ["sequent_peak", "simulate_yield", "storage_yield_reliability"]
+
+# Flows are rates (e.g. m³/s) and storages volumes in flow units × seconds
+# (m³); evaporation is a depth per step in the length unit of the
+# storage–area table.
+
+
+def sequent_peak(
+    inflow: Sequence[float],
+    demand: float,
+    time_step: timedelta,
+    demand_pattern: Optional[Sequence[float]] = None,
+) -> float:
+    """
+    Storage required to meet a demand without shortage (sequent-peak
+    algorithm).
+
+    The record is analysed twice over so that a critical period that spans
+    its end is included. Evaporation is not considered; use
+    `storage_yield_reliability` for that.
+
+    Args:
+        inflow (Sequence[float]): Inflow rates (e.g. m³/s).
+        demand (float): Mean demand rate.
+        time_step (timedelta): Interval between consecutive values.
+        demand_pattern (Optional[Sequence[float]], optional): Demand
+            multipliers repeated over the record, e.g. 12 monthly factors
+            with mean 1. Defaults to a constant demand.
+
+    Returns:
+        float: Required storage, in flow units × seconds.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reservoir.yield_analysis import sequent_peak
+
+        storage = sequent_peak(monthly_flows, demand=4.0, time_step=timedelta(days=30.44))
+        ```
+    """
+    pattern = list(demand_pattern) if demand_pattern is not None else None
+    return reservoir.sequent_peak(inflow, demand, time_step, pattern)
+
+
+def simulate_yield(
+    inflow: Sequence[float],
+    capacity: float,
+    demand: float,
+    time_step: timedelta,
+    initial_storage: Optional[float] = None,
+    demand_pattern: Optional[Sequence[float]] = None,
+    evaporation: Optional[Sequence[float]] = None,
+    storage_area: Optional[tuple[Sequence[float], Sequence[float]]] = None,
+) -> dict:
+    """
+    Simulates a supply reservoir under the standard operating policy.
+
+    Each step the demand is released if storage allows, evaporation is
+    taken from the water surface, and water above `capacity` spills.
+
+    Args:
+        inflow (Sequence[float]): Inflow rates (e.g. m³/s).
+        capacity (float): Active storage capacity (flow units × seconds).
+        demand (float): Mean demand rate.
+        time_step (timedelta): Interval between consecutive values.
+        initial_storage (Optional[float], optional): Storage at the start.
+            Defaults to full.
+        demand_pattern (Optional[Sequence[float]], optional): Demand
+            multipliers repeated over the record. Defaults to constant.
+        evaporation (Optional[Sequence[float]], optional): Evaporation depth
+            per step, repeated over the record (e.g. 12 monthly depths).
+            Requires `storage_area`.
+        storage_area (Optional[tuple[Sequence[float], Sequence[float]]],
+            optional): `(storage, area)` table giving the water surface area.
+
+    Returns:
+        dict: `storage` (end of each step), `release` and `spill` rates,
+            time-based `reliability` and `volumetric_reliability`.
+
+    Raises:
+        ValueError: If the storage–area table is invalid, or evaporation is
+            given without it.
+    """
+    return reservoir.simulate_yield(
+        inflow,
+        capacity,
+        demand,
+        time_step,
+        initial_storage,
+        list(demand_pattern) if demand_pattern is not None else None,
+        list(evaporation) if evaporation is not None else None,
+        _table(storage_area),
+    )
+
+
+def storage_yield_reliability(
+    traces: Sequence[Sequence[float]],
+    capacities: Sequence[float],
+    reliability: float,
+    time_step: timedelta,
+    demand_pattern: Optional[Sequence[float]] = None,
+    evaporation: Optional[Sequence[float]] = None,
+    storage_area: Optional[tuple[Sequence[float], Sequence[float]]] = None,
+) -> list[float]:
+    """
+    Storage–yield–reliability relation from simulation over inflow traces.
+
+    For each capacity, finds the largest yield whose time-based
+    reliability, pooled over all traces (each starting full), is at least
+    `reliability`. Capacities are evaluated in parallel in Rust, which makes
+    thousands of synthetic traces (see
+    `rustflow.stochastic.streamflow_generator`) practical.
+
+    Args:
+        traces (Sequence[Sequence[float]]): Inflow traces (rates).
+        capacities (Sequence[float]): Storage capacities to evaluate.
+        reliability (float): Target fraction of steps with full supply,
+            e.g. 0.98. Use 1.0 for the firm yield.
+        time_step (timedelta): Interval between consecutive values.
+        demand_pattern (Optional[Sequence[float]], optional): Demand
+            multipliers repeated over each trace. Defaults to constant.
+        evaporation (Optional[Sequence[float]], optional): Evaporation depth
+            per step, repeated over each trace. Requires `storage_area`.
+        storage_area (Optional[tuple[Sequence[float], Sequence[float]]],
+            optional): `(storage, area)` table for evaporation.
+
+    Returns:
+        list[float]: Yield (mean demand rate) for each capacity.
+
+    Raises:
+        ValueError: If `reliability` is outside [0, 1] or the evaporation
+            inputs are invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.reservoir.yield_analysis import storage_yield_reliability
+        from rustflow.stochastic import streamflow_generator
+
+        traces = streamflow_generator(monthly_flows, 12 * 50, realizations=500, seed=1)
+        capacities = [1e7, 5e7, 1e8, 2e8]  # m³
+        yields = storage_yield_reliability(
+            traces, capacities, 0.95, timedelta(days=30.44)
+        )
+        ```
+    """
+    return reservoir.storage_yield_reliability(
+        list(traces),
+        list(capacities),
+        reliability,
+        time_step,
+        list(demand_pattern) if demand_pattern is not None else None,
+        list(evaporation) if evaporation is not None else None,
+        _table(storage_area),
+    )
+
+
+def _table(storage_area):
+    if storage_area is None:
+        return None
+    storage, area = storage_area
+    return list(storage), list(area)
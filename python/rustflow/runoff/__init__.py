@@ -0,0 +1,463 @@
+from datetime import datetime, timedelta
+from typing import Literal, Optional, Union
+
+from ..rustflow import runoff
+
+NonlinearReservoir = runoff.NonlinearReservoir
+
+
+def clark_unit_hydrograph(
+    time_area: list[float],
+    storage_coefficient: timedelta,
+    time_step: timedelta,
+    units: Literal["si", "us"] = "si",
+) -> list[float]:
+    """
+    Computes the Clark unit hydrograph of a time–area histogram.
+
+    Excess falling in one time step reaches the outlet as the time–area
+    histogram, translated by travel time, and is then attenuated by a
+    linear reservoir with the storage coefficient `R`. The ordinates are
+    mean discharges over each step and carry exactly one mm (or inch) of
+    excess over the basin.
+
+    Args:
+        time_area (list[float]): Drainage area (km² or mi², per `units`)
+            reaching the outlet within each time step, as returned by
+            `rustflow.terrain.FlowGrid.travel_time`.
+        storage_coefficient (timedelta): Clark storage coefficient `R`. Zero
+            gives the translation hydrograph alone.
+        time_step (timedelta): Time step of the histogram and of the
+            ordinates.
+        units (Literal["si", "us"], optional): Unit system of the areas and
+            ordinates. Defaults to `"si"`.
+
+    Returns:
+        list[float]: Ordinates (m³/s per mm or cfs per inch of excess), for
+            `rustflow.design.pmf(unit_hydrograph=...)` or convolution with
+            excess.
+
+    Raises:
+        ValueError: If an area or the storage coefficient is negative, or
+            the histogram is empty.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.runoff import clark_unit_hydrograph
+
+        uh = clark_unit_hydrograph(
+            [1.5, 4.0, 6.5, 3.0], timedelta(hours=3), timedelta(hours=1)
+        )
+        ```
+    """
+    return runoff.clark_unit_hydrograph(
+        time_area, storage_coefficient, time_step, units
+    )
+
+
+def nonlinear_reservoir_runoff(
+    rainfall: list[float],
+    time_step: timedelta,
+    area: float,
+    width: float,
+    slope: float,
+    n: float,
+    depression_storage: float = 0.0,
+    loss_rate: float = 0.0,
+    units: Literal["si", "us"] = "si",
+) -> list[float]:
+    """
+    Computes subcatchment runoff with the SWMM nonlinear reservoir method.
+
+    Rainfall fills a surface store; losses are withdrawn at a constant rate
+    and water above the depression storage runs off as sheet flow following
+    Manning's equation across the subcatchment width.
+
+    Args:
+        rainfall (list[float]): Rainfall intensity per time step (mm/h or
+            in/h, per `units`).
+        time_step (timedelta): Interval between consecutive rainfall values.
+        area (float): Subcatchment area (ha or acres).
+        width (float): Characteristic overland flow width (m or ft).
+        slope (float): Average surface slope (m/m).
+        n (float): Manning's roughness for overland flow.
+        depression_storage (float, optional): Depression storage depth (mm or
+            in). Defaults to 0.
+        loss_rate (float, optional): Constant infiltration/evaporation loss
+            (mm/h or in/h). Defaults to 0.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            result. Defaults to `"si"`.
+
+    Returns:
+        list[float]: Runoff at the end of each time step (m³/s or cfs).
+
+    Raises:
+        ValueError: If `area`, `width`, `slope` or `n` is not positive.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.runoff import nonlinear_reservoir_runoff
+
+        rain = [0.0, 10.0, 25.0, 10.0, 0.0, 0.0]  # mm/h
+        runoff = nonlinear_reservoir_runoff(
+            rain, timedelta(minutes=5), area=4.0, width=200.0, slope=0.01, n=0.015
+        )
+        ```
+    """
+    return runoff.nonlinear_reservoir_runoff(
+        rainfall,
+        time_step,
+        area,
+        width,
+        slope,
+        n,
+        depression_storage,
+        loss_rate,
+        units,
+    )
+
+
+def rainfall_excess(
+    rainfall: list[float],
+    time_step: timedelta,
+    method: Literal["initial_constant", "curve_number", "api", "deficit_constant"],
+    initial_loss: float = 0.0,
+    loss_rate: float = 0.0,
+    curve_number: Optional[float] = None,
+    initial_abstraction_ratio: float = 0.2,
+    initial_index: float = 0.0,
+    decay: float = 0.9,
+    capacity: Optional[float] = None,
+    exponent: float = 1.0,
+    max_deficit: Optional[float] = None,
+    initial_deficit: float = 0.0,
+    recovery_rate: float = 0.0,
+    impervious_fraction: float = 0.0,
+    units: Literal["si", "us"] = "si",
+) -> list[float]:
+    """
+    Converts incremental rainfall into rainfall excess with a loss method.
+
+    `initial_constant` and `curve_number` are event methods. The other two
+    carry the wetness of the catchment through dry spells, so a continuous
+    record with several storms can be simulated without a full conceptual
+    model:
+
+    - `api`: an antecedent precipitation index that gains each step's
+      rainfall and decays by `decay` per day. The share of each step's
+      rainfall that runs off is `min(1, API / capacity) ** exponent`, so
+      storms on wet ground yield more runoff.
+    - `deficit_constant` (HEC-HMS): a soil moisture accounting layer that
+      takes up rainfall until its deficit is filled, after which the rain
+      runs off. The layer drains at `loss_rate` while it holds water and
+      dries at `recovery_rate` (evapotranspiration) in steps without rain,
+      recovering its deficit between storms.
+
+    Args:
+        rainfall (list[float]): Incremental rainfall depth (mm or in per
+            step, per `units`). Any iterable of numbers or Arrow array is
+            accepted.
+        time_step (timedelta): Interval between values.
+        method (str): `initial_constant`, `curve_number`, `api` or
+            `deficit_constant`.
+        initial_loss (float, optional): Initial loss (mm or in) for
+            `initial_constant`. Defaults to 0.0.
+        loss_rate (float, optional): Constant loss rate (mm/h or in/h) for
+            `initial_constant`, and drainage rate for `deficit_constant`.
+            Defaults to 0.0.
+        curve_number (Optional[float], optional): NRCS curve number, needed
+            for `curve_number`. Defaults to None.
+        initial_abstraction_ratio (float, optional): `Ia / S` for
+            `curve_number`. Defaults to 0.2.
+        initial_index (float, optional): Antecedent precipitation index
+            (mm or in) at the start, for `api`. Defaults to 0.0.
+        decay (float, optional): Daily decay factor of the index, within
+            [0, 1], for `api`. Defaults to 0.9.
+        capacity (Optional[float], optional): Index (mm or in) at which all
+            rainfall runs off, needed for `api`. Defaults to None.
+        exponent (float, optional): Shape of the runoff share for `api`.
+            Defaults to 1.0.
+        max_deficit (Optional[float], optional): Water (mm or in) the dry
+            soil layer can take up, needed for `deficit_constant`. Defaults
+            to None.
+        initial_deficit (float, optional): Deficit (mm or in) at the start,
+            at most `max_deficit`, for `deficit_constant`. Defaults to 0.0.
+        recovery_rate (float, optional): Drying rate (mm/h or in/h) in steps
+            without rain, for `deficit_constant`. Defaults to 0.0.
+        impervious_fraction (float, optional): Share of the catchment that
+            is directly connected impervious area, where all rainfall runs
+            off. The loss method applies to the rest, as with the percentage
+            impervious of HEC-HMS loss methods. Defaults to 0.0.
+        units (Literal["si", "us"], optional): Unit system of the depths and
+            rates. Defaults to `"si"`.
+
+    Returns:
+        list[float]: Rainfall excess (mm or in per step).
+
+    Raises:
+        ValueError: If the method is unknown, a required parameter is
+            missing, or a parameter (including `impervious_fraction`) is out
+            of range.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.runoff import rainfall_excess
+
+        excess = rainfall_excess(
+            hourly_rain, timedelta(hours=1), "deficit_constant",
+            max_deficit=60.0, initial_deficit=25.0, loss_rate=1.5, recovery_rate=0.2,
+        )
+        ```
+    """
+    return runoff.rainfall_excess(
+        rainfall,
+        time_step,
+        method,
+        initial_loss,
+        loss_rate,
+        curve_number,
+        initial_abstraction_ratio,
+        initial_index,
+        decay,
+        capacity,
+        exponent,
+        max_deficit,
+        initial_deficit,
+        recovery_rate,
+        impervious_fraction,
+        units,
+    )
+
+
+def baseflow(
+    direct_runoff: list[float],
+    time_step: timedelta,
+    method: Literal["constant", "monthly_constant", "recession"] = "constant",
+    flow: float = 0.0,
+    monthly: Optional[list[float]] = None,
+    start: Optional[datetime] = None,
+    recession_constant: Optional[float] = None,
+    ratio_to_peak: Optional[float] = None,
+    threshold_flow: Optional[float] = None,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, list[float]]:
+    """
+    Adds baseflow to a direct runoff hydrograph.
+
+    The methods match their HEC-HMS counterparts, so subbasins imported
+    from HMS models give the same total flow:
+
+    - `constant`: the same baseflow throughout;
+    - `monthly_constant`: a baseflow for each calendar month, applied by
+      the month of each value's time;
+    - `recession`: baseflow receding from `flow` by `recession_constant`
+      per day. After the peak of an event, once the total flow falls to the
+      threshold, the total flow itself recedes, so the falling limb goes on
+      after the direct runoff ends. Rising direct runoff of a later event
+      starts on top of the receding flow.
+
+    Args:
+        direct_runoff (list[float]): Direct runoff (m³/s or cfs, per
+            `units`), e.g. rainfall excess convolved with a unit
+            hydrograph. Any iterable of numbers or Arrow array is accepted.
+        time_step (timedelta): Interval between values.
+        method (Literal["constant", "monthly_constant"], optional): Baseflow
+            method. Defaults to "constant".
+        flow (float, optional): Baseflow for `constant`, or initial
+            baseflow for `recession`. Defaults to 0.0.
+        monthly (Optional[list[float]], optional): Twelve baseflows,
+            January first, needed for `monthly_constant`. Defaults to None.
+        start (Optional[datetime], optional): Time of the first value,
+            needed for `monthly_constant`. Defaults to None.
+        recession_constant (Optional[float], optional): Ratio of the flow to
+            the flow one day earlier, within (0, 1], needed for
+            `recession`. Defaults to None.
+        ratio_to_peak (Optional[float], optional): Threshold as a share of
+            the event's peak total flow, within (0, 1], for `recession`.
+            Defaults to None.
+        threshold_flow (Optional[float], optional): Threshold as a flow, for
+            `recession` instead of `ratio_to_peak`. Defaults to None.
+        units (Literal["si", "us"], optional): Unit system of the flows.
+            Defaults to `"si"`.
+
+    Returns:
+        dict[str, list[float]]: `baseflow` and `total`, the direct runoff
+            plus baseflow, at each step.
+
+    Raises:
+        ValueError: If the method is unknown, a required parameter is
+            missing, `monthly` does not have 12 values, a baseflow is
+            negative, or a recession parameter is out of range.
+
+    Example:
+        ```python
+        from datetime import datetime, timedelta
+        from rustflow.runoff import baseflow
+
+        flows = baseflow(
+            direct, timedelta(hours=1), "monthly_constant",
+            monthly=[4.0, 4.5, 6.0, 7.5, 6.0, 3.0, 1.5, 1.0, 1.2, 2.0, 3.0, 3.5],
+            start=datetime(2024, 3, 30),
+        )
+        event = baseflow(
+            direct, timedelta(hours=1), "recession",
+            flow=5.0, recession_constant=0.8, ratio_to_peak=0.2,
+        )
+        ```
+    """
+    return runoff.baseflow(
+        direct_runoff,
+        time_step,
+        method,
+        flow,
+        monthly,
+        start,
+        recession_constant,
+        ratio_to_peak,
+        threshold_flow,
+        units,
+    )
+
+
+def refh_parameters(
+    area: float,
+    propwet: float,
+    dplbar: float,
+    dpsbar: float,
+    bfihost: float,
+    urbext: float = 0.0,
+) -> dict[str, Union[float, timedelta]]:
+    """
+    Estimates rural ReFH parameters from FEH catchment descriptors.
+
+    Uses the regression equations of the Revitalised Flood Hydrograph model
+    (Kjeldsen, 2007) for the time to peak, the soil moisture capacity and
+    the baseflow lag and recharge. The result can be passed straight to
+    `refh`.
+
+    Args:
+        area (float): Catchment area (km²), AREA.
+        propwet (float): Proportion of time soils are wet, PROPWET, within
+            (0, 1].
+        dplbar (float): Mean drainage path length (km), DPLBAR.
+        dpsbar (float): Mean drainage path slope (m/km), DPSBAR.
+        bfihost (float): Base flow index from HOST soils, BFIHOST, within
+            (0, 1].
+        urbext (float, optional): Urban extent, URBEXT2000, within [0, 1].
+            Defaults to 0.0.
+
+    Returns:
+        dict[str, Union[float, timedelta]]: `time_to_peak`, `max_moisture`
+            (mm), `baseflow_lag` and `baseflow_recharge`.
+
+    Raises:
+        ValueError: If a descriptor is out of range.
+
+    Example:
+        ```python
+        from rustflow.runoff import refh_parameters
+
+        parameters = refh_parameters(
+            area=120.0, propwet=0.45, dplbar=12.5, dpsbar=80.0, bfihost=0.42,
+        )
+        ```
+    """
+    return runoff.refh_parameters(area, propwet, dplbar, dpsbar, bfihost, urbext)
+
+
+def refh(
+    rainfall: list[float],
+    time_step: timedelta,
+    area: float,
+    time_to_peak: timedelta,
+    max_moisture: float,
+    baseflow_lag: timedelta,
+    baseflow_recharge: float,
+    initial_moisture: float = 0.0,
+    initial_baseflow: float = 0.0,
+    peak: float = 0.65,
+    kink: float = 0.8,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, list[float]]:
+    """
+    Runs the ReFH rainfall–runoff model of the UK Flood Estimation Handbook.
+
+    The Revitalised Flood Hydrograph model (Kjeldsen, 2007) has three
+    components:
+
+    - loss: a soil moisture store fills from `initial_moisture` with each
+      step's rainfall `P`, and `(C + P / 2) / Cmax` of that rainfall runs
+      off, with `C` the moisture before the step;
+    - unit hydrograph: rises linearly to `peak` at the time to peak `Tp`,
+      falls at the same slope to `kink × peak`, then linearly to zero at
+      the time that gives it unit area, all in multiples of `Tp`;
+    - baseflow: a linear reservoir with lag `BL`, recharged by
+      `baseflow_recharge` times the direct runoff, starting from
+      `initial_baseflow`.
+
+    The hydrograph runs until the unit hydrograph of the last rainfall has
+    passed. Use `refh_parameters` to estimate the parameters of an ungauged
+    rural catchment.
+
+    Args:
+        rainfall (list[float]): Rainfall depth (mm or in, per `units`) in
+            each time step. Any iterable of numbers or Arrow array is
+            accepted.
+        time_step (timedelta): Interval between rainfall values and of the
+            hydrograph.
+        area (float): Catchment area (km² or mi²).
+        time_to_peak (timedelta): Time to peak `Tp` of the instantaneous
+            unit hydrograph.
+        max_moisture (float): Soil moisture capacity `Cmax` (mm or in).
+        baseflow_lag (timedelta): Baseflow lag `BL`.
+        baseflow_recharge (float): Baseflow recharge `BR`.
+        initial_moisture (float, optional): Initial soil moisture `Cini`
+            (mm or in), within [0, `max_moisture`]. Defaults to 0.0.
+        initial_baseflow (float, optional): Initial baseflow `BF0` (m³/s or
+            cfs). Defaults to 0.0.
+        peak (float, optional): Peak ordinate `Up` of the dimensionless unit
+            hydrograph. Defaults to 0.65.
+        kink (float, optional): Kink ordinate `Uk` as a share of the peak,
+            within (0, 1). Defaults to 0.8.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            outputs. Defaults to `"si"`.
+
+    Returns:
+        dict[str, list[float]]: `excess` (mm or in per step) and the
+            `direct` runoff, `baseflow` and `total` flow (m³/s or cfs).
+
+    Raises:
+        ValueError: If the rainfall is empty or a parameter is out of
+            range.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.runoff import refh, refh_parameters
+
+        parameters = refh_parameters(
+            area=120.0, propwet=0.45, dplbar=12.5, dpsbar=80.0, bfihost=0.42,
+        )
+        flows = refh(
+            [0.0, 4.0, 12.0, 18.0, 9.0, 3.0], timedelta(hours=1), area=120.0,
+            initial_moisture=100.0, initial_baseflow=2.0, **parameters,
+        )
+        ```
+    """
+    return runoff.refh(
+        rainfall,
+        time_step,
+        area,
+        time_to_peak,
+        max_moisture,
+        baseflow_lag,
+        baseflow_recharge,
+        initial_moisture,
+        initial_baseflow,
+        peak,
+        kink,
+        units,
+    )
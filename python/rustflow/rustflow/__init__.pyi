@@ -0,0 +1,56 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+from . import calibrate as calibrate
+from . import climate as climate
+from . import design as design
+from . import drought as drought
+from . import economics as economics
+from . import ensemble as ensemble
+from . import forecast as forecast
+from . import frequency as frequency
+from . import functional as functional
+from . import groundwater as groundwater
+from . import hydraulics as hydraulics
+from . import hydrograph as hydrograph
+from . import io as io
+from . import network as network
+from . import precip as precip
+from . import quality as quality
+from . import reach as reach
+from . import regionalize as regionalize
+from . import reservoir as reservoir
+from . import runoff as runoff
+from . import scenario as scenario
+from . import sediment as sediment
+from . import stochastic as stochastic
+from . import terrain as terrain
+from . import timeseries as timeseries
+
+class RustflowError(ValueError): ...
+
+
+class ConvergenceError(RustflowError): ...
+
+
+class DataError(RustflowError): ...
+
+
+class MassBalanceError(RustflowError): ...
+
+
+class ParameterError(RustflowError): ...
+
+
+InvalidParameterError = ParameterError
+
+
+def reset_logging() -> Any:
+    """
+    Re-reads the level of the `rustflow` logger. The level is read when the
+    module is imported; call this after changing it, e.g. with
+    `logging.getLogger("rustflow").setLevel(logging.DEBUG)`.
+    """
@@ -0,0 +1,151 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class CrossValidationResult:
+    """Calibration and validation scores of a temporal cross-validation."""
+    def __len__(self) -> int:
+        ...
+    @property
+    def calibration_scores(self) -> list[float]:
+        """list[float]: Calibration score of each split."""
+    @property
+    def degradation(self) -> list[float]:
+        """list[float]: Calibration less validation score of each split."""
+    @property
+    def folds(self) -> list[dict]:
+        """
+        list[dict]: One dict per split: the `calibration` periods and
+        `validation` period as `(start, end)` step ranges, the calibrated
+        `parameters`, and the `calibration`, `validation` and `degradation`
+        scores.
+        """
+    @property
+    def likelihood(self) -> str:
+        """str: Likelihood measure used to calibrate and validate."""
+    @property
+    def parameters(self) -> list[str]:
+        """list[str]: Calibrated parameter paths."""
+    @property
+    def samples(self) -> int:
+        """int: Number of parameter sets tried."""
+    @property
+    def summary(self) -> dict[str, float]:
+        """
+        dict[str, float]: `mean_calibration`, `mean_validation`,
+        `mean_degradation` and `max_degradation` over the splits.
+        """
+    @property
+    def validation_scores(self) -> list[float]:
+        """list[float]: Validation score of each split."""
+
+
+class GlueResult:
+    """Outcome of a GLUE analysis."""
+    @property
+    def behavioral(self) -> list[int]:
+        """list[int]: Indices of the behavioural sets."""
+    @property
+    def behavioral_parameters(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Behavioural parameter sets as a dict of
+        parameter path to values.
+        """
+    @property
+    def likelihood(self) -> list[float]:
+        """list[float]: Likelihood of every set (`nan` where the run failed)."""
+    @property
+    def lower(self) -> list[float]:
+        """list[float]: Lower prediction bound at each step."""
+    @property
+    def median(self) -> list[float]:
+        """list[float]: Median prediction at each step."""
+    @property
+    def parameters(self) -> list[str]:
+        """list[str]: Calibrated parameter paths, in sample column order."""
+    @property
+    def samples(self) -> list[list[float]]:
+        """list[list[float]]: All sampled parameter sets, one row per set."""
+    @property
+    def upper(self) -> list[float]:
+        """list[float]: Upper prediction bound at each step."""
+
+
+class ParetoFront:
+    """Non-dominated parameter sets found by NSGA-II."""
+    def __len__(self) -> int:
+        ...
+    @property
+    def evaluations(self) -> int:
+        """int: Number of model runs made."""
+    @property
+    def objective_values(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Scores on the front as a dict of objective
+        name to values.
+        """
+    @property
+    def objectives(self) -> list[str]:
+        """list[str]: Objective names, in score column order."""
+    @property
+    def parameter_sets(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Sets on the front as a dict of parameter
+        path to values.
+        """
+    @property
+    def parameters(self) -> list[str]:
+        """list[str]: Calibrated parameter paths, in set column order."""
+    @property
+    def scores(self) -> list[list[float]]:
+        """list[list[float]]: Objective values of each set on the front."""
+    @property
+    def sets(self) -> list[list[float]]:
+        """list[list[float]]: Parameter values of each set on the front."""
+
+
+def cross_validate(config: dict, parameters: Mapping[str, tuple[float, float]], inflows: Mapping[str, Sequence[float]], observed: Sequence[float], node: str, time_step: timedelta, scheme: Literal['split_sample', 'k_fold', 'rolling'] = 'split_sample', fraction: float = 0.5, folds: int = 5, calibration_window: Optional[Union[int, timedelta]] = None, validation_window: Optional[Union[int, timedelta]] = None, step: Optional[Union[int, timedelta]] = None, warm_up: Union[int, timedelta] = 0, samples: int = 1000, likelihood: Literal['nse', 'kge', 'log_nse'] = 'nse', seed: Optional[int] = None, progress_callback: Optional[Callable[[int, int], None]] = None) -> Any:
+    ...
+
+
+def glue(config: dict, parameters: Mapping[str, tuple[float, float]], inflows: Mapping[str, Sequence[float]], observed: Sequence[float], node: str, time_step: timedelta, samples: int = 1000, likelihood: Literal['nse', 'kge', 'log_nse'] = 'nse', threshold: float = 0.5, bounds: tuple[float, float] = ..., seed: Optional[int] = None, progress_callback: Optional[Callable[[int, int], None]] = None) -> Any:
+    ...
+
+
+def kge(simulated: Sequence[float], observed: Sequence[float]) -> Any:
+    ...
+
+
+def log_nse(simulated: Sequence[float], observed: Sequence[float]) -> Any:
+    ...
+
+
+def morris(config: dict, parameters: Mapping[str, tuple[float, float]], inflows: Mapping[str, Sequence[float]], node: str, time_step: timedelta, objective: Literal['peak', 'volume', 'mean', 'nse', 'kge', 'log_nse'] = 'peak', observed: Optional[Sequence[float]] = None, trajectories: int = 20, levels: int = 4, seed: Optional[int] = None, progress_callback: Optional[Callable[[int, int], None]] = None) -> Any:
+    ...
+
+
+def muskingum_gradient(inflow: Sequence[float], k: timedelta, x: float, time_step: timedelta, sub_reaches: int = 1, initial_outflow: Optional[float] = None, observed: Optional[Sequence[float]] = None) -> Any:
+    ...
+
+
+def network_gradient(config: dict, parameters: Mapping[str, float], inflows: Mapping[str, Sequence[float]], node: str, time_step: timedelta, objective: Literal['peak', 'volume', 'mean', 'nse', 'kge', 'log_nse'] = 'nse', observed: Optional[Sequence[float]] = None, relative_step: float = 1e-06) -> Any:
+    ...
+
+
+def nse(simulated: Sequence[float], observed: Sequence[float]) -> Any:
+    ...
+
+
+def nsga2(config: dict, parameters: Mapping[str, tuple[float, float]], inflows: Mapping[str, Sequence[float]], observed: Sequence[float], node: str, time_step: timedelta, objectives: Sequence[Literal['nse', 'kge', 'log_nse']] = ..., population: int = 100, generations: int = 50, crossover: float = 0.9, seed: Optional[int] = None, progress_callback: Optional[Callable[[int, int], None]] = None) -> Any:
+    ...
+
+
+def rmse(simulated: Sequence[float], observed: Sequence[float]) -> Any:
+    ...
+
+
+def sobol(config: dict, parameters: Mapping[str, tuple[float, float]], inflows: Mapping[str, Sequence[float]], node: str, time_step: timedelta, objective: Literal['peak', 'volume', 'mean', 'nse', 'kge', 'log_nse'] = 'peak', observed: Optional[Sequence[float]] = None, samples: int = 1024, seed: Optional[int] = None, progress_callback: Optional[Callable[[int, int], None]] = None) -> Any:
+    ...
@@ -0,0 +1,13 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def delta_change(series: list[float], factors: list[float], start: Optional[datetime] = None, time_step: Optional[timedelta] = None, kind: Literal['additive', 'multiplicative'] = 'multiplicative') -> Any:
+    ...
+
+
+def quantile_mapping(series: list[float], observed: list[float], modelled: list[float], method: Literal['empirical', 'quantile_delta'] = 'empirical', kind: Literal['additive', 'multiplicative'] = 'multiplicative') -> Any:
+    ...
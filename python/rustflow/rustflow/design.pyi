@@ -0,0 +1,9 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def pmf(rainfall: list[float], time_step: timedelta, area: float, elevation: list[float], storage: list[float], outflow: list[float], initial_elevation: float, crest_elevation: float, loss: Literal['curve_number', 'initial_constant'] = 'curve_number', curve_number: Optional[float] = None, initial_abstraction_ratio: float = 0.2, initial_loss: float = 0.0, loss_rate: float = 0.0, lag: Optional[timedelta] = None, unit_hydrograph: Optional[list[float]] = None, time_area: Optional[list[float]] = None, storage_coefficient: Optional[timedelta] = None, peaking_factor: float = 1.0, baseflow: float = 0.0, freeboard: float = 0.0, units: Literal['si', 'us'] = ...) -> Any:
+    ...
@@ -0,0 +1,13 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def spi(precipitation: Union[list[float], dict[str, list[float]]], windows: list[int] = ..., seasons: int = 12, distribution: Literal['gamma', 'lognormal'] = 'gamma') -> Any:
+    ...
+
+
+def ssi(flows: Union[list[float], dict[str, list[float]]], windows: list[int] = ..., seasons: int = 12, distribution: Literal['gamma', 'lognormal'] = 'lognormal') -> Any:
+    ...
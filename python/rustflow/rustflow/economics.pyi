@@ -0,0 +1,17 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def event_damage(peaks: dict[str, float], curves: dict[str, tuple[list[float], list[float]]]) -> Any:
+    ...
+
+
+def expected_annual_damage(probabilities: list[float], peaks: dict[str, list[float]], curves: dict[str, tuple[list[float], list[float]]]) -> Any:
+    ...
+
+
+def integrate_damage(probabilities: list[float], damages: list[float]) -> Any:
+    ...
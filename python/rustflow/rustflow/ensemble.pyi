@@ -0,0 +1,51 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+from .network import Network
+
+class EnsembleResult:
+    """Member traces and their per-time-step summary."""
+    def __len__(self) -> int:
+        ...
+    @property
+    def dtype(self) -> Literal["float32", "float64"]:
+        """Literal["float32", "float64"]: Precision the members are stored in."""
+    @property
+    def mean(self) -> list[float]:
+        """list[float]: Mean across members at each time step."""
+    @property
+    def members(self) -> list[list[float]]:
+        """
+        list[list[float]]: Routed series, one list per member. Members
+        routed in `float32` are a 2-D `float32` NumPy array instead, with
+        one row per member.
+        """
+    @property
+    def parameters(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Parameter values each member was routed
+        with (`k` in seconds and `x` for `muskingum_uncertainty`); empty
+        for inflow ensembles.
+        """
+    @property
+    def quantiles(self) -> dict[float, list[float]]:
+        """dict[float, list[float]]: Quantile series keyed by probability."""
+
+
+def muskingum_ensemble(inflows: Sequence[Sequence[float]], k: timedelta, x: float, time_step: timedelta, sub_reaches: int = 1, initial_outflow: Optional[float] = None, quantiles: Sequence[float] = ...) -> Any:
+    ...
+
+
+def muskingum_fosm(inflow: Sequence[float], k: timedelta, x: float, time_step: timedelta, k_sd: timedelta, x_sd: float, correlation: float = 0.0, sub_reaches: int = 1, initial_outflow: Optional[float] = None) -> Any:
+    ...
+
+
+def muskingum_uncertainty(inflow: Sequence[float], k: Union[float, timedelta, Sequence[Union[float, timedelta]], tuple], x: Union[float, timedelta, Sequence[Union[float, timedelta]], tuple], time_step: timedelta, sub_reaches: int = 1, initial_outflow: Optional[float] = None, samples: int = 1000, quantiles: Sequence[float] = ..., seed: Optional[int] = None, allow_negative_c0: bool = False) -> Any:
+    ...
+
+
+def network_ensemble(network: Network, inflows: Mapping[str, Sequence[Sequence[float]]], time_step: timedelta, quantiles: Sequence[float] = ..., progress_callback: Optional[Callable[[int, int], None]] = None) -> Any:
+    ...
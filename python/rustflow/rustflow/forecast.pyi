@@ -0,0 +1,13 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def ar1_error_correction(simulated: Sequence[float], observed: Sequence[float], phi: Optional[float] = None) -> Any:
+    ...
+
+
+def enkf_muskingum(inflow: Sequence[float], observed: Sequence[float], k: timedelta, x: float, time_step: timedelta, sub_reaches: int = 1, members: int = 100, inflow_error: float = 0.2, observation_error: float = 0.05, seed: Optional[int] = None) -> Any:
+    ...
@@ -0,0 +1,74 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class IdfCurve:
+    """
+    Fitted intensity–duration–frequency relationship, callable as
+    `curve(duration, return_period)` for the intensity.
+    """
+    def __call__(self, *args: Any, **kwargs: Any) -> Any:
+        ...
+    def depth(self, duration: timedelta, return_period: float) -> float:
+        """
+        Rainfall depth accumulated over `duration`.
+
+        Args:
+            duration (timedelta): Storm duration.
+            return_period (float): Return period in years, above 1.
+
+        Returns:
+            float: The depth.
+        """
+    def intensity(self, duration: timedelta, return_period: float) -> float:
+        """
+        Rainfall intensity.
+
+        Args:
+            duration (timedelta): Storm duration.
+            return_period (float): Return period in years, above 1.
+
+        Returns:
+            float: The intensity, in depth units per hour.
+        """
+    @property
+    def method(self) -> Literal["gev", "sherman"]:
+        """Literal["gev", "sherman"]: Per-duration GEV (`"gev"`) or `"sherman"`."""
+    @property
+    def parameters(self) -> dict[str, Any]:
+        """
+        dict[str, Any]: Fitted parameters: per-duration `durations` (hours),
+        `location`, `scale` and `shape`, or Sherman `k`, `m`, `b` (hours) and
+        `n`.
+        """
+
+
+def empirical_cdf(values: list[float], points: list[float]) -> Any:
+    ...
+
+
+def empirical_quantiles(values: list[float], return_periods: list[float], method: Literal['weibull', 'gringorten', 'cunnane', 'hazen'] = 'weibull') -> Any:
+    ...
+
+
+def empirical_return_periods(values: list[float], magnitudes: list[float], method: Literal['weibull', 'gringorten', 'cunnane', 'hazen'] = 'weibull') -> Any:
+    ...
+
+
+def idf_fit(durations: list[timedelta], annual_maxima: list[list[float]], method: Literal['gev', 'sherman'] = 'gev', distribution: Literal['gev', 'gumbel'] = 'gev') -> Any:
+    ...
+
+
+def low_flow(flows: list[float], start: datetime, n_days: int = 7, return_periods: list[float] = ..., distribution: Literal['lp3', 'weibull'] = 'lp3', year_start_month: int = 4) -> Any:
+    ...
+
+
+def plotting_positions(values: list[float], method: Literal['weibull', 'gringorten', 'cunnane', 'hazen'] = 'weibull') -> Any:
+    ...
+
+
+def regression_peaks(equations: Union[str, PathLike, dict[str, Any]], characteristics: dict[str, float], confidence: float = 0.9) -> Any:
+    ...
@@ -0,0 +1,13 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def muskingum(inflow: Any, k: Union[float, Any], x: Union[float, Any], dt: float, sub_reaches: int = 1) -> Any:
+    ...
+
+
+def muskingum_vjp(inflow: Any, k: Union[float, Any], x: Union[float, Any], dt: float, grad_outflow: Any, sub_reaches: int = 1) -> Any:
+    ...
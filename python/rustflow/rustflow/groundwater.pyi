@@ -0,0 +1,9 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def simulate(recharge: list[float], time_step: timedelta, area: float, specific_yield: float, k: Optional[timedelta] = None, coefficient: Optional[float] = None, exponent: float = 1.0, bottom: float = 0.0, initial_head: Optional[float] = None, stream_level: Optional[list[float]] = None, conductance: Optional[float] = None, stream_bed: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
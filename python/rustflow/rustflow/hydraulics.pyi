@@ -0,0 +1,104 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class Culvert:
+    """
+    Circular or box culvert with inlet and outlet control after FHWA HDS-5.
+
+    Headwaters are depths above the inlet invert and tailwaters depths above
+    the outlet invert, in metres (`units="si"`) or feet (`units="us"`);
+    discharges are in m³/s or cfs and are shared equally by the barrels.
+
+    Args:
+        shape (str): `"circular"` or `"box"`.
+        inlet (str): Inlet configuration, selecting the HDS-5 inlet-control
+            coefficients and the entrance loss coefficient.
+        n (float): Manning's roughness of the barrel.
+        length (float): Barrel length.
+        slope (float, optional): Barrel slope. Defaults to 0.
+        diameter (float, optional): Diameter of a circular barrel.
+        span (float, optional): Span of a box barrel.
+        rise (float, optional): Rise of a box barrel.
+        barrels (int, optional): Number of identical barrels. Defaults to 1.
+        entrance_loss (float, optional): Entrance loss coefficient `ke`
+            overriding the inlet's.
+        units (str, optional): `"si"` or `"us"`. Defaults to `"si"`.
+    """
+    def __init__(self, shape: str, inlet: str, n: float, length: float, slope: float = 0.0, diameter: Optional[float] = None, span: Optional[float] = None, rise: Optional[float] = None, barrels: int = 1, entrance_loss: Optional[float] = None, units: str = ...) -> None: ...
+    def discharge(self, headwater: float, tailwater: float = 0.0) -> float:
+        """
+        Discharge passed at a headwater.
+
+        Args:
+            headwater (float): Headwater depth.
+            tailwater (float, optional): Tailwater depth. Defaults to 0.
+
+        Returns:
+            float: Total discharge, zero at or below the tailwater level.
+        """
+    def headwater(self, discharge: float, tailwater: float = 0.0) -> dict[str, Any]:
+        """
+        Headwater needed to pass a discharge.
+
+        Args:
+            discharge (float): Total discharge.
+            tailwater (float, optional): Tailwater depth. Defaults to 0.
+
+        Returns:
+            dict[str, Any]: `headwater` (the larger of the two controls),
+            `inlet` and `outlet` control headwaters, `control` (`"inlet"` or
+            `"outlet"`) and the full-barrel `velocity`.
+        """
+    def performance(self, discharges: Sequence[float], tailwater: float = 0.0) -> dict[str, list]:
+        """
+        Performance curve: the headwater of each discharge.
+
+        Args:
+            discharges (Sequence[float]): Total discharges.
+            tailwater (float, optional): Tailwater depth. Defaults to 0.
+
+        Returns:
+            dict[str, list]: `discharge`, `headwater`, `inlet`, `outlet`,
+            `control` and `velocity`, one entry per discharge.
+        """
+    def rating(self, elevations: Sequence[float], invert: float, tailwater: float = 0.0) -> list[float]:
+        """
+        Outflow at each pool elevation of a reservoir drained by the
+        culvert, for the `outflow` column of a level-pool table.
+
+        Args:
+            elevations (Sequence[float]): Pool elevations.
+            invert (float): Elevation of the inlet invert.
+            tailwater (float, optional): Tailwater depth. Defaults to 0.
+
+        Returns:
+            list[float]: Total discharge at each elevation.
+        """
+
+
+def bridge_backwater(discharges: list[float], bottom_width: float, side_slope: float, n: float, slope: float, opening_width: float, low_chord: float, length: float, pier_width: float = 0.0, pier_drag: float = 2.0, abutment_slope: float = 0.0, contraction: float = 0.3, expansion: float = 0.5, tailwater: Optional[list[float]] = None, method: Literal['energy', 'momentum', 'highest'] = 'highest', units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def manning_discharge(depth: float, bottom_width: float, side_slope: float, n: float, slope: float, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def normal_depth(discharge: float, bottom_width: float, side_slope: float, n: float, slope: float, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def rate_stage(stage: list[float], times: list[datetime], rating_stage: Optional[list[float]] = None, rating_discharge: Optional[list[float]] = None, coefficient: Optional[float] = None, offset: float = 0.0, exponent: Optional[float] = None, shifts: Optional[list[tuple[datetime, Union[float, list[tuple[float, float]]]]]] = None, fall: Optional[list[float]] = None, reference_fall: Optional[Union[float, list[tuple[float, float]]]] = None, fall_exponent: float = 0.5, ice: Optional[list[tuple[datetime, datetime, float]]] = None) -> Any:
+    ...
+
+
+def travel_time(discharges: list[float], length: float, bottom_width: float, side_slope: float, n: float, slope: float, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def weir_flow(upstream: float, downstream: float, crest: float, length: float, coefficient: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
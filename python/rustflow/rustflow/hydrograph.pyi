@@ -0,0 +1,33 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def balanced_hydrograph(durations: list[timedelta], flows: list[float], time_step: timedelta, pattern: Optional[list[float]] = None, peak_position: float = 0.5) -> Any:
+    ...
+
+
+def compare_signatures(observed: list[float], simulated: list[float], precipitation: Optional[list[float]] = None, time_step: Optional[timedelta] = None) -> Any:
+    ...
+
+
+def iha(flows: list[float], start: datetime, impact_year: Optional[int] = None, statistic: Literal['parametric', 'nonparametric'] = 'nonparametric', year_start_month: int = 10) -> Any:
+    ...
+
+
+def lag_correlation(upstream: list[float], downstream: list[float], max_lag: int) -> Any:
+    ...
+
+
+def scale_to_peak(flows: list[float], peak: float, baseflow: float = 0.0) -> Any:
+    ...
+
+
+def scale_to_volume(flows: list[float], volume: float, time_step: timedelta, baseflow: float = 0.0) -> Any:
+    ...
+
+
+def signatures(flows: list[float], precipitation: Optional[list[float]] = None, time_step: Optional[timedelta] = None) -> Any:
+    ...
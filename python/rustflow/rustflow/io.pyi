@@ -0,0 +1,94 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class CsvChunkReader:
+    """
+    Iterator over a hydrograph CSV file in blocks of rows, returned by
+    `read_hydrograph_csv_chunks`. Each item is a `(times, flows)` pair like
+    the result of `read_hydrograph_csv`.
+    """
+
+
+class SeriesFile:
+    """
+    Series file opened for reading through a memory map. Only the header is
+    parsed on opening; indexing by name returns a zero-copy `float64`
+    memoryview of that series, and pages are read from disk only as values
+    are touched. Slice the view (or wrap it with `numpy.asarray`) to work on
+    part of a record. Close the file, or use it as a context manager, to
+    release the map once no views are in use.
+
+    Args:
+        path (str | PathLike): File written by `write_series_file`.
+    """
+    def __init__(self, path: str | PathLike) -> None: ...
+    def __len__(self) -> int:
+        ...
+    def close(self) -> Any:
+        """
+        Releases the memory map. Fails while views returned by indexing are
+        still in use.
+        """
+    @property
+    def closed(self) -> bool:
+        """bool: Whether the file has been closed."""
+    @property
+    def names(self) -> list[str]:
+        """list[str]: Names of the series, in file order."""
+    @property
+    def start(self) -> Optional[datetime]:
+        """Optional[datetime]: Time of the first value, if recorded."""
+    @property
+    def steps(self) -> int:
+        """int: Number of values in each series."""
+    @property
+    def time_step(self) -> Optional[timedelta]:
+        """Optional[timedelta]: Interval between values, if recorded."""
+
+
+def dss_interval(time_step: Any) -> Any:
+    ...
+
+
+def dss_interval_seconds(e_part: Any) -> Any:
+    ...
+
+
+def format_dss_pathname(a: Any, b: Any, c: Any, d: Any, e: Any, f: Any) -> Any:
+    ...
+
+
+def from_arrow(batch: Any) -> Any:
+    ...
+
+
+def parse_dss_pathname(pathname: Any) -> Any:
+    ...
+
+
+def read_geojson_network(text: Any, id_field: str = 'id', from_field: str = 'from_node', to_field: str = 'to_node', geographic: bool = False, units: Literal['si', 'us'] = ..., reference_flow: Optional[float] = None, tolerance: Optional[float] = None) -> Any:
+    ...
+
+
+def read_hydrograph_csv(path: Union[str, PathLike], datetime_format: Optional[str] = None, time_column: Optional[str] = None, columns: Optional[Union[Sequence[str], Mapping[str, str]]] = None, missing_values: Optional[Sequence[str]] = None, delimiter: str = ',') -> Any:
+    ...
+
+
+def read_hydrograph_csv_chunks(path: Union[str, PathLike], rows: int = 100000, datetime_format: Optional[str] = None, time_column: Optional[str] = None, columns: Optional[Union[Sequence[str], Mapping[str, str]]] = None, missing_values: Optional[Sequence[str]] = None, delimiter: str = ',') -> Any:
+    ...
+
+
+def read_swmm_inp(path: Union[str, PathLike]) -> Any:
+    ...
+
+
+def write_hydrograph_csv(path: Union[str, PathLike], times: Sequence[datetime], flows: Mapping[str, Sequence[float]], datetime_format: Optional[str] = None, time_column: Optional[str] = None, missing_value: Optional[str] = None, delimiter: str = ',') -> Any:
+    ...
+
+
+def write_series_file(path: Union[str, PathLike], series: Mapping[str, Sequence[float]], start: Optional[datetime] = None, time_step: Optional[timedelta] = None) -> Any:
+    ...
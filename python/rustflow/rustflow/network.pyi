@@ -0,0 +1,526 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class Network:
+    """
+    A routing network of elements connected upstream to downstream.
+
+    Args:
+        config (dict): Network configuration with a `nodes` list. Each node
+            has an `id`, a `type` (`"muskingum"`, `"kinematic_wave"`,
+            `"reservoir"`, `"junction"`, `"subcatchment"`, `"demand"`,
+            `"bifurcation"`, `"groundwater"`, `"treatment"`, `"bridge"`,
+            `"pump"`, `"lateral_weir"` or `"flood_cells"`), the element
+            parameters, and optional `downstream` (id of the node it drains
+            to), `inflow` (name of an external inflow series), `rainfall`
+            (name of a rainfall series), `load` (name of a pollutant load
+            series, mass per second) and `min_flow` (minimum instream flow
+            protected from demand withdrawals).
+
+    Muskingum nodes take the storage constant `k` in seconds, `x` within
+    [0, 0.5] and optional `sub_reaches`. Runs reject a time step shorter
+    than 2 K x / `sub_reaches`, for which the routing coefficient c0 is
+    negative, unless the node sets `allow_negative_c0` to true.
+
+    Junction nodes sum the outflow of every node draining to them plus their
+    own `inflow` series, less an optional constant `loss` (flow units) and
+    `loss_fraction` of the summed flow.
+
+    Bifurcation nodes send part of their inflow to the node named by
+    `divert_to` and the rest `downstream`. The diverted flow is set by one
+    of `ratio` (fraction of inflow), `threshold` (flow above it, up to an
+    optional `capacity`) or a `table_inflow`/`table_diverted` rating table.
+
+    Groundwater nodes are aquifers (`area`, `specific_yield`, `bottom`,
+    `initial_head`, and a recession constant `k` in seconds or a nonlinear
+    `coefficient`/`exponent`) recharged by their `rainfall` series, read as
+    a recharge rate, and by their inflow. They drain baseflow downstream
+    and, given a streambed `conductance` and `stream_bed` elevation, gain
+    from or lose to the downstream stream according to the head difference.
+
+    Treatment nodes are stormwater devices selected by `device`: a
+    `"bioretention"` cell (`area`, `ponding_depth`, `filtration_rate` and
+    the `removal` fraction of filtered load), a `"detention"` basin
+    (`capacity`, drawdown constant `k` in seconds and pollutant `decay` in
+    1/day) or an `"infiltration_trench"` (`area`, `depth`, `porosity` and
+    `infiltration_rate`). They capture the first flush of their inflow up to
+    their storage and bypass the rest. Pollutant loads are carried with the
+    flow between nodes and reduced only by treatment devices and by flow
+    leaving the network.
+
+    Reservoir nodes lose water from the pool to evaporation, given a
+    water surface `area` table over their `elevation` table and the name of
+    an `evaporation` series of depths per step, and to `seepage`, a constant
+    flow or a table over `elevation`. Both are reported as node losses.
+
+    Bridge nodes pass their inflow unchanged and report as their stage the
+    water level at the upstream face of the opening, from the arguments of
+    `hydraulics.bridge_backwater` (the channel's `bottom_width`,
+    `side_slope`, `n` and `slope`, the bridge's `opening_width`,
+    `low_chord`, `length`, `pier_width`, `pier_drag`, `abutment_slope`,
+    `contraction`, `expansion` and `method`) with the tailwater at normal
+    depth and the stage above the `bed` elevation below the bridge.
+
+    Pump nodes collect their inflow in a wet well (`elevation` and `storage`
+    tables, `initial_elevation`) and lift it to a discharge `outlet`
+    elevation with the `pumps` listed, each with a pump curve (`head` and
+    `flow` arrays), `start` and `stop` wet-well elevations, optional
+    `min_speed` and `full_speed` elevation for a variable-speed pump whose
+    speed rises linearly from the stop level, and `min_on` and `min_off`
+    times in seconds limiting how often it cycles. The node stage is the
+    wet-well level and its outflow the pumped flow.
+
+    Lateral weir nodes connect the reach to an off-channel storage area
+    over a levee or side weir (`crest`, `length`, optional weir
+    `coefficient`, default 1.7 m^0.5/s). The river level is read from a
+    `channel_discharge`/`channel_stage` rating and the storage area is a
+    level pool (`elevation` and `storage` tables, `initial_elevation`). Flow
+    crosses the weir in either direction, reduced for submergence, and is
+    reported in `exchange`; the node stage is the storage-area level.
+
+    Flood cell nodes attach interconnected storage areas, such as floodplain
+    compartments or interior drainage, to the reach. The river level is read
+    from a `channel_discharge`/`channel_stage` rating; each of the `cells`
+    has an `id`, `elevation` and `storage` tables, `initial_elevation` and an
+    optional `area` collecting the node's rainfall. `links` join two cells,
+    or a cell and `"river"`, by `from` and `to`: a `"weir"` link has a
+    `crest`, `length` and optional `coefficient`, a `"culvert"` link the
+    keys of `hydraulics.Culvert` and the `invert` of its `from` end, and
+    either may have a `flap_gate` stopping flow from `to` to `from`. Cell
+    levels and the river flow are solved implicitly each step; the net flow
+    returned to the river is reported in `exchange`, the node storage is the
+    total in the cells and it has no stage.
+
+    A reservoir drained by a culvert may give a `culvert` table instead of
+    `outflow`, with the keys of `hydraulics.Culvert` (`shape`, `inlet`, `n`,
+    `length`, `slope`, `diameter` or `span` and `rise`, `barrels`,
+    `entrance_loss`, `units`), the `invert` elevation of its inlet and an
+    optional constant `tailwater` depth; the outflow at each elevation is
+    read from the culvert's headwater–discharge curve.
+
+    Reservoir nodes may rate their outlet against the tailwater with
+    `tailwater` levels and a `tailwater_outflow` table holding one outflow
+    row per level over the reservoir `elevation` table. The tailwater is the
+    pool of the downstream reservoir, coupling closely spaced dams within
+    each step, or is read from a `tailwater_discharge`/`tailwater_stage`
+    rating of the channel below the dam.
+
+    A `series` list may be given instead of `nodes` to build a series system
+    such as a cascade of reservoirs and reaches (see `Network.series`).
+    """
+    def __init__(self, config: dict) -> None: ...
+    def downstream(self, node_id: str) -> list[str]:
+        """
+        Traces the nodes downstream of a node.
+
+        Args:
+            node_id (str): Node to trace from.
+
+        Returns:
+            list[str]: Ids of the nodes that water leaving `node_id` passes
+            through, including diverted flow, in network order.
+
+        Raises:
+            KeyError: If the network has no node `node_id`.
+        """
+    @staticmethod
+    def from_file(path: str | PathLike) -> Network:
+        """
+        Builds a network from a `.toml` or `.json` configuration file.
+
+        Args:
+            path (str | PathLike): Configuration file.
+
+        Returns:
+            Network: The network.
+        """
+    @staticmethod
+    def from_json(text: str) -> Network:
+        """
+        Builds a network from a JSON configuration written by `to_json`.
+
+        Args:
+            text (str): The JSON text.
+
+        Returns:
+            Network: The network.
+        """
+    def links(self) -> list[tuple[str, str, str]]:
+        """
+        Links between nodes, in network order.
+
+        Returns:
+            list[tuple[str, str, str]]: `(from, to, type)` triples, with type
+            `"downstream"` for a node draining to its downstream node and
+            `"diversion"` for the flow a bifurcation diverts.
+        """
+    def load_state(self, path: str | PathLike) -> int:
+        """
+        Restores a state saved by `save_state` or written as a `run`
+        checkpoint. The next run continues from it instead of starting from
+        the initial conditions.
+
+        Args:
+            path (str | PathLike): File to read.
+
+        Returns:
+            int: Number of steps of the simulation completed at the saved
+            state, counting those before any earlier restart.
+            To restart an interrupted run, pass it the inflows from this
+            index on.
+        """
+    @property
+    def node_ids(self) -> list[str]:
+        """list[str]: Node ids in configuration order."""
+    @property
+    def outlets(self) -> list[str]:
+        """
+        list[str]: Ids of the nodes without a downstream node, where water
+        leaves the network.
+        """
+    def report(self, result: NetworkResult, time_step: timedelta, start: Optional[datetime] = None, title: str = 'rustflow network simulation', parameters: Optional[dict] = None) -> dict:
+        """
+        Builds the run report of a simulation of this network.
+
+        The report holds the configuration and its SHA-256 hash, the run
+        parameters, the mass balance of every node, a table of peak flows,
+        stages and storages, and the warnings logged during the run.
+        Balances cover the period from the end of the first step to the end
+        of the last, with trapezoidal volumes in flow units times seconds;
+        `unaccounted` volume is rainfall, evaporation and streambed exchange
+        plus any continuity error.
+
+        Args:
+            result (NetworkResult): Result of `run` on this network.
+            time_step (timedelta): Time step the run used.
+            start (datetime, optional): Time of the first result, used to
+                time the peaks. Defaults to None, giving step numbers.
+            title (str, optional): Report title.
+            parameters (dict, optional): Further run parameters to record,
+                such as a scenario name or the source of the inflows.
+
+        Returns:
+            dict: The report, in the layout of the JSON written by
+            `write_report`.
+        """
+    def run(self, inflows: dict[str, list[float]], time_step: timedelta, progress_callback: Optional[Callable[[int, int], None]] = None, adaptive_tolerance: Optional[float] = None, max_refinement: int = 4, max_coarsening: int = 3, checkpoint: Optional[str | PathLike] = None, checkpoint_every: int = 1) -> NetworkResult:
+        """
+        Simulates the network.
+
+        Args:
+            inflows (dict[str, list[float]]): External inflow, rainfall and
+                demand series keyed by the names used in node `inflow`,
+                `rainfall` and `demand` fields.
+            time_step (timedelta): Interval between consecutive values.
+            progress_callback (Callable[[int, int], None], optional): Called
+                with the completed and total number of time steps as the run
+                proceeds, at most 20 times a second. Raising an exception
+                from it cancels the run. Ctrl-C also interrupts the run.
+            adaptive_tolerance (float, optional): Adapts the time step to the
+                flows when given: a step is repeated at half the size while
+                any node's inflow or outflow changes over it by more than
+                this share of its value, and doubled after a step changing
+                them by less than half of it. Results are still reported
+                every `time_step`, interpolated linearly within coarse steps.
+                Defaults to None, a fixed step.
+            max_refinement (int, optional): Finest adaptive step is
+                `time_step / 2**max_refinement`, but no shorter than
+                2 K x / `sub_reaches` of any Muskingum node not allowing a
+                negative c0. Defaults to 4.
+            max_coarsening (int, optional): Coarsest adaptive step is
+                `time_step * 2**max_coarsening`. Defaults to 3.
+            checkpoint (str | PathLike, optional): File the network state is
+                saved to as the run proceeds, replacing the previous
+                checkpoint, so that a run interrupted by a crash can be
+                restarted with `load_state`. Defaults to None.
+            checkpoint_every (int, optional): Steps between checkpoints.
+                Defaults to 1.
+
+        Demand nodes (`type = "demand"`, with a constant `demand` or the name
+        of a demand series, and an integer `priority`, lower first) are
+        allocated every step: each receives the largest withdrawal that
+        keeps all more senior demands fully served and all nodes at or above
+        their `min_flow`.
+
+        Elements start from their initial conditions, or continue from a
+        state restored with `load_state` or `set_state`, and keep their
+        state at the end of the run for `save_state`.
+
+        Returns:
+            NetworkResult: Inflow, outflow, storage, stage, withdrawal,
+            diversion, exchange, loss, captured flow and pollutant loads of
+            every node.
+        """
+    def save_state(self, path: str | PathLike) -> Any:
+        """
+        Saves the state of the network at the end of the last run as JSON,
+        for a later run to continue from (a hot start).
+
+        Args:
+            path (str | PathLike): File to write.
+        """
+    @staticmethod
+    def series(nodes: list[dict]) -> Network:
+        """
+        Builds a series system in which each node drains to the next.
+
+        Args:
+            nodes (list[dict]): Node configurations listed upstream to
+                downstream, without `downstream` fields; the last node is the
+                outlet. All nodes are simulated with the shared time step of
+                `run`.
+
+        Returns:
+            Network: The series system.
+        """
+    def set_state(self, state: dict) -> Any:
+        """
+        Restores a state returned by `state`, e.g. to run several forecasts
+        from the same initial conditions. The next run continues from it
+        instead of starting from the initial conditions.
+
+        Args:
+            state (dict): State of a network with the same nodes.
+        """
+    def state(self) -> dict:
+        """
+        State of the network at the end of the last run, or as last
+        restored: the state of every node's element (in SI units) and
+        tailwater level, keyed by node id, with the `time_step` (seconds)
+        and number of `steps` completed since the simulation started,
+        counting those before any restart.
+
+        Returns:
+            dict: The state, accepted by `set_state`.
+        """
+    def to_dict(self) -> dict:
+        """
+        Configuration that rebuilds this network: a `nodes` list holding
+        every node's fields, including its `downstream` link.
+
+        Returns:
+            dict: The configuration, accepted by `Network(config)`.
+        """
+    def to_dot(self, path: Optional[str | PathLike] = None) -> str:
+        """
+        Exports the network as a Graphviz DOT graph.
+
+        Nodes are labelled with their id and element type, shaped by type
+        and carry their configuration as a tooltip; diversions are dashed.
+        Render it with e.g. `dot -Tsvg network.dot -o network.svg`.
+
+        Args:
+            path (str | PathLike, optional): File to write the graph to.
+                Defaults to None, only returning it.
+
+        Returns:
+            str: The DOT text.
+        """
+    def to_json(self, indent: bool = True) -> str:
+        """
+        Serialises the network configuration as JSON.
+
+        The output depends only on the configuration, with keys in the
+        order they were given, so it can be kept under version control and
+        compared between runs. `Network.from_json` rebuilds the network.
+
+        Args:
+            indent (bool, optional): If True, indent nested values by two
+                spaces per level; otherwise write a single line. Defaults to
+                True.
+
+        Returns:
+            str: The JSON text.
+        """
+    def to_networkx(self) -> Any:
+        """
+        Exports the network as a NetworkX directed graph.
+
+        Each graph node is a network node id with its configuration fields
+        (other than `id` and `downstream`) as attributes, including `type`,
+        and its position in network order as `order`. Each edge has a
+        `type` attribute, `"downstream"` or `"diversion"`.
+
+        Returns:
+            networkx.DiGraph: The graph.
+
+        Raises:
+            ImportError: If NetworkX is not installed.
+        """
+    def upstream(self, node_id: str) -> list[str]:
+        """
+        Traces the nodes upstream of a node.
+
+        Args:
+            node_id (str): Node to trace from.
+
+        Returns:
+            list[str]: Ids of the nodes whose water reaches `node_id`
+            through downstream or diversion links, in network order.
+
+        Raises:
+            KeyError: If the network has no node `node_id`.
+        """
+    def write_report(self, path: str | PathLike, result: NetworkResult, time_step: timedelta, start: Optional[datetime] = None, title: str = 'rustflow network simulation', parameters: Optional[dict] = None) -> Any:
+        """
+        Writes the run report of a simulation of this network (see
+        `report`) as a self-contained HTML page if `path` ends in `.html`
+        or `.htm`, and as JSON otherwise.
+
+        Args:
+            path (str | PathLike): File to create.
+            result (NetworkResult): Result of `run` on this network.
+            time_step (timedelta): Time step the run used.
+            start (datetime, optional): Time of the first result. Defaults to
+                None.
+            title (str, optional): Report title.
+            parameters (dict, optional): Further run parameters to record.
+        """
+
+
+class NetworkResult:
+    """Simulated series of every node of a `Network`."""
+    @property
+    def captured(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Inflow captured by each treatment node rather
+        than bypassed (zero for other nodes), keyed by node id.
+        """
+    @property
+    def diversion(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Flow sent to the diversion node by each
+        bifurcation (zero for other nodes), keyed by node id.
+        """
+    @property
+    def exchange(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Flow from each groundwater node to its stream
+        through the streambed (negative when the stream loses water; zero for
+        other nodes), keyed by node id. Included in the node outflow.
+        """
+    @property
+    def inflow(self) -> dict[str, list[float]]:
+        """dict[str, list[float]]: Inflow to each node, keyed by node id."""
+    @property
+    def load(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Pollutant load leaving each node downstream
+        (mass per second), keyed by node id.
+        """
+    @property
+    def load_removed(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Pollutant load removed by each treatment node
+        (mass per second; zero for other nodes), keyed by node id.
+        """
+    @property
+    def loss(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Flow lost from the channel at each node (zero
+        for nodes without losses), keyed by node id.
+        """
+    @property
+    def node_ids(self) -> list[str]:
+        """list[str]: Node ids in network order."""
+    @property
+    def outflow(self) -> dict[str, list[float]]:
+        """dict[str, list[float]]: Outflow from each node, keyed by node id."""
+    def plot(self, nodes: Optional[str | list[str]] = None, times: Optional[list] = None, inflow: bool = True, precipitation: Optional[list[float]] = None, events: Optional[list[tuple]] = None, ax: Any = None, **kwargs: Any) -> Any:
+        """
+        Plots the hydrographs of network nodes with matplotlib (see
+        `rustflow.plot.hydrograph`).
+
+        Args:
+            nodes (str | list[str], optional): Node or nodes to plot.
+                Defaults to None, the last node.
+            times (list, optional): Time of each step, e.g. datetimes.
+                Defaults to None, plotting against the step number.
+            inflow (bool, optional): If True, overlay each node's inflow
+                (dashed) on its outflow. Defaults to True.
+            precipitation (list[float], optional): Precipitation at each step,
+                drawn as bars on an inverted axis at the top. Defaults to
+                None.
+            events (list[tuple], optional): `(start, end)` times to shade.
+                Defaults to None.
+            ax (matplotlib.axes.Axes, optional): Axes to draw on. Defaults to
+                None, creating a new figure.
+            **kwargs: Further arguments of `rustflow.plot.hydrograph`, e.g.
+                `title` or `flow_label`.
+
+        Returns:
+            matplotlib.axes.Axes: The flow axes.
+
+        Raises:
+            ImportError: If matplotlib is not installed.
+            KeyError: If a node is not in the network.
+        """
+    @property
+    def stage(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Water surface elevation of each node element
+        (`nan` where not defined), keyed by node id.
+        """
+    @property
+    def storage(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Storage of each node element (flow units ×
+        seconds), keyed by node id.
+        """
+    @property
+    def tailwater(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Tailwater level below each reservoir with a
+        tailwater-dependent outlet (`nan` for other nodes), keyed by node id.
+        """
+    def to_arrow(self, times: Optional[list[datetime]] = None, variable: str = 'outflow') -> Any:
+        """
+        Builds a `pyarrow.Table` of one recorded series of every node.
+
+        Args:
+            times (list[datetime], optional): Timestamp of each step, written
+                as a `time` column. Defaults to None, no time column.
+            variable (str, optional): Series to export, one of the result
+                attributes such as `"outflow"`, `"storage"` or `"stage"`.
+                Defaults to `"outflow"`.
+
+        Returns:
+            pyarrow.Table: An optional `time` column followed by one float64
+            column per node, named by node id.
+
+        Raises:
+            ImportError: If `pyarrow` is not installed.
+            ValueError: If `variable` is not a recorded series, or `times`
+                does not have one timestamp per step.
+        """
+    def to_netcdf(self, path: str | PathLike, times: list[datetime], units: Literal["si", "us"] = ..., title: str = 'rustflow network simulation') -> Any:
+        """
+        Writes the results to a CF-1.8 compliant NetCDF file.
+
+        Args:
+            path (str | PathLike): File to create.
+            times (list[datetime]): Timestamp of each simulated step.
+            units (Literal["si", "us"], optional): Unit system of the flows and
+                stages, used for the `units` attributes. Defaults to `"si"`.
+            title (str, optional): Dataset title.
+
+        Raises:
+            DataError: If the result has no steps, or `times` does not have
+                one timestamp per step.
+        """
+    @property
+    def warnings(self) -> list[str]:
+        """
+        list[str]: Warnings logged during the run, such as unstable routing
+        coefficients or unconverged iterations.
+        """
+    @property
+    def withdrawal(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Flow withdrawn by each demand node (zero for
+        other nodes), keyed by node id.
+        """
@@ -0,0 +1,25 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def areal_average(series: dict[str, list[float]], gauges: dict[str, tuple[float, float]], basin: list[tuple[float, float]], method: Literal['thiessen', 'idw', 'weights'] = 'thiessen', power: float = 2.0, weights: Optional[dict[str, float]] = None, resolution: int = 200) -> Any:
+    ...
+
+
+def areal_reduction(depths: list[float], durations: list[timedelta], area: float, method: Literal['fsr', 'tp29'] = 'fsr', table: Optional[dict[str, Any]] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def areal_reduction_factors(durations: list[timedelta], area: float, method: Literal['fsr', 'tp29'] = 'fsr', table: Optional[dict[str, Any]] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def depth_area_duration(rainfall: list[list[float]], time_step: timedelta, cell_area: float, durations: list[timedelta], areas: list[float]) -> Any:
+    ...
+
+
+def grid_to_catchments(values: Any, x: list[float], y: list[float], catchments: Mapping[str, Any], geographic: bool = False) -> Any:
+    ...
@@ -0,0 +1,17 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def advection_dispersion(discharge: list[float], time_step: timedelta, length: float, bottom_width: float, side_slope: float, n: float, slope: float, dispersion: float, decay: float = 0.0, upstream_concentration: Optional[list[float]] = None, initial_concentration: float = 0.0, spill_mass: Optional[float] = None, spill_position: float = 0.0, spill_step: int = 0, stations: Optional[Sequence[float]] = None, cells: int = 100, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def pollutant_load(runoff: list[float], time_step: timedelta, area: float, washoff: Literal['exponential', 'rating_curve', 'emc'] = 'exponential', coefficient: float = 0.0, exponent: float = 1.0, buildup_max: Optional[float] = None, buildup_rate: float = 0.0, initial_buildup: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def stream_temperature(discharge: list[float], inflow_temperature: list[float], air_temperature: list[float], relative_humidity: list[float], wind_speed: list[float], solar_radiation: list[float], time_step: timedelta, length: float, bottom_width: float, side_slope: float, n: float, slope: float, cloud_cover: Optional[list[float]] = None, shade: float = 0.0, method: Literal['energy_balance', 'equilibrium'] = 'energy_balance', initial_temperature: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
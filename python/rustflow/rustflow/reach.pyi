@@ -0,0 +1,217 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class MuskingumReach:
+    """
+    Muskingum reach routing a long record block by block. The routing state
+    carries over between blocks, so the outflow is the same as routing the
+    whole record at once with `muskingum_routing`.
+
+    Args:
+        k (timedelta): Storage time constant of the whole reach.
+        x (float): Weighting factor, typically 0.0-0.5.
+        time_step (timedelta): Interval between consecutive values.
+        sub_reaches (int, optional): Number of sub-reaches. Defaults to 1.
+        initial_outflow (float, optional): Outflow at the first step.
+            Defaults to the first inflow.
+        allow_negative_c0 (bool, optional): Accept a time step shorter than
+            `2 K x / sub_reaches`. Defaults to False.
+    """
+    def __init__(self, k: timedelta, x: float, time_step: timedelta, sub_reaches: int = 1, initial_outflow: Optional[float] = None, allow_negative_c0: bool = False) -> None: ...
+    @property
+    def outflows(self) -> list[float]:
+        """
+        list[float]: Outflow of each sub-reach at the end of the last block;
+        empty before the first.
+        """
+    def reset(self) -> Any:
+        """Clears the routing state so the next block starts a new record."""
+    def route(self, inflow: Sequence[float]) -> list[float]:
+        """
+        Routes the next block of inflow.
+
+        Args:
+            inflow (Sequence[float]): Consecutive inflow values.
+
+        Returns:
+            list[float]: Outflow at each step of the block.
+        """
+    @property
+    def storage(self) -> float:
+        """
+        float: Reach storage at the end of the last block, in flow units ×
+        seconds.
+        """
+
+
+class RoutingResult:
+    """Routed hydrograph with its inputs, parameters and diagnostics."""
+    def __len__(self) -> int:
+        ...
+    @property
+    def diagnostics(self) -> dict[str, Any]:
+        """
+        dict[str, Any]: Peak attenuation, peak lag (timedelta),
+        outflow-to-inflow volume ratio and, where storage is recorded, the
+        relative continuity error.
+        """
+    @property
+    def inflow(self) -> list[float]:
+        """list[float]: Inflow at each step."""
+    @property
+    def method(self) -> str:
+        """str: Routing method, `"muskingum"` or `"kinematic_wave"`."""
+    @property
+    def outflow(self) -> list[float]:
+        """list[float]: Routed outflow at each step."""
+    @property
+    def parameters(self) -> dict[str, Any]:
+        """
+        dict[str, Any]: Parameters the reach was routed with, in the units
+        they were given; durations are timedeltas.
+        """
+    def peak(self) -> Optional[float]:
+        """
+        Largest outflow.
+
+        Returns:
+            Optional[float]: The peak, or `None` for an empty result.
+        """
+    def peak_time(self) -> Optional[Union[datetime, timedelta]]:
+        """
+        Time of the largest outflow.
+
+        Returns:
+            Optional[Union[datetime, timedelta]]: A datetime when a start was
+            given, otherwise the offset from the first value; `None` for an
+            empty result.
+        """
+    def plot(self, precipitation: Optional[list[float]] = None, events: Optional[list[tuple]] = None, ax: Any = None, **kwargs: Any) -> Any:
+        """
+        Plots the inflow and outflow hydrographs with matplotlib (see
+        `rustflow.plot.hydrograph`).
+
+        Args:
+            precipitation (list[float], optional): Precipitation at each step,
+                drawn as bars on an inverted axis at the top. Defaults to
+                None.
+            events (list[tuple], optional): `(start, end)` times to shade.
+                Defaults to None.
+            ax (matplotlib.axes.Axes, optional): Axes to draw on. Defaults to
+                None, creating a new figure.
+            **kwargs: Further arguments of `rustflow.plot.hydrograph`, e.g.
+                `title` or `flow_label`.
+
+        Returns:
+            matplotlib.axes.Axes: The flow axes.
+
+        Raises:
+            ImportError: If matplotlib is not installed.
+        """
+    @property
+    def states(self) -> dict[str, list[float]]:
+        """
+        dict[str, list[float]]: Series recorded at each step besides the
+        outflow, e.g. `storage`.
+        """
+    @property
+    def sub_reach_outflow(self) -> list[list[float]]:
+        """
+        list[list[float]]: Outflow of each sub-reach, `[sub_reach][step]`;
+        empty unless requested with `intermediate=True`.
+        """
+    @property
+    def time_step(self) -> timedelta:
+        """timedelta: Interval between consecutive values."""
+    @property
+    def times(self) -> list[datetime] | list[timedelta]:
+        """
+        list[datetime] | list[timedelta]: Time of each value: datetimes when
+        a start was given, otherwise offsets from the first value.
+        """
+    def to_arrow(self) -> Any:
+        """
+        Builds a `pyarrow.Table` of the routed series.
+
+        Returns:
+            pyarrow.Table: `time` (timestamps, or offsets from the first
+            value without a start time), `inflow`, `outflow` and one column
+            per recorded state, such as `storage`.
+
+        Raises:
+            ImportError: If `pyarrow` is not installed.
+        """
+    def to_dict(self) -> dict[str, Any]:
+        """
+        Plain dict of times, inflow, outflow, states, parameters and
+        diagnostics.
+
+        Returns:
+            dict[str, Any]: The result as built-in Python objects.
+        """
+    def to_numpy(self) -> Any:
+        """
+        Outflow as a NumPy array.
+
+        Returns:
+            numpy.ndarray: The outflow series.
+        """
+    def volume(self) -> float:
+        """
+        Outflow volume: the sum of the outflows times the time step.
+
+        Returns:
+            float: The volume, in flow units × seconds.
+        """
+
+
+def backwater_profile(discharge: list[float], downstream_stage: list[float], length: float, bottom_width: float, side_slope: float, n: float, slope: float, segments: int = 20, outlet_bed: float = 0.0, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def dynamic_wave(inflow: list[float], downstream_stage: list[float], time_step: timedelta, length: float, bottom_width: float, side_slope: float, n: float, slope: float, segments: int = 20, theta: float = 0.6, outlet_bed: float = 0.0, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def kinematic_wave_routing(inflow: list[float], time_step: timedelta, length: float, bottom_width: float, side_slope: float, n: float, slope: float, segments: int = 10, units: Literal['si', 'us'] = ..., states: bool = False, result: bool = False, start: Optional[datetime] = None) -> Any:
+    ...
+
+
+def muskingum_cunge(reference_flow: float, length: float, bottom_width: float, side_slope: float, n: float, slope: float, units: Literal['si', 'us'] = ...) -> Any:
+    """
+    Muskingum `k` and `x` of a prismatic reach by the Muskingum–Cunge
+    method, with the geometry and flow in `units`.
+    """
+
+
+def muskingum_discretization(k: timedelta, x: float, time_step: timedelta, max_divisions: int = 100) -> Any:
+    """
+    Sub-reaches and sub-steps that bring the Courant number of a Muskingum
+    reach closest to one, with the resulting coefficients.
+    """
+
+
+def muskingum_routing(inflow: list[float], k: timedelta, x: float, time_step: timedelta, sub_reaches: Union[int, Literal['auto'], None] = 1, initial_outflow: Optional[float] = None, states: bool = False, result: bool = False, start: Optional[datetime] = None, intermediate: bool = False, allow_negative_c0: bool = False, sub_steps: int = 1) -> Any:
+    ...
+
+
+def muskingum_routing_many(inflows: Any, k: Any, x: Any, time_step: Any, sub_reaches: Any, initial_outflow: Any, allow_negative_c0: Any = None) -> Any:
+    """
+    Routes several inflow series, each through its own Muskingum reach, in
+    parallel. If every series is a `float32` array, the outflows are
+    `float32` arrays; otherwise they are lists of floats. `allow_negative_c0`
+    holds one flag per reach and defaults to rejecting a negative c0 for
+    every reach.
+    """
+
+
+def tide_stage(steps: int, time_step: timedelta, constituents: list[tuple[str, float, float]], mean_level: float = 0.0, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def transmission_loss(flow: list[float], time_step: timedelta, method: Literal['seepage', 'lane', 'fraction'], rate: Optional[float] = None, width: Optional[float] = None, length: Optional[float] = None, fraction: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
@@ -0,0 +1,9 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def transfer_parameters(target_location: Any, target_attributes: Any, donor_locations: Any, donor_attributes: Any, donor_parameters: Any, donor_scores: Any = None, method: Literal['spatial_proximity', 'physical_similarity'] = 'spatial_proximity', donors: Mapping[str, Mapping[str, Any]] = 5, power: float = 2.0, geographic: bool = False, attribute_weights: Optional[Mapping[str, float]] = None, min_score: Optional[float] = None, bounds: tuple[float, float] = ...) -> Any:
+    ...
@@ -0,0 +1,33 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def dam_breach(inflow: list[float], time_step: timedelta, elevation: list[float], storage: list[float], outflow: list[float], initial_elevation: float, crest_elevation: float, bottom_elevation: float, mode: Literal['overtopping', 'piping'] = 'overtopping', piping_elevation: Optional[float] = None, method: Literal['froehlich', 'macdonald', 'user'] = 'froehlich', crest_width: Optional[float] = None, upstream_slope: Optional[float] = None, downstream_slope: Optional[float] = None, bottom_width: Optional[float] = None, side_slope: Optional[float] = None, formation_time: Optional[timedelta] = None, trigger_elevation: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def hydropower(inflow: list[float], time_step: timedelta, elevation: list[float], storage: list[float], outflow: list[float], initial_elevation: float, tailwater_discharge: list[float], tailwater_elevation: list[float], turbine_capacity: float, efficiency: float = 0.9, efficiency_curve: Optional[tuple[list[float], list[float]]] = None, power_capacity: Optional[float] = None, min_head: float = 0.0, loss_coefficient: float = 0.0, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def level_pool_routing(inflow: list[float], elevation: list[float], storage: list[float], outflow: list[float], time_step: timedelta, initial_elevation: float, area: Optional[list[float]] = None, evaporation: Optional[list[float]] = None, seepage: Optional[Union[float, list[float]]] = None) -> Any:
+    ...
+
+
+def optimize_drawdown(inflow: list[list[float]], time_step: timedelta, elevation: list[float], storage: list[float], initial_elevation: float, max_release: float, min_release: float = 0.0, max_ramp: Optional[float] = None, initial_release: Optional[float] = None, min_elevation: Optional[float] = None, max_elevation: Optional[float] = None, target_elevation: Optional[float] = None, channel_capacity: Optional[float] = None, local_inflow: Optional[list[list[float]]] = None, objective: Literal['peak_pool', 'exceedance'] = 'peak_pool', release_levels: int = 51, storage_levels: int = 101, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def sequent_peak(inflow: Sequence[float], demand: float, time_step: timedelta, demand_pattern: Optional[Sequence[float]] = None) -> Any:
+    ...
+
+
+def simulate_yield(inflow: Sequence[float], capacity: float, demand: float, time_step: timedelta, initial_storage: Optional[float] = None, demand_pattern: Optional[Sequence[float]] = None, evaporation: Optional[Sequence[float]] = None, storage_area: Optional[tuple[Sequence[float], Sequence[float]]] = None) -> Any:
+    ...
+
+
+def storage_yield_reliability(traces: Sequence[Sequence[float]], capacities: Sequence[float], reliability: float, time_step: timedelta, demand_pattern: Optional[Sequence[float]] = None, evaporation: Optional[Sequence[float]] = None, storage_area: Optional[tuple[Sequence[float], Sequence[float]]] = None) -> Any:
+    ...
@@ -0,0 +1,66 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class NonlinearReservoir:
+    """
+    SWMM-style nonlinear reservoir run over a long rainfall record block by
+    block. The ponded depth carries over between blocks, so the runoff is
+    the same as from `nonlinear_reservoir_runoff` over the whole record.
+
+    Args:
+        time_step (timedelta): Interval between consecutive values.
+        area (float): Subcatchment area (ha or acres).
+        width (float): Overland flow width (m or ft).
+        slope (float): Surface slope (m/m).
+        n (float): Manning's roughness for overland flow.
+        depression_storage (float, optional): Depression storage (mm or
+            in). Defaults to 0.0.
+        loss_rate (float, optional): Constant loss rate (mm/h or in/h).
+            Defaults to 0.0.
+        units (Literal["si", "us"], optional): Unit system. Defaults to
+            `"si"`.
+    """
+    def __init__(self, time_step: timedelta, area: float, width: float, slope: float, n: float, depression_storage: float = 0.0, loss_rate: float = 0.0, units: Literal["si", "us"] = ...) -> None: ...
+    @property
+    def depth(self) -> float:
+        """float: Ponded depth at the end of the last block (mm or in)."""
+    def reset(self) -> Any:
+        """Empties the surface so the next block starts a new record."""
+    def runoff(self, rainfall: Sequence[float]) -> list[float]:
+        """
+        Runoff for the next block of rainfall.
+
+        Args:
+            rainfall (Sequence[float]): Rainfall intensity (mm/h or in/h).
+
+        Returns:
+            list[float]: Runoff at each step (m³/s or cfs).
+        """
+
+
+def baseflow(direct_runoff: list[float], time_step: timedelta, method: Literal['constant', 'monthly_constant', 'recession'] = 'constant', flow: float = 0.0, monthly: Optional[list[float]] = None, start: Optional[datetime] = None, recession_constant: Optional[float] = None, ratio_to_peak: Optional[float] = None, threshold_flow: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def clark_unit_hydrograph(time_area: list[float], storage_coefficient: timedelta, time_step: timedelta, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def nonlinear_reservoir_runoff(rainfall: list[float], time_step: timedelta, area: float, width: float, slope: float, n: float, depression_storage: float = 0.0, loss_rate: float = 0.0, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def rainfall_excess(rainfall: list[float], time_step: timedelta, method: Literal['initial_constant', 'curve_number', 'api', 'deficit_constant'], initial_loss: float = 0.0, loss_rate: float = 0.0, curve_number: Optional[float] = None, initial_abstraction_ratio: float = 0.2, initial_index: float = 0.0, decay: float = 0.9, capacity: Optional[float] = None, exponent: float = 1.0, max_deficit: Optional[float] = None, initial_deficit: float = 0.0, recovery_rate: float = 0.0, impervious_fraction: float = 0.0, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def refh(rainfall: list[float], time_step: timedelta, area: float, time_to_peak: timedelta, max_moisture: float, baseflow_lag: timedelta, baseflow_recharge: float, initial_moisture: float = 0.0, initial_baseflow: float = 0.0, peak: float = ..., kink: float = ..., units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def refh_parameters(area: float, propwet: float, dplbar: float, dpsbar: float, bfihost: float, urbext: float = 0.0) -> Any:
+    ...
@@ -0,0 +1,9 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def run(base: dict[str, Any], inflows: dict[str, list[float]], time_step: timedelta, scenarios: list[dict[str, Any]], nodes: Optional[list[str]] = None, include_base: bool = True) -> Any:
+    ...
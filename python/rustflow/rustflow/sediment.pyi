@@ -0,0 +1,13 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def route_sediment(discharge: list[float], load: list[float], time_step: timedelta, bottom_width: float, side_slope: float, n: float, slope: float, d50: float, formula: Literal['engelund_hansen', 'yang', 'meyer_peter_muller'] = 'engelund_hansen', specific_gravity: float = 2.65, bed_storage: Optional[float] = None, units: Literal['si', 'us'] = ...) -> Any:
+    ...
+
+
+def transport_capacity(discharge: list[float], bottom_width: float, side_slope: float, n: float, slope: float, d50: float, formula: Literal['engelund_hansen', 'yang', 'meyer_peter_muller'] = 'engelund_hansen', specific_gravity: float = 2.65, units: Literal['si', 'us'] = ...) -> Any:
+    ...
@@ -0,0 +1,21 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+def disaggregate(synthetic: Sequence[float], fragments: Sequence[Sequence[float]], period: int = 12, start: int = 0) -> Any:
+    ...
+
+
+def fit_markov_rainfall(observed: Sequence[float], threshold: float = 0.0, period: int = 0, seasons: int = 12) -> Any:
+    ...
+
+
+def rainfall_generator(observed: Sequence[float], n_steps: int, threshold: float = 0.0, period: int = 0, seasons: int = 12, realizations: int = 1, start: int = 0, seed: Optional[int] = None) -> Any:
+    ...
+
+
+def streamflow_generator(observed: Sequence[float], n_steps: int, method: Literal['ar1', 'arma', 'index_sequential'] = 'ar1', period: int = 12, realizations: Optional[int] = None, log: bool = True, start: int = 0, seed: Optional[int] = None) -> Any:
+    ...
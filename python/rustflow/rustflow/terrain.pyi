@@ -0,0 +1,273 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class FlowGrid:
+    """
+    D8 flow directions of a DEM, with flow accumulation, stream and
+    catchment delineation.
+
+    Rows run north to south and columns west to east, as in a GeoTIFF read
+    with rasterio. Each cell drains to the neighbour of steepest descent.
+    Depressions and flats are filled first by priority flood, raising cells
+    by the smallest representable amount so that every cell drains to the
+    edge of the DEM. NaN cells are outside the domain; cells next to them
+    or on the grid edge with no lower neighbour are outlets.
+
+    Args:
+        dem (numpy.ndarray): 2-D float32 or float64 elevations in a
+            projected coordinate system.
+        cell_size (float | tuple[float, float]): Cell size, or its width and
+            height `(dx, dy)`, in map units.
+        origin (tuple[float, float], optional): Map coordinates of the
+            north-west corner of the grid. Defaults to `(0, 0)`.
+        fill (bool, optional): Whether to fill depressions. Without filling,
+            cells with no lower neighbour are sinks. Defaults to True.
+    """
+    def __init__(self, dem: Any, cell_size: float | tuple[float, float], origin: tuple[float, float] = ..., fill: bool = True) -> None: ...
+    def accumulation(self, weights: Any = None, area: bool = False) -> Any:
+        """
+        Computes flow accumulation.
+
+        Args:
+            weights (numpy.ndarray, optional): 2-D weight of each cell, such
+                as runoff depth. NaN weights count as zero. By default each
+                cell weighs one.
+            area (bool, optional): Whether to multiply the result by the cell
+                area, giving contributing area (or, with depth weights,
+                volume). Defaults to False.
+
+        Returns:
+            numpy.ndarray: The number of cells (or summed weight) draining
+                through each cell, including itself; NaN outside the domain.
+        """
+    def basin_characteristics(self, outlet: tuple[int, int], snap: int = 0) -> dict:
+        """
+        Computes the longest flow path and basin characteristics of the
+        catchment of an outlet.
+
+        The results are the usual inputs to Snyder and NRCS lag formulas and
+        regional regression equations. Lengths and areas are in map units,
+        elevations in DEM units, and slopes are ratios.
+
+        Args:
+            outlet (tuple[int, int]): Row and column of the outlet.
+            snap (int, optional): Radius in cells within which the outlet is
+                moved to the cell of largest flow accumulation. Defaults to 0.
+
+        Returns:
+            dict: `outlet` (snapped row and column), `cells`, `area`,
+                `perimeter`, `centroid` (map coordinates), `flow_path` (cells
+                from the most remote to the outlet), `flow_path_length` (`L`),
+                `centroid_length` (`Lc`, along the flow path from the outlet
+                to the point nearest the centroid), `flow_path_slope` (total
+                drop over `L`), `slope_10_85` (between 10 % and 85 % of `L`
+                from the outlet), `mean_slope` (mean cell slope by Horn's
+                method), `min_elevation`, `mean_elevation`, `max_elevation`,
+                `relief`, `relief_ratio`, `hypsometric_integral`,
+                `form_factor`, `elongation_ratio`, `circularity_ratio` and
+                `compactness` (Gravelius coefficient).
+
+        Raises:
+            ValueError: If the outlet is outside the domain or drains only
+                itself.
+        """
+    def catchments(self, pour_points: list[tuple[int, int]], snap: int = 0) -> dict:
+        """
+        Delineates the catchments of pour points.
+
+        Each cell belongs to the first pour point it drains through, so a
+        pour point upstream of another splits off its own sub-catchment.
+
+        Args:
+            pour_points (list[tuple[int, int]]): Row and column of each
+                outlet.
+            snap (int, optional): Radius in cells within which each pour point
+                is moved to the cell of largest flow accumulation. Defaults to
+                0.
+
+        Returns:
+            dict: `labels` (int32 array of the index of the pour point each
+                cell drains to, -1 for none), the snapped `pour_points` and
+                the `area` of each catchment in squared map units.
+
+        Raises:
+            ValueError: If a pour point is outside the domain.
+        """
+    def cell_at(self, x: float, y: float) -> tuple[int, int] | None:
+        """
+        The cell containing a map point.
+
+        Args:
+            x (float): Easting.
+            y (float): Northing.
+
+        Returns:
+            tuple[int, int] | None: Row and column, or None off the grid.
+        """
+    @property
+    def cell_size(self) -> tuple[float, float]:
+        """tuple[float, float]: Cell width and height."""
+    def centre(self, row: int, col: int) -> tuple[float, float]:
+        """
+        Map coordinates of the centre of a cell.
+
+        Args:
+            row (int): Row.
+            col (int): Column.
+
+        Returns:
+            tuple[float, float]: Easting and northing.
+        """
+    @property
+    def directions(self) -> Any:
+        """
+        numpy.ndarray: D8 direction codes (uint8): 1 east, 2 south-east,
+        4 south, 8 south-west, 16 west, 32 north-west, 64 north, 128
+        north-east, and 0 for outlets and cells outside the domain.
+        """
+    @property
+    def elevation(self) -> Any:
+        """
+        numpy.ndarray: Elevations after filling (float64), NaN outside the
+        domain.
+        """
+    @property
+    def origin(self) -> tuple[float, float]:
+        """tuple[float, float]: Map coordinates of the north-west corner."""
+    def route_runoff(self, runoff: Any, time_step: timedelta, outlets: list[tuple[int, int]], channel_area: float, method: str = 'linear_reservoir', hillslope_velocity: float = 0.1, channel_velocity: float = 1.0, hillslope_n: float = 0.2, channel_n: float = 0.04, channel_width: float = 5.0, min_slope: float = 0.0001, units: str = ..., progress_callback: Optional[Callable[[int, int], None]] = None) -> dict:
+        """
+        Routes gridded runoff from cell to cell along the flow directions to
+        outlet hydrographs.
+
+        Each cell is a store receiving the runoff falling on it and the
+        outflow of the cells draining to it. Cells whose contributing area
+        reaches `channel_area` are channel cells, the rest hillslope cells.
+        With `"linear_reservoir"`, each cell releases its storage divided by
+        its flow length over the hillslope or channel velocity. With
+        `"kinematic_wave"`, it releases the Manning flow of the depth of its
+        storage spread over its flow length, as sheet flow across the cell
+        on hillslopes and in a wide channel of `channel_width` in channel
+        cells, on the slope of the filled DEM to the downstream cell.
+
+        Args:
+            runoff (numpy.ndarray): 3-D float32 or float64 runoff depth of
+                each step and cell, shaped `(steps, rows, cols)`, in mm (SI)
+                or inches (US). NaN counts as no runoff.
+            time_step (timedelta): Interval between consecutive steps.
+            outlets (list[tuple[int, int]]): Row and column of the cells
+                whose outflow is returned.
+            channel_area (float): Contributing area in squared map units from
+                which cells are channels.
+            method (str, optional): `"linear_reservoir"` or
+                `"kinematic_wave"`. Defaults to `"linear_reservoir"`.
+            hillslope_velocity (float, optional): Linear reservoir velocity
+                of hillslope cells. Defaults to 0.1.
+            channel_velocity (float, optional): Linear reservoir velocity of
+                channel cells. Defaults to 1.0.
+            hillslope_n (float, optional): Manning's roughness of hillslope
+                sheet flow. Defaults to 0.2.
+            channel_n (float, optional): Manning's roughness of channels.
+                Defaults to 0.04.
+            channel_width (float, optional): Width of channels. Defaults to
+                5.0.
+            min_slope (float, optional): Slope used where the filled DEM is
+                flatter. Defaults to 1e-4.
+            units (str, optional): `"si"` for map units, widths and velocities
+                in metres and flows in m³/s, or `"us"` for feet and cfs.
+                Defaults to `"si"`.
+            progress_callback (Callable[[int, int], None], optional): Called
+                with the completed and total number of time steps as the run
+                proceeds, at most 20 times a second. Raising an exception
+                from it cancels the run. Ctrl-C also interrupts the run.
+
+        Returns:
+            dict: `outlets` (mean outflow of each outlet over each step),
+                `peak_flow` (array of the largest outflow of each cell, NaN
+                outside the domain), `runoff_volume` (volume of runoff on the
+                domain) and `storage` (volume left in the cells at the end),
+                in m³ or ft³.
+
+        Raises:
+            ValueError: If the runoff does not match the grid, an outlet is
+                outside the domain, or a parameter is not positive.
+        """
+    @property
+    def shape(self) -> tuple[int, int]:
+        """tuple[int, int]: Rows and columns of the grid."""
+    def streams(self, threshold: float) -> dict:
+        """
+        Delineates the stream network.
+
+        Cells whose contributing area reaches `threshold` are streams. They
+        are ordered by Strahler's scheme and split into links at
+        confluences.
+
+        Args:
+            threshold (float): Contributing area that starts a stream, in
+                squared map units.
+
+        Returns:
+            dict: `links` (int32 array of the link index of each cell, -1 off
+                the streams), `order` (uint8 array of Strahler orders, 0 off
+                the streams), `segments` (one dict per link with its `cells`
+                as `(row, col)` from upstream to downstream, `downstream`
+                link index or None, `order`, `length`, draining `area` and
+                `slope`) and `geojson`, a FeatureCollection of the links with
+                `from_node`/`to_node` attributes for
+                `rustflow.io.read_geojson_network`.
+        """
+    def travel_time(self, outlet: tuple[int, int], time_step: timedelta, channel_area: float, hillslope_n: float = 0.1, channel_n: float = 0.04, hillslope_radius: float = 0.03, channel_radius: float = 1.0, roughness: Any = None, min_slope: float = 0.0001, min_velocity: float = 0.01, max_velocity: float = 5.0, snap: int = 0, units: str = ...) -> dict:
+        """
+        Computes the travel time from each cell to an outlet and the
+        time–area histogram of its catchment.
+
+        Velocity follows Manning's equation in each cell, on the slope of
+        the filled DEM to the downstream cell, with a shallow hydraulic
+        radius on hillslopes and a deeper one in cells whose contributing
+        area reaches `channel_area`. Travel times add flow length over
+        velocity along the flow path. The histogram is the translation
+        input of `rustflow.runoff.clark_unit_hydrograph` and of
+        `rustflow.design.pmf(time_area=...)`.
+
+        Args:
+            outlet (tuple[int, int]): Row and column of the outlet.
+            time_step (timedelta): Bin width of the time–area histogram.
+            channel_area (float): Contributing area in squared map units from
+                which cells are channels.
+            hillslope_n (float, optional): Manning's roughness of hillslope
+                flow. Defaults to 0.1.
+            channel_n (float, optional): Manning's roughness of channels.
+                Defaults to 0.04.
+            hillslope_radius (float, optional): Hydraulic radius of
+                hillslope flow. Defaults to 0.03.
+            channel_radius (float, optional): Hydraulic radius of channel
+                flow. Defaults to 1.0.
+            roughness (numpy.ndarray, optional): 2-D Manning's roughness of
+                each cell, for example from land cover, used instead of
+                `hillslope_n` and `channel_n` where finite and positive.
+            min_slope (float, optional): Slope used where the filled DEM is
+                flatter. Defaults to 1e-4.
+            min_velocity (float, optional): Lower bound on the velocity.
+                Defaults to 0.01.
+            max_velocity (float, optional): Upper bound on the velocity.
+                Defaults to 5.0.
+            snap (int, optional): Radius in cells within which the outlet is
+                moved to the cell of largest flow accumulation. Defaults to 0.
+            units (str, optional): `"si"` for map units, radii and
+                velocities in metres and areas in km², or `"us"` for feet and
+                mi². Defaults to `"si"`.
+
+        Returns:
+            dict: `outlet` (snapped row and column), `travel_time` (array of
+                hours to the outlet, NaN outside the catchment), `velocity`
+                (array), `time_of_concentration` (timedelta) and `time_area`
+                (area reaching the outlet within each time step).
+
+        Raises:
+            ValueError: If the outlet is outside the domain, the roughness
+                does not match the grid, or a parameter is not positive.
+        """
@@ -0,0 +1,138 @@
+# Generated by python/generate_stubs.py from the compiled module; do not edit.
+from collections.abc import Callable, Mapping, Sequence
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Literal, Optional, Union
+
+
+class MovingMean:
+    """
+    Trailing moving mean of a record fed block by block. The window carries
+    over between blocks, so the output does not depend on how the record is
+    split. The first `window - 1` values of the record, and windows holding
+    a NaN, give NaN.
+
+    Args:
+        window (int): Number of steps averaged, at least 1.
+    """
+    def __init__(self, window: int) -> None: ...
+    def process(self, block: Sequence[float]) -> list[float]:
+        """
+        Moving means ending at each value of the next block.
+
+        Args:
+            block (Sequence[float]): Consecutive values of the record.
+
+        Returns:
+            list[float]: One mean per value of `block`.
+        """
+    @property
+    def window(self) -> int:
+        """int: Number of steps averaged."""
+
+
+class Resampler:
+    """
+    Aggregates a record fed block by block to a coarser step, combining
+    every `factor` consecutive values into one. Values left over at the end
+    of a block wait for the next; `finish` aggregates the final incomplete
+    group.
+
+    Args:
+        factor (int): Number of values per aggregated value, e.g. 60 for
+            minutes to hours.
+        how (Literal["mean", "sum", "min", "max"], optional): How values are
+            combined. Defaults to `"mean"`.
+    """
+    def __init__(self, factor: int, how: Literal["mean", "sum", "min", "max"] = 'mean') -> None: ...
+    @property
+    def factor(self) -> int:
+        """int: Number of values per aggregated value."""
+    def finish(self) -> Optional[float]:
+        """
+        Aggregate of the incomplete group at the end of the record.
+
+        Returns:
+            Optional[float]: The aggregate, or None if no values are pending.
+        """
+    @property
+    def how(self) -> Literal["mean", "sum", "min", "max"]:
+        """Literal["mean", "sum", "min", "max"]: How values are combined."""
+    def process(self, block: Sequence[float]) -> list[float]:
+        """
+        Aggregates of the groups completed by the next block.
+
+        Args:
+            block (Sequence[float]): Consecutive values of the record.
+
+        Returns:
+            list[float]: One value per completed group; NaN for a group
+                holding a NaN.
+        """
+
+
+class RunningStats:
+    """
+    Count, mean, variance and extremes of a record fed block by block.
+    NaN values are counted as missing.
+    """
+    def __init__(self) -> None: ...
+    @property
+    def count(self) -> int:
+        """int: Number of values seen, excluding NaN."""
+    @property
+    def max(self) -> float:
+        """float: Largest value seen; NaN before the first."""
+    @property
+    def mean(self) -> float:
+        """float: Mean of the values seen; NaN before the first."""
+    @property
+    def min(self) -> float:
+        """float: Smallest value seen; NaN before the first."""
+    @property
+    def missing(self) -> int:
+        """int: Number of NaN values seen."""
+    @property
+    def std(self) -> float:
+        """float: Sample standard deviation; NaN for fewer than two values."""
+    @property
+    def sum(self) -> float:
+        """float: Sum of the values seen."""
+    def update(self, block: Sequence[float]) -> Any:
+        """
+        Adds the values of the next block.
+
+        Args:
+            block (Sequence[float]): Consecutive values of the record.
+        """
+    @property
+    def variance(self) -> float:
+        """float: Sample variance; NaN for fewer than two values."""
+
+
+def disaggregate(series: Any, steps: Any = 24, method: Any = 'spline', reference: Any = None) -> Any:
+    ...
+
+
+def double_mass(series: list[float], reference: list[float], break_index: Optional[int] = None) -> Any:
+    ...
+
+
+def mann_kendall(series: list[float]) -> Any:
+    ...
+
+
+def periodogram(series: Sequence[float], time_step: timedelta, segment_length: Optional[int] = None, window: Literal['hann', 'rectangular'] = 'hann') -> Any:
+    ...
+
+
+def rolling(series: list[float], window: Union[int, list[int]], statistic: Literal['sum', 'mean', 'std', 'min', 'max', 'median', 'quantile'] = 'mean', quantile: float = 0.5, min_values: Optional[int] = None, center: bool = False) -> Any:
+    ...
+
+
+def sens_slope(series: list[float], confidence: float = 0.95) -> Any:
+    ...
+
+
+def wavelet(series: Sequence[float], time_step: timedelta, voices: int = 8, min_period: Optional[timedelta] = None, max_period: Optional[timedelta] = None) -> Any:
+    ...
@@ -0,0 +1,72 @@
+from datetime import timedelta
+from typing import Any, Optional
+
+from ..rustflow import scenario
+
+
+def run(
+    base: dict[str, Any],
+    inflows: dict[str, list[float]],
+    time_step: timedelta,
+    scenarios: list[dict[str, Any]],
+    nodes: Optional[list[str]] = None,
+    include_base: bool = True,
+) -> dict[str, list[Any]]:
+    """
+    Runs what-if scenarios of a network in parallel and compares their
+    outflow peaks, volumes and timing.
+
+    Each scenario is a dict with a `name` and any of:
+
+    - `nodes`: fields to set on existing nodes, keyed by node id (e.g.
+      `{"reach1": {"k": 5400.0}}`, or a new `downstream` to reroute flow).
+    - `add`: node configurations to add, such as a detention reservoir.
+    - `remove`: ids of nodes to remove.
+    - `scale`: a factor applied to every input series, or a dict of
+      factors by series name (e.g. climate-scaled inflows).
+
+    Added nodes are appended to the configuration, so in a `series`
+    network they drain from its last node.
+
+    Args:
+        base (dict): Base network configuration, as accepted by `Network`.
+        inflows (dict[str, list[float]]): Input series keyed by the names
+            used in the node configurations.
+        time_step (timedelta): Interval between consecutive values.
+        scenarios (list[dict]): Scenario definitions with unique names.
+        nodes (list[str], optional): Nodes to compare. Defaults to every
+            node of each scenario.
+        include_base (bool, optional): Whether to run the unchanged network
+            first as scenario `"base"` and report changes relative to it.
+            Defaults to True.
+
+    Returns:
+        dict[str, list]: A tidy table with one row per scenario and node.
+            The columns are `scenario`, `node`, `peak` (outflow),
+            `peak_time` (timedelta from the start), `volume` (flow units ×
+            seconds), and `peak_change` and `volume_change` relative to the
+            base run (`nan` without it). Nodes missing from a scenario have
+            `nan` values. Pass the dict to `polars.DataFrame` for analysis.
+
+    Raises:
+        ValueError: If a scenario refers to an unknown node, names repeat,
+            or a scenario network is invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow import scenario
+
+        table = scenario.run(
+            config,
+            {"q": inflow},
+            timedelta(hours=1),
+            scenarios=[
+                {"name": "slower", "nodes": {"reach1": {"k": 7200.0}}},
+                {"name": "wetter", "scale": 1.2},
+            ],
+            nodes=["outlet"],
+        )
+        ```
+    """
+    return scenario.run(base, inflows, time_step, scenarios, nodes, include_base)
@@ -0,0 +1,153 @@
+from datetime import timedelta
+from typing import Literal, Optional
+
+from ..rustflow import sediment
+
+Formula = Literal["engelund_hansen", "yang", "meyer_peter_muller"]
+
+
+def transport_capacity(
+    discharge: list[float],
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    d50: float,
+    formula: Formula = "engelund_hansen",
+    specific_gravity: float = 2.65,
+    units: Literal["si", "us"] = "si",
+) -> list[float]:
+    """
+    Computes the sediment transport capacity of a trapezoidal channel.
+
+    Hydraulics (depth, velocity, hydraulic radius) are taken at normal flow
+    for each discharge, following Manning's equation. Engelund–Hansen and
+    Yang give the total bed-material load of sand-bed channels;
+    Meyer-Peter–Müller gives the bedload of gravel-bed channels, which is
+    zero below the critical Shields stress.
+
+    Args:
+        discharge (list[float]): Flow series (m³/s or cfs, per `units`),
+            e.g. routed outflows.
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Horizontal run per unit vertical rise of the
+            banks.
+        n (float): Manning's roughness coefficient.
+        slope (float): Bed slope (m/m), used as the energy slope.
+        d50 (float): Median grain diameter (mm or in).
+        formula (Formula, optional): `"engelund_hansen"`, `"yang"` or
+            `"meyer_peter_muller"`. Defaults to `"engelund_hansen"`.
+        specific_gravity (float, optional): Grain specific gravity.
+            Defaults to 2.65 (quartz).
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            result. Defaults to `"si"`.
+
+    Returns:
+        list[float]: Transport capacity (kg/s or lb/s) for each discharge.
+
+    Raises:
+        ValueError: If the formula is unknown or a parameter is out of
+            range.
+
+    Example:
+        ```python
+        from rustflow.sediment import transport_capacity
+
+        capacity = transport_capacity(
+            [5.0, 50.0, 200.0], bottom_width=20.0, side_slope=2.0, n=0.03,
+            slope=0.002, d50=0.5, formula="yang",
+        )
+        ```
+    """
+    return sediment.transport_capacity(
+        discharge,
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        d50,
+        formula,
+        specific_gravity,
+        units,
+    )
+
+
+def route_sediment(
+    discharge: list[float],
+    load: list[float],
+    time_step: timedelta,
+    bottom_width: float,
+    side_slope: float,
+    n: float,
+    slope: float,
+    d50: float,
+    formula: Formula = "engelund_hansen",
+    specific_gravity: float = 2.65,
+    bed_storage: Optional[float] = None,
+    units: Literal["si", "us"] = "si",
+) -> dict[str, list[float]]:
+    """
+    Routes a sediment load through a reach with a finite erodible bed.
+
+    Each step the reach carries sediment at its transport capacity where
+    supply allows. Load in excess of capacity deposits on the bed; a
+    capacity deficit erodes the bed until its store is exhausted, after
+    which transport is supply-limited. Pass the routed flow of the reach
+    (e.g. from `rustflow.reach` or a network run) as `discharge`.
+
+    Args:
+        discharge (list[float]): Flow through the reach (m³/s or cfs).
+        load (list[float]): Incoming sediment load (kg/s or lb/s), e.g. the
+            routed load of an upstream reach or a post-fire hillslope yield.
+        time_step (timedelta): Interval between consecutive values.
+        bottom_width (float): Channel bottom width (m or ft).
+        side_slope (float): Horizontal run per unit vertical rise of the
+            banks.
+        n (float): Manning's roughness coefficient.
+        slope (float): Bed slope (m/m).
+        d50 (float): Median grain diameter (mm or in).
+        formula (Formula, optional): Transport formula; see
+            `transport_capacity`. Defaults to `"engelund_hansen"`.
+        specific_gravity (float, optional): Grain specific gravity.
+            Defaults to 2.65.
+        bed_storage (float, optional): Erodible bed material at the start
+            (kg or lb). Defaults to an unlimited supply.
+        units (Literal["si", "us"], optional): Unit system of the inputs and
+            results. Defaults to `"si"`.
+
+    Returns:
+        dict[str, list[float]]: `load` leaving the reach and transport
+            `capacity` (kg/s or lb/s), and `bed` material stored at the end
+            of each step (kg or lb).
+
+    Raises:
+        ValueError: If `discharge` and `load` differ in length, the formula
+            is unknown or a parameter is out of range.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.sediment import route_sediment
+
+        result = route_sediment(
+            [20.0, 80.0, 150.0, 60.0], [5.0, 400.0, 300.0, 20.0],
+            timedelta(hours=1), bottom_width=15.0, side_slope=2.0, n=0.035,
+            slope=0.001, d50=0.4, bed_storage=1.0e6,
+        )
+        deposited = result["bed"][-1] - 1.0e6
+        ```
+    """
+    return sediment.route_sediment(
+        discharge,
+        load,
+        time_step,
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        d50,
+        formula,
+        specific_gravity,
+        bed_storage,
+        units,
+    )
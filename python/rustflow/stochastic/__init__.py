@@ -0,0 +1,207 @@
+from typing import Literal, Optional, Sequence
+
+from ..rustflow import stochastic
+
+__all__ = [
+    "disaggregate",
+    "fit_markov_rainfall",
+    "rainfall_generator",
+    "streamflow_generator",
+]
+
+
+def fit_markov_rainfall(
+    observed: Sequence[float],
+    threshold: float = 0.0,
+    period: int = 0,
+    seasons: int = 12,
+) -> list[dict[str, float]]:
+    """
+    Fits the Markov chain rainfall model used by `rainfall_generator`.
+
+    Args:
+        observed (Sequence[float]): Observed rainfall depths per step, with
+            the first value at the start of the seasonal cycle. `nan` values
+            are skipped.
+        threshold (float, optional): Depth above which a step is wet.
+            Defaults to 0.
+        period (int, optional): Steps per seasonal cycle, e.g. 8766 for
+            hourly data over a year or 365 for daily data. `0` fits a single
+            parameter set. Defaults to 0.
+        seasons (int, optional): Number of equal-length seasons per cycle.
+            Defaults to 12.
+
+    Returns:
+        list[dict[str, float]]: For each season, the transition probabilities
+            `p_wet_after_dry` and `p_wet_after_wet` and the gamma `shape` and
+            `scale` of wet-step depths in excess of `threshold`.
+
+    Raises:
+        ValueError: If a season has no observed dry steps.
+    """
+    return stochastic.fit_markov_rainfall(observed, threshold, period, seasons)
+
+
+def rainfall_generator(
+    observed: Sequence[float],
+    n_steps: int,
+    threshold: float = 0.0,
+    period: int = 0,
+    seasons: int = 12,
+    realizations: int = 1,
+    start: int = 0,
+    seed: Optional[int] = None,
+) -> list[list[float]]:
+    """
+    Generates synthetic rainfall with a first-order, two-state Markov chain.
+
+    Wet/dry occurrence follows the observed transition probabilities, which
+    preserves the persistence of wet and dry spells. Wet-step depths are
+    gamma distributed, matching the mean and variance of the observed wet
+    depths. Parameters are fitted per season when `period` is given, and
+    realisations are generated in parallel in Rust.
+
+    Args:
+        observed (Sequence[float]): Observed rainfall per step, with the first
+            value at the start of the seasonal cycle.
+        n_steps (int): Length of each synthetic series.
+        threshold (float, optional): Depth above which a step is wet; dry
+            steps are generated as 0. Defaults to 0.
+        period (int, optional): Steps per seasonal cycle (see
+            `fit_markov_rainfall`). Defaults to 0 (no seasonality).
+        seasons (int, optional): Seasons per cycle. Defaults to 12.
+        realizations (int, optional): Number of independent series.
+            Defaults to 1.
+        start (int, optional): Position in the seasonal cycle of the first
+            generated step. Defaults to 0.
+        seed (Optional[int], optional): Seed for reproducible output; results
+            do not depend on the number of threads.
+
+    Returns:
+        list[list[float]]: `realizations` series of `n_steps` values, in the
+            units of `observed`.
+
+    Raises:
+        ValueError: If a season has no observed dry steps.
+
+    Example:
+        ```python
+        from rustflow.stochastic import rainfall_generator
+
+        # 10,000 years of hourly rainfall from a 30-year hourly record
+        synthetic = rainfall_generator(
+            observed_hourly, n_steps=10_000 * 8766, threshold=0.1,
+            period=8766, seasons=12, seed=2024,
+        )[0]
+        ```
+    """
+    return stochastic.rainfall_generator(
+        observed, n_steps, threshold, period, seasons, realizations, start, seed
+    )
+
+
+def streamflow_generator(
+    observed: Sequence[float],
+    n_steps: int,
+    method: Literal["ar1", "arma", "index_sequential"] = "ar1",
+    period: int = 12,
+    realizations: Optional[int] = None,
+    log: bool = True,
+    start: int = 0,
+    seed: Optional[int] = None,
+) -> list[list[float]]:
+    """
+    Generates synthetic streamflow traces from an observed record.
+
+    Methods:
+
+    - `"ar1"`: periodic lag-1 autoregression (Thomas-Fiering), preserving
+      the mean, standard deviation and season-to-season correlation of each
+      season.
+    - `"arma"`: ARMA(1,1) fitted to flows standardised by season, which
+      also captures longer persistence than AR(1).
+    - `"index_sequential"`: replays the historical record starting at each
+      cycle in turn, wrapping at the end (no randomness).
+
+    Traces are generated in parallel in Rust, with independent random
+    streams so results do not depend on the number of threads.
+
+    Args:
+        observed (Sequence[float]): Observed flows, with the first value in
+            season 0 (e.g. January for monthly data).
+        n_steps (int): Length of each trace.
+        method (Literal["ar1", "arma", "index_sequential"], optional):
+            Generation method. Defaults to `"ar1"`.
+        period (int, optional): Seasons per cycle, e.g. 12 for monthly or 365
+            for daily flows; 1 for no seasonality. Defaults to 12.
+        realizations (Optional[int], optional): Number of traces. Defaults to
+            1, or to the number of complete cycles for `"index_sequential"`.
+        log (bool, optional): Fit the stochastic models to log flows, which
+            keeps flows positive and handles skew. Otherwise negative values
+            are truncated to 0. Defaults to `True`.
+        start (int, optional): Season of the first generated value. Defaults
+            to 0.
+        seed (Optional[int], optional): Seed for reproducible traces.
+
+    Returns:
+        list[list[float]]: Synthetic traces, in the units of `observed`.
+
+    Raises:
+        ValueError: If the record is shorter than two cycles, `log=True` with
+            non-positive flows, or `method` is unknown.
+
+    Example:
+        ```python
+        from rustflow.stochastic import streamflow_generator
+
+        traces = streamflow_generator(
+            monthly_flows, n_steps=50 * 12, method="ar1", realizations=1000, seed=7
+        )
+        ```
+    """
+    return stochastic.streamflow_generator(
+        observed, n_steps, method, period, realizations, log, start, seed
+    )
+
+
+def disaggregate(
+    synthetic: Sequence[float],
+    fragments: Sequence[Sequence[float]],
+    period: int = 12,
+    start: int = 0,
+) -> list[float]:
+    """
+    Disaggregates a coarse synthetic series to shorter steps with the method
+    of fragments.
+
+    Each synthetic value (e.g. a monthly mean flow) takes the within-period
+    pattern of the observed period of the same season whose mean is
+    closest, scaled so that its mean equals the synthetic value.
+
+    Args:
+        synthetic (Sequence[float]): Coarse synthetic flows (mean flow per
+            coarse step).
+        fragments (Sequence[Sequence[float]]): Observed fine-step flows
+            grouped by coarse step, e.g. the daily flows of each observed
+            month. Group `i` is in season `i % period`.
+        period (int, optional): Seasons per cycle. Defaults to 12.
+        start (int, optional): Season of the first synthetic value. Defaults
+            to 0.
+
+    Returns:
+        list[float]: The fine-step series; its length is the sum of the
+            lengths of the chosen fragments.
+
+    Raises:
+        ValueError: If a season has no observed fragment with a positive
+            mean.
+
+    Example:
+        ```python
+        from rustflow.stochastic import disaggregate, streamflow_generator
+
+        monthly = streamflow_generator(monthly_flows, 120, seed=1)[0]
+        daily = disaggregate(monthly, daily_flows_by_month)
+        ```
+    """
+    return stochastic.disaggregate(synthetic, [list(f) for f in fragments], period, start)
@@ -0,0 +1,5 @@
+from ..rustflow import terrain
+
+FlowGrid = terrain.FlowGrid
+
+__all__ = ["FlowGrid"]
@@ -0,0 +1,218 @@
+from typing import Literal, Optional, Union
+
+from ..rustflow import timeseries
+
+MovingMean = timeseries.MovingMean
+Resampler = timeseries.Resampler
+RunningStats = timeseries.RunningStats
+
+from . import spectral
+
+
+def double_mass(
+    series: list[float],
+    reference: list[float],
+    break_index: Optional[int] = None,
+) -> dict[str, Union[list[float], float, int]]:
+    """
+    Builds a double-mass curve to check the consistency of a gauge record.
+
+    The cumulative series is plotted against the cumulative reference (e.g.
+    the mean of neighbouring gauges) over steps where both are present. A
+    change in the gauge, such as a relocation, shows as a break in slope. A
+    continuous two-segment line through the origin is fitted with its break
+    at `break_index`, or where it best fits if not given.
+
+    Args:
+        series (list[float]): Values of the gauge being checked, e.g. annual
+            totals.
+        reference (list[float]): Reference values over the same steps.
+        break_index (int, optional): Index in the cumulative curves of the
+            last point before the break. Found by least squares if omitted.
+
+    Returns:
+        dict: `cumulative_reference` and `cumulative_series`, the
+            `break_index`, the `slope_before` and `slope_after` it, and the
+            `adjustment` factor (`slope_before / slope_after`) to apply to
+            values after the break.
+
+    Raises:
+        ValueError: If the lengths differ, fewer than four steps have both
+            values, or the break leaves fewer than two points on a side.
+
+    Example:
+        ```python
+        from rustflow.timeseries import double_mass
+
+        curve = double_mass(gauge_annual, neighbours_mean_annual)
+        if abs(curve["adjustment"] - 1.0) > 0.1:
+            print("Possible inconsistency after", curve["break_index"])
+        ```
+    """
+    return timeseries.double_mass(series, reference, break_index)
+
+
+def mann_kendall(series: list[float]) -> dict[str, float]:
+    """
+    Tests a series for a monotonic trend with the Mann–Kendall test.
+
+    The variance of S is corrected for tied values and the normal statistic
+    uses a continuity correction. Missing (NaN) values are skipped. The test
+    compares every pair of values, so aggregate long daily records (e.g. to
+    annual values) first.
+
+    Args:
+        series (list[float]): Values in time order.
+
+    Returns:
+        dict[str, float]: Kendall's `s`, its `variance`, the normal
+            statistic `z`, the two-sided `p_value` and Kendall's `tau`.
+
+    Raises:
+        ValueError: If fewer than three values are present.
+
+    Example:
+        ```python
+        from rustflow.timeseries import mann_kendall
+
+        test = mann_kendall(annual_peaks)
+        significant = test["p_value"] < 0.05
+        ```
+    """
+    return timeseries.mann_kendall(series)
+
+
+def sens_slope(series: list[float], confidence: float = 0.95) -> dict[str, float]:
+    """
+    Estimates the magnitude of a trend with Sen's slope.
+
+    The slope is the median of the slopes between all pairs of values, per
+    time step, with Gilbert's (1987) confidence interval. Missing (NaN)
+    values are skipped while keeping the time index of the others.
+
+    Args:
+        series (list[float]): Values in time order.
+        confidence (float, optional): Coverage of the two-sided interval.
+            Defaults to 0.95.
+
+    Returns:
+        dict[str, float]: The `slope`, the `intercept` of the line
+            `intercept + slope * t` (with `t` the step index), and the
+            interval bounds `lower` and `upper`.
+
+    Raises:
+        ValueError: If fewer than three values are present or `confidence`
+            is outside (0, 1).
+
+    Example:
+        ```python
+        from rustflow.timeseries import sens_slope
+
+        trend = sens_slope(annual_peaks)
+        change_per_decade = 10 * trend["slope"]
+        ```
+    """
+    return timeseries.sens_slope(series, confidence)
+
+
+def disaggregate(
+    series: list[float],
+    steps: int = 24,
+    method: Literal["spline", "pattern"] = "spline",
+    reference: Optional[list[float]] = None,
+) -> list[float]:
+    """
+    Disaggregates daily (or other coarse) mean flows into sub-daily steps
+    that conserve each period's volume.
+
+    Two methods are available:
+
+    - `"spline"`: a monotone cubic (Fritsch–Carlson) curve of cumulative
+      volume is passed through the period boundaries and differenced at
+      the sub-step boundaries. Flows are smooth and never negative.
+    - `"pattern"`: the method of fragments with the nearest-neighbour
+      period of a fine `reference` record, e.g. an hourly gauge nearby.
+      The reference period whose mean flow and neighbouring means are
+      closest to the target's lends its within-period pattern, rescaled to
+      the target mean.
+
+    The mean of each period's sub-steps equals the period's value.
+
+    Args:
+        series (list[float]): Mean flow of each period, not negative. Any
+            iterable of numbers or Arrow array is accepted.
+        steps (int, optional): Sub-steps per period, e.g. 24 for daily to
+            hourly. Defaults to 24.
+        method (Literal["spline", "pattern"], optional): Disaggregation
+            method. Defaults to `"spline"`.
+        reference (list[float], optional): Fine-resolution reference record
+            covering whole periods; required for `"pattern"`.
+
+    Returns:
+        list[float]: `steps` values per input period.
+
+    Raises:
+        ValueError: If flows are negative or not finite, or the reference
+            record is missing, has no flow, or does not cover whole periods.
+
+    Example:
+        ```python
+        from rustflow.timeseries import disaggregate
+
+        hourly = disaggregate(daily_flows, steps=24, method="pattern", reference=hourly_gauge)
+        ```
+    """
+    return timeseries.disaggregate(series, steps, method, reference)
+
+
+def rolling(
+    series: list[float],
+    window: Union[int, list[int]],
+    statistic: Literal["sum", "mean", "std", "min", "max", "median", "quantile"] = "mean",
+    quantile: float = 0.5,
+    min_values: Optional[int] = None,
+    center: bool = False,
+) -> Union[list[float], list[list[float]]]:
+    """
+    Computes a statistic over a moving window of a series.
+
+    Each window statistic is updated incrementally rather than recomputed,
+    so long records and long windows are fast. This includes quantiles,
+    which keep the window sorted as it moves. Typical uses are n-day mean
+    flows (e.g. the annual minimum 7-day mean for 7Q10), flood volumes over
+    several durations, and antecedent precipitation totals.
+
+    Args:
+        series (list[float]): Series at a constant time step. Any iterable
+            of numbers or Arrow array is accepted. `nan` values are skipped.
+        window (Union[int, list[int]]): Window length in steps. With a list,
+            each window is computed in parallel.
+        statistic (str, optional): `sum`, `mean`, `std` (sample standard
+            deviation), `min`, `max`, `median` or `quantile`. Defaults to
+            "mean".
+        quantile (float, optional): Probability for `quantile`, interpolated
+            linearly between window values. Defaults to 0.5.
+        min_values (Optional[int], optional): Fewest valid values a window
+            needs; windows with fewer give `nan`. Defaults to None (the
+            whole window).
+        center (bool, optional): If True, label each window by its middle
+            value instead of its last. Defaults to False.
+
+    Returns:
+        Union[list[float], list[list[float]]]: The statistic of the window
+            at each value, `nan` where the window overhangs the ends of the
+            series, or one such series per window.
+
+    Raises:
+        ValueError: If a window is zero, the statistic is unknown, or
+            `quantile` is outside [0, 1].
+
+    Example:
+        ```python
+        from rustflow.timeseries import rolling
+
+        mean_7, mean_30 = rolling(daily_flows, [7, 30])
+        q90 = rolling(daily_flows, 365, "quantile", quantile=0.9)
+        ```
+    """
+    return timeseries.rolling(series, window, statistic, quantile, min_values, center)
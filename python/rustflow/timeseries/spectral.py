@@ -0,0 +1,108 @@
+from datetime import timedelta
+from typing import Any, Literal, Optional, Sequence
+
+from ..rustflow import timeseries
+
+
+def periodogram(
+    series: Sequence[float],
+    time_step: timedelta,
+    segment_length: Optional[int] = None,
+    window: Literal["hann", "rectangular"] = "hann",
+) -> dict[str, list[float]]:
+    """
+    Computes the power spectrum of a series with the FFT.
+
+    Peaks show periodic signals, such as the diurnal cycle of snowmelt or
+    evapotranspiration, hydropeaking releases, or the seasonal cycle. With
+    `segment_length`, Welch's method averages the spectra of half-overlapping
+    segments, which gives a steadier estimate at a coarser resolution.
+    Series of any length are transformed exactly, so decades of
+    15-minute data need no padding or trimming.
+
+    Args:
+        series (Sequence[float]): Gap-free series at a constant time step.
+            Any iterable of numbers or Arrow array is accepted.
+        time_step (timedelta): Interval between values.
+        segment_length (Optional[int], optional): Values per Welch segment.
+            Defaults to None (one segment spanning the whole series).
+        window (Literal["hann", "rectangular"], optional): Taper applied to
+            each segment after its mean is removed. Defaults to "hann".
+
+    Returns:
+        dict[str, list[float]]: `frequency` (cycles per day), `period`
+            (days) and the one-sided power spectral density `power`
+            (squared series units per cycle per day), from the lowest
+            resolved frequency to the Nyquist frequency.
+
+    Raises:
+        ValueError: If the series has gaps or fewer than four values, or
+            the segment length is outside [4, len(series)].
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.timeseries.spectral import periodogram
+
+        spectrum = periodogram(flows, timedelta(minutes=15), segment_length=96 * 30)
+        peak = max(range(len(spectrum["power"])), key=spectrum["power"].__getitem__)
+        print(spectrum["period"][peak])  # ~1.0 for a diurnal cycle
+        ```
+    """
+    return timeseries.periodogram(series, time_step, segment_length, window)
+
+
+def wavelet(
+    series: Sequence[float],
+    time_step: timedelta,
+    voices: int = 8,
+    min_period: Optional[timedelta] = None,
+    max_period: Optional[timedelta] = None,
+) -> dict[str, Any]:
+    """
+    Computes the continuous Morlet wavelet transform of a series.
+
+    The wavelet power shows how strong each periodic signal is at each
+    point in time. This reveals changes in the flow regime, such as a
+    diurnal cycle that appears only during the melt season, or seasonality
+    that weakens after a dam is built (Torrence and Compo, 1998). The
+    transform uses the FFT at every scale in parallel.
+
+    Args:
+        series (Sequence[float]): Gap-free series at a constant time step.
+        time_step (timedelta): Interval between values.
+        voices (int, optional): Scales per octave (doubling of period).
+            Defaults to 8.
+        min_period (Optional[timedelta], optional): Shortest period.
+            Defaults to two time steps.
+        max_period (Optional[timedelta], optional): Longest period.
+            Defaults to the record length.
+
+    Returns:
+        dict[str, Any]:
+            - `period`, the Fourier period of each scale (days);
+            - `power`, the wavelet power `|W|²` (squared series units), with
+              shape (scales, len(series)). It is a NumPy array, or a 2-D
+              memoryview without NumPy. Divide it by the variance of the
+              series to compare it against white noise;
+            - `global_power`, the time-averaged power at each scale;
+            - `cone_of_influence`, the longest period (days) at each step
+              that is unaffected by the ends of the series.
+
+    Raises:
+        ValueError: If the series has gaps or fewer than four values,
+            `voices` is zero, or the periods are invalid.
+
+    Example:
+        ```python
+        from datetime import timedelta
+        from rustflow.timeseries.spectral import wavelet
+
+        result = wavelet(
+            flows, timedelta(hours=1), min_period=timedelta(hours=6), max_period=timedelta(days=400)
+        )
+        annual = min(range(len(result["period"])), key=lambda j: abs(result["period"][j] - 365.25))
+        strength = result["power"][annual]  # annual-cycle power over time
+        ```
+    """
+    return timeseries.wavelet(series, time_step, voices, min_period, max_period)
@@ -0,0 +1,614 @@
+//! Import of Arrow arrays through the Arrow PyCapsule interface
+//! (`__arrow_c_array__`), so pyarrow, polars and other Arrow producers can
+//! hand columns to rustflow without a Python-level list conversion. The
+//! values are copied once into `Vec<f64>`s rather than borrowed, since the
+//! numerical cores own their inputs and the exported arrays are released
+//! when the capsules are dropped.
+
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{PyImportError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyCapsule, PyList};
+use std::ffi::{c_char, c_void, CStr};
+
+/// `struct ArrowSchema` of the Arrow C data interface.
+#[repr(C)]
+struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+/// `struct ArrowArray` of the Arrow C data interface.
+#[repr(C)]
+struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+/// Returns `true` if `ob` implements the Arrow PyCapsule array protocol.
+pub fn is_arrow_array(ob: &Bound<'_, PyAny>) -> bool {
+    ob.hasattr("__arrow_c_array__").unwrap_or(false)
+}
+
+/// Exports `ob` through `__arrow_c_array__` and passes the schema and array
+/// to `f`. The capsules own the exported data and release it when dropped.
+fn with_arrow_array<T>(
+    ob: &Bound<'_, PyAny>,
+    f: impl FnOnce(&ArrowSchema, &ArrowArray) -> PyResult<T>,
+) -> PyResult<T> {
+    let exported = ob.call_method0("__arrow_c_array__")?;
+    let (schema, array): (Bound<'_, PyCapsule>, Bound<'_, PyCapsule>) = exported.extract()?;
+    let schema_name = schema.name()?.map(CStr::to_bytes);
+    let array_name = array.name()?.map(CStr::to_bytes);
+    if schema_name != Some(b"arrow_schema") || array_name != Some(b"arrow_array") {
+        return Err(PyTypeError::new_err(
+            "`__arrow_c_array__` did not return Arrow schema and array capsules.",
+        ));
+    }
+    // SAFETY: the capsule names guarantee the pointee types, and the capsules
+    // are alive for the duration of `f`.
+    let (schema, array) = unsafe {
+        (
+            &*(schema.pointer() as *const ArrowSchema),
+            &*(array.pointer() as *const ArrowArray),
+        )
+    };
+    f(schema, array)
+}
+
+/// Copies a primitive numeric Arrow array into `f64` values; nulls become
+/// `NaN`. Errors are the messages of a `TypeError`, kept free of Python so
+/// that the copy can be tested without an interpreter.
+///
+/// # Safety
+///
+/// `schema` and `array` must describe a valid, unreleased Arrow array.
+unsafe fn primitive_to_vec(
+    schema: &ArrowSchema,
+    array: &ArrowArray,
+) -> std::result::Result<Vec<f64>, String> {
+    let format = CStr::from_ptr(schema.format).to_bytes();
+    let length = array.length as usize;
+    let offset = array.offset as usize;
+    if array.n_buffers < 2 {
+        return Err("Arrow array is not a primitive numeric array.".into());
+    }
+    // Producers may export empty arrays without a data buffer, which
+    // `slice::from_raw_parts` does not accept.
+    let (data, offset) = match *array.buffers.add(1) {
+        _ if length == 0 => (
+            std::ptr::NonNull::<f64>::dangling().as_ptr() as *const c_void,
+            0,
+        ),
+        data => (data, offset),
+    };
+    macro_rules! read {
+        ($t:ty) => {
+            std::slice::from_raw_parts((data as *const $t).add(offset), length)
+                .iter()
+                .map(|&v| v as f64)
+                .collect::<Vec<f64>>()
+        };
+    }
+    let mut values = match format {
+        b"g" => std::slice::from_raw_parts((data as *const f64).add(offset), length).to_vec(),
+        b"f" => read!(f32),
+        b"l" => read!(i64),
+        b"i" => read!(i32),
+        b"s" => read!(i16),
+        b"c" => read!(i8),
+        b"L" => read!(u64),
+        b"I" => read!(u32),
+        b"S" => read!(u16),
+        b"C" => read!(u8),
+        other => {
+            return Err(format!(
+                "Unsupported Arrow type `{}`; expected a numeric array.",
+                String::from_utf8_lossy(other)
+            ))
+        }
+    };
+    let validity = *array.buffers as *const u8;
+    if array.null_count != 0 && !validity.is_null() {
+        for (i, value) in values.iter_mut().enumerate() {
+            let bit = offset + i;
+            if *validity.add(bit / 8) & (1 << (bit % 8)) == 0 {
+                *value = f64::NAN;
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// Reads a numeric Arrow array as `f64` values (nulls become `NaN`).
+pub fn arrow_array_to_vec(ob: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    // SAFETY: `with_arrow_array` validates the capsules it hands out.
+    with_arrow_array(ob, |schema, array| unsafe {
+        primitive_to_vec(schema, array).map_err(PyTypeError::new_err)
+    })
+}
+
+/// Reads an Arrow record batch (struct array) as named `f64` columns.
+pub fn arrow_batch_to_columns(ob: &Bound<'_, PyAny>) -> PyResult<Vec<(String, Vec<f64>)>> {
+    // SAFETY: `with_arrow_array` validates the capsules it hands out.
+    with_arrow_array(ob, |schema, array| unsafe {
+        if CStr::from_ptr(schema.format).to_bytes() != b"+s" {
+            return Err(PyValueError::new_err(
+                "Expected an Arrow record batch or struct array.",
+            ));
+        }
+        struct_to_columns(schema, array).map_err(PyTypeError::new_err)
+    })
+}
+
+/// Copies the primitive numeric children of a struct array into named
+/// `f64` columns.
+///
+/// # Safety
+///
+/// `schema` and `array` must describe a valid, unreleased Arrow struct
+/// array.
+unsafe fn struct_to_columns(
+    schema: &ArrowSchema,
+    array: &ArrowArray,
+) -> std::result::Result<Vec<(String, Vec<f64>)>, String> {
+    (0..schema.n_children as usize)
+        .map(|i| {
+            let child_schema = &**schema.children.add(i);
+            let child_array = &**array.children.add(i);
+            let name = CStr::from_ptr(child_schema.name)
+                .to_string_lossy()
+                .into_owned();
+            let mut values = primitive_to_vec(child_schema, child_array)?;
+            // Struct-level offsets apply on top of the child offsets.
+            let start = array.offset as usize;
+            values.drain(..start.min(values.len()));
+            values.truncate(array.length as usize);
+            Ok((name, values))
+        })
+        .collect()
+}
+
+/// A 1-D series of floats extracted from a Python object: an Arrow array,
+/// a sequence, or any iterable of numbers.
+pub struct FloatSeries(pub Vec<f64>);
+
+impl<'py> FromPyObject<'py> for FloatSeries {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if is_arrow_array(ob) {
+            return arrow_array_to_vec(ob).map(FloatSeries);
+        }
+        if let Ok(values) = ob.extract::<Vec<f64>>() {
+            return Ok(FloatSeries(values));
+        }
+        ob.try_iter()?
+            .map(|item| item?.extract::<f64>())
+            .collect::<PyResult<Vec<f64>>>()
+            .map(FloatSeries)
+    }
+}
+
+/// A 1-D series kept in the precision it was given in: `float32` buffers,
+/// such as NumPy arrays of that dtype, as `f32`, and anything else
+/// [`FloatSeries`] accepts as `f64`.
+pub enum FloatArray {
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+impl<'py> FromPyObject<'py> for FloatArray {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(buffer) = PyBuffer::<f32>::get(ob) {
+            if buffer.dimensions() == 1 {
+                return Ok(FloatArray::F32(buffer.to_vec(ob.py())?));
+            }
+        }
+        ob.extract::<FloatSeries>().map(|s| FloatArray::F64(s.0))
+    }
+}
+
+/// Several series in one precision: `f32` if every series was given as
+/// `f32`, and `f64` otherwise.
+pub enum FloatArrays {
+    F32(Vec<Vec<f32>>),
+    F64(Vec<Vec<f64>>),
+}
+
+impl From<Vec<FloatArray>> for FloatArrays {
+    fn from(arrays: Vec<FloatArray>) -> Self {
+        if arrays.iter().all(|a| matches!(a, FloatArray::F32(_))) {
+            FloatArrays::F32(
+                arrays
+                    .into_iter()
+                    .filter_map(|a| match a {
+                        FloatArray::F32(values) => Some(values),
+                        FloatArray::F64(_) => None,
+                    })
+                    .collect(),
+            )
+        } else {
+            FloatArrays::F64(
+                arrays
+                    .into_iter()
+                    .map(|a| match a {
+                        FloatArray::F32(values) => values.into_iter().map(f64::from).collect(),
+                        FloatArray::F64(values) => values,
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// An n-dimensional C-contiguous `float64` or `float32` buffer, such as a
+/// NumPy array, read in place without copying.
+pub enum FloatBuffer {
+    F32(PyBuffer<f32>),
+    F64(PyBuffer<f64>),
+}
+
+/// The values of a [`FloatBuffer`] in row-major order.
+#[derive(Clone, Copy)]
+pub enum FloatSlice<'a> {
+    F32(&'a [f32]),
+    F64(&'a [f64]),
+}
+
+impl<'py> FromPyObject<'py> for FloatBuffer {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let buffer = if let Ok(buffer) = PyBuffer::<f64>::get(ob) {
+            FloatBuffer::F64(buffer)
+        } else if let Ok(buffer) = PyBuffer::<f32>::get(ob) {
+            FloatBuffer::F32(buffer)
+        } else {
+            return Err(PyTypeError::new_err(
+                "Expected a float64 or float32 array, such as a NumPy array.",
+            ));
+        };
+        let contiguous = match &buffer {
+            FloatBuffer::F32(b) => b.is_c_contiguous(),
+            FloatBuffer::F64(b) => b.is_c_contiguous(),
+        };
+        if !contiguous {
+            return Err(PyValueError::new_err(
+                "The array must be C-contiguous; pass `numpy.ascontiguousarray(values)`.",
+            ));
+        }
+        Ok(buffer)
+    }
+}
+
+impl FloatBuffer {
+    pub fn shape(&self) -> &[usize] {
+        match self {
+            FloatBuffer::F32(b) => b.shape(),
+            FloatBuffer::F64(b) => b.shape(),
+        }
+    }
+
+    /// The values, borrowed from the exporting object for as long as the
+    /// buffer is held. Python code writing to the array at the same time
+    /// (from another thread) is not prevented, as with NumPy's own
+    /// GIL-free operations.
+    pub fn values(&self) -> FloatSlice<'_> {
+        // SAFETY: the buffer is C-contiguous (checked on extraction) and
+        // holds `item_count` items of the element type; the export keeps
+        // the memory alive until `self` is dropped.
+        unsafe {
+            match self {
+                FloatBuffer::F32(b) => FloatSlice::F32(std::slice::from_raw_parts(
+                    b.buf_ptr() as *const f32,
+                    b.item_count(),
+                )),
+                FloatBuffer::F64(b) => FloatSlice::F64(std::slice::from_raw_parts(
+                    b.buf_ptr() as *const f64,
+                    b.item_count(),
+                )),
+            }
+        }
+    }
+}
+
+fn f32_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+}
+
+/// Returns `values` as a 1-D `float32` NumPy array, or as an
+/// `array.array("f")` when NumPy is not installed.
+pub fn f32_array<'py>(py: Python<'py>, values: &[f32]) -> PyResult<Bound<'py, PyAny>> {
+    let bytes = PyByteArray::new(py, &f32_bytes(values));
+    match py.import("numpy") {
+        Ok(numpy) => numpy.call_method1("frombuffer", (bytes, "float32")),
+        Err(e) if e.is_instance_of::<PyImportError>(py) => {
+            py.import("array")?.getattr("array")?.call1(("f", bytes))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Element types of the 2-D arrays returned by [`grid_array`], with their
+/// `struct` / NumPy format character.
+pub trait GridValue: Copy {
+    const FORMAT: &'static str;
+    fn push_bytes(self, out: &mut Vec<u8>);
+}
+
+macro_rules! grid_value {
+    ($($t:ty => $format:literal),*) => {$(
+        impl GridValue for $t {
+            const FORMAT: &'static str = $format;
+            fn push_bytes(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_ne_bytes());
+            }
+        }
+    )*};
+}
+
+grid_value!(f64 => "d", f32 => "f", i32 => "i", u8 => "B");
+
+/// Returns row-major `values` as a `(rows, cols)` NumPy array, or as a 2-D
+/// memoryview when NumPy is not installed.
+pub fn grid_array<'py, T: GridValue>(
+    py: Python<'py>,
+    values: &[T],
+    rows: usize,
+    cols: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut bytes = Vec::with_capacity(std::mem::size_of_val(values));
+    for &v in values {
+        v.push_bytes(&mut bytes);
+    }
+    let bytes = PyByteArray::new(py, &bytes);
+    match py.import("numpy") {
+        Ok(numpy) => numpy
+            .call_method1("frombuffer", (bytes, T::FORMAT))?
+            .call_method1("reshape", ((rows, cols),)),
+        Err(e) if e.is_instance_of::<PyImportError>(py) => py
+            .import("builtins")?
+            .call_method1("memoryview", (bytes,))?
+            .call_method1("cast", (T::FORMAT, (rows, cols))),
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns `rows` (of equal length) as a 2-D `float32` NumPy array, or as
+/// a list of `array.array("f")` rows when NumPy is not installed.
+pub fn f32_rows<'py>(py: Python<'py>, rows: &[Vec<f32>]) -> PyResult<Bound<'py, PyAny>> {
+    match py.import("numpy") {
+        Ok(_) => {
+            let flat: Vec<f32> = rows.concat();
+            let steps = rows.first().map_or(0, Vec::len);
+            f32_array(py, &flat)?.call_method1("reshape", ((rows.len(), steps),))
+        }
+        Err(e) if e.is_instance_of::<PyImportError>(py) => {
+            let rows = rows
+                .iter()
+                .map(|row| f32_array(py, row))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new(py, rows)?.into_any())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::ptr::{null, null_mut};
+
+    /// An array exported the way an Arrow producer would, owning the
+    /// buffers its C structs point to.
+    struct Exported {
+        schema: ArrowSchema,
+        array: ArrowArray,
+        _format: CString,
+        _name: CString,
+        _buffers: Box<[*const c_void; 2]>,
+        _data: Vec<u8>,
+        _validity: Option<Vec<u8>>,
+        _children: Vec<Exported>,
+        _child_schemas: Vec<*mut ArrowSchema>,
+        _child_arrays: Vec<*mut ArrowArray>,
+    }
+
+    fn schema(format: &CString, name: &CString) -> ArrowSchema {
+        ArrowSchema {
+            format: format.as_ptr(),
+            name: name.as_ptr(),
+            metadata: null(),
+            flags: 0,
+            n_children: 0,
+            children: null_mut(),
+            dictionary: null_mut(),
+            release: None,
+            private_data: null_mut(),
+        }
+    }
+
+    /// Exports `data`, native-endian values of `width` bytes, as a primitive
+    /// array of `format`, with `valid` flags for a validity bitmap and the
+    /// first `offset` values skipped.
+    fn export(
+        format: &str,
+        name: &str,
+        data: Vec<u8>,
+        width: usize,
+        valid: Option<&[bool]>,
+        offset: usize,
+    ) -> Exported {
+        let format = CString::new(format).unwrap();
+        let name = CString::new(name).unwrap();
+        let validity = valid.map(|flags| {
+            let mut bits = vec![0u8; flags.len().div_ceil(8)];
+            for (i, _) in flags.iter().enumerate().filter(|(_, &v)| v) {
+                bits[i / 8] |= 1 << (i % 8);
+            }
+            bits
+        });
+        let null_count = valid.map_or(0, |flags| flags[offset..].iter().filter(|v| !**v).count());
+        let data_pointer = if data.is_empty() {
+            null()
+        } else {
+            data.as_ptr() as *const c_void
+        };
+        let buffers = Box::new([
+            validity
+                .as_ref()
+                .map_or(null(), |v| v.as_ptr() as *const c_void),
+            data_pointer,
+        ]);
+        let mut exported = Exported {
+            schema: schema(&format, &name),
+            array: ArrowArray {
+                length: (data.len() / width - offset) as i64,
+                null_count: null_count as i64,
+                offset: offset as i64,
+                n_buffers: 2,
+                n_children: 0,
+                buffers: null_mut(),
+                children: null_mut(),
+                dictionary: null_mut(),
+                release: None,
+                private_data: null_mut(),
+            },
+            _format: format,
+            _name: name,
+            _buffers: buffers,
+            _data: data,
+            _validity: validity,
+            _children: Vec::new(),
+            _child_schemas: Vec::new(),
+            _child_arrays: Vec::new(),
+        };
+        exported.array.buffers = exported._buffers.as_mut_ptr();
+        exported
+    }
+
+    fn export_f64(values: &[f64], valid: Option<&[bool]>, offset: usize) -> Exported {
+        let data = values.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        export("g", "values", data, 8, valid, offset)
+    }
+
+    /// Exports `children` as the columns of a struct array of `length` rows
+    /// from `offset`.
+    fn export_struct(children: Vec<Exported>, offset: usize, length: usize) -> Exported {
+        let mut batch = export("+s", "", Vec::new(), 1, None, 0);
+        batch._children = children;
+        for child in &mut batch._children {
+            batch._child_schemas.push(&mut child.schema);
+            batch._child_arrays.push(&mut child.array);
+        }
+        batch.schema.n_children = batch._children.len() as i64;
+        batch.schema.children = batch._child_schemas.as_mut_ptr();
+        batch.array.n_children = batch._children.len() as i64;
+        batch.array.children = batch._child_arrays.as_mut_ptr();
+        batch.array.n_buffers = 1;
+        batch.array.offset = offset as i64;
+        batch.array.length = length as i64;
+        batch
+    }
+
+    fn read(exported: &Exported) -> std::result::Result<Vec<f64>, String> {
+        unsafe { primitive_to_vec(&exported.schema, &exported.array) }
+    }
+
+    #[test]
+    fn round_trips_primitive_arrays() {
+        let values = [1.5, -2.25, 0.0, 1e300, 7.0];
+        assert_eq!(read(&export_f64(&values, None, 0)).unwrap(), values);
+        let ints: Vec<u8> = [3i32, -4, 5].iter().flat_map(|v| v.to_ne_bytes()).collect();
+        assert_eq!(
+            read(&export("i", "i", ints, 4, None, 0)).unwrap(),
+            [3.0, -4.0, 5.0]
+        );
+        let floats: Vec<u8> = [0.5f32, 2.0].iter().flat_map(|v| v.to_ne_bytes()).collect();
+        assert_eq!(
+            read(&export("f", "f", floats, 4, None, 0)).unwrap(),
+            [0.5, 2.0]
+        );
+        assert_eq!(
+            read(&export("C", "c", vec![0, 255], 1, None, 0)).unwrap(),
+            [0.0, 255.0]
+        );
+        // Empty arrays may come without a data buffer.
+        assert!(read(&export_f64(&[], None, 0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn nulls_and_offsets_are_honoured() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let valid = [true, false, true, true, true, true, true, true, false, true];
+        let read = read(&export_f64(&values, Some(&valid), 2)).unwrap();
+        assert_eq!(read.len(), 8);
+        assert_eq!(read[..6], [3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        assert!(read[6].is_nan());
+        assert_eq!(read[7], 10.0);
+    }
+
+    #[test]
+    fn round_trips_record_batches() {
+        let batch = export_struct(
+            vec![
+                export_f64(&[1.0, 2.0, 3.0, 4.0], None, 0),
+                export(
+                    "l",
+                    "count",
+                    [10i64, 20, 30, 40, 50]
+                        .iter()
+                        .flat_map(|v| v.to_ne_bytes())
+                        .collect(),
+                    8,
+                    None,
+                    1,
+                ),
+            ],
+            1,
+            2,
+        );
+        let columns = unsafe { struct_to_columns(&batch.schema, &batch.array) }.unwrap();
+        assert_eq!(
+            columns,
+            [
+                ("values".to_string(), vec![2.0, 3.0]),
+                ("count".to_string(), vec![30.0, 40.0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_schemas() {
+        let text = export("u", "names", b"abc".to_vec(), 1, None, 0);
+        assert_eq!(
+            read(&text),
+            Err("Unsupported Arrow type `u`; expected a numeric array.".into())
+        );
+        let mut short = export_f64(&[1.0], None, 0);
+        short.array.n_buffers = 1;
+        assert_eq!(
+            read(&short),
+            Err("Arrow array is not a primitive numeric array.".into())
+        );
+        // Columns of a batch must be numeric too.
+        let nested = export_struct(vec![export("+l", "list", vec![0; 8], 4, None, 0)], 0, 1);
+        match unsafe { struct_to_columns(&nested.schema, &nested.array) } {
+            Err(msg) => assert!(msg.contains("`+l`"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+    }
+}
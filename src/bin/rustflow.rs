@@ -0,0 +1,166 @@
+//! Command-line batch routing.
+//!
+//! ```text
+//! rustflow run --config network.toml --inflow inflow.csv --output outflow.csv \
+//!     [--report report.html] [--restart state.json] [--save-state state.json]
+//! ```
+
+use rustflow::config::read_config;
+use rustflow::io::csv::{read_csv, write_csv, CsvOptions, HydrographTable};
+use rustflow::io::report::RunReport;
+use rustflow::log::capture_warnings;
+use rustflow::network::{AdaptiveStep, Checkpoint, Network};
+use rustflow::progress::Silent;
+use rustflow::Error;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: rustflow run --config <network.toml|json> --inflow <inflow.csv> --output <outflow.csv>
+                    [--report <report.json|html>] [--restart <state.json>]
+                    [--save-state <state.json>]
+
+Routes the inflow series through the network described in the configuration
+file and writes the outflow of every node to a CSV file.
+
+The configuration holds a `nodes` array and an optional top-level
+`time_step` in seconds (inferred from the first two inflow rows when absent);
+each node's `inflow` field names a column of the inflow CSV. An optional
+`adaptive_step` table (`tolerance`, `max_refinement`, `max_coarsening`)
+adapts the routing time step to the flows while still reporting every
+`time_step`.
+
+With `--report`, a run report (configuration hash, parameters, mass balance
+per node, peaks and warnings) is written as HTML for a `.html` path and as
+JSON otherwise.
+
+With `--restart`, the run continues from a network state saved by an
+earlier run instead of the initial conditions, and `--save-state` saves the
+state at the end of the run. An optional `checkpoint` table (`path`,
+`every`) saves the state every `every` steps as the run proceeds; to
+restart an interrupted run, pass the inflow rows after the last checkpoint
+with `--restart`.";
+
+struct Args {
+    config: PathBuf,
+    inflow: PathBuf,
+    output: PathBuf,
+    report: Option<PathBuf>,
+    restart: Option<PathBuf>,
+    save_state: Option<PathBuf>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    if args.next().as_deref() != Some("run") {
+        return Err("expected the `run` command".into());
+    }
+    let (mut config, mut inflow, mut output, mut report) = (None, None, None, None);
+    let (mut restart, mut save_state) = (None, None);
+    while let Some(flag) = args.next() {
+        let slot = match flag.as_str() {
+            "--config" => &mut config,
+            "--inflow" => &mut inflow,
+            "--output" => &mut output,
+            "--report" => &mut report,
+            "--restart" => &mut restart,
+            "--save-state" => &mut save_state,
+            _ => return Err(format!("unknown argument `{flag}`")),
+        };
+        *slot = Some(PathBuf::from(
+            args.next()
+                .ok_or_else(|| format!("`{flag}` needs a value"))?,
+        ));
+    }
+    Ok(Args {
+        config: config.ok_or("missing `--config`")?,
+        inflow: inflow.ok_or("missing `--inflow`")?,
+        output: output.ok_or("missing `--output`")?,
+        report,
+        restart,
+        save_state,
+    })
+}
+
+fn run(args: &Args) -> rustflow::Result<()> {
+    let config = read_config(&args.config)?;
+    let mut network = Network::from_value(&config)?;
+
+    let options = CsvOptions::default();
+    let inflow_table = read_csv(&args.inflow, &options)?;
+    let dt = match config.opt_f64_field("time_step")? {
+        Some(dt) => dt,
+        None => inflow_table.time_step().ok_or_else(|| {
+            Error::Data(
+                "`time_step` is not configured and the inflow has fewer than 2 rows.".into(),
+            )
+        })?,
+    };
+    let inflows: BTreeMap<String, Vec<f64>> = inflow_table.columns.iter().cloned().collect();
+    let step = AdaptiveStep::from_value(&config)?.unwrap_or(AdaptiveStep::FIXED);
+    network.set_checkpoint(Checkpoint::from_value(&config)?)?;
+    if let Some(path) = &args.restart {
+        network.load_state(path)?;
+    }
+    let (result, warnings) =
+        capture_warnings(|| network.run_adaptive(&inflows, dt, &step, &Silent));
+    let result = result?;
+    if let Some(path) = &args.save_state {
+        network.save_state(path)?;
+    }
+
+    if let Some(path) = &args.report {
+        let mut report = RunReport::new(
+            "rustflow network simulation",
+            &network.to_value(),
+            &result,
+            dt,
+            warnings,
+        );
+        report.start = inflow_table.times.first().copied();
+        report.parameters = vec![
+            (
+                "config_file".to_string(),
+                args.config.display().to_string().into(),
+            ),
+            (
+                "inflow_file".to_string(),
+                args.inflow.display().to_string().into(),
+            ),
+        ];
+        if let Some(path) = &args.restart {
+            report
+                .parameters
+                .push(("restart".to_string(), path.display().to_string().into()));
+        }
+        if let Some(adaptive) = config.get("adaptive_step") {
+            report
+                .parameters
+                .push(("adaptive_step".to_string(), adaptive.clone()));
+        }
+        report.write(path)?;
+    }
+
+    let output = HydrographTable {
+        times: inflow_table.times,
+        columns: result.node_ids.into_iter().zip(result.outflow).collect(),
+    };
+    write_csv(&args.output, &output, &options)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Temporal cross-validation of calibrated parameters.
+//!
+//! The record is split into calibration and validation periods by a
+//! [`SplitScheme`]. Parameter sets are drawn by Latin hypercube sampling,
+//! and the network is run once for each set over the whole record, in
+//! parallel. Each split is then calibrated by choosing the set with the
+//! best likelihood over its calibration periods, and that set is scored
+//! over its validation period. The state at the start of every period
+//! therefore comes from the continuous run, so no period needs its own
+//! warm-up.
+
+use super::glue::latin_hypercube;
+use super::{check_setup, simulate, Likelihood, Parameter};
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+use crate::progress::{Progress, Tracker};
+use crate::random::Rng;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// How the record is split into calibration and validation periods.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplitScheme {
+    /// Klemeš's split-sample test: the first `fraction` of the record
+    /// calibrates and the rest validates, then the other way round.
+    SplitSample { fraction: f64 },
+    /// `folds` contiguous blocks; each is validated in turn with the
+    /// others calibrating.
+    KFold { folds: usize },
+    /// Calibration windows of `calibration` steps, each validated on the
+    /// `validation` steps that follow, advanced by `step` steps.
+    Rolling {
+        calibration: usize,
+        validation: usize,
+        step: usize,
+    },
+}
+
+impl SplitScheme {
+    /// Splits of the steps in `record`.
+    pub fn splits(&self, record: Range<usize>) -> Result<Vec<Split>> {
+        let (start, end) = (record.start, record.end);
+        let len = end.saturating_sub(start);
+        let too_short = || {
+            Error::InvalidParameter(format!(
+                "A record of {len} steps is too short for these splits."
+            ))
+        };
+        match *self {
+            SplitScheme::SplitSample { fraction } => {
+                if !(fraction > 0.0 && fraction < 1.0) {
+                    return Err(Error::InvalidParameter(
+                        "The split fraction must lie strictly between 0 and 1.".into(),
+                    ));
+                }
+                let middle = start + (fraction * len as f64).round() as usize;
+                if middle - start < 2 || end - middle < 2 {
+                    return Err(too_short());
+                }
+                Ok(vec![
+                    Split {
+                        calibration: std::iter::once(start..middle).collect(),
+                        validation: middle..end,
+                    },
+                    Split {
+                        calibration: std::iter::once(middle..end).collect(),
+                        validation: start..middle,
+                    },
+                ])
+            }
+            SplitScheme::KFold { folds } => {
+                if folds < 2 {
+                    return Err(Error::InvalidParameter(
+                        "At least two folds are needed.".into(),
+                    ));
+                }
+                if len < 2 * folds {
+                    return Err(too_short());
+                }
+                let bound = |i: usize| start + i * len / folds;
+                Ok((0..folds)
+                    .map(|i| Split {
+                        calibration: [start..bound(i), bound(i + 1)..end]
+                            .into_iter()
+                            .filter(|r| !r.is_empty())
+                            .collect(),
+                        validation: bound(i)..bound(i + 1),
+                    })
+                    .collect())
+            }
+            SplitScheme::Rolling {
+                calibration,
+                validation,
+                step,
+            } => {
+                if calibration < 2 || validation < 2 || step == 0 {
+                    return Err(Error::InvalidParameter(
+                        "Rolling windows need at least two calibration and two validation \
+                         steps, and a positive step."
+                            .into(),
+                    ));
+                }
+                let splits: Vec<Split> = (start..)
+                    .step_by(step)
+                    .take_while(|s| s + calibration + validation <= end)
+                    .map(|s| Split {
+                        calibration: std::iter::once(s..s + calibration).collect(),
+                        validation: s + calibration..s + calibration + validation,
+                    })
+                    .collect();
+                if splits.is_empty() {
+                    return Err(too_short());
+                }
+                Ok(splits)
+            }
+        }
+    }
+}
+
+/// Calibration periods and validation period of one split, as step ranges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Split {
+    pub calibration: Vec<Range<usize>>,
+    pub validation: Range<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CrossValidationOptions {
+    pub scheme: SplitScheme,
+    pub samples: usize,
+    pub likelihood: Likelihood,
+    /// Steps at the start of the record left out of every period.
+    pub warm_up: usize,
+    pub seed: Option<u64>,
+}
+
+/// Calibrated set and scores of one split.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fold {
+    pub split: Split,
+    /// Best parameter values over the calibration periods.
+    pub parameters: Vec<f64>,
+    pub calibration: f64,
+    pub validation: f64,
+}
+
+impl Fold {
+    /// Loss of likelihood from calibration to validation.
+    pub fn degradation(&self) -> f64 {
+        self.calibration - self.validation
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossValidationResult {
+    pub folds: Vec<Fold>,
+    /// Number of parameter sets tried.
+    pub samples: usize,
+}
+
+impl CrossValidationResult {
+    /// Mean calibration and validation likelihood and mean and largest
+    /// degradation over the folds.
+    pub fn summary(&self) -> (f64, f64, f64, f64) {
+        let n = self.folds.len() as f64;
+        let mean = |f: &dyn Fn(&Fold) -> f64| self.folds.iter().map(f).sum::<f64>() / n;
+        (
+            mean(&|f| f.calibration),
+            mean(&|f| f.validation),
+            mean(&Fold::degradation),
+            self.folds
+                .iter()
+                .map(Fold::degradation)
+                .fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+}
+
+/// Values of `series` over `ranges`, concatenated.
+fn gather(series: &[f64], ranges: &[Range<usize>]) -> Vec<f64> {
+    ranges
+        .iter()
+        .flat_map(|r| series[r.clone()].iter().copied())
+        .collect()
+}
+
+/// Cross-validates the calibration of a network configuration against the
+/// observed outflow of `node`. Each completed model run is reported to
+/// `progress`.
+#[allow(clippy::too_many_arguments)]
+pub fn cross_validate(
+    config: &Value,
+    parameters: &[Parameter],
+    series: &BTreeMap<String, Vec<f64>>,
+    dt: f64,
+    node: &str,
+    observed: &[f64],
+    options: &CrossValidationOptions,
+    progress: &dyn Progress,
+) -> Result<CrossValidationResult> {
+    if options.samples == 0 {
+        return Err(Error::InvalidParameter(
+            "`samples` must be positive.".into(),
+        ));
+    }
+    let splits = options
+        .scheme
+        .splits(options.warm_up.min(observed.len())..observed.len())?;
+    check_setup(config, parameters, node)?;
+
+    let ranges: Vec<(f64, f64)> = parameters.iter().map(|p| (p.low, p.high)).collect();
+    let mut rng = Rng::from_seed(options.seed);
+    let samples = latin_hypercube(options.samples, &ranges, &mut rng);
+    let observed_periods: Vec<(Vec<f64>, Vec<f64>)> = splits
+        .iter()
+        .map(|s| {
+            (
+                gather(observed, &s.calibration),
+                observed[s.validation.clone()].to_vec(),
+            )
+        })
+        .collect();
+    let score = |simulated: &[f64], observed: &[f64]| {
+        options
+            .likelihood
+            .evaluate(simulated, observed)
+            .ok()
+            .filter(|v| v.is_finite())
+            .unwrap_or(f64::NAN)
+    };
+    let tracker = Tracker::new(progress, samples.len());
+    // Calibration and validation likelihood of every set in every split.
+    let scores = par_map(&samples, |values| -> Option<Vec<(f64, f64)>> {
+        if tracker.is_cancelled() {
+            return None;
+        }
+        let simulated = simulate(config, parameters, values, series, dt, node);
+        tracker.advance();
+        let simulated = simulated.filter(|s| s.len() == observed.len())?;
+        Some(
+            splits
+                .iter()
+                .zip(&observed_periods)
+                .map(|(split, (cal_obs, val_obs))| {
+                    (
+                        score(&gather(&simulated, &split.calibration), cal_obs),
+                        score(&simulated[split.validation.clone()], val_obs),
+                    )
+                })
+                .collect(),
+        )
+    });
+    tracker.check()?;
+
+    let folds = splits
+        .into_iter()
+        .enumerate()
+        .map(|(k, split)| {
+            let best = scores
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| s.as_ref().map(|s| (i, s[k])))
+                .filter(|(_, (cal, _))| !cal.is_nan())
+                .max_by(|a, b| a.1 .0.total_cmp(&b.1 .0));
+            match best {
+                Some((i, (calibration, validation))) => Ok(Fold {
+                    split,
+                    parameters: samples[i].clone(),
+                    calibration,
+                    validation,
+                }),
+                None => Err(Error::Data(format!(
+                    "No parameter set could be scored over the calibration periods of split {}.",
+                    k + 1
+                ))),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(CrossValidationResult {
+        folds,
+        samples: samples.len(),
+    })
+}
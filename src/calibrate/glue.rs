@@ -0,0 +1,347 @@
+//! Generalised Likelihood Uncertainty Estimation (Beven & Binley, 1992).
+//!
+//! Parameter sets are drawn by Latin hypercube sampling, the network is run
+//! for each set in parallel, and sets whose likelihood reaches the
+//! threshold are kept as behavioural. Prediction bounds are
+//! likelihood-weighted quantiles of the behavioural simulations, with
+//! weights proportional to `likelihood - threshold`.
+
+use super::{check_setup, simulate, Likelihood, Parameter};
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+use crate::progress::{Progress, Tracker};
+use crate::random::Rng;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug)]
+pub struct GlueOptions {
+    pub samples: usize,
+    pub likelihood: Likelihood,
+    pub threshold: f64,
+    /// Lower and upper probabilities of the prediction bounds.
+    pub bounds: (f64, f64),
+    pub seed: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlueResult {
+    /// Sampled parameter values, `samples[i][j]` for set `i`, parameter `j`.
+    pub samples: Vec<Vec<f64>>,
+    /// Likelihood of each set; NaN if the run failed.
+    pub likelihood: Vec<f64>,
+    /// Indices of the behavioural sets.
+    pub behavioral: Vec<usize>,
+    pub lower: Vec<f64>,
+    pub median: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+/// Latin hypercube sample of `n` points within `ranges`.
+pub fn latin_hypercube(n: usize, ranges: &[(f64, f64)], rng: &mut Rng) -> Vec<Vec<f64>> {
+    let mut samples = vec![Vec::with_capacity(ranges.len()); n];
+    let mut strata: Vec<usize> = (0..n).collect();
+    for &(low, high) in ranges {
+        rng.shuffle(&mut strata);
+        for (sample, &stratum) in samples.iter_mut().zip(&strata) {
+            let u = (stratum as f64 + rng.uniform()) / n as f64;
+            sample.push(low + u * (high - low));
+        }
+    }
+    samples
+}
+
+/// Quantile of `values` weighted by `weights`, interpolating between the
+/// midpoints of the cumulative weights.
+pub fn weighted_quantile(values: &[f64], weights: &[f64], p: f64) -> f64 {
+    let mut pairs: Vec<(f64, f64)> = values
+        .iter()
+        .zip(weights)
+        .filter(|(v, w)| !v.is_nan() && **w > 0.0)
+        .map(|(&v, &w)| (v, w))
+        .collect();
+    if pairs.is_empty() {
+        return f64::NAN;
+    }
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let total: f64 = pairs.iter().map(|p| p.1).sum();
+    let mut cumulative = 0.0;
+    let positions: Vec<f64> = pairs
+        .iter()
+        .map(|&(_, w)| {
+            let mid = (cumulative + 0.5 * w) / total;
+            cumulative += w;
+            mid
+        })
+        .collect();
+    let xs: Vec<f64> = pairs.iter().map(|p| p.0).collect();
+    if p <= positions[0] {
+        return xs[0];
+    }
+    if p >= positions[positions.len() - 1] {
+        return xs[xs.len() - 1];
+    }
+    let i = positions.partition_point(|&q| q < p);
+    let t = (p - positions[i - 1]) / (positions[i] - positions[i - 1]);
+    xs[i - 1] + t * (xs[i] - xs[i - 1])
+}
+
+/// Runs GLUE on a network configuration. The outflow of `node` is compared
+/// with `observed`. Each completed model run is reported to `progress`.
+#[allow(clippy::too_many_arguments)]
+pub fn glue(
+    config: &Value,
+    parameters: &[Parameter],
+    series: &BTreeMap<String, Vec<f64>>,
+    dt: f64,
+    node: &str,
+    observed: &[f64],
+    options: &GlueOptions,
+    progress: &dyn Progress,
+) -> Result<GlueResult> {
+    if options.samples == 0 {
+        return Err(Error::InvalidParameter(
+            "`samples` must be positive.".into(),
+        ));
+    }
+    check_setup(config, parameters, node)?;
+
+    let ranges: Vec<(f64, f64)> = parameters.iter().map(|p| (p.low, p.high)).collect();
+    let mut rng = Rng::from_seed(options.seed);
+    let samples = latin_hypercube(options.samples, &ranges, &mut rng);
+    let tracker = Tracker::new(progress, samples.len());
+    let runs = par_map(&samples, |values| -> Option<(f64, Vec<f64>)> {
+        if tracker.is_cancelled() {
+            return None;
+        }
+        let simulated = simulate(config, parameters, values, series, dt, node);
+        tracker.advance();
+        let simulated = simulated?;
+        let likelihood = options.likelihood.evaluate(&simulated, observed).ok()?;
+        Some((likelihood, simulated))
+    });
+    tracker.check()?;
+
+    let likelihood: Vec<f64> = runs
+        .iter()
+        .map(|run| run.as_ref().map_or(f64::NAN, |r| r.0))
+        .collect();
+    let behavioral: Vec<usize> = (0..runs.len())
+        .filter(|&i| likelihood[i] >= options.threshold)
+        .collect();
+    let weights: Vec<f64> = behavioral
+        .iter()
+        .map(|&i| likelihood[i] - options.threshold)
+        .collect();
+    // All sets exactly at the threshold: weight them equally.
+    let weights = if weights.iter().all(|&w| w == 0.0) {
+        vec![1.0; weights.len()]
+    } else {
+        weights
+    };
+    let (mut lower, mut median, mut upper) = (Vec::new(), Vec::new(), Vec::new());
+    let mut column = Vec::with_capacity(behavioral.len());
+    for t in 0..observed.len() {
+        column.clear();
+        column.extend(
+            behavioral
+                .iter()
+                .map(|&i| runs[i].as_ref().map_or(f64::NAN, |r| r.1[t])),
+        );
+        lower.push(weighted_quantile(&column, &weights, options.bounds.0));
+        median.push(weighted_quantile(&column, &weights, 0.5));
+        upper.push(weighted_quantile(&column, &weights, options.bounds.1));
+    }
+    Ok(GlueResult {
+        samples,
+        likelihood,
+        behavioral,
+        lower,
+        median,
+        upper,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::toml;
+    use crate::network::Network;
+    use crate::progress::Silent;
+
+    const REACH: &str = r#"
+[[nodes]]
+id = "reach"
+type = "muskingum"
+k = 7200.0
+x = 0.2
+inflow = "q"
+"#;
+
+    const DT: f64 = 3600.0;
+
+    fn inputs() -> BTreeMap<String, Vec<f64>> {
+        let q = (0..48)
+            .map(|t| 5.0 + 30.0 * (-((t as f64 - 12.0) / 4.0).powi(2)).exp())
+            .collect();
+        BTreeMap::from([("q".to_string(), q)])
+    }
+
+    /// Outflow of the reach with its true `k` of 7200 s.
+    fn observed(config: &Value) -> Vec<f64> {
+        let result = Network::from_value(config)
+            .unwrap()
+            .run(&inputs(), DT)
+            .unwrap();
+        result.outflow_of("reach").unwrap().to_vec()
+    }
+
+    fn options(samples: usize) -> GlueOptions {
+        GlueOptions {
+            samples,
+            likelihood: Likelihood::Nse,
+            threshold: 0.5,
+            bounds: (0.05, 0.95),
+            seed: Some(7),
+        }
+    }
+
+    #[test]
+    fn latin_hypercube_fills_every_stratum() {
+        let mut rng = Rng::seed_from_u64(1);
+        let samples = latin_hypercube(10, &[(0.0, 1.0), (100.0, 200.0)], &mut rng);
+        assert_eq!(samples.len(), 10);
+        for (j, (low, high)) in [(0.0, 1.0), (100.0, 200.0)].into_iter().enumerate() {
+            let mut strata: Vec<usize> = samples
+                .iter()
+                .map(|s| ((s[j] - low) / (high - low) * 10.0) as usize)
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..10).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn weighted_quantile_interpolates_between_midpoints() {
+        let values = [4.0, 1.0, 3.0, 2.0];
+        let equal = [1.0; 4];
+        assert_eq!(weighted_quantile(&values, &equal, 0.5), 2.5);
+        assert_eq!(weighted_quantile(&values, &equal, 0.125), 1.0);
+        assert_eq!(weighted_quantile(&values, &equal, 0.0), 1.0);
+        assert_eq!(weighted_quantile(&values, &equal, 1.0), 4.0);
+        // Zero weights and NaN values are ignored.
+        assert_eq!(
+            weighted_quantile(&[1.0, f64::NAN, 9.0], &[1.0, 1.0, 0.0], 0.5),
+            1.0
+        );
+        assert!(weighted_quantile(&[], &[], 0.5).is_nan());
+    }
+
+    #[test]
+    fn behavioural_sets_bracket_the_observations() {
+        let config = toml::parse(REACH).unwrap();
+        let observed = observed(&config);
+        let parameters = [Parameter::new("reach.k", 3600.0, 9000.0).unwrap()];
+        let result = glue(
+            &config,
+            &parameters,
+            &inputs(),
+            DT,
+            "reach",
+            &observed,
+            &options(40),
+            &Silent,
+        )
+        .unwrap();
+        assert_eq!(result.samples.len(), 40);
+        assert!(result.likelihood.iter().all(|l| l.is_finite()));
+        assert!(!result.behavioral.is_empty());
+        assert!(result
+            .behavioral
+            .iter()
+            .all(|&i| result.likelihood[i] >= 0.5));
+        // The most likely set lies within a stratum or two of the true `k`.
+        let best = (0..40)
+            .max_by(|&a, &b| result.likelihood[a].total_cmp(&result.likelihood[b]))
+            .unwrap();
+        assert!((result.samples[best][0] - 7200.0).abs() < 2.0 * 5400.0 / 40.0);
+        assert!(result.likelihood[best] > 0.99);
+        for (t, &q) in observed.iter().enumerate() {
+            assert!(result.lower[t] <= result.median[t] && result.median[t] <= result.upper[t]);
+            assert!(result.lower[t] - 1e-9 <= q && q <= result.upper[t] + 1e-9);
+        }
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let config = toml::parse(REACH).unwrap();
+        let observed = observed(&config);
+        let parameters = [
+            Parameter::new("reach.k", 3600.0, 10800.0).unwrap(),
+            Parameter::new("reach.x", 0.0, 0.25).unwrap(),
+        ];
+        let run = || {
+            glue(
+                &config,
+                &parameters,
+                &inputs(),
+                DT,
+                "reach",
+                &observed,
+                &options(16),
+                &Silent,
+            )
+            .unwrap()
+        };
+        let first = run();
+        let second = run();
+        assert_eq!(second.samples, first.samples);
+        let bits = |r: &GlueResult| r.likelihood.iter().map(|l| l.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&second), bits(&first));
+        // Sets with a negative c0 at this time step fail and score NaN.
+        assert!(first.likelihood.iter().any(|l| l.is_nan()));
+        assert!(first
+            .behavioral
+            .iter()
+            .all(|&i| !first.likelihood[i].is_nan()));
+    }
+
+    #[test]
+    fn rejects_invalid_setups() {
+        let config = toml::parse(REACH).unwrap();
+        let observed = observed(&config);
+        let k = [Parameter::new("reach.k", 3600.0, 10800.0).unwrap()];
+        let run = |parameters: &[Parameter], node: &str, samples: usize| {
+            glue(
+                &config,
+                parameters,
+                &inputs(),
+                DT,
+                node,
+                &observed,
+                &options(samples),
+                &Silent,
+            )
+        };
+        assert!(matches!(
+            run(&k, "reach", 0),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            run(&k, "outlet", 4),
+            Err(Error::InvalidParameter(_))
+        ));
+        let missing = [Parameter::new("dam.k", 1.0, 2.0).unwrap()];
+        assert!(matches!(
+            run(&missing, "reach", 4),
+            Err(Error::InvalidParameter(_))
+        ));
+        let negative = [Parameter::new("reach.k", -10.0, -1.0).unwrap()];
+        assert!(matches!(
+            run(&negative, "reach", 4),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(Parameter::new("k", 1.0, 2.0).is_err());
+        assert!(Parameter::new("reach.k", 2.0, 1.0).is_err());
+    }
+}
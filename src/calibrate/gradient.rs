@@ -0,0 +1,192 @@
+//! Gradients of routed outflow with respect to parameters, for
+//! gradient-based calibration and variational data assimilation.
+//!
+//! Muskingum routing is linear in its inflow, so its sensitivities are
+//! propagated exactly alongside the outflow (forward sensitivity
+//! equations). Other elements of a network are differentiated by central
+//! finite differences.
+
+use super::sensitivity::Objective;
+use super::{check_setup, simulate, Parameter};
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+use crate::reach_routing::muskingum::{check_muskingum, muskingum_coefficients};
+use std::collections::BTreeMap;
+
+/// Outflow of a Muskingum reach and its derivatives with respect to `k`
+/// (per second) and `x` at each step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MuskingumGradient {
+    pub outflow: Vec<f64>,
+    pub d_k: Vec<f64>,
+    pub d_x: Vec<f64>,
+}
+
+impl MuskingumGradient {
+    /// Sum of squared errors against `observed` and its gradient with
+    /// respect to `k` and `x`. Steps where either value is NaN are skipped.
+    pub fn sse(&self, observed: &[f64]) -> Result<(f64, [f64; 2])> {
+        if observed.len() != self.outflow.len() {
+            return Err(Error::Data(format!(
+                "Got {} observations for {} routed values.",
+                observed.len(),
+                self.outflow.len()
+            )));
+        }
+        let (mut sse, mut gradient) = (0.0, [0.0; 2]);
+        for (t, (&q, &obs)) in self.outflow.iter().zip(observed).enumerate() {
+            if q.is_nan() || obs.is_nan() {
+                continue;
+            }
+            let error = q - obs;
+            sse += error * error;
+            gradient[0] += 2.0 * error * self.d_k[t];
+            gradient[1] += 2.0 * error * self.d_x[t];
+        }
+        Ok((sse, gradient))
+    }
+}
+
+/// Derivatives of `(c0, c1, c2)` with respect to the sub-reach `k` and to
+/// `x`.
+fn coefficient_derivatives(dt: f64, k: f64, x: f64) -> ([f64; 3], [f64; 3]) {
+    let den = 2.0 * k * (1.0 - x) + dt;
+    let (d_den_k, d_den_x) = (2.0 * (1.0 - x), -2.0 * k);
+    let numerators = [dt - 2.0 * k * x, dt + 2.0 * k * x, 2.0 * k * (1.0 - x) - dt];
+    let d_num_k = [-2.0 * x, 2.0 * x, 2.0 * (1.0 - x)];
+    let d_num_x = [-2.0 * k, 2.0 * k, -2.0 * k];
+    let quotient = |num: f64, d_num: f64, d_den: f64| (d_num * den - num * d_den) / (den * den);
+    let mut d_k = [0.0; 3];
+    let mut d_x = [0.0; 3];
+    for i in 0..3 {
+        d_k[i] = quotient(numerators[i], d_num_k[i], d_den_k);
+        d_x[i] = quotient(numerators[i], d_num_x[i], d_den_x);
+    }
+    (d_k, d_x)
+}
+
+/// Routes `inflow` as [`muskingum_cascade_rs`] does and differentiates the
+/// outflow exactly with respect to the whole-reach `k` (s) and `x`.
+///
+/// [`muskingum_cascade_rs`]: crate::reach_routing::muskingum::muskingum_cascade_rs
+pub fn muskingum_gradient_rs(
+    inflow: &[f64],
+    dt: f64,
+    k: f64,
+    x: f64,
+    sub_reaches: usize,
+    initial_outflow: Option<f64>,
+) -> Result<MuskingumGradient> {
+    check_muskingum(inflow, dt, k, x, sub_reaches as i64, true)?;
+    let k_sub = k / sub_reaches as f64;
+    let (c0, c1, c2) = muskingum_coefficients(dt, k_sub, x);
+    let (dc_k, dc_x) = coefficient_derivatives(dt, k_sub, x);
+    // Each sub-reach uses k / sub_reaches.
+    let dc_k = dc_k.map(|d| d / sub_reaches as f64);
+    let n = inflow.len();
+    let mut q_in = inflow.to_vec();
+    let mut dq_k = vec![0.0; n];
+    let mut dq_x = vec![0.0; n];
+    for reach in 0..sub_reaches {
+        let start = if reach == 0 { initial_outflow } else { None };
+        let mut out = vec![start.unwrap_or(q_in[0]); n];
+        let mut out_k = vec![0.0; n];
+        let mut out_x = vec![0.0; n];
+        for t in 1..n {
+            out[t] = c0 * q_in[t] + c1 * q_in[t - 1] + c2 * out[t - 1];
+            out_k[t] = dc_k[0] * q_in[t]
+                + dc_k[1] * q_in[t - 1]
+                + dc_k[2] * out[t - 1]
+                + c0 * dq_k[t]
+                + c1 * dq_k[t - 1]
+                + c2 * out_k[t - 1];
+            out_x[t] = dc_x[0] * q_in[t]
+                + dc_x[1] * q_in[t - 1]
+                + dc_x[2] * out[t - 1]
+                + c0 * dq_x[t]
+                + c1 * dq_x[t - 1]
+                + c2 * out_x[t - 1];
+        }
+        q_in = out;
+        dq_k = out_k;
+        dq_x = out_x;
+    }
+    Ok(MuskingumGradient {
+        outflow: q_in,
+        d_k: dq_k,
+        d_x: dq_x,
+    })
+}
+
+/// Objective of a network run and its central finite-difference gradient.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkGradient {
+    pub objective: f64,
+    /// Derivative of the objective with respect to each parameter.
+    pub gradient: Vec<f64>,
+    /// Outflow of the node, and its derivative with respect to each
+    /// parameter at each step (`jacobian[j][t]`).
+    pub outflow: Vec<f64>,
+    pub jacobian: Vec<Vec<f64>>,
+}
+
+/// Differentiates the outflow of `node`, and `objective` of it, with
+/// respect to the `parameters` at `values` by central differences with
+/// steps of `relative_step` times each value (or times one for zero
+/// values). The perturbed runs are made in parallel.
+#[allow(clippy::too_many_arguments)]
+pub fn network_gradient(
+    config: &Value,
+    parameters: &[Parameter],
+    values: &[f64],
+    series: &BTreeMap<String, Vec<f64>>,
+    dt: f64,
+    node: &str,
+    objective: &Objective,
+    relative_step: f64,
+) -> Result<NetworkGradient> {
+    if relative_step.is_nan() || relative_step <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The finite-difference step must be positive.".into(),
+        ));
+    }
+    check_setup(config, parameters, node)?;
+    let run = |values: &[f64]| simulate(config, parameters, values, series, dt, node);
+    let outflow = run(values).ok_or_else(|| {
+        Error::InvalidParameter("The parameter values give an invalid network.".into())
+    })?;
+    let steps: Vec<f64> = values
+        .iter()
+        .map(|v| relative_step * v.abs().max(1.0))
+        .collect();
+    let points: Vec<Vec<f64>> = (0..parameters.len())
+        .flat_map(|j| {
+            [1.0, -1.0].map(|sign| {
+                let mut shifted = values.to_vec();
+                shifted[j] += sign * steps[j];
+                shifted
+            })
+        })
+        .collect();
+    let runs = par_map(&points, |point| run(point));
+    let mut gradient = Vec::with_capacity(parameters.len());
+    let mut jacobian = Vec::with_capacity(parameters.len());
+    for (j, pair) in runs.chunks(2).enumerate() {
+        let (Some(up), Some(down)) = (&pair[0], &pair[1]) else {
+            return Err(Error::InvalidParameter(format!(
+                "Parameter `{}.{}` gives an invalid network when perturbed.",
+                parameters[j].node, parameters[j].field
+            )));
+        };
+        let h = 2.0 * steps[j];
+        gradient.push((objective.evaluate(up, dt) - objective.evaluate(down, dt)) / h);
+        jacobian.push(up.iter().zip(down).map(|(a, b)| (a - b) / h).collect());
+    }
+    Ok(NetworkGradient {
+        objective: objective.evaluate(&outflow, dt),
+        gradient,
+        outflow,
+        jacobian,
+    })
+}
@@ -0,0 +1,135 @@
+//! Goodness-of-fit metrics between simulated and observed series. Pairs in
+//! which either value is NaN are skipped.
+
+use crate::error::{Error, Result};
+
+fn pairs(simulated: &[f64], observed: &[f64]) -> Result<Vec<(f64, f64)>> {
+    if simulated.len() != observed.len() {
+        return Err(Error::Data(format!(
+            "Simulated ({}) and observed ({}) series differ in length.",
+            simulated.len(),
+            observed.len()
+        )));
+    }
+    let pairs: Vec<_> = simulated
+        .iter()
+        .zip(observed)
+        .filter(|(s, o)| !s.is_nan() && !o.is_nan())
+        .map(|(&s, &o)| (s, o))
+        .collect();
+    if pairs.len() < 2 {
+        return Err(Error::Data(
+            "At least two valid simulated/observed pairs are required.".into(),
+        ));
+    }
+    Ok(pairs)
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    sum / n as f64
+}
+
+/// Nash–Sutcliffe efficiency (1 is a perfect fit).
+pub fn nse(simulated: &[f64], observed: &[f64]) -> Result<f64> {
+    let pairs = pairs(simulated, observed)?;
+    let mean_obs = mean(pairs.iter().map(|p| p.1));
+    let error: f64 = pairs.iter().map(|(s, o)| (s - o).powi(2)).sum();
+    let variance: f64 = pairs.iter().map(|(_, o)| (o - mean_obs).powi(2)).sum();
+    Ok(1.0 - error / variance)
+}
+
+/// Nash–Sutcliffe efficiency of the logarithms of the flows, which weights
+/// low flows. An offset of 1% of the mean observed flow keeps zero flows
+/// finite (Pushpalatha et al., 2012); negative flows count as zero.
+pub fn log_nse(simulated: &[f64], observed: &[f64]) -> Result<f64> {
+    let pairs = pairs(simulated, observed)?;
+    let offset = 0.01 * mean(pairs.iter().map(|p| p.1.max(0.0)));
+    let log = |q: f64| (q.max(0.0) + offset).ln();
+    let logged: (Vec<f64>, Vec<f64>) = pairs.iter().map(|&(s, o)| (log(s), log(o))).unzip();
+    nse(&logged.0, &logged.1)
+}
+
+/// Kling–Gupta efficiency (2009 form; 1 is a perfect fit).
+pub fn kge(simulated: &[f64], observed: &[f64]) -> Result<f64> {
+    let pairs = pairs(simulated, observed)?;
+    let mean_sim = mean(pairs.iter().map(|p| p.0));
+    let mean_obs = mean(pairs.iter().map(|p| p.1));
+    let (mut cov, mut var_sim, mut var_obs) = (0.0, 0.0, 0.0);
+    for (s, o) in &pairs {
+        cov += (s - mean_sim) * (o - mean_obs);
+        var_sim += (s - mean_sim).powi(2);
+        var_obs += (o - mean_obs).powi(2);
+    }
+    let r = cov / (var_sim * var_obs).sqrt();
+    let alpha = (var_sim / var_obs).sqrt();
+    let beta = mean_sim / mean_obs;
+    Ok(1.0 - ((r - 1.0).powi(2) + (alpha - 1.0).powi(2) + (beta - 1.0).powi(2)).sqrt())
+}
+
+/// Root-mean-square error, in the units of the series.
+pub fn rmse(simulated: &[f64], observed: &[f64]) -> Result<f64> {
+    let pairs = pairs(simulated, observed)?;
+    Ok(mean(pairs.iter().map(|(s, o)| (s - o).powi(2))).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OBSERVED: [f64; 5] = [1.0, 3.0, 5.0, 3.0, 1.0];
+
+    #[test]
+    fn perfect_fits_score_one() {
+        assert_eq!(nse(&OBSERVED, &OBSERVED).unwrap(), 1.0);
+        assert_eq!(kge(&OBSERVED, &OBSERVED).unwrap(), 1.0);
+        assert_eq!(rmse(&OBSERVED, &OBSERVED).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn matches_known_values() {
+        // The mean observed flow scores zero.
+        assert_eq!(nse(&[2.6; 5], &OBSERVED).unwrap(), 0.0);
+        let simulated = [2.0, 3.0, 4.0, 3.0, 2.0];
+        // Squared error 3 against an observed sum of squares of 11.2.
+        assert!((nse(&simulated, &OBSERVED).unwrap() - (1.0 - 3.0 / 11.2)).abs() < 1e-12);
+        assert!((rmse(&simulated, &OBSERVED).unwrap() - 0.6_f64.sqrt()).abs() < 1e-12);
+        // Doubling the flows keeps r = 1 and doubles the spread and bias.
+        let doubled: Vec<f64> = OBSERVED.iter().map(|q| 2.0 * q).collect();
+        assert!((kge(&doubled, &OBSERVED).unwrap() - (1.0 - 2.0_f64.sqrt())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn log_nse_weights_low_flows() {
+        assert_eq!(log_nse(&OBSERVED, &OBSERVED).unwrap(), 1.0);
+        // The same absolute error costs more at low flows.
+        let low = [2.0, 3.0, 5.0, 3.0, 1.0];
+        let high = [1.0, 3.0, 6.0, 3.0, 1.0];
+        assert_eq!(
+            nse(&low, &OBSERVED).unwrap(),
+            nse(&high, &OBSERVED).unwrap()
+        );
+        assert!(log_nse(&low, &OBSERVED).unwrap() < log_nse(&high, &OBSERVED).unwrap());
+        // Zero and negative flows are offset rather than taken to -inf.
+        let dry = log_nse(&[0.0, -1.0, 5.0, 3.0, 1.0], &OBSERVED).unwrap();
+        assert!(dry.is_finite() && dry < 1.0);
+    }
+
+    #[test]
+    fn skips_missing_pairs() {
+        let simulated = [1.0, f64::NAN, 5.0, 3.0, 1.0];
+        let observed = [1.0, 3.0, 5.0, f64::NAN, 1.0];
+        assert_eq!(nse(&simulated, &observed).unwrap(), 1.0);
+        assert_eq!(rmse(&simulated, &observed).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn rejects_mismatched_or_empty_series() {
+        assert!(matches!(nse(&[1.0, 2.0], &OBSERVED), Err(Error::Data(_))));
+        assert!(matches!(
+            kge(&[1.0, f64::NAN], &[1.0, 2.0]),
+            Err(Error::Data(_))
+        ));
+        assert!(matches!(rmse(&[], &[]), Err(Error::Data(_))));
+    }
+}
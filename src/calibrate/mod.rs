@@ -0,0 +1,153 @@
+//! Model evaluation and parameter uncertainty analysis.
+//!
+//! The analyses perturb numeric fields of a network configuration, named
+//! `node.field`, and evaluate the outflow of one node.
+
+pub mod cross_validation;
+pub mod glue;
+pub mod gradient;
+pub mod metrics;
+pub mod nsga2;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod sensitivity;
+
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::network::Network;
+use metrics::{kge, log_nse, nse};
+use std::collections::BTreeMap;
+
+/// Likelihood measure used to rank parameter sets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Likelihood {
+    Nse,
+    Kge,
+    /// NSE of the log flows, sensitive to low flows.
+    LogNse,
+}
+
+impl Likelihood {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "nse" => Ok(Likelihood::Nse),
+            "kge" => Ok(Likelihood::Kge),
+            "log_nse" => Ok(Likelihood::LogNse),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown likelihood `{name}`; expected \"nse\", \"kge\" or \"log_nse\"."
+            ))),
+        }
+    }
+
+    pub fn evaluate(self, simulated: &[f64], observed: &[f64]) -> Result<f64> {
+        match self {
+            Likelihood::Nse => nse(simulated, observed),
+            Likelihood::Kge => kge(simulated, observed),
+            Likelihood::LogNse => log_nse(simulated, observed),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Likelihood::Nse => "nse",
+            Likelihood::Kge => "kge",
+            Likelihood::LogNse => "log_nse",
+        }
+    }
+}
+
+/// A calibrated parameter: `node.field` in the network configuration and
+/// its sampling range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameter {
+    pub node: String,
+    pub field: String,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Parameter {
+    /// Parses a `node.field` path.
+    pub fn new(path: &str, low: f64, high: f64) -> Result<Self> {
+        let (node, field) = path.split_once('.').ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "Parameter `{path}` must be written as `node.field`."
+            ))
+        })?;
+        if low.is_nan() || high.is_nan() || low > high {
+            return Err(Error::InvalidParameter(format!(
+                "Parameter `{path}` has an empty range [{low}, {high}]."
+            )));
+        }
+        Ok(Parameter {
+            node: node.to_string(),
+            field: field.to_string(),
+            low,
+            high,
+        })
+    }
+}
+
+fn set_field(config: &mut Value, parameter: &Parameter, value: f64) -> Result<()> {
+    let node = match config {
+        Value::Object(entries) => {
+            entries
+                .iter_mut()
+                .find(|(k, _)| k == "nodes")
+                .and_then(|(_, nodes)| match nodes {
+                    Value::Array(nodes) => nodes
+                        .iter_mut()
+                        .find(|n| n.get("id").and_then(Value::as_str) == Some(&parameter.node)),
+                    _ => None,
+                })
+        }
+        _ => None,
+    };
+    match node {
+        Some(node) => {
+            node.insert(&parameter.field, value.into());
+            Ok(())
+        }
+        None => Err(Error::InvalidParameter(format!(
+            "Parameter node `{}` is not in the network.",
+            parameter.node
+        ))),
+    }
+}
+
+/// Checks that every parameter and `node` exist and that the configuration
+/// builds, so errors surface once rather than once per sample.
+fn check_setup(config: &Value, parameters: &[Parameter], node: &str) -> Result<()> {
+    let mut probe = config.clone();
+    for parameter in parameters {
+        set_field(&mut probe, parameter, parameter.low)?;
+    }
+    if !Network::from_value(&probe)?
+        .nodes()
+        .iter()
+        .any(|n| n.id == node)
+    {
+        return Err(Error::InvalidParameter(format!(
+            "Node `{node}` is not in the network."
+        )));
+    }
+    Ok(())
+}
+
+/// Outflow of `node` with `values` substituted for `parameters`, or `None`
+/// if the parameter set gives an invalid network.
+fn simulate(
+    config: &Value,
+    parameters: &[Parameter],
+    values: &[f64],
+    series: &BTreeMap<String, Vec<f64>>,
+    dt: f64,
+    node: &str,
+) -> Option<Vec<f64>> {
+    let mut config = config.clone();
+    for (parameter, &value) in parameters.iter().zip(values) {
+        set_field(&mut config, parameter, value).ok()?;
+    }
+    let result = Network::from_value(&config).ok()?.run(series, dt).ok()?;
+    Some(result.outflow_of(node)?.to_vec())
+}
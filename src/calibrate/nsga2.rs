@@ -0,0 +1,547 @@
+//! Multi-objective calibration with NSGA-II (Deb et al., 2002).
+//!
+//! The initial population is drawn by Latin hypercube sampling. Each
+//! generation breeds as many offspring by binary tournament, simulated
+//! binary crossover and polynomial mutation, runs the network for them in
+//! parallel, and keeps the best half of parents and offspring by
+//! non-domination rank and then crowding distance. Every objective is a
+//! likelihood and is maximised; a failed run is dominated by every
+//! successful one.
+
+use super::glue::latin_hypercube;
+use super::{check_setup, simulate, Likelihood, Parameter};
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+use crate::progress::{Progress, Tracker};
+use crate::random::{powf, Rng};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug)]
+pub struct NsgaOptions {
+    pub objectives: Vec<Likelihood>,
+    pub population: usize,
+    pub generations: usize,
+    /// Probability that a pair of parents is crossed.
+    pub crossover: f64,
+    /// Distribution index of simulated binary crossover.
+    pub crossover_index: f64,
+    /// Distribution index of polynomial mutation.
+    pub mutation_index: f64,
+    pub seed: Option<u64>,
+}
+
+impl Default for NsgaOptions {
+    fn default() -> Self {
+        NsgaOptions {
+            objectives: vec![Likelihood::Nse, Likelihood::LogNse],
+            population: 100,
+            generations: 50,
+            crossover: 0.9,
+            crossover_index: 15.0,
+            mutation_index: 20.0,
+            seed: None,
+        }
+    }
+}
+
+/// Non-dominated parameter sets of the final population.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParetoFront {
+    /// Parameter values, `sets[i][j]` for set `i`, parameter `j`.
+    pub sets: Vec<Vec<f64>>,
+    /// Objective values, `scores[i][k]` for set `i`, objective `k`.
+    pub scores: Vec<Vec<f64>>,
+    /// Number of model runs made.
+    pub evaluations: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Individual {
+    values: Vec<f64>,
+    /// Negated objectives, minimised; infinite if the run failed.
+    cost: Vec<f64>,
+    rank: usize,
+    crowding: f64,
+}
+
+impl Individual {
+    fn dominates(&self, other: &Individual) -> bool {
+        let mut strictly = false;
+        for (a, b) in self.cost.iter().zip(&other.cost) {
+            if a > b {
+                return false;
+            }
+            strictly |= a < b;
+        }
+        strictly
+    }
+
+    /// Crowded comparison: lower rank, then larger crowding distance.
+    fn beats(&self, other: &Individual) -> bool {
+        self.rank < other.rank || (self.rank == other.rank && self.crowding > other.crowding)
+    }
+}
+
+/// Splits `population` into fronts of equal non-domination rank, setting
+/// each individual's rank.
+fn sort_fronts(population: &mut [Individual]) -> Vec<Vec<usize>> {
+    let n = population.len();
+    let mut dominated: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut count = vec![0usize; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            if population[i].dominates(&population[j]) {
+                dominated[i].push(j);
+                count[j] += 1;
+            } else if population[j].dominates(&population[i]) {
+                dominated[j].push(i);
+                count[i] += 1;
+            }
+        }
+    }
+    let mut fronts = Vec::new();
+    let mut current: Vec<usize> = (0..n).filter(|&i| count[i] == 0).collect();
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &i in &current {
+            population[i].rank = fronts.len();
+            for &j in &dominated[i] {
+                count[j] -= 1;
+                if count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        fronts.push(current);
+        current = next;
+    }
+    fronts
+}
+
+/// Sets the crowding distance of the individuals of `front`.
+fn assign_crowding(population: &mut [Individual], front: &[usize]) {
+    for &i in front {
+        population[i].crowding = 0.0;
+    }
+    let objectives = population[front[0]].cost.len();
+    let mut order = front.to_vec();
+    for k in 0..objectives {
+        order.sort_by(|&a, &b| population[a].cost[k].total_cmp(&population[b].cost[k]));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        let span = population[last].cost[k] - population[first].cost[k];
+        population[first].crowding = f64::INFINITY;
+        population[last].crowding = f64::INFINITY;
+        if !(span.is_finite() && span > 0.0) {
+            continue;
+        }
+        for w in order.windows(3) {
+            let gap = population[w[2]].cost[k] - population[w[0]].cost[k];
+            population[w[1]].crowding += gap / span;
+        }
+    }
+}
+
+/// Simulated binary crossover of `a` and `b` within `[low, high]`.
+fn crossover(a: f64, b: f64, low: f64, high: f64, eta: f64, rng: &mut Rng) -> (f64, f64) {
+    if (a - b).abs() <= 1e-14 * (high - low) {
+        return (a, b);
+    }
+    let (y1, y2) = (a.min(b), a.max(b));
+    let u = rng.uniform();
+    let spread = |beta: f64| {
+        let alpha = 2.0 - powf(beta, -(eta + 1.0));
+        if u <= 1.0 / alpha {
+            powf(u * alpha, 1.0 / (eta + 1.0))
+        } else {
+            powf(1.0 / (2.0 - u * alpha), 1.0 / (eta + 1.0))
+        }
+    };
+    let beta_low = spread(1.0 + 2.0 * (y1 - low) / (y2 - y1));
+    let beta_high = spread(1.0 + 2.0 * (high - y2) / (y2 - y1));
+    let c1 = (0.5 * ((y1 + y2) - beta_low * (y2 - y1))).clamp(low, high);
+    let c2 = (0.5 * ((y1 + y2) + beta_high * (y2 - y1))).clamp(low, high);
+    if rng.uniform() < 0.5 {
+        (c2, c1)
+    } else {
+        (c1, c2)
+    }
+}
+
+/// Polynomial mutation of `y` within `[low, high]`.
+fn mutate(y: f64, low: f64, high: f64, eta: f64, rng: &mut Rng) -> f64 {
+    let range = high - low;
+    let power = 1.0 / (eta + 1.0);
+    let u = rng.uniform();
+    let delta = if u < 0.5 {
+        let xy = 1.0 - (y - low) / range;
+        powf(2.0 * u + (1.0 - 2.0 * u) * powf(xy, eta + 1.0), power) - 1.0
+    } else {
+        let xy = 1.0 - (high - y) / range;
+        1.0 - powf(
+            2.0 * (1.0 - u) + 2.0 * (u - 0.5) * powf(xy, eta + 1.0),
+            power,
+        )
+    };
+    (y + delta * range).clamp(low, high)
+}
+
+/// Runs NSGA-II on a network configuration. The outflow of `node` is
+/// compared with `observed` under each of `options.objectives`. Each
+/// completed model run is reported to `progress`.
+#[allow(clippy::too_many_arguments)]
+pub fn nsga2(
+    config: &Value,
+    parameters: &[Parameter],
+    series: &BTreeMap<String, Vec<f64>>,
+    dt: f64,
+    node: &str,
+    observed: &[f64],
+    options: &NsgaOptions,
+    progress: &dyn Progress,
+) -> Result<ParetoFront> {
+    if options.population < 4 {
+        return Err(Error::InvalidParameter(
+            "`population` must be at least 4.".into(),
+        ));
+    }
+    if options.objectives.is_empty() {
+        return Err(Error::InvalidParameter(
+            "At least one objective is required.".into(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&options.crossover) {
+        return Err(Error::InvalidParameter(
+            "`crossover` must be a probability.".into(),
+        ));
+    }
+    if !(options.crossover_index >= 0.0 && options.mutation_index >= 0.0) {
+        return Err(Error::InvalidParameter(
+            "Distribution indices must be non-negative.".into(),
+        ));
+    }
+    check_setup(config, parameters, node)?;
+
+    let n = options.population;
+    let tracker = Tracker::new(progress, n * (options.generations + 1));
+    let evaluate = |sets: Vec<Vec<f64>>| -> Vec<Individual> {
+        par_map(&sets, |values| {
+            let cost = if tracker.is_cancelled() {
+                None
+            } else {
+                let simulated = simulate(config, parameters, values, series, dt, node);
+                tracker.advance();
+                simulated.map(|simulated| {
+                    options
+                        .objectives
+                        .iter()
+                        .map(|o| match o.evaluate(&simulated, observed) {
+                            Ok(v) if v.is_finite() => -v,
+                            _ => f64::INFINITY,
+                        })
+                        .collect()
+                })
+            };
+            Individual {
+                values: values.clone(),
+                cost: cost.unwrap_or_else(|| vec![f64::INFINITY; options.objectives.len()]),
+                rank: 0,
+                crowding: 0.0,
+            }
+        })
+    };
+
+    let ranges: Vec<(f64, f64)> = parameters.iter().map(|p| (p.low, p.high)).collect();
+    let mutation = 1.0 / ranges.len().max(1) as f64;
+    let mut rng = Rng::from_seed(options.seed);
+    let mut population = evaluate(latin_hypercube(n, &ranges, &mut rng));
+    for front in sort_fronts(&mut population) {
+        assign_crowding(&mut population, &front);
+    }
+    tracker.check()?;
+
+    for _ in 0..options.generations {
+        let tournament = |rng: &mut Rng| {
+            let (a, b) = (&population[rng.below(n)], &population[rng.below(n)]);
+            if b.beats(a) {
+                b
+            } else {
+                a
+            }
+        };
+        let mut offspring = Vec::with_capacity(n + 1);
+        while offspring.len() < n {
+            let mut a = tournament(&mut rng).values.clone();
+            let mut b = tournament(&mut rng).values.clone();
+            let cross = rng.uniform() < options.crossover;
+            for (j, &(low, high)) in ranges.iter().enumerate() {
+                if high <= low {
+                    continue;
+                }
+                if cross && rng.uniform() < 0.5 {
+                    (a[j], b[j]) =
+                        crossover(a[j], b[j], low, high, options.crossover_index, &mut rng);
+                }
+                for child in [&mut a, &mut b] {
+                    if rng.uniform() < mutation {
+                        child[j] = mutate(child[j], low, high, options.mutation_index, &mut rng);
+                    }
+                }
+            }
+            offspring.push(a);
+            offspring.push(b);
+        }
+        offspring.truncate(n);
+        population.extend(evaluate(offspring));
+        tracker.check()?;
+
+        let fronts = sort_fronts(&mut population);
+        let mut survivors = Vec::with_capacity(n);
+        for front in fronts {
+            assign_crowding(&mut population, &front);
+            if survivors.len() + front.len() <= n {
+                survivors.extend(front);
+            } else {
+                let mut front = front;
+                front.sort_by(|&a, &b| population[b].crowding.total_cmp(&population[a].crowding));
+                survivors.extend(front.into_iter().take(n - survivors.len()));
+            }
+            if survivors.len() == n {
+                break;
+            }
+        }
+        survivors.sort_unstable();
+        let mut keep = survivors.into_iter().peekable();
+        let mut index = 0;
+        population.retain(|_| {
+            let kept = keep.peek() == Some(&index);
+            if kept {
+                keep.next();
+            }
+            index += 1;
+            kept
+        });
+    }
+
+    // Distinct successful sets of the first front, best first objective
+    // first.
+    let mut front: Vec<&Individual> = population
+        .iter()
+        .filter(|i| i.rank == 0 && i.cost.iter().all(|c| c.is_finite()))
+        .collect();
+    front.sort_by(|a, b| a.cost[0].total_cmp(&b.cost[0]));
+    front.dedup_by(|a, b| a.values == b.values);
+    Ok(ParetoFront {
+        sets: front.iter().map(|i| i.values.clone()).collect(),
+        scores: front
+            .iter()
+            .map(|i| i.cost.iter().map(|c| -c).collect())
+            .collect(),
+        evaluations: n * (options.generations + 1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::toml;
+    use crate::network::Network;
+    use crate::progress::Silent;
+
+    const REACH: &str = r#"
+[[nodes]]
+id = "reach"
+type = "muskingum"
+k = 7200.0
+x = 0.2
+inflow = "q"
+"#;
+
+    const DT: f64 = 3600.0;
+
+    fn inputs() -> BTreeMap<String, Vec<f64>> {
+        let q = (0..48)
+            .map(|t| 5.0 + 30.0 * (-((t as f64 - 12.0) / 4.0).powi(2)).exp())
+            .collect();
+        BTreeMap::from([("q".to_string(), q)])
+    }
+
+    /// Outflow of the reach with its true `k` of 7200 s and `x` of 0.2.
+    fn observed(config: &Value) -> Vec<f64> {
+        let result = Network::from_value(config)
+            .unwrap()
+            .run(&inputs(), DT)
+            .unwrap();
+        result.outflow_of("reach").unwrap().to_vec()
+    }
+
+    fn options() -> NsgaOptions {
+        NsgaOptions {
+            population: 12,
+            generations: 6,
+            seed: Some(3),
+            ..NsgaOptions::default()
+        }
+    }
+
+    fn individuals(costs: &[[f64; 2]]) -> Vec<Individual> {
+        costs
+            .iter()
+            .map(|cost| Individual {
+                values: vec![],
+                cost: cost.to_vec(),
+                rank: 0,
+                crowding: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorts_fronts_by_domination() {
+        let mut population = individuals(&[
+            [3.0, 3.0],
+            [1.0, 2.0],
+            [f64::INFINITY, f64::INFINITY],
+            [2.0, 1.0],
+            [2.0, 2.0],
+            [1.0, 2.0],
+        ]);
+        let fronts = sort_fronts(&mut population);
+        assert_eq!(fronts, [vec![1, 3, 5], vec![4], vec![0], vec![2]]);
+        let ranks: Vec<usize> = population.iter().map(|i| i.rank).collect();
+        assert_eq!(ranks, [2, 0, 3, 0, 1, 0]);
+        // Equal costs do not dominate each other.
+        assert!(!population[1].dominates(&population[5]));
+        assert!(population[1].dominates(&population[4]));
+    }
+
+    #[test]
+    fn crowding_favours_the_ends_of_a_front() {
+        let mut population = individuals(&[[0.0, 4.0], [1.0, 3.0], [3.0, 1.0], [4.0, 0.0]]);
+        assign_crowding(&mut population, &[0, 1, 2, 3]);
+        let crowding: Vec<f64> = population.iter().map(|i| i.crowding).collect();
+        assert_eq!(crowding, [f64::INFINITY, 1.5, 1.5, f64::INFINITY]);
+        population[1].rank = 1;
+        assert!(population[2].beats(&population[1]));
+        assert!(population[0].beats(&population[2]));
+    }
+
+    #[test]
+    fn operators_stay_within_bounds() {
+        let mut rng = Rng::seed_from_u64(11);
+        for _ in 0..1000 {
+            let (a, b) = (rng.uniform() * 10.0, rng.uniform() * 10.0);
+            let (c1, c2) = crossover(a, b, 0.0, 10.0, 15.0, &mut rng);
+            assert!((0.0..=10.0).contains(&c1) && (0.0..=10.0).contains(&c2));
+            let y = mutate(a, 0.0, 10.0, 20.0, &mut rng);
+            assert!((0.0..=10.0).contains(&y));
+        }
+        // Identical parents are returned unchanged.
+        assert_eq!(crossover(2.0, 2.0, 0.0, 10.0, 15.0, &mut rng), (2.0, 2.0));
+    }
+
+    #[test]
+    fn finds_the_true_parameters_on_the_front() {
+        let config = toml::parse(REACH).unwrap();
+        let observed = observed(&config);
+        let parameters = [
+            Parameter::new("reach.k", 3600.0, 9000.0).unwrap(),
+            Parameter::new("reach.x", 0.0, 0.4).unwrap(),
+        ];
+        let run = || {
+            nsga2(
+                &config,
+                &parameters,
+                &inputs(),
+                DT,
+                "reach",
+                &observed,
+                &options(),
+                &Silent,
+            )
+            .unwrap()
+        };
+        let front = run();
+        assert_eq!(front.evaluations, 12 * 7);
+        assert!(!front.sets.is_empty());
+        assert_eq!(front.sets.len(), front.scores.len());
+        for (set, scores) in front.sets.iter().zip(&front.scores) {
+            assert!((3600.0..=9000.0).contains(&set[0]) && (0.0..=0.4).contains(&set[1]));
+            assert!(scores.len() == 2 && scores.iter().all(|s| s.is_finite()));
+        }
+        // Sorted by the first objective, best first, and mutually
+        // non-dominated.
+        assert!(front.scores.windows(2).all(|w| w[0][0] >= w[1][0]));
+        for a in &front.scores {
+            assert!(!front
+                .scores
+                .iter()
+                .any(|b| b[0] >= a[0] && b[1] >= a[1] && b != a));
+        }
+        assert!(front.scores[0][0] > 0.99, "{:?}", front.scores[0]);
+        assert!(
+            (front.sets[0][0] - 7200.0).abs() < 1000.0,
+            "{:?}",
+            front.sets[0]
+        );
+        assert_eq!(run(), front);
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        let config = toml::parse(REACH).unwrap();
+        let observed = observed(&config);
+        let parameters = [Parameter::new("reach.k", 3600.0, 9000.0).unwrap()];
+        let run = |options: NsgaOptions, node: &str| {
+            nsga2(
+                &config,
+                &parameters,
+                &inputs(),
+                DT,
+                node,
+                &observed,
+                &options,
+                &Silent,
+            )
+        };
+        for (options, expected) in [
+            (
+                NsgaOptions {
+                    population: 3,
+                    ..options()
+                },
+                "`population`",
+            ),
+            (
+                NsgaOptions {
+                    objectives: vec![],
+                    ..options()
+                },
+                "objective",
+            ),
+            (
+                NsgaOptions {
+                    crossover: 1.5,
+                    ..options()
+                },
+                "`crossover`",
+            ),
+            (
+                NsgaOptions {
+                    mutation_index: -1.0,
+                    ..options()
+                },
+                "Distribution indices",
+            ),
+        ] {
+            match run(options, "reach") {
+                Err(Error::InvalidParameter(msg)) => assert!(msg.contains(expected), "{msg}"),
+                other => panic!("{expected}: {other:?}"),
+            }
+        }
+        assert!(matches!(
+            run(options(), "outlet"),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+}
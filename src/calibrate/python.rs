@@ -0,0 +1,825 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict, PyType};
+use std::collections::BTreeMap;
+
+use super::cross_validation::{
+    cross_validate as cross_validate_rs, CrossValidationOptions, CrossValidationResult, Fold,
+    Split, SplitScheme,
+};
+use super::glue::{glue as glue_rs, GlueOptions, GlueResult};
+use super::gradient::{muskingum_gradient_rs, network_gradient as network_gradient_rs};
+use super::metrics;
+use super::nsga2::{nsga2 as nsga2_rs, NsgaOptions, ParetoFront};
+use super::sensitivity::{morris as morris_rs, sobol as sobol_rs, Model, Objective};
+use super::{Likelihood, Parameter};
+use crate::arrow::FloatSeries;
+use crate::config::Value;
+use crate::progress::run_with_progress;
+use crate::time::timedelta_to_seconds;
+
+/// Reads `{"node.field": (low, high)}` in insertion order.
+fn extract_parameters(parameters: &Bound<'_, PyDict>) -> PyResult<(Vec<String>, Vec<Parameter>)> {
+    let mut names = Vec::new();
+    let mut ranges = Vec::new();
+    for (name, range) in parameters.iter() {
+        let name: String = name.extract()?;
+        let (low, high): (f64, f64) = range.extract()?;
+        ranges.push(Parameter::new(&name, low, high)?);
+        names.push(name);
+    }
+    Ok((names, ranges))
+}
+
+#[pyfunction]
+pub fn nse(simulated: FloatSeries, observed: FloatSeries) -> PyResult<f64> {
+    Ok(metrics::nse(&simulated.0, &observed.0)?)
+}
+
+#[pyfunction]
+pub fn kge(simulated: FloatSeries, observed: FloatSeries) -> PyResult<f64> {
+    Ok(metrics::kge(&simulated.0, &observed.0)?)
+}
+
+#[pyfunction]
+pub fn log_nse(simulated: FloatSeries, observed: FloatSeries) -> PyResult<f64> {
+    Ok(metrics::log_nse(&simulated.0, &observed.0)?)
+}
+
+#[pyfunction]
+pub fn rmse(simulated: FloatSeries, observed: FloatSeries) -> PyResult<f64> {
+    Ok(metrics::rmse(&simulated.0, &observed.0)?)
+}
+
+/// Outcome of a GLUE analysis.
+#[pyclass(name = "GlueResult", module = "rustflow.calibrate")]
+pub struct PyGlueResult {
+    names: Vec<String>,
+    inner: GlueResult,
+}
+
+#[pymethods]
+impl PyGlueResult {
+    /// list[str]: Calibrated parameter paths, in sample column order.
+    #[getter]
+    fn parameters(&self) -> Vec<String> {
+        self.names.clone()
+    }
+
+    /// list[list[float]]: All sampled parameter sets, one row per set.
+    #[getter]
+    fn samples(&self) -> Vec<Vec<f64>> {
+        self.inner.samples.clone()
+    }
+
+    /// list[float]: Likelihood of every set (`nan` where the run failed).
+    #[getter]
+    fn likelihood(&self) -> Vec<f64> {
+        self.inner.likelihood.clone()
+    }
+
+    /// list[int]: Indices of the behavioural sets.
+    #[getter]
+    fn behavioral(&self) -> Vec<usize> {
+        self.inner.behavioral.clone()
+    }
+
+    /// dict[str, list[float]]: Behavioural parameter sets as a dict of
+    /// parameter path to values.
+    #[getter]
+    fn behavioral_parameters<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (j, name) in self.names.iter().enumerate() {
+            let values: Vec<f64> = self
+                .inner
+                .behavioral
+                .iter()
+                .map(|&i| self.inner.samples[i][j])
+                .collect();
+            dict.set_item(name, values)?;
+        }
+        Ok(dict)
+    }
+
+    /// list[float]: Lower prediction bound at each step.
+    #[getter]
+    fn lower(&self) -> Vec<f64> {
+        self.inner.lower.clone()
+    }
+
+    /// list[float]: Median prediction at each step.
+    #[getter]
+    fn median(&self) -> Vec<f64> {
+        self.inner.median.clone()
+    }
+
+    /// list[float]: Upper prediction bound at each step.
+    #[getter]
+    fn upper(&self) -> Vec<f64> {
+        self.inner.upper.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GlueResult(samples={}, behavioral={})",
+            self.inner.samples.len(),
+            self.inner.behavioral.len()
+        )
+    }
+
+    /// Pickles the result by value.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (GlueState,))> {
+        let this = slf.borrow();
+        let r = &this.inner;
+        let state = (
+            this.names.clone(),
+            r.samples.clone(),
+            r.likelihood.clone(),
+            r.behavioral.clone(),
+            r.lower.clone(),
+            r.median.clone(),
+            r.upper.clone(),
+        );
+        Ok((slf.get_type().getattr("_from_state")?, (state,)))
+    }
+
+    #[classmethod]
+    fn _from_state(_cls: &Bound<'_, PyType>, state: GlueState) -> Self {
+        let (names, samples, likelihood, behavioral, lower, median, upper) = state;
+        PyGlueResult {
+            names,
+            inner: GlueResult {
+                samples,
+                likelihood,
+                behavioral,
+                lower,
+                median,
+                upper,
+            },
+        }
+    }
+}
+
+/// Fields of a `GlueResult` as pickled: parameter names, then the result's
+/// fields.
+type GlueState = (
+    Vec<String>,
+    Vec<Vec<f64>>,
+    Vec<f64>,
+    Vec<usize>,
+    Vec<f64>,
+    Vec<f64>,
+    Vec<f64>,
+);
+
+#[pyfunction]
+#[pyo3(signature = (config, parameters, inflows, observed, node, time_step, samples=1000, likelihood="nse", threshold=0.5, bounds=(0.05, 0.95), seed=None, progress_callback=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn glue(
+    py: Python<'_>,
+    config: Value,
+    parameters: Bound<'_, PyDict>,
+    inflows: BTreeMap<String, FloatSeries>,
+    observed: FloatSeries,
+    node: String,
+    time_step: Bound<'_, PyDelta>,
+    samples: usize,
+    likelihood: &str,
+    threshold: f64,
+    bounds: (f64, f64),
+    seed: Option<u64>,
+    progress_callback: Option<Bound<'_, PyAny>>,
+) -> PyResult<PyGlueResult> {
+    let (names, ranges) = extract_parameters(&parameters)?;
+    let options = GlueOptions {
+        samples,
+        likelihood: Likelihood::parse(likelihood)?,
+        threshold,
+        bounds,
+        seed,
+    };
+    let series: BTreeMap<String, Vec<f64>> =
+        inflows.into_iter().map(|(name, s)| (name, s.0)).collect();
+    let dt_s = timedelta_to_seconds(&time_step);
+    let inner = run_with_progress(py, progress_callback.as_ref(), |progress| {
+        glue_rs(
+            &config,
+            &ranges,
+            &series,
+            dt_s,
+            &node,
+            &observed.0,
+            &options,
+            progress,
+        )
+    })?;
+    Ok(PyGlueResult { names, inner })
+}
+
+/// Calibration and validation scores of a temporal cross-validation.
+#[pyclass(name = "CrossValidationResult", module = "rustflow.calibrate")]
+pub struct PyCrossValidationResult {
+    names: Vec<String>,
+    likelihood: String,
+    inner: CrossValidationResult,
+}
+
+#[pymethods]
+impl PyCrossValidationResult {
+    /// list[str]: Calibrated parameter paths.
+    #[getter]
+    fn parameters(&self) -> Vec<String> {
+        self.names.clone()
+    }
+
+    /// str: Likelihood measure used to calibrate and validate.
+    #[getter]
+    fn likelihood(&self) -> String {
+        self.likelihood.clone()
+    }
+
+    /// int: Number of parameter sets tried.
+    #[getter]
+    fn samples(&self) -> usize {
+        self.inner.samples
+    }
+
+    /// list[dict]: One dict per split: the `calibration` periods and
+    /// `validation` period as `(start, end)` step ranges, the calibrated
+    /// `parameters`, and the `calibration`, `validation` and `degradation`
+    /// scores.
+    #[getter]
+    fn folds<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.inner
+            .folds
+            .iter()
+            .map(|fold| {
+                let dict = PyDict::new(py);
+                dict.set_item(
+                    "calibration_periods",
+                    fold.split
+                        .calibration
+                        .iter()
+                        .map(|r| (r.start, r.end))
+                        .collect::<Vec<_>>(),
+                )?;
+                dict.set_item(
+                    "validation_period",
+                    (fold.split.validation.start, fold.split.validation.end),
+                )?;
+                let parameters = PyDict::new(py);
+                for (name, value) in self.names.iter().zip(&fold.parameters) {
+                    parameters.set_item(name, value)?;
+                }
+                dict.set_item("parameters", parameters)?;
+                dict.set_item("calibration", fold.calibration)?;
+                dict.set_item("validation", fold.validation)?;
+                dict.set_item("degradation", fold.degradation())?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// list[float]: Calibration score of each split.
+    #[getter]
+    fn calibration_scores(&self) -> Vec<f64> {
+        self.inner.folds.iter().map(|f| f.calibration).collect()
+    }
+
+    /// list[float]: Validation score of each split.
+    #[getter]
+    fn validation_scores(&self) -> Vec<f64> {
+        self.inner.folds.iter().map(|f| f.validation).collect()
+    }
+
+    /// list[float]: Calibration less validation score of each split.
+    #[getter]
+    fn degradation(&self) -> Vec<f64> {
+        self.inner.folds.iter().map(Fold::degradation).collect()
+    }
+
+    /// dict[str, float]: `mean_calibration`, `mean_validation`,
+    /// `mean_degradation` and `max_degradation` over the splits.
+    #[getter]
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (calibration, validation, degradation, worst) = self.inner.summary();
+        let dict = PyDict::new(py);
+        dict.set_item("mean_calibration", calibration)?;
+        dict.set_item("mean_validation", validation)?;
+        dict.set_item("mean_degradation", degradation)?;
+        dict.set_item("max_degradation", worst)?;
+        Ok(dict)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.folds.len()
+    }
+
+    fn __repr__(&self) -> String {
+        let (calibration, validation, _, _) = self.inner.summary();
+        format!(
+            "CrossValidationResult(folds={}, mean_calibration={calibration:.4}, mean_validation={validation:.4})",
+            self.inner.folds.len()
+        )
+    }
+
+    /// Pickles the result by value.
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (CrossValidationState,))> {
+        let this = slf.borrow();
+        let folds = this
+            .inner
+            .folds
+            .iter()
+            .map(|f| {
+                (
+                    f.split
+                        .calibration
+                        .iter()
+                        .map(|r| (r.start, r.end))
+                        .collect(),
+                    (f.split.validation.start, f.split.validation.end),
+                    f.parameters.clone(),
+                    f.calibration,
+                    f.validation,
+                )
+            })
+            .collect();
+        let state = (
+            this.names.clone(),
+            this.likelihood.clone(),
+            folds,
+            this.inner.samples,
+        );
+        Ok((slf.get_type().getattr("_from_state")?, (state,)))
+    }
+
+    #[classmethod]
+    fn _from_state(_cls: &Bound<'_, PyType>, state: CrossValidationState) -> Self {
+        let (names, likelihood, folds, samples) = state;
+        let folds = folds
+            .into_iter()
+            .map(|(calibration, validation, parameters, cal, val)| Fold {
+                split: Split {
+                    calibration: calibration.into_iter().map(|(a, b)| a..b).collect(),
+                    validation: validation.0..validation.1,
+                },
+                parameters,
+                calibration: cal,
+                validation: val,
+            })
+            .collect();
+        PyCrossValidationResult {
+            names,
+            likelihood,
+            inner: CrossValidationResult { folds, samples },
+        }
+    }
+}
+
+/// A fold as pickled: calibration periods, validation period, parameters
+/// and the two scores.
+type FoldState = (Vec<(usize, usize)>, (usize, usize), Vec<f64>, f64, f64);
+
+/// Fields of a `CrossValidationResult` as pickled: parameter names, the
+/// likelihood name, the folds and the number of samples.
+type CrossValidationState = (Vec<String>, String, Vec<FoldState>, usize);
+
+#[pyfunction]
+#[pyo3(signature = (config, parameters, inflows, observed, node, time_step, scheme="split_sample", fraction=0.5, folds=5, calibration_window=None, validation_window=None, step=None, warm_up=0, samples=1000, likelihood="nse", seed=None, progress_callback=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn cross_validate(
+    py: Python<'_>,
+    config: Value,
+    parameters: Bound<'_, PyDict>,
+    inflows: BTreeMap<String, FloatSeries>,
+    observed: FloatSeries,
+    node: String,
+    time_step: Bound<'_, PyDelta>,
+    scheme: &str,
+    fraction: f64,
+    folds: usize,
+    calibration_window: Option<usize>,
+    validation_window: Option<usize>,
+    step: Option<usize>,
+    warm_up: usize,
+    samples: usize,
+    likelihood: &str,
+    seed: Option<u64>,
+    progress_callback: Option<Bound<'_, PyAny>>,
+) -> PyResult<PyCrossValidationResult> {
+    let (names, ranges) = extract_parameters(&parameters)?;
+    let scheme = match (scheme, calibration_window, validation_window) {
+        ("split_sample", _, _) => SplitScheme::SplitSample { fraction },
+        ("k_fold", _, _) => SplitScheme::KFold { folds },
+        ("rolling", Some(calibration), Some(validation)) => SplitScheme::Rolling {
+            calibration,
+            validation,
+            step: step.unwrap_or(validation),
+        },
+        ("rolling", _, _) => {
+            return Err(crate::error::Error::InvalidParameter(
+                "The `rolling` scheme needs `calibration_window` and `validation_window`.".into(),
+            )
+            .into())
+        }
+        _ => {
+            return Err(crate::error::Error::InvalidParameter(format!(
+                "Unknown scheme `{scheme}`; expected \"split_sample\", \"k_fold\" or \"rolling\"."
+            ))
+            .into())
+        }
+    };
+    let likelihood = Likelihood::parse(likelihood)?;
+    let options = CrossValidationOptions {
+        scheme,
+        samples,
+        likelihood,
+        warm_up,
+        seed,
+    };
+    let series: BTreeMap<String, Vec<f64>> =
+        inflows.into_iter().map(|(name, s)| (name, s.0)).collect();
+    let dt_s = timedelta_to_seconds(&time_step);
+    let inner = run_with_progress(py, progress_callback.as_ref(), |progress| {
+        cross_validate_rs(
+            &config,
+            &ranges,
+            &series,
+            dt_s,
+            &node,
+            &observed.0,
+            &options,
+            progress,
+        )
+    })?;
+    Ok(PyCrossValidationResult {
+        names,
+        likelihood: likelihood.name().to_string(),
+        inner,
+    })
+}
+
+/// Non-dominated parameter sets found by NSGA-II.
+#[pyclass(name = "ParetoFront", module = "rustflow.calibrate")]
+pub struct PyParetoFront {
+    names: Vec<String>,
+    objectives: Vec<String>,
+    inner: ParetoFront,
+}
+
+#[pymethods]
+impl PyParetoFront {
+    /// list[str]: Calibrated parameter paths, in set column order.
+    #[getter]
+    fn parameters(&self) -> Vec<String> {
+        self.names.clone()
+    }
+
+    /// list[str]: Objective names, in score column order.
+    #[getter]
+    fn objectives(&self) -> Vec<String> {
+        self.objectives.clone()
+    }
+
+    /// list[list[float]]: Parameter values of each set on the front.
+    #[getter]
+    fn sets(&self) -> Vec<Vec<f64>> {
+        self.inner.sets.clone()
+    }
+
+    /// list[list[float]]: Objective values of each set on the front.
+    #[getter]
+    fn scores(&self) -> Vec<Vec<f64>> {
+        self.inner.scores.clone()
+    }
+
+    /// dict[str, list[float]]: Sets on the front as a dict of parameter
+    /// path to values.
+    #[getter]
+    fn parameter_sets<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        columns(py, &self.names, &self.inner.sets)
+    }
+
+    /// dict[str, list[float]]: Scores on the front as a dict of objective
+    /// name to values.
+    #[getter]
+    fn objective_values<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        columns(py, &self.objectives, &self.inner.scores)
+    }
+
+    /// int: Number of model runs made.
+    #[getter]
+    fn evaluations(&self) -> usize {
+        self.inner.evaluations
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.sets.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParetoFront(objectives={:?}, sets={})",
+            self.objectives,
+            self.inner.sets.len()
+        )
+    }
+
+    /// Pickles the front by value.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (ParetoState,))> {
+        let this = slf.borrow();
+        let state = (
+            this.names.clone(),
+            this.objectives.clone(),
+            this.inner.sets.clone(),
+            this.inner.scores.clone(),
+            this.inner.evaluations,
+        );
+        Ok((slf.get_type().getattr("_from_state")?, (state,)))
+    }
+
+    #[classmethod]
+    fn _from_state(_cls: &Bound<'_, PyType>, state: ParetoState) -> Self {
+        let (names, objectives, sets, scores, evaluations) = state;
+        PyParetoFront {
+            names,
+            objectives,
+            inner: ParetoFront {
+                sets,
+                scores,
+                evaluations,
+            },
+        }
+    }
+}
+
+/// Fields of a `ParetoFront` as pickled: parameter and objective names,
+/// then the front's fields.
+type ParetoState = (
+    Vec<String>,
+    Vec<String>,
+    Vec<Vec<f64>>,
+    Vec<Vec<f64>>,
+    usize,
+);
+
+/// `{name: column}` of the rows of `table`.
+fn columns<'py>(
+    py: Python<'py>,
+    names: &[String],
+    table: &[Vec<f64>],
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (j, name) in names.iter().enumerate() {
+        dict.set_item(name, table.iter().map(|row| row[j]).collect::<Vec<f64>>())?;
+    }
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (config, parameters, inflows, observed, node, time_step, objectives=vec!["nse".to_string(), "log_nse".to_string()], population=100, generations=50, crossover=0.9, seed=None, progress_callback=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn nsga2(
+    py: Python<'_>,
+    config: Value,
+    parameters: Bound<'_, PyDict>,
+    inflows: BTreeMap<String, FloatSeries>,
+    observed: FloatSeries,
+    node: String,
+    time_step: Bound<'_, PyDelta>,
+    objectives: Vec<String>,
+    population: usize,
+    generations: usize,
+    crossover: f64,
+    seed: Option<u64>,
+    progress_callback: Option<Bound<'_, PyAny>>,
+) -> PyResult<PyParetoFront> {
+    let (names, ranges) = extract_parameters(&parameters)?;
+    let objectives = objectives
+        .iter()
+        .map(|name| Likelihood::parse(name))
+        .collect::<crate::error::Result<Vec<_>>>()?;
+    let options = NsgaOptions {
+        objectives: objectives.clone(),
+        population,
+        generations,
+        crossover,
+        seed,
+        ..NsgaOptions::default()
+    };
+    let series: BTreeMap<String, Vec<f64>> =
+        inflows.into_iter().map(|(name, s)| (name, s.0)).collect();
+    let dt_s = timedelta_to_seconds(&time_step);
+    let inner = run_with_progress(py, progress_callback.as_ref(), |progress| {
+        nsga2_rs(
+            &config,
+            &ranges,
+            &series,
+            dt_s,
+            &node,
+            &observed.0,
+            &options,
+            progress,
+        )
+    })?;
+    Ok(PyParetoFront {
+        names,
+        objectives: objectives.iter().map(|o| o.name().to_string()).collect(),
+        inner,
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (config, parameters, inflows, node, time_step, objective="peak", observed=None, trajectories=20, levels=4, seed=None, progress_callback=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn morris<'py>(
+    py: Python<'py>,
+    config: Value,
+    parameters: Bound<'py, PyDict>,
+    inflows: BTreeMap<String, FloatSeries>,
+    node: String,
+    time_step: Bound<'py, PyDelta>,
+    objective: &str,
+    observed: Option<FloatSeries>,
+    trajectories: usize,
+    levels: usize,
+    seed: Option<u64>,
+    progress_callback: Option<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (names, ranges) = extract_parameters(&parameters)?;
+    let objective = Objective::parse(objective, observed.map(|o| o.0))?;
+    let series: BTreeMap<String, Vec<f64>> =
+        inflows.into_iter().map(|(name, s)| (name, s.0)).collect();
+    let dt_s = timedelta_to_seconds(&time_step);
+    let indices = run_with_progress(py, progress_callback.as_ref(), |progress| {
+        let model = Model {
+            config: &config,
+            parameters: &ranges,
+            series: &series,
+            dt: dt_s,
+            node: &node,
+            objective: &objective,
+            progress,
+        };
+        morris_rs(&model, trajectories, levels, seed)
+    })?;
+    let result = PyDict::new(py);
+    for (i, name) in names.iter().enumerate() {
+        let entry = PyDict::new(py);
+        entry.set_item("mu", indices.mu[i])?;
+        entry.set_item("mu_star", indices.mu_star[i])?;
+        entry.set_item("sigma", indices.sigma[i])?;
+        result.set_item(name, entry)?;
+    }
+    Ok(result)
+}
+
+#[pyfunction]
+#[pyo3(signature = (config, parameters, inflows, node, time_step, objective="peak", observed=None, samples=1024, seed=None, progress_callback=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn sobol<'py>(
+    py: Python<'py>,
+    config: Value,
+    parameters: Bound<'py, PyDict>,
+    inflows: BTreeMap<String, FloatSeries>,
+    node: String,
+    time_step: Bound<'py, PyDelta>,
+    objective: &str,
+    observed: Option<FloatSeries>,
+    samples: usize,
+    seed: Option<u64>,
+    progress_callback: Option<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (names, ranges) = extract_parameters(&parameters)?;
+    let objective = Objective::parse(objective, observed.map(|o| o.0))?;
+    let series: BTreeMap<String, Vec<f64>> =
+        inflows.into_iter().map(|(name, s)| (name, s.0)).collect();
+    let dt_s = timedelta_to_seconds(&time_step);
+    let indices = run_with_progress(py, progress_callback.as_ref(), |progress| {
+        let model = Model {
+            config: &config,
+            parameters: &ranges,
+            series: &series,
+            dt: dt_s,
+            node: &node,
+            objective: &objective,
+            progress,
+        };
+        sobol_rs(&model, samples, seed)
+    })?;
+    let result = PyDict::new(py);
+    for (i, name) in names.iter().enumerate() {
+        let entry = PyDict::new(py);
+        entry.set_item("first", indices.first[i])?;
+        entry.set_item("total", indices.total[i])?;
+        result.set_item(name, entry)?;
+    }
+    Ok(result)
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, k, x, time_step, sub_reaches=1, initial_outflow=None, observed=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_gradient<'py>(
+    py: Python<'py>,
+    inflow: FloatSeries,
+    k: Bound<'py, PyDelta>,
+    x: f64,
+    time_step: Bound<'py, PyDelta>,
+    sub_reaches: i64,
+    initial_outflow: Option<f64>,
+    observed: Option<FloatSeries>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let result = muskingum_gradient_rs(
+        &inflow.0,
+        timedelta_to_seconds(&time_step),
+        timedelta_to_seconds(&k),
+        x,
+        sub_reaches.max(1) as usize,
+        initial_outflow,
+    )?;
+    let dict = PyDict::new(py);
+    if let Some(observed) = observed {
+        let (sse, gradient) = result.sse(&observed.0)?;
+        dict.set_item("sse", sse)?;
+        dict.set_item("sse_gradient", gradient.to_vec())?;
+    }
+    dict.set_item("outflow", result.outflow)?;
+    dict.set_item("d_k", result.d_k)?;
+    dict.set_item("d_x", result.d_x)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (config, parameters, inflows, node, time_step, objective="nse", observed=None, relative_step=1e-6))]
+#[allow(clippy::too_many_arguments)]
+pub fn network_gradient<'py>(
+    py: Python<'py>,
+    config: Value,
+    parameters: Bound<'py, PyDict>,
+    inflows: BTreeMap<String, FloatSeries>,
+    node: String,
+    time_step: Bound<'py, PyDelta>,
+    objective: &str,
+    observed: Option<FloatSeries>,
+    relative_step: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut names = Vec::new();
+    let mut points = Vec::new();
+    let mut values = Vec::new();
+    for (name, value) in parameters.iter() {
+        let name: String = name.extract()?;
+        let value: f64 = value.extract()?;
+        points.push(Parameter::new(&name, value, value)?);
+        values.push(value);
+        names.push(name);
+    }
+    let objective = Objective::parse(objective, observed.map(|o| o.0))?;
+    let series: BTreeMap<String, Vec<f64>> =
+        inflows.into_iter().map(|(name, s)| (name, s.0)).collect();
+    let dt_s = timedelta_to_seconds(&time_step);
+    let result = py.allow_threads(|| {
+        network_gradient_rs(
+            &config,
+            &points,
+            &values,
+            &series,
+            dt_s,
+            &node,
+            &objective,
+            relative_step,
+        )
+    })?;
+    let gradient = PyDict::new(py);
+    let jacobian = PyDict::new(py);
+    for (j, name) in names.iter().enumerate() {
+        gradient.set_item(name, result.gradient[j])?;
+        jacobian.set_item(name, &result.jacobian[j])?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("objective", result.objective)?;
+    dict.set_item("gradient", gradient)?;
+    dict.set_item("outflow", result.outflow)?;
+    dict.set_item("jacobian", jacobian)?;
+    Ok(dict)
+}
+
+#[pymodule]
+pub fn init_calibrate(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCrossValidationResult>()?;
+    m.add_class::<PyGlueResult>()?;
+    m.add_class::<PyParetoFront>()?;
+    m.add_function(wrap_pyfunction!(nse, m)?)?;
+    m.add_function(wrap_pyfunction!(kge, m)?)?;
+    m.add_function(wrap_pyfunction!(log_nse, m)?)?;
+    m.add_function(wrap_pyfunction!(rmse, m)?)?;
+    m.add_function(wrap_pyfunction!(cross_validate, m)?)?;
+    m.add_function(wrap_pyfunction!(glue, m)?)?;
+    m.add_function(wrap_pyfunction!(nsga2, m)?)?;
+    m.add_function(wrap_pyfunction!(morris, m)?)?;
+    m.add_function(wrap_pyfunction!(sobol, m)?)?;
+    m.add_function(wrap_pyfunction!(muskingum_gradient, m)?)?;
+    m.add_function(wrap_pyfunction!(network_gradient, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,240 @@
+//! Global sensitivity analysis: Morris elementary effects (screening) and
+//! Sobol variance-based indices (Saltelli 2010 first-order, Jansen total).
+//!
+//! Parameters are sampled in the unit hypercube and scaled to their ranges;
+//! elementary effects are therefore expressed per unit of normalised range.
+//! Model runs that fail or give a non-finite objective are left out of the
+//! estimators.
+
+use super::{check_setup, simulate, Likelihood, Parameter};
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+use crate::progress::{Progress, Tracker};
+use crate::random::Rng;
+use std::collections::BTreeMap;
+
+/// Scalar summary of a simulated outflow series.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Objective {
+    Peak,
+    /// Sum of flow × time step.
+    Volume,
+    Mean,
+    /// Goodness of fit against observations.
+    Fit(Likelihood, Vec<f64>),
+}
+
+impl Objective {
+    /// Parses `"peak"`, `"volume"`, `"mean"`, `"nse"`, `"kge"` or
+    /// `"log_nse"`; the fit objectives require `observed`.
+    pub fn parse(name: &str, observed: Option<Vec<f64>>) -> Result<Self> {
+        match (name.to_ascii_lowercase().as_str(), observed) {
+            ("peak", _) => Ok(Objective::Peak),
+            ("volume", _) => Ok(Objective::Volume),
+            ("mean", _) => Ok(Objective::Mean),
+            ("nse" | "kge" | "log_nse", None) => Err(Error::InvalidParameter(format!(
+                "Objective `{name}` requires observed flows."
+            ))),
+            (_, Some(observed)) => Ok(Objective::Fit(Likelihood::parse(name)?, observed)),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown objective `{name}`; expected \"peak\", \"volume\", \"mean\", \"nse\", \"kge\" or \"log_nse\"."
+            ))),
+        }
+    }
+
+    pub fn evaluate(&self, simulated: &[f64], dt: f64) -> f64 {
+        match self {
+            Objective::Peak => simulated.iter().copied().fold(f64::NAN, f64::max),
+            Objective::Volume => simulated.iter().sum::<f64>() * dt,
+            Objective::Mean => simulated.iter().sum::<f64>() / simulated.len() as f64,
+            Objective::Fit(likelihood, observed) => {
+                likelihood.evaluate(simulated, observed).unwrap_or(f64::NAN)
+            }
+        }
+    }
+}
+
+/// Model inputs shared by the sensitivity methods.
+pub struct Model<'a> {
+    pub config: &'a Value,
+    pub parameters: &'a [Parameter],
+    pub series: &'a BTreeMap<String, Vec<f64>>,
+    pub dt: f64,
+    pub node: &'a str,
+    pub objective: &'a Objective,
+    /// Receives each completed model run.
+    pub progress: &'a dyn Progress,
+}
+
+impl Model<'_> {
+    /// Evaluates the objective at points of the unit hypercube in parallel.
+    fn evaluate(&self, points: &[Vec<f64>]) -> Result<Vec<f64>> {
+        let tracker = Tracker::new(self.progress, points.len());
+        let y = par_map(points, |unit| {
+            if tracker.is_cancelled() {
+                return f64::NAN;
+            }
+            let values: Vec<f64> = self
+                .parameters
+                .iter()
+                .zip(unit)
+                .map(|(p, u)| p.low + u * (p.high - p.low))
+                .collect();
+            let y = simulate(
+                self.config,
+                self.parameters,
+                &values,
+                self.series,
+                self.dt,
+                self.node,
+            )
+            .map_or(f64::NAN, |q| self.objective.evaluate(&q, self.dt));
+            tracker.advance();
+            y
+        });
+        tracker.check()?;
+        Ok(y)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MorrisIndices {
+    /// Mean elementary effect.
+    pub mu: Vec<f64>,
+    /// Mean absolute elementary effect (Campolongo et al., 2007).
+    pub mu_star: Vec<f64>,
+    /// Standard deviation of the elementary effects.
+    pub sigma: Vec<f64>,
+}
+
+/// Morris screening with `trajectories` one-at-a-time trajectories on a
+/// grid of `levels` (even) levels; costs `trajectories × (k + 1)` runs.
+pub fn morris(
+    model: &Model,
+    trajectories: usize,
+    levels: usize,
+    seed: Option<u64>,
+) -> Result<MorrisIndices> {
+    if trajectories == 0 {
+        return Err(Error::InvalidParameter(
+            "`trajectories` must be positive.".into(),
+        ));
+    }
+    if levels < 2 || !levels.is_multiple_of(2) {
+        return Err(Error::InvalidParameter(
+            "`levels` must be an even number of at least 2.".into(),
+        ));
+    }
+    check_setup(model.config, model.parameters, model.node)?;
+    let k = model.parameters.len();
+    let delta = levels as f64 / (2.0 * (levels - 1) as f64);
+    let mut rng = Rng::from_seed(seed);
+    let mut points = Vec::with_capacity(trajectories * (k + 1));
+    let mut orders = Vec::with_capacity(trajectories);
+    for _ in 0..trajectories {
+        let mut x: Vec<f64> = (0..k)
+            .map(|_| rng.below(levels / 2) as f64 / (levels - 1) as f64)
+            .collect();
+        let mut order: Vec<usize> = (0..k).collect();
+        rng.shuffle(&mut order);
+        points.push(x.clone());
+        for &i in &order {
+            x[i] += delta;
+            points.push(x.clone());
+        }
+        orders.push(order);
+    }
+    let y = model.evaluate(&points)?;
+
+    let mut effects = vec![Vec::with_capacity(trajectories); k];
+    for (t, order) in orders.iter().enumerate() {
+        let base = t * (k + 1);
+        for (step, &i) in order.iter().enumerate() {
+            let effect = (y[base + step + 1] - y[base + step]) / delta;
+            if effect.is_finite() {
+                effects[i].push(effect);
+            }
+        }
+    }
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    Ok(MorrisIndices {
+        mu: effects.iter().map(|e| mean(e)).collect(),
+        mu_star: effects
+            .iter()
+            .map(|e| e.iter().map(|v| v.abs()).sum::<f64>() / e.len() as f64)
+            .collect(),
+        sigma: effects
+            .iter()
+            .map(|e| {
+                let m = mean(e);
+                (e.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (e.len() as f64 - 1.0)).sqrt()
+            })
+            .collect(),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SobolIndices {
+    pub first: Vec<f64>,
+    pub total: Vec<f64>,
+}
+
+/// Sobol indices from `samples` base rows; costs `samples × (k + 2)` runs.
+pub fn sobol(model: &Model, samples: usize, seed: Option<u64>) -> Result<SobolIndices> {
+    if samples < 2 {
+        return Err(Error::InvalidParameter(
+            "`samples` must be at least 2.".into(),
+        ));
+    }
+    check_setup(model.config, model.parameters, model.node)?;
+    let k = model.parameters.len();
+    let mut rng = Rng::from_seed(seed);
+    let mut draw = || -> Vec<Vec<f64>> {
+        (0..samples)
+            .map(|_| (0..k).map(|_| rng.uniform()).collect())
+            .collect()
+    };
+    let a = draw();
+    let b = draw();
+    let mut points = Vec::with_capacity(samples * (k + 2));
+    points.extend(a.iter().cloned());
+    points.extend(b.iter().cloned());
+    for i in 0..k {
+        points.extend(a.iter().zip(&b).map(|(a_row, b_row)| {
+            let mut row = a_row.clone();
+            row[i] = b_row[i];
+            row
+        }));
+    }
+    let mut y = model.evaluate(&points)?;
+    // Centre the outputs: the estimators lose precision when the mean is
+    // large relative to the spread.
+    let finite: Vec<f64> = y[..2 * samples]
+        .iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .collect();
+    let mean = finite.iter().sum::<f64>() / finite.len() as f64;
+    let variance = finite.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / finite.len() as f64;
+    y.iter_mut().for_each(|v| *v -= mean);
+    let (f_a, rest) = y.split_at(samples);
+    let (f_b, f_ab) = rest.split_at(samples);
+
+    let mut first = Vec::with_capacity(k);
+    let mut total = Vec::with_capacity(k);
+    for i in 0..k {
+        let f_abi = &f_ab[i * samples..(i + 1) * samples];
+        let (mut s, mut st, mut n) = (0.0, 0.0, 0usize);
+        for j in 0..samples {
+            if f_a[j].is_finite() && f_b[j].is_finite() && f_abi[j].is_finite() {
+                s += f_b[j] * (f_abi[j] - f_a[j]);
+                st += (f_a[j] - f_abi[j]).powi(2);
+                n += 1;
+            }
+        }
+        first.push(s / n as f64 / variance);
+        total.push(st / (2.0 * n as f64) / variance);
+    }
+    Ok(SobolIndices { first, total })
+}
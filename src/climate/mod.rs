@@ -0,0 +1,7 @@
+//! Climate change perturbation of forcing series: delta-change factors and
+//! quantile-mapping bias correction, applied to historical records before
+//! they drive the runoff and routing chain.
+
+pub mod perturb;
+#[cfg(feature = "python")]
+pub mod python;
@@ -0,0 +1,197 @@
+use crate::datetime::DateTime;
+use crate::error::{Error, Result};
+use crate::interp::interp;
+
+/// How a change is applied to a variable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChangeKind {
+    /// Changes are differences, as for temperature.
+    Additive,
+    /// Changes are ratios, as for precipitation and flow.
+    Multiplicative,
+}
+
+impl ChangeKind {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "additive" => Ok(ChangeKind::Additive),
+            "multiplicative" => Ok(ChangeKind::Multiplicative),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown change kind `{name}`; expected `additive` or `multiplicative`."
+            ))),
+        }
+    }
+
+    fn apply(self, value: f64, change: f64) -> f64 {
+        match self {
+            ChangeKind::Additive => value + change,
+            ChangeKind::Multiplicative => value * change,
+        }
+    }
+}
+
+/// Applies delta-change `factors` to `series`: one factor for the whole
+/// record, or twelve monthly factors selected by the calendar month of each
+/// value, the first at `start` (s since the epoch) and then every `dt`
+/// seconds.
+pub fn delta_change_rs(
+    series: &[f64],
+    factors: &[f64],
+    kind: ChangeKind,
+    start: f64,
+    dt: f64,
+) -> Result<Vec<f64>> {
+    match factors.len() {
+        1 => Ok(series.iter().map(|&x| kind.apply(x, factors[0])).collect()),
+        12 => {
+            if dt.is_nan() || dt <= 0.0 {
+                return Err(Error::InvalidParameter(
+                    "Monthly factors need a positive time step.".into(),
+                ));
+            }
+            Ok(series
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| {
+                    let month = DateTime::from_epoch(start + i as f64 * dt).month;
+                    kind.apply(x, factors[month as usize - 1])
+                })
+                .collect())
+        }
+        n => Err(Error::InvalidParameter(format!(
+            "Expected 1 or 12 delta-change factors, got {n}."
+        ))),
+    }
+}
+
+/// Quantile-mapping variant.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MappingMethod {
+    /// Empirical quantile mapping: each value is located in the modelled
+    /// reference distribution and replaced by the observed value at the
+    /// same non-exceedance probability.
+    Empirical,
+    /// Quantile delta mapping (Cannon et al., 2015): each value is located
+    /// in its own distribution, and its change from the modelled reference
+    /// at that probability is applied to the observed quantile, preserving
+    /// the modelled change in every quantile.
+    QuantileDelta,
+}
+
+impl MappingMethod {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "empirical" => Ok(MappingMethod::Empirical),
+            "quantile_delta" => Ok(MappingMethod::QuantileDelta),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown quantile mapping method `{name}`; expected `empirical` or `quantile_delta`."
+            ))),
+        }
+    }
+}
+
+/// Empirical distribution of the finite values of a sample, with Hazen
+/// plotting positions `(i + 0.5) / n`.
+struct Empirical {
+    values: Vec<f64>,
+    probabilities: Vec<f64>,
+}
+
+impl Empirical {
+    fn new(sample: &[f64], name: &str) -> Result<Self> {
+        let mut values: Vec<f64> = sample.iter().copied().filter(|x| x.is_finite()).collect();
+        if values.len() < 2 {
+            return Err(Error::Data(format!(
+                "The {name} series needs at least two finite values."
+            )));
+        }
+        values.sort_by(f64::total_cmp);
+        let n = values.len() as f64;
+        let probabilities = (0..values.len()).map(|i| (i as f64 + 0.5) / n).collect();
+        Ok(Empirical {
+            values,
+            probabilities,
+        })
+    }
+
+    /// Non-exceedance probability of `x`, clamped to the sample's range.
+    /// Ties take the probability of their middle rank.
+    fn cdf(&self, x: f64) -> f64 {
+        let below = self.values.partition_point(|&v| v < x);
+        let through = self.values.partition_point(|&v| v <= x);
+        if through > below {
+            let n = self.values.len() as f64;
+            return (0.5 * (below + through) as f64) / n;
+        }
+        let last = self.values.len() - 1;
+        if below == 0 {
+            self.probabilities[0]
+        } else if below > last {
+            self.probabilities[last]
+        } else {
+            interp(x, &self.values, &self.probabilities)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        interp(
+            p.clamp(
+                self.probabilities[0],
+                self.probabilities[self.values.len() - 1],
+            ),
+            &self.probabilities,
+            &self.values,
+        )
+    }
+}
+
+/// Bias-corrects `series` with the `observed` and `modelled` records of a
+/// common reference period. Values beyond the reference range keep the
+/// correction of the nearest reference quantile; non-finite values pass
+/// through unchanged.
+pub fn quantile_mapping_rs(
+    series: &[f64],
+    observed: &[f64],
+    modelled: &[f64],
+    method: MappingMethod,
+    kind: ChangeKind,
+) -> Result<Vec<f64>> {
+    let observed = Empirical::new(observed, "observed")?;
+    let modelled = Empirical::new(modelled, "modelled")?;
+    let own = match method {
+        MappingMethod::Empirical => None,
+        MappingMethod::QuantileDelta => Some(Empirical::new(series, "input")?),
+    };
+    let change = |to: f64, from: f64| match kind {
+        ChangeKind::Additive => to - from,
+        ChangeKind::Multiplicative => {
+            if from > 0.0 {
+                to / from
+            } else {
+                f64::NAN
+            }
+        }
+    };
+    Ok(series
+        .iter()
+        .map(|&x| {
+            if !x.is_finite() {
+                return x;
+            }
+            let p = own.as_ref().unwrap_or(&modelled).cdf(x);
+            let (target, reference) = (observed.quantile(p), modelled.quantile(p));
+            let corrected = match method {
+                // x ⊕ (obs(p) ⊖ mod(p)), exact mapping inside the range.
+                MappingMethod::Empirical => kind.apply(x, change(target, reference)),
+                MappingMethod::QuantileDelta => kind.apply(target, change(x, reference)),
+            };
+            // A zero modelled quantile leaves no ratio: take the observed
+            // quantile itself.
+            if corrected.is_nan() {
+                target
+            } else {
+                corrected
+            }
+        })
+        .collect())
+}
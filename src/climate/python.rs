@@ -0,0 +1,53 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta};
+
+use super::perturb::{delta_change_rs, quantile_mapping_rs, ChangeKind, MappingMethod};
+use crate::arrow::FloatSeries;
+use crate::time::{datetime_to_epoch, timedelta_to_seconds};
+
+#[pyfunction]
+#[pyo3(signature = (series, factors, start=None, time_step=None, kind="multiplicative"))]
+pub fn delta_change(
+    py: Python<'_>,
+    series: FloatSeries,
+    factors: Vec<f64>,
+    start: Option<Bound<'_, PyDateTime>>,
+    time_step: Option<Bound<'_, PyDelta>>,
+    kind: &str,
+) -> PyResult<Vec<f64>> {
+    let kind = ChangeKind::parse(kind)?;
+    let (start, dt) = match (start, time_step) {
+        (Some(start), Some(step)) => (datetime_to_epoch(&start), timedelta_to_seconds(&step)),
+        (None, None) if factors.len() == 1 => (0.0, f64::NAN),
+        _ => {
+            return Err(PyValueError::new_err(
+                "Monthly factors require both `start` and `time_step`.",
+            ))
+        }
+    };
+    Ok(py.allow_threads(|| delta_change_rs(&series.0, &factors, kind, start, dt))?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (series, observed, modelled, method="empirical", kind="multiplicative"))]
+pub fn quantile_mapping(
+    py: Python<'_>,
+    series: FloatSeries,
+    observed: FloatSeries,
+    modelled: FloatSeries,
+    method: &str,
+    kind: &str,
+) -> PyResult<Vec<f64>> {
+    let method = MappingMethod::parse(method)?;
+    let kind = ChangeKind::parse(kind)?;
+    Ok(py
+        .allow_threads(|| quantile_mapping_rs(&series.0, &observed.0, &modelled.0, method, kind))?)
+}
+
+#[pymodule]
+pub fn init_climate(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(delta_change, m)?)?;
+    m.add_function(wrap_pyfunction!(quantile_mapping, m)?)?;
+    Ok(())
+}
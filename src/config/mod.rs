@@ -0,0 +1,29 @@
+//! Model configuration values and their JSON / TOML representations.
+
+pub mod json;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod toml;
+mod value;
+
+pub use value::Value;
+
+use crate::error::{Error, Result};
+use std::path::Path;
+
+/// Reads a configuration file, choosing the parser from the file extension
+/// (`.toml`, `.json`, or a SWMM `.inp` file translated by
+/// [`crate::io::swmm::parse_inp`]).
+pub fn read_config(path: &Path) -> Result<Value> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| Error::Io(format!("{}: {e}", path.display())))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::parse(&text),
+        Some("json") => json::parse(&text),
+        Some("inp") => crate::io::swmm::parse_inp(&text),
+        _ => Err(Error::Data(format!(
+            "{}: configuration must be a `.toml`, `.json` or `.inp` file.",
+            path.display()
+        ))),
+    }
+}
@@ -0,0 +1,61 @@
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+
+use super::Value;
+
+impl<'py> FromPyObject<'py> for Value {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if ob.is_none() {
+            Ok(Value::Null)
+        } else if let Ok(b) = ob.downcast::<PyBool>() {
+            Ok(Value::Bool(b.is_true()))
+        } else if ob.is_instance_of::<PyInt>() || ob.is_instance_of::<PyFloat>() {
+            Ok(Value::Number(ob.extract()?))
+        } else if let Ok(s) = ob.downcast::<PyString>() {
+            Ok(Value::String(s.to_str()?.to_string()))
+        } else if let Ok(dict) = ob.downcast::<PyDict>() {
+            dict.iter()
+                .map(|(k, v)| Ok((k.extract::<String>()?, v.extract::<Value>()?)))
+                .collect::<PyResult<_>>()
+                .map(Value::Object)
+        } else if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+            ob.try_iter()?
+                .map(|item| item?.extract::<Value>())
+                .collect::<PyResult<_>>()
+                .map(Value::Array)
+        } else if let Ok(n) = ob.extract::<f64>() {
+            Ok(Value::Number(n))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "Cannot convert `{}` to a configuration value.",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
+
+/// Converts a configuration value to plain Python objects (`dict`, `list`,
+/// `str`, `float`, `bool`, `None`).
+pub fn value_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any().unbind(),
+        Value::Number(n) => n.into_pyobject(py)?.into_any().unbind(),
+        Value::String(s) => s.into_pyobject(py)?.into_any().unbind(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(value_to_py(py, item)?)?;
+            }
+            list.into_any().unbind()
+        }
+        Value::Object(entries) => {
+            let dict = PyDict::new(py);
+            for (k, v) in entries {
+                dict.set_item(k, value_to_py(py, v)?)?;
+            }
+            dict.into_any().unbind()
+        }
+    })
+}
@@ -0,0 +1,349 @@
+//! Reader for the subset of TOML used by model configurations: tables,
+//! arrays of tables, and `key = value` pairs whose values are strings,
+//! numbers, booleans, inline tables or (possibly multi-line) arrays.
+
+use super::Value;
+use crate::error::{Error, Result};
+
+/// Parses a TOML document.
+pub fn parse(text: &str) -> Result<Value> {
+    let mut root = Value::Object(Vec::new());
+    let mut path: Vec<String> = Vec::new();
+    let mut lines = text.lines().enumerate();
+
+    while let Some((number, raw)) = lines.next() {
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let err = |msg: &str| Error::Data(format!("Invalid TOML on line {}: {msg}.", number + 1));
+
+        if let Some(header) = line.strip_prefix("[[") {
+            let header = header
+                .strip_suffix("]]")
+                .ok_or_else(|| err("unclosed `[[`"))?;
+            path = split_key(header);
+            let (last, parents) = path.split_last().ok_or_else(|| err("empty table name"))?;
+            let parent = table_at(&mut root, parents).ok_or_else(|| err("key is not a table"))?;
+            match parent.get(last) {
+                None => parent.insert(last, Value::Array(Vec::new())),
+                Some(Value::Array(_)) => {}
+                Some(_) => return Err(err("key is not an array of tables")),
+            }
+            if let Some((_, Value::Array(items))) = match parent {
+                Value::Object(entries) => entries.iter_mut().find(|(k, _)| k == last),
+                _ => None,
+            } {
+                items.push(Value::Object(Vec::new()));
+            }
+        } else if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| err("unclosed `[`"))?;
+            path = split_key(header);
+            table_at(&mut root, &path).ok_or_else(|| err("key is not a table"))?;
+        } else {
+            let (key, rest) = line.split_once('=').ok_or_else(|| err("expected `=`"))?;
+            let mut rest = rest.trim().to_string();
+            // Multi-line arrays continue until brackets balance.
+            while bracket_depth(&rest) > 0 {
+                match lines.next() {
+                    Some((_, next)) => {
+                        rest.push(' ');
+                        rest.push_str(strip_comment(next).trim());
+                    }
+                    None => return Err(err("unclosed array")),
+                }
+            }
+            let mut parser = ValueParser {
+                chars: rest.chars().collect(),
+                pos: 0,
+            };
+            let value = parser.value().map_err(|msg| err(&msg))?;
+            parser.skip_whitespace();
+            if parser.pos != parser.chars.len() {
+                return Err(err("trailing characters"));
+            }
+            let table = table_at(&mut root, &path).ok_or_else(|| err("key is not a table"))?;
+            let key = split_key(key);
+            let (last, parents) = key.split_last().ok_or_else(|| err("empty key"))?;
+            let target = table_at(table, parents).ok_or_else(|| err("key is not a table"))?;
+            if target.get(last).is_some() {
+                return Err(err(&format!("duplicate key `{last}`")));
+            }
+            target.insert(last, value);
+        }
+    }
+    Ok(root)
+}
+
+fn split_key(key: &str) -> Vec<String> {
+    key.split('.')
+        .map(|part| part.trim().trim_matches('"').to_string())
+        .collect()
+}
+
+/// Walks (creating as needed) to the table at `path`. Arrays of tables
+/// resolve to their last element.
+fn table_at<'a>(root: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    let mut current = root;
+    for key in path {
+        if current.get(key).is_none() {
+            current.insert(key, Value::Object(Vec::new()));
+        }
+        let entries = match current {
+            Value::Object(entries) => entries,
+            _ => return None,
+        };
+        let next = &mut entries.iter_mut().find(|(k, _)| k == key)?.1;
+        current = match next {
+            Value::Array(items) => items.last_mut()?,
+            other => other,
+        };
+        if !matches!(current, Value::Object(_)) {
+            return None;
+        }
+    }
+    Some(current)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if in_string => escaped = !escaped,
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => escaped = false,
+        }
+        if c != '\\' {
+            escaped = false;
+        }
+    }
+    line
+}
+
+fn bracket_depth(text: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string = false;
+    for c in text.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '[' | '{' if !in_string => depth += 1,
+            ']' | '}' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+struct ValueParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl ValueParser {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> std::result::Result<Value, String> {
+        self.skip_whitespace();
+        match self.chars.get(self.pos) {
+            Some('"') => self.string().map(Value::String),
+            Some('\'') => {
+                self.pos += 1;
+                let start = self.pos;
+                while self.pos < self.chars.len() && self.chars[self.pos] != '\'' {
+                    self.pos += 1;
+                }
+                if self.pos == self.chars.len() {
+                    return Err("unterminated string".into());
+                }
+                self.pos += 1;
+                Ok(Value::String(
+                    self.chars[start..self.pos - 1].iter().collect(),
+                ))
+            }
+            Some('[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.chars.get(self.pos) == Some(&']') {
+                        self.pos += 1;
+                        return Ok(Value::Array(items));
+                    }
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.chars.get(self.pos) {
+                        Some(',') => self.pos += 1,
+                        Some(']') => {}
+                        _ => return Err("expected `,` or `]`".into()),
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut table = Value::Object(Vec::new());
+                loop {
+                    self.skip_whitespace();
+                    if self.chars.get(self.pos) == Some(&'}') {
+                        self.pos += 1;
+                        return Ok(table);
+                    }
+                    let start = self.pos;
+                    while self.pos < self.chars.len() && !matches!(self.chars[self.pos], '=' | '}')
+                    {
+                        self.pos += 1;
+                    }
+                    if self.chars.get(self.pos) != Some(&'=') {
+                        return Err("expected `=` in inline table".into());
+                    }
+                    let key: String = self.chars[start..self.pos].iter().collect();
+                    self.pos += 1;
+                    let value = self.value()?;
+                    table.insert(key.trim().trim_matches('"'), value);
+                    self.skip_whitespace();
+                    match self.chars.get(self.pos) {
+                        Some(',') => self.pos += 1,
+                        Some('}') => {}
+                        _ => return Err("expected `,` or `}`".into()),
+                    }
+                }
+            }
+            Some(_) => {
+                let start = self.pos;
+                while self.pos < self.chars.len()
+                    && !matches!(self.chars[self.pos], ',' | ']' | '}')
+                {
+                    self.pos += 1;
+                }
+                let token: String = self.chars[start..self.pos].iter().collect();
+                let token = token.trim();
+                match token {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "inf" | "+inf" => Ok(Value::Number(f64::INFINITY)),
+                    "-inf" => Ok(Value::Number(f64::NEG_INFINITY)),
+                    _ => token
+                        .replace('_', "")
+                        .parse::<f64>()
+                        .map(Value::Number)
+                        .map_err(|_| format!("unsupported value `{token}`")),
+                }
+            }
+            None => Err("missing value".into()),
+        }
+    }
+
+    fn string(&mut self) -> std::result::Result<String, String> {
+        self.pos += 1;
+        let mut out = String::new();
+        while let Some(&c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escape = self.chars.get(self.pos).copied();
+                    self.pos += 1;
+                    match escape {
+                        Some('n') => out.push('\n'),
+                        Some('t') => out.push('\t'),
+                        Some('r') => out.push('\r'),
+                        Some('"') => out.push('"'),
+                        Some('\\') => out.push('\\'),
+                        _ => return Err("unsupported escape".into()),
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        Err("unterminated string".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tables_arrays_and_values() {
+        let value = parse(
+            r#"
+# Network
+name = "creek" # trailing comment
+time_step = 3_600
+
+[adaptive_step]
+tolerance = 0.1
+
+[[nodes]]
+id = "reach"
+type = "muskingum"
+k = 7200.0
+allow_negative_c0 = false
+
+[[nodes]]
+id = "dam"
+elevation = [
+    100.0, 101.0,  # multi-line
+    102.0,
+]
+options = { units = 'us', seepage = -inf }
+"#,
+        )
+        .unwrap();
+        assert_eq!(value.str_field("name").unwrap(), "creek");
+        assert_eq!(value.f64_field("time_step").unwrap(), 3600.0);
+        let step = value.get("adaptive_step").unwrap();
+        assert_eq!(step.f64_field("tolerance").unwrap(), 0.1);
+        let nodes = value.get("nodes").and_then(Value::as_array).unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].f64_field("k").unwrap(), 7200.0);
+        assert_eq!(
+            nodes[0].get("allow_negative_c0").and_then(Value::as_bool),
+            Some(false)
+        );
+        assert_eq!(
+            nodes[1].f64_array_field("elevation").unwrap(),
+            [100.0, 101.0, 102.0]
+        );
+        let options = nodes[1].get("options").unwrap();
+        assert_eq!(options.str_field("units").unwrap(), "us");
+        assert_eq!(options.f64_field("seepage").unwrap(), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn dotted_keys_create_tables() {
+        let value = parse("a.b = 1\n[c.d]\ne = \"x\\ty\"\n").unwrap();
+        assert_eq!(value.get("a").unwrap().f64_field("b").unwrap(), 1.0);
+        let d = value.get("c").and_then(|c| c.get("d")).unwrap();
+        assert_eq!(d.str_field("e").unwrap(), "x\ty");
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_input() {
+        for (text, line) in [
+            ("a = 1\nb 2\n", 2),
+            ("a = 1\na = 2\n", 2),
+            ("[table\n", 1),
+            ("[[nodes]\n", 1),
+            ("a = [1, 2\n", 1),
+            ("a = \"open\n", 1),
+            ("a = 1 2\n", 1),
+            ("a = 1\n[a]\n", 2),
+            ("a = nope\n", 1),
+        ] {
+            match parse(text) {
+                Err(Error::Data(msg)) => {
+                    assert!(msg.contains(&format!("line {line}")), "{text:?}: {msg}")
+                }
+                other => panic!("{text:?} parsed as {other:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::{Error, Result};
+
+/// A dynamically typed configuration value.
+///
+/// Objects keep their keys in insertion order so that serialised
+/// configurations are deterministic.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Looks up `key` in an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Inserts or replaces `key` in an object. Does nothing for other values.
+    pub fn insert(&mut self, key: &str, value: Value) {
+        if let Value::Object(entries) = self {
+            match entries.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = value,
+                None => entries.push((key.to_string(), value)),
+            }
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Required numeric field of an object.
+    pub fn f64_field(&self, key: &str) -> Result<f64> {
+        self.get(key)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| Error::Data(format!("Missing or non-numeric field `{key}`.")))
+    }
+
+    /// Optional numeric field of an object.
+    pub fn opt_f64_field(&self, key: &str) -> Result<Option<f64>> {
+        match self.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value
+                .as_f64()
+                .map(Some)
+                .ok_or_else(|| Error::Data(format!("Field `{key}` must be a number."))),
+        }
+    }
+
+    /// Required string field of an object.
+    pub fn str_field(&self, key: &str) -> Result<&str> {
+        self.get(key)
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Data(format!("Missing or non-string field `{key}`.")))
+    }
+
+    /// Optional string field of an object.
+    pub fn opt_str_field(&self, key: &str) -> Result<Option<&str>> {
+        match self.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value
+                .as_str()
+                .map(Some)
+                .ok_or_else(|| Error::Data(format!("Field `{key}` must be a string."))),
+        }
+    }
+
+    /// Required array-of-numbers field of an object.
+    pub fn f64_array_field(&self, key: &str) -> Result<Vec<f64>> {
+        self.get(key)
+            .and_then(Value::as_array)
+            .and_then(|items| items.iter().map(Value::as_f64).collect())
+            .ok_or_else(|| Error::Data(format!("Field `{key}` must be an array of numbers.")))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<&[f64]> for Value {
+    fn from(values: &[f64]) -> Self {
+        Value::Array(values.iter().map(|&n| Value::Number(n)).collect())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
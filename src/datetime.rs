@@ -0,0 +1,295 @@
+//! Calendar conversions between civil date-times and seconds since the Unix
+//! epoch, plus a `strftime`-style parser and formatter.
+//!
+//! Times are treated as naive (time-zone free); the epoch offset is only used
+//! as a convenient linear time axis.
+
+/// Days since 1970-01-01 of the proleptic Gregorian date `y-m-d`.
+pub fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Number of days in month `m` (1–12) of year `y`.
+pub fn days_in_month(y: i64, m: u32) -> u32 {
+    match m {
+        2 if y % 4 == 0 && (y % 100 != 0 || y % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Proleptic Gregorian date `(y, m, d)` of `days` since 1970-01-01.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+/// Broken-down civil date-time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub microsecond: u32,
+}
+
+impl DateTime {
+    /// Seconds since 1970-01-01T00:00:00.
+    pub fn to_epoch(&self) -> f64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        (days * 86_400 + (self.hour * 3600 + self.minute * 60 + self.second) as i64) as f64
+            + self.microsecond as f64 * 1e-6
+    }
+
+    /// Date-time `seconds` after 1970-01-01T00:00:00, rounded to the
+    /// nearest microsecond.
+    pub fn from_epoch(seconds: f64) -> Self {
+        let micros = (seconds * 1e6).round() as i64;
+        let days = micros.div_euclid(86_400_000_000);
+        let rem = micros.rem_euclid(86_400_000_000);
+        let (year, month, day) = civil_from_days(days);
+        let secs = (rem / 1_000_000) as u32;
+        DateTime {
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs / 60 % 60,
+            second: secs % 60,
+            microsecond: (rem % 1_000_000) as u32,
+        }
+    }
+}
+
+/// Formats tried, in order, when no explicit format is given.
+pub const ISO_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d",
+    "%m/%d/%Y %H:%M",
+];
+
+/// Parses `text` with a `strftime`-style `format` supporting `%Y`, `%y`,
+/// `%m`, `%d`, `%H`, `%M`, `%S`, `%f` and `%%`. `%S` also accepts a
+/// fractional part (e.g. `12.5`). Returns seconds since the epoch.
+pub fn parse_datetime(text: &str, format: &str) -> Option<f64> {
+    let text = text.trim().as_bytes();
+    let mut pos = 0;
+    let mut dt = DateTime {
+        year: 1970,
+        month: 1,
+        day: 1,
+        ..Default::default()
+    };
+    let mut fraction = 0.0;
+
+    let digits = |pos: &mut usize, max: usize| -> Option<u32> {
+        let start = *pos;
+        while *pos < text.len() && *pos - start < max && text[*pos].is_ascii_digit() {
+            *pos += 1;
+        }
+        std::str::from_utf8(&text[start..*pos]).ok()?.parse().ok()
+    };
+
+    let mut spec = format.chars();
+    while let Some(c) = spec.next() {
+        if c != '%' {
+            let mut buf = [0u8; 4];
+            let literal = c.encode_utf8(&mut buf).as_bytes();
+            if !text[pos..].starts_with(literal) {
+                return None;
+            }
+            pos += literal.len();
+            continue;
+        }
+        match spec.next()? {
+            'Y' => dt.year = digits(&mut pos, 4)? as i64,
+            'y' => {
+                // POSIX pivot: 69–99 are 1969–1999 and 00–68 are 2000–2068.
+                let year = digits(&mut pos, 2)? as i64;
+                dt.year = if year < 69 { 2000 + year } else { 1900 + year };
+            }
+            'm' => dt.month = digits(&mut pos, 2)?,
+            'd' => dt.day = digits(&mut pos, 2)?,
+            'H' => dt.hour = digits(&mut pos, 2)?,
+            'M' => dt.minute = digits(&mut pos, 2)?,
+            'S' => {
+                dt.second = digits(&mut pos, 2)?;
+                if text.get(pos) == Some(&b'.') {
+                    let start = pos;
+                    pos += 1;
+                    while pos < text.len() && text[pos].is_ascii_digit() {
+                        pos += 1;
+                    }
+                    fraction = std::str::from_utf8(&text[start..pos]).ok()?.parse().ok()?;
+                }
+            }
+            'f' => {
+                let start = pos;
+                let value = digits(&mut pos, 9)?;
+                fraction = value as f64 / 10f64.powi((pos - start) as i32);
+            }
+            '%' => {
+                if text.get(pos) != Some(&b'%') {
+                    return None;
+                }
+                pos += 1;
+            }
+            _ => return None,
+        }
+    }
+    let valid = pos == text.len()
+        && (1..=12).contains(&dt.month)
+        && (1..=days_in_month(dt.year, dt.month)).contains(&dt.day)
+        && dt.hour < 24
+        && dt.minute < 60
+        && dt.second < 61;
+    valid.then(|| dt.to_epoch() + fraction)
+}
+
+/// Finds the first of [`ISO_FORMATS`] that parses `text`.
+pub fn detect_format(text: &str) -> Option<&'static str> {
+    ISO_FORMATS
+        .into_iter()
+        .find(|format| parse_datetime(text, format).is_some())
+}
+
+/// Formats `seconds` since the epoch with a `strftime`-style `format`
+/// (same directives as [`parse_datetime`]).
+pub fn format_datetime(seconds: f64, format: &str) -> String {
+    let dt = DateTime::from_epoch(seconds);
+    let mut out = String::with_capacity(format.len() + 8);
+    let mut spec = format.chars();
+    while let Some(c) = spec.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match spec.next() {
+            Some('Y') => out.push_str(&format!("{:04}", dt.year)),
+            Some('y') => out.push_str(&format!("{:02}", dt.year.rem_euclid(100))),
+            Some('m') => out.push_str(&format!("{:02}", dt.month)),
+            Some('d') => out.push_str(&format!("{:02}", dt.day)),
+            Some('H') => out.push_str(&format!("{:02}", dt.hour)),
+            Some('M') => out.push_str(&format!("{:02}", dt.minute)),
+            Some('S') => out.push_str(&format!("{:02}", dt.second)),
+            Some('f') => out.push_str(&format!("{:06}", dt.microsecond)),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_known_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        for days in [-719_468, -1, 0, 11_016, 11_017, 19_417, 2_932_896] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+        assert_eq!(civil_from_days(19_417), (2023, 3, 1));
+    }
+
+    #[test]
+    fn counts_days_in_month() {
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2023, 2), 28);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2023, 4), 30);
+        assert_eq!(days_in_month(2023, 12), 31);
+    }
+
+    #[test]
+    fn parses_and_formats_datetimes() {
+        let t = parse_datetime("2021-03-14 15:09:26", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(t, 1_615_734_566.0);
+        assert_eq!(
+            format_datetime(t, "%Y-%m-%dT%H:%M:%S"),
+            "2021-03-14T15:09:26"
+        );
+        assert_eq!(
+            parse_datetime("14/03/21 15:09:26.25", "%d/%m/%y %H:%M:%S"),
+            Some(t + 0.25)
+        );
+        let t = parse_datetime("20210314150926_5", "%Y%m%d%H%M%S_%f").unwrap();
+        assert_eq!(format_datetime(t, "%H:%M:%S.%f %%"), "15:09:26.500000 %");
+        assert_eq!(
+            parse_datetime("2024-02-29", "%Y-%m-%d"),
+            Some(days_from_civil(2024, 2, 29) as f64 * 86_400.0)
+        );
+    }
+
+    #[test]
+    fn two_digit_years_pivot_at_1969() {
+        let year = |text: &str| {
+            let t = parse_datetime(text, "%y-%m-%d").unwrap();
+            civil_from_days((t / 86_400.0).floor() as i64).0
+        };
+        assert_eq!(year("00-01-01"), 2000);
+        assert_eq!(year("68-12-31"), 2068);
+        assert_eq!(year("69-01-01"), 1969);
+        assert_eq!(year("99-12-31"), 1999);
+        let t = parse_datetime("85-07-04", "%y-%m-%d").unwrap();
+        assert_eq!(format_datetime(t, "%y%m%d"), "850704");
+    }
+
+    #[test]
+    fn rejects_invalid_datetimes() {
+        for text in [
+            "2021-02-31",
+            "2023-02-29",
+            "2021-04-31",
+            "2021-13-01",
+            "2021-00-10",
+            "2021-01-00",
+            "2021-01-01x",
+            "2021-1",
+        ] {
+            assert_eq!(parse_datetime(text, "%Y-%m-%d"), None, "{text}");
+        }
+        assert_eq!(parse_datetime("2021-01-01 24:00", "%Y-%m-%d %H:%M"), None);
+        assert_eq!(parse_datetime("2021-01-01 10:60", "%Y-%m-%d %H:%M"), None);
+        assert_eq!(parse_datetime("2021-01-01", "%Y-%m-%d %Q"), None);
+    }
+
+    #[test]
+    fn detects_iso_formats() {
+        assert_eq!(detect_format("2021-01-01T06:30"), Some("%Y-%m-%dT%H:%M"));
+        assert_eq!(detect_format("2021-01-01"), Some("%Y-%m-%d"));
+        assert_eq!(detect_format("01/31/2021 06:30"), Some("%m/%d/%Y %H:%M"));
+        assert_eq!(detect_format("2021-02-31"), None);
+        assert_eq!(detect_format("yesterday"), None);
+    }
+}
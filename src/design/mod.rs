@@ -0,0 +1,6 @@
+//! Design-flood workflows that chain the rainfall, runoff and reservoir
+//! components, such as the probable maximum flood.
+
+pub mod pmf;
+#[cfg(feature = "python")]
+pub mod python;
@@ -0,0 +1,176 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+
+use super::pmf::{pmf_rs, PmfBasin, PmfDam, UnitHydrograph};
+use crate::arrow::FloatSeries;
+use crate::reservoir::level_pool::LevelPoolReservoir;
+use crate::runoff::loss::LossMethod;
+use crate::time::timedelta_to_seconds;
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (rainfall, time_step, area, elevation, storage, outflow, initial_elevation, crest_elevation, loss="curve_number", curve_number=None, initial_abstraction_ratio=0.2, initial_loss=0.0, loss_rate=0.0, lag=None, unit_hydrograph=None, time_area=None, storage_coefficient=None, peaking_factor=1.0, baseflow=0.0, freeboard=0.0, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn pmf<'py>(
+    py: Python<'py>,
+    rainfall: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    area: f64,
+    elevation: Vec<f64>,
+    storage: Vec<f64>,
+    outflow: Vec<f64>,
+    initial_elevation: f64,
+    crest_elevation: f64,
+    loss: &str,
+    curve_number: Option<f64>,
+    initial_abstraction_ratio: f64,
+    initial_loss: f64,
+    loss_rate: f64,
+    lag: Option<Bound<'py, PyDelta>>,
+    unit_hydrograph: Option<Vec<f64>>,
+    time_area: Option<Vec<f64>>,
+    storage_coefficient: Option<Bound<'py, PyDelta>>,
+    peaking_factor: f64,
+    baseflow: f64,
+    freeboard: f64,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dt = timedelta_to_seconds(&time_step);
+    let loss = match (loss.to_ascii_lowercase().as_str(), curve_number) {
+        ("curve_number" | "scs", Some(curve_number)) => LossMethod::CurveNumber {
+            curve_number,
+            ratio: initial_abstraction_ratio,
+        },
+        ("curve_number" | "scs", None) => {
+            return Err(PyValueError::new_err(
+                "Loss method `curve_number` requires `curve_number`.",
+            ))
+        }
+        ("initial_constant", _) => LossMethod::InitialConstant {
+            initial: units.small_depth_to_si(initial_loss),
+            rate: units.rate_to_si(loss_rate),
+        },
+        (other, _) => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown loss method `{other}`; expected `curve_number` or `initial_constant`."
+            )))
+        }
+    };
+    // Ordinates are given per mm or inch of excess.
+    let per_metre = 1.0 / units.small_depth_to_si(1.0);
+    let unit_hydrograph = match (lag, unit_hydrograph, time_area, storage_coefficient) {
+        (Some(lag), None, None, None) => UnitHydrograph::Scs {
+            lag: timedelta_to_seconds(&lag),
+        },
+        (None, Some(ordinates), None, None) => UnitHydrograph::Ordinates(
+            ordinates
+                .iter()
+                .map(|&u| units.discharge_to_si(u) * per_metre)
+                .collect(),
+        ),
+        (None, None, Some(time_area), Some(storage)) => UnitHydrograph::Clark {
+            time_area: time_area
+                .iter()
+                .map(|&a| units.basin_area_to_si(a))
+                .collect(),
+            storage: timedelta_to_seconds(&storage),
+        },
+        _ => {
+            return Err(PyValueError::new_err(
+                "Give either `lag` for an NRCS unit hydrograph, `unit_hydrograph` ordinates, \
+                 or `time_area` and `storage_coefficient` for a Clark unit hydrograph.",
+            ))
+        }
+    };
+    let basin = PmfBasin {
+        area: units.basin_area_to_si(area),
+        loss,
+        unit_hydrograph,
+        peaking_factor,
+        baseflow: units.discharge_to_si(baseflow),
+    };
+    let reservoir = LevelPoolReservoir::new(
+        elevation.iter().map(|&z| units.length_to_si(z)).collect(),
+        storage.iter().map(|&s| units.volume_to_si(s)).collect(),
+        outflow.iter().map(|&q| units.discharge_to_si(q)).collect(),
+        units.length_to_si(initial_elevation),
+    )?;
+    let dam = PmfDam {
+        reservoir,
+        crest_elevation: units.length_to_si(crest_elevation),
+        required_freeboard: units.length_to_si(freeboard),
+    };
+    let rain: Vec<f64> = rainfall
+        .0
+        .iter()
+        .map(|&p| units.small_depth_to_si(p))
+        .collect();
+    let report = py.allow_threads(|| pmf_rs(&rain, dt, &basin, &dam))?;
+
+    let depth = |values: &[f64]| -> Vec<f64> {
+        values
+            .iter()
+            .map(|&d| units.small_depth_from_si(d))
+            .collect()
+    };
+    let discharge = |values: &[f64]| -> Vec<f64> {
+        values.iter().map(|&q| units.discharge_from_si(q)).collect()
+    };
+    let time = |step: usize| PyDelta::new(py, 0, (step as f64 * dt).round() as i32, 0, true);
+    let summary = PyDict::new(py);
+    summary.set_item(
+        "rainfall",
+        units.small_depth_from_si(report.rainfall.iter().sum()),
+    )?;
+    summary.set_item("loss", units.small_depth_from_si(report.loss.iter().sum()))?;
+    summary.set_item(
+        "excess",
+        units.small_depth_from_si(report.excess.iter().sum()),
+    )?;
+    summary.set_item("peak_inflow", units.discharge_from_si(report.peak_inflow()))?;
+    summary.set_item("peak_inflow_time", time(report.peak_inflow_step)?)?;
+    summary.set_item(
+        "peak_outflow",
+        units.discharge_from_si(report.peak_outflow()),
+    )?;
+    summary.set_item("peak_outflow_time", time(report.peak_outflow_step)?)?;
+    summary.set_item(
+        "max_elevation",
+        units.length_from_si(report.max_elevation()),
+    )?;
+    summary.set_item("max_elevation_time", time(report.max_elevation_step)?)?;
+    summary.set_item("freeboard", units.length_from_si(report.freeboard))?;
+    summary.set_item("overtopped", report.overtopped())?;
+    summary.set_item("adequate", report.adequate)?;
+
+    let result = PyDict::new(py);
+    result.set_item("summary", summary)?;
+    result.set_item("rainfall", depth(&report.rainfall))?;
+    result.set_item("loss", depth(&report.loss))?;
+    result.set_item("excess", depth(&report.excess))?;
+    result.set_item(
+        "unit_hydrograph",
+        discharge(&report.unit_hydrograph)
+            .into_iter()
+            .map(|u| u / per_metre)
+            .collect::<Vec<_>>(),
+    )?;
+    result.set_item("inflow", discharge(&report.inflow))?;
+    result.set_item("outflow", discharge(&report.outflow))?;
+    result.set_item(
+        "elevation",
+        report
+            .elevation
+            .iter()
+            .map(|&z| units.length_from_si(z))
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(result)
+}
+
+#[pymodule]
+pub fn init_design(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(pmf, m)?)?;
+    Ok(())
+}
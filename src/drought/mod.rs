@@ -0,0 +1,6 @@
+//! Drought indices: the Standardized Precipitation Index (SPI) and the
+//! Standardized Streamflow Index (SSI).
+
+#[cfg(feature = "python")]
+pub mod python;
+pub mod standardized;
@@ -0,0 +1,58 @@
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+
+use super::standardized::{standardized_indices_rs, IndexDistribution};
+use crate::arrow::FloatSeries;
+
+/// Indices keyed by series name and then by window.
+type Indices = BTreeMap<String, BTreeMap<usize, Vec<f64>>>;
+
+fn indices(
+    py: Python<'_>,
+    series: BTreeMap<String, FloatSeries>,
+    windows: Vec<usize>,
+    seasons: usize,
+    distribution: &str,
+) -> PyResult<Indices> {
+    let distribution = IndexDistribution::parse(distribution)?;
+    let (names, values): (Vec<String>, Vec<Vec<f64>>) =
+        series.into_iter().map(|(name, s)| (name, s.0)).unzip();
+    let results =
+        py.allow_threads(|| standardized_indices_rs(&values, &windows, seasons, distribution))?;
+    Ok(names
+        .into_iter()
+        .zip(results)
+        .map(|(name, by_window)| (name, windows.iter().copied().zip(by_window).collect()))
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (precipitation, windows=vec![1, 3, 6, 12], seasons=12, distribution="gamma"))]
+pub fn spi(
+    py: Python<'_>,
+    precipitation: BTreeMap<String, FloatSeries>,
+    windows: Vec<usize>,
+    seasons: usize,
+    distribution: &str,
+) -> PyResult<Indices> {
+    indices(py, precipitation, windows, seasons, distribution)
+}
+
+#[pyfunction]
+#[pyo3(signature = (flows, windows=vec![1, 3, 6, 12], seasons=12, distribution="lognormal"))]
+pub fn ssi(
+    py: Python<'_>,
+    flows: BTreeMap<String, FloatSeries>,
+    windows: Vec<usize>,
+    seasons: usize,
+    distribution: &str,
+) -> PyResult<Indices> {
+    indices(py, flows, windows, seasons, distribution)
+}
+
+#[pymodule]
+pub fn init_drought(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(spi, m)?)?;
+    m.add_function(wrap_pyfunction!(ssi, m)?)?;
+    Ok(())
+}
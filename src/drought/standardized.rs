@@ -0,0 +1,160 @@
+//! Standardized indices of accumulated series (McKee et al., 1993).
+//!
+//! The series is summed over a trailing window of `k` steps, a
+//! distribution is fitted separately to the sums of each season (e.g. each
+//! calendar month) and every sum is mapped through its fitted distribution
+//! function to a standard normal deviate. Zero sums are handled with a
+//! mixed distribution, `H(x) = q + (1 - q) G(x)`, where `q` is the
+//! fraction of zeros in the season.
+
+use crate::error::{Error, Result};
+use crate::frequency::distributions::{normal_cdf, normal_quantile, regularized_gamma};
+use crate::parallel::par_map;
+
+/// Distribution fitted to the non-zero accumulated values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexDistribution {
+    /// Two-parameter gamma, by Thom's maximum-likelihood approximation.
+    Gamma,
+    /// Log-normal, from the mean and standard deviation of the logarithms.
+    LogNormal,
+}
+
+impl IndexDistribution {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "gamma" => Ok(IndexDistribution::Gamma),
+            "lognormal" | "log_normal" => Ok(IndexDistribution::LogNormal),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown distribution `{name}`; expected `gamma` or `lognormal`."
+            ))),
+        }
+    }
+}
+
+/// Fitted distribution of one season.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Fit {
+    Gamma { shape: f64, scale: f64 },
+    LogNormal { mean: f64, std: f64 },
+}
+
+impl Fit {
+    /// Fits `distribution` to positive `values`; `None` if there are fewer
+    /// than three or they do not vary.
+    fn new(values: &[f64], distribution: IndexDistribution) -> Option<Self> {
+        let n = values.len() as f64;
+        if values.len() < 3 {
+            return None;
+        }
+        let mean_log = values.iter().map(|x| x.ln()).sum::<f64>() / n;
+        match distribution {
+            IndexDistribution::Gamma => {
+                let mean = values.iter().sum::<f64>() / n;
+                let a = mean.ln() - mean_log;
+                if a <= 0.0 {
+                    return None;
+                }
+                let shape = (1.0 + (1.0 + 4.0 * a / 3.0).sqrt()) / (4.0 * a);
+                Some(Fit::Gamma {
+                    shape,
+                    scale: mean / shape,
+                })
+            }
+            IndexDistribution::LogNormal => {
+                let variance = values
+                    .iter()
+                    .map(|x| (x.ln() - mean_log).powi(2))
+                    .sum::<f64>()
+                    / (n - 1.0);
+                (variance > 0.0).then(|| Fit::LogNormal {
+                    mean: mean_log,
+                    std: variance.sqrt(),
+                })
+            }
+        }
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        match *self {
+            Fit::Gamma { shape, scale } => regularized_gamma(shape, x / scale),
+            Fit::LogNormal { mean, std } => normal_cdf((x.ln() - mean) / std),
+        }
+    }
+}
+
+/// Trailing sums of `window` steps; the first `window - 1` values, and any
+/// window containing NaN, are NaN.
+pub fn accumulate(series: &[f64], window: usize) -> Vec<f64> {
+    (0..series.len())
+        .map(|t| {
+            if t + 1 < window {
+                f64::NAN
+            } else {
+                series[t + 1 - window..=t].iter().sum()
+            }
+        })
+        .collect()
+}
+
+/// Standardized index of `series` accumulated over `window` steps, fitting
+/// a separate distribution to every `seasons`-th step (12 for monthly
+/// data; 1 for a single fit). Negative values count as zero. Steps whose
+/// season has too few non-zero values to fit are NaN.
+pub fn standardized_index(
+    series: &[f64],
+    window: usize,
+    seasons: usize,
+    distribution: IndexDistribution,
+) -> Result<Vec<f64>> {
+    if window == 0 || seasons == 0 {
+        return Err(Error::InvalidParameter(
+            "The accumulation window and the number of seasons must be positive.".into(),
+        ));
+    }
+    // `max` would replace NaN, so negative sums are clamped explicitly.
+    let sums: Vec<f64> = accumulate(series, window)
+        .into_iter()
+        .map(|x| if x < 0.0 { 0.0 } else { x })
+        .collect();
+    let mut index = vec![f64::NAN; sums.len()];
+    for season in 0..seasons {
+        let steps: Vec<usize> = (season..sums.len())
+            .step_by(seasons)
+            .filter(|&t| !sums[t].is_nan())
+            .collect();
+        let positive: Vec<f64> = steps
+            .iter()
+            .map(|&t| sums[t])
+            .filter(|&x| x > 0.0)
+            .collect();
+        let Some(fit) = Fit::new(&positive, distribution) else {
+            continue;
+        };
+        let zero_fraction = 1.0 - positive.len() as f64 / steps.len() as f64;
+        for &t in &steps {
+            let x = sums[t];
+            let g = if x > 0.0 { fit.cdf(x) } else { 0.0 };
+            index[t] = normal_quantile(zero_fraction + (1.0 - zero_fraction) * g);
+        }
+    }
+    Ok(index)
+}
+
+/// Standardized indices of many series (e.g. stations or grid cells) in
+/// parallel: `result[i][j]` is series `i` accumulated over `windows[j]`.
+pub fn standardized_indices_rs(
+    series: &[Vec<f64>],
+    windows: &[usize],
+    seasons: usize,
+    distribution: IndexDistribution,
+) -> Result<Vec<Vec<Vec<f64>>>> {
+    par_map(series, |s| {
+        windows
+            .iter()
+            .map(|&w| standardized_index(s, w, seasons, distribution))
+            .collect::<Result<Vec<_>>>()
+    })
+    .into_iter()
+    .collect()
+}
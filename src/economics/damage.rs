@@ -0,0 +1,131 @@
+use crate::error::{Error, Result};
+use crate::interp::interp;
+
+/// Damage as a function of peak stage or flow at a damage reach.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DamageCurve {
+    /// Stages or flows, strictly increasing.
+    pub values: Vec<f64>,
+    /// Damage at each value, non-decreasing and not negative.
+    pub damages: Vec<f64>,
+}
+
+impl DamageCurve {
+    pub fn new(values: Vec<f64>, damages: Vec<f64>) -> Result<Self> {
+        if values.is_empty() || values.len() != damages.len() {
+            return Err(Error::InvalidParameter(
+                "A damage curve needs at least one point with one damage per value.".into(),
+            ));
+        }
+        if values.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(Error::InvalidParameter(
+                "Damage curve values must be strictly increasing.".into(),
+            ));
+        }
+        if damages.iter().any(|d| d.is_nan() || *d < 0.0) || damages.windows(2).any(|w| w[1] < w[0])
+        {
+            return Err(Error::InvalidParameter(
+                "Damages must be non-negative and non-decreasing.".into(),
+            ));
+        }
+        Ok(DamageCurve { values, damages })
+    }
+
+    /// Damage at `peak`: zero below the first value and held at the last
+    /// damage above the last value. A `NaN` peak (no flooding recorded)
+    /// gives zero.
+    pub fn damage(&self, peak: f64) -> f64 {
+        let last = self.values.len() - 1;
+        if peak.is_nan() || peak < self.values[0] {
+            0.0
+        } else if peak >= self.values[last] {
+            self.damages[last]
+        } else {
+            interp(peak, &self.values, &self.damages)
+        }
+    }
+}
+
+/// Expected annual damage from events with annual exceedance
+/// `probabilities` and their `damages`, by trapezoidal integration of the
+/// damage–probability curve between the most and least frequent events.
+/// Include a frequent zero-damage event (and a rare one held at the largest
+/// damage) to bound the curve beyond the simulated events.
+pub fn expected_annual_damage(probabilities: &[f64], damages: &[f64]) -> Result<f64> {
+    if probabilities.len() != damages.len() || probabilities.len() < 2 {
+        return Err(Error::InvalidParameter(
+            "Expected annual damage needs at least two events with one damage each.".into(),
+        ));
+    }
+    if probabilities.iter().any(|p| !(*p > 0.0 && *p <= 1.0)) {
+        return Err(Error::InvalidParameter(
+            "Annual exceedance probabilities must be within (0, 1].".into(),
+        ));
+    }
+    let mut events: Vec<(f64, f64)> = probabilities
+        .iter()
+        .copied()
+        .zip(damages.iter().copied())
+        .collect();
+    events.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if events.windows(2).any(|w| w[1].0 == w[0].0) {
+        return Err(Error::InvalidParameter(
+            "Each event must have a different exceedance probability.".into(),
+        ));
+    }
+    Ok(events
+        .windows(2)
+        .map(|w| 0.5 * (w[0].1 + w[1].1) * (w[1].0 - w[0].0))
+        .sum())
+}
+
+/// Damages and expected annual damage of a set of frequency events.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DamageAnalysis {
+    /// Damage of each event at each damage reach, `[reach][event]`.
+    pub event_damages: Vec<Vec<f64>>,
+    /// Total damage of each event over all reaches.
+    pub total_damages: Vec<f64>,
+    /// Expected annual damage of each reach.
+    pub expected_annual_damage: Vec<f64>,
+    /// Expected annual damage over all reaches.
+    pub total_expected_annual_damage: f64,
+}
+
+/// Evaluates `curves[r]` at the routed `peaks[r][e]` of each reach `r` and
+/// event `e` with exceedance `probabilities[e]`, and integrates the expected
+/// annual damage of each reach and of their total.
+pub fn damage_analysis_rs(
+    probabilities: &[f64],
+    peaks: &[Vec<f64>],
+    curves: &[DamageCurve],
+) -> Result<DamageAnalysis> {
+    if peaks.len() != curves.len() {
+        return Err(Error::InvalidParameter(
+            "Each damage reach needs one damage curve.".into(),
+        ));
+    }
+    if peaks.iter().any(|p| p.len() != probabilities.len()) {
+        return Err(Error::InvalidParameter(
+            "Each damage reach needs one peak per event.".into(),
+        ));
+    }
+    let event_damages: Vec<Vec<f64>> = peaks
+        .iter()
+        .zip(curves)
+        .map(|(peaks, curve)| peaks.iter().map(|&p| curve.damage(p)).collect())
+        .collect();
+    let total_damages: Vec<f64> = (0..probabilities.len())
+        .map(|e| event_damages.iter().map(|d| d[e]).sum())
+        .collect();
+    let by_reach = event_damages
+        .iter()
+        .map(|d| expected_annual_damage(probabilities, d))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DamageAnalysis {
+        total_expected_annual_damage: expected_annual_damage(probabilities, &total_damages)?,
+        event_damages,
+        total_damages,
+        expected_annual_damage: by_reach,
+    })
+}
@@ -0,0 +1,7 @@
+//! Flood damage economics: stage–damage and flow–damage curves evaluated
+//! at routed peaks, and expected annual damage integrated over a set of
+//! frequency events.
+
+pub mod damage;
+#[cfg(feature = "python")]
+pub mod python;
@@ -0,0 +1,78 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::BTreeMap;
+
+use super::damage::{self, damage_analysis_rs, DamageCurve};
+
+/// Damage curves keyed by damage reach, matched to `peaks` by key.
+fn matched_curves(
+    keys: Vec<&String>,
+    mut curves: BTreeMap<String, (Vec<f64>, Vec<f64>)>,
+) -> PyResult<Vec<DamageCurve>> {
+    keys.into_iter()
+        .map(|key| {
+            let (values, damages) = curves.remove(key).ok_or_else(|| {
+                PyValueError::new_err(format!("No damage curve was given for `{key}`."))
+            })?;
+            Ok(DamageCurve::new(values, damages)?)
+        })
+        .collect()
+}
+
+#[pyfunction]
+pub fn event_damage(
+    peaks: BTreeMap<String, f64>,
+    curves: BTreeMap<String, (Vec<f64>, Vec<f64>)>,
+) -> PyResult<BTreeMap<String, f64>> {
+    let curves = matched_curves(peaks.keys().collect(), curves)?;
+    Ok(peaks
+        .iter()
+        .zip(&curves)
+        .map(|((key, &peak), curve)| (key.clone(), curve.damage(peak)))
+        .collect())
+}
+
+#[pyfunction]
+pub fn expected_annual_damage<'py>(
+    py: Python<'py>,
+    probabilities: Vec<f64>,
+    peaks: BTreeMap<String, Vec<f64>>,
+    curves: BTreeMap<String, (Vec<f64>, Vec<f64>)>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let curves = matched_curves(peaks.keys().collect(), curves)?;
+    let (keys, peaks): (Vec<String>, Vec<Vec<f64>>) = peaks.into_iter().unzip();
+    let analysis = damage_analysis_rs(&probabilities, &peaks, &curves)?;
+    let dict = PyDict::new(py);
+    let by_reach = PyDict::new(py);
+    let ead = PyDict::new(py);
+    for ((key, damages), value) in keys
+        .iter()
+        .zip(analysis.event_damages)
+        .zip(analysis.expected_annual_damage)
+    {
+        by_reach.set_item(key, damages)?;
+        ead.set_item(key, value)?;
+    }
+    dict.set_item("event_damage", by_reach)?;
+    dict.set_item("total_event_damage", analysis.total_damages)?;
+    dict.set_item("expected_annual_damage", ead)?;
+    dict.set_item(
+        "total_expected_annual_damage",
+        analysis.total_expected_annual_damage,
+    )?;
+    Ok(dict)
+}
+
+#[pyfunction]
+pub fn integrate_damage(probabilities: Vec<f64>, damages: Vec<f64>) -> PyResult<f64> {
+    Ok(damage::expected_annual_damage(&probabilities, &damages)?)
+}
+
+#[pymodule]
+pub fn init_economics(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(event_damage, m)?)?;
+    m.add_function(wrap_pyfunction!(expected_annual_damage, m)?)?;
+    m.add_function(wrap_pyfunction!(integrate_damage, m)?)?;
+    Ok(())
+}
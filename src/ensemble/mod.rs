@@ -0,0 +1,130 @@
+//! Ensemble routing: many inflow traces through the same reach or network,
+//! run in parallel, with per-time-step summary statistics.
+
+#[cfg(feature = "python")]
+pub mod python;
+pub mod uncertainty;
+
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::network::{Network, NetworkResult};
+use crate::parallel::par_map;
+use crate::progress::{Progress, Tracker};
+use crate::reach_routing::muskingum::muskingum_cascade_rs;
+use std::collections::BTreeMap;
+
+/// Per-time-step statistics across ensemble members.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnsembleSummary {
+    pub probabilities: Vec<f64>,
+    /// `quantiles[j][t]` is the `probabilities[j]` quantile at step `t`.
+    pub quantiles: Vec<Vec<f64>>,
+    pub mean: Vec<f64>,
+}
+
+/// Linearly interpolated quantile of sorted values (Hyndman–Fan type 7, the
+/// NumPy default).
+pub fn quantile_sorted(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        1 => sorted[0],
+        n => {
+            let h = p * (n - 1) as f64;
+            let lo = h.floor() as usize;
+            let hi = (lo + 1).min(n - 1);
+            sorted[lo] + (h - lo as f64) * (sorted[hi] - sorted[lo])
+        }
+    }
+}
+
+/// Summarises equal-length member traces. NaN values are ignored.
+pub fn summarize<T: Float>(members: &[Vec<T>], probabilities: &[f64]) -> Result<EnsembleSummary> {
+    if let Some(p) = probabilities.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+        return Err(Error::InvalidParameter(format!(
+            "Quantile probability {p} is outside [0, 1]."
+        )));
+    }
+    let n_steps = members.first().map_or(0, Vec::len);
+    if members.iter().any(|m| m.len() != n_steps) {
+        return Err(Error::Data(
+            "Ensemble members must have equal lengths.".into(),
+        ));
+    }
+    let mut quantiles = vec![Vec::with_capacity(n_steps); probabilities.len()];
+    let mut mean = Vec::with_capacity(n_steps);
+    let mut column = Vec::with_capacity(members.len());
+    for t in 0..n_steps {
+        column.clear();
+        column.extend(
+            members
+                .iter()
+                .map(|m| m[t].to_f64())
+                .filter(|v| !v.is_nan()),
+        );
+        column.sort_by(f64::total_cmp);
+        for (j, &p) in probabilities.iter().enumerate() {
+            quantiles[j].push(quantile_sorted(&column, p));
+        }
+        mean.push(if column.is_empty() {
+            f64::NAN
+        } else {
+            column.iter().sum::<f64>() / column.len() as f64
+        });
+    }
+    Ok(EnsembleSummary {
+        probabilities: probabilities.to_vec(),
+        quantiles,
+        mean,
+    })
+}
+
+/// Routes every member through the same Muskingum reach in parallel.
+/// Arguments are as for [`muskingum_cascade_rs`]; with no `initial_outflow`
+/// each member starts from its own first inflow.
+pub fn muskingum_ensemble_rs<T: Float>(
+    members: &[Vec<T>],
+    dt: f64,
+    k: f64,
+    x: f64,
+    sub_reaches: usize,
+    initial_outflow: Option<f64>,
+) -> Vec<Vec<T>> {
+    par_map(members, |inflow| {
+        muskingum_cascade_rs(inflow, dt, k, x, sub_reaches, initial_outflow)
+    })
+}
+
+/// Runs the network once per member in parallel. `series` maps each series
+/// name to its member traces; a series with a single trace is shared by all
+/// members. Each completed member is reported to `progress`.
+pub fn network_ensemble_rs(
+    network: &Network,
+    series: &BTreeMap<String, Vec<Vec<f64>>>,
+    dt: f64,
+    progress: &dyn Progress,
+) -> Result<Vec<NetworkResult>> {
+    let n_members = series.values().map(Vec::len).max().unwrap_or(0);
+    if let Some((name, traces)) = series
+        .iter()
+        .find(|(_, traces)| traces.len() != n_members && traces.len() != 1)
+    {
+        return Err(Error::Data(format!(
+            "Series `{name}` has {} members; expected {n_members} or 1.",
+            traces.len()
+        )));
+    }
+    let members: Vec<usize> = (0..n_members).collect();
+    let tracker = Tracker::new(progress, n_members);
+    let results = par_map(&members, |&m| {
+        tracker.check()?;
+        let inputs: BTreeMap<String, Vec<f64>> = series
+            .iter()
+            .map(|(name, traces)| (name.clone(), traces[m.min(traces.len() - 1)].clone()))
+            .collect();
+        let result = network.clone().run(&inputs, dt);
+        tracker.advance();
+        result
+    });
+    tracker.check()?;
+    results.into_iter().collect()
+}
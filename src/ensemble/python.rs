@@ -0,0 +1,371 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict, PyList, PyTuple, PyType};
+use std::collections::BTreeMap;
+
+use super::uncertainty::{muskingum_fosm_rs, muskingum_uncertainty_rs, Distribution};
+use super::{muskingum_ensemble_rs, network_ensemble_rs, summarize, EnsembleSummary};
+use crate::arrow::{f32_rows, FloatArray, FloatArrays, FloatSeries};
+use crate::network::python::PyNetwork;
+use crate::progress::run_with_progress;
+use crate::time::{seconds_to_timedelta, timedelta_to_seconds};
+
+const DEFAULT_QUANTILES: [f64; 3] = [0.05, 0.5, 0.95];
+
+/// Member traces and their per-time-step summary.
+#[pyclass(name = "EnsembleResult", module = "rustflow.ensemble")]
+pub struct PyEnsembleResult {
+    members: FloatArrays,
+    summary: EnsembleSummary,
+    /// Parameter values of each member, for parameter-uncertainty runs.
+    parameters: BTreeMap<String, Vec<f64>>,
+}
+
+impl PyEnsembleResult {
+    fn new(members: FloatArrays, probabilities: &[f64]) -> PyResult<Self> {
+        let summary = match &members {
+            FloatArrays::F32(members) => summarize(members, probabilities)?,
+            FloatArrays::F64(members) => summarize(members, probabilities)?,
+        };
+        Ok(PyEnsembleResult {
+            members,
+            summary,
+            parameters: BTreeMap::new(),
+        })
+    }
+
+    fn len(&self) -> usize {
+        match &self.members {
+            FloatArrays::F32(members) => members.len(),
+            FloatArrays::F64(members) => members.len(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyEnsembleResult {
+    /// list[list[float]]: Routed series, one list per member. Members
+    /// routed in `float32` are a 2-D `float32` NumPy array instead, with
+    /// one row per member.
+    #[getter]
+    fn members<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        match &self.members {
+            FloatArrays::F32(members) => f32_rows(py, members),
+            FloatArrays::F64(members) => members.into_pyobject(py),
+        }
+    }
+
+    /// Literal["float32", "float64"]: Precision the members are stored in.
+    #[getter]
+    fn dtype(&self) -> &'static str {
+        match self.members {
+            FloatArrays::F32(_) => "float32",
+            FloatArrays::F64(_) => "float64",
+        }
+    }
+
+    /// list[float]: Mean across members at each time step.
+    #[getter]
+    fn mean(&self) -> Vec<f64> {
+        self.summary.mean.clone()
+    }
+
+    /// dict[float, list[float]]: Quantile series keyed by probability.
+    #[getter]
+    fn quantiles<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (p, values) in self
+            .summary
+            .probabilities
+            .iter()
+            .zip(&self.summary.quantiles)
+        {
+            dict.set_item(p, values)?;
+        }
+        Ok(dict)
+    }
+
+    /// dict[str, list[float]]: Parameter values each member was routed
+    /// with (`k` in seconds and `x` for `muskingum_uncertainty`); empty
+    /// for inflow ensembles.
+    #[getter]
+    fn parameters(&self) -> BTreeMap<String, Vec<f64>> {
+        self.parameters.clone()
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EnsembleResult(members={}, steps={})",
+            self.len(),
+            self.summary.mean.len()
+        )
+    }
+
+    /// Pickles the members, quantile probabilities, dtype and parameters;
+    /// the summary is recomputed on unpickling.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, EnsembleState)> {
+        let this = slf.borrow();
+        // `f32` values are exact as `f64`, so they survive the round trip.
+        let members = match &this.members {
+            FloatArrays::F32(members) => members
+                .iter()
+                .map(|m| m.iter().copied().map(f64::from).collect())
+                .collect(),
+            FloatArrays::F64(members) => members.clone(),
+        };
+        Ok((
+            slf.get_type().getattr("_from_state")?,
+            (
+                members,
+                this.summary.probabilities.clone(),
+                this.dtype(),
+                this.parameters.clone(),
+            ),
+        ))
+    }
+
+    #[classmethod]
+    fn _from_state(
+        _cls: &Bound<'_, PyType>,
+        members: Vec<Vec<f64>>,
+        probabilities: Vec<f64>,
+        dtype: &str,
+        parameters: BTreeMap<String, Vec<f64>>,
+    ) -> PyResult<Self> {
+        let members = match dtype {
+            "float32" => FloatArrays::F32(
+                members
+                    .iter()
+                    .map(|m| m.iter().map(|&v| v as f32).collect())
+                    .collect(),
+            ),
+            _ => FloatArrays::F64(members),
+        };
+        let mut result = PyEnsembleResult::new(members, &probabilities)?;
+        result.parameters = parameters;
+        Ok(result)
+    }
+}
+
+/// Pickled state of an `EnsembleResult`.
+type EnsembleState = (
+    Vec<Vec<f64>>,
+    Vec<f64>,
+    &'static str,
+    BTreeMap<String, Vec<f64>>,
+);
+
+/// Reads a number, or a `timedelta` as seconds.
+fn number_or_seconds(value: &Bound<'_, PyAny>) -> PyResult<f64> {
+    match value.downcast::<PyDelta>() {
+        Ok(delta) => Ok(timedelta_to_seconds(delta)),
+        Err(_) => value.extract(),
+    }
+}
+
+/// Reads a parameter given as a single value, a list of values to resample,
+/// or a `(name, ...)` distribution tuple.
+fn extract_distribution(value: &Bound<'_, PyAny>) -> PyResult<Distribution> {
+    if let Ok(list) = value.downcast::<PyList>() {
+        let values = list
+            .iter()
+            .map(|v| number_or_seconds(&v))
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(Distribution::Samples(values));
+    }
+    let Ok(tuple) = value.downcast::<PyTuple>() else {
+        return Ok(Distribution::Fixed(number_or_seconds(value)?));
+    };
+    let name: String = tuple.get_item(0)?.extract()?;
+    let args = tuple
+        .iter()
+        .skip(1)
+        .map(|v| number_or_seconds(&v))
+        .collect::<PyResult<Vec<_>>>()?;
+    match (name.as_str(), args.as_slice()) {
+        ("uniform", &[low, high]) => Ok(Distribution::Uniform { low, high }),
+        ("normal", &[mean, sd]) => Ok(Distribution::Normal { mean, sd }),
+        ("lognormal", &[median, sigma]) => Ok(Distribution::LogNormal { median, sigma }),
+        ("triangular", &[low, mode, high]) => Ok(Distribution::Triangular { low, mode, high }),
+        _ => Err(PyValueError::new_err(format!(
+            "Unknown distribution `{name}` with {} parameters; expected (\"uniform\", low, high), \
+             (\"normal\", mean, sd), (\"lognormal\", median, sigma) or (\"triangular\", low, \
+             mode, high).",
+            args.len()
+        ))),
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflows, k, x, time_step, sub_reaches=1, initial_outflow=None, quantiles=DEFAULT_QUANTILES.to_vec()))]
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_ensemble(
+    py: Python<'_>,
+    inflows: Vec<FloatArray>,
+    k: Bound<'_, PyDelta>,
+    x: f64,
+    time_step: Bound<'_, PyDelta>,
+    sub_reaches: i64,
+    initial_outflow: Option<f64>,
+    quantiles: Vec<f64>,
+) -> PyResult<PyEnsembleResult> {
+    let k_s = timedelta_to_seconds(&k);
+    let dt_s = timedelta_to_seconds(&time_step);
+    let sub_reaches = sub_reaches.max(1) as usize;
+    let outflow = py.allow_threads(|| match FloatArrays::from(inflows) {
+        FloatArrays::F32(members) => FloatArrays::F32(muskingum_ensemble_rs(
+            &members,
+            dt_s,
+            k_s,
+            x,
+            sub_reaches,
+            initial_outflow,
+        )),
+        FloatArrays::F64(members) => FloatArrays::F64(muskingum_ensemble_rs(
+            &members,
+            dt_s,
+            k_s,
+            x,
+            sub_reaches,
+            initial_outflow,
+        )),
+    });
+    PyEnsembleResult::new(outflow, &quantiles)
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, k, x, time_step, sub_reaches=1, initial_outflow=None, samples=1000, quantiles=DEFAULT_QUANTILES.to_vec(), seed=None, allow_negative_c0=false))]
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_uncertainty(
+    py: Python<'_>,
+    inflow: FloatSeries,
+    k: Bound<'_, PyAny>,
+    x: Bound<'_, PyAny>,
+    time_step: Bound<'_, PyDelta>,
+    sub_reaches: i64,
+    initial_outflow: Option<f64>,
+    samples: usize,
+    quantiles: Vec<f64>,
+    seed: Option<u64>,
+    allow_negative_c0: bool,
+) -> PyResult<PyEnsembleResult> {
+    let k = extract_distribution(&k)?;
+    let x = extract_distribution(&x)?;
+    let dt_s = timedelta_to_seconds(&time_step);
+    let sub_reaches = sub_reaches.max(1) as usize;
+    let result = py.allow_threads(|| {
+        muskingum_uncertainty_rs(
+            &inflow.0,
+            dt_s,
+            &k,
+            &x,
+            sub_reaches,
+            initial_outflow,
+            samples,
+            &quantiles,
+            seed,
+            allow_negative_c0,
+        )
+    })?;
+    Ok(PyEnsembleResult {
+        members: FloatArrays::F64(result.members),
+        summary: result.summary,
+        parameters: BTreeMap::from([("k".to_string(), result.k), ("x".to_string(), result.x)]),
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, k, x, time_step, k_sd, x_sd, correlation=0.0, sub_reaches=1, initial_outflow=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_fosm<'py>(
+    py: Python<'py>,
+    inflow: FloatSeries,
+    k: Bound<'py, PyDelta>,
+    x: f64,
+    time_step: Bound<'py, PyDelta>,
+    k_sd: Bound<'py, PyDelta>,
+    x_sd: f64,
+    correlation: f64,
+    sub_reaches: i64,
+    initial_outflow: Option<f64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    if !(-1.0..=1.0).contains(&correlation) {
+        return Err(PyValueError::new_err(format!(
+            "Correlation {correlation} is outside [-1, 1]."
+        )));
+    }
+    let k_sd = timedelta_to_seconds(&k_sd);
+    let cov = correlation * k_sd * x_sd;
+    let result = muskingum_fosm_rs(
+        &inflow.0,
+        timedelta_to_seconds(&time_step),
+        timedelta_to_seconds(&k),
+        x,
+        [[k_sd * k_sd, cov], [cov, x_sd * x_sd]],
+        sub_reaches.max(1) as usize,
+        initial_outflow,
+    )?;
+    let dict = PyDict::new(py);
+    dict.set_item("peak", result.peak)?;
+    dict.set_item("peak_variance", result.peak_variance)?;
+    dict.set_item("peak_sd", result.peak_variance.sqrt())?;
+    dict.set_item(
+        "time_to_peak",
+        seconds_to_timedelta(py, result.time_to_peak)?,
+    )?;
+    dict.set_item("time_to_peak_variance", result.time_to_peak_variance)?;
+    dict.set_item(
+        "time_to_peak_sd",
+        seconds_to_timedelta(py, result.time_to_peak_variance.sqrt())?,
+    )?;
+    dict.set_item("peak_gradient", result.peak_gradient.to_vec())?;
+    dict.set_item(
+        "time_to_peak_gradient",
+        result.time_to_peak_gradient.to_vec(),
+    )?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (network, inflows, time_step, quantiles=DEFAULT_QUANTILES.to_vec(), progress_callback=None))]
+pub fn network_ensemble(
+    py: Python<'_>,
+    network: PyRef<'_, PyNetwork>,
+    inflows: BTreeMap<String, Vec<FloatSeries>>,
+    time_step: Bound<'_, PyDelta>,
+    quantiles: Vec<f64>,
+    progress_callback: Option<Bound<'_, PyAny>>,
+) -> PyResult<BTreeMap<String, PyEnsembleResult>> {
+    let series: BTreeMap<String, Vec<Vec<f64>>> = inflows
+        .into_iter()
+        .map(|(name, traces)| (name, traces.into_iter().map(|s| s.0).collect()))
+        .collect();
+    let dt_s = timedelta_to_seconds(&time_step);
+    let network = &network.inner;
+    let results = run_with_progress(py, progress_callback.as_ref(), |progress| {
+        network_ensemble_rs(network, &series, dt_s, progress)
+    })?;
+    let mut by_node = BTreeMap::new();
+    for (i, id) in network.nodes().iter().map(|n| &n.id).enumerate() {
+        let members = results.iter().map(|r| r.outflow[i].clone()).collect();
+        by_node.insert(
+            id.clone(),
+            PyEnsembleResult::new(FloatArrays::F64(members), &quantiles)?,
+        );
+    }
+    Ok(by_node)
+}
+
+#[pymodule]
+pub fn init_ensemble(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyEnsembleResult>()?;
+    m.add_function(wrap_pyfunction!(muskingum_ensemble, m)?)?;
+    m.add_function(wrap_pyfunction!(muskingum_uncertainty, m)?)?;
+    m.add_function(wrap_pyfunction!(muskingum_fosm, m)?)?;
+    m.add_function(wrap_pyfunction!(network_ensemble, m)?)?;
+    Ok(())
+}
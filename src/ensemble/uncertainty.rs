@@ -0,0 +1,262 @@
+//! Parameter uncertainty in routing: Muskingum `K` and `x` are drawn from
+//! distributions or resampled from given values, every draw routes the
+//! same inflow, and the spread of the outflows gives prediction bands. For
+//! quick screening, the first-order second-moment (FOSM) approximation
+//! gives the variance of the routed peak from the parameter covariance
+//! instead.
+
+use super::{summarize, EnsembleSummary};
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+use crate::random::{self, Rng};
+use crate::reach_routing::muskingum::{check_muskingum, muskingum_cascade_rs};
+
+/// Largest number of draws per accepted sample before giving up on a
+/// distribution that rarely yields valid parameters.
+const MAX_DRAWS_PER_SAMPLE: usize = 100;
+
+/// Distribution of an uncertain parameter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Distribution {
+    Fixed(f64),
+    Uniform {
+        low: f64,
+        high: f64,
+    },
+    Normal {
+        mean: f64,
+        sd: f64,
+    },
+    /// Log-normal with the given `median` and standard deviation `sigma` of
+    /// the logarithm.
+    LogNormal {
+        median: f64,
+        sigma: f64,
+    },
+    Triangular {
+        low: f64,
+        mode: f64,
+        high: f64,
+    },
+    /// Values drawn with replacement, such as calibrated or regionalised
+    /// estimates.
+    Samples(Vec<f64>),
+}
+
+impl Distribution {
+    /// Checks that the distribution can be sampled.
+    pub fn validate(&self, name: &str) -> Result<()> {
+        let valid = match self {
+            Distribution::Fixed(v) => v.is_finite(),
+            Distribution::Uniform { low, high } => {
+                low.is_finite() && high.is_finite() && low <= high
+            }
+            Distribution::Normal { mean, sd } => mean.is_finite() && sd.is_finite() && *sd >= 0.0,
+            Distribution::LogNormal { median, sigma } => {
+                median.is_finite() && *median > 0.0 && sigma.is_finite() && *sigma >= 0.0
+            }
+            Distribution::Triangular { low, mode, high } => {
+                low.is_finite() && high.is_finite() && low <= mode && mode <= high
+            }
+            Distribution::Samples(values) => {
+                !values.is_empty() && values.iter().all(|v| v.is_finite())
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidParameter(format!(
+                "Invalid distribution for `{name}`: {self:?}."
+            )))
+        }
+    }
+
+    pub fn sample(&self, rng: &mut Rng) -> f64 {
+        match self {
+            Distribution::Fixed(v) => *v,
+            Distribution::Uniform { low, high } => low + rng.uniform() * (high - low),
+            Distribution::Normal { mean, sd } => mean + sd * rng.normal(),
+            Distribution::LogNormal { median, sigma } => median * random::exp(sigma * rng.normal()),
+            Distribution::Triangular { low, mode, high } => {
+                let u = rng.uniform();
+                let width = high - low;
+                if width <= 0.0 {
+                    return *low;
+                }
+                let split = (mode - low) / width;
+                if u < split {
+                    low + (u * width * (mode - low)).sqrt()
+                } else {
+                    high - ((1.0 - u) * width * (high - mode)).sqrt()
+                }
+            }
+            Distribution::Samples(values) => values[rng.below(values.len())],
+        }
+    }
+}
+
+/// Outflow bands of a Muskingum reach with uncertain parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MuskingumUncertainty {
+    /// Sampled storage constants (s) and weighting factors.
+    pub k: Vec<f64>,
+    pub x: Vec<f64>,
+    /// Outflow routed with each parameter pair.
+    pub members: Vec<Vec<f64>>,
+    pub summary: EnsembleSummary,
+}
+
+/// Routes `inflow` with `samples` draws of `k` (s) and `x`, in parallel,
+/// and summarises the outflows at `probabilities`. Draws outside the valid
+/// Muskingum range (negative `k`, `x` outside [0, 0.5], or a negative
+/// inflow coefficient unless `allow_negative_c0`) are rejected and redrawn,
+/// truncating the distributions. Other arguments are as for
+/// [`muskingum_cascade_rs`].
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_uncertainty_rs(
+    inflow: &[f64],
+    dt: f64,
+    k: &Distribution,
+    x: &Distribution,
+    sub_reaches: usize,
+    initial_outflow: Option<f64>,
+    samples: usize,
+    probabilities: &[f64],
+    seed: Option<u64>,
+    allow_negative_c0: bool,
+) -> Result<MuskingumUncertainty> {
+    k.validate("k")?;
+    x.validate("x")?;
+    if samples == 0 {
+        return Err(Error::InvalidParameter(
+            "At least one sample is needed.".into(),
+        ));
+    }
+    check_muskingum(inflow, dt, 0.0, 0.0, sub_reaches as i64, true)?;
+    let mut rng = Rng::from_seed(seed);
+    let mut parameters = Vec::with_capacity(samples);
+    let mut draws = 0;
+    while parameters.len() < samples {
+        if draws == samples * MAX_DRAWS_PER_SAMPLE {
+            return Err(Error::InvalidParameter(
+                "Too few draws of `k` and `x` are valid Muskingum parameters; check the \
+                 distributions."
+                    .into(),
+            ));
+        }
+        draws += 1;
+        let (k, x) = (k.sample(&mut rng), x.sample(&mut rng));
+        let valid = (0.0..=0.5).contains(&x)
+            && check_muskingum(inflow, dt, k, x, sub_reaches as i64, allow_negative_c0).is_ok();
+        if valid {
+            parameters.push((k, x));
+        }
+    }
+    let members = par_map(&parameters, |&(k, x)| {
+        muskingum_cascade_rs(inflow, dt, k, x, sub_reaches, initial_outflow)
+    });
+    let summary = summarize(&members, probabilities)?;
+    Ok(MuskingumUncertainty {
+        k: parameters.iter().map(|p| p.0).collect(),
+        x: parameters.iter().map(|p| p.1).collect(),
+        members,
+        summary,
+    })
+}
+
+/// First-order second-moment estimate of the routed peak.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeakFosm {
+    /// Peak outflow and its time (s from the first value) at the mean
+    /// parameters.
+    pub peak: f64,
+    pub time_to_peak: f64,
+    pub peak_variance: f64,
+    /// Variance (s²) of the time to peak.
+    pub time_to_peak_variance: f64,
+    /// Derivatives of the peak with respect to `k` (per s) and `x`.
+    pub peak_gradient: [f64; 2],
+    /// Derivatives of the time to peak with respect to `k` and `x` (s).
+    pub time_to_peak_gradient: [f64; 2],
+}
+
+/// Peak of `outflow` and its time (s), refined by a parabola through the
+/// highest value and its neighbours so that both vary smoothly with the
+/// parameters.
+fn refined_peak(outflow: &[f64], dt: f64) -> (f64, f64) {
+    let i = outflow
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map_or(0, |(i, _)| i);
+    if i == 0 || i + 1 == outflow.len() {
+        return (outflow[i], i as f64 * dt);
+    }
+    let (a, b, c) = (outflow[i - 1], outflow[i], outflow[i + 1]);
+    let curvature = a - 2.0 * b + c;
+    if curvature >= 0.0 {
+        return (b, i as f64 * dt);
+    }
+    let offset = 0.5 * (a - c) / curvature;
+    (b - 0.25 * (a - c) * offset, (i as f64 + offset) * dt)
+}
+
+/// FOSM variance of the peak outflow and time to peak of `inflow` routed
+/// with mean parameters `k` (s) and `x`, given their `covariance`
+/// (`[[var k, cov], [cov, var x]]`, with `k` in s). The peak is linearised
+/// about the means by central differences. Other arguments are as for
+/// [`muskingum_cascade_rs`].
+pub fn muskingum_fosm_rs(
+    inflow: &[f64],
+    dt: f64,
+    k: f64,
+    x: f64,
+    covariance: [[f64; 2]; 2],
+    sub_reaches: usize,
+    initial_outflow: Option<f64>,
+) -> Result<PeakFosm> {
+    check_muskingum(inflow, dt, k, x, sub_reaches as i64, true)?;
+    let valid = covariance[0][0] >= 0.0
+        && covariance[1][1] >= 0.0
+        && covariance[0][1] == covariance[1][0]
+        && covariance[0][1].powi(2) <= covariance[0][0] * covariance[1][1];
+    if !valid {
+        return Err(Error::InvalidParameter(
+            "The parameter covariance must be symmetric and positive semi-definite.".into(),
+        ));
+    }
+    let peak_at = |k: f64, x: f64| {
+        let outflow = muskingum_cascade_rs(inflow, dt, k, x, sub_reaches, initial_outflow);
+        refined_peak(&outflow, dt)
+    };
+    let (peak, time_to_peak) = peak_at(k, x);
+    let steps = [1e-3 * k.max(dt), 1e-3];
+    let mut peak_gradient = [0.0; 2];
+    let mut time_to_peak_gradient = [0.0; 2];
+    for (j, step) in steps.into_iter().enumerate() {
+        let shift = |sign: f64| {
+            let (dk, dx) = if j == 0 {
+                (sign * step, 0.0)
+            } else {
+                (0.0, sign * step)
+            };
+            peak_at(k + dk, x + dx)
+        };
+        let (up, down) = (shift(1.0), shift(-1.0));
+        peak_gradient[j] = (up.0 - down.0) / (2.0 * step);
+        time_to_peak_gradient[j] = (up.1 - down.1) / (2.0 * step);
+    }
+    let variance = |g: [f64; 2]| {
+        g[0] * g[0] * covariance[0][0]
+            + 2.0 * g[0] * g[1] * covariance[0][1]
+            + g[1] * g[1] * covariance[1][1]
+    };
+    Ok(PeakFosm {
+        peak,
+        time_to_peak,
+        peak_variance: variance(peak_gradient),
+        time_to_peak_variance: variance(time_to_peak_gradient),
+        peak_gradient,
+        time_to_peak_gradient,
+    })
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+/// Errors raised by the pure-Rust API.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// A parameter is outside its physically meaningful range.
+    InvalidParameter(String),
+    /// Input data is empty, malformed or inconsistent.
+    Data(String),
+    /// An iterative solution did not converge.
+    Convergence(String),
+    /// Water entering and leaving a computation does not balance.
+    MassBalance(String),
+    /// A long run was cancelled through its progress callback.
+    Cancelled(String),
+    /// Reading or writing a file failed.
+    Io(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidParameter(msg)
+            | Error::Data(msg)
+            | Error::Convergence(msg)
+            | Error::MassBalance(msg)
+            | Error::Cancelled(msg)
+            | Error::Io(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// Python exception hierarchy. `RustflowError` derives from `ValueError`, so
+// handlers written before the hierarchy existed keep catching every rustflow
+// failure.
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    rustflow,
+    RustflowError,
+    pyo3::exceptions::PyValueError,
+    "Base class of the errors raised by rustflow."
+);
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    rustflow,
+    ParameterError,
+    RustflowError,
+    "A parameter is outside its physically meaningful range."
+);
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    rustflow,
+    DataError,
+    RustflowError,
+    "Input data is empty, malformed or inconsistent."
+);
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    rustflow,
+    ConvergenceError,
+    RustflowError,
+    "An iterative solution did not converge."
+);
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    rustflow,
+    MassBalanceError,
+    RustflowError,
+    "Water entering and leaving a computation does not balance."
+);
+
+#[cfg(feature = "python")]
+impl From<Error> for pyo3::PyErr {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::InvalidParameter(msg) => ParameterError::new_err(msg),
+            Error::Data(msg) => DataError::new_err(msg),
+            Error::Convergence(msg) => ConvergenceError::new_err(msg),
+            Error::MassBalance(msg) => MassBalanceError::new_err(msg),
+            Error::Cancelled(msg) => pyo3::exceptions::PyKeyboardInterrupt::new_err(msg),
+            Error::Io(msg) => pyo3::exceptions::PyIOError::new_err(msg),
+        }
+    }
+}
+
+/// Adds the exception classes to the top-level Python module, with
+/// `InvalidParameterError` kept as an alias of `ParameterError`.
+#[cfg(feature = "python")]
+pub fn add_exceptions(m: &pyo3::Bound<'_, pyo3::types::PyModule>) -> pyo3::PyResult<()> {
+    use pyo3::types::PyModuleMethods;
+
+    let py = m.py();
+    m.add("RustflowError", py.get_type::<RustflowError>())?;
+    m.add("ParameterError", py.get_type::<ParameterError>())?;
+    m.add("InvalidParameterError", py.get_type::<ParameterError>())?;
+    m.add("DataError", py.get_type::<DataError>())?;
+    m.add("ConvergenceError", py.get_type::<ConvergenceError>())?;
+    m.add("MassBalanceError", py.get_type::<MassBalanceError>())?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! Storage precision of routed series.
+//!
+//! Batch and ensemble routines are generic over [`Float`] so that large
+//! ensembles can be held as `f32`, halving their memory. Each step is
+//! computed in `f64` and only the stored values are rounded.
+
+/// Floating-point type in which a series is stored.
+pub trait Float: Copy + Send + Sync + 'static {
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Float for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl Float for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
@@ -0,0 +1,46 @@
+//! Autoregressive output error correction.
+
+use crate::error::{Error, Result};
+
+/// Lag-1 autocorrelation of the simulation errors `observed - simulated`,
+/// from consecutive steps where both errors are available.
+pub fn fit_ar1(simulated: &[f64], observed: &[f64]) -> Result<f64> {
+    let errors: Vec<f64> = simulated.iter().zip(observed).map(|(s, o)| o - s).collect();
+    let (mut lagged, mut total, mut n) = (0.0, 0.0, 0usize);
+    for pair in errors.windows(2) {
+        if pair[0].is_finite() && pair[1].is_finite() {
+            lagged += pair[0] * pair[1];
+            total += pair[0] * pair[0];
+            n += 1;
+        }
+    }
+    if n == 0 || total == 0.0 {
+        return Err(Error::Data(
+            "Not enough consecutive observations to fit the AR(1) error model.".into(),
+        ));
+    }
+    Ok((lagged / total).clamp(-1.0, 1.0))
+}
+
+/// Corrects `simulated` with the most recent error seen before each step,
+/// decayed by `phi` per step: `sim[t] + phi^h e[t - h]`. Each value only
+/// uses observations from earlier steps, so the output is what a forecaster
+/// would have issued in real time; after the last observation the
+/// correction decays towards the raw simulation.
+pub fn ar1_error_correction(simulated: &[f64], observed: &[f64], phi: f64) -> Result<Vec<f64>> {
+    if simulated.len() != observed.len() {
+        return Err(Error::Data(
+            "Simulated and observed series must have equal lengths.".into(),
+        ));
+    }
+    let mut last_error: Option<f64> = None;
+    let mut corrected = Vec::with_capacity(simulated.len());
+    for (s, o) in simulated.iter().zip(observed) {
+        last_error = last_error.map(|e| e * phi);
+        corrected.push(s + last_error.unwrap_or(0.0));
+        if o.is_finite() && s.is_finite() {
+            last_error = Some(o - s);
+        }
+    }
+    Ok(corrected)
+}
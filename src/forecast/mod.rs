@@ -0,0 +1,10 @@
+//! Real-time updating of routed flows with observed downstream flows.
+//!
+//! Observations are given as a series aligned with the simulation; NaN
+//! marks steps without an observation, so a forecast period is simply a
+//! trailing run of NaN.
+
+pub mod enkf;
+pub mod error_correction;
+#[cfg(feature = "python")]
+pub mod python;
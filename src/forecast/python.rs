@@ -0,0 +1,62 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+
+use super::enkf::{enkf_muskingum as enkf_muskingum_rs, EnkfOptions};
+use super::error_correction::{ar1_error_correction as ar1_rs, fit_ar1};
+use crate::arrow::FloatSeries;
+use crate::reach_routing::muskingum::MuskingumReach;
+use crate::time::timedelta_to_seconds;
+
+#[pyfunction]
+#[pyo3(signature = (simulated, observed, phi=None))]
+pub fn ar1_error_correction(
+    simulated: FloatSeries,
+    observed: FloatSeries,
+    phi: Option<f64>,
+) -> PyResult<(Vec<f64>, f64)> {
+    let phi = match phi {
+        Some(phi) => phi,
+        None => fit_ar1(&simulated.0, &observed.0)?,
+    };
+    Ok((ar1_rs(&simulated.0, &observed.0, phi)?, phi))
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, observed, k, x, time_step, sub_reaches=1, members=100, inflow_error=0.2, observation_error=0.05, seed=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn enkf_muskingum<'py>(
+    py: Python<'py>,
+    inflow: FloatSeries,
+    observed: FloatSeries,
+    k: Bound<'py, PyDelta>,
+    x: f64,
+    time_step: Bound<'py, PyDelta>,
+    sub_reaches: usize,
+    members: usize,
+    inflow_error: f64,
+    observation_error: f64,
+    seed: Option<u64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let reach = MuskingumReach::new(timedelta_to_seconds(&k), x, sub_reaches, None);
+    let options = EnkfOptions {
+        members,
+        inflow_error,
+        observation_error,
+        seed,
+    };
+    let dt = timedelta_to_seconds(&time_step);
+    let result =
+        py.allow_threads(|| enkf_muskingum_rs(&reach, &inflow.0, &observed.0, dt, &options))?;
+    let dict = PyDict::new(py);
+    dict.set_item("mean", result.mean)?;
+    dict.set_item("lower", result.lower)?;
+    dict.set_item("upper", result.upper)?;
+    Ok(dict)
+}
+
+#[pymodule]
+pub fn init_forecast(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(ar1_error_correction, m)?)?;
+    m.add_function(wrap_pyfunction!(enkf_muskingum, m)?)?;
+    Ok(())
+}
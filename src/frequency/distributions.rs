@@ -0,0 +1,268 @@
+//! Special functions and the normal and Student's t distributions used by
+//! the frequency analyses.
+
+use std::f64::consts::PI;
+
+/// Natural logarithm of the gamma function for `x > 0` (Lanczos
+/// approximation, g = 7).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula.
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regularized lower incomplete gamma function `P(a, x)`.
+pub fn regularized_gamma(a: f64, x: f64) -> f64 {
+    incomplete_gamma(a, x).0
+}
+
+/// `(P(a, x), Q(a, x))`, by the series of `P` below `x = a + 1` and the
+/// continued fraction of `Q` above.
+fn incomplete_gamma(a: f64, x: f64) -> (f64, f64) {
+    if x <= 0.0 {
+        return (0.0, 1.0);
+    }
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..500 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-16 {
+                break;
+            }
+        }
+        let p = (sum * prefactor).min(1.0);
+        (p, 1.0 - p)
+    } else {
+        let q = prefactor * continued_fraction(|i| -i * (i - a), |i| x + 2.0 * i + 1.0 - a);
+        (1.0 - q, q)
+    }
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+pub fn regularized_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let prefactor =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges fastest below the mean.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        prefactor * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - prefactor * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction of the incomplete beta function, `a I_x(a, b)`
+/// divided by the prefactor `x^a (1 - x)^b / B(a, b)`.
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let numerator = |i: f64| {
+        let m = (i / 2.0).floor();
+        if i % 2.0 == 0.0 {
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m))
+        } else {
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0))
+        }
+    };
+    continued_fraction(numerator, |_| 1.0)
+}
+
+/// Evaluates `1 / (d(0) + n(1) / (d(1) + n(2) / (d(2) + ...)))` by the
+/// modified Lentz method.
+fn continued_fraction(n: impl Fn(f64) -> f64, d: impl Fn(f64) -> f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let guard = |v: f64| if v.abs() < TINY { TINY } else { v };
+    let mut c = guard(d(0.0));
+    let mut f = c;
+    let mut denominator = 0.0;
+    for i in 1..1000 {
+        let i = i as f64;
+        denominator = guard(d(i) + n(i) * denominator);
+        c = guard(d(i) + n(i) / c);
+        denominator = 1.0 / denominator;
+        let delta = c * denominator;
+        f *= delta;
+        if (delta - 1.0).abs() < 1e-16 {
+            break;
+        }
+    }
+    1.0 / f
+}
+
+/// Standard normal cumulative distribution function.
+pub fn normal_cdf(z: f64) -> f64 {
+    // Phi(z) = 1/2 + P(1/2, z²/2) / 2 for z >= 0; the lower tail uses Q
+    // directly to keep its precision.
+    let (p, q) = incomplete_gamma(0.5, 0.5 * z * z);
+    if z >= 0.0 {
+        0.5 + 0.5 * p
+    } else {
+        0.5 * q
+    }
+}
+
+/// Standard normal quantile: Acklam's rational approximation refined by
+/// one Halley step.
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let polynomial = |coefficients: &[f64], x: f64| coefficients.iter().fold(0.0, |s, c| s * x + c);
+    let tail = |q: f64| {
+        let r = (-2.0 * q.ln()).sqrt();
+        polynomial(&C, r) / (polynomial(&D, r) * r + 1.0)
+    };
+    let z = if p < 0.024_25 {
+        tail(p)
+    } else if p > 1.0 - 0.024_25 {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        polynomial(&A, r) * q / (polynomial(&B, r) * r + 1.0)
+    };
+    let error = normal_cdf(z) - p;
+    let u = error * (2.0 * PI).sqrt() * (0.5 * z * z).exp();
+    z - u / (1.0 + 0.5 * z * u)
+}
+
+/// Student's t cumulative distribution function with `df` degrees of
+/// freedom.
+pub fn student_t_cdf(t: f64, df: f64) -> f64 {
+    let tail = 0.5 * regularized_beta(0.5 * df, 0.5, df / (df + t * t));
+    if t >= 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Student's t quantile with `df` degrees of freedom, by bisection of the
+/// distribution function.
+pub fn student_t_quantile(p: f64, df: f64) -> f64 {
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let mut high = normal_quantile(p).abs().max(1.0);
+    while student_t_cdf(high, df) < p.max(1.0 - p) {
+        high *= 2.0;
+    }
+    let mut low = -high;
+    for _ in 0..100 {
+        let mid = 0.5 * (low + high);
+        if student_t_cdf(mid, df) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// Quantile of the standard gamma distribution with `shape`, by bisection
+/// of [`regularized_gamma`] from the Wilson–Hilferty approximation.
+pub fn gamma_quantile(p: f64, shape: f64) -> f64 {
+    if p <= 0.0 {
+        return 0.0;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let z = normal_quantile(p);
+    let c = 1.0 / (9.0 * shape);
+    let guess = shape * (1.0 - c + z * c.sqrt()).max(0.01).powi(3);
+    let mut high = guess.max(1e-3);
+    while regularized_gamma(shape, high) < p {
+        high *= 2.0;
+    }
+    let mut low = 0.0;
+    for _ in 0..200 {
+        let mid = 0.5 * (low + high);
+        if regularized_gamma(shape, mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low <= 1e-14 * high {
+            break;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// Frequency factor `K` of the Pearson type III distribution with
+/// coefficient of skewness `skew` at non-exceedance probability `p`: the
+/// quantile is `mean + K · standard deviation`.
+pub fn pearson3_frequency_factor(p: f64, skew: f64) -> f64 {
+    if skew.abs() < 1e-6 {
+        return normal_quantile(p);
+    }
+    let shape = 4.0 / (skew * skew);
+    if skew > 0.0 {
+        (gamma_quantile(p, shape) - shape) / shape.sqrt()
+    } else {
+        (shape - gamma_quantile(1.0 - p, shape)) / shape.sqrt()
+    }
+}
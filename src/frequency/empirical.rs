@@ -0,0 +1,116 @@
+//! Plotting positions and empirical frequency curves of annual series.
+//!
+//! The exceedance probability of the value of rank `i` (1 for the largest)
+//! in a sample of `n` is `(i - a) / (n + 1 - 2a)`, with `a = 0` for Weibull,
+//! 0.44 for Gringorten, 0.4 for Cunnane and 0.5 for Hazen.
+
+use crate::error::{Error, Result};
+use crate::interp::interp;
+
+/// Plotting-position formula.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlottingPosition {
+    Weibull,
+    Gringorten,
+    Cunnane,
+    Hazen,
+}
+
+impl PlottingPosition {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "weibull" => Ok(PlottingPosition::Weibull),
+            "gringorten" => Ok(PlottingPosition::Gringorten),
+            "cunnane" => Ok(PlottingPosition::Cunnane),
+            "hazen" => Ok(PlottingPosition::Hazen),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown plotting position `{name}`; expected `weibull`, `gringorten`, \
+                 `cunnane` or `hazen`."
+            ))),
+        }
+    }
+
+    fn alpha(self) -> f64 {
+        match self {
+            PlottingPosition::Weibull => 0.0,
+            PlottingPosition::Gringorten => 0.44,
+            PlottingPosition::Cunnane => 0.4,
+            PlottingPosition::Hazen => 0.5,
+        }
+    }
+
+    /// Exceedance probability of the value of `rank` (1 for the largest)
+    /// in a sample of `n`.
+    pub fn exceedance(self, rank: usize, n: usize) -> f64 {
+        let a = self.alpha();
+        (rank as f64 - a) / (n as f64 + 1.0 - 2.0 * a)
+    }
+}
+
+/// Empirical frequency curve of a sample, e.g. annual maximum flows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmpiricalFrequency {
+    /// Sample values in descending order, NaNs removed.
+    pub values: Vec<f64>,
+    /// Exceedance probability of each value.
+    pub exceedance: Vec<f64>,
+}
+
+impl EmpiricalFrequency {
+    pub fn new(sample: &[f64], method: PlottingPosition) -> Result<Self> {
+        let mut values: Vec<f64> = sample.iter().copied().filter(|v| !v.is_nan()).collect();
+        if values.is_empty() {
+            return Err(Error::Data("The sample has no values.".into()));
+        }
+        values.sort_by(|a, b| b.total_cmp(a));
+        let n = values.len();
+        let exceedance = (1..=n).map(|rank| method.exceedance(rank, n)).collect();
+        Ok(EmpiricalFrequency { values, exceedance })
+    }
+
+    /// Return period (in sampling intervals, e.g. years) of each value.
+    pub fn return_periods(&self) -> Vec<f64> {
+        self.exceedance.iter().map(|p| 1.0 / p).collect()
+    }
+
+    /// Value with return period `return_period` (> 1), interpolated
+    /// linearly in the logarithm of the return period and extrapolated from
+    /// the end segments beyond the plotted range.
+    pub fn value_at(&self, return_period: f64) -> Result<f64> {
+        if return_period <= 1.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Return period {return_period} must be greater than 1."
+            )));
+        }
+        let (log_periods, values) = self.ascending();
+        Ok(interp(return_period.ln(), &log_periods, &values))
+    }
+
+    /// Return period of `value`, interpolated as in [`Self::value_at`].
+    pub fn return_period_of(&self, value: f64) -> f64 {
+        let (log_periods, values) = self.ascending();
+        interp(value, &values, &log_periods).exp()
+    }
+
+    /// Logarithms of the return periods and the values, smallest first.
+    fn ascending(&self) -> (Vec<f64>, Vec<f64>) {
+        self.exceedance
+            .iter()
+            .zip(&self.values)
+            .rev()
+            .map(|(p, v)| (-p.ln(), *v))
+            .unzip()
+    }
+}
+
+/// Empirical cumulative distribution function of `sample` at each of
+/// `points`: the fraction of non-NaN values less than or equal to it.
+pub fn empirical_cdf_rs(sample: &[f64], points: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<f64> = sample.iter().copied().filter(|v| !v.is_nan()).collect();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+    points
+        .iter()
+        .map(|&x| sorted.partition_point(|&v| v <= x) as f64 / n)
+        .collect()
+}
@@ -0,0 +1,465 @@
+//! Intensity–duration–frequency (IDF) relationships from annual maximum
+//! rainfall depths at several durations.
+//!
+//! A GEV or Gumbel distribution is fitted by L-moments (Hosking, 1990) to
+//! the annual maximum intensities of each duration. The curve is either
+//! kept per duration, with quantiles interpolated linearly in log
+//! intensity and log duration, or summarised by the generalized Sherman
+//! formula `i = k T^m / (d + b)^n` fitted by least squares in log space to
+//! the per-duration quantiles. Durations are in hours and intensities in
+//! depth units per hour.
+
+use super::distributions::ln_gamma;
+use crate::error::{Error, Result};
+use crate::interp::interp;
+
+/// Return periods (years) at which the Sherman formula is fitted.
+const SHERMAN_RETURN_PERIODS: [f64; 7] = [2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 200.0];
+
+/// Distribution fitted to the annual maxima of each duration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdfDistribution {
+    Gev,
+    Gumbel,
+}
+
+impl IdfDistribution {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "gev" => Ok(IdfDistribution::Gev),
+            "gumbel" | "ev1" => Ok(IdfDistribution::Gumbel),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown IDF distribution `{name}`; expected `gev` or `gumbel`."
+            ))),
+        }
+    }
+}
+
+/// Form of the fitted IDF relationship.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdfMethod {
+    /// Per-duration distributions interpolated between durations.
+    PerDuration,
+    /// Generalized Sherman formula.
+    Sherman,
+}
+
+impl IdfMethod {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "gev" | "per_duration" => Ok(IdfMethod::PerDuration),
+            "sherman" => Ok(IdfMethod::Sherman),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown IDF method `{name}`; expected `gev` or `sherman`."
+            ))),
+        }
+    }
+}
+
+/// Generalized extreme value distribution in Hosking's parameterisation,
+/// `x(F) = location + scale (1 - (-ln F)^shape) / shape`; `shape = 0` is
+/// the Gumbel distribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gev {
+    pub location: f64,
+    pub scale: f64,
+    pub shape: f64,
+}
+
+impl Gev {
+    /// Fits by L-moments to a sample of at least three values.
+    pub fn fit(sample: &[f64], distribution: IdfDistribution) -> Result<Self> {
+        let mut x: Vec<f64> = sample.iter().copied().filter(|v| !v.is_nan()).collect();
+        if x.len() < 3 {
+            return Err(Error::Data(
+                "At least three annual maxima are required per duration.".into(),
+            ));
+        }
+        x.sort_by(f64::total_cmp);
+        let n = x.len() as f64;
+        let (mut b0, mut b1, mut b2) = (0.0, 0.0, 0.0);
+        for (j, &v) in x.iter().enumerate() {
+            let j = j as f64;
+            b0 += v;
+            b1 += v * j / (n - 1.0);
+            b2 += v * j * (j - 1.0) / ((n - 1.0) * (n - 2.0));
+        }
+        let (b0, b1, b2) = (b0 / n, b1 / n, b2 / n);
+        let l1 = b0;
+        let l2 = 2.0 * b1 - b0;
+        let l3 = 6.0 * b2 - 6.0 * b1 + b0;
+        if l2 <= 0.0 {
+            return Err(Error::Data(
+                "Annual maxima have no spread; a distribution cannot be fitted.".into(),
+            ));
+        }
+        let shape = match distribution {
+            IdfDistribution::Gumbel => 0.0,
+            IdfDistribution::Gev => {
+                // Hosking's approximation of the shape from the L-skewness.
+                let c = 2.0 / (3.0 + l3 / l2) - 2f64.ln() / 3f64.ln();
+                7.8590 * c + 2.9554 * c * c
+            }
+        };
+        if shape.abs() < 1e-9 {
+            let scale = l2 / 2f64.ln();
+            return Ok(Gev {
+                location: l1 - 0.577_215_664_901_532_9 * scale,
+                scale,
+                shape: 0.0,
+            });
+        }
+        let gamma = ln_gamma(1.0 + shape).exp();
+        let scale = l2 * shape / ((1.0 - 2f64.powf(-shape)) * gamma);
+        Ok(Gev {
+            location: l1 - scale * (1.0 - gamma) / shape,
+            scale,
+            shape,
+        })
+    }
+
+    /// Value with return period `return_period` (years).
+    pub fn quantile(&self, return_period: f64) -> f64 {
+        let y = -(1.0 - 1.0 / return_period).ln();
+        if self.shape == 0.0 {
+            self.location - self.scale * y.ln()
+        } else {
+            self.location + self.scale * (1.0 - y.powf(self.shape)) / self.shape
+        }
+    }
+}
+
+/// A fitted IDF relationship.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdfCurve {
+    /// Distribution of annual maximum intensity for each duration (hours,
+    /// ascending).
+    PerDuration { durations: Vec<f64>, fits: Vec<Gev> },
+    /// `i = k T^m / (d + b)^n` with `d` and `b` in hours.
+    Sherman { k: f64, m: f64, b: f64, n: f64 },
+}
+
+impl IdfCurve {
+    /// Rainfall intensity for `duration` (hours) and `return_period`
+    /// (years, > 1).
+    pub fn intensity(&self, duration: f64, return_period: f64) -> Result<f64> {
+        if !(duration > 0.0 && return_period > 1.0) {
+            return Err(Error::InvalidParameter(format!(
+                "IDF curves need a positive duration and a return period above 1 \
+                 (got {duration} h and {return_period} years)."
+            )));
+        }
+        Ok(match self {
+            IdfCurve::PerDuration { durations, fits } => {
+                let log_durations: Vec<f64> = durations.iter().map(|d| d.ln()).collect();
+                let log_intensities: Vec<f64> = fits
+                    .iter()
+                    .map(|fit| fit.quantile(return_period).max(f64::MIN_POSITIVE).ln())
+                    .collect();
+                interp(duration.ln(), &log_durations, &log_intensities).exp()
+            }
+            IdfCurve::Sherman { k, m, b, n } => {
+                k * return_period.powf(*m) / (duration + b).powf(*n)
+            }
+        })
+    }
+
+    /// Rainfall depth for `duration` (hours) and `return_period` (years).
+    pub fn depth(&self, duration: f64, return_period: f64) -> Result<f64> {
+        Ok(self.intensity(duration, return_period)? * duration)
+    }
+}
+
+/// Fits an IDF relationship to annual maximum `depths[i]` accumulated over
+/// `durations[i]` (hours, distinct).
+pub fn idf_fit_rs(
+    durations: &[f64],
+    depths: &[Vec<f64>],
+    method: IdfMethod,
+    distribution: IdfDistribution,
+) -> Result<IdfCurve> {
+    if durations.is_empty() || durations.len() != depths.len() {
+        return Err(Error::InvalidParameter(format!(
+            "Got {} durations for {} annual maximum series.",
+            durations.len(),
+            depths.len()
+        )));
+    }
+    if let Some(d) = durations.iter().find(|&&d| d.is_nan() || d <= 0.0) {
+        return Err(Error::InvalidParameter(format!(
+            "Duration {d} h must be positive."
+        )));
+    }
+    let mut order: Vec<usize> = (0..durations.len()).collect();
+    order.sort_by(|&a, &b| durations[a].total_cmp(&durations[b]));
+    if order.windows(2).any(|w| durations[w[0]] == durations[w[1]]) {
+        return Err(Error::InvalidParameter(
+            "Durations must be distinct.".into(),
+        ));
+    }
+    let sorted: Vec<f64> = order.iter().map(|&i| durations[i]).collect();
+    let fits = order
+        .iter()
+        .map(|&i| {
+            let intensities: Vec<f64> = depths[i].iter().map(|p| p / durations[i]).collect();
+            Gev::fit(&intensities, distribution)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    match method {
+        IdfMethod::PerDuration => Ok(IdfCurve::PerDuration {
+            durations: sorted,
+            fits,
+        }),
+        IdfMethod::Sherman => fit_sherman(&sorted, &fits),
+    }
+}
+
+/// Least-squares fit of `ln i = ln k + m ln T - n ln(d + b)` to the
+/// per-duration quantiles, searching `b` and solving the rest linearly.
+fn fit_sherman(durations: &[f64], fits: &[Gev]) -> Result<IdfCurve> {
+    if durations.len() < 2 {
+        return Err(Error::Data(
+            "The Sherman formula needs annual maxima at two or more durations.".into(),
+        ));
+    }
+    let mut points = Vec::new();
+    for (&d, fit) in durations.iter().zip(fits) {
+        for &t in &SHERMAN_RETURN_PERIODS {
+            let i = fit.quantile(t);
+            if i > 0.0 {
+                points.push((d, t.ln(), i.ln()));
+            }
+        }
+    }
+    // Returns (sum of squared residuals, [ln k, m, -n]) for a given `b`.
+    let solve = |b: f64| {
+        let mut ata = [[0.0; 3]; 3];
+        let mut aty = [0.0; 3];
+        for &(d, log_t, log_i) in &points {
+            let row = [1.0, log_t, (d + b).ln()];
+            for r in 0..3 {
+                for c in 0..3 {
+                    ata[r][c] += row[r] * row[c];
+                }
+                aty[r] += row[r] * log_i;
+            }
+        }
+        let x = solve3(ata, aty)?;
+        let sse = points
+            .iter()
+            .map(|&(d, log_t, log_i)| (x[0] + x[1] * log_t + x[2] * (d + b).ln() - log_i).powi(2))
+            .sum::<f64>();
+        Some((sse, x))
+    };
+    // Coarse logarithmic scan of `b`, refined by golden-section search.
+    let top = durations[durations.len() - 1];
+    let candidates: Vec<f64> = std::iter::once(0.0)
+        .chain((0..=200).map(|j| top * 10f64.powf(-4.0 + 4.0 * j as f64 / 200.0)))
+        .collect();
+    let sse = |b: f64| solve(b).map_or(f64::INFINITY, |(s, _)| s);
+    let best = (0..candidates.len())
+        .min_by(|&a, &b| sse(candidates[a]).total_cmp(&sse(candidates[b])))
+        .unwrap_or(0);
+    let (mut low, mut high) = (
+        candidates[best.saturating_sub(1)],
+        candidates[(best + 1).min(candidates.len() - 1)],
+    );
+    let ratio = 0.5 * (5f64.sqrt() - 1.0);
+    for _ in 0..100 {
+        let (a, c) = (high - ratio * (high - low), low + ratio * (high - low));
+        if sse(a) < sse(c) {
+            high = c;
+        } else {
+            low = a;
+        }
+    }
+    let b = 0.5 * (low + high);
+    let (_, x) = solve(b).ok_or_else(|| {
+        Error::Data("The Sherman formula cannot be fitted to these maxima.".into())
+    })?;
+    Ok(IdfCurve::Sherman {
+        k: x[0].exp(),
+        m: x[1],
+        b,
+        n: -x[2],
+    })
+}
+
+/// Solves the 3 x 3 system `a x = y` by Gaussian elimination with partial
+/// pivoting.
+fn solve3(mut a: [[f64; 3]; 3], mut y: [f64; 3]) -> Option<[f64; 3]> {
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|&r, &s| a[r][col].abs().total_cmp(&a[s][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        y.swap(col, pivot);
+        for row in col + 1..3 {
+            let f = a[row][col] / a[col][col];
+            let pivot_row = a[col];
+            for (v, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *v -= f * p;
+            }
+            y[row] -= f * y[col];
+        }
+    }
+    let mut x = [0.0; 3];
+    for row in (0..3).rev() {
+        let s: f64 = (row + 1..3).map(|c| a[row][c] * x[c]).sum();
+        x[row] = (y[row] - s) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEV: Gev = Gev {
+        location: 20.0,
+        scale: 6.0,
+        shape: -0.1,
+    };
+    const DURATIONS: [f64; 6] = [0.25, 0.5, 1.0, 2.0, 6.0, 24.0];
+    const B: f64 = 0.3;
+    const N: f64 = 0.75;
+
+    /// Annual maximum intensities at evenly spaced probabilities of `gev`.
+    fn sample(gev: Gev, n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|j| {
+                let f = (j as f64 + 0.5) / n as f64;
+                gev.quantile(1.0 / (1.0 - f))
+            })
+            .collect()
+    }
+
+    /// Depths whose intensities are those of [`GEV`] scaled by
+    /// `(d + B)^-N` at each duration.
+    fn depths() -> Vec<Vec<f64>> {
+        let x = sample(GEV, 2000);
+        DURATIONS
+            .iter()
+            .map(|&d| x.iter().map(|v| v * (d + B).powf(-N) * d).collect())
+            .collect()
+    }
+
+    #[test]
+    fn gev_fits_recover_the_parameters() {
+        let fit = Gev::fit(&sample(GEV, 2000), IdfDistribution::Gev).unwrap();
+        assert!((fit.location - 20.0).abs() < 0.02, "{fit:?}");
+        assert!((fit.scale - 6.0).abs() < 0.02, "{fit:?}");
+        assert!((fit.shape + 0.1).abs() < 0.005, "{fit:?}");
+        let gumbel = Gev { shape: 0.0, ..GEV };
+        let fit = Gev::fit(&sample(gumbel, 2000), IdfDistribution::Gumbel).unwrap();
+        assert_eq!(fit.shape, 0.0);
+        assert!((fit.location - 20.0).abs() < 0.02, "{fit:?}");
+        assert!((fit.scale - 6.0).abs() < 0.02, "{fit:?}");
+        // The 100-year value of a Gumbel distribution is μ + 4.600 σ.
+        assert!((gumbel.quantile(100.0) - 20.0 - 6.0 * 4.600_149_226_776_579).abs() < 1e-9);
+    }
+
+    #[test]
+    fn per_duration_curves_scale_with_the_intensities() {
+        let curve = idf_fit_rs(
+            &DURATIONS,
+            &depths(),
+            IdfMethod::PerDuration,
+            IdfDistribution::Gev,
+        )
+        .unwrap();
+        let IdfCurve::PerDuration { durations, fits } = &curve else {
+            panic!("{curve:?}")
+        };
+        assert_eq!(durations, &DURATIONS);
+        for (&d, fit) in DURATIONS.iter().zip(fits) {
+            let factor = (d + B).powf(-N);
+            assert!((fit.shape + 0.1).abs() < 0.005, "{d}: {fit:?}");
+            assert!((fit.scale / factor - 6.0).abs() < 0.02, "{d}: {fit:?}");
+        }
+        let expected = GEV.quantile(100.0) * (1.0 + B).powf(-N);
+        let i = curve.intensity(1.0, 100.0).unwrap();
+        assert!((i / expected - 1.0).abs() < 0.005, "{i} vs {expected}");
+    }
+
+    #[test]
+    fn sherman_fits_recover_the_duration_scaling() {
+        let curve = idf_fit_rs(
+            &DURATIONS,
+            &depths(),
+            IdfMethod::Sherman,
+            IdfDistribution::Gev,
+        )
+        .unwrap();
+        let IdfCurve::Sherman { k, m, b, n } = curve else {
+            panic!("{curve:?}")
+        };
+        // Every quantile shares the same duration factor, so `b` and `n`
+        // are those of the data, and `k T^m` is the log-log regression line
+        // of the unscaled quantiles.
+        assert!((b - B).abs() < 1e-3, "{b}");
+        assert!((n - N).abs() < 1e-3, "{n}");
+        let x: Vec<f64> = SHERMAN_RETURN_PERIODS.iter().map(|t| t.ln()).collect();
+        let y: Vec<f64> = SHERMAN_RETURN_PERIODS
+            .iter()
+            .map(|&t| GEV.quantile(t).ln())
+            .collect();
+        let (x_mean, y_mean) = (x.iter().sum::<f64>() / 7.0, y.iter().sum::<f64>() / 7.0);
+        let slope = x
+            .iter()
+            .zip(&y)
+            .map(|(a, b)| (a - x_mean) * (b - y_mean))
+            .sum::<f64>()
+            / x.iter().map(|a| (a - x_mean).powi(2)).sum::<f64>();
+        assert!((m - slope).abs() < 1e-3, "{m} vs {slope}");
+        assert!((k.ln() - (y_mean - slope * x_mean)).abs() < 1e-2, "{k}");
+        for t in SHERMAN_RETURN_PERIODS {
+            let expected = GEV.quantile(t) * (1.0 + B).powf(-N);
+            let i = curve.intensity(1.0, t).unwrap();
+            assert!((i / expected - 1.0).abs() < 0.1, "{t}: {i} vs {expected}");
+        }
+    }
+
+    #[test]
+    fn intensity_falls_with_duration() {
+        for method in [IdfMethod::PerDuration, IdfMethod::Sherman] {
+            let curve = idf_fit_rs(&DURATIONS, &depths(), method, IdfDistribution::Gev).unwrap();
+            for t in [2.0, 10.0, 100.0] {
+                let mut last = f64::INFINITY;
+                for j in 0..=40 {
+                    let d = 0.25 * 96f64.powf(j as f64 / 40.0);
+                    let i = curve.intensity(d, t).unwrap();
+                    assert!(i < last, "{method:?} {t}: {d} h");
+                    last = i;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        let depths = depths();
+        let fit = |durations: &[f64], depths: &[Vec<f64>]| {
+            idf_fit_rs(durations, depths, IdfMethod::Sherman, IdfDistribution::Gev)
+        };
+        assert!(matches!(
+            fit(&DURATIONS[..5], &depths),
+            Err(Error::InvalidParameter(_))
+        ));
+        let mut repeated = DURATIONS;
+        repeated[1] = 0.25;
+        assert!(matches!(
+            fit(&repeated, &depths),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(fit(&[1.0], &depths[..1]), Err(Error::Data(_))));
+        assert!(matches!(
+            fit(&[1.0, 2.0], &[vec![1.0; 10], vec![2.0; 10]]),
+            Err(Error::Data(_))
+        ));
+        let curve = fit(&DURATIONS, &depths).unwrap();
+        assert!(curve.intensity(0.0, 10.0).is_err());
+        assert!(curve.intensity(1.0, 1.0).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Low-flow frequency statistics such as 7Q10: the annual minimum `n`-day
+//! mean flow with a `y`-year return period.
+//!
+//! Annual minima of the `n`-day moving average are computed for climatic
+//! years (by default starting on 1 April, so that a low-flow season is not
+//! split), and a log-Pearson type III or two-parameter Weibull
+//! distribution is fitted to them. Years with zero minima are handled by
+//! the conditional probability adjustment of USGS SWSTAT: the distribution
+//! is fitted to the non-zero minima and scaled by their fraction.
+
+use std::collections::BTreeMap;
+
+use super::distributions::pearson3_frequency_factor;
+use crate::datetime::{civil_from_days, days_from_civil};
+use crate::error::{Error, Result};
+
+/// Distribution fitted to annual low flows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LowFlowDistribution {
+    /// Log-Pearson type III, by the method of moments on base-10 logarithms.
+    LogPearson3,
+    /// Two-parameter Weibull, by maximum likelihood.
+    Weibull,
+}
+
+impl LowFlowDistribution {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "lp3" | "log_pearson3" => Ok(LowFlowDistribution::LogPearson3),
+            "weibull" => Ok(LowFlowDistribution::Weibull),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown low-flow distribution `{name}`; expected `lp3` or `weibull`."
+            ))),
+        }
+    }
+}
+
+/// Annual minimum of the `n_days` moving average of a daily series starting
+/// on day `start_day` (days since 1970-01-01). Each average is assigned to
+/// the climatic year of its last day; climatic years start on the first of
+/// `year_start_month` and are named by the calendar year in which they end.
+/// Only years whose every day is present and not NaN are returned.
+pub fn annual_low_flows(
+    flows: &[f64],
+    start_day: i64,
+    n_days: usize,
+    year_start_month: u32,
+) -> Result<Vec<(i64, f64)>> {
+    if n_days == 0 {
+        return Err(Error::InvalidParameter(
+            "The averaging period must be at least one day.".into(),
+        ));
+    }
+    if !(1..=12).contains(&year_start_month) {
+        return Err(Error::InvalidParameter(format!(
+            "Year start month {year_start_month} is outside 1–12."
+        )));
+    }
+    let climatic_year = |day: i64| {
+        let (y, m, _) = civil_from_days(day);
+        if m >= year_start_month && year_start_month > 1 {
+            y + 1
+        } else {
+            y
+        }
+    };
+    // Per year: number of valid days and minimum n-day mean.
+    let mut years: BTreeMap<i64, (i64, f64)> = BTreeMap::new();
+    // Zero days are counted so that rounding in the running sum cannot
+    // turn a zero-flow window into a small positive mean.
+    let (mut sum, mut missing, mut zeros) = (0.0, 0usize, 0usize);
+    for (t, &q) in flows.iter().enumerate() {
+        if q.is_nan() {
+            missing += 1;
+        } else {
+            sum += q;
+            zeros += usize::from(q == 0.0);
+        }
+        if t >= n_days {
+            let old = flows[t - n_days];
+            if old.is_nan() {
+                missing -= 1;
+            } else {
+                sum -= old;
+                zeros -= usize::from(old == 0.0);
+            }
+        }
+        let entry = years
+            .entry(climatic_year(start_day + t as i64))
+            .or_insert((0, f64::INFINITY));
+        if !q.is_nan() {
+            entry.0 += 1;
+        }
+        if t + 1 >= n_days && missing == 0 {
+            let mean = if zeros == n_days {
+                0.0
+            } else {
+                sum / n_days as f64
+            };
+            entry.1 = entry.1.min(mean);
+        }
+    }
+    Ok(years
+        .into_iter()
+        .filter(|&(year, (valid, minimum))| {
+            let m = year_start_month;
+            let length = if m > 1 {
+                days_from_civil(year, m, 1) - days_from_civil(year - 1, m, 1)
+            } else {
+                days_from_civil(year + 1, 1, 1) - days_from_civil(year, 1, 1)
+            };
+            valid == length && minimum.is_finite()
+        })
+        .map(|(year, (_, minimum))| (year, minimum.max(0.0)))
+        .collect())
+}
+
+/// Distribution fitted to a series of annual low flows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LowFlowFit {
+    /// Mean, standard deviation and skew of the base-10 logarithms.
+    LogPearson3 { mean: f64, std: f64, skew: f64 },
+    /// Weibull `F(x) = 1 - exp(-(x / scale)^shape)`.
+    Weibull { shape: f64, scale: f64 },
+}
+
+/// Fitted low-flow distribution with the fraction of non-zero years.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LowFlowFrequency {
+    pub fit: LowFlowFit,
+    /// Fraction of the annual minima that are greater than zero.
+    pub nonzero_fraction: f64,
+}
+
+impl LowFlowFrequency {
+    /// Fits `distribution` to annual `minima`, which need at least three
+    /// non-zero values.
+    pub fn fit(minima: &[f64], distribution: LowFlowDistribution) -> Result<Self> {
+        let nonzero: Vec<f64> = minima.iter().copied().filter(|&q| q > 0.0).collect();
+        if nonzero.len() < 3 {
+            return Err(Error::Data(
+                "At least three years with non-zero low flows are required.".into(),
+            ));
+        }
+        let n = nonzero.len() as f64;
+        let fit = match distribution {
+            LowFlowDistribution::LogPearson3 => {
+                let logs: Vec<f64> = nonzero.iter().map(|q| q.log10()).collect();
+                let mean = logs.iter().sum::<f64>() / n;
+                let m2 = logs.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+                let m3 = logs.iter().map(|x| (x - mean).powi(3)).sum::<f64>();
+                let std = (m2 / (n - 1.0)).sqrt();
+                let skew = if std > 0.0 {
+                    n * m3 / ((n - 1.0) * (n - 2.0) * std.powi(3))
+                } else {
+                    0.0
+                };
+                LowFlowFit::LogPearson3 { mean, std, skew }
+            }
+            LowFlowDistribution::Weibull => {
+                let shape = weibull_shape(&nonzero)?;
+                let scale =
+                    (nonzero.iter().map(|q| q.powf(shape)).sum::<f64>() / n).powf(1.0 / shape);
+                LowFlowFit::Weibull { shape, scale }
+            }
+        };
+        Ok(LowFlowFrequency {
+            fit,
+            nonzero_fraction: n / minima.len() as f64,
+        })
+    }
+
+    /// Low flow with non-exceedance probability `p`, i.e. the flow not
+    /// reached in a year with probability `p` (`p = 1/10` for 7Q10).
+    pub fn quantile(&self, p: f64) -> f64 {
+        let zero_fraction = 1.0 - self.nonzero_fraction;
+        if p <= zero_fraction {
+            return 0.0;
+        }
+        let p = (p - zero_fraction) / self.nonzero_fraction;
+        match self.fit {
+            LowFlowFit::LogPearson3 { mean, std, skew } => {
+                10f64.powf(mean + pearson3_frequency_factor(p, skew) * std)
+            }
+            LowFlowFit::Weibull { shape, scale } => scale * (-(1.0 - p).ln()).powf(1.0 / shape),
+        }
+    }
+}
+
+/// Maximum-likelihood Weibull shape of positive `values`, by bisection of
+/// the profile likelihood equation.
+fn weibull_shape(values: &[f64]) -> Result<f64> {
+    let n = values.len() as f64;
+    let mean_log = values.iter().map(|q| q.ln()).sum::<f64>() / n;
+    let top = values.iter().copied().fold(f64::MIN, f64::max);
+    let score = |k: f64| {
+        // Powers are scaled by the largest value to avoid overflow.
+        let (mut s0, mut s1) = (0.0, 0.0);
+        for &q in values {
+            let w = (q / top).powf(k);
+            s0 += w;
+            s1 += w * q.ln();
+        }
+        s1 / s0 - 1.0 / k - mean_log
+    };
+    let (mut low, mut high) = (1e-3, 1e3);
+    if score(low) > 0.0 || score(high) < 0.0 {
+        return Err(Error::Data(
+            "Annual low flows are too uniform to fit a Weibull distribution.".into(),
+        ));
+    }
+    for _ in 0..200 {
+        let mid = (low * high).sqrt();
+        if score(mid) < 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Ok((low * high).sqrt())
+}
+
+/// `nQy` statistics of a daily flow series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LowFlowStatistics {
+    /// Climatic year and annual minimum `n`-day mean flow.
+    pub minima: Vec<(i64, f64)>,
+    pub frequency: LowFlowFrequency,
+    /// Low flow for each requested return period.
+    pub quantiles: Vec<f64>,
+}
+
+/// Computes the annual minima (by climatic year) of the `n_days` mean of a
+/// daily series, fits `distribution` to them and evaluates the low flow
+/// for each of `return_periods` (years).
+pub fn low_flow_rs(
+    flows: &[f64],
+    start_day: i64,
+    n_days: usize,
+    year_start_month: u32,
+    distribution: LowFlowDistribution,
+    return_periods: &[f64],
+) -> Result<LowFlowStatistics> {
+    if let Some(t) = return_periods.iter().find(|&&t| t <= 1.0) {
+        return Err(Error::InvalidParameter(format!(
+            "Return period {t} must be greater than 1."
+        )));
+    }
+    let minima = annual_low_flows(flows, start_day, n_days, year_start_month)?;
+    let values: Vec<f64> = minima.iter().map(|&(_, q)| q).collect();
+    let frequency = LowFlowFrequency::fit(&values, distribution)?;
+    let quantiles = return_periods
+        .iter()
+        .map(|t| frequency.quantile(1.0 / t))
+        .collect();
+    Ok(LowFlowStatistics {
+        minima,
+        frequency,
+        quantiles,
+    })
+}
@@ -0,0 +1,12 @@
+//! Flood, rainfall and low-flow frequency analysis: empirical frequency
+//! curves of annual series, intensity–duration–frequency curves, low-flow
+//! statistics such as 7Q10 and regional regression estimates of peak-flow
+//! statistics at ungauged sites.
+
+pub mod distributions;
+pub mod empirical;
+pub mod idf;
+pub mod low_flow;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod regression;
@@ -0,0 +1,315 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDict, PyType};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::empirical::{empirical_cdf_rs, EmpiricalFrequency, PlottingPosition};
+use super::idf::{idf_fit_rs, Gev, IdfCurve, IdfDistribution, IdfMethod};
+use super::low_flow::{low_flow_rs, LowFlowDistribution, LowFlowFit};
+use super::regression::{equations_from_value, regression_peaks_rs};
+use crate::arrow::FloatSeries;
+use crate::config::{read_config, Value};
+use crate::time::{datetime_to_epoch, timedelta_to_seconds};
+
+/// Fitted intensity–duration–frequency relationship, callable as
+/// `curve(duration, return_period)` for the intensity.
+#[pyclass(name = "IdfCurve", module = "rustflow.frequency")]
+pub struct PyIdfCurve {
+    pub inner: IdfCurve,
+}
+
+#[pymethods]
+impl PyIdfCurve {
+    fn __call__(&self, duration: Bound<'_, PyDelta>, return_period: f64) -> PyResult<f64> {
+        self.intensity(duration, return_period)
+    }
+
+    /// Rainfall intensity.
+    ///
+    /// Args:
+    ///     duration (timedelta): Storm duration.
+    ///     return_period (float): Return period in years, above 1.
+    ///
+    /// Returns:
+    ///     float: The intensity, in depth units per hour.
+    fn intensity(&self, duration: Bound<'_, PyDelta>, return_period: f64) -> PyResult<f64> {
+        let hours = timedelta_to_seconds(&duration) / 3600.0;
+        Ok(self.inner.intensity(hours, return_period)?)
+    }
+
+    /// Rainfall depth accumulated over `duration`.
+    ///
+    /// Args:
+    ///     duration (timedelta): Storm duration.
+    ///     return_period (float): Return period in years, above 1.
+    ///
+    /// Returns:
+    ///     float: The depth.
+    fn depth(&self, duration: Bound<'_, PyDelta>, return_period: f64) -> PyResult<f64> {
+        let hours = timedelta_to_seconds(&duration) / 3600.0;
+        Ok(self.inner.depth(hours, return_period)?)
+    }
+
+    /// Literal["gev", "sherman"]: Per-duration GEV (`"gev"`) or `"sherman"`.
+    #[getter]
+    fn method(&self) -> &'static str {
+        match self.inner {
+            IdfCurve::PerDuration { .. } => "gev",
+            IdfCurve::Sherman { .. } => "sherman",
+        }
+    }
+
+    /// dict[str, Any]: Fitted parameters: per-duration `durations` (hours),
+    /// `location`, `scale` and `shape`, or Sherman `k`, `m`, `b` (hours) and
+    /// `n`.
+    #[getter]
+    fn parameters<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let result = PyDict::new(py);
+        match &self.inner {
+            IdfCurve::PerDuration { durations, fits } => {
+                result.set_item("durations", durations)?;
+                result.set_item(
+                    "location",
+                    fits.iter().map(|f| f.location).collect::<Vec<_>>(),
+                )?;
+                result.set_item("scale", fits.iter().map(|f| f.scale).collect::<Vec<_>>())?;
+                result.set_item("shape", fits.iter().map(|f| f.shape).collect::<Vec<_>>())?;
+            }
+            IdfCurve::Sherman { k, m, b, n } => {
+                result.set_item("k", k)?;
+                result.set_item("m", m)?;
+                result.set_item("b", b)?;
+                result.set_item("n", n)?;
+            }
+        }
+        Ok(result)
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.inner {
+            IdfCurve::PerDuration { durations, .. } => {
+                format!("IdfCurve(method='gev', durations={})", durations.len())
+            }
+            IdfCurve::Sherman { k, m, b, n } => {
+                format!("IdfCurve(method='sherman', k={k:.4}, m={m:.4}, b={b:.4}, n={n:.4})")
+            }
+        }
+    }
+
+    /// Pickles the curve as its method and parameters: per-duration
+    /// durations and `(location, scale, shape)` fits, or Sherman
+    /// `[k, m, b, n]`.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(
+        Bound<'py, PyAny>,
+        (&'static str, Vec<f64>, Vec<(f64, f64, f64)>),
+    )> {
+        let state = match &slf.borrow().inner {
+            IdfCurve::PerDuration { durations, fits } => (
+                "gev",
+                durations.clone(),
+                fits.iter()
+                    .map(|f| (f.location, f.scale, f.shape))
+                    .collect(),
+            ),
+            IdfCurve::Sherman { k, m, b, n } => ("sherman", vec![*k, *m, *b, *n], Vec::new()),
+        };
+        Ok((slf.get_type().getattr("_from_state")?, state))
+    }
+
+    #[classmethod]
+    fn _from_state(
+        _cls: &Bound<'_, PyType>,
+        method: &str,
+        values: Vec<f64>,
+        fits: Vec<(f64, f64, f64)>,
+    ) -> PyResult<Self> {
+        let inner = match (method, values.as_slice()) {
+            ("gev", _) => IdfCurve::PerDuration {
+                durations: values,
+                fits: fits
+                    .into_iter()
+                    .map(|(location, scale, shape)| Gev {
+                        location,
+                        scale,
+                        shape,
+                    })
+                    .collect(),
+            },
+            ("sherman", &[k, m, b, n]) => IdfCurve::Sherman { k, m, b, n },
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Cannot restore an IDF curve of method `{method}`."
+                )))
+            }
+        };
+        Ok(PyIdfCurve { inner })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, method="weibull"))]
+pub fn plotting_positions<'py>(
+    py: Python<'py>,
+    values: FloatSeries,
+    method: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let curve = EmpiricalFrequency::new(&values.0, PlottingPosition::parse(method)?)?;
+    let result = PyDict::new(py);
+    result.set_item("value", &curve.values)?;
+    result.set_item("exceedance", &curve.exceedance)?;
+    result.set_item("return_period", curve.return_periods())?;
+    Ok(result)
+}
+
+#[pyfunction]
+pub fn empirical_cdf(values: FloatSeries, points: FloatSeries) -> Vec<f64> {
+    empirical_cdf_rs(&values.0, &points.0)
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, return_periods, method="weibull"))]
+pub fn empirical_quantiles(
+    values: FloatSeries,
+    return_periods: Vec<f64>,
+    method: &str,
+) -> PyResult<Vec<f64>> {
+    let curve = EmpiricalFrequency::new(&values.0, PlottingPosition::parse(method)?)?;
+    return_periods
+        .iter()
+        .map(|&t| Ok(curve.value_at(t)?))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, magnitudes, method="weibull"))]
+pub fn empirical_return_periods(
+    values: FloatSeries,
+    magnitudes: FloatSeries,
+    method: &str,
+) -> PyResult<Vec<f64>> {
+    let curve = EmpiricalFrequency::new(&values.0, PlottingPosition::parse(method)?)?;
+    Ok(magnitudes
+        .0
+        .iter()
+        .map(|&q| curve.return_period_of(q))
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (flows, start, n_days=7, return_periods=vec![10.0], distribution="lp3", year_start_month=4))]
+pub fn low_flow<'py>(
+    py: Python<'py>,
+    flows: FloatSeries,
+    start: Bound<'py, PyDateTime>,
+    n_days: usize,
+    return_periods: Vec<f64>,
+    distribution: &str,
+    year_start_month: u32,
+) -> PyResult<Bound<'py, PyDict>> {
+    let distribution = LowFlowDistribution::parse(distribution)?;
+    let start_day = (datetime_to_epoch(&start) / 86_400.0).floor() as i64;
+    let statistics = py.allow_threads(|| {
+        low_flow_rs(
+            &flows.0,
+            start_day,
+            n_days,
+            year_start_month,
+            distribution,
+            &return_periods,
+        )
+    })?;
+    let result = PyDict::new(py);
+    let minima = &statistics.minima;
+    result.set_item("years", minima.iter().map(|m| m.0).collect::<Vec<_>>())?;
+    result.set_item(
+        "annual_minima",
+        minima.iter().map(|m| m.1).collect::<Vec<_>>(),
+    )?;
+    let named = PyDict::new(py);
+    for (t, q) in return_periods.iter().zip(&statistics.quantiles) {
+        named.set_item(format!("{n_days}Q{t}"), q)?;
+    }
+    result.set_item("statistics", named)?;
+    let frequency = statistics.frequency;
+    let parameters = PyDict::new(py);
+    match frequency.fit {
+        LowFlowFit::LogPearson3 { mean, std, skew } => {
+            parameters.set_item("mean", mean)?;
+            parameters.set_item("std", std)?;
+            parameters.set_item("skew", skew)?;
+        }
+        LowFlowFit::Weibull { shape, scale } => {
+            parameters.set_item("shape", shape)?;
+            parameters.set_item("scale", scale)?;
+        }
+    }
+    parameters.set_item("nonzero_fraction", frequency.nonzero_fraction)?;
+    result.set_item("parameters", parameters)?;
+    Ok(result)
+}
+
+#[pyfunction]
+#[pyo3(signature = (equations, characteristics, confidence=0.9))]
+pub fn regression_peaks<'py>(
+    py: Python<'py>,
+    equations: Bound<'py, PyAny>,
+    characteristics: BTreeMap<String, f64>,
+    confidence: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let config = match equations.extract::<PathBuf>() {
+        Ok(path) => read_config(&path)?,
+        Err(_) => equations.extract::<Value>()?,
+    };
+    let equations = equations_from_value(&config)?;
+    let estimates = regression_peaks_rs(&equations, &characteristics, confidence)?;
+    let result = PyDict::new(py);
+    for (equation, estimate) in equations.iter().zip(estimates) {
+        let entry = PyDict::new(py);
+        entry.set_item("estimate", estimate.estimate)?;
+        entry.set_item("lower", estimate.lower)?;
+        entry.set_item("upper", estimate.upper)?;
+        entry.set_item("standard_error", estimate.standard_error)?;
+        entry.set_item("extrapolated", estimate.extrapolated)?;
+        result.set_item(&equation.statistic, entry)?;
+    }
+    Ok(result)
+}
+
+#[pyfunction]
+#[pyo3(signature = (durations, annual_maxima, method="gev", distribution="gev"))]
+pub fn idf_fit(
+    durations: Vec<Bound<'_, PyDelta>>,
+    annual_maxima: Vec<FloatSeries>,
+    method: &str,
+    distribution: &str,
+) -> PyResult<PyIdfCurve> {
+    let hours: Vec<f64> = durations
+        .iter()
+        .map(|d| timedelta_to_seconds(d) / 3600.0)
+        .collect();
+    let depths: Vec<Vec<f64>> = annual_maxima.into_iter().map(|s| s.0).collect();
+    let inner = idf_fit_rs(
+        &hours,
+        &depths,
+        IdfMethod::parse(method)?,
+        IdfDistribution::parse(distribution)?,
+    )?;
+    Ok(PyIdfCurve { inner })
+}
+
+#[pymodule]
+pub fn init_frequency(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(plotting_positions, m)?)?;
+    m.add_function(wrap_pyfunction!(empirical_cdf, m)?)?;
+    m.add_function(wrap_pyfunction!(empirical_quantiles, m)?)?;
+    m.add_function(wrap_pyfunction!(empirical_return_periods, m)?)?;
+    m.add_function(wrap_pyfunction!(idf_fit, m)?)?;
+    m.add_function(wrap_pyfunction!(low_flow, m)?)?;
+    m.add_function(wrap_pyfunction!(regression_peaks, m)?)?;
+    m.add_class::<PyIdfCurve>()?;
+    Ok(())
+}
@@ -0,0 +1,279 @@
+//! Regional regression equations for peak-flow statistics at ungauged
+//! sites, in the form published for USGS StreamStats.
+//!
+//! An equation predicts a transformed statistic as a linear function of
+//! transformed basin characteristics,
+//!
+//! ```text
+//! y = b0 + b1 f1(X1 + c1) + ... + bp fp(Xp + cp)
+//! ```
+//!
+//! with `y = log10(Q)` for most published equations. Prediction intervals
+//! follow Tasker and Driver (1988): the variance of prediction at a site
+//! is the model error variance plus the sampling variance `x U x'`, where
+//! `x` is the row of transformed characteristics (with a leading 1) and
+//! `U` the inverse of `X' Λ X` published with the equation.
+
+use std::collections::BTreeMap;
+
+use super::distributions::{normal_quantile, student_t_quantile};
+use crate::config::Value;
+use crate::error::{Error, Result};
+
+/// Transformation applied to a basin characteristic or to the statistic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    None,
+    Log10,
+    Ln,
+}
+
+impl Transform {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" | "linear" => Ok(Transform::None),
+            "log10" | "log" => Ok(Transform::Log10),
+            "ln" => Ok(Transform::Ln),
+            _ => Err(Error::Data(format!(
+                "Unknown transform `{name}`; expected `none`, `log10` or `ln`."
+            ))),
+        }
+    }
+
+    fn apply(self, x: f64) -> f64 {
+        match self {
+            Transform::None => x,
+            Transform::Log10 => x.log10(),
+            Transform::Ln => x.ln(),
+        }
+    }
+
+    fn invert(self, y: f64) -> f64 {
+        match self {
+            Transform::None => y,
+            Transform::Log10 => 10f64.powf(y),
+            Transform::Ln => y.exp(),
+        }
+    }
+}
+
+/// One explanatory basin characteristic of a regression equation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Predictor {
+    /// Name of the basin characteristic, e.g. `"drainage_area"`.
+    pub name: String,
+    pub coefficient: f64,
+    pub transform: Transform,
+    /// Constant added to the characteristic before transforming it.
+    pub offset: f64,
+    /// Range of the characteristic in the data used to develop the
+    /// equation; estimates outside it are extrapolations.
+    pub range: Option<(f64, f64)>,
+}
+
+/// A regional regression equation for one statistic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegressionEquation {
+    /// Name of the statistic, e.g. `"Q100"`.
+    pub statistic: String,
+    pub intercept: f64,
+    pub predictors: Vec<Predictor>,
+    /// Transform of the statistic in the regression.
+    pub response: Transform,
+    /// Model error variance, in transformed units.
+    pub model_variance: f64,
+    /// Inverse of `X' Λ X` (intercept first), for the sampling variance at
+    /// a site. Without it, `average_sampling_variance` is used.
+    pub covariance: Option<Vec<Vec<f64>>>,
+    /// Sampling variance used when `covariance` is not given.
+    pub average_sampling_variance: f64,
+    /// Degrees of freedom of the regression; intervals use the normal
+    /// distribution if `None`.
+    pub degrees_of_freedom: Option<f64>,
+}
+
+/// Estimate of one statistic at a site.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegressionEstimate {
+    pub estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+    /// Standard error of prediction, in transformed units.
+    pub standard_error: f64,
+    /// Whether any characteristic is outside the equation's range.
+    pub extrapolated: bool,
+}
+
+impl RegressionEquation {
+    /// Builds an equation from a table with `statistic`, `intercept`,
+    /// `predictors` (tables with `name`, `coefficient` and optional
+    /// `transform`, `offset`, `min` and `max`) and optional `response`
+    /// (default `"log10"`), `model_variance`, `covariance`,
+    /// `average_sampling_variance` and `degrees_of_freedom`.
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let predictors = value
+            .get("predictors")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::Data("Field `predictors` must be an array of tables.".into()))?
+            .iter()
+            .map(|p| {
+                let range = match (p.opt_f64_field("min")?, p.opt_f64_field("max")?) {
+                    (None, None) => None,
+                    (min, max) => Some((
+                        min.unwrap_or(f64::NEG_INFINITY),
+                        max.unwrap_or(f64::INFINITY),
+                    )),
+                };
+                Ok(Predictor {
+                    name: p.str_field("name")?.to_string(),
+                    coefficient: p.f64_field("coefficient")?,
+                    transform: Transform::parse(p.opt_str_field("transform")?.unwrap_or("log10"))?,
+                    offset: p.opt_f64_field("offset")?.unwrap_or(0.0),
+                    range,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let covariance = match value.get("covariance") {
+            None | Some(Value::Null) => None,
+            Some(rows) => Some(
+                rows.as_array()
+                    .and_then(|rows| {
+                        rows.iter()
+                            .map(|row| row.as_array()?.iter().map(Value::as_f64).collect())
+                            .collect::<Option<Vec<Vec<f64>>>>()
+                    })
+                    .ok_or_else(|| {
+                        Error::Data("Field `covariance` must be an array of number arrays.".into())
+                    })?,
+            ),
+        };
+        let equation = RegressionEquation {
+            statistic: value.str_field("statistic")?.to_string(),
+            intercept: value.f64_field("intercept")?,
+            predictors,
+            response: Transform::parse(value.opt_str_field("response")?.unwrap_or("log10"))?,
+            model_variance: value.opt_f64_field("model_variance")?.unwrap_or(0.0),
+            covariance,
+            average_sampling_variance: value
+                .opt_f64_field("average_sampling_variance")?
+                .unwrap_or(0.0),
+            degrees_of_freedom: value.opt_f64_field("degrees_of_freedom")?,
+        };
+        equation.validate()?;
+        Ok(equation)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.model_variance < 0.0 || self.average_sampling_variance < 0.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Variances of `{}` must not be negative.",
+                self.statistic
+            )));
+        }
+        if self.degrees_of_freedom.is_some_and(|df| df <= 0.0) {
+            return Err(Error::InvalidParameter(format!(
+                "Degrees of freedom of `{}` must be positive.",
+                self.statistic
+            )));
+        }
+        let size = self.predictors.len() + 1;
+        if let Some(covariance) = &self.covariance {
+            if covariance.len() != size || covariance.iter().any(|row| row.len() != size) {
+                return Err(Error::Data(format!(
+                    "Covariance of `{}` must be a {size} x {size} matrix (intercept first).",
+                    self.statistic
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates the equation for the basin `characteristics` with a
+    /// two-sided prediction interval at `confidence` (e.g. 0.9).
+    pub fn evaluate(
+        &self,
+        characteristics: &BTreeMap<String, f64>,
+        confidence: f64,
+    ) -> Result<RegressionEstimate> {
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err(Error::InvalidParameter(
+                "Confidence must be within (0, 1).".into(),
+            ));
+        }
+        let mut row = Vec::with_capacity(self.predictors.len() + 1);
+        row.push(1.0);
+        let mut extrapolated = false;
+        for predictor in &self.predictors {
+            let x = *characteristics.get(&predictor.name).ok_or_else(|| {
+                Error::Data(format!(
+                    "Basin characteristic `{}` required by `{}` is missing.",
+                    predictor.name, self.statistic
+                ))
+            })?;
+            let value = predictor.transform.apply(x + predictor.offset);
+            if !value.is_finite() {
+                return Err(Error::InvalidParameter(format!(
+                    "Basin characteristic `{}` = {x} cannot be transformed.",
+                    predictor.name
+                )));
+            }
+            extrapolated |= predictor.range.is_some_and(|(min, max)| x < min || x > max);
+            row.push(value);
+        }
+        let y = self.intercept
+            + self
+                .predictors
+                .iter()
+                .zip(&row[1..])
+                .map(|(p, x)| p.coefficient * x)
+                .sum::<f64>();
+        let sampling = match &self.covariance {
+            Some(u) => row
+                .iter()
+                .zip(u)
+                .map(|(xi, ui)| xi * ui.iter().zip(&row).map(|(uij, xj)| uij * xj).sum::<f64>())
+                .sum::<f64>(),
+            None => self.average_sampling_variance,
+        };
+        let standard_error = (self.model_variance + sampling).max(0.0).sqrt();
+        let p = 0.5 + 0.5 * confidence;
+        let quantile = match self.degrees_of_freedom {
+            Some(df) => student_t_quantile(p, df),
+            None => normal_quantile(p),
+        };
+        let half_width = quantile * standard_error;
+        Ok(RegressionEstimate {
+            estimate: self.response.invert(y),
+            lower: self.response.invert(y - half_width),
+            upper: self.response.invert(y + half_width),
+            standard_error,
+            extrapolated,
+        })
+    }
+}
+
+/// Reads regression equations from a configuration holding either a single
+/// equation table or an `equations` array of them.
+pub fn equations_from_value(value: &Value) -> Result<Vec<RegressionEquation>> {
+    match value.get("equations") {
+        Some(equations) => equations
+            .as_array()
+            .ok_or_else(|| Error::Data("Field `equations` must be an array of tables.".into()))?
+            .iter()
+            .map(RegressionEquation::from_value)
+            .collect(),
+        None => Ok(vec![RegressionEquation::from_value(value)?]),
+    }
+}
+
+/// Evaluates each equation for the same basin, in order.
+pub fn regression_peaks_rs(
+    equations: &[RegressionEquation],
+    characteristics: &BTreeMap<String, f64>,
+    confidence: f64,
+) -> Result<Vec<RegressionEstimate>> {
+    equations
+        .iter()
+        .map(|equation| equation.evaluate(characteristics, confidence))
+        .collect()
+}
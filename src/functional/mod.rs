@@ -0,0 +1,163 @@
+//! Pure batched kernels for embedding routing in differentiable pipelines.
+//!
+//! Every function maps flat row-major arrays to flat arrays with no state:
+//! a batch of `batch` series of `steps` values each, with one parameter
+//! value per series (or one shared by all). Outputs are stored in the
+//! precision of the inflow. The vector–Jacobian products give the gradient
+//! of a scalar loss with respect to every input from the gradient with
+//! respect to the outflow, as needed by reverse-mode automatic
+//! differentiation frameworks.
+//!
+//! Each series starts from its first inflow, so the initial outflow is not
+//! a separate input.
+
+#[cfg(feature = "python")]
+pub mod python;
+
+use crate::calibrate::gradient::muskingum_gradient_rs;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::parallel::par_map;
+use crate::reach_routing::muskingum::{
+    check_muskingum, muskingum_cascade_rs, muskingum_coefficients,
+};
+
+/// Gradients of a loss with respect to the inputs of [`muskingum`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MuskingumVjp<T> {
+    /// Row-major `(batch, steps)`, like the inflow.
+    pub inflow: Vec<T>,
+    /// One value per series, `k` per second.
+    pub k: Vec<f64>,
+    pub x: Vec<f64>,
+}
+
+/// Checks the batch layout and broadcasts `k` and `x` to one value per
+/// series.
+fn batch_parameters(
+    values: usize,
+    batch: usize,
+    dt: f64,
+    k: &[f64],
+    x: &[f64],
+    sub_reaches: usize,
+) -> Result<Vec<(f64, f64)>> {
+    if batch == 0 || values == 0 || !values.is_multiple_of(batch) {
+        return Err(Error::InvalidParameter(format!(
+            "{values} values do not split into {batch} non-empty series."
+        )));
+    }
+    let broadcast = |v: &[f64], name: &str| -> Result<Vec<f64>> {
+        match v.len() {
+            1 => Ok(vec![v[0]; batch]),
+            n if n == batch => Ok(v.to_vec()),
+            n => Err(Error::InvalidParameter(format!(
+                "Got {n} values of `{name}` for {batch} series; expected 1 or {batch}."
+            ))),
+        }
+    };
+    let pairs: Vec<(f64, f64)> = broadcast(k, "k")?
+        .into_iter()
+        .zip(broadcast(x, "x")?)
+        .collect();
+    for &(k, x) in &pairs {
+        check_muskingum(&[0.0], dt, k, x, sub_reaches as i64, true)?;
+    }
+    Ok(pairs)
+}
+
+/// Routes each of the `batch` series in `inflow` (row-major) through its
+/// own Muskingum reach of `sub_reaches` sub-reaches with storage constant
+/// `k` (s) and weighting `x`, in parallel.
+pub fn muskingum<T: Float>(
+    inflow: &[T],
+    batch: usize,
+    dt: f64,
+    k: &[f64],
+    x: &[f64],
+    sub_reaches: usize,
+) -> Result<Vec<T>> {
+    let parameters = batch_parameters(inflow.len(), batch, dt, k, x, sub_reaches)?;
+    let steps = inflow.len() / batch;
+    let rows: Vec<(&[T], (f64, f64))> = inflow.chunks(steps).zip(parameters).collect();
+    Ok(par_map(&rows, |&(row, (k, x))| {
+        muskingum_cascade_rs(row, dt, k, x, sub_reaches, None)
+    })
+    .concat())
+}
+
+/// Vector–Jacobian product of [`muskingum`]: the gradients of a loss with
+/// respect to the inflow, `k` and `x` given its gradient `grad_outflow`
+/// with respect to the outflow (same layout as the inflow).
+///
+/// The routing is linear in the inflow, so the inflow gradient is the
+/// adjoint recursion run backwards in time; the parameter gradients use the
+/// exact forward sensitivities.
+pub fn muskingum_vjp<T: Float>(
+    inflow: &[T],
+    batch: usize,
+    dt: f64,
+    k: &[f64],
+    x: &[f64],
+    sub_reaches: usize,
+    grad_outflow: &[T],
+) -> Result<MuskingumVjp<T>> {
+    let parameters = batch_parameters(inflow.len(), batch, dt, k, x, sub_reaches)?;
+    if grad_outflow.len() != inflow.len() {
+        return Err(Error::InvalidParameter(format!(
+            "Got {} outflow gradients for {} inflows.",
+            grad_outflow.len(),
+            inflow.len()
+        )));
+    }
+    let steps = inflow.len() / batch;
+    let rows: Vec<_> = inflow
+        .chunks(steps)
+        .zip(grad_outflow.chunks(steps))
+        .zip(parameters)
+        .collect();
+    let results = par_map(&rows, |&((row, grad), (k, x))| {
+        let row: Vec<f64> = row.iter().map(|v| v.to_f64()).collect();
+        let mut adjoint: Vec<f64> = grad.iter().map(|v| v.to_f64()).collect();
+        let sensitivity = muskingum_gradient_rs(&row, dt, k, x, sub_reaches, None)?;
+        let dot = |d: &[f64]| d.iter().zip(&adjoint).map(|(a, b)| a * b).sum::<f64>();
+        let (grad_k, grad_x) = (dot(&sensitivity.d_k), dot(&sensitivity.d_x));
+        let (c0, c1, c2) = muskingum_coefficients(dt, k / sub_reaches as f64, x);
+        for _ in 0..sub_reaches {
+            adjoint = reach_adjoint(&adjoint, c0, c1, c2);
+        }
+        Ok((adjoint, grad_k, grad_x))
+    });
+    let mut vjp = MuskingumVjp {
+        inflow: Vec::with_capacity(inflow.len()),
+        k: Vec::with_capacity(batch),
+        x: Vec::with_capacity(batch),
+    };
+    for result in results {
+        let (grad_inflow, grad_k, grad_x) = result?;
+        vjp.inflow.extend(grad_inflow.into_iter().map(T::from_f64));
+        vjp.k.push(grad_k);
+        vjp.x.push(grad_x);
+    }
+    Ok(vjp)
+}
+
+/// Gradient with respect to the inflow of one Muskingum reach, whose
+/// outflow starts at its first inflow, from the gradient `grad` with
+/// respect to its outflow.
+fn reach_adjoint(grad: &[f64], c0: f64, c1: f64, c2: f64) -> Vec<f64> {
+    let n = grad.len();
+    // adjoint[t]: total derivative of the loss with respect to outflow t.
+    let mut adjoint = vec![0.0; n];
+    for t in (0..n).rev() {
+        let later = if t + 1 < n { c2 * adjoint[t + 1] } else { 0.0 };
+        adjoint[t] = grad[t] + later;
+    }
+    (0..n)
+        .map(|t| {
+            let now = if t == 0 { adjoint[0] } else { c0 * adjoint[t] };
+            let next = if t + 1 < n { c1 * adjoint[t + 1] } else { 0.0 };
+            now + next
+        })
+        .collect()
+}
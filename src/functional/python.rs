@@ -0,0 +1,129 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+use super::{muskingum as muskingum_rs, muskingum_vjp as muskingum_vjp_rs};
+use crate::arrow::{grid_array, FloatBuffer, FloatSeries, FloatSlice, GridValue};
+
+/// Number of series and the shape of a 1-D `(steps,)` or 2-D
+/// `(batch, steps)` inflow.
+fn batch_shape(inflow: &FloatBuffer) -> PyResult<(usize, Vec<usize>)> {
+    match inflow.shape() {
+        [_] => Ok((1, inflow.shape().to_vec())),
+        [batch, _] => Ok((*batch, inflow.shape().to_vec())),
+        shape => Err(PyValueError::new_err(format!(
+            "Expected a 1-D or 2-D inflow array, got {} dimensions.",
+            shape.len()
+        ))),
+    }
+}
+
+/// Reads a parameter given as a number or a 1-D array of one value per
+/// series.
+fn parameter(value: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    match value.extract::<f64>() {
+        Ok(v) => Ok(vec![v]),
+        Err(_) => Ok(value.extract::<FloatSeries>()?.0),
+    }
+}
+
+/// Returns row-major `values` as a NumPy array of `shape`, or as a
+/// memoryview of that shape when NumPy is not installed.
+fn output<'py, T: GridValue>(
+    py: Python<'py>,
+    values: &[T],
+    shape: &[usize],
+) -> PyResult<Bound<'py, PyAny>> {
+    let (rows, cols) = match shape {
+        [n] => (1, *n),
+        [rows, cols] => (*rows, *cols),
+        _ => (1, values.len()),
+    };
+    let array = grid_array(py, values, rows, cols)?;
+    if shape.len() == 2 {
+        return Ok(array);
+    }
+    if py.import("numpy").is_ok() {
+        array.call_method1("reshape", (PyTuple::new(py, shape)?,))
+    } else {
+        array
+            .call_method1("cast", ("B",))?
+            .call_method1("cast", (T::FORMAT,))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, k, x, dt, sub_reaches=1))]
+pub fn muskingum<'py>(
+    py: Python<'py>,
+    inflow: FloatBuffer,
+    k: Bound<'py, PyAny>,
+    x: Bound<'py, PyAny>,
+    dt: f64,
+    sub_reaches: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    let (batch, shape) = batch_shape(&inflow)?;
+    let (k, x) = (parameter(&k)?, parameter(&x)?);
+    match inflow.values() {
+        FloatSlice::F32(values) => {
+            let out = py.allow_threads(|| muskingum_rs(values, batch, dt, &k, &x, sub_reaches))?;
+            output(py, &out, &shape)
+        }
+        FloatSlice::F64(values) => {
+            let out = py.allow_threads(|| muskingum_rs(values, batch, dt, &k, &x, sub_reaches))?;
+            output(py, &out, &shape)
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, k, x, dt, grad_outflow, sub_reaches=1))]
+#[allow(clippy::type_complexity)]
+pub fn muskingum_vjp<'py>(
+    py: Python<'py>,
+    inflow: FloatBuffer,
+    k: Bound<'py, PyAny>,
+    x: Bound<'py, PyAny>,
+    dt: f64,
+    grad_outflow: FloatBuffer,
+    sub_reaches: usize,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    let (batch, shape) = batch_shape(&inflow)?;
+    if grad_outflow.shape() != inflow.shape() {
+        return Err(PyValueError::new_err(
+            "`grad_outflow` must have the shape of `inflow`.",
+        ));
+    }
+    let (k, x) = (parameter(&k)?, parameter(&x)?);
+    match (inflow.values(), grad_outflow.values()) {
+        (FloatSlice::F32(values), FloatSlice::F32(grad)) => {
+            let vjp = py
+                .allow_threads(|| muskingum_vjp_rs(values, batch, dt, &k, &x, sub_reaches, grad))?;
+            let narrow = |v: &[f64]| v.iter().map(|&g| g as f32).collect::<Vec<_>>();
+            Ok((
+                output(py, &vjp.inflow, &shape)?,
+                output(py, &narrow(&vjp.k), &[batch])?,
+                output(py, &narrow(&vjp.x), &[batch])?,
+            ))
+        }
+        (FloatSlice::F64(values), FloatSlice::F64(grad)) => {
+            let vjp = py
+                .allow_threads(|| muskingum_vjp_rs(values, batch, dt, &k, &x, sub_reaches, grad))?;
+            Ok((
+                output(py, &vjp.inflow, &shape)?,
+                output(py, &vjp.k, &[batch])?,
+                output(py, &vjp.x, &[batch])?,
+            ))
+        }
+        _ => Err(PyValueError::new_err(
+            "`grad_outflow` must have the dtype of `inflow`.",
+        )),
+    }
+}
+
+#[pymodule]
+pub fn init_functional(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(muskingum, m)?)?;
+    m.add_function(wrap_pyfunction!(muskingum_vjp, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,6 @@
+//! Conceptual groundwater storage: aquifers draining to streams as
+//! baseflow and exchanging water with them through the streambed.
+
+pub mod aquifer;
+#[cfg(feature = "python")]
+pub mod python;
@@ -0,0 +1,98 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+
+use super::aquifer::{simulate_aquifer_rs, Aquifer, Drainage, StreamExchange};
+use crate::arrow::FloatSeries;
+use crate::time::timedelta_to_seconds;
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (recharge, time_step, area, specific_yield, k=None, coefficient=None, exponent=1.0, bottom=0.0, initial_head=None, stream_level=None, conductance=None, stream_bed=None, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn simulate<'py>(
+    py: Python<'py>,
+    recharge: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    area: f64,
+    specific_yield: f64,
+    k: Option<Bound<'py, PyDelta>>,
+    coefficient: Option<f64>,
+    exponent: f64,
+    bottom: f64,
+    initial_head: Option<f64>,
+    stream_level: Option<FloatSeries>,
+    conductance: Option<f64>,
+    stream_bed: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let drainage = match (k, coefficient) {
+        (Some(k), None) => Drainage::Linear {
+            k: timedelta_to_seconds(&k),
+        },
+        (None, Some(a)) => Drainage::nonlinear_from_units(a, exponent, units),
+        _ => {
+            return Err(PyValueError::new_err(
+                "Give either `k` (linear) or `coefficient` (nonlinear drainage).",
+            ))
+        }
+    };
+    let exchange = match (&stream_level, conductance) {
+        (None, _) => None,
+        (Some(_), Some(c)) => Some(StreamExchange {
+            conductance: units.area_to_si(c),
+            bed: units.length_to_si(stream_bed.unwrap_or(bottom)),
+        }),
+        (Some(_), None) => {
+            return Err(PyValueError::new_err(
+                "`stream_level` requires a streambed `conductance`.",
+            ))
+        }
+    };
+    let level = stream_level.map(|s| {
+        s.0.iter()
+            .map(|&h| units.length_to_si(h))
+            .collect::<Vec<_>>()
+    });
+    if level.as_ref().is_some_and(|l| l.len() != recharge.0.len()) {
+        return Err(PyValueError::new_err(
+            "`stream_level` must have the same length as `recharge`.",
+        ));
+    }
+    let mut aquifer = Aquifer::new(
+        units.land_area_to_si(area),
+        specific_yield,
+        units.length_to_si(bottom),
+        drainage,
+        exchange,
+        initial_head.map(|h| units.length_to_si(h)),
+    )?;
+    let dt = timedelta_to_seconds(&time_step);
+    let recharge_si: Vec<f64> = recharge.0.iter().map(|&r| units.rate_to_si(r)).collect();
+    let series =
+        py.allow_threads(|| simulate_aquifer_rs(&recharge_si, level.as_deref(), dt, &mut aquifer));
+    let convert = |values: Vec<f64>, f: fn(UnitSystem, f64) -> f64| -> Vec<f64> {
+        values.into_iter().map(|v| f(units, v)).collect()
+    };
+    let result = PyDict::new(py);
+    result.set_item(
+        "baseflow",
+        convert(series.baseflow, UnitSystem::discharge_from_si),
+    )?;
+    result.set_item(
+        "exchange",
+        convert(series.exchange, UnitSystem::discharge_from_si),
+    )?;
+    result.set_item("head", convert(series.head, UnitSystem::length_from_si))?;
+    result.set_item(
+        "storage",
+        convert(series.storage, UnitSystem::volume_from_si),
+    )?;
+    Ok(result)
+}
+
+#[pymodule]
+pub fn init_groundwater(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,469 @@
+//! Backwater from a bridge crossing a prismatic channel, after the low-flow
+//! and pressure-flow methods of HEC-RAS (Hydraulic Reference Manual,
+//! chapter 5), in SI units.
+//!
+//! The bridge opening is a trapezoid between the abutments, less the width
+//! of the piers, with its low chord a fixed height above the bed. From the
+//! tailwater depth in the channel below, the energy method balances energy
+//! through the opening with expansion, friction and contraction losses; the
+//! momentum method balances momentum with the hydrostatic force on the pier
+//! faces and the pier drag. Where no subcritical depth satisfies the
+//! balance, the opening chokes and flow in it is critical. When the energy
+//! upstream reaches the low chord, the opening flows as a sluice gate, or
+//! as an orifice once the tailwater also submerges it. Flow over the deck
+//! is not modelled.
+
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::units::UnitSystem;
+
+use super::manning::TrapezoidalChannel;
+
+const GRAVITY: f64 = 9.81;
+/// Discharge coefficient of a sluice gate with only the inlet submerged.
+const SLUICE_COEFFICIENT: f64 = 0.5;
+/// Discharge coefficient of a fully submerged opening.
+const ORIFICE_COEFFICIENT: f64 = 0.8;
+
+/// Low-flow method giving the upstream depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BridgeMethod {
+    Energy,
+    Momentum,
+    /// The higher of the energy and momentum answers, the HEC-RAS default.
+    Highest,
+}
+
+impl BridgeMethod {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "energy" => Ok(BridgeMethod::Energy),
+            "momentum" => Ok(BridgeMethod::Momentum),
+            "highest" => Ok(BridgeMethod::Highest),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown bridge method `{other}`; expected `energy`, `momentum` or `highest`."
+            ))),
+        }
+    }
+}
+
+/// Flow regime through the opening.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BridgeFlow {
+    /// Subcritical flow controlled by the tailwater.
+    Free,
+    /// Critical depth in the opening.
+    Choked,
+    /// Sluice-gate flow under the low chord.
+    Sluice,
+    /// Orifice flow with the opening submerged on both sides.
+    Orifice,
+}
+
+impl BridgeFlow {
+    pub fn name(self) -> &'static str {
+        match self {
+            BridgeFlow::Free => "free",
+            BridgeFlow::Choked => "choked",
+            BridgeFlow::Sluice => "sluice",
+            BridgeFlow::Orifice => "orifice",
+        }
+    }
+}
+
+/// Bridge opening across a channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bridge {
+    /// Channel upstream and downstream of the bridge; its roughness also
+    /// applies in the opening.
+    pub channel: TrapezoidalChannel,
+    /// Bottom width (m) between the abutments.
+    pub opening_width: f64,
+    /// Abutment slope, horizontal per unit rise.
+    pub abutment_slope: f64,
+    /// Total width (m) of the piers in the opening.
+    pub pier_width: f64,
+    /// Pier drag coefficient: 1.2 circular, 1.33 semicircular, 2.0 square.
+    pub pier_drag: f64,
+    /// Height (m) of the low chord above the bed.
+    pub low_chord: f64,
+    /// Length (m) of the opening along the flow.
+    pub length: f64,
+    pub contraction: f64,
+    pub expansion: f64,
+    pub method: BridgeMethod,
+}
+
+/// Depths (m) through the bridge at one discharge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BridgeProfile {
+    /// Depth above the bed at the upstream face.
+    pub upstream: f64,
+    /// Depth in the opening.
+    pub opening: f64,
+    /// Tailwater depth.
+    pub downstream: f64,
+    /// Rise of the upstream water surface over the unobstructed channel.
+    pub backwater: f64,
+    pub flow: BridgeFlow,
+}
+
+/// Depth above `lower` at which `f`, increasing there, reaches `target`;
+/// `None` when it already exceeds `target` at `lower`.
+fn depth_reaching(f: impl Fn(f64) -> f64, target: f64, lower: f64) -> Option<f64> {
+    if f(lower) > target {
+        return None;
+    }
+    let mut high = (2.0 * lower).max(1.0);
+    for _ in 0..100 {
+        if f(high) >= target {
+            break;
+        }
+        high *= 2.0;
+    }
+    let mut low = lower;
+    for _ in 0..80 {
+        let mid = 0.5 * (low + high);
+        if f(mid) < target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(0.5 * (low + high))
+}
+
+impl Bridge {
+    /// Checks the opening against the channel it crosses.
+    pub fn validate(&self) -> Result<()> {
+        let positive = |v: f64| v.is_finite() && v > 0.0;
+        let non_negative = |v: f64| v.is_finite() && v >= 0.0;
+        let valid = positive(self.opening_width)
+            && positive(self.low_chord)
+            && positive(self.length)
+            && non_negative(self.abutment_slope)
+            && non_negative(self.pier_width)
+            && self.pier_width < self.opening_width;
+        if !valid {
+            return Err(Error::InvalidParameter(
+                "The bridge opening width, low chord and length must be positive, with piers \
+                 narrower than the opening."
+                    .into(),
+            ));
+        }
+        if ![self.pier_drag, self.contraction, self.expansion]
+            .into_iter()
+            .all(non_negative)
+        {
+            return Err(Error::InvalidParameter(
+                "Pier drag, contraction and expansion coefficients must not be negative.".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Builds a bridge from a configuration table with the channel's
+    /// `bottom_width`, `side_slope`, `n` and `slope`, and the bridge's
+    /// `opening_width`, `low_chord`, `length`, optional `abutment_slope`,
+    /// `pier_width`, `pier_drag`, `contraction`, `expansion` and `method`.
+    pub fn from_value(value: &Value, units: UnitSystem) -> Result<Self> {
+        let length = |key: &str| value.f64_field(key).map(|v| units.length_to_si(v));
+        let optional =
+            |key: &str, default: f64| value.opt_f64_field(key).map(|v| v.unwrap_or(default));
+        let bridge = Bridge {
+            channel: TrapezoidalChannel::from_units(
+                value.f64_field("bottom_width")?,
+                optional("side_slope", 0.0)?,
+                value.f64_field("n")?,
+                value.f64_field("slope")?,
+                units,
+            )?,
+            opening_width: length("opening_width")?,
+            abutment_slope: optional("abutment_slope", 0.0)?,
+            pier_width: units.length_to_si(optional("pier_width", 0.0)?),
+            pier_drag: optional("pier_drag", 2.0)?,
+            low_chord: length("low_chord")?,
+            length: length("length")?,
+            contraction: optional("contraction", 0.3)?,
+            expansion: optional("expansion", 0.5)?,
+            method: BridgeMethod::parse(value.opt_str_field("method")?.unwrap_or("highest"))?,
+        };
+        bridge.validate()?;
+        Ok(bridge)
+    }
+
+    /// Flow area (m²) of the opening at `depth`, net of the piers and
+    /// limited by the low chord.
+    pub fn opening_area(&self, depth: f64) -> f64 {
+        let y = depth.min(self.low_chord);
+        (self.opening_width - self.pier_width + self.abutment_slope * y) * y
+    }
+
+    /// First moment of the opening area about the water surface (m³).
+    fn opening_moment(&self, depth: f64) -> f64 {
+        (self.opening_width - self.pier_width) * depth * depth / 2.0
+            + self.abutment_slope * depth.powi(3) / 3.0
+    }
+
+    fn opening_friction_slope(&self, discharge: f64, depth: f64) -> f64 {
+        let y = depth.min(self.low_chord);
+        let area = self.opening_area(y);
+        let perimeter = self.opening_width
+            + 2.0 * y * (1.0 + self.abutment_slope * self.abutment_slope).sqrt()
+            + 2.0 * y * (self.pier_width > 0.0) as u8 as f64;
+        let radius = area / perimeter;
+        (discharge * self.channel.manning_n / (area * radius.powf(2.0 / 3.0))).powi(2)
+    }
+
+    /// Critical depth (m) of `discharge` in the opening, ignoring the low
+    /// chord.
+    fn opening_critical_depth(&self, discharge: f64) -> f64 {
+        let width = |y: f64| self.opening_width - self.pier_width + 2.0 * self.abutment_slope * y;
+        let area = |y: f64| (self.opening_width - self.pier_width + self.abutment_slope * y) * y;
+        let froude = |y: f64| area(y).powi(3) / width(y);
+        depth_reaching(froude, discharge * discharge / GRAVITY, 0.0).unwrap_or(0.0)
+    }
+
+    fn channel_critical_depth(&self, discharge: f64) -> f64 {
+        let c = &self.channel;
+        let froude = |y: f64| c.area(y).powi(3) / c.top_width(y);
+        depth_reaching(froude, discharge * discharge / GRAVITY, 0.0).unwrap_or(0.0)
+    }
+
+    fn channel_momentum(&self, discharge: f64, depth: f64) -> f64 {
+        let c = &self.channel;
+        let moment = c.bottom_width * depth * depth / 2.0 + c.side_slope * depth.powi(3) / 3.0;
+        moment + discharge * discharge / (GRAVITY * c.area(depth))
+    }
+
+    /// Upstream depth above `critical` whose velocity head, plus `loss`
+    /// times it, balances `energy`; critical depth when none does.
+    fn upstream_energy_depth(&self, discharge: f64, energy: f64, loss: f64) -> f64 {
+        let c = &self.channel;
+        let critical = self.channel_critical_depth(discharge);
+        let head = |y: f64| y + (1.0 + loss) * (discharge / c.area(y)).powi(2) / (2.0 * GRAVITY);
+        depth_reaching(head, energy, critical).unwrap_or(critical)
+    }
+
+    /// Upstream and opening depths of the energy method.
+    fn energy(&self, discharge: f64, tailwater: f64) -> (f64, f64, BridgeFlow) {
+        let q = discharge;
+        let velocity_head = |area: f64| (q / area).powi(2) / (2.0 * GRAVITY);
+        let tail_head = velocity_head(self.channel.area(tailwater));
+        let drop = self.channel.bed_slope * self.length;
+        let critical = self.opening_critical_depth(q);
+        // Expansion and friction losses from the opening to the section
+        // below.
+        let losses = |y: f64| {
+            let head = velocity_head(self.opening_area(y));
+            self.expansion * (head - tail_head).abs()
+                + self.opening_friction_slope(q, y) * self.length
+        };
+        // Energy left at the section below from depth `y` in the opening.
+        let below = |y: f64| drop + y + velocity_head(self.opening_area(y)) - losses(y);
+        let target = tailwater + tail_head;
+        let (opening, flow) = match depth_reaching(below, target, critical) {
+            Some(y) => (y, BridgeFlow::Free),
+            None => (critical, BridgeFlow::Choked),
+        };
+        let head = velocity_head(self.opening_area(opening));
+        let energy = opening + (1.0 + self.contraction) * head;
+        let upstream = self.upstream_energy_depth(q, energy, self.contraction);
+        (upstream.max(opening), opening, flow)
+    }
+
+    /// Upstream and opening depths of the momentum method.
+    fn momentum(&self, discharge: f64, tailwater: f64) -> (f64, f64, BridgeFlow) {
+        let q = discharge;
+        let c = &self.channel;
+        let static_moment = |y: f64| c.bottom_width * y * y / 2.0 + c.side_slope * y.powi(3) / 3.0;
+        let opening_momentum =
+            |y: f64| self.opening_moment(y) + q * q / (GRAVITY * self.opening_area(y));
+        let critical = self.opening_critical_depth(q);
+        // Downstream face: the abutments and piers bear the pressure of the
+        // water in the opening over the rest of the channel width.
+        let expansion = |y: f64| static_moment(y) + q * q / (GRAVITY * self.opening_area(y));
+        let target = self.channel_momentum(q, tailwater);
+        let (opening, flow) = match depth_reaching(expansion, target, critical) {
+            Some(y) => (y, BridgeFlow::Free),
+            None => (critical, BridgeFlow::Choked),
+        };
+        // Upstream face: the abutments and piers take the upstream pressure
+        // over the blocked width, and the piers add their drag.
+        let upstream_momentum = |y: f64| {
+            self.opening_moment(y) + q * q / (GRAVITY * c.area(y))
+                - 0.5 * self.pier_drag * self.pier_width * y * q * q / (GRAVITY * c.area(y).powi(2))
+        };
+        let target = opening_momentum(opening);
+        let lower = self.channel_critical_depth(q).max(opening);
+        let upstream = depth_reaching(upstream_momentum, target, lower).unwrap_or(lower);
+        (upstream, opening, flow)
+    }
+
+    /// Upstream depth of pressure flow, when the opening runs full.
+    fn pressure(&self, discharge: f64, tailwater: f64) -> (f64, BridgeFlow) {
+        let q = discharge;
+        let c = &self.channel;
+        let area = self.opening_area(self.low_chord);
+        let critical = self.channel_critical_depth(q);
+        let velocity_head = |y: f64| (q / c.area(y)).powi(2) / (2.0 * GRAVITY);
+        // Energy head across the opening, above a datum that differs
+        // between the two regimes.
+        let (offset, coefficient, flow) = if tailwater >= self.low_chord {
+            (
+                self.channel.bed_slope * self.length - tailwater,
+                ORIFICE_COEFFICIENT,
+                BridgeFlow::Orifice,
+            )
+        } else {
+            (
+                -self.low_chord / 2.0,
+                SLUICE_COEFFICIENT,
+                BridgeFlow::Sluice,
+            )
+        };
+        let energy = |y: f64| y + velocity_head(y) + offset;
+        let target = (q / (coefficient * area)).powi(2) / (2.0 * GRAVITY);
+        let upstream = depth_reaching(energy, target, critical).unwrap_or(critical);
+        (upstream, flow)
+    }
+
+    /// Depths through the bridge at `discharge` (m³/s) with `tailwater`
+    /// depth (m), or normal depth in the channel when `None`.
+    pub fn profile(&self, discharge: f64, tailwater: Option<f64>) -> BridgeProfile {
+        let c = &self.channel;
+        let q = discharge.max(0.0);
+        let downstream = tailwater.unwrap_or_else(|| c.normal_depth(q)).max(0.0);
+        if q == 0.0 {
+            return BridgeProfile {
+                upstream: downstream,
+                opening: downstream,
+                downstream,
+                backwater: 0.0,
+                flow: BridgeFlow::Free,
+            };
+        }
+        let (mut upstream, mut opening, mut flow) = match self.method {
+            BridgeMethod::Energy => self.energy(q, downstream),
+            BridgeMethod::Momentum => self.momentum(q, downstream),
+            BridgeMethod::Highest => {
+                let energy = self.energy(q, downstream);
+                let momentum = self.momentum(q, downstream);
+                if momentum.0 > energy.0 {
+                    momentum
+                } else {
+                    energy
+                }
+            }
+        };
+        let energy = upstream + (q / c.area(upstream)).powi(2) / (2.0 * GRAVITY);
+        if energy >= self.low_chord || opening >= self.low_chord {
+            let (depth, regime) = self.pressure(q, downstream);
+            upstream = depth;
+            opening = self.low_chord;
+            flow = regime;
+        }
+        // Unobstructed channel: the tailwater raised by friction over the
+        // length of the opening, less the bed drop.
+        let radius = c.hydraulic_radius(downstream);
+        let friction = (q * c.manning_n / (c.area(downstream) * radius.powf(2.0 / 3.0))).powi(2);
+        let unobstructed = downstream + (friction - c.bed_slope) * self.length;
+        BridgeProfile {
+            upstream,
+            opening,
+            downstream,
+            backwater: (upstream - unobstructed).max(0.0),
+            flow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::toml;
+
+    fn bridge(opening_width: f64, pier_width: f64, method: BridgeMethod) -> Bridge {
+        Bridge {
+            channel: TrapezoidalChannel {
+                bottom_width: 20.0,
+                side_slope: 0.0,
+                manning_n: 0.03,
+                bed_slope: 0.001,
+            },
+            opening_width,
+            abutment_slope: 0.0,
+            pier_width,
+            pier_drag: 2.0,
+            low_chord: 5.0,
+            length: 10.0,
+            contraction: 0.3,
+            expansion: 0.5,
+            method,
+        }
+    }
+
+    #[test]
+    fn energy_method_matches_a_hand_balance() {
+        // 40 m³/s through a 12 m opening with 1 m of piers, tailwater
+        // 2.5 m. Solving the energy balance below the opening and the
+        // contraction loss above it by hand gives these depths.
+        let profile = bridge(12.0, 1.0, BridgeMethod::Energy).profile(40.0, Some(2.5));
+        assert_eq!(profile.flow, BridgeFlow::Free);
+        assert!((profile.opening - 2.465_563_020_623_829).abs() < 1e-9);
+        assert!((profile.upstream - 2.569_549_276_865_049).abs() < 1e-9);
+        assert!((profile.backwater - 0.077_263_419_350_215).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unobstructed_openings_cause_no_afflux() {
+        for method in [
+            BridgeMethod::Energy,
+            BridgeMethod::Momentum,
+            BridgeMethod::Highest,
+        ] {
+            let bridge = bridge(20.0, 0.0, method);
+            for q in [5.0, 40.0, 80.0] {
+                let profile = bridge.profile(q, None);
+                assert_eq!(profile.flow, BridgeFlow::Free);
+                assert!(profile.backwater < 1e-9, "{method:?} {q}: {profile:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn narrower_openings_raise_the_backwater() {
+        let mut last = 0.0;
+        for width in [18.0, 14.0, 10.0] {
+            let profile = bridge(width, 1.0, BridgeMethod::Highest).profile(40.0, None);
+            assert!(profile.backwater > last, "{width}: {profile:?}");
+            last = profile.backwater;
+        }
+        let choked = bridge(7.0, 1.0, BridgeMethod::Energy).profile(40.0, None);
+        assert_eq!(choked.flow, BridgeFlow::Choked);
+        let pressure = bridge(12.0, 1.0, BridgeMethod::Energy).profile(40.0, Some(5.5));
+        assert_eq!(pressure.flow, BridgeFlow::Orifice);
+        assert_eq!(pressure.opening, 5.0);
+    }
+
+    #[test]
+    fn rejects_invalid_bridges() {
+        let text = "bottom_width = 20.0\nn = 0.03\nslope = 0.001\nopening_width = 12.0\n\
+                    low_chord = 5.0\nlength = 10.0\n";
+        let value = toml::parse(text).unwrap();
+        let bridge = Bridge::from_value(&value, UnitSystem::Si).unwrap();
+        assert_eq!(bridge.method, BridgeMethod::Highest);
+        for (from, to) in [
+            ("low_chord = 5.0", "low_chord = 0.0"),
+            ("length = 10.0", "length = 10.0\npier_width = 12.0"),
+            ("length = 10.0", "length = 10.0\nexpansion = -0.5"),
+            ("length = 10.0", "length = 10.0\nmethod = \"yarnell\""),
+        ] {
+            let value = toml::parse(&text.replace(from, to)).unwrap();
+            match Bridge::from_value(&value, UnitSystem::Si) {
+                Err(Error::InvalidParameter(_)) => {}
+                other => panic!("{to}: {other:?}"),
+            }
+        }
+    }
+}
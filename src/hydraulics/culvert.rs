@@ -0,0 +1,582 @@
+//! Culvert hydraulics after FHWA HDS-5 (Schall et al., 2012), in SI units.
+//!
+//! The headwater needed to pass a discharge is the larger of the inlet- and
+//! outlet-control headwaters. Inlet control uses the HDS-5 nomograph
+//! equations: unsubmerged (form 1 or 2) below `Ku Q / (A D^0.5) = 3.5`,
+//! submerged above 4.0, and a linear transition between. Outlet control is
+//! the full-barrel energy balance `HW = ho + (1 + ke + kf) V²/2g − L S`,
+//! with `ho` the larger of the tailwater and `(dc + D) / 2`, or of the
+//! tailwater and `dc` when that gives less than 0.75 D. Headwaters
+//! are depths above the inlet invert and tailwaters depths above the
+//! outlet invert.
+
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::units::UnitSystem;
+
+const GRAVITY: f64 = 9.81;
+/// Unit conversion factor `Ku` of the HDS-5 inlet equations in SI units.
+const KU: f64 = 1.811;
+
+/// Barrel cross-section.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarrelShape {
+    /// Diameter (m).
+    Circular { diameter: f64 },
+    /// Span and rise (m).
+    Box { span: f64, rise: f64 },
+}
+
+impl BarrelShape {
+    pub fn rise(&self) -> f64 {
+        match *self {
+            BarrelShape::Circular { diameter } => diameter,
+            BarrelShape::Box { rise, .. } => rise,
+        }
+    }
+
+    /// Flow area (m²) at `depth`.
+    pub fn area(&self, depth: f64) -> f64 {
+        let depth = depth.clamp(0.0, self.rise());
+        match *self {
+            BarrelShape::Circular { diameter } => {
+                let theta = 2.0 * (1.0 - 2.0 * depth / diameter).acos();
+                diameter * diameter / 8.0 * (theta - theta.sin())
+            }
+            BarrelShape::Box { span, .. } => span * depth,
+        }
+    }
+
+    /// Water surface width (m) at `depth`.
+    pub fn top_width(&self, depth: f64) -> f64 {
+        match *self {
+            BarrelShape::Circular { diameter } => {
+                let depth = depth.clamp(0.0, diameter);
+                2.0 * (depth * (diameter - depth)).sqrt()
+            }
+            BarrelShape::Box { span, .. } => span,
+        }
+    }
+
+    /// Hydraulic radius (m) of the full barrel.
+    pub fn full_radius(&self) -> f64 {
+        match *self {
+            BarrelShape::Circular { diameter } => diameter / 4.0,
+            BarrelShape::Box { span, rise } => span * rise / (2.0 * (span + rise)),
+        }
+    }
+
+    /// Critical depth (m) of `discharge` (m³/s), at most the rise.
+    pub fn critical_depth(&self, discharge: f64) -> f64 {
+        if discharge <= 0.0 {
+            return 0.0;
+        }
+        let rise = self.rise();
+        if let BarrelShape::Box { span, .. } = *self {
+            return ((discharge / span).powi(2) / GRAVITY).cbrt().min(rise);
+        }
+        // Q²/g = A³/T, increasing with depth; bisect below the crown, where
+        // the top width vanishes.
+        let target = discharge * discharge / GRAVITY;
+        let section = |y: f64| self.area(y).powi(3) / self.top_width(y);
+        let (mut low, mut high) = (0.0, 0.999 * rise);
+        if section(high) <= target {
+            return rise;
+        }
+        for _ in 0..60 {
+            let mid = 0.5 * (low + high);
+            if section(mid) < target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        0.5 * (low + high)
+    }
+}
+
+/// Form of the unsubmerged inlet-control equation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InletForm {
+    /// `HW/D = Hc/D + K (Ku Q / (A D^0.5))^M + Ks S`.
+    One,
+    /// `HW/D = K (Ku Q / (A D^0.5))^M`.
+    Two,
+}
+
+/// Inlet-control coefficients of HDS-5 Table A.1 and the entrance loss
+/// coefficient of Table C.2.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InletCoefficients {
+    pub form: InletForm,
+    pub k: f64,
+    pub m: f64,
+    pub c: f64,
+    pub y: f64,
+    /// Slope correction: −0.5, or +0.7 for mitered inlets.
+    pub ks: f64,
+    /// Entrance loss coefficient `ke` of outlet control.
+    pub entrance_loss: f64,
+}
+
+impl InletCoefficients {
+    /// Coefficients of a named inlet:
+    ///
+    /// - `"circular_concrete_square_headwall"`: concrete pipe, square edge
+    ///   with headwall;
+    /// - `"circular_concrete_groove_headwall"`: concrete pipe, groove end
+    ///   with headwall;
+    /// - `"circular_concrete_groove_projecting"`: concrete pipe, groove end
+    ///   projecting;
+    /// - `"circular_cmp_headwall"`: corrugated metal pipe with headwall;
+    /// - `"circular_cmp_mitered"`: corrugated metal pipe mitered to the
+    ///   slope;
+    /// - `"circular_cmp_projecting"`: corrugated metal pipe projecting;
+    /// - `"box_wingwall_30_75"`: concrete box, 30°–75° wingwall flares;
+    /// - `"box_wingwall_90_15"`: concrete box, 90° or 15° wingwall flares;
+    /// - `"box_wingwall_0"`: concrete box, 0° wingwall flares (parallel
+    ///   extensions).
+    pub fn named(name: &str) -> Result<Self> {
+        let (k, m, c, y, ks, entrance_loss) = match name {
+            "circular_concrete_square_headwall" => (0.0098, 2.0, 0.0398, 0.67, -0.5, 0.5),
+            "circular_concrete_groove_headwall" => (0.0018, 2.0, 0.0292, 0.74, -0.5, 0.2),
+            "circular_concrete_groove_projecting" => (0.0045, 2.0, 0.0317, 0.69, -0.5, 0.2),
+            "circular_cmp_headwall" => (0.0078, 2.0, 0.0379, 0.69, -0.5, 0.5),
+            "circular_cmp_mitered" => (0.0210, 1.33, 0.0463, 0.75, 0.7, 0.7),
+            "circular_cmp_projecting" => (0.0340, 1.5, 0.0553, 0.54, -0.5, 0.9),
+            "box_wingwall_30_75" => (0.026, 1.0, 0.0347, 0.81, -0.5, 0.4),
+            "box_wingwall_90_15" => (0.061, 0.75, 0.0400, 0.80, -0.5, 0.5),
+            "box_wingwall_0" => (0.061, 0.75, 0.0423, 0.82, -0.5, 0.7),
+            other => {
+                return Err(Error::InvalidParameter(format!(
+                    "Unknown culvert inlet `{other}`."
+                )))
+            }
+        };
+        Ok(InletCoefficients {
+            form: InletForm::One,
+            k,
+            m,
+            c,
+            y,
+            ks,
+            entrance_loss,
+        })
+    }
+}
+
+/// Which control sets the headwater.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    Inlet,
+    Outlet,
+}
+
+impl Control {
+    pub fn name(self) -> &'static str {
+        match self {
+            Control::Inlet => "inlet",
+            Control::Outlet => "outlet",
+        }
+    }
+}
+
+/// Headwater (m above the inlet invert) of one discharge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Headwater {
+    pub inlet: f64,
+    pub outlet: f64,
+    pub headwater: f64,
+    pub control: Control,
+    /// Barrel velocity (m/s) of the full barrel.
+    pub velocity: f64,
+}
+
+/// One or more identical barrels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Culvert {
+    pub shape: BarrelShape,
+    pub inlet: InletCoefficients,
+    pub manning_n: f64,
+    /// Barrel length (m).
+    pub length: f64,
+    /// Barrel slope (m/m).
+    pub slope: f64,
+    pub barrels: usize,
+}
+
+impl Culvert {
+    pub fn new(
+        shape: BarrelShape,
+        inlet: InletCoefficients,
+        manning_n: f64,
+        length: f64,
+        slope: f64,
+        barrels: usize,
+    ) -> Result<Self> {
+        let sizes = match shape {
+            BarrelShape::Circular { diameter } => diameter > 0.0,
+            BarrelShape::Box { span, rise } => span > 0.0 && rise > 0.0,
+        };
+        if !(sizes && manning_n > 0.0 && length > 0.0 && slope.is_finite() && barrels > 0) {
+            return Err(Error::InvalidParameter(
+                "Culvert dimensions, roughness, length and number of barrels must be positive."
+                    .into(),
+            ));
+        }
+        Ok(Culvert {
+            shape,
+            inlet,
+            manning_n,
+            length,
+            slope,
+            barrels,
+        })
+    }
+
+    /// Builds a culvert from a configuration table with `shape`
+    /// (`"circular"` with `diameter`, or `"box"` with `span` and `rise`),
+    /// `inlet` (see [`InletCoefficients::named`]), optional `entrance_loss`
+    /// overriding the inlet's, `n`, `length`, `slope`, optional `barrels`
+    /// and `units`.
+    pub fn from_value(value: &Value, units: UnitSystem) -> Result<Self> {
+        let length = |key: &str| value.f64_field(key).map(|v| units.length_to_si(v));
+        let shape = match value.str_field("shape")? {
+            "circular" => BarrelShape::Circular {
+                diameter: length("diameter")?,
+            },
+            "box" => BarrelShape::Box {
+                span: length("span")?,
+                rise: length("rise")?,
+            },
+            other => {
+                return Err(Error::Data(format!(
+                    "Unknown culvert shape `{other}`; expected `circular` or `box`."
+                )))
+            }
+        };
+        let mut inlet = InletCoefficients::named(value.str_field("inlet")?)?;
+        if let Some(ke) = value.opt_f64_field("entrance_loss")? {
+            inlet.entrance_loss = ke;
+        }
+        Culvert::new(
+            shape,
+            inlet,
+            value.f64_field("n")?,
+            length("length")?,
+            value.opt_f64_field("slope")?.unwrap_or(0.0),
+            value.opt_f64_field("barrels")?.unwrap_or(1.0) as usize,
+        )
+    }
+
+    /// Inlet-control headwater of `discharge` (m³/s) through all barrels.
+    pub fn inlet_headwater(&self, discharge: f64) -> f64 {
+        if discharge <= 0.0 {
+            return 0.0;
+        }
+        let q = discharge / self.barrels as f64;
+        let d = self.shape.rise();
+        let area = self.shape.area(d);
+        let x = KU * q / (area * d.sqrt());
+        let c = &self.inlet;
+        let unsubmerged = |x: f64| match c.form {
+            InletForm::One => {
+                let dc = self.shape.critical_depth(q);
+                let hc = dc + (q / self.shape.area(dc)).powi(2) / (2.0 * GRAVITY);
+                hc / d + c.k * x.powf(c.m) + c.ks * self.slope
+            }
+            InletForm::Two => c.k * x.powf(c.m),
+        };
+        let submerged = |x: f64| c.c * x * x + c.y + c.ks * self.slope;
+        let ratio = if x <= 3.5 {
+            unsubmerged(x)
+        } else if x >= 4.0 {
+            submerged(x)
+        } else {
+            let w = (x - 3.5) / 0.5;
+            (1.0 - w) * unsubmerged(x) + w * submerged(x)
+        };
+        ratio * d
+    }
+
+    /// Outlet-control headwater of `discharge` (m³/s) with `tailwater` (m
+    /// above the outlet invert).
+    pub fn outlet_headwater(&self, discharge: f64, tailwater: f64) -> f64 {
+        let q = discharge.max(0.0) / self.barrels as f64;
+        let d = self.shape.rise();
+        let area = self.shape.area(d);
+        let velocity = q / area;
+        let friction = 2.0 * GRAVITY * self.manning_n.powi(2) * self.length
+            / self.shape.full_radius().powf(4.0 / 3.0);
+        let losses =
+            (1.0 + self.inlet.entrance_loss + friction) * velocity * velocity / (2.0 * GRAVITY);
+        let dc = self.shape.critical_depth(q);
+        let fall = self.length * self.slope;
+        let full = tailwater.max(0.5 * (dc + d)) + losses - fall;
+        if tailwater >= d || full >= 0.75 * d {
+            full
+        } else {
+            // Part-full barrel, for which HDS-5 limits the `(dc + D) / 2`
+            // approximation to headwaters of 0.75 D or more.
+            tailwater.max(dc) + losses - fall
+        }
+    }
+
+    /// Controlling headwater of `discharge` (m³/s) with `tailwater`.
+    pub fn headwater(&self, discharge: f64, tailwater: f64) -> Headwater {
+        let inlet = self.inlet_headwater(discharge);
+        let outlet = if discharge > 0.0 {
+            self.outlet_headwater(discharge, tailwater)
+        } else {
+            (tailwater - self.length * self.slope).max(0.0)
+        };
+        let (headwater, control) = if outlet > inlet {
+            (outlet, Control::Outlet)
+        } else {
+            (inlet, Control::Inlet)
+        };
+        Headwater {
+            inlet,
+            outlet,
+            headwater,
+            control,
+            velocity: discharge.max(0.0)
+                / (self.barrels as f64 * self.shape.area(self.shape.rise())),
+        }
+    }
+
+    /// Discharge (m³/s) passed at `headwater` (m above the inlet invert)
+    /// with `tailwater`, inverting [`Culvert::headwater`] by bisection.
+    pub fn discharge(&self, headwater: f64, tailwater: f64) -> f64 {
+        let hw = |q: f64| self.headwater(q, tailwater).headwater;
+        if headwater.is_nan() || headwater <= hw(0.0) {
+            return 0.0;
+        }
+        if headwater == f64::INFINITY {
+            return f64::INFINITY;
+        }
+        let mut high = self.barrels as f64 * self.shape.area(self.shape.rise());
+        while hw(high) < headwater {
+            high *= 2.0;
+        }
+        let mut low = 0.0;
+        for _ in 0..60 {
+            let mid = 0.5 * (low + high);
+            if hw(mid) < headwater {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        0.5 * (low + high)
+    }
+
+    /// Headwater of each of `discharges` (m³/s): the culvert performance
+    /// curve at `tailwater`.
+    pub fn performance(&self, discharges: &[f64], tailwater: f64) -> Vec<Headwater> {
+        discharges
+            .iter()
+            .map(|&q| self.headwater(q, tailwater))
+            .collect()
+    }
+
+    /// Outflow (m³/s) at each pool `elevation` (m) over an inlet at
+    /// `invert`, with `tailwater`: the outflow column of a level-pool
+    /// reservoir drained by the culvert.
+    pub fn rating(&self, elevation: &[f64], invert: f64, tailwater: f64) -> Vec<f64> {
+        elevation
+            .iter()
+            .map(|&z| self.discharge(z - invert, tailwater))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::toml;
+
+    fn pipe(diameter: f64, slope: f64) -> Culvert {
+        Culvert::new(
+            BarrelShape::Circular { diameter },
+            InletCoefficients::named("circular_concrete_square_headwall").unwrap(),
+            0.012,
+            40.0,
+            slope,
+            1,
+        )
+        .unwrap()
+    }
+
+    fn box_culvert() -> Culvert {
+        Culvert::new(
+            BarrelShape::Box {
+                span: 2.0,
+                rise: 1.5,
+            },
+            InletCoefficients::named("box_wingwall_90_15").unwrap(),
+            0.013,
+            30.0,
+            0.002,
+            1,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn critical_depths_satisfy_the_critical_flow_condition() {
+        // A half-full circular barrel is critical at Q = √(g (π D²/8)³ / D).
+        let circle = BarrelShape::Circular { diameter: 1.0 };
+        let dc = circle.critical_depth(0.770_769_165_136_538);
+        assert!((dc - 0.5).abs() < 1e-9, "{dc}");
+        for q in [0.1, 0.5, 1.5] {
+            let dc = circle.critical_depth(q);
+            let froude = q * q * circle.top_width(dc) / (GRAVITY * circle.area(dc).powi(3));
+            assert!((froude - 1.0).abs() < 1e-9, "{q}: {froude}");
+        }
+        let rect = BarrelShape::Box {
+            span: 2.0,
+            rise: 1.5,
+        };
+        assert!((rect.critical_depth(3.0) - 0.612_121_786_253_843).abs() < 1e-12);
+        // Flows too large to be critical below the crown return the rise.
+        assert_eq!(rect.critical_depth(100.0), 1.5);
+        assert_eq!(circle.critical_depth(0.0), 0.0);
+    }
+
+    #[test]
+    fn inlet_control_follows_the_hds5_equations() {
+        // Submerged: HW/D = c (Ku Q / A D^0.5)² + Y − 0.5 S. With
+        // Ku Q / (A D^0.5) = 5 through a 1 m square-edged concrete pipe on
+        // a 1 % slope, HW/D = 0.0398 × 25 + 0.67 − 0.005.
+        let culvert = pipe(1.0, 0.01);
+        let q = 5.0 * std::f64::consts::FRAC_PI_4 / KU;
+        assert!((culvert.inlet_headwater(q) - 1.66).abs() < 1e-12);
+        // Unsubmerged form 1 for a 2 m × 1.5 m box carrying 3 m³/s:
+        // HW/D = Hc/D + K (Ku Q / A D^0.5)^M − 0.5 S with Hc = 1.5 dc.
+        let hw = box_culvert().inlet_headwater(3.0);
+        assert!((hw - 1.039_377_352_991_218).abs() < 1e-12, "{hw}");
+        assert_eq!(culvert.inlet_headwater(0.0), 0.0);
+    }
+
+    #[test]
+    fn si_inlet_control_matches_the_us_charts() {
+        // The HDS-5 charts are drawn in US units, where Ku = 1. A 36 in
+        // square-edged concrete pipe passing 50 cfs reads
+        // HW/D = 0.0398 (50 / (7.0686 × √3))² + 0.67.
+        let x: f64 = 50.0 / (std::f64::consts::FRAC_PI_4 * 9.0 * 3f64.sqrt());
+        let chart = 0.0398 * x * x + 0.67;
+        let d = 36.0 * 0.0254;
+        let hw = pipe(d, 0.0).inlet_headwater(50.0 * 0.3048f64.powi(3));
+        assert!(
+            (hw / d - chart).abs() < 1e-3 * chart,
+            "{} != {chart}",
+            hw / d
+        );
+    }
+
+    #[test]
+    fn outlet_control_balances_energy_over_the_full_barrel() {
+        // HW = TW + (1 + ke + kf) V²/2g − L S with kf = 2 g n² L / R^(4/3),
+        // for 3 m³/s through a 1.2 m pipe against a 1.5 m tailwater.
+        let culvert = pipe(1.2, 0.002);
+        let hw = culvert.outlet_headwater(3.0, 1.5);
+        assert!((hw - 2.159_740_260_812_087).abs() < 1e-12, "{hw}");
+        let result = culvert.headwater(3.0, 1.5);
+        assert_eq!(result.control, Control::Outlet);
+        assert_eq!(result.headwater, hw);
+        assert!((result.velocity - 2.652_582_384_864_922).abs() < 1e-12);
+        // With a free outfall, a small flow is under inlet control.
+        let free = culvert.headwater(0.3, 0.0);
+        assert_eq!(free.control, Control::Inlet);
+        assert!(free.outlet < free.inlet);
+    }
+
+    #[test]
+    fn discharge_inverts_the_headwater() {
+        for culvert in [pipe(1.2, 0.002), box_culvert()] {
+            for tailwater in [0.0, 0.8, 2.0] {
+                let mut last = 0.0;
+                for i in 1..=40 {
+                    let q = 0.25 * i as f64;
+                    let hw = culvert.headwater(q, tailwater).headwater;
+                    assert!(hw > last, "{q}: the performance curve must rise");
+                    last = hw;
+                    let back = culvert.discharge(hw, tailwater);
+                    assert!((back - q).abs() < 1e-9 * q, "{q} → {hw} → {back}");
+                }
+            }
+        }
+        let culvert = pipe(1.2, 0.002);
+        assert_eq!(culvert.discharge(0.0, 0.0), 0.0);
+        assert_eq!(culvert.discharge(f64::NAN, 0.0), 0.0);
+        // A tailwater above the headwater passes nothing.
+        assert_eq!(culvert.discharge(1.0, 1.5), 0.0);
+        let rating = culvert.rating(&[10.0, 11.0, 12.0], 10.0, 0.0);
+        assert_eq!(rating[0], 0.0);
+        assert!(rating[2] > rating[1] && rating[1] > 0.0);
+    }
+
+    #[test]
+    fn barrels_share_the_flow() {
+        let single = pipe(1.2, 0.002);
+        let twin = Culvert {
+            barrels: 2,
+            ..single.clone()
+        };
+        assert_eq!(
+            twin.headwater(6.0, 0.5),
+            Headwater {
+                velocity: single.headwater(3.0, 0.5).velocity,
+                ..single.headwater(3.0, 0.5)
+            }
+        );
+    }
+
+    #[test]
+    fn reads_culverts_in_the_given_units() {
+        let value = toml::parse(
+            "shape = \"box\"\nspan = 10.0\nrise = 5.0\ninlet = \"box_wingwall_0\"\n\
+             entrance_loss = 0.3\nn = 0.013\nlength = 100.0\nbarrels = 2",
+        )
+        .unwrap();
+        let culvert = Culvert::from_value(&value, UnitSystem::Us).unwrap();
+        match culvert.shape {
+            BarrelShape::Box { span, rise } => {
+                assert!((span - 3.048).abs() < 1e-12 && (rise - 1.524).abs() < 1e-12)
+            }
+            other => panic!("{other:?}"),
+        }
+        assert_eq!(culvert.inlet.entrance_loss, 0.3);
+        assert_eq!((culvert.barrels, culvert.slope), (2, 0.0));
+        assert!((culvert.length - 30.48).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rejects_invalid_culverts() {
+        assert!(matches!(
+            InletCoefficients::named("circular_clay"),
+            Err(Error::InvalidParameter(msg)) if msg.contains("`circular_clay`")
+        ));
+        let inlet = InletCoefficients::named("circular_cmp_mitered").unwrap();
+        let circle = BarrelShape::Circular { diameter: 1.0 };
+        assert!(Culvert::new(
+            BarrelShape::Circular { diameter: 0.0 },
+            inlet,
+            0.024,
+            10.0,
+            0.0,
+            1
+        )
+        .is_err());
+        assert!(Culvert::new(circle, inlet, 0.024, 10.0, 0.0, 0).is_err());
+        assert!(Culvert::new(circle, inlet, 0.024, 10.0, f64::NAN, 1).is_err());
+        let oval =
+            toml::parse("shape = \"oval\"\ninlet = \"box_wingwall_0\"\nn = 0.013\nlength = 1.0")
+                .unwrap();
+        assert!(matches!(
+            Culvert::from_value(&oval, UnitSystem::Si),
+            Err(Error::Data(_))
+        ));
+    }
+}
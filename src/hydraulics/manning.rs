@@ -0,0 +1,128 @@
+use crate::error::{Error, Result};
+use crate::units::UnitSystem;
+
+/// Prismatic trapezoidal channel described in SI units.
+///
+/// A rectangular channel has `side_slope == 0` and a triangular channel has
+/// `bottom_width == 0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrapezoidalChannel {
+    /// Bottom width (m).
+    pub bottom_width: f64,
+    /// Horizontal run per unit vertical rise of each bank (z in zH:1V).
+    pub side_slope: f64,
+    /// Manning's roughness coefficient.
+    pub manning_n: f64,
+    /// Longitudinal bed slope (m/m).
+    pub bed_slope: f64,
+}
+
+impl TrapezoidalChannel {
+    /// Builds a channel in SI units from a bottom width expressed in `units`,
+    /// rejecting non-physical geometry and roughness.
+    pub fn from_units(
+        bottom_width: f64,
+        side_slope: f64,
+        manning_n: f64,
+        bed_slope: f64,
+        units: UnitSystem,
+    ) -> Result<Self> {
+        if bottom_width < 0.0 || side_slope < 0.0 || (bottom_width == 0.0 && side_slope == 0.0) {
+            return Err(Error::InvalidParameter(
+                "Channel must have a positive bottom width or side slope.".into(),
+            ));
+        }
+        if manning_n <= 0.0 || bed_slope <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "Manning's `n` and `slope` must be positive.".into(),
+            ));
+        }
+        Ok(TrapezoidalChannel {
+            bottom_width: units.length_to_si(bottom_width),
+            side_slope,
+            manning_n,
+            bed_slope,
+        })
+    }
+
+    pub fn area(&self, depth: f64) -> f64 {
+        (self.bottom_width + self.side_slope * depth) * depth
+    }
+
+    pub fn wetted_perimeter(&self, depth: f64) -> f64 {
+        self.bottom_width + 2.0 * depth * (1.0 + self.side_slope * self.side_slope).sqrt()
+    }
+
+    pub fn top_width(&self, depth: f64) -> f64 {
+        self.bottom_width + 2.0 * self.side_slope * depth
+    }
+
+    pub fn hydraulic_radius(&self, depth: f64) -> f64 {
+        let perimeter = self.wetted_perimeter(depth);
+        if perimeter > 0.0 {
+            self.area(depth) / perimeter
+        } else {
+            0.0
+        }
+    }
+
+    /// Normal-flow discharge (m³/s) at the given depth (m).
+    pub fn discharge(&self, depth: f64) -> f64 {
+        if depth <= 0.0 {
+            return 0.0;
+        }
+        self.area(depth) * self.hydraulic_radius(depth).powf(2.0 / 3.0) * self.bed_slope.sqrt()
+            / self.manning_n
+    }
+
+    /// Mean velocity (m/s) at the given depth (m).
+    pub fn velocity(&self, depth: f64) -> f64 {
+        let area = self.area(depth);
+        if area > 0.0 {
+            self.discharge(depth) / area
+        } else {
+            0.0
+        }
+    }
+
+    /// Kinematic wave celerity (m/s) at the given depth (m): `dQ/dA` of the
+    /// normal-flow rating, from Manning's equation differentiated
+    /// analytically. Equals 5/3 of the velocity in a wide rectangular
+    /// channel.
+    pub fn celerity(&self, depth: f64) -> f64 {
+        if depth <= 0.0 {
+            return 0.0;
+        }
+        let area = self.area(depth);
+        let perimeter = self.wetted_perimeter(depth);
+        let perimeter_rate = 2.0 * (1.0 + self.side_slope * self.side_slope).sqrt();
+        self.velocity(depth)
+            * (5.0 / 3.0 - 2.0 / 3.0 * area * perimeter_rate / (self.top_width(depth) * perimeter))
+    }
+
+    /// Depth (m) at which normal flow carries `discharge` (m³/s), found by
+    /// bisection on the monotonic depth–discharge relation.
+    pub fn normal_depth(&self, discharge: f64) -> f64 {
+        if discharge <= 0.0 {
+            return 0.0;
+        }
+        let mut low = 0.0;
+        let mut high = 1.0;
+        while self.discharge(high) < discharge {
+            low = high;
+            high *= 2.0;
+        }
+        for _ in 0..100 {
+            let mid = 0.5 * (low + high);
+            if self.discharge(mid) < discharge {
+                low = mid;
+            } else {
+                high = mid;
+            }
+            if high - low < 1e-10 * high {
+                break;
+            }
+        }
+        0.5 * (low + high)
+    }
+}
@@ -0,0 +1,8 @@
+pub mod bridge;
+pub mod culvert;
+pub mod manning;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod rating;
+pub mod travel_time;
+pub mod weir;
@@ -0,0 +1,577 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDict};
+
+use super::bridge::{Bridge, BridgeMethod, BridgeProfile};
+use super::culvert::{BarrelShape, Culvert, Headwater, InletCoefficients};
+use super::manning::TrapezoidalChannel;
+use super::rating::{
+    rate_stage as rate_stage_rs, BaseRating, FallRating, IcePeriod, Rating, ReferenceFall, Shift,
+};
+use super::travel_time::travel_time_rs;
+use super::weir::{weir_flow as weir_flow_rs, BROAD_CRESTED};
+use crate::arrow::FloatSeries;
+use crate::time::datetime_to_epoch;
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (depth, bottom_width, side_slope, n, slope, units=UnitSystem::Si))]
+pub fn manning_discharge(
+    depth: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    units: UnitSystem,
+) -> PyResult<f64> {
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let q_si = channel.discharge(units.length_to_si(depth));
+    Ok(units.discharge_from_si(q_si))
+}
+
+#[pyfunction]
+#[pyo3(signature = (discharge, bottom_width, side_slope, n, slope, units=UnitSystem::Si))]
+pub fn normal_depth(
+    discharge: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    units: UnitSystem,
+) -> PyResult<f64> {
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let depth_si = channel.normal_depth(units.discharge_to_si(discharge));
+    Ok(units.length_from_si(depth_si))
+}
+
+#[pyfunction]
+#[pyo3(signature = (upstream, downstream, crest, length, coefficient=None, units=UnitSystem::Si))]
+pub fn weir_flow(
+    upstream: f64,
+    downstream: f64,
+    crest: f64,
+    length: f64,
+    coefficient: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<f64> {
+    let coefficient = coefficient.map_or(BROAD_CRESTED, |c| units.weir_coefficient_to_si(c));
+    if !(length > 0.0 && coefficient > 0.0) {
+        return Err(PyValueError::new_err(
+            "The weir `length` and `coefficient` must be positive.",
+        ));
+    }
+    let q = weir_flow_rs(
+        coefficient,
+        units.length_to_si(length),
+        units.length_to_si(crest),
+        units.length_to_si(upstream),
+        units.length_to_si(downstream),
+    );
+    Ok(units.discharge_from_si(q))
+}
+
+#[pyfunction]
+#[pyo3(signature = (discharges, length, bottom_width, side_slope, n, slope, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn travel_time<'py>(
+    py: Python<'py>,
+    discharges: FloatSeries,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let discharges_si: Vec<f64> = discharges
+        .0
+        .iter()
+        .map(|&q| units.discharge_to_si(q))
+        .collect();
+    let table = travel_time_rs(&channel, units.length_to_si(length), &discharges_si)?;
+    let durations = |values: &[f64]| {
+        values
+            .iter()
+            .map(|&s| {
+                let whole = s.floor();
+                PyDelta::new(
+                    py,
+                    0,
+                    whole as i32,
+                    ((s - whole) * 1e6).round() as i32,
+                    true,
+                )
+            })
+            .collect::<PyResult<Vec<_>>>()
+    };
+    let convert = |values: &[f64], f: fn(UnitSystem, f64) -> f64| {
+        values.iter().map(|&v| f(units, v)).collect::<Vec<f64>>()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("discharge", &discharges.0)?;
+    dict.set_item("depth", convert(&table.depth, UnitSystem::length_from_si))?;
+    dict.set_item(
+        "velocity",
+        convert(&table.velocity, UnitSystem::velocity_from_si),
+    )?;
+    dict.set_item(
+        "celerity",
+        convert(&table.celerity, UnitSystem::velocity_from_si),
+    )?;
+    dict.set_item("travel_time", durations(&table.travel_time)?)?;
+    dict.set_item("water_travel_time", durations(&table.water_travel_time)?)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (discharges, bottom_width, side_slope, n, slope, opening_width, low_chord, length, pier_width=0.0, pier_drag=2.0, abutment_slope=0.0, contraction=0.3, expansion=0.5, tailwater=None, method="highest", units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn bridge_backwater<'py>(
+    py: Python<'py>,
+    discharges: FloatSeries,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    opening_width: f64,
+    low_chord: f64,
+    length: f64,
+    pier_width: f64,
+    pier_drag: f64,
+    abutment_slope: f64,
+    contraction: f64,
+    expansion: f64,
+    tailwater: Option<FloatSeries>,
+    method: &str,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let bridge = Bridge {
+        channel: TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?,
+        opening_width: units.length_to_si(opening_width),
+        abutment_slope,
+        pier_width: units.length_to_si(pier_width),
+        pier_drag,
+        low_chord: units.length_to_si(low_chord),
+        length: units.length_to_si(length),
+        contraction,
+        expansion,
+        method: BridgeMethod::parse(method)?,
+    };
+    bridge.validate()?;
+    if tailwater
+        .as_ref()
+        .is_some_and(|t| t.0.len() != discharges.0.len())
+    {
+        return Err(PyValueError::new_err(
+            "`tailwater` needs one depth per discharge.",
+        ));
+    }
+    let profiles: Vec<_> = discharges
+        .0
+        .iter()
+        .enumerate()
+        .map(|(i, &q)| {
+            let tail = tailwater.as_ref().map(|t| units.length_to_si(t.0[i]));
+            bridge.profile(units.discharge_to_si(q), tail)
+        })
+        .collect();
+    let depths = |f: fn(&BridgeProfile) -> f64| -> Vec<f64> {
+        profiles
+            .iter()
+            .map(|p| units.length_from_si(f(p)))
+            .collect()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("discharge", &discharges.0)?;
+    dict.set_item("upstream_depth", depths(|p| p.upstream))?;
+    dict.set_item("opening_depth", depths(|p| p.opening))?;
+    dict.set_item("downstream_depth", depths(|p| p.downstream))?;
+    dict.set_item("backwater", depths(|p| p.backwater))?;
+    dict.set_item(
+        "flow",
+        profiles.iter().map(|p| p.flow.name()).collect::<Vec<_>>(),
+    )?;
+    Ok(dict)
+}
+
+/// A value given as one number or as `(stage, value)` pairs.
+#[derive(FromPyObject)]
+pub enum StageValue {
+    Constant(f64),
+    Table(Vec<(f64, f64)>),
+}
+
+impl StageValue {
+    fn split(self) -> (Vec<f64>, Vec<f64>) {
+        match self {
+            StageValue::Constant(v) => (Vec::new(), vec![v]),
+            StageValue::Table(pairs) => pairs.into_iter().unzip(),
+        }
+    }
+}
+
+/// Start, end and coefficient of an ice period.
+type IceInput<'py> = (Bound<'py, PyDateTime>, Bound<'py, PyDateTime>, f64);
+
+#[pyfunction]
+#[pyo3(signature = (stage, times, rating_stage=None, rating_discharge=None, coefficient=None, offset=0.0, exponent=None, shifts=None, fall=None, reference_fall=None, fall_exponent=0.5, ice=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn rate_stage<'py>(
+    py: Python<'py>,
+    stage: FloatSeries,
+    times: Vec<Bound<'py, PyDateTime>>,
+    rating_stage: Option<Vec<f64>>,
+    rating_discharge: Option<Vec<f64>>,
+    coefficient: Option<f64>,
+    offset: f64,
+    exponent: Option<f64>,
+    shifts: Option<Vec<(Bound<'py, PyDateTime>, StageValue)>>,
+    fall: Option<FloatSeries>,
+    reference_fall: Option<StageValue>,
+    fall_exponent: f64,
+    ice: Option<Vec<IceInput<'py>>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let base = match (rating_stage, rating_discharge, coefficient, exponent) {
+        (Some(stage), Some(discharge), None, None) => BaseRating::Table { stage, discharge },
+        (None, None, Some(coefficient), Some(exponent)) => BaseRating::Power {
+            coefficient,
+            offset,
+            exponent,
+        },
+        _ => {
+            return Err(PyValueError::new_err(
+                "Give either `rating_stage` and `rating_discharge`, or `coefficient` and \
+                 `exponent`.",
+            ))
+        }
+    };
+    let shifts = shifts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(time, value)| {
+            let (stage, shift) = value.split();
+            Shift {
+                time: datetime_to_epoch(&time),
+                stage,
+                shift,
+            }
+        })
+        .collect();
+    let fall_rating = reference_fall.map(|reference| FallRating {
+        reference: match reference {
+            StageValue::Constant(f) => ReferenceFall::Constant(f),
+            StageValue::Table(pairs) => {
+                let (stage, fall) = pairs.into_iter().unzip();
+                ReferenceFall::Table { stage, fall }
+            }
+        },
+        exponent: fall_exponent,
+    });
+    let ice = ice
+        .unwrap_or_default()
+        .iter()
+        .map(|(start, end, coefficient)| IcePeriod {
+            start: datetime_to_epoch(start),
+            end: datetime_to_epoch(end),
+            coefficient: *coefficient,
+        })
+        .collect();
+    let rating = Rating {
+        base,
+        shifts,
+        fall: fall_rating,
+        ice,
+    };
+    let times: Vec<f64> = times.iter().map(datetime_to_epoch).collect();
+    let rated = py.allow_threads(|| {
+        rate_stage_rs(
+            &rating,
+            &times,
+            &stage.0,
+            fall.as_ref().map(|f| f.0.as_slice()),
+        )
+    })?;
+    let dict = PyDict::new(py);
+    dict.set_item("discharge", rated.discharge)?;
+    dict.set_item("shift", rated.shift)?;
+    dict.set_item("fall_factor", rated.fall_factor)?;
+    dict.set_item("ice_coefficient", rated.ice_coefficient)?;
+    Ok(dict)
+}
+
+type CulvertArgs = (
+    String,
+    String,
+    f64,
+    f64,
+    f64,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    usize,
+    Option<f64>,
+    &'static str,
+);
+
+/// Circular or box culvert with inlet and outlet control after FHWA HDS-5.
+///
+/// Headwaters are depths above the inlet invert and tailwaters depths above
+/// the outlet invert, in metres (`units="si"`) or feet (`units="us"`);
+/// discharges are in m³/s or cfs and are shared equally by the barrels.
+///
+/// Args:
+///     shape (str): `"circular"` or `"box"`.
+///     inlet (str): Inlet configuration, selecting the HDS-5 inlet-control
+///         coefficients and the entrance loss coefficient.
+///     n (float): Manning's roughness of the barrel.
+///     length (float): Barrel length.
+///     slope (float, optional): Barrel slope. Defaults to 0.
+///     diameter (float, optional): Diameter of a circular barrel.
+///     span (float, optional): Span of a box barrel.
+///     rise (float, optional): Rise of a box barrel.
+///     barrels (int, optional): Number of identical barrels. Defaults to 1.
+///     entrance_loss (float, optional): Entrance loss coefficient `ke`
+///         overriding the inlet's.
+///     units (str, optional): `"si"` or `"us"`. Defaults to `"si"`.
+#[pyclass(name = "Culvert", module = "rustflow.hydraulics")]
+pub struct PyCulvert {
+    inner: Culvert,
+    inlet: String,
+    units: UnitSystem,
+}
+
+impl PyCulvert {
+    fn headwater_dict<'py>(&self, py: Python<'py>, h: &Headwater) -> PyResult<Bound<'py, PyDict>> {
+        let units = self.units;
+        let dict = PyDict::new(py);
+        dict.set_item("headwater", units.length_from_si(h.headwater))?;
+        dict.set_item("inlet", units.length_from_si(h.inlet))?;
+        dict.set_item("outlet", units.length_from_si(h.outlet))?;
+        dict.set_item("control", h.control.name())?;
+        dict.set_item("velocity", units.velocity_from_si(h.velocity))?;
+        Ok(dict)
+    }
+}
+
+#[pymethods]
+impl PyCulvert {
+    #[new]
+    #[pyo3(signature = (shape, inlet, n, length, slope=0.0, diameter=None, span=None, rise=None, barrels=1, entrance_loss=None, units=UnitSystem::Si))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        shape: &str,
+        inlet: &str,
+        n: f64,
+        length: f64,
+        slope: f64,
+        diameter: Option<f64>,
+        span: Option<f64>,
+        rise: Option<f64>,
+        barrels: usize,
+        entrance_loss: Option<f64>,
+        units: UnitSystem,
+    ) -> PyResult<Self> {
+        let barrel = match (shape, diameter, span, rise) {
+            ("circular", Some(d), None, None) => BarrelShape::Circular {
+                diameter: units.length_to_si(d),
+            },
+            ("box", None, Some(b), Some(d)) => BarrelShape::Box {
+                span: units.length_to_si(b),
+                rise: units.length_to_si(d),
+            },
+            _ => {
+                return Err(PyValueError::new_err(
+                    "A circular culvert needs `diameter` and a box culvert `span` and `rise`.",
+                ))
+            }
+        };
+        let mut coefficients = InletCoefficients::named(inlet)?;
+        if let Some(ke) = entrance_loss {
+            coefficients.entrance_loss = ke;
+        }
+        Ok(PyCulvert {
+            inner: Culvert::new(
+                barrel,
+                coefficients,
+                n,
+                units.length_to_si(length),
+                slope,
+                barrels,
+            )?,
+            inlet: inlet.to_owned(),
+            units,
+        })
+    }
+
+    /// Headwater needed to pass a discharge.
+    ///
+    /// Args:
+    ///     discharge (float): Total discharge.
+    ///     tailwater (float, optional): Tailwater depth. Defaults to 0.
+    ///
+    /// Returns:
+    ///     dict[str, Any]: `headwater` (the larger of the two controls),
+    ///     `inlet` and `outlet` control headwaters, `control` (`"inlet"` or
+    ///     `"outlet"`) and the full-barrel `velocity`.
+    #[pyo3(signature = (discharge, tailwater=0.0))]
+    fn headwater<'py>(
+        &self,
+        py: Python<'py>,
+        discharge: f64,
+        tailwater: f64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let units = self.units;
+        let h = self.inner.headwater(
+            units.discharge_to_si(discharge),
+            units.length_to_si(tailwater),
+        );
+        self.headwater_dict(py, &h)
+    }
+
+    /// Discharge passed at a headwater.
+    ///
+    /// Args:
+    ///     headwater (float): Headwater depth.
+    ///     tailwater (float, optional): Tailwater depth. Defaults to 0.
+    ///
+    /// Returns:
+    ///     float: Total discharge, zero at or below the tailwater level.
+    #[pyo3(signature = (headwater, tailwater=0.0))]
+    fn discharge(&self, headwater: f64, tailwater: f64) -> f64 {
+        let units = self.units;
+        units.discharge_from_si(
+            self.inner
+                .discharge(units.length_to_si(headwater), units.length_to_si(tailwater)),
+        )
+    }
+
+    /// Performance curve: the headwater of each discharge.
+    ///
+    /// Args:
+    ///     discharges (Sequence[float]): Total discharges.
+    ///     tailwater (float, optional): Tailwater depth. Defaults to 0.
+    ///
+    /// Returns:
+    ///     dict[str, list]: `discharge`, `headwater`, `inlet`, `outlet`,
+    ///     `control` and `velocity`, one entry per discharge.
+    #[pyo3(signature = (discharges, tailwater=0.0))]
+    fn performance<'py>(
+        &self,
+        py: Python<'py>,
+        discharges: FloatSeries,
+        tailwater: f64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let units = self.units;
+        let q: Vec<f64> = discharges
+            .0
+            .iter()
+            .map(|&q| units.discharge_to_si(q))
+            .collect();
+        let curve = self.inner.performance(&q, units.length_to_si(tailwater));
+        let lengths = |f: fn(&Headwater) -> f64| -> Vec<f64> {
+            curve.iter().map(|h| units.length_from_si(f(h))).collect()
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("discharge", discharges.0)?;
+        dict.set_item("headwater", lengths(|h| h.headwater))?;
+        dict.set_item("inlet", lengths(|h| h.inlet))?;
+        dict.set_item("outlet", lengths(|h| h.outlet))?;
+        dict.set_item(
+            "control",
+            curve.iter().map(|h| h.control.name()).collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "velocity",
+            curve
+                .iter()
+                .map(|h| units.velocity_from_si(h.velocity))
+                .collect::<Vec<_>>(),
+        )?;
+        Ok(dict)
+    }
+
+    /// Outflow at each pool elevation of a reservoir drained by the
+    /// culvert, for the `outflow` column of a level-pool table.
+    ///
+    /// Args:
+    ///     elevations (Sequence[float]): Pool elevations.
+    ///     invert (float): Elevation of the inlet invert.
+    ///     tailwater (float, optional): Tailwater depth. Defaults to 0.
+    ///
+    /// Returns:
+    ///     list[float]: Total discharge at each elevation.
+    #[pyo3(signature = (elevations, invert, tailwater=0.0))]
+    fn rating(&self, elevations: FloatSeries, invert: f64, tailwater: f64) -> Vec<f64> {
+        let units = self.units;
+        let depth: Vec<f64> = elevations
+            .0
+            .iter()
+            .map(|&z| units.length_to_si(z - invert))
+            .collect();
+        self.inner
+            .rating(&depth, 0.0, units.length_to_si(tailwater))
+            .into_iter()
+            .map(|q| units.discharge_from_si(q))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        let units = self.units;
+        let size = match self.inner.shape {
+            BarrelShape::Circular { diameter } => {
+                format!("diameter={}", units.length_from_si(diameter))
+            }
+            BarrelShape::Box { span, rise } => format!(
+                "span={}, rise={}",
+                units.length_from_si(span),
+                units.length_from_si(rise)
+            ),
+        };
+        format!(
+            "Culvert({size}, inlet='{}', barrels={}, units='{}')",
+            self.inlet,
+            self.inner.barrels,
+            units.name()
+        )
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, CulvertArgs)> {
+        let this = slf.borrow();
+        let c = &this.inner;
+        let units = this.units;
+        let length = |v: f64| Some(units.length_from_si(v));
+        let (shape, diameter, span, rise) = match c.shape {
+            BarrelShape::Circular { diameter } => ("circular", length(diameter), None, None),
+            BarrelShape::Box { span, rise } => ("box", None, length(span), length(rise)),
+        };
+        Ok((
+            slf.get_type().into_any(),
+            (
+                shape.to_owned(),
+                this.inlet.clone(),
+                c.manning_n,
+                units.length_from_si(c.length),
+                c.slope,
+                diameter,
+                span,
+                rise,
+                c.barrels,
+                Some(c.inlet.entrance_loss),
+                units.name(),
+            ),
+        ))
+    }
+}
+
+#[pymodule]
+pub fn init_hydraulics(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCulvert>()?;
+    m.add_function(wrap_pyfunction!(bridge_backwater, m)?)?;
+    m.add_function(wrap_pyfunction!(manning_discharge, m)?)?;
+    m.add_function(wrap_pyfunction!(normal_depth, m)?)?;
+    m.add_function(wrap_pyfunction!(rate_stage, m)?)?;
+    m.add_function(wrap_pyfunction!(travel_time, m)?)?;
+    m.add_function(wrap_pyfunction!(weir_flow, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::error::{Error, Result};
+
+use super::manning::TrapezoidalChannel;
+
+/// Discharge–travel-time table of a uniform reach, in SI units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TravelTimeTable {
+    /// Discharges (m³/s).
+    pub discharge: Vec<f64>,
+    /// Normal depth at each discharge (m).
+    pub depth: Vec<f64>,
+    /// Mean velocity (m/s).
+    pub velocity: Vec<f64>,
+    /// Kinematic wave celerity (m/s).
+    pub celerity: Vec<f64>,
+    /// Time for a flood wave to traverse the reach (s): length over
+    /// celerity. This is the Muskingum `K` of the reach at each discharge.
+    pub travel_time: Vec<f64>,
+    /// Time for a parcel of water, such as a spill, to traverse the reach
+    /// at the mean velocity (s).
+    pub water_travel_time: Vec<f64>,
+}
+
+/// Travel times over a reach of `length` (m) of `channel` at each of
+/// `discharges` (m³/s), assuming normal flow.
+pub fn travel_time_rs(
+    channel: &TrapezoidalChannel,
+    length: f64,
+    discharges: &[f64],
+) -> Result<TravelTimeTable> {
+    if length.is_nan() || length <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The reach length must be positive.".into(),
+        ));
+    }
+    if discharges.iter().any(|q| q.is_nan() || *q <= 0.0) {
+        return Err(Error::InvalidParameter(
+            "Discharges must be positive.".into(),
+        ));
+    }
+    let n = discharges.len();
+    let mut table = TravelTimeTable {
+        discharge: discharges.to_vec(),
+        depth: Vec::with_capacity(n),
+        velocity: Vec::with_capacity(n),
+        celerity: Vec::with_capacity(n),
+        travel_time: Vec::with_capacity(n),
+        water_travel_time: Vec::with_capacity(n),
+    };
+    for &q in discharges {
+        let depth = channel.normal_depth(q);
+        let velocity = channel.velocity(depth);
+        let celerity = channel.celerity(depth);
+        table.depth.push(depth);
+        table.velocity.push(velocity);
+        table.celerity.push(celerity);
+        table.travel_time.push(length / celerity);
+        table.water_travel_time.push(length / velocity);
+    }
+    Ok(table)
+}
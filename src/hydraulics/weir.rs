@@ -0,0 +1,23 @@
+//! Weir flow between two water levels, in SI units.
+
+/// Broad-crested weir coefficient `C` of `Q = C L H^1.5` (m^0.5/s).
+pub const BROAD_CRESTED: f64 = 1.7;
+
+/// Flow (m³/s) over a weir of `length` (m) and `crest` elevation (m) from
+/// the side at level `from` to the side at level `to`, negative when the
+/// flow runs the other way. The free-flow discharge `C L H^1.5` is reduced
+/// for submergence by the Villemonte (1947) factor
+/// `(1 − (H2 / H1)^1.5)^0.385`.
+pub fn weir_flow(coefficient: f64, length: f64, crest: f64, from: f64, to: f64) -> f64 {
+    let (high, low, sign) = if from >= to {
+        (from, to, 1.0)
+    } else {
+        (to, from, -1.0)
+    };
+    let head = high - crest;
+    if head.is_nan() || head <= 0.0 {
+        return 0.0;
+    }
+    let submergence = ((low - crest).max(0.0) / head).powf(1.5);
+    sign * coefficient * length * head.powf(1.5) * (1.0 - submergence).powf(0.385)
+}
@@ -0,0 +1,198 @@
+//! Design hydrograph construction: scaling a pattern hydrograph to a peak
+//! or volume, and balanced hydrographs whose maximum mean flow over each of
+//! several durations matches a frequency quantile.
+//!
+//! Volumes are sums of ordinates times the time step, in flow units ×
+//! seconds.
+
+use crate::error::{Error, Result};
+
+fn check_pattern(hydrograph: &[f64]) -> Result<()> {
+    if hydrograph.is_empty() || hydrograph.iter().any(|q| !q.is_finite()) {
+        return Err(Error::InvalidParameter(
+            "The hydrograph must be non-empty and finite.".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Scales the flow above `baseflow` so that the peak equals `peak`.
+pub fn scale_to_peak(hydrograph: &[f64], peak: f64, baseflow: f64) -> Result<Vec<f64>> {
+    check_pattern(hydrograph)?;
+    let current = hydrograph.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)) - baseflow;
+    if current <= 0.0 || peak.is_nan() || peak < baseflow {
+        return Err(Error::InvalidParameter(
+            "The hydrograph must rise above the baseflow and the target peak must not be below it."
+                .into(),
+        ));
+    }
+    let factor = (peak - baseflow) / current;
+    Ok(hydrograph
+        .iter()
+        .map(|&q| baseflow + factor * (q - baseflow))
+        .collect())
+}
+
+/// Scales the flow above `baseflow` so that the volume above it equals
+/// `volume` with steps of `dt` seconds.
+pub fn scale_to_volume(
+    hydrograph: &[f64],
+    volume: f64,
+    dt: f64,
+    baseflow: f64,
+) -> Result<Vec<f64>> {
+    check_pattern(hydrograph)?;
+    if dt.is_nan() || dt <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The time step must be positive.".into(),
+        ));
+    }
+    let current: f64 = hydrograph.iter().map(|q| q - baseflow).sum::<f64>() * dt;
+    if current <= 0.0 || volume.is_nan() || volume < 0.0 {
+        return Err(Error::InvalidParameter(
+            "The hydrograph must carry volume above the baseflow and the target volume must not be negative."
+                .into(),
+        ));
+    }
+    let factor = volume / current;
+    Ok(hydrograph
+        .iter()
+        .map(|&q| baseflow + factor * (q - baseflow))
+        .collect())
+}
+
+/// Checks that durations (in steps) increase and the maximum mean flows
+/// give nested, consistent volumes: mean flow does not increase and volume
+/// does not decrease with duration.
+fn check_quantiles(durations: &[usize], flows: &[f64]) -> Result<()> {
+    if durations.is_empty() || durations.len() != flows.len() {
+        return Err(Error::InvalidParameter(
+            "A balanced hydrograph needs at least one duration with one mean flow each.".into(),
+        ));
+    }
+    if durations[0] == 0 || durations.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(Error::InvalidParameter(
+            "Durations must be whole, strictly increasing numbers of time steps.".into(),
+        ));
+    }
+    if flows.iter().any(|q| !q.is_finite() || *q < 0.0) {
+        return Err(Error::InvalidParameter(
+            "Mean flows must be finite and not negative.".into(),
+        ));
+    }
+    for (d, q) in durations.windows(2).zip(flows.windows(2)) {
+        if q[1] > q[0] || q[1] * (d[1] as f64) < q[0] * (d[0] as f64) {
+            return Err(Error::InvalidParameter(format!(
+                "Mean flow {} over {} steps is inconsistent with {} over {} steps: mean flows \
+                 must not increase and volumes must not decrease with duration.",
+                q[1], d[1], q[0], d[0]
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Balanced hydrograph whose maximum mean flow over each of `durations`
+/// (in steps, increasing) equals `flows`.
+///
+/// Without a pattern, the hydrograph is built from nested blocks: the
+/// shortest duration at its mean flow, then each longer duration's extra
+/// volume spread evenly over its extra steps, with `peak_position` of them
+/// before the peak block and the rest after. The result spans the longest
+/// duration.
+///
+/// With a `pattern`, the nested windows of maximum pattern volume are
+/// found around its peak, and the ordinates of each window outside the
+/// next shorter one are scaled to its volume increment, keeping the
+/// pattern's shape. Ordinates outside the longest window are unchanged.
+pub fn balanced_hydrograph_rs(
+    durations: &[usize],
+    flows: &[f64],
+    pattern: Option<&[f64]>,
+    peak_position: f64,
+) -> Result<Vec<f64>> {
+    check_quantiles(durations, flows)?;
+    let volumes: Vec<f64> = durations
+        .iter()
+        .zip(flows)
+        .map(|(&d, &q)| d as f64 * q)
+        .collect();
+    let Some(pattern) = pattern else {
+        if !(0.0..=1.0).contains(&peak_position) {
+            return Err(Error::InvalidParameter(
+                "The peak position must be within [0, 1].".into(),
+            ));
+        }
+        let longest = durations[durations.len() - 1];
+        let mut hydrograph = vec![0.0; longest];
+        // Window of the previous duration: [start, end).
+        let mut start = ((longest - durations[0]) as f64 * peak_position).round() as usize;
+        let mut end = start + durations[0];
+        hydrograph[start..end].fill(flows[0]);
+        for k in 1..durations.len() {
+            let extra = durations[k] - durations[k - 1];
+            let q = (volumes[k] - volumes[k - 1]) / extra as f64;
+            let before = ((extra as f64 * peak_position).round() as usize)
+                .min(start)
+                .max(extra.saturating_sub(longest - end));
+            let after = extra - before;
+            hydrograph[start - before..start].fill(q);
+            hydrograph[end..end + after].fill(q);
+            start -= before;
+            end += after;
+        }
+        return Ok(hydrograph);
+    };
+
+    check_pattern(pattern)?;
+    if pattern.iter().any(|&q| q < 0.0) {
+        return Err(Error::InvalidParameter(
+            "Pattern ordinates must not be negative.".into(),
+        ));
+    }
+    let longest = durations[durations.len() - 1];
+    if longest > pattern.len() {
+        return Err(Error::InvalidParameter(format!(
+            "The longest duration ({longest} steps) exceeds the pattern ({} steps).",
+            pattern.len()
+        )));
+    }
+    let window_sum = |s: usize, d: usize| pattern[s..s + d].iter().sum::<f64>();
+    // Nested windows of maximum pattern volume, each containing the last.
+    let mut windows: Vec<(usize, usize)> = Vec::with_capacity(durations.len());
+    for &d in durations {
+        let (lo, hi) = match windows.last() {
+            None => (0, pattern.len() - d),
+            Some(&(s, e)) => (e.saturating_sub(d), s.min(pattern.len() - d)),
+        };
+        let mut best = (lo, f64::NEG_INFINITY);
+        for s in lo..=hi {
+            let v = window_sum(s, d);
+            if v > best.1 {
+                best = (s, v);
+            }
+        }
+        windows.push((best.0, best.0 + d));
+    }
+    let mut hydrograph = pattern.to_vec();
+    for k in 0..durations.len() {
+        let (s, e) = windows[k];
+        let target = volumes[k] - if k == 0 { 0.0 } else { volumes[k - 1] };
+        let ring: Vec<usize> = match k {
+            0 => (s..e).collect(),
+            _ => {
+                let (ps, pe) = windows[k - 1];
+                (s..ps).chain(pe..e).collect()
+            }
+        };
+        let current: f64 = ring.iter().map(|&i| pattern[i]).sum();
+        for &i in &ring {
+            hydrograph[i] = if current > 0.0 {
+                pattern[i] * target / current
+            } else {
+                target / ring.len() as f64
+            };
+        }
+    }
+    Ok(hydrograph)
+}
@@ -0,0 +1,523 @@
+//! Indicators of Hydrologic Alteration (Richter et al., 1996).
+//!
+//! The 33 IHA parameters are computed for every complete year of a daily
+//! flow series:
+//!
+//! 1. the flow of each calendar month (12);
+//! 2. the annual minimum and maximum 1-, 3-, 7-, 30- and 90-day means, the
+//!    number of zero-flow days and the base flow index (12);
+//! 3. the day of year of the annual 1-day minimum and maximum (2);
+//! 4. the number and mean duration of low and high pulses, below the 25th
+//!    and above the 75th percentile of all daily flows (4);
+//! 5. the mean rise and fall rates and the number of reversals (3).
+//!
+//! Pre- and post-impact periods are compared by their central tendency and
+//! by the Range of Variability Approach (Richter et al., 1997).
+
+use crate::datetime::{civil_from_days, days_from_civil};
+use crate::ensemble::quantile_sorted;
+use crate::error::{Error, Result};
+use crate::kernels::window_sums;
+
+/// Names of the IHA parameters, in order.
+pub const IHA_PARAMETERS: [&str; 33] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+    "min_1_day",
+    "min_3_day",
+    "min_7_day",
+    "min_30_day",
+    "min_90_day",
+    "max_1_day",
+    "max_3_day",
+    "max_7_day",
+    "max_30_day",
+    "max_90_day",
+    "zero_flow_days",
+    "base_flow_index",
+    "date_of_min",
+    "date_of_max",
+    "low_pulse_count",
+    "low_pulse_duration",
+    "high_pulse_count",
+    "high_pulse_duration",
+    "rise_rate",
+    "fall_rate",
+    "reversals",
+];
+
+/// Statistics used for monthly flows and to summarise years.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IhaStatistic {
+    /// Means, with the coefficient of variation as dispersion.
+    Parametric,
+    /// Medians, with the coefficient of dispersion (interquartile range
+    /// over the median) as dispersion.
+    NonParametric,
+}
+
+impl IhaStatistic {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "parametric" | "mean" => Ok(IhaStatistic::Parametric),
+            "nonparametric" | "non_parametric" | "median" => Ok(IhaStatistic::NonParametric),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown IHA statistic `{name}`; expected `parametric` or `nonparametric`."
+            ))),
+        }
+    }
+
+    fn central(self, values: &[f64]) -> f64 {
+        match self {
+            IhaStatistic::Parametric => mean(values),
+            IhaStatistic::NonParametric => quantile_sorted(&sorted(values), 0.5),
+        }
+    }
+
+    /// Central tendency and dispersion of `values`.
+    pub fn summarize(self, values: &[f64]) -> (f64, f64) {
+        match self {
+            IhaStatistic::Parametric => {
+                let m = mean(values);
+                (m, std(values) / m.abs())
+            }
+            IhaStatistic::NonParametric => {
+                let s = sorted(values);
+                let median = quantile_sorted(&s, 0.5);
+                (
+                    median,
+                    (quantile_sorted(&s, 0.75) - quantile_sorted(&s, 0.25)) / median.abs(),
+                )
+            }
+        }
+    }
+
+    /// Lower and upper bounds of the middle RVA category of `values`.
+    fn rva_bounds(self, values: &[f64]) -> (f64, f64) {
+        match self {
+            IhaStatistic::Parametric => {
+                let (m, s) = (mean(values), std(values));
+                (m - s, m + s)
+            }
+            IhaStatistic::NonParametric => {
+                let s = sorted(values);
+                (
+                    quantile_sorted(&s, 1.0 / 3.0),
+                    quantile_sorted(&s, 2.0 / 3.0),
+                )
+            }
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn std(values: &[f64]) -> f64 {
+    let m = mean(values);
+    let n = values.len() as f64;
+    (values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// Complete years of a daily series starting on `start_day` (days since
+/// 1970-01-01), as `(year, index of the first day, length)`. Years start
+/// on the first of `year_start_month` and are named by the calendar year
+/// in which they end; years with NaN values are skipped.
+pub fn complete_years(
+    flows: &[f64],
+    start_day: i64,
+    year_start_month: u32,
+) -> Result<Vec<(i64, usize, usize)>> {
+    if !(1..=12).contains(&year_start_month) {
+        return Err(Error::InvalidParameter(format!(
+            "Year start month {year_start_month} is outside 1–12."
+        )));
+    }
+    let end_day = start_day + flows.len() as i64;
+    // First year start on or after the first day.
+    let mut year = civil_from_days(start_day).0 - 1;
+    while days_from_civil(year, year_start_month, 1) < start_day {
+        year += 1;
+    }
+    let mut years = Vec::new();
+    loop {
+        let first = days_from_civil(year, year_start_month, 1);
+        let next = days_from_civil(year + 1, year_start_month, 1);
+        if next > end_day {
+            break;
+        }
+        let (offset, length) = ((first - start_day) as usize, (next - first) as usize);
+        if flows[offset..offset + length].iter().all(|q| !q.is_nan()) {
+            let name = if year_start_month > 1 { year + 1 } else { year };
+            years.push((name, offset, length));
+        }
+        year += 1;
+    }
+    Ok(years)
+}
+
+/// The 33 IHA parameters of one year of daily `flows` starting on
+/// `start_day`, with pulse thresholds `low` and `high`.
+fn year_parameters(
+    flows: &[f64],
+    start_day: i64,
+    statistic: IhaStatistic,
+    (low, high): (f64, f64),
+) -> [f64; 33] {
+    let mut values = [0.0; 33];
+    // Group 1: monthly flows.
+    let mut months: Vec<Vec<f64>> = vec![Vec::new(); 12];
+    for (t, &q) in flows.iter().enumerate() {
+        let (_, m, _) = civil_from_days(start_day + t as i64);
+        months[m as usize - 1].push(q);
+    }
+    for (value, month) in values.iter_mut().zip(&months) {
+        *value = statistic.central(month);
+    }
+    // Group 2: extremes of moving means.
+    for (j, &n) in [1, 3, 7, 30, 90].iter().enumerate() {
+        let (min, max) = moving_mean_extremes(flows, n);
+        values[12 + j] = min;
+        values[17 + j] = max;
+    }
+    values[22] = flows.iter().filter(|&&q| q == 0.0).count() as f64;
+    values[23] = values[14] / mean(flows);
+    // Group 3: timing, as day of year (1 = 1 January).
+    let day_of_year = |t: usize| {
+        let day = start_day + t as i64;
+        let (y, _, _) = civil_from_days(day);
+        (day - days_from_civil(y, 1, 1) + 1) as f64
+    };
+    let (mut t_min, mut t_max) = (0, 0);
+    for (t, &q) in flows.iter().enumerate() {
+        if q < flows[t_min] {
+            t_min = t;
+        }
+        if q > flows[t_max] {
+            t_max = t;
+        }
+    }
+    values[24] = day_of_year(t_min);
+    values[25] = day_of_year(t_max);
+    // Group 4: pulses.
+    let (count, duration) = pulses(flows, |q| q < low);
+    values[26] = count;
+    values[27] = duration;
+    let (count, duration) = pulses(flows, |q| q > high);
+    values[28] = count;
+    values[29] = duration;
+    // Group 5: rates of change and reversals.
+    let (mut rises, mut falls) = (Vec::new(), Vec::new());
+    let mut reversals = 0;
+    let mut rising: Option<bool> = None;
+    for pair in flows.windows(2) {
+        let change = pair[1] - pair[0];
+        if change == 0.0 {
+            continue;
+        }
+        if change > 0.0 {
+            rises.push(change);
+        } else {
+            falls.push(change);
+        }
+        if rising.is_some_and(|r| r != (change > 0.0)) {
+            reversals += 1;
+        }
+        rising = Some(change > 0.0);
+    }
+    values[30] = if rises.is_empty() {
+        0.0
+    } else {
+        statistic.central(&rises)
+    };
+    values[31] = if falls.is_empty() {
+        0.0
+    } else {
+        statistic.central(&falls)
+    };
+    values[32] = reversals as f64;
+    values
+}
+
+/// Minimum and maximum of the `n`-day moving mean of `flows`. Each window
+/// is summed afresh so that zero-flow windows stay exactly zero.
+fn moving_mean_extremes(flows: &[f64], n: usize) -> (f64, f64) {
+    let n = n.min(flows.len());
+    window_sums(flows, n)
+        .into_iter()
+        .map(|s| s / n as f64)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), m| {
+            (min.min(m), max.max(m))
+        })
+}
+
+/// Number and mean duration (days) of runs of days satisfying `inside`.
+fn pulses(flows: &[f64], inside: impl Fn(f64) -> bool) -> (f64, f64) {
+    let (mut count, mut days) = (0, 0);
+    let mut previous = false;
+    for &q in flows {
+        let current = inside(q);
+        if current {
+            days += 1;
+            if !previous {
+                count += 1;
+            }
+        }
+        previous = current;
+    }
+    let duration = if count > 0 {
+        days as f64 / count as f64
+    } else {
+        0.0
+    };
+    (count as f64, duration)
+}
+
+/// Central tendency and dispersion of each parameter over a set of years.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IhaSummary {
+    pub central: Vec<f64>,
+    pub dispersion: Vec<f64>,
+}
+
+/// IHA parameters of a daily flow series, summarised over all years or
+/// compared between pre- and post-impact periods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IhaResult {
+    pub years: Vec<i64>,
+    /// `parameters[j][k]` is parameter `j` in year `years[k]`.
+    pub parameters: Vec<Vec<f64>>,
+    /// Summary over all years, or over the pre-impact years.
+    pub pre: IhaSummary,
+    /// Summary over the post-impact years.
+    pub post: Option<IhaSummary>,
+    /// Change of the central tendency relative to the pre-impact period.
+    pub deviation: Option<Vec<f64>>,
+    /// RVA hydrologic alteration of the middle category: the observed
+    /// minus the expected number of post-impact years within the
+    /// pre-impact middle range, over the expected number.
+    pub alteration: Option<Vec<f64>>,
+}
+
+/// Computes the IHA parameters of daily `flows` starting on `start_day`
+/// (days since 1970-01-01) for complete years starting in
+/// `year_start_month`. With an `impact_year`, years named before it form
+/// the pre-impact period, which also sets the pulse thresholds.
+pub fn iha_rs(
+    flows: &[f64],
+    start_day: i64,
+    year_start_month: u32,
+    impact_year: Option<i64>,
+    statistic: IhaStatistic,
+) -> Result<IhaResult> {
+    let years = complete_years(flows, start_day, year_start_month)?;
+    let is_pre = |year: i64| impact_year.is_none_or(|impact| year < impact);
+    let n_pre = years.iter().filter(|y| is_pre(y.0)).count();
+    let n_post = years.len() - n_pre;
+    if n_pre < 2 || (impact_year.is_some() && n_post < 2) {
+        return Err(Error::Data(
+            "At least two complete years are needed in each period.".into(),
+        ));
+    }
+    let reference: Vec<f64> = years
+        .iter()
+        .filter(|y| is_pre(y.0))
+        .flat_map(|&(_, offset, length)| flows[offset..offset + length].iter().copied())
+        .collect();
+    let reference = sorted(&reference);
+    let thresholds = (
+        quantile_sorted(&reference, 0.25),
+        quantile_sorted(&reference, 0.75),
+    );
+    let mut parameters = vec![Vec::with_capacity(years.len()); IHA_PARAMETERS.len()];
+    for &(_, offset, length) in &years {
+        let values = year_parameters(
+            &flows[offset..offset + length],
+            start_day + offset as i64,
+            statistic,
+            thresholds,
+        );
+        for (series, value) in parameters.iter_mut().zip(values) {
+            series.push(value);
+        }
+    }
+    let summarize = |pre: bool| {
+        let (central, dispersion) = parameters
+            .iter()
+            .map(|series| {
+                let values: Vec<f64> = series
+                    .iter()
+                    .zip(&years)
+                    .filter(|(_, y)| is_pre(y.0) == pre)
+                    .map(|(v, _)| *v)
+                    .collect();
+                statistic.summarize(&values)
+            })
+            .unzip();
+        IhaSummary {
+            central,
+            dispersion,
+        }
+    };
+    let pre = summarize(true);
+    let (post, deviation, alteration) = if impact_year.is_some() {
+        let post = summarize(false);
+        let deviation = pre
+            .central
+            .iter()
+            .zip(&post.central)
+            .map(|(a, b)| (b - a) / a)
+            .collect();
+        let alteration = parameters
+            .iter()
+            .map(|series| {
+                let (before, after): (Vec<_>, Vec<_>) =
+                    series.iter().zip(&years).partition(|(_, y)| is_pre(y.0));
+                let before: Vec<f64> = before.into_iter().map(|(v, _)| *v).collect();
+                let (lower, upper) = statistic.rva_bounds(&before);
+                let within = |v: &f64| (lower..=upper).contains(v);
+                let expected = before.iter().filter(|v| within(v)).count() as f64
+                    / before.len() as f64
+                    * after.len() as f64;
+                let observed = after.iter().filter(|(v, _)| within(v)).count() as f64;
+                (observed - expected) / expected
+            })
+            .collect();
+        (Some(post), Some(deviation), Some(alteration))
+    } else {
+        (None, None, None)
+    };
+    Ok(IhaResult {
+        years: years.iter().map(|y| y.0).collect(),
+        parameters,
+        pre,
+        post,
+        deviation,
+        alteration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter(name: &str) -> usize {
+        IHA_PARAMETERS.iter().position(|&p| p == name).unwrap()
+    }
+
+    #[test]
+    fn constant_flows_have_no_variability() {
+        // Four years from 1 January 2001, with an impact in 2003.
+        let start = days_from_civil(2001, 1, 1);
+        let days = (days_from_civil(2005, 1, 1) - start) as usize;
+        let result = iha_rs(
+            &vec![10.0; days],
+            start,
+            1,
+            Some(2003),
+            IhaStatistic::Parametric,
+        )
+        .unwrap();
+        assert_eq!(result.years, [2001, 2002, 2003, 2004]);
+        for name in [
+            "min_1_day",
+            "min_7_day",
+            "min_90_day",
+            "max_1_day",
+            "max_7_day",
+            "max_90_day",
+            "june",
+        ] {
+            assert_eq!(result.parameters[parameter(name)], [10.0; 4], "{name}");
+        }
+        for name in ["reversals", "rise_rate", "fall_rate", "zero_flow_days"] {
+            assert_eq!(result.parameters[parameter(name)], [0.0; 4], "{name}");
+        }
+        // Flows at the thresholds are neither low nor high pulses.
+        assert_eq!(result.parameters[parameter("low_pulse_count")], [0.0; 4]);
+        assert_eq!(result.parameters[parameter("high_pulse_count")], [0.0; 4]);
+        assert_eq!(result.parameters[parameter("base_flow_index")], [1.0; 4]);
+        assert_eq!(result.parameters[parameter("date_of_min")], [1.0; 4]);
+        // Nothing changes after the impact.
+        let j = parameter("min_7_day");
+        assert_eq!(result.deviation.as_ref().unwrap()[j], 0.0);
+        assert_eq!(result.alteration.as_ref().unwrap()[j], 0.0);
+    }
+
+    #[test]
+    fn short_series_match_hand_computed_parameters() {
+        let flows = [1.0, 3.0, 2.0, 2.0, 5.0, 4.0, 0.0, 0.0, 6.0, 1.0];
+        let start = days_from_civil(2001, 1, 1);
+        let values = year_parameters(&flows, start, IhaStatistic::Parametric, (2.0, 4.0));
+        let value = |name: &str| values[parameter(name)];
+        assert_eq!(value("january"), 2.4);
+        assert!(value("february").is_nan());
+        assert_eq!((value("min_1_day"), value("max_1_day")), (0.0, 6.0));
+        // 3-day sums run 6, 7, 9, 11, 9, 4, 6, 7 and 7-day sums 17, 16, 19,
+        // 18; longer windows cover the whole series.
+        assert_eq!(value("min_3_day"), 4.0 / 3.0);
+        assert_eq!(value("max_3_day"), 11.0 / 3.0);
+        assert_eq!(value("min_7_day"), 16.0 / 7.0);
+        assert_eq!(value("max_7_day"), 19.0 / 7.0);
+        assert_eq!(value("min_30_day"), 2.4);
+        assert_eq!(value("max_90_day"), 2.4);
+        assert_eq!(value("zero_flow_days"), 2.0);
+        assert!((value("base_flow_index") - 16.0 / 7.0 / 2.4).abs() < 1e-15);
+        // The first of equal extremes sets the date.
+        assert_eq!((value("date_of_min"), value("date_of_max")), (7.0, 9.0));
+        // Below 2: day 1, days 7–8 and day 10; above 4: days 5 and 9.
+        assert_eq!(value("low_pulse_count"), 3.0);
+        assert_eq!(value("low_pulse_duration"), 4.0 / 3.0);
+        assert_eq!(value("high_pulse_count"), 2.0);
+        assert_eq!(value("high_pulse_duration"), 1.0);
+        // Changes +2 −1 0 +3 −1 −4 0 +6 −5 reverse five times, ignoring the
+        // unchanged days.
+        assert_eq!(value("rise_rate"), 11.0 / 3.0);
+        assert_eq!(value("fall_rate"), -11.0 / 4.0);
+        assert_eq!(value("reversals"), 5.0);
+
+        let values = year_parameters(&flows, start, IhaStatistic::NonParametric, (2.0, 4.0));
+        assert_eq!(values[parameter("january")], 2.0);
+        assert_eq!(values[parameter("rise_rate")], 3.0);
+        assert_eq!(values[parameter("fall_rate")], -2.5);
+    }
+
+    #[test]
+    fn years_follow_the_start_month() {
+        // From 15 March 2001 to the end of 2003, water years starting in
+        // October are complete for 2002 and 2003 only.
+        let start = days_from_civil(2001, 3, 15);
+        let mut flows = vec![1.0; (days_from_civil(2004, 1, 1) - start) as usize];
+        let years = complete_years(&flows, start, 10).unwrap();
+        assert_eq!(
+            years.iter().map(|y| (y.0, y.2)).collect::<Vec<_>>(),
+            [(2002, 365), (2003, 365)]
+        );
+        assert_eq!(years[0].1 as i64, days_from_civil(2001, 10, 1) - start);
+        // Years with missing days are skipped.
+        flows[years[0].1 + 40] = f64::NAN;
+        assert_eq!(complete_years(&flows, start, 10).unwrap().len(), 1);
+        assert!(complete_years(&flows, start, 13).is_err());
+        assert!(matches!(
+            iha_rs(&flows, start, 10, None, IhaStatistic::Parametric),
+            Err(Error::Data(_))
+        ));
+    }
+}
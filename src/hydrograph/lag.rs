@@ -0,0 +1,110 @@
+//! Cross-correlation between an upstream and a downstream gauge, for
+//! estimating travel time (e.g. an initial Muskingum K) and checking routed
+//! results against observations.
+
+use crate::error::{Error, Result};
+
+/// Cross-correlation function and its best lag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LagCorrelation {
+    /// Lags in steps, from `-max_lag` to `max_lag`; positive lags mean the
+    /// downstream series follows the upstream one.
+    pub lags: Vec<i64>,
+    /// Pearson correlation at each lag (`NaN` with fewer than three pairs
+    /// or a constant series).
+    pub correlation: Vec<f64>,
+    /// Lag of the highest correlation.
+    pub best_lag: i64,
+    pub best_correlation: f64,
+    /// Best lag refined by a parabola through the peak and its neighbours
+    /// (fractional steps).
+    pub refined_lag: f64,
+}
+
+/// Pearson correlation of the pairs where both values are finite.
+fn pearson(pairs: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let (mut n, mut sx, mut sy, mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    for (x, y) in pairs.filter(|(x, y)| x.is_finite() && y.is_finite()) {
+        n += 1.0;
+        sx += x;
+        sy += y;
+        sxx += x * x;
+        syy += y * y;
+        sxy += x * y;
+    }
+    if n < 3.0 {
+        return f64::NAN;
+    }
+    let cov = sxy - sx * sy / n;
+    let (vx, vy) = (sxx - sx * sx / n, syy - sy * sy / n);
+    if vx <= 0.0 || vy <= 0.0 {
+        return f64::NAN;
+    }
+    cov / (vx * vy).sqrt()
+}
+
+/// Correlates `downstream[t + lag]` with `upstream[t]` for every lag up to
+/// `max_lag` steps either way.
+pub fn lag_correlation_rs(
+    upstream: &[f64],
+    downstream: &[f64],
+    max_lag: usize,
+) -> Result<LagCorrelation> {
+    if upstream.len() != downstream.len() {
+        return Err(Error::Data(
+            "Upstream and downstream series must have equal lengths.".into(),
+        ));
+    }
+    let n = upstream.len();
+    if max_lag + 3 > n {
+        return Err(Error::InvalidParameter(format!(
+            "A maximum lag of {max_lag} steps leaves fewer than three pairs in {n} values."
+        )));
+    }
+    let max_lag = max_lag as i64;
+    let lags: Vec<i64> = (-max_lag..=max_lag).collect();
+    let correlation: Vec<f64> = lags
+        .iter()
+        .map(|&lag| {
+            let shift = lag.unsigned_abs() as usize;
+            if lag >= 0 {
+                pearson(
+                    upstream[..n - shift]
+                        .iter()
+                        .copied()
+                        .zip(downstream[shift..].iter().copied()),
+                )
+            } else {
+                pearson(
+                    upstream[shift..]
+                        .iter()
+                        .copied()
+                        .zip(downstream[..n - shift].iter().copied()),
+                )
+            }
+        })
+        .collect();
+    let best = (0..lags.len())
+        .filter(|&i| !correlation[i].is_nan())
+        .max_by(|&a, &b| correlation[a].total_cmp(&correlation[b]))
+        .ok_or_else(|| Error::Data("The series have no variation to correlate.".into()))?;
+    let mut refined_lag = lags[best] as f64;
+    if best > 0 && best + 1 < lags.len() {
+        let (a, b, c) = (
+            correlation[best - 1],
+            correlation[best],
+            correlation[best + 1],
+        );
+        let curvature = a - 2.0 * b + c;
+        if curvature < 0.0 {
+            refined_lag += 0.5 * (a - c) / curvature;
+        }
+    }
+    Ok(LagCorrelation {
+        best_lag: lags[best],
+        best_correlation: correlation[best],
+        refined_lag,
+        lags,
+        correlation,
+    })
+}
@@ -0,0 +1,10 @@
+//! Hydrograph statistics of daily flow series (Indicators of Hydrologic
+//! Alteration for environmental flow studies), lag correlation between
+//! gauges, hydrologic signatures and design hydrograph construction.
+
+pub mod design;
+pub mod iha;
+pub mod lag;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod signatures;
@@ -0,0 +1,199 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDict};
+
+use super::design::{
+    balanced_hydrograph_rs, scale_to_peak as scale_peak, scale_to_volume as scale_volume,
+};
+use super::iha::{iha_rs, IhaStatistic, IhaSummary, IHA_PARAMETERS};
+use super::lag::lag_correlation_rs;
+use super::signatures::{compare_signatures_rs, signatures_rs, SIGNATURES};
+use crate::arrow::FloatSeries;
+use crate::time::{datetime_to_epoch, timedelta_to_seconds};
+
+/// Dict of `{parameter: {"central": ..., "dispersion": ...}}`.
+fn summary_dict<'py>(py: Python<'py>, summary: &IhaSummary) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (j, name) in IHA_PARAMETERS.iter().enumerate() {
+        let entry = PyDict::new(py);
+        entry.set_item("central", summary.central[j])?;
+        entry.set_item("dispersion", summary.dispersion[j])?;
+        dict.set_item(name, entry)?;
+    }
+    Ok(dict)
+}
+
+/// Dict of `{parameter: value}`.
+fn parameter_dict<'py>(py: Python<'py>, values: &[f64]) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (name, value) in IHA_PARAMETERS.iter().zip(values) {
+        dict.set_item(name, value)?;
+    }
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (flows, start, impact_year=None, statistic="nonparametric", year_start_month=10))]
+pub fn iha<'py>(
+    py: Python<'py>,
+    flows: FloatSeries,
+    start: Bound<'py, PyDateTime>,
+    impact_year: Option<i64>,
+    statistic: &str,
+    year_start_month: u32,
+) -> PyResult<Bound<'py, PyDict>> {
+    let statistic = IhaStatistic::parse(statistic)?;
+    let start_day = (datetime_to_epoch(&start) / 86_400.0).floor() as i64;
+    let result = py.allow_threads(|| {
+        iha_rs(
+            &flows.0,
+            start_day,
+            year_start_month,
+            impact_year,
+            statistic,
+        )
+    })?;
+    let dict = PyDict::new(py);
+    dict.set_item("years", &result.years)?;
+    let parameters = PyDict::new(py);
+    for (name, series) in IHA_PARAMETERS.iter().zip(&result.parameters) {
+        parameters.set_item(name, series)?;
+    }
+    dict.set_item("parameters", parameters)?;
+    match (&result.post, &result.deviation, &result.alteration) {
+        (Some(post), Some(deviation), Some(alteration)) => {
+            dict.set_item("pre", summary_dict(py, &result.pre)?)?;
+            dict.set_item("post", summary_dict(py, post)?)?;
+            dict.set_item("deviation", parameter_dict(py, deviation)?)?;
+            dict.set_item("alteration", parameter_dict(py, alteration)?)?;
+        }
+        _ => dict.set_item("summary", summary_dict(py, &result.pre)?)?,
+    }
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (flows, peak, baseflow=0.0))]
+pub fn scale_to_peak(flows: FloatSeries, peak: f64, baseflow: f64) -> PyResult<Vec<f64>> {
+    Ok(scale_peak(&flows.0, peak, baseflow)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (flows, volume, time_step, baseflow=0.0))]
+pub fn scale_to_volume(
+    flows: FloatSeries,
+    volume: f64,
+    time_step: Bound<'_, PyDelta>,
+    baseflow: f64,
+) -> PyResult<Vec<f64>> {
+    let dt = timedelta_to_seconds(&time_step);
+    Ok(scale_volume(&flows.0, volume, dt, baseflow)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (durations, flows, time_step, pattern=None, peak_position=0.5))]
+pub fn balanced_hydrograph(
+    durations: Vec<Bound<'_, PyDelta>>,
+    flows: Vec<f64>,
+    time_step: Bound<'_, PyDelta>,
+    pattern: Option<FloatSeries>,
+    peak_position: f64,
+) -> PyResult<Vec<f64>> {
+    let dt = timedelta_to_seconds(&time_step);
+    let steps = durations
+        .iter()
+        .map(|d| {
+            let n = timedelta_to_seconds(d) / dt;
+            if (n - n.round()).abs() > 1e-9 * n.max(1.0) || n.round() < 1.0 {
+                Err(PyValueError::new_err(
+                    "Durations must be whole multiples of the time step.",
+                ))
+            } else {
+                Ok(n.round() as usize)
+            }
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(balanced_hydrograph_rs(
+        &steps,
+        &flows,
+        pattern.as_ref().map(|p| p.0.as_slice()),
+        peak_position,
+    )?)
+}
+
+#[pyfunction]
+pub fn lag_correlation<'py>(
+    py: Python<'py>,
+    upstream: FloatSeries,
+    downstream: FloatSeries,
+    max_lag: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let result = py.allow_threads(|| lag_correlation_rs(&upstream.0, &downstream.0, max_lag))?;
+    let dict = PyDict::new(py);
+    dict.set_item("lags", &result.lags)?;
+    dict.set_item("correlation", &result.correlation)?;
+    dict.set_item("best_lag", result.best_lag)?;
+    dict.set_item("best_correlation", result.best_correlation)?;
+    dict.set_item("refined_lag", result.refined_lag)?;
+    Ok(dict)
+}
+
+/// Dict of `{signature: value}`.
+fn signature_dict<'py>(py: Python<'py>, values: &[f64]) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (name, value) in SIGNATURES.iter().zip(values) {
+        dict.set_item(name, value)?;
+    }
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (flows, precipitation=None, time_step=None))]
+pub fn signatures<'py>(
+    py: Python<'py>,
+    flows: FloatSeries,
+    precipitation: Option<FloatSeries>,
+    time_step: Option<Bound<'py, PyDelta>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dt = time_step.map_or(86_400.0, |t| timedelta_to_seconds(&t));
+    let values = signatures_rs(&flows.0, precipitation.as_ref().map(|p| p.0.as_slice()), dt)?;
+    signature_dict(py, &values)
+}
+
+#[pyfunction]
+#[pyo3(signature = (observed, simulated, precipitation=None, time_step=None))]
+pub fn compare_signatures<'py>(
+    py: Python<'py>,
+    observed: FloatSeries,
+    simulated: FloatSeries,
+    precipitation: Option<FloatSeries>,
+    time_step: Option<Bound<'py, PyDelta>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dt = time_step.map_or(86_400.0, |t| timedelta_to_seconds(&t));
+    let comparison = compare_signatures_rs(
+        &observed.0,
+        &simulated.0,
+        precipitation.as_ref().map(|p| p.0.as_slice()),
+        dt,
+    )?;
+    let dict = PyDict::new(py);
+    dict.set_item("observed", signature_dict(py, &comparison.observed)?)?;
+    dict.set_item("simulated", signature_dict(py, &comparison.simulated)?)?;
+    dict.set_item(
+        "relative_error",
+        signature_dict(py, &comparison.relative_error)?,
+    )?;
+    Ok(dict)
+}
+
+#[pymodule]
+pub fn init_hydrograph(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(balanced_hydrograph, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_signatures, m)?)?;
+    m.add_function(wrap_pyfunction!(iha, m)?)?;
+    m.add_function(wrap_pyfunction!(lag_correlation, m)?)?;
+    m.add_function(wrap_pyfunction!(scale_to_peak, m)?)?;
+    m.add_function(wrap_pyfunction!(scale_to_volume, m)?)?;
+    m.add_function(wrap_pyfunction!(signatures, m)?)?;
+    Ok(())
+}
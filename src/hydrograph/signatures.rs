@@ -0,0 +1,194 @@
+//! Hydrologic signatures of flow series (Sawicz et al., 2011; Addor et
+//! al., 2018), for signature-based calibration and model diagnostics.
+//!
+//! High flows are those above nine times the median flow and low flows
+//! those below a fifth of the mean flow. The base flow is separated with
+//! the Lyne–Hollick filter (three passes, α = 0.925), which is calibrated
+//! for daily flows.
+
+use crate::ensemble::quantile_sorted;
+use crate::error::{Error, Result};
+
+/// Names of the signatures, in order.
+pub const SIGNATURES: [&str; 11] = [
+    "mean_flow",
+    "runoff_ratio",
+    "flashiness",
+    "baseflow_index",
+    "fdc_slope",
+    "q5",
+    "q95",
+    "high_flow_frequency",
+    "high_flow_duration",
+    "low_flow_frequency",
+    "low_flow_duration",
+];
+
+/// Seconds in a mean year, for event frequencies per year.
+const YEAR: f64 = 365.25 * 86_400.0;
+
+/// Lyne–Hollick filter parameter.
+const FILTER_ALPHA: f64 = 0.925;
+
+/// Signatures of a flow series, in the order of [`SIGNATURES`].
+///
+/// Frequencies are events per year and durations are mean event lengths in
+/// days. The runoff ratio is NaN without precipitation; the flow duration
+/// curve slope is NaN if either of its quantiles is not positive.
+pub fn signatures_rs(flows: &[f64], precipitation: Option<&[f64]>, dt: f64) -> Result<Vec<f64>> {
+    if flows.len() < 2 {
+        return Err(Error::Data(
+            "Signatures need at least two flow values.".into(),
+        ));
+    }
+    if flows.iter().any(|q| q.is_nan()) {
+        return Err(Error::Data(
+            "Signatures need a gap-free flow series; fill the gaps first.".into(),
+        ));
+    }
+    if dt.is_nan() || dt <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The time step must be positive.".into(),
+        ));
+    }
+    let total: f64 = flows.iter().sum();
+    let mean = total / flows.len() as f64;
+    let runoff_ratio = match precipitation {
+        Some(p) if p.len() != flows.len() => {
+            return Err(Error::Data(format!(
+                "Got {} precipitation values for {} flows.",
+                p.len(),
+                flows.len()
+            )))
+        }
+        Some(p) => {
+            // Only steps with both values count, so gaps in the
+            // precipitation do not bias the ratio.
+            let (q, p) = flows
+                .iter()
+                .zip(p)
+                .filter(|(_, p)| !p.is_nan())
+                .fold((0.0, 0.0), |(sq, sp), (q, p)| (sq + q, sp + p));
+            q / p
+        }
+        None => f64::NAN,
+    };
+    let flashiness =
+        flows.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>() / flows[1..].iter().sum::<f64>();
+    let baseflow_index = lyne_hollick(flows).iter().sum::<f64>() / total;
+
+    let mut sorted = flows.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    // Flows exceeded 33 % and 66 % of the time.
+    let (q33, q66) = (
+        quantile_sorted(&sorted, 0.67),
+        quantile_sorted(&sorted, 0.34),
+    );
+    let fdc_slope = if q33 > 0.0 && q66 > 0.0 {
+        (q33.ln() - q66.ln()) / (0.66 - 0.33)
+    } else {
+        f64::NAN
+    };
+    let median = quantile_sorted(&sorted, 0.5);
+    let years = flows.len() as f64 * dt / YEAR;
+    let (high_count, high_length) = events(flows, |q| q > 9.0 * median);
+    let (low_count, low_length) = events(flows, |q| q < 0.2 * mean);
+    let duration = |count: usize, length: usize| {
+        if count == 0 {
+            0.0
+        } else {
+            length as f64 / count as f64 * dt / 86_400.0
+        }
+    };
+    Ok(vec![
+        mean,
+        runoff_ratio,
+        flashiness,
+        baseflow_index,
+        fdc_slope,
+        quantile_sorted(&sorted, 0.05),
+        quantile_sorted(&sorted, 0.95),
+        high_count as f64 / years,
+        duration(high_count, high_length),
+        low_count as f64 / years,
+        duration(low_count, low_length),
+    ])
+}
+
+/// Signatures of `observed` and `simulated` flows and the relative error of
+/// each simulated signature, `(simulated - observed) / |observed|` (zero
+/// where the two agree exactly).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureComparison {
+    pub observed: Vec<f64>,
+    pub simulated: Vec<f64>,
+    pub relative_error: Vec<f64>,
+}
+
+/// Compares the signatures of `simulated` flows with those of `observed`
+/// flows over the same period.
+pub fn compare_signatures_rs(
+    observed: &[f64],
+    simulated: &[f64],
+    precipitation: Option<&[f64]>,
+    dt: f64,
+) -> Result<SignatureComparison> {
+    if observed.len() != simulated.len() {
+        return Err(Error::Data(format!(
+            "Got {} simulated values for {} observations.",
+            simulated.len(),
+            observed.len()
+        )));
+    }
+    let observed = signatures_rs(observed, precipitation, dt)?;
+    let simulated = signatures_rs(simulated, precipitation, dt)?;
+    let relative_error = observed
+        .iter()
+        .zip(&simulated)
+        .map(|(o, s)| if s == o { 0.0 } else { (s - o) / o.abs() })
+        .collect();
+    Ok(SignatureComparison {
+        observed,
+        simulated,
+        relative_error,
+    })
+}
+
+/// Base flow separated by three passes (forward, backward, forward) of the
+/// Lyne–Hollick digital filter.
+pub fn lyne_hollick(flows: &[f64]) -> Vec<f64> {
+    let mut baseflow = flows.to_vec();
+    for pass in 0..3 {
+        if pass == 1 {
+            baseflow.reverse();
+        }
+        let input = baseflow.clone();
+        let mut quick = 0.0;
+        for t in 1..input.len() {
+            quick = FILTER_ALPHA * quick + 0.5 * (1.0 + FILTER_ALPHA) * (input[t] - input[t - 1]);
+            quick = quick.clamp(0.0, input[t].max(0.0));
+            baseflow[t] = input[t] - quick;
+        }
+        if pass == 1 {
+            baseflow.reverse();
+        }
+    }
+    baseflow
+}
+
+/// Number of runs of consecutive values for which `inside` holds, and
+/// their total length.
+fn events(flows: &[f64], inside: impl Fn(f64) -> bool) -> (usize, usize) {
+    let (mut count, mut length, mut previous) = (0, 0, false);
+    for &q in flows {
+        let now = inside(q);
+        if now {
+            length += 1;
+            if !previous {
+                count += 1;
+            }
+        }
+        previous = now;
+    }
+    (count, length)
+}
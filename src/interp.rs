@@ -0,0 +1,22 @@
+/// Linear interpolation of `x` in the table `(xs, ys)`, with `xs` ascending.
+///
+/// Values outside the table are extrapolated from the end segments.
+pub fn interp(x: f64, xs: &[f64], ys: &[f64]) -> f64 {
+    match xs.len() {
+        0 => f64::NAN,
+        1 => ys[0],
+        n => {
+            let i = match xs.partition_point(|&v| v <= x) {
+                0 => 0,
+                i if i >= n => n - 2,
+                i => i - 1,
+            };
+            let (x0, x1, y0, y1) = (xs[i], xs[i + 1], ys[i], ys[i + 1]);
+            if x1 == x0 {
+                y0
+            } else {
+                y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+            }
+        }
+    }
+}
@@ -0,0 +1,545 @@
+use crate::datetime::{detect_format, format_datetime, parse_datetime};
+use crate::error::{Error, Result};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// A time-indexed table of flow series: one time per row (seconds since the
+/// Unix epoch) and one named column per series.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HydrographTable {
+    pub times: Vec<f64>,
+    pub columns: Vec<(String, Vec<f64>)>,
+}
+
+impl HydrographTable {
+    /// Values of the column called `name`.
+    pub fn column(&self, name: &str) -> Option<&[f64]> {
+        self.columns
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, values)| values.as_slice())
+    }
+
+    /// Interval between the first two rows, in seconds.
+    pub fn time_step(&self) -> Option<f64> {
+        match self.times.as_slice() {
+            [first, second, ..] => Some(second - first),
+            _ => None,
+        }
+    }
+}
+
+/// Options controlling how hydrograph CSV files are read and written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvOptions {
+    /// Field separator.
+    pub delimiter: char,
+    /// Name of the timestamp column; the first column when `None`.
+    pub time_column: Option<String>,
+    /// `strftime`-style timestamp format. When `None`, the format is detected
+    /// from the first row on read and `%Y-%m-%d %H:%M:%S` is used on write.
+    pub datetime_format: Option<String>,
+    /// `(csv column, table column)` pairs selecting and renaming value
+    /// columns. All non-time columns are read under their own names when
+    /// empty.
+    pub columns: Vec<(String, String)>,
+    /// Cell values read as missing (`NaN`), in addition to empty cells. The
+    /// first code is written for missing values.
+    pub missing_values: Vec<String>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            time_column: None,
+            datetime_format: None,
+            columns: Vec::new(),
+            missing_values: Vec::new(),
+        }
+    }
+}
+
+/// Parses hydrograph CSV text with a header row.
+pub fn parse_csv(text: &str, options: &CsvOptions) -> Result<HydrographTable> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| Error::Data("CSV input is empty.".into()))?;
+    let mut layout = Layout::new(header, options)?;
+    let mut table = layout.table();
+    for (line_number, line) in lines {
+        layout.push_row(&mut table, line_number, line, options)?;
+    }
+    Ok(table)
+}
+
+/// Splits `line` at `delimiter` into trimmed fields. Delimiters inside
+/// double quotes do not split, the quotes are removed and `""` inside them
+/// stands for one quote. `None` when a quote is left open.
+fn split_fields(line: &str, delimiter: char) -> Option<Vec<Cow<'_, str>>> {
+    if !line.contains('"') {
+        return Some(
+            line.split(delimiter)
+                .map(|f| Cow::Borrowed(f.trim()))
+                .collect(),
+        );
+    }
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    fields.push(field);
+    Some(
+        fields
+            .into_iter()
+            .map(|f| Cow::Owned(f.trim().to_string()))
+            .collect(),
+    )
+}
+
+/// Columns of a CSV file, resolved from its header row, and the timestamp
+/// format once known.
+struct Layout {
+    width: usize,
+    time_index: usize,
+    selected: Vec<(usize, String)>,
+    format: Option<String>,
+}
+
+impl Layout {
+    fn new(header: &str, options: &CsvOptions) -> Result<Self> {
+        let header = split_fields(header, options.delimiter).ok_or_else(|| {
+            Error::Data("The CSV header has an unterminated quoted field.".into())
+        })?;
+
+        let time_index = match &options.time_column {
+            None => 0,
+            Some(name) => header
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| Error::Data(format!("Time column `{name}` not found in header.")))?,
+        };
+        let selected: Vec<(usize, String)> = if options.columns.is_empty() {
+            header
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != time_index)
+                .map(|(i, h)| (i, h.to_string()))
+                .collect()
+        } else {
+            options
+                .columns
+                .iter()
+                .map(|(source, target)| {
+                    header
+                        .iter()
+                        .position(|h| h == source)
+                        .map(|i| (i, target.clone()))
+                        .ok_or_else(|| {
+                            Error::Data(format!("Column `{source}` not found in header."))
+                        })
+                })
+                .collect::<Result<_>>()?
+        };
+        Ok(Layout {
+            width: header.len(),
+            time_index,
+            selected,
+            format: options.datetime_format.clone(),
+        })
+    }
+
+    /// An empty table with the selected columns.
+    fn table(&self) -> HydrographTable {
+        HydrographTable {
+            times: Vec::new(),
+            columns: self
+                .selected
+                .iter()
+                .map(|(_, name)| (name.clone(), Vec::new()))
+                .collect(),
+        }
+    }
+
+    /// Parses `line`, the zero-based `line_number` of the file, into a row
+    /// of `table`.
+    fn push_row(
+        &mut self,
+        table: &mut HydrographTable,
+        line_number: usize,
+        line: &str,
+        options: &CsvOptions,
+    ) -> Result<()> {
+        let row_error = |msg: String| Error::Data(format!("Line {}: {msg}", line_number + 1));
+        let fields = split_fields(line, options.delimiter)
+            .ok_or_else(|| row_error("unterminated quoted field.".into()))?;
+        if fields.len() < self.width {
+            return Err(row_error(format!(
+                "expected {} fields, found {}.",
+                self.width,
+                fields.len()
+            )));
+        }
+
+        let stamp = &*fields[self.time_index];
+        let fmt = match &self.format {
+            Some(fmt) => fmt.as_str(),
+            None => {
+                let detected = detect_format(stamp).ok_or_else(|| {
+                    row_error(format!("cannot detect the datetime format of `{stamp}`."))
+                })?;
+                self.format.insert(detected.to_string())
+            }
+        };
+        let time = parse_datetime(stamp, fmt)
+            .ok_or_else(|| row_error(format!("`{stamp}` does not match format `{fmt}`.")))?;
+        table.times.push(time);
+
+        for ((index, name), (_, values)) in self.selected.iter().zip(table.columns.iter_mut()) {
+            let field = &*fields[*index];
+            let value = if field.is_empty() || options.missing_values.iter().any(|m| m == field) {
+                f64::NAN
+            } else {
+                field.parse().map_err(|_| {
+                    row_error(format!("`{field}` in column `{name}` is not a number."))
+                })?
+            };
+            values.push(value);
+        }
+        Ok(())
+    }
+}
+
+/// Reads a hydrograph CSV file as consecutive tables of at most `rows`
+/// rows, holding only one table in memory at a time. Iteration stops after
+/// the first error.
+pub struct CsvChunks<R> {
+    lines: std::io::Lines<R>,
+    line_number: usize,
+    layout: Layout,
+    options: CsvOptions,
+    rows: usize,
+    path: String,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for CsvChunks<R> {
+    type Item = Result<HydrographTable>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut table = self.layout.table();
+        while table.times.len() < self.rows {
+            let line = match self.lines.next() {
+                None => break,
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(Error::Io(format!("{}: {e}", self.path))));
+                }
+            };
+            self.line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Err(e) = self
+                .layout
+                .push_row(&mut table, self.line_number, &line, &self.options)
+            {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        if table.times.is_empty() {
+            self.done = true;
+            return None;
+        }
+        Some(Ok(table))
+    }
+}
+
+/// Opens a hydrograph CSV file for reading in tables of at most `rows`
+/// rows; see [`CsvChunks`].
+pub fn read_csv_chunks(
+    path: &Path,
+    options: &CsvOptions,
+    rows: usize,
+) -> Result<CsvChunks<BufReader<File>>> {
+    if rows == 0 {
+        return Err(Error::InvalidParameter(
+            "Chunks must hold at least one row.".into(),
+        ));
+    }
+    let io_error = |e: std::io::Error| Error::Io(format!("{}: {e}", path.display()));
+    let mut lines = BufReader::new(File::open(path).map_err(io_error)?).lines();
+    let mut line_number = 0;
+    let header = loop {
+        match lines.next() {
+            None => return Err(Error::Data("CSV input is empty.".into())),
+            Some(line) => {
+                let line = line.map_err(io_error)?;
+                if !line.trim().is_empty() {
+                    break line;
+                }
+                line_number += 1;
+            }
+        }
+    };
+    Ok(CsvChunks {
+        lines,
+        line_number,
+        layout: Layout::new(&header, options)?,
+        options: options.clone(),
+        rows,
+        path: path.display().to_string(),
+        done: false,
+    })
+}
+
+/// `name` as a CSV field, quoted when it holds the delimiter or quotes.
+fn quote(name: &str, delimiter: char) -> Cow<'_, str> {
+    if name.contains(delimiter) || name.contains('"') {
+        Cow::Owned(format!("\"{}\"", name.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// Formats `table` as CSV; the timestamp column is named after
+/// `options.time_column` (default `time`).
+pub fn format_csv(table: &HydrographTable, options: &CsvOptions) -> String {
+    let delimiter = options.delimiter;
+    let format = options
+        .datetime_format
+        .as_deref()
+        .unwrap_or("%Y-%m-%d %H:%M:%S");
+    let missing = options.missing_values.first().map_or("", String::as_str);
+
+    let mut out = String::with_capacity(table.times.len() * (20 + 12 * table.columns.len()));
+    out.push_str(&quote(
+        options.time_column.as_deref().unwrap_or("time"),
+        delimiter,
+    ));
+    for (name, _) in &table.columns {
+        out.push(delimiter);
+        out.push_str(&quote(name, delimiter));
+    }
+    out.push('\n');
+    for (row, &time) in table.times.iter().enumerate() {
+        out.push_str(&format_datetime(time, format));
+        for (_, values) in &table.columns {
+            out.push(delimiter);
+            match values.get(row) {
+                Some(value) if !value.is_nan() => {
+                    let _ = write!(out, "{value}");
+                }
+                _ => out.push_str(missing),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+pub fn read_csv(path: &Path, options: &CsvOptions) -> Result<HydrographTable> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| Error::Io(format!("{}: {e}", path.display())))?;
+    parse_csv(&text, options)
+}
+
+pub fn write_csv(path: &Path, table: &HydrographTable, options: &CsvOptions) -> Result<()> {
+    std::fs::write(path, format_csv(table, options))
+        .map_err(|e| Error::Io(format!("{}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: f64 = 3600.0;
+
+    #[test]
+    fn reads_columns_and_missing_values() {
+        let text = "\"time\",inflow,stage\n\
+                    2021-01-01 00:00,1.5,10\n\
+                    \n\
+                    2021-01-01 01:00,,11\n\
+                    2021-01-01 02:00,-999,12.5\n";
+        let options = CsvOptions {
+            missing_values: vec!["-999".into()],
+            ..CsvOptions::default()
+        };
+        let table = parse_csv(text, &options).unwrap();
+        let start = parse_datetime("2021-01-01", "%Y-%m-%d").unwrap();
+        assert_eq!(table.times, [start, start + HOUR, start + 2.0 * HOUR]);
+        assert_eq!(table.time_step(), Some(HOUR));
+        let inflow = table.column("inflow").unwrap();
+        assert_eq!(inflow[0], 1.5);
+        assert!(inflow[1].is_nan() && inflow[2].is_nan());
+        assert_eq!(table.column("stage").unwrap(), [10.0, 11.0, 12.5]);
+    }
+
+    #[test]
+    fn selects_renames_and_finds_the_time_column() {
+        let text = "a;b;date\n1;2;31.12.2020\n3;4;01.01.2021\n";
+        let options = CsvOptions {
+            delimiter: ';',
+            time_column: Some("date".into()),
+            datetime_format: Some("%d.%m.%Y".into()),
+            columns: vec![("b".into(), "flow".into())],
+            ..CsvOptions::default()
+        };
+        let table = parse_csv(text, &options).unwrap();
+        assert_eq!(table.columns, [("flow".to_string(), vec![2.0, 4.0])]);
+        assert_eq!(table.time_step(), Some(86_400.0));
+    }
+
+    #[test]
+    fn round_trips_through_the_writer() {
+        let table = HydrographTable {
+            times: vec![0.0, 900.0, 1800.0],
+            columns: vec![
+                ("q".into(), vec![1.25, f64::NAN, 3.0]),
+                ("h".into(), vec![0.5, 0.75, 1.0]),
+            ],
+        };
+        let options = CsvOptions {
+            missing_values: vec!["NA".into()],
+            ..CsvOptions::default()
+        };
+        let text = format_csv(&table, &options);
+        assert_eq!(
+            text,
+            "time,q,h\n1970-01-01 00:00:00,1.25,0.5\n1970-01-01 00:15:00,NA,0.75\n\
+             1970-01-01 00:30:00,3,1\n"
+        );
+        let read = parse_csv(&text, &options).unwrap();
+        assert_eq!(read.times, table.times);
+        assert_eq!(read.column("h"), table.column("h"));
+        assert!(read.column("q").unwrap()[1].is_nan());
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_rows() {
+        let options = CsvOptions::default();
+        for (text, expected) in [
+            (
+                "time,q\n2021-01-01,1\n2021-02-31,2\n",
+                "Line 3: `2021-02-31`",
+            ),
+            ("time,q\n2021-01-01,1\n2021-01-02,abc\n", "Line 3: `abc`"),
+            ("time,q\nnoon,1\n", "Line 2: cannot detect"),
+            ("time,q\n2021-01-01,1\n\n2021/01/02,2\n", "Line 4:"),
+        ] {
+            match parse_csv(text, &options) {
+                Err(Error::Data(msg)) => assert!(msg.starts_with(expected), "{msg}"),
+                other => panic!("{text:?}: {other:?}"),
+            }
+        }
+        assert!(matches!(parse_csv("\n\n", &options), Err(Error::Data(_))));
+        let missing = CsvOptions {
+            columns: vec![("flow".into(), "flow".into())],
+            ..CsvOptions::default()
+        };
+        assert!(matches!(
+            parse_csv("time,q\n2021-01-01,1\n", &missing),
+            Err(Error::Data(msg)) if msg.contains("`flow`")
+        ));
+    }
+
+    #[test]
+    fn quoted_fields_keep_their_delimiters() {
+        let text = "time,\"flow, m3/s\",\"stage \"\"A\"\"\"\n\
+                    \"01/02/2021, 10:00\",\"1.5\", 2\n";
+        let options = CsvOptions {
+            datetime_format: Some("%d/%m/%Y, %H:%M".into()),
+            ..CsvOptions::default()
+        };
+        let table = parse_csv(text, &options).unwrap();
+        assert_eq!(
+            table.times,
+            [parse_datetime("2021-02-01 10:00", "%Y-%m-%d %H:%M").unwrap()]
+        );
+        assert_eq!(table.column("flow, m3/s"), Some(&[1.5][..]));
+        assert_eq!(table.column("stage \"A\""), Some(&[2.0][..]));
+        // The writer quotes such names so that they read back.
+        let text = format_csv(&table, &CsvOptions::default());
+        assert!(
+            text.starts_with("time,\"flow, m3/s\",\"stage \"\"A\"\"\"\n"),
+            "{text}"
+        );
+        let read = parse_csv(&text, &CsvOptions::default()).unwrap();
+        assert_eq!(read, table);
+    }
+
+    #[test]
+    fn rejects_short_rows_and_open_quotes() {
+        let options = CsvOptions::default();
+        for (text, expected) in [
+            (
+                "time,q,h\n2021-01-01,1,2\n2021-01-02,3\n",
+                "Line 3: expected 3 fields, found 2.",
+            ),
+            (
+                "time,q\n2021-01-01,\"1\n",
+                "Line 2: unterminated quoted field.",
+            ),
+            ("time,\"q\n2021-01-01,1\n", "The CSV header"),
+        ] {
+            match parse_csv(text, &options) {
+                Err(Error::Data(msg)) => assert!(msg.starts_with(expected), "{msg}"),
+                other => panic!("{text:?}: {other:?}"),
+            }
+        }
+        // Empty trailing fields still count.
+        let table = parse_csv("time,q,h\n2021-01-01,1,\n", &options).unwrap();
+        assert!(table.column("h").unwrap()[0].is_nan());
+    }
+
+    #[test]
+    fn reads_chunks_with_the_same_rows() {
+        let path = std::env::temp_dir().join(format!("rustflow-csv-{}.csv", std::process::id()));
+        let mut text = String::from("time,q\n");
+        for hour in 0..10 {
+            text.push_str(&format!("2021-01-01 {hour:02}:00,{hour}\n"));
+        }
+        std::fs::write(&path, &text).unwrap();
+        let options = CsvOptions::default();
+        let whole = read_csv(&path, &options).unwrap();
+        let chunks: Vec<HydrographTable> = read_csv_chunks(&path, &options, 4)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            chunks.iter().map(|c| c.times.len()).collect::<Vec<_>>(),
+            [4, 4, 2]
+        );
+        let times: Vec<f64> = chunks.iter().flat_map(|c| c.times.clone()).collect();
+        assert_eq!(times, whole.times);
+        assert!(read_csv_chunks(&path, &options, 0).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! HEC-DSS pathname and interval conventions.
+//!
+//! A DSS pathname has six parts, `/A/B/C/D/E/F/`: A = project or basin,
+//! B = location, C = parameter (e.g. `FLOW`), D = start date of the block,
+//! E = time interval (e.g. `15Minute`), F = version or scenario.
+
+use crate::error::{Error, Result};
+use std::fmt;
+
+/// Regular-interval E parts and their lengths in seconds (DSS 7 spelling).
+const INTERVALS: [(&str, f64); 26] = [
+    ("1Second", 1.0),
+    ("2Second", 2.0),
+    ("3Second", 3.0),
+    ("4Second", 4.0),
+    ("5Second", 5.0),
+    ("6Second", 6.0),
+    ("10Second", 10.0),
+    ("15Second", 15.0),
+    ("20Second", 20.0),
+    ("30Second", 30.0),
+    ("1Minute", 60.0),
+    ("2Minute", 120.0),
+    ("3Minute", 180.0),
+    ("4Minute", 240.0),
+    ("5Minute", 300.0),
+    ("6Minute", 360.0),
+    ("10Minute", 600.0),
+    ("12Minute", 720.0),
+    ("15Minute", 900.0),
+    ("20Minute", 1200.0),
+    ("30Minute", 1800.0),
+    ("1Hour", 3600.0),
+    ("2Hour", 7200.0),
+    ("3Hour", 10800.0),
+    ("6Hour", 21600.0),
+    ("1Day", 86400.0),
+];
+
+/// A parsed DSS pathname.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DssPathname {
+    pub a: String,
+    pub b: String,
+    pub c: String,
+    pub d: String,
+    pub e: String,
+    pub f: String,
+}
+
+impl DssPathname {
+    /// Parses a `/A/B/C/D/E/F/` pathname. Parts other than the interval are
+    /// upper-cased as DSS does.
+    pub fn parse(pathname: &str) -> Result<Self> {
+        let inner = pathname
+            .trim()
+            .strip_prefix('/')
+            .and_then(|p| p.strip_suffix('/'))
+            .ok_or_else(|| {
+                Error::Data(format!(
+                    "DSS pathname `{pathname}` must start and end with `/`."
+                ))
+            })?;
+        let parts: Vec<&str> = inner.split('/').collect();
+        if parts.len() != 6 {
+            return Err(Error::Data(format!(
+                "DSS pathname `{pathname}` must have six parts, found {}.",
+                parts.len()
+            )));
+        }
+        let part = |i: usize| parts[i].trim().to_ascii_uppercase();
+        Ok(DssPathname {
+            a: part(0),
+            b: part(1),
+            c: part(2),
+            d: part(3),
+            e: parts[4].trim().to_string(),
+            f: part(5),
+        })
+    }
+}
+
+impl fmt::Display for DssPathname {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "/{}/{}/{}/{}/{}/{}/",
+            self.a, self.b, self.c, self.d, self.e, self.f
+        )
+    }
+}
+
+/// E part for a regular time step of `seconds`, if DSS defines one.
+pub fn interval_e_part(seconds: f64) -> Option<&'static str> {
+    INTERVALS
+        .iter()
+        .find(|(_, s)| (s - seconds).abs() < 1e-6)
+        .map(|(name, _)| *name)
+}
+
+/// Length in seconds of a regular-interval E part. Accepts DSS 7 names
+/// (`15Minute`) and DSS 6 abbreviations (`15MIN`, `1HOUR`), in any case.
+pub fn e_part_seconds(e_part: &str) -> Option<f64> {
+    let upper = e_part.trim().to_ascii_uppercase();
+    let digits = upper.chars().take_while(|c| c.is_ascii_digit()).count();
+    let (count, unit) = upper.split_at(digits);
+    let count: f64 = count.parse().ok()?;
+    let unit_seconds = match unit {
+        "SECOND" | "SEC" => 1.0,
+        "MINUTE" | "MIN" => 60.0,
+        "HOUR" => 3600.0,
+        "DAY" => 86400.0,
+        _ => return None,
+    };
+    let seconds = count * unit_seconds;
+    interval_e_part(seconds).map(|_| seconds)
+}
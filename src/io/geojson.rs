@@ -0,0 +1,479 @@
+//! Network configurations built from GeoJSON reach and catchment layers.
+//!
+//! | GeoJSON feature                 | rustflow node                          |
+//! | ------------------------------- | -------------------------------------- |
+//! | LineString / MultiLineString    | reach, Muskingum–Cunge by default      |
+//! | Polygon / MultiPolygon          | catchment, `subcatchment` by default   |
+//! | node with no outgoing reach     | outlet `junction`                      |
+//!
+//! Connectivity comes from attributes, not geometry: every reach names the
+//! node it leaves and the node it enters, and every catchment names the
+//! node it drains to. A reach drains to the reach leaving its end node;
+//! reaches meeting at a node are summed there. A feature's properties are
+//! copied into its node configuration, so element parameters, `inflow`
+//! and `rainfall` series names can be attributes of the layer.
+//!
+//! Reach lengths are measured along the polylines and catchment areas
+//! within the polygons, in the coordinate units of the unit system or, for
+//! geographic coordinates, on the sphere. A reach without a `type`
+//! property becomes a Muskingum reach with `k` and `x` from the Cunge
+//! (1969) matching of numerical and physical diffusion at a reference flow
+//! `q0`: `k = L / c` and `x = ½ (1 − q0 / (B S c L))` for celerity `c` and
+//! top width `B` at the normal depth of `q0`, bed slope `S` and length `L`.
+
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::log::warning;
+use crate::network::Network;
+use crate::reach_routing::muskingum::muskingum_cunge_rs;
+use crate::units::UnitSystem;
+use std::collections::HashMap;
+
+/// Mean Earth radius (m).
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Attribute names and geometry settings of a GeoJSON network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeoJsonOptions {
+    /// Property holding the feature id; the feature's own `id` is used when
+    /// the property is absent.
+    pub id_field: String,
+    /// Property naming the node a reach leaves.
+    pub from_field: String,
+    /// Property naming the node a reach enters or a catchment drains to.
+    pub to_field: String,
+    /// Coordinates are longitude and latitude in degrees.
+    pub geographic: bool,
+    /// Unit system of the configuration, and of planar coordinates.
+    pub units: UnitSystem,
+    /// Muskingum–Cunge reference flow of reaches without a
+    /// `reference_flow` property.
+    pub reference_flow: Option<f64>,
+    /// Largest gap allowed between the end of a reach and the start of the
+    /// reach it drains to; unchecked if `None`.
+    pub tolerance: Option<f64>,
+}
+
+impl Default for GeoJsonOptions {
+    fn default() -> Self {
+        GeoJsonOptions {
+            id_field: "id".into(),
+            from_field: "from_node".into(),
+            to_field: "to_node".into(),
+            geographic: false,
+            units: UnitSystem::Si,
+            reference_flow: None,
+            tolerance: None,
+        }
+    }
+}
+
+/// Text of a string or integer attribute.
+fn key(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) if n.fract() == 0.0 => Some(format!("{n:.0}")),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Coordinates of a GeoJSON position array.
+fn position(value: &Value) -> Option<(f64, f64)> {
+    match value.as_array()? {
+        [x, y, ..] => Some((x.as_f64()?, y.as_f64()?)),
+        _ => None,
+    }
+}
+
+/// Vertex lists of a nested coordinate array `depth` levels above the
+/// positions, flattened.
+fn lines(value: &Value, depth: usize) -> Option<Vec<Vec<(f64, f64)>>> {
+    if depth == 0 {
+        return Some(vec![value
+            .as_array()?
+            .iter()
+            .map(position)
+            .collect::<Option<_>>()?]);
+    }
+    let mut all = Vec::new();
+    for item in value.as_array()? {
+        all.extend(lines(item, depth - 1)?);
+    }
+    Some(all)
+}
+
+/// Projection of geographic coordinates to metres about latitude `lat0`,
+/// or the identity for planar coordinates in the length unit of `units`.
+struct Projection {
+    geographic: bool,
+    units: UnitSystem,
+    cos_lat0: f64,
+}
+
+impl Projection {
+    fn metres(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        if self.geographic {
+            let degree = EARTH_RADIUS * std::f64::consts::PI / 180.0;
+            (x * degree * self.cos_lat0, y * degree)
+        } else {
+            (self.units.length_to_si(x), self.units.length_to_si(y))
+        }
+    }
+
+    /// Distance between two points (m): great-circle for geographic
+    /// coordinates.
+    fn distance(&self, a: (f64, f64), b: (f64, f64)) -> f64 {
+        if self.geographic {
+            let (lat1, lat2) = (a.1.to_radians(), b.1.to_radians());
+            let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+                + lat1.cos() * lat2.cos() * ((b.0 - a.0).to_radians() / 2.0).sin().powi(2);
+            2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+        } else {
+            let (a, b) = (self.metres(a), self.metres(b));
+            (b.0 - a.0).hypot(b.1 - a.1)
+        }
+    }
+}
+
+struct Reach {
+    id: String,
+    from: String,
+    to: String,
+    start: (f64, f64),
+    end: (f64, f64),
+    /// Length along the polyline (m).
+    length: f64,
+    properties: Value,
+}
+
+struct Catchment {
+    id: String,
+    to: String,
+    /// Area inside the polygon (m²).
+    area: f64,
+    properties: Value,
+}
+
+/// Muskingum `k` (s) and `x` of a reach of `length` (m) by the
+/// Muskingum–Cunge method.
+fn cunge(reach: &Reach, options: &GeoJsonOptions) -> Result<(f64, f64)> {
+    let p = &reach.properties;
+    let field = |name: &str| {
+        p.opt_f64_field(name)?.ok_or_else(|| {
+            Error::Data(format!(
+                "Reach `{}` has no `type`, so it needs the `{name}` property for Muskingum–Cunge \
+                 routing.",
+                reach.id
+            ))
+        })
+    };
+    let units = options.units;
+    let channel = TrapezoidalChannel::from_units(
+        field("bottom_width")?,
+        p.opt_f64_field("side_slope")?.unwrap_or(0.0),
+        field("n")?,
+        field("slope")?,
+        units,
+    )
+    .map_err(|e| Error::Data(format!("Reach `{}`: {e}", reach.id)))?;
+    let q0 = match (p.opt_f64_field("reference_flow")?, options.reference_flow) {
+        (Some(q), _) | (None, Some(q)) => units.discharge_to_si(q),
+        (None, None) => field("reference_flow")?,
+    };
+    if q0.is_nan() || q0 <= 0.0 || reach.length <= 0.0 {
+        return Err(Error::Data(format!(
+            "Reach `{}` needs a positive reference flow and length.",
+            reach.id
+        )));
+    }
+    let (k, x) = muskingum_cunge_rs(&channel, reach.length, q0)?;
+    if x < 0.0 {
+        warning!(
+            "Reach `{}`: Muskingum–Cunge x = {x:.3} is negative; using 0. Split the reach or \
+             route it with a kinematic wave.",
+            reach.id
+        );
+    }
+    Ok((k, x.clamp(0.0, 0.5)))
+}
+
+/// Converts a GeoJSON FeatureCollection of reach polylines and catchment
+/// polygons into a network configuration accepted by
+/// [`Network::from_value`], and checks that it builds.
+pub fn network_from_geojson(geojson: &Value, options: &GeoJsonOptions) -> Result<Value> {
+    let features = match geojson.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => geojson
+            .get("features")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::Data("The FeatureCollection has no `features` array.".into()))?,
+        _ => {
+            return Err(Error::Data(
+                "The network must be a GeoJSON FeatureCollection.".into(),
+            ))
+        }
+    };
+
+    // Geometries, in input order, before projecting.
+    let mut raw_reaches = Vec::new();
+    let mut raw_catchments = Vec::new();
+    for (i, feature) in features.iter().enumerate() {
+        let properties = match feature.get("properties") {
+            Some(p @ Value::Object(_)) => p.clone(),
+            _ => Value::Object(Vec::new()),
+        };
+        let id = key(properties.get(&options.id_field))
+            .or_else(|| key(feature.get("id")))
+            .ok_or_else(|| {
+                Error::Data(format!(
+                    "Feature {} has no `{}` property or feature id.",
+                    i + 1,
+                    options.id_field
+                ))
+            })?;
+        let geometry = feature
+            .get("geometry")
+            .ok_or_else(|| Error::Data(format!("Feature `{id}` has no geometry.")))?;
+        let kind = geometry.get("type").and_then(Value::as_str).unwrap_or("");
+        let depth = match kind {
+            "LineString" | "Polygon" => 0,
+            "MultiLineString" | "MultiPolygon" => 1,
+            _ => {
+                return Err(Error::Data(format!(
+                    "Feature `{id}` has a `{kind}` geometry; only (multi)line strings and \
+                     (multi)polygons are supported."
+                )))
+            }
+        };
+        let polygon = kind.ends_with("Polygon");
+        let coordinates = geometry
+            .get("coordinates")
+            .and_then(|c| lines(c, depth + polygon as usize))
+            .filter(|l| !l.is_empty() && l.iter().all(|p| p.len() >= 2))
+            .ok_or_else(|| Error::Data(format!("Feature `{id}` has invalid coordinates.")))?;
+        let attribute = |field: &str| {
+            key(properties.get(field))
+                .ok_or_else(|| Error::Data(format!("Feature `{id}` has no `{field}` property.")))
+        };
+        if polygon {
+            let to = attribute(&options.to_field)?;
+            // Rings of each part: the first is the exterior.
+            let parts = geometry
+                .get("coordinates")
+                .and_then(Value::as_array)
+                .map(|items| {
+                    if depth == 0 {
+                        vec![coordinates.clone()]
+                    } else {
+                        items.iter().filter_map(|part| lines(part, 1)).collect()
+                    }
+                })
+                .unwrap_or_default();
+            raw_catchments.push((id, to, parts, properties));
+        } else {
+            let from = attribute(&options.from_field)?;
+            let to = attribute(&options.to_field)?;
+            if from == to {
+                return Err(Error::Data(format!(
+                    "Reach `{id}` starts and ends at node `{from}`."
+                )));
+            }
+            raw_reaches.push((id, from, to, coordinates, properties));
+        }
+    }
+    if raw_reaches.is_empty() && raw_catchments.is_empty() {
+        return Err(Error::Data("The FeatureCollection has no features.".into()));
+    }
+
+    // Geographic areas are projected about the mean latitude of the layer.
+    let latitudes: Vec<f64> = raw_reaches
+        .iter()
+        .flat_map(|r| r.3.iter().flatten())
+        .chain(
+            raw_catchments
+                .iter()
+                .flat_map(|c| c.2.iter().flatten().flatten()),
+        )
+        .map(|p| p.1)
+        .collect();
+    let projection = Projection {
+        geographic: options.geographic,
+        units: options.units,
+        cos_lat0: (latitudes.iter().sum::<f64>() / latitudes.len().max(1) as f64)
+            .to_radians()
+            .cos(),
+    };
+
+    let reaches: Vec<Reach> = raw_reaches
+        .into_iter()
+        .map(|(id, from, to, parts, properties)| {
+            let length = parts
+                .iter()
+                .flat_map(|line| line.windows(2))
+                .map(|w| projection.distance(w[0], w[1]))
+                .sum();
+            let start = parts[0][0];
+            let end = *parts[parts.len() - 1].last().unwrap_or(&start);
+            Reach {
+                id,
+                from,
+                to,
+                start,
+                end,
+                length,
+                properties,
+            }
+        })
+        .collect();
+    let catchments: Vec<Catchment> = raw_catchments
+        .into_iter()
+        .map(|(id, to, parts, properties)| {
+            let area = parts
+                .iter()
+                .flat_map(|rings| rings.iter().enumerate())
+                .map(|(r, ring)| {
+                    let ring: Vec<(f64, f64)> =
+                        ring.iter().map(|&p| projection.metres(p)).collect();
+                    let mut twice = 0.0;
+                    let mut previous = ring[ring.len() - 1];
+                    for &p in &ring {
+                        twice += previous.0 * p.1 - p.0 * previous.1;
+                        previous = p;
+                    }
+                    let area = 0.5 * f64::abs(twice);
+                    if r == 0 {
+                        area
+                    } else {
+                        -area
+                    }
+                })
+                .sum();
+            Catchment {
+                id,
+                to,
+                area,
+                properties,
+            }
+        })
+        .collect();
+
+    // The reach leaving each node.
+    let mut leaving: HashMap<&str, &Reach> = HashMap::new();
+    for reach in &reaches {
+        if let Some(other) = leaving.insert(&reach.from, reach) {
+            return Err(Error::Data(format!(
+                "Reaches `{}` and `{}` both leave node `{}`; flow splits are not supported.",
+                other.id, reach.id, reach.from
+            )));
+        }
+    }
+    let known: std::collections::HashSet<&str> = reaches
+        .iter()
+        .flat_map(|r| [r.from.as_str(), r.to.as_str()])
+        .collect();
+    if let Some(c) = catchments
+        .iter()
+        .find(|c| !reaches.is_empty() && !known.contains(c.to.as_str()))
+    {
+        return Err(Error::Data(format!(
+            "Catchment `{}` drains to node `{}`, which no reach starts or ends at.",
+            c.id, c.to
+        )));
+    }
+    if let Some(tolerance) = options.tolerance {
+        for reach in &reaches {
+            if let Some(next) = leaving.get(reach.to.as_str()) {
+                let gap = options
+                    .units
+                    .length_from_si(projection.distance(reach.end, next.start));
+                if gap > tolerance {
+                    return Err(Error::Data(format!(
+                        "Reach `{}` ends {gap:.3} from the start of reach `{}` at node `{}`.",
+                        reach.id, next.id, reach.to
+                    )));
+                }
+            }
+        }
+    }
+
+    let units_name = match options.units {
+        UnitSystem::Si => "si",
+        UnitSystem::Us => "us",
+    };
+    let connectivity = [
+        options.id_field.as_str(),
+        options.from_field.as_str(),
+        options.to_field.as_str(),
+    ];
+    let node = |id: &str, properties: &Value| {
+        let mut value = Value::Object(vec![("id".to_string(), id.into())]);
+        if let Value::Object(entries) = properties {
+            for (k, v) in entries {
+                if !connectivity.contains(&k.as_str()) {
+                    value.insert(k, v.clone());
+                }
+            }
+        }
+        value
+    };
+    // The node receiving flow arriving at graph node `to`.
+    let receiver = |to: &str| leaving.get(to).map_or(to, |r| r.id.as_str()).to_string();
+
+    let mut nodes = Vec::new();
+    for c in &catchments {
+        let mut value = node(&c.id, &c.properties);
+        if value.get("type").is_none() {
+            value.insert("type", "subcatchment".into());
+        }
+        if value.get("units").is_none() {
+            value.insert("units", units_name.into());
+        }
+        if value.get("area").is_none() {
+            value.insert("area", options.units.land_area_from_si(c.area).into());
+        }
+        value.insert("downstream", receiver(&c.to).into());
+        nodes.push(value);
+    }
+    for reach in &reaches {
+        let mut value = node(&reach.id, &reach.properties);
+        let length = options.units.length_from_si(reach.length);
+        if value.get("type").is_none() {
+            let (k, x) = cunge(reach, options)?;
+            value.insert("type", "muskingum".into());
+            value.insert("k", k.into());
+            value.insert("x", x.into());
+        }
+        if value.get("units").is_none() {
+            value.insert("units", units_name.into());
+        }
+        if value.get("length").is_none() {
+            value.insert("length", length.into());
+        }
+        value.insert("downstream", receiver(&reach.to).into());
+        nodes.push(value);
+    }
+    // Outlet junctions, in order of first appearance.
+    let mut outlets: Vec<&str> = Vec::new();
+    for to in reaches
+        .iter()
+        .map(|r| r.to.as_str())
+        .chain(catchments.iter().map(|c| c.to.as_str()))
+    {
+        if !leaving.contains_key(to) && !outlets.contains(&to) {
+            outlets.push(to);
+        }
+    }
+    for outlet in outlets {
+        let mut value = Value::Object(Vec::new());
+        value.insert("id", outlet.into());
+        value.insert("type", "junction".into());
+        nodes.push(value);
+    }
+
+    let mut config = Value::Object(Vec::new());
+    config.insert("units", units_name.into());
+    config.insert("nodes", Value::Array(nodes));
+    Network::from_value(&config)?;
+    Ok(config)
+}
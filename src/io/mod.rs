@@ -0,0 +1,9 @@
+pub mod csv;
+pub mod dss;
+pub mod geojson;
+pub mod netcdf;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod report;
+pub mod series_file;
+pub mod swmm;
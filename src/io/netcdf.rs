@@ -0,0 +1,590 @@
+//! Writer for the NetCDF classic data model (64-bit offset format, CDF-2),
+//! readable by netCDF-C, xarray and any CF-aware tool.
+
+use crate::error::{Error, Result};
+use crate::network::NetworkResult;
+use crate::units::UnitSystem;
+use std::path::Path;
+
+const NC_DIMENSION: u32 = 0x0A;
+const NC_VARIABLE: u32 = 0x0B;
+const NC_ATTRIBUTE: u32 = 0x0C;
+const NC_CHAR: u32 = 2;
+const NC_DOUBLE: u32 = 6;
+
+/// Attribute value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NcAttr {
+    Text(String),
+    Double(Vec<f64>),
+}
+
+/// Variable data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NcData {
+    Char(Vec<u8>),
+    Double(Vec<f64>),
+}
+
+impl NcData {
+    fn nc_type(&self) -> u32 {
+        match self {
+            NcData::Char(_) => NC_CHAR,
+            NcData::Double(_) => NC_DOUBLE,
+        }
+    }
+
+    fn byte_len(&self) -> usize {
+        match self {
+            NcData::Char(v) => v.len(),
+            NcData::Double(v) => v.len() * 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct NcVariable {
+    name: String,
+    dims: Vec<usize>,
+    attrs: Vec<(String, NcAttr)>,
+    data: NcData,
+}
+
+/// In-memory NetCDF dataset with fixed-size dimensions.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NcFile {
+    dims: Vec<(String, usize)>,
+    attrs: Vec<(String, NcAttr)>,
+    vars: Vec<NcVariable>,
+}
+
+fn pad4(n: usize) -> usize {
+    n.div_ceil(4) * 4
+}
+
+fn put_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_be_bytes());
+}
+
+fn put_name(out: &mut Vec<u8>, name: &str) {
+    put_u32(out, name.len() as u32);
+    out.extend_from_slice(name.as_bytes());
+    out.resize(pad4(out.len()), 0);
+}
+
+fn put_attrs(out: &mut Vec<u8>, attrs: &[(String, NcAttr)]) {
+    if attrs.is_empty() {
+        put_u32(out, 0);
+        put_u32(out, 0);
+        return;
+    }
+    put_u32(out, NC_ATTRIBUTE);
+    put_u32(out, attrs.len() as u32);
+    for (name, value) in attrs {
+        put_name(out, name);
+        match value {
+            NcAttr::Text(text) => {
+                put_u32(out, NC_CHAR);
+                put_u32(out, text.len() as u32);
+                out.extend_from_slice(text.as_bytes());
+                out.resize(pad4(out.len()), 0);
+            }
+            NcAttr::Double(values) => {
+                put_u32(out, NC_DOUBLE);
+                put_u32(out, values.len() as u32);
+                for v in values {
+                    out.extend_from_slice(&v.to_be_bytes());
+                }
+            }
+        }
+    }
+}
+
+impl NcFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a dimension and returns its id.
+    pub fn add_dim(&mut self, name: &str, len: usize) -> usize {
+        self.dims.push((name.to_string(), len));
+        self.dims.len() - 1
+    }
+
+    pub fn add_global_attr(&mut self, name: &str, value: NcAttr) {
+        self.attrs.push((name.to_string(), value));
+    }
+
+    /// Adds a variable over the dimension ids `dims` (row-major). The data
+    /// length must equal the product of the dimension lengths, none of
+    /// which may be zero: a zero length marks the unlimited record
+    /// dimension in the classic format, which this writer does not lay out.
+    pub fn add_var(
+        &mut self,
+        name: &str,
+        dims: &[usize],
+        data: NcData,
+        attrs: Vec<(&str, NcAttr)>,
+    ) -> Result<()> {
+        if let Some(&d) = dims.iter().find(|&&d| self.dims[d].1 == 0) {
+            return Err(Error::Data(format!(
+                "Variable `{name}` uses the empty dimension `{}`.",
+                self.dims[d].0
+            )));
+        }
+        let expected: usize = dims.iter().map(|&d| self.dims[d].1).product();
+        let len = match &data {
+            NcData::Char(v) => v.len(),
+            NcData::Double(v) => v.len(),
+        };
+        if len != expected {
+            return Err(Error::Data(format!(
+                "Variable `{name}` has {len} values but its dimensions hold {expected}."
+            )));
+        }
+        self.vars.push(NcVariable {
+            name: name.to_string(),
+            dims: dims.to_vec(),
+            attrs: attrs.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            data,
+        });
+        Ok(())
+    }
+
+    fn header(&self, begins: &[u64]) -> Vec<u8> {
+        let mut out = b"CDF\x02".to_vec();
+        put_u32(&mut out, 0);
+        if self.dims.is_empty() {
+            put_u32(&mut out, 0);
+            put_u32(&mut out, 0);
+        } else {
+            put_u32(&mut out, NC_DIMENSION);
+            put_u32(&mut out, self.dims.len() as u32);
+            for (name, len) in &self.dims {
+                put_name(&mut out, name);
+                put_u32(&mut out, *len as u32);
+            }
+        }
+        put_attrs(&mut out, &self.attrs);
+        if self.vars.is_empty() {
+            put_u32(&mut out, 0);
+            put_u32(&mut out, 0);
+        } else {
+            put_u32(&mut out, NC_VARIABLE);
+            put_u32(&mut out, self.vars.len() as u32);
+            for (var, begin) in self.vars.iter().zip(begins) {
+                put_name(&mut out, &var.name);
+                put_u32(&mut out, var.dims.len() as u32);
+                for &d in &var.dims {
+                    put_u32(&mut out, d as u32);
+                }
+                put_attrs(&mut out, &var.attrs);
+                put_u32(&mut out, var.data.nc_type());
+                put_u32(
+                    &mut out,
+                    pad4(var.data.byte_len()).min(u32::MAX as usize) as u32,
+                );
+                out.extend_from_slice(&begin.to_be_bytes());
+            }
+        }
+        out
+    }
+
+    /// Encodes the dataset.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // The header length does not depend on the offset values.
+        let header_len = self.header(&vec![0; self.vars.len()]).len();
+        let mut begins = Vec::with_capacity(self.vars.len());
+        let mut offset = header_len as u64;
+        for var in &self.vars {
+            begins.push(offset);
+            offset += pad4(var.data.byte_len()) as u64;
+        }
+        let mut out = self.header(&begins);
+        out.reserve(offset as usize - header_len);
+        for var in &self.vars {
+            match &var.data {
+                NcData::Char(v) => out.extend_from_slice(v),
+                NcData::Double(v) => {
+                    for x in v {
+                        out.extend_from_slice(&x.to_be_bytes());
+                    }
+                }
+            }
+            out.resize(pad4(out.len()), 0);
+        }
+        out
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_bytes())
+            .map_err(|e| Error::Io(format!("{}: {e}", path.display())))
+    }
+}
+
+/// Transposes per-node series into a row-major `(time, node)` block.
+fn time_by_node(series: &[Vec<f64>], n_times: usize) -> Vec<f64> {
+    let mut out = Vec::with_capacity(n_times * series.len());
+    for t in 0..n_times {
+        out.extend(series.iter().map(|s| s.get(t).copied().unwrap_or(f64::NAN)));
+    }
+    out
+}
+
+/// Builds a CF-1.8 `timeSeries` dataset of network results. `times` are
+/// seconds since the Unix epoch; `units` names the unit system of the flows
+/// and stages.
+pub fn network_result_to_netcdf(
+    result: &NetworkResult,
+    times: &[f64],
+    units: UnitSystem,
+    title: &str,
+) -> Result<NcFile> {
+    let n_times = result.outflow.first().map_or(0, Vec::len);
+    if n_times == 0 {
+        return Err(Error::Data(
+            "The network result has no time steps to write.".into(),
+        ));
+    }
+    if times.len() != n_times {
+        return Err(Error::Data(format!(
+            "{} times were given for {n_times} simulated steps.",
+            times.len()
+        )));
+    }
+    let (flow_units, storage_units, length_units) = match units {
+        UnitSystem::Si => ("m3 s-1", "m3", "m"),
+        UnitSystem::Us => ("ft3 s-1", "ft3", "ft"),
+    };
+    let text = |s: &str| NcAttr::Text(s.to_string());
+    let fill = || NcAttr::Double(vec![f64::NAN]);
+
+    let mut nc = NcFile::new();
+    let time = nc.add_dim("time", n_times);
+    let node = nc.add_dim("node", result.node_ids.len());
+    let strlen = nc.add_dim(
+        "name_strlen",
+        result
+            .node_ids
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max(1),
+    );
+    nc.add_global_attr("Conventions", text("CF-1.8"));
+    nc.add_global_attr("featureType", text("timeSeries"));
+    nc.add_global_attr("title", text(title));
+    nc.add_global_attr(
+        "source",
+        text(concat!("rustflow ", env!("CARGO_PKG_VERSION"))),
+    );
+
+    nc.add_var(
+        "time",
+        &[time],
+        NcData::Double(times.to_vec()),
+        vec![
+            ("standard_name", text("time")),
+            ("long_name", text("time")),
+            ("units", text("seconds since 1970-01-01 00:00:00")),
+            ("calendar", text("standard")),
+            ("axis", text("T")),
+        ],
+    )?;
+    let width = nc.dims[strlen].1;
+    let mut names = Vec::with_capacity(width * result.node_ids.len());
+    for id in &result.node_ids {
+        let start = names.len();
+        names.extend_from_slice(id.as_bytes());
+        names.resize(start + width, 0);
+    }
+    nc.add_var(
+        "node_id",
+        &[node, strlen],
+        NcData::Char(names),
+        vec![
+            ("cf_role", text("timeseries_id")),
+            ("long_name", text("node identifier")),
+        ],
+    )?;
+
+    let variables = [
+        (
+            "inflow",
+            &result.inflow,
+            "",
+            "inflow to the node",
+            flow_units,
+        ),
+        (
+            "outflow",
+            &result.outflow,
+            "water_volume_transport_in_river_channel",
+            "outflow from the node",
+            flow_units,
+        ),
+        (
+            "storage",
+            &result.storage,
+            "",
+            "water stored in the node element",
+            storage_units,
+        ),
+        (
+            "stage",
+            &result.stage,
+            "water_surface_height_above_reference_datum",
+            "water surface elevation of the node element",
+            length_units,
+        ),
+    ];
+    for (name, series, standard_name, long_name, unit) in variables {
+        let mut attrs = vec![
+            ("long_name", text(long_name)),
+            ("units", text(unit)),
+            ("coordinates", text("node_id")),
+            ("_FillValue", fill()),
+        ];
+        if !standard_name.is_empty() {
+            attrs.insert(0, ("standard_name", text(standard_name)));
+        }
+        nc.add_var(
+            name,
+            &[time, node],
+            NcData::Double(time_by_node(series, n_times)),
+            attrs,
+        )?;
+    }
+    Ok(nc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rustflow-{name}-{}.nc", std::process::id()))
+    }
+
+    struct Cursor<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+    }
+
+    impl Cursor<'_> {
+        fn take(&mut self, n: usize) -> &[u8] {
+            let slice = &self.bytes[self.pos..self.pos + n];
+            self.pos += n;
+            slice
+        }
+
+        fn u32(&mut self) -> u32 {
+            u32::from_be_bytes(self.take(4).try_into().unwrap())
+        }
+
+        fn name(&mut self) -> String {
+            let n = self.u32() as usize;
+            let name = String::from_utf8(self.take(n).to_vec()).unwrap();
+            self.take(pad4(n) - n);
+            name
+        }
+
+        /// The length of a tagged list, which must be `tag` unless empty.
+        fn list(&mut self, tag: u32) -> usize {
+            let (found, n) = (self.u32(), self.u32() as usize);
+            assert!(found == tag || (found == 0 && n == 0), "{found:#x}");
+            n
+        }
+
+        fn attrs(&mut self) -> Vec<(String, NcAttr)> {
+            (0..self.list(NC_ATTRIBUTE))
+                .map(|_| {
+                    let name = self.name();
+                    let (kind, n) = (self.u32(), self.u32() as usize);
+                    let value = match kind {
+                        NC_CHAR => {
+                            let text = String::from_utf8(self.take(n).to_vec()).unwrap();
+                            self.take(pad4(n) - n);
+                            NcAttr::Text(text)
+                        }
+                        NC_DOUBLE => NcAttr::Double(doubles(self.take(8 * n))),
+                        other => panic!("attribute type {other}"),
+                    };
+                    (name, value)
+                })
+                .collect()
+        }
+    }
+
+    fn doubles(bytes: &[u8]) -> Vec<f64> {
+        bytes
+            .chunks_exact(8)
+            .map(|b| f64::from_be_bytes(b.try_into().unwrap()))
+            .collect()
+    }
+
+    /// Reads the encoding back, checking the layout rules that netCDF
+    /// readers rely on along the way.
+    fn decode(bytes: &[u8]) -> NcFile {
+        let mut cursor = Cursor { bytes, pos: 0 };
+        assert_eq!(cursor.take(4), b"CDF\x02");
+        assert_eq!(cursor.u32(), 0, "record count");
+        let dims: Vec<(String, usize)> = (0..cursor.list(NC_DIMENSION))
+            .map(|_| (cursor.name(), cursor.u32() as usize))
+            .collect();
+        let attrs = cursor.attrs();
+        let mut vars = Vec::new();
+        for _ in 0..cursor.list(NC_VARIABLE) {
+            let name = cursor.name();
+            let ids: Vec<usize> = (0..cursor.u32()).map(|_| cursor.u32() as usize).collect();
+            let var_attrs = cursor.attrs();
+            let kind = cursor.u32();
+            let vsize = cursor.u32() as usize;
+            let begin = u64::from_be_bytes(cursor.take(8).try_into().unwrap()) as usize;
+            let count: usize = ids.iter().map(|&d| dims[d].1).product();
+            let data = match kind {
+                NC_CHAR => NcData::Char(bytes[begin..begin + count].to_vec()),
+                NC_DOUBLE => NcData::Double(doubles(&bytes[begin..begin + 8 * count])),
+                other => panic!("variable type {other}"),
+            };
+            assert_eq!(begin % 4, 0);
+            assert_eq!(vsize, pad4(data.byte_len()));
+            vars.push(NcVariable {
+                name,
+                dims: ids,
+                attrs: var_attrs,
+                data,
+            });
+        }
+        // Data follows the header directly, variable after variable.
+        let end = vars.iter().map(|v| pad4(v.data.byte_len())).sum::<usize>();
+        assert_eq!(cursor.pos + end, bytes.len());
+        NcFile { dims, attrs, vars }
+    }
+
+    fn sample() -> NcFile {
+        let mut nc = NcFile::new();
+        let x = nc.add_dim("x", 3);
+        let label = nc.add_dim("label", 5);
+        nc.add_global_attr("title", NcAttr::Text("odd length".into()));
+        nc.add_var(
+            "depth",
+            &[x],
+            NcData::Double(vec![0.5, -1.0, 1e10]),
+            vec![("valid_range", NcAttr::Double(vec![-2.0, 2e10]))],
+        )
+        .unwrap();
+        nc.add_var("name", &[label], NcData::Char(b"abc\0\0".to_vec()), vec![])
+            .unwrap();
+        nc.add_var("both", &[x, label], NcData::Double(vec![2.0; 15]), vec![])
+            .unwrap();
+        nc
+    }
+
+    fn network_result() -> NetworkResult {
+        let series = |a: f64| vec![vec![a, a + 1.0, a + 2.0], vec![-a; 3]];
+        let nan = vec![vec![f64::NAN; 3]; 2];
+        NetworkResult {
+            node_ids: vec!["upper".into(), "outlet".into()],
+            inflow: series(1.0),
+            outflow: series(2.0),
+            storage: series(3.0),
+            stage: vec![vec![10.0, 10.5, 11.0], vec![f64::NAN; 3]],
+            withdrawal: nan.clone(),
+            diversion: nan.clone(),
+            exchange: nan.clone(),
+            loss: nan.clone(),
+            captured: nan.clone(),
+            load: nan.clone(),
+            load_removed: nan.clone(),
+            tailwater: nan,
+        }
+    }
+
+    #[test]
+    fn round_trips_datasets() {
+        let path = temp_path("netcdf-round-trip");
+        let nc = sample();
+        nc.write(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bytes, nc.to_bytes());
+        assert_eq!(decode(&bytes), nc);
+        // Empty datasets still have the three (empty) header lists.
+        let empty = NcFile::new().to_bytes();
+        assert_eq!(empty.len(), 32);
+        assert_eq!(decode(&empty), NcFile::new());
+    }
+
+    #[test]
+    fn writes_cf_time_series_of_network_results() {
+        let result = network_result();
+        let times = [0.0, 3600.0, 7200.0];
+        let nc = network_result_to_netcdf(&result, &times, UnitSystem::Us, "test").unwrap();
+        let decoded = decode(&nc.to_bytes());
+        assert_eq!(
+            decoded.dims,
+            [
+                ("time".to_string(), 3),
+                ("node".to_string(), 2),
+                ("name_strlen".to_string(), 6)
+            ]
+        );
+        let var = |name: &str| decoded.vars.iter().find(|v| v.name == name).unwrap();
+        assert_eq!(var("time").data, NcData::Double(times.to_vec()));
+        assert_eq!(var("node_id").data, NcData::Char(b"upper\0outlet".to_vec()));
+        // Values are laid out time by node.
+        assert_eq!(
+            var("outflow").data,
+            NcData::Double(vec![2.0, -2.0, 3.0, -2.0, 4.0, -2.0])
+        );
+        let NcData::Double(stage) = &var("stage").data else {
+            panic!()
+        };
+        assert_eq!(stage[4], 11.0);
+        assert!(stage[5].is_nan());
+        let unit = var("storage")
+            .attrs
+            .iter()
+            .find(|(name, _)| name == "units")
+            .map(|(_, value)| value.clone());
+        assert_eq!(unit, Some(NcAttr::Text("ft3".into())));
+        assert!(decoded
+            .attrs
+            .contains(&("featureType".to_string(), NcAttr::Text("timeSeries".into()))));
+    }
+
+    #[test]
+    fn rejects_malformed_datasets() {
+        let mut nc = NcFile::new();
+        let x = nc.add_dim("x", 3);
+        let empty = nc.add_dim("empty", 0);
+        match nc.add_var("v", &[x], NcData::Double(vec![1.0; 4]), vec![]) {
+            Err(Error::Data(msg)) => assert!(msg.contains("4 values"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+        match nc.add_var("w", &[x, empty], NcData::Double(vec![]), vec![]) {
+            Err(Error::Data(msg)) => assert!(msg.contains("`empty`"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+        assert!(nc.vars.is_empty());
+
+        let result = network_result();
+        match network_result_to_netcdf(&result, &[0.0, 1.0], UnitSystem::Si, "") {
+            Err(Error::Data(msg)) => assert!(msg.contains("2 times"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+        let mut empty = result.clone();
+        empty.outflow = vec![Vec::new(); 2];
+        assert!(matches!(
+            network_result_to_netcdf(&empty, &[], UnitSystem::Si, ""),
+            Err(Error::Data(_))
+        ));
+        let path = std::env::temp_dir()
+            .join("rustflow-missing-dir")
+            .join("x.nc");
+        assert!(matches!(NcFile::new().write(&path), Err(Error::Io(_))));
+    }
+}
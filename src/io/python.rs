@@ -0,0 +1,435 @@
+use pyo3::exceptions::PyKeyError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDict, PySlice};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use super::csv::{read_csv, read_csv_chunks, write_csv, CsvChunks, CsvOptions, HydrographTable};
+use super::dss::{e_part_seconds, interval_e_part, DssPathname};
+use super::geojson::{network_from_geojson, GeoJsonOptions};
+use super::series_file::{
+    write_series_file as write_series_file_rs, SeriesFileReader, SeriesHeader,
+};
+use super::swmm::parse_inp;
+use crate::arrow::{arrow_batch_to_columns, FloatSeries};
+use crate::config::python::value_to_py;
+use crate::error::Error;
+use crate::time::{
+    datetime_to_epoch, epoch_to_datetime, seconds_to_timedelta, timedelta_to_seconds,
+};
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (path, datetime_format=None, time_column=None, columns=None, missing_values=None, delimiter=','))]
+pub fn read_hydrograph_csv<'py>(
+    py: Python<'py>,
+    path: PathBuf,
+    datetime_format: Option<String>,
+    time_column: Option<String>,
+    columns: Option<Vec<(String, String)>>,
+    missing_values: Option<Vec<String>>,
+    delimiter: char,
+) -> PyResult<(Vec<Bound<'py, PyDateTime>>, Bound<'py, PyDict>)> {
+    let options = CsvOptions {
+        delimiter,
+        time_column,
+        datetime_format,
+        columns: columns.unwrap_or_default(),
+        missing_values: missing_values.unwrap_or_default(),
+    };
+    let table = py.allow_threads(|| read_csv(&path, &options))?;
+    table_to_py(py, table)
+}
+
+/// Iterator over a hydrograph CSV file in blocks of rows, returned by
+/// `read_hydrograph_csv_chunks`. Each item is a `(times, flows)` pair like
+/// the result of `read_hydrograph_csv`.
+#[pyclass(name = "CsvChunkReader", module = "rustflow.io")]
+pub struct PyCsvChunkReader {
+    chunks: CsvChunks<BufReader<File>>,
+}
+
+#[pymethods]
+impl PyCsvChunkReader {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn __next__<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Option<(Vec<Bound<'py, PyDateTime>>, Bound<'py, PyDict>)>> {
+        let Some(table) = py.allow_threads(|| self.chunks.next()) else {
+            return Ok(None);
+        };
+        table_to_py(py, table?).map(Some)
+    }
+}
+
+fn table_to_py(
+    py: Python<'_>,
+    table: HydrographTable,
+) -> PyResult<(Vec<Bound<'_, PyDateTime>>, Bound<'_, PyDict>)> {
+    let times = table
+        .times
+        .iter()
+        .map(|&t| epoch_to_datetime(py, t))
+        .collect::<PyResult<Vec<_>>>()?;
+    let flows = PyDict::new(py);
+    for (name, values) in table.columns {
+        flows.set_item(name, values)?;
+    }
+    Ok((times, flows))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, rows=100_000, datetime_format=None, time_column=None, columns=None, missing_values=None, delimiter=','))]
+#[allow(clippy::too_many_arguments)]
+pub fn read_hydrograph_csv_chunks(
+    path: PathBuf,
+    rows: usize,
+    datetime_format: Option<String>,
+    time_column: Option<String>,
+    columns: Option<Vec<(String, String)>>,
+    missing_values: Option<Vec<String>>,
+    delimiter: char,
+) -> PyResult<PyCsvChunkReader> {
+    let options = CsvOptions {
+        delimiter,
+        time_column,
+        datetime_format,
+        columns: columns.unwrap_or_default(),
+        missing_values: missing_values.unwrap_or_default(),
+    };
+    Ok(PyCsvChunkReader {
+        chunks: read_csv_chunks(&path, &options, rows)?,
+    })
+}
+
+/// Series file opened for reading through a memory map. Only the header is
+/// parsed on opening; indexing by name returns a zero-copy `float64`
+/// memoryview of that series, and pages are read from disk only as values
+/// are touched. Slice the view (or wrap it with `numpy.asarray`) to work on
+/// part of a record. Close the file, or use it as a context manager, to
+/// release the map once no views are in use.
+///
+/// Args:
+///     path (str | PathLike): File written by `write_series_file`.
+#[pyclass(name = "SeriesFile", module = "rustflow.io")]
+pub struct PySeriesFile {
+    path: PathBuf,
+    header: SeriesHeader,
+    /// The `mmap.mmap` of the file and a `float64` memoryview of its data;
+    /// `None` once closed.
+    mapped: Option<(PyObject, PyObject)>,
+}
+
+#[pymethods]
+impl PySeriesFile {
+    #[new]
+    fn new(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        if cfg!(target_endian = "big") {
+            return Err(PyValueError::new_err(
+                "Series files can only be mapped on little-endian machines.",
+            ));
+        }
+        let header = SeriesFileReader::open(&path)?.header().clone();
+        let mmap = py.import("mmap")?;
+        let builtins = py.import("builtins")?;
+        let file = builtins.call_method1("open", (&path, "rb"))?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("access", mmap.getattr("ACCESS_READ")?)?;
+        let mapped = mmap
+            .getattr("mmap")?
+            .call((file.call_method0("fileno")?, 0), Some(&kwargs));
+        // The map holds its own handle to the file.
+        file.call_method0("close")?;
+        let mapped = mapped?;
+        let data = data_view(&mapped, &header)?;
+        Ok(PySeriesFile {
+            path,
+            header,
+            mapped: Some((mapped.unbind(), data.unbind())),
+        })
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        let index = self
+            .header
+            .index(name)
+            .ok_or_else(|| PyKeyError::new_err(name.to_string()))?;
+        let (_, data) = self
+            .mapped
+            .as_ref()
+            .ok_or_else(|| PyValueError::new_err("The series file is closed."))?;
+        let steps = self.header.steps as isize;
+        let start = index as isize * steps;
+        data.bind(py)
+            .get_item(PySlice::new(py, start, start + steps, 1))
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.header.index(name).is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.header.names.len()
+    }
+
+    /// list[str]: Names of the series, in file order.
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.header.names.clone()
+    }
+
+    /// int: Number of values in each series.
+    #[getter]
+    fn steps(&self) -> u64 {
+        self.header.steps
+    }
+
+    /// Optional[datetime]: Time of the first value, if recorded.
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        self.header
+            .start
+            .map(|t| epoch_to_datetime(py, t))
+            .transpose()
+    }
+
+    /// Optional[timedelta]: Interval between values, if recorded.
+    #[getter]
+    fn time_step<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDelta>>> {
+        self.header
+            .time_step
+            .map(|dt| seconds_to_timedelta(py, dt))
+            .transpose()
+    }
+
+    /// bool: Whether the file has been closed.
+    #[getter]
+    fn closed(&self) -> bool {
+        self.mapped.is_none()
+    }
+
+    /// Releases the memory map. Fails while views returned by indexing are
+    /// still in use.
+    fn close(&mut self, py: Python<'_>) -> PyResult<()> {
+        if let Some((mapped, data)) = self.mapped.take() {
+            data.bind(py).call_method0("release")?;
+            if let Err(e) = mapped.bind(py).call_method0("close") {
+                // Still in use: keep the file open and usable.
+                let data = data_view(mapped.bind(py), &self.header)?.unbind();
+                self.mapped = Some((mapped, data));
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<()> {
+        self.close(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SeriesFile('{}', series={}, steps={})",
+            self.path.display(),
+            self.header.names.len(),
+            self.header.steps
+        )
+    }
+
+    /// Pickles the path; the file is mapped again on unpickling.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (PathBuf,))> {
+        Ok((slf.get_type().into_any(), (slf.borrow().path.clone(),)))
+    }
+}
+
+/// `float64` memoryview of the data of the mapped series file `mapped`.
+fn data_view<'py>(
+    mapped: &Bound<'py, PyAny>,
+    header: &SeriesHeader,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = mapped.py();
+    let start = header.offset(0, 0) as isize;
+    let end = header.offset(header.names.len(), 0) as isize;
+    py.import("builtins")?
+        .call_method1("memoryview", (mapped,))?
+        .get_item(PySlice::new(py, start, end, 1))?
+        .call_method1("cast", ("d",))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, series, start=None, time_step=None))]
+pub fn write_series_file(
+    py: Python<'_>,
+    path: PathBuf,
+    series: Vec<(String, FloatSeries)>,
+    start: Option<Bound<'_, PyDateTime>>,
+    time_step: Option<Bound<'_, PyDelta>>,
+) -> PyResult<()> {
+    let series: Vec<(String, Vec<f64>)> = series
+        .into_iter()
+        .map(|(name, values)| (name, values.0))
+        .collect();
+    let start = start.as_ref().map(datetime_to_epoch);
+    let time_step = time_step.as_ref().map(timedelta_to_seconds);
+    py.allow_threads(|| write_series_file_rs(&path, &series, start, time_step))?;
+    Ok(())
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, times, flows, datetime_format=None, time_column=None, missing_value=None, delimiter=','))]
+#[allow(clippy::too_many_arguments)]
+pub fn write_hydrograph_csv(
+    py: Python<'_>,
+    path: PathBuf,
+    times: Vec<Bound<'_, PyDateTime>>,
+    flows: Bound<'_, PyDict>,
+    datetime_format: Option<String>,
+    time_column: Option<String>,
+    missing_value: Option<String>,
+    delimiter: char,
+) -> PyResult<()> {
+    let table = HydrographTable {
+        times: times.iter().map(datetime_to_epoch).collect(),
+        columns: flows
+            .iter()
+            .map(|(name, values)| Ok((name.extract()?, values.extract()?)))
+            .collect::<PyResult<_>>()?,
+    };
+    let options = CsvOptions {
+        delimiter,
+        time_column,
+        datetime_format,
+        columns: Vec::new(),
+        missing_values: missing_value.into_iter().collect(),
+    };
+    py.allow_threads(|| write_csv(&path, &table, &options))?;
+    Ok(())
+}
+
+#[pyfunction]
+pub fn from_arrow<'py>(py: Python<'py>, batch: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyDict>> {
+    let columns = PyDict::new(py);
+    for (name, values) in arrow_batch_to_columns(&batch)? {
+        columns.set_item(name, values)?;
+    }
+    Ok(columns)
+}
+
+#[pyfunction]
+pub fn parse_dss_pathname(
+    pathname: &str,
+) -> PyResult<(String, String, String, String, String, String)> {
+    let p = DssPathname::parse(pathname)?;
+    Ok((p.a, p.b, p.c, p.d, p.e, p.f))
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, c, d, e, f))]
+pub fn format_dss_pathname(a: &str, b: &str, c: &str, d: &str, e: &str, f: &str) -> String {
+    DssPathname {
+        a: a.to_ascii_uppercase(),
+        b: b.to_ascii_uppercase(),
+        c: c.to_ascii_uppercase(),
+        d: d.to_ascii_uppercase(),
+        e: e.to_string(),
+        f: f.to_ascii_uppercase(),
+    }
+    .to_string()
+}
+
+#[pyfunction]
+pub fn dss_interval(time_step: Bound<'_, PyDelta>) -> PyResult<&'static str> {
+    let seconds = timedelta_to_seconds(&time_step);
+    interval_e_part(seconds).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "A time step of {seconds} s is not a regular HEC-DSS interval."
+        ))
+    })
+}
+
+#[pyfunction]
+pub fn dss_interval_seconds(e_part: &str) -> PyResult<f64> {
+    e_part_seconds(e_part).ok_or_else(|| {
+        PyValueError::new_err(format!("`{e_part}` is not a regular HEC-DSS interval."))
+    })
+}
+
+#[pyfunction]
+pub fn read_swmm_inp(py: Python<'_>, path: PathBuf) -> PyResult<PyObject> {
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| Error::Io(format!("{}: {e}", path.display())))?;
+    let config = parse_inp(&text)?;
+    value_to_py(py, &config)
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    id_field="id",
+    from_field="from_node",
+    to_field="to_node",
+    geographic=false,
+    units=UnitSystem::Si,
+    reference_flow=None,
+    tolerance=None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn read_geojson_network(
+    py: Python<'_>,
+    text: &str,
+    id_field: &str,
+    from_field: &str,
+    to_field: &str,
+    geographic: bool,
+    units: UnitSystem,
+    reference_flow: Option<f64>,
+    tolerance: Option<f64>,
+) -> PyResult<PyObject> {
+    let options = GeoJsonOptions {
+        id_field: id_field.to_string(),
+        from_field: from_field.to_string(),
+        to_field: to_field.to_string(),
+        geographic,
+        units,
+        reference_flow,
+        tolerance,
+    };
+    let geojson = crate::config::json::parse(text)?;
+    let config = network_from_geojson(&geojson, &options)?;
+    value_to_py(py, &config)
+}
+
+#[pymodule]
+pub fn init_io(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(read_hydrograph_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(read_hydrograph_csv_chunks, m)?)?;
+    m.add_function(wrap_pyfunction!(write_hydrograph_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(from_arrow, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_dss_pathname, m)?)?;
+    m.add_function(wrap_pyfunction!(format_dss_pathname, m)?)?;
+    m.add_function(wrap_pyfunction!(dss_interval, m)?)?;
+    m.add_function(wrap_pyfunction!(dss_interval_seconds, m)?)?;
+    m.add_function(wrap_pyfunction!(read_swmm_inp, m)?)?;
+    m.add_function(wrap_pyfunction!(read_geojson_network, m)?)?;
+    m.add_function(wrap_pyfunction!(write_series_file, m)?)?;
+    m.add_class::<PyCsvChunkReader>()?;
+    m.add_class::<PySeriesFile>()?;
+    Ok(())
+}
@@ -0,0 +1,457 @@
+//! Run reports documenting a network simulation: the configuration and its
+//! SHA-256 hash, run parameters, the mass balance of every element, a table
+//! of peaks and the warnings raised during the run. Reports are written as
+//! JSON for archiving and comparison or as a self-contained HTML page for
+//! review.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{json, Value};
+use crate::datetime::format_datetime;
+use crate::error::{Error, Result};
+use crate::network::NetworkResult;
+
+/// Volumes of one node over the run (flow units × seconds).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementBalance {
+    pub id: String,
+    pub inflow: f64,
+    pub outflow: f64,
+    pub withdrawal: f64,
+    pub diversion: f64,
+    pub loss: f64,
+    pub storage_change: f64,
+    /// Inflow less outflow, withdrawal, diversion, loss and storage change:
+    /// rainfall, evaporation and streambed exchange, plus any numerical
+    /// continuity error.
+    pub unaccounted: f64,
+    /// `unaccounted` as a percentage of the larger of the inflow and
+    /// outflow volumes.
+    pub continuity_error: f64,
+}
+
+/// Peak values of one node and the steps at which they occur.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeakRow {
+    pub id: String,
+    pub inflow: f64,
+    pub inflow_step: usize,
+    pub outflow: f64,
+    pub outflow_step: usize,
+    /// Highest stage (`NaN` for elements without a stage).
+    pub stage: f64,
+    pub storage: f64,
+}
+
+/// Report of one network simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunReport {
+    pub title: String,
+    /// Network configuration the run used.
+    pub config: Value,
+    /// SHA-256 of the compact JSON of `config`, hex encoded.
+    pub config_hash: String,
+    /// Interval of the results (s).
+    pub time_step: f64,
+    /// Number of reported steps.
+    pub steps: usize,
+    /// Time of the first result (seconds since the Unix epoch), if known.
+    pub start: Option<f64>,
+    /// Further run parameters, such as adaptive step settings.
+    pub parameters: Vec<(String, Value)>,
+    pub balance: Vec<ElementBalance>,
+    pub peaks: Vec<PeakRow>,
+    pub warnings: Vec<String>,
+    /// Time the report was made (seconds since the Unix epoch).
+    pub generated: f64,
+}
+
+impl RunReport {
+    /// Report of `result`, simulated from `config` with time step `dt`,
+    /// with the `warnings` raised during the run.
+    ///
+    /// Balances cover the period from the end of the first step to the end
+    /// of the last, with volumes integrated by the trapezoidal rule like
+    /// the routing schemes, so that routing elements close to rounding
+    /// error.
+    pub fn new(
+        title: &str,
+        config: &Value,
+        result: &NetworkResult,
+        dt: f64,
+        warnings: Vec<String>,
+    ) -> Self {
+        let volume = |series: &[f64]| {
+            series
+                .windows(2)
+                .map(|w| 0.5 * (w[0] + w[1]) * dt)
+                .sum::<f64>()
+        };
+        let balance = result
+            .node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let storage = &result.storage[i];
+                let storage_change = match (storage.first(), storage.last()) {
+                    (Some(first), Some(last)) => last - first,
+                    _ => 0.0,
+                };
+                let inflow = volume(&result.inflow[i]);
+                let outflow = volume(&result.outflow[i]);
+                let withdrawal = volume(&result.withdrawal[i]);
+                let diversion = volume(&result.diversion[i]);
+                let loss = volume(&result.loss[i]);
+                let unaccounted = inflow - outflow - withdrawal - diversion - loss - storage_change;
+                let scale = inflow.abs().max(outflow.abs());
+                ElementBalance {
+                    id: id.clone(),
+                    inflow,
+                    outflow,
+                    withdrawal,
+                    diversion,
+                    loss,
+                    storage_change,
+                    unaccounted,
+                    continuity_error: if scale > 0.0 {
+                        100.0 * unaccounted / scale
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        let peaks = result
+            .node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let (inflow_step, inflow) = peak(&result.inflow[i]);
+                let (outflow_step, outflow) = peak(&result.outflow[i]);
+                PeakRow {
+                    id: id.clone(),
+                    inflow,
+                    inflow_step,
+                    outflow,
+                    outflow_step,
+                    stage: peak(&result.stage[i]).1,
+                    storage: peak(&result.storage[i]).1,
+                }
+            })
+            .collect();
+        let compact = json::to_string(config);
+        RunReport {
+            title: title.to_string(),
+            config: config.clone(),
+            config_hash: sha256_hex(compact.as_bytes()),
+            time_step: dt,
+            steps: result.outflow.first().map_or(0, Vec::len),
+            start: None,
+            parameters: Vec::new(),
+            balance,
+            peaks,
+            warnings,
+            generated: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |d| d.as_secs_f64()),
+        }
+    }
+
+    /// Timestamp of `step`, or the step number when the start is unknown.
+    fn time_of(&self, step: usize) -> Value {
+        match self.start {
+            Some(start) => {
+                format_datetime(start + step as f64 * self.time_step, TIME_FORMAT).into()
+            }
+            None => (step as f64).into(),
+        }
+    }
+
+    /// Report as a configuration value, in the layout of the JSON report.
+    pub fn to_value(&self) -> Value {
+        let object = |entries: Vec<(&str, Value)>| {
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            )
+        };
+        let mut run = vec![
+            ("time_step", self.time_step.into()),
+            ("steps", (self.steps as f64).into()),
+            (
+                "start",
+                self.start
+                    .map_or(Value::Null, |s| format_datetime(s, TIME_FORMAT).into()),
+            ),
+        ];
+        run.extend(self.parameters.iter().map(|(k, v)| (k.as_str(), v.clone())));
+        let balance = self
+            .balance
+            .iter()
+            .map(|b| {
+                object(vec![
+                    ("id", b.id.as_str().into()),
+                    ("inflow", b.inflow.into()),
+                    ("outflow", b.outflow.into()),
+                    ("withdrawal", b.withdrawal.into()),
+                    ("diversion", b.diversion.into()),
+                    ("loss", b.loss.into()),
+                    ("storage_change", b.storage_change.into()),
+                    ("unaccounted", b.unaccounted.into()),
+                    ("continuity_error", b.continuity_error.into()),
+                ])
+            })
+            .collect();
+        let peaks = self
+            .peaks
+            .iter()
+            .map(|p| {
+                object(vec![
+                    ("id", p.id.as_str().into()),
+                    ("peak_inflow", p.inflow.into()),
+                    ("peak_inflow_time", self.time_of(p.inflow_step)),
+                    ("peak_outflow", p.outflow.into()),
+                    ("peak_outflow_time", self.time_of(p.outflow_step)),
+                    ("max_stage", p.stage.into()),
+                    ("max_storage", p.storage.into()),
+                ])
+            })
+            .collect();
+        object(vec![
+            ("title", self.title.as_str().into()),
+            (
+                "generator",
+                format!("rustflow {}", env!("CARGO_PKG_VERSION")).into(),
+            ),
+            (
+                "generated",
+                format_datetime(self.generated, TIME_FORMAT).into(),
+            ),
+            ("config_hash", self.config_hash.as_str().into()),
+            ("run", object(run)),
+            ("mass_balance", Value::Array(balance)),
+            ("peaks", Value::Array(peaks)),
+            (
+                "warnings",
+                Value::Array(self.warnings.iter().map(|w| w.as_str().into()).collect()),
+            ),
+            ("config", self.config.clone()),
+        ])
+    }
+
+    pub fn to_json(&self) -> String {
+        json::to_string_pretty(&self.to_value())
+    }
+
+    /// Self-contained HTML page with the summary, parameters, mass balance,
+    /// peaks, warnings and configuration.
+    pub fn to_html(&self) -> String {
+        let value = self.to_value();
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>\n\
+             body {{ font-family: sans-serif; margin: 2em; color: #222; }}\n\
+             table {{ border-collapse: collapse; margin-bottom: 1.5em; }}\n\
+             th, td {{ border: 1px solid #bbb; padding: 0.25em 0.6em; }}\n\
+             td.number {{ text-align: right; font-variant-numeric: tabular-nums; }}\n\
+             th {{ background: #eee; text-align: left; }}\n\
+             pre {{ background: #f6f6f6; padding: 1em; overflow-x: auto; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+            title = escape(&self.title)
+        );
+
+        html.push_str("<h2>Run</h2>\n<table>\n");
+        let mut summary: Vec<(&str, Value)> = ["generator", "generated", "config_hash"]
+            .into_iter()
+            .filter_map(|k| value.get(k).map(|v| (k, v.clone())))
+            .collect();
+        if let Some(Value::Object(run)) = value.get("run") {
+            summary.extend(run.iter().map(|(k, v)| (k.as_str(), v.clone())));
+        }
+        for (key, item) in &summary {
+            let _ = writeln!(
+                html,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                escape(key),
+                cell(item)
+            );
+        }
+        html.push_str("</table>\n");
+
+        html.push_str(
+            "<h2>Mass balance</h2>\n<p>Volumes in the flow units of each node times seconds; \
+             continuity error in percent.</p>\n",
+        );
+        if let Some(rows) = value.get("mass_balance").and_then(Value::as_array) {
+            table(&mut html, rows);
+        }
+        html.push_str("<h2>Peaks</h2>\n");
+        if let Some(rows) = value.get("peaks").and_then(Value::as_array) {
+            table(&mut html, rows);
+        }
+
+        html.push_str("<h2>Warnings</h2>\n");
+        if self.warnings.is_empty() {
+            html.push_str("<p>None.</p>\n");
+        } else {
+            html.push_str("<ul>\n");
+            for warning in &self.warnings {
+                let _ = writeln!(html, "<li>{}</li>", escape(warning));
+            }
+            html.push_str("</ul>\n");
+        }
+        let _ = write!(
+            html,
+            "<h2>Configuration</h2>\n<pre>{}</pre>\n</body>\n</html>\n",
+            escape(&json::to_string_pretty(&self.config))
+        );
+        html
+    }
+
+    /// Writes the report as HTML if `path` ends in `.html` or `.htm`, and
+    /// as JSON otherwise.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let html = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm"));
+        let text = if html { self.to_html() } else { self.to_json() };
+        std::fs::write(path, text).map_err(|e| Error::Io(format!("{}: {e}", path.display())))
+    }
+}
+
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Step and value of the largest non-NaN value (NaN if there is none).
+fn peak(series: &[f64]) -> (usize, f64) {
+    series.iter().enumerate().filter(|(_, v)| !v.is_nan()).fold(
+        (0, f64::NAN),
+        |(i, best), (j, &v)| {
+            if best.is_nan() || v > best {
+                (j, v)
+            } else {
+                (i, best)
+            }
+        },
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Table cell content; numbers are shown to six significant digits.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Number(n) if !n.is_finite() => String::new(),
+        Value::Number(n) if *n == n.trunc() && n.abs() < 1e15 => format!("{n}"),
+        Value::Number(n) => {
+            let digits = (5 - n.abs().log10().floor() as i32).clamp(0, 12) as usize;
+            format!("{n:.digits$}")
+        }
+        Value::String(s) => escape(s),
+        other => escape(&json::to_string(other)),
+    }
+}
+
+/// HTML table of `rows`, objects sharing the keys of the first row.
+fn table(html: &mut String, rows: &[Value]) {
+    let Some(Value::Object(first)) = rows.first() else {
+        html.push_str("<p>None.</p>\n");
+        return;
+    };
+    html.push_str("<table>\n<tr>");
+    for (key, _) in first {
+        let _ = write!(html, "<th>{}</th>", escape(key));
+    }
+    html.push_str("</tr>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for (key, _) in first {
+            let item = row.get(key).unwrap_or(&Value::Null);
+            let class = if matches!(item, Value::Number(_)) {
+                " class=\"number\""
+            } else {
+                ""
+            };
+            let _ = write!(html, "<td{class}>{}</td>", cell(item));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+}
+
+/// SHA-256 digest (FIPS 180-4) of `data`, hex encoded.
+fn sha256_hex(data: &[u8]) -> String {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+    h.iter().map(|word| format!("{word:08x}")).collect()
+}
@@ -0,0 +1,339 @@
+//! Binary container for long simulated series, written once and read back
+//! by slicing without parsing the whole file.
+//!
+//! The layout is little-endian and aligned for memory mapping:
+//!
+//! | offset | size | content                                               |
+//! |--------|------|-------------------------------------------------------|
+//! | 0      | 8    | magic `RFSERIES`                                      |
+//! | 8      | 4    | format version (1)                                    |
+//! | 12     | 4    | number of series                                      |
+//! | 16     | 8    | number of steps in every series                       |
+//! | 24     | 8    | time of the first step, seconds since the Unix epoch |
+//! | 32     | 8    | time step in seconds                                  |
+//! | 40     | 8    | byte offset of the data, a multiple of 64             |
+//! | 48     |      | per series: name length (`u32`) and UTF-8 name        |
+//! | data   |      | per series, in name order: `steps` `f64` values       |
+//!
+//! An unknown start time or time step is stored as NaN. Each series is one
+//! contiguous block, so the values of series `i` from step `t` start at
+//! byte `data + 8 (i steps + t)`.
+
+use crate::error::{Error, Result};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"RFSERIES";
+const VERSION: u32 = 1;
+const FIXED_HEADER: usize = 48;
+const ALIGN: usize = 64;
+
+/// Contents of a series file header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeriesHeader {
+    pub names: Vec<String>,
+    pub steps: u64,
+    /// Time of the first step (seconds since the Unix epoch), if known.
+    pub start: Option<f64>,
+    /// Interval between steps (s), if known.
+    pub time_step: Option<f64>,
+    /// Byte offset of the first value of the first series.
+    pub data_offset: u64,
+}
+
+impl SeriesHeader {
+    /// Index of the series called `name`.
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    /// Byte offset of value `step` of series `index`.
+    pub fn offset(&self, index: usize, step: u64) -> u64 {
+        self.data_offset + 8 * (index as u64 * self.steps + step)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.data_offset as usize);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&(self.names.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.steps.to_le_bytes());
+        out.extend_from_slice(&self.start.unwrap_or(f64::NAN).to_le_bytes());
+        out.extend_from_slice(&self.time_step.unwrap_or(f64::NAN).to_le_bytes());
+        out.extend_from_slice(&self.data_offset.to_le_bytes());
+        for name in &self.names {
+            out.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+        }
+        out.resize(self.data_offset as usize, 0);
+        out
+    }
+}
+
+/// Writes `series`, all of the same length, as a series file.
+pub fn write_series_file(
+    path: &Path,
+    series: &[(String, Vec<f64>)],
+    start: Option<f64>,
+    time_step: Option<f64>,
+) -> Result<()> {
+    let steps = series.first().map_or(0, |(_, values)| values.len());
+    if let Some((name, values)) = series.iter().find(|(_, values)| values.len() != steps) {
+        return Err(Error::Data(format!(
+            "Series `{name}` has {} values; the others have {steps}.",
+            values.len()
+        )));
+    }
+    for (i, (name, _)) in series.iter().enumerate() {
+        if series[..i].iter().any(|(other, _)| other == name) {
+            return Err(Error::Data(format!("Series `{name}` is given twice.")));
+        }
+    }
+    let names: Vec<String> = series.iter().map(|(name, _)| name.clone()).collect();
+    let names_len: usize = names.iter().map(|n| 4 + n.len()).sum();
+    let header = SeriesHeader {
+        names,
+        steps: steps as u64,
+        start,
+        time_step,
+        data_offset: (FIXED_HEADER + names_len).div_ceil(ALIGN) as u64 * ALIGN as u64,
+    };
+    let io_error = |e: std::io::Error| Error::Io(format!("{}: {e}", path.display()));
+    let mut out = BufWriter::new(File::create(path).map_err(io_error)?);
+    out.write_all(&header.encode()).map_err(io_error)?;
+    for (_, values) in series {
+        for v in values {
+            out.write_all(&v.to_le_bytes()).map_err(io_error)?;
+        }
+    }
+    out.flush().map_err(io_error)
+}
+
+/// Random-access reader of a series file; only the header is read on
+/// opening.
+pub struct SeriesFileReader {
+    file: File,
+    header: SeriesHeader,
+    path: String,
+}
+
+impl SeriesFileReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let display = path.display().to_string();
+        let io_error = |e: std::io::Error| Error::Io(format!("{display}: {e}"));
+        let format_error = |msg: &str| Error::Data(format!("{display}: {msg}"));
+        let mut file = File::open(path).map_err(io_error)?;
+        let mut fixed = [0u8; FIXED_HEADER];
+        file.read_exact(&mut fixed)
+            .map_err(|_| format_error("not a rustflow series file."))?;
+        if &fixed[..8] != MAGIC {
+            return Err(format_error("not a rustflow series file."));
+        }
+        let u32_at = |i: usize| u32::from_le_bytes(fixed[i..i + 4].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_le_bytes(fixed[i..i + 8].try_into().unwrap());
+        let f64_at = |i: usize| Some(f64::from_le_bytes(fixed[i..i + 8].try_into().unwrap()));
+        let version = u32_at(8);
+        if version != VERSION {
+            return Err(format_error(&format!(
+                "unsupported series file version {version}."
+            )));
+        }
+        let count = u32_at(12) as usize;
+        let data_offset = u64_at(40);
+        let size = file.metadata().map_err(io_error)?.len();
+        if data_offset > size {
+            return Err(format_error("the header is truncated."));
+        }
+        let mut names_block = vec![0u8; (data_offset as usize).saturating_sub(FIXED_HEADER)];
+        file.read_exact(&mut names_block)
+            .map_err(|_| format_error("the header is truncated."))?;
+        let mut names = Vec::new();
+        let mut rest = names_block.as_slice();
+        for _ in 0..count {
+            let (len, tail) = rest
+                .split_first_chunk::<4>()
+                .ok_or_else(|| format_error("the header is truncated."))?;
+            let len = u32::from_le_bytes(*len) as usize;
+            let name = tail
+                .get(..len)
+                .ok_or_else(|| format_error("the header is truncated."))?;
+            names.push(
+                String::from_utf8(name.to_vec())
+                    .map_err(|_| format_error("a series name is not UTF-8."))?,
+            );
+            rest = &tail[len..];
+        }
+        let header = SeriesHeader {
+            names,
+            steps: u64_at(16),
+            start: f64_at(24).filter(|t| !t.is_nan()),
+            time_step: f64_at(32).filter(|t| !t.is_nan()),
+            data_offset,
+        };
+        let end = (count as u64)
+            .checked_mul(header.steps)
+            .and_then(|n| n.checked_mul(8))
+            .and_then(|n| n.checked_add(data_offset));
+        if end.is_none_or(|end| end > size) {
+            return Err(format_error("the data is truncated."));
+        }
+        Ok(SeriesFileReader {
+            file,
+            header,
+            path: display,
+        })
+    }
+
+    pub fn header(&self) -> &SeriesHeader {
+        &self.header
+    }
+
+    /// Values of series `index` over the steps in `steps`, clamped to the
+    /// record.
+    pub fn read(&mut self, index: usize, steps: Range<u64>) -> Result<Vec<f64>> {
+        if index >= self.header.names.len() {
+            return Err(Error::InvalidParameter(format!(
+                "The file holds {} series; there is no series {index}.",
+                self.header.names.len()
+            )));
+        }
+        let end = steps.end.min(self.header.steps);
+        let start = steps.start.min(end);
+        let mut bytes = vec![0u8; 8 * (end - start) as usize];
+        let io_error = |e: std::io::Error| Error::Io(format!("{}: {e}", self.path));
+        self.file
+            .seek(SeekFrom::Start(self.header.offset(index, start)))
+            .map_err(io_error)?;
+        self.file.read_exact(&mut bytes).map_err(io_error)?;
+        Ok(bytes
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rustflow-{name}-{}.rfs", std::process::id()))
+    }
+
+    fn sample() -> Vec<(String, Vec<f64>)> {
+        vec![
+            (
+                "outflow".to_string(),
+                (0..100).map(|t| t as f64 * 0.5).collect(),
+            ),
+            ("stage".to_string(), vec![f64::NAN; 100]),
+            ("a much longer series name".to_string(), vec![-1.0; 100]),
+        ]
+    }
+
+    #[test]
+    fn round_trips_headers_and_slices() {
+        let path = temp_path("series-round-trip");
+        write_series_file(&path, &sample(), Some(1.6e9), Some(3600.0)).unwrap();
+        let mut reader = SeriesFileReader::open(&path).unwrap();
+        let header = reader.header().clone();
+        assert_eq!(
+            header.names,
+            ["outflow", "stage", "a much longer series name"]
+        );
+        assert_eq!(header.steps, 100);
+        assert_eq!(header.start, Some(1.6e9));
+        assert_eq!(header.time_step, Some(3600.0));
+        assert_eq!(header.data_offset % ALIGN as u64, 0);
+        assert_eq!(header.index("stage"), Some(1));
+        assert_eq!(header.index("inflow"), None);
+        assert_eq!(header.offset(2, 5), header.data_offset + 8 * 205);
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(size, header.offset(3, 0));
+
+        assert_eq!(reader.read(0, 10..14).unwrap(), [5.0, 5.5, 6.0, 6.5]);
+        assert!(reader.read(1, 0..3).unwrap().iter().all(|v| v.is_nan()));
+        assert_eq!(reader.read(2, 0..100).unwrap(), vec![-1.0; 100]);
+        // Ranges are clamped to the record.
+        assert_eq!(reader.read(0, 98..500).unwrap(), [49.0, 49.5]);
+        assert!(reader.read(0, 300..400).unwrap().is_empty());
+        assert!(matches!(
+            reader.read(3, 0..1),
+            Err(Error::InvalidParameter(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn unknown_times_and_empty_files_round_trip() {
+        let path = temp_path("series-empty");
+        write_series_file(&path, &[], None, None).unwrap();
+        let reader = SeriesFileReader::open(&path).unwrap();
+        assert_eq!(
+            reader.header(),
+            &SeriesHeader {
+                names: vec![],
+                steps: 0,
+                start: None,
+                time_step: None,
+                data_offset: ALIGN as u64,
+            }
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_unequal_and_duplicate_series() {
+        let path = temp_path("series-invalid");
+        let mut series = sample();
+        series[1].1.pop();
+        match write_series_file(&path, &series, None, None) {
+            Err(Error::Data(msg)) => assert!(msg.contains("`stage` has 99 values"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+        let mut series = sample();
+        series[2].0 = "outflow".to_string();
+        match write_series_file(&path, &series, None, None) {
+            Err(Error::Data(msg)) => assert!(msg.contains("`outflow` is given twice"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn rejects_malformed_and_truncated_files() {
+        let path = temp_path("series-malformed");
+        write_series_file(&path, &sample(), None, Some(60.0)).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let data_offset = u64::from_le_bytes(bytes[40..48].try_into().unwrap()) as usize;
+        let mut version = bytes.clone();
+        version[8] = 2;
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        let mut name = bytes.clone();
+        name[52] = 0xff;
+        let mut count = bytes.clone();
+        count[12] = 200;
+        for (contents, expected) in [
+            (bytes[..20].to_vec(), "not a rustflow series file"),
+            (magic, "not a rustflow series file"),
+            (version, "unsupported series file version 2"),
+            (bytes[..data_offset - 8].to_vec(), "the header is truncated"),
+            (count, "the header is truncated"),
+            (name, "not UTF-8"),
+            (bytes[..bytes.len() - 1].to_vec(), "the data is truncated"),
+        ] {
+            std::fs::write(&path, contents).unwrap();
+            match SeriesFileReader::open(&path) {
+                Err(Error::Data(msg)) => assert!(msg.contains(expected), "{msg}"),
+                Err(other) => panic!("{expected}: {other:?}"),
+                Ok(_) => panic!("{expected}: opened"),
+            }
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(SeriesFileReader::open(&path), Err(Error::Io(_))));
+    }
+}
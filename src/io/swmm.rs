@@ -0,0 +1,570 @@
+//! Translation of EPA SWMM 5 `.inp` files into rustflow network
+//! configurations, for fast screening runs.
+//!
+//! | SWMM object                     | rustflow element                       |
+//! | ------------------------------- | -------------------------------------- |
+//! | subcatchment                    | `subcatchment` (nonlinear reservoir)   |
+//! | conduit                         | `kinematic_wave` reach                 |
+//! | storage node                    | `reservoir` (level pool)               |
+//! | junction, outfall, divider      | `junction`                             |
+//! | pump, orifice, weir, outlet     | `junction` (pass-through link)         |
+//!
+//! Simplifications: the network must be a tree (one outgoing link per node);
+//! circular and other closed shapes are represented by a rectangle of equal
+//! full-flow area; infiltration is a constant loss on the pervious fraction
+//! (Horton minimum rate or Green–Ampt conductivity); storage outflow is the
+//! normal-flow capacity of the outlet conduit at the pool head.
+
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::interp::interp;
+use crate::units::UnitSystem;
+use std::collections::HashMap;
+
+/// Rows of the storage tables generated for storage nodes.
+const STORAGE_ROWS: usize = 25;
+
+#[derive(Default)]
+struct Sections<'a> {
+    rows: HashMap<String, Vec<Vec<&'a str>>>,
+    order: Vec<String>,
+}
+
+impl<'a> Sections<'a> {
+    fn parse(text: &'a str) -> Self {
+        let mut sections = Sections::default();
+        let mut current = String::new();
+        for line in text.lines() {
+            let line = line.split(';').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                current = line[1..line.len() - 1].trim().to_ascii_uppercase();
+                sections.order.push(current.clone());
+                continue;
+            }
+            sections
+                .rows
+                .entry(current.clone())
+                .or_default()
+                .push(line.split_whitespace().collect());
+        }
+        sections
+    }
+
+    fn get(&self, name: &str) -> &[Vec<&'a str>] {
+        self.rows.get(name).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn number(row: &[&str], index: usize, what: &str) -> Result<f64> {
+    row.get(index)
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or_else(|| {
+            Error::Data(format!(
+                "SWMM {what} `{}`: field {} is missing or not a number.",
+                row.first().copied().unwrap_or_default(),
+                index + 1
+            ))
+        })
+}
+
+fn field<'a>(row: &[&'a str], index: usize, what: &str) -> Result<&'a str> {
+    row.get(index).copied().ok_or_else(|| {
+        Error::Data(format!(
+            "SWMM {what} `{}`: field {} is missing.",
+            row.first().copied().unwrap_or_default(),
+            index + 1
+        ))
+    })
+}
+
+fn optional_number(row: &[&str], index: usize) -> f64 {
+    row.get(index).and_then(|s| s.parse().ok()).unwrap_or(0.0)
+}
+
+/// Parses `HH:MM:SS` or plain seconds.
+fn parse_duration(text: &str) -> Option<f64> {
+    if let Ok(seconds) = text.parse::<f64>() {
+        return Some(seconds);
+    }
+    text.split(':')
+        .map(|part| part.parse::<f64>().ok())
+        .try_fold(0.0, |acc, part| Some(acc * 60.0 + part?))
+}
+
+struct Link<'a> {
+    id: &'a str,
+    to: &'a str,
+    conduit: Option<Conduit>,
+}
+
+struct Conduit {
+    channel: TrapezoidalChannel,
+    length: f64,
+    inlet_elevation: f64,
+    /// Full depth for closed shapes (used to cap storage outflow head).
+    full_depth: f64,
+}
+
+/// Converts a SWMM `.inp` file into a network configuration accepted by
+/// [`crate::network::Network::from_value`]. Flows are in cfs for US flow
+/// units and m³/s for SI flow units; rain gauges become `rainfall` series
+/// named after the gauge (intensity in in/h or mm/h).
+pub fn parse_inp(text: &str) -> Result<Value> {
+    let sections = Sections::parse(text);
+
+    let mut flow_units = "CFS".to_string();
+    let mut infiltration = "HORTON".to_string();
+    let mut routing_step = None;
+    for row in sections.get("OPTIONS") {
+        match (row[0].to_ascii_uppercase().as_str(), row.get(1)) {
+            ("FLOW_UNITS", Some(value)) => flow_units = value.to_ascii_uppercase(),
+            ("INFILTRATION", Some(value)) => infiltration = value.to_ascii_uppercase(),
+            ("ROUTING_STEP", Some(value)) => routing_step = parse_duration(value),
+            _ => {}
+        }
+    }
+    let units = match flow_units.as_str() {
+        "CFS" | "GPM" | "MGD" => UnitSystem::Us,
+        "CMS" | "LPS" | "MLD" => UnitSystem::Si,
+        other => return Err(Error::Data(format!("Unknown SWMM FLOW_UNITS `{other}`."))),
+    };
+    let units_name = match units {
+        UnitSystem::Si => "si",
+        UnitSystem::Us => "us",
+    };
+
+    // Node inverts.
+    let mut invert: HashMap<&str, f64> = HashMap::new();
+    for section in ["JUNCTIONS", "OUTFALLS", "DIVIDERS", "STORAGE"] {
+        for row in sections.get(section) {
+            invert.insert(row[0], number(row, 1, "node")?);
+        }
+    }
+
+    // Cross sections.
+    let mut xsections: HashMap<&str, &Vec<&str>> = HashMap::new();
+    for row in sections.get("XSECTIONS") {
+        xsections.insert(row[0], row);
+    }
+
+    // Links, keyed by their upstream node.
+    let mut links: Vec<(&str, Link)> = Vec::new();
+    for row in sections.get("CONDUITS") {
+        let id = row[0];
+        let (from, to) = (field(row, 1, "conduit")?, field(row, 2, "conduit")?);
+        let length = number(row, 3, "conduit")?;
+        let n = number(row, 4, "conduit")?;
+        let in_offset = optional_number(row, 5);
+        let out_offset = optional_number(row, 6);
+        let upstream = invert.get(from).copied().unwrap_or(0.0) + in_offset;
+        let downstream = invert.get(to).copied().unwrap_or(0.0) + out_offset;
+        let slope = ((upstream - downstream) / length).max(1e-4);
+        let xs = xsections
+            .get(id)
+            .ok_or_else(|| Error::Data(format!("SWMM conduit `{id}` has no [XSECTIONS] entry.")))?;
+        let shape = xs
+            .get(1)
+            .map(|s| s.to_ascii_uppercase())
+            .unwrap_or_default();
+        let geom = |i: usize| optional_number(xs, i + 1);
+        let (bottom_width, side_slope, full_depth) = match shape.as_str() {
+            "RECT_OPEN" => (geom(2), 0.0, f64::INFINITY),
+            "RECT_CLOSED" => (geom(2), 0.0, geom(1)),
+            "TRAPEZOIDAL" => (geom(2), 0.5 * (geom(3) + geom(4)), f64::INFINITY),
+            "TRIANGULAR" => (0.0, geom(2) / (2.0 * geom(1)), f64::INFINITY),
+            _ => (std::f64::consts::FRAC_PI_4 * geom(1), 0.0, geom(1)),
+        };
+        let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)
+            .map_err(|e| Error::Data(format!("SWMM conduit `{id}`: {e}")))?;
+        links.push((
+            from,
+            Link {
+                id,
+                to,
+                conduit: Some(Conduit {
+                    channel,
+                    length,
+                    inlet_elevation: upstream,
+                    full_depth,
+                }),
+            },
+        ));
+    }
+    for (section, what) in [
+        ("PUMPS", "pump"),
+        ("ORIFICES", "orifice"),
+        ("WEIRS", "weir"),
+        ("OUTLETS", "outlet"),
+    ] {
+        for row in sections.get(section) {
+            links.push((
+                field(row, 1, what)?,
+                Link {
+                    id: row[0],
+                    to: field(row, 2, what)?,
+                    conduit: None,
+                },
+            ));
+        }
+    }
+    let mut outgoing: HashMap<&str, Link> = HashMap::new();
+    for (from, link) in links {
+        if outgoing.contains_key(from) {
+            return Err(Error::Data(format!(
+                "SWMM node `{from}` has more than one outgoing link; flow splits are not supported."
+            )));
+        }
+        outgoing.insert(from, link);
+    }
+
+    let mut nodes = Vec::new();
+    let node = |id: &str, kind: &str| {
+        let mut value = Value::Object(Vec::new());
+        value.insert("id", id.into());
+        value.insert("type", kind.into());
+        value
+    };
+
+    // Subcatchments.
+    let subareas: HashMap<&str, &Vec<&str>> = sections
+        .get("SUBAREAS")
+        .iter()
+        .map(|row| (row[0], row))
+        .collect();
+    let infiltration_rows: HashMap<&str, &Vec<&str>> = sections
+        .get("INFILTRATION")
+        .iter()
+        .map(|row| (row[0], row))
+        .collect();
+    for row in sections.get("SUBCATCHMENTS") {
+        let id = row[0];
+        let impervious = number(row, 4, "subcatchment")? / 100.0;
+        let pervious = 1.0 - impervious;
+        let (n, depression) = match subareas.get(id) {
+            Some(sa) => (
+                impervious * number(sa, 1, "subarea")? + pervious * number(sa, 2, "subarea")?,
+                impervious * number(sa, 3, "subarea")? + pervious * number(sa, 4, "subarea")?,
+            ),
+            None => (0.015 * impervious + 0.1 * pervious, 0.0),
+        };
+        let rate = match (infiltration_rows.get(id), infiltration.as_str()) {
+            (Some(inf), "HORTON" | "MODIFIED_HORTON") => optional_number(inf, 2),
+            (Some(inf), "GREEN_AMPT" | "MODIFIED_GREEN_AMPT") => optional_number(inf, 2),
+            _ => 0.0,
+        };
+        let mut value = node(id, "subcatchment");
+        value.insert("units", units_name.into());
+        value.insert("rainfall", row[1].into());
+        value.insert("downstream", row[2].into());
+        value.insert("area", number(row, 3, "subcatchment")?.into());
+        value.insert("width", number(row, 5, "subcatchment")?.into());
+        value.insert(
+            "slope",
+            (number(row, 6, "subcatchment")? / 100.0).max(1e-4).into(),
+        );
+        value.insert("n", n.into());
+        value.insert("depression_storage", depression.into());
+        value.insert("loss_rate", (rate * pervious).into());
+        nodes.push(value);
+    }
+
+    // Curves for tabular storage.
+    let mut curves: HashMap<&str, (Vec<f64>, Vec<f64>)> = HashMap::new();
+    for row in sections.get("CURVES") {
+        let entry = curves.entry(row[0]).or_default();
+        let start = if row.len() % 2 == 0 { 2 } else { 1 };
+        for pair in row[start..].chunks(2) {
+            if let [x, y] = pair {
+                if let (Ok(x), Ok(y)) = (x.parse(), y.parse()) {
+                    entry.0.push(x);
+                    entry.1.push(y);
+                }
+            }
+        }
+    }
+
+    let link_target = |id: &str| outgoing.get(id).map(|link| link.id);
+
+    // Plain nodes.
+    for section in ["JUNCTIONS", "OUTFALLS", "DIVIDERS"] {
+        for row in sections.get(section) {
+            let mut value = node(row[0], "junction");
+            if let Some(target) = link_target(row[0]) {
+                value.insert("downstream", target.into());
+            }
+            nodes.push(value);
+        }
+    }
+
+    // Storage nodes.
+    for row in sections.get("STORAGE") {
+        let id = row[0];
+        let bottom = number(row, 1, "storage")?;
+        let max_depth = number(row, 2, "storage")?.max(1e-3);
+        let init_depth = optional_number(row, 3);
+        let shape = row
+            .get(4)
+            .map(|s| s.to_ascii_uppercase())
+            .unwrap_or_default();
+        let area_at: Box<dyn Fn(f64) -> f64> = match shape.as_str() {
+            "FUNCTIONAL" => {
+                let (a, b, c) = (
+                    optional_number(row, 5),
+                    optional_number(row, 6),
+                    optional_number(row, 7),
+                );
+                Box::new(move |d: f64| a * d.powf(b) + c)
+            }
+            "TABULAR" => {
+                let (xs, ys) = row
+                    .get(5)
+                    .and_then(|name| curves.get(name))
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::Data(format!("SWMM storage `{id}` references an unknown curve."))
+                    })?;
+                Box::new(move |d: f64| interp(d, &xs, &ys).max(0.0))
+            }
+            other => {
+                return Err(Error::Data(format!(
+                    "SWMM storage `{id}`: shape `{other}` is not supported."
+                )))
+            }
+        };
+        let outlet = outgoing.get(id);
+        let mut elevation = Vec::with_capacity(STORAGE_ROWS);
+        let mut storage = Vec::with_capacity(STORAGE_ROWS);
+        let mut outflow = Vec::with_capacity(STORAGE_ROWS);
+        let mut volume = 0.0;
+        for i in 0..STORAGE_ROWS {
+            let depth = max_depth * i as f64 / (STORAGE_ROWS - 1) as f64;
+            if i > 0 {
+                let previous = max_depth * (i - 1) as f64 / (STORAGE_ROWS - 1) as f64;
+                volume += 0.5 * (area_at(previous) + area_at(depth)) * (depth - previous);
+            }
+            let stage = bottom + depth;
+            let q = match outlet.and_then(|link| link.conduit.as_ref()) {
+                Some(c) => {
+                    let head = (stage - c.inlet_elevation).clamp(0.0, c.full_depth);
+                    units.discharge_from_si(c.channel.discharge(units.length_to_si(head)))
+                }
+                None => 0.0,
+            };
+            elevation.push(stage);
+            storage.push(volume);
+            outflow.push(q);
+        }
+        let mut value = node(id, "reservoir");
+        value.insert("elevation", Value::from(elevation.as_slice()));
+        value.insert("storage", Value::from(storage.as_slice()));
+        value.insert("outflow", Value::from(outflow.as_slice()));
+        value.insert("initial_elevation", (bottom + init_depth).into());
+        if let Some(link) = outlet {
+            value.insert("downstream", link.id.into());
+        }
+        nodes.push(value);
+    }
+
+    // Links.
+    for link in outgoing.values() {
+        let mut value = match &link.conduit {
+            Some(c) => {
+                let mut value = node(link.id, "kinematic_wave");
+                value.insert("units", units_name.into());
+                value.insert("length", c.length.into());
+                value.insert(
+                    "bottom_width",
+                    units.length_from_si(c.channel.bottom_width).into(),
+                );
+                value.insert("side_slope", c.channel.side_slope.into());
+                value.insert("n", c.channel.manning_n.into());
+                value.insert("slope", c.channel.bed_slope.into());
+                value.insert("segments", 5.0.into());
+                value
+            }
+            None => node(link.id, "junction"),
+        };
+        value.insert("downstream", link.to.into());
+        nodes.push(value);
+    }
+    // Stable output order: links were collected from a hash map.
+    let link_start = nodes.len() - outgoing.len();
+    nodes[link_start..].sort_by(|a, b| {
+        a.get("id")
+            .and_then(Value::as_str)
+            .cmp(&b.get("id").and_then(Value::as_str))
+    });
+
+    let mut config = Value::Object(Vec::new());
+    config.insert("units", units_name.into());
+    if let Some(step) = routing_step {
+        config.insert("time_step", step.into());
+    }
+    config.insert("nodes", Value::Array(nodes));
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use std::collections::BTreeMap;
+
+    const INP: &str = "
+[TITLE]
+Test catchment
+
+[OPTIONS]
+FLOW_UNITS     CMS
+INFILTRATION   HORTON
+ROUTING_STEP   0:00:30
+
+[RAINGAGES]
+RG1  INTENSITY 0:15 1.0 TIMESERIES TS1
+
+[SUBCATCHMENTS]
+;;Name RainGage Outlet Area %Imperv Width %Slope
+S1     RG1      J1     4     50      200   0.5
+
+[SUBAREAS]
+S1  0.012  0.15  1.5  5  25  OUTLET
+
+[INFILTRATION]
+S1  75  4  4  7  0
+
+[JUNCTIONS]
+J1  10.0  2.0
+
+[OUTFALLS]
+O1  9.0  FREE
+
+[STORAGE]
+P1  9.5  2.0  0.5  FUNCTIONAL  0  0  1000
+
+[CONDUITS]
+;;Name From To Length Roughness InOffset OutOffset
+C1     J1   P1 400    0.015     0        0
+C2     P1   O1 100    0.015     0        0
+
+[XSECTIONS]
+C1  RECT_OPEN  1.0  2.0  0  0  1
+C2  CIRCULAR   0.6  0    0  0  1
+";
+
+    fn node<'a>(config: &'a Value, id: &str) -> &'a Value {
+        config
+            .get("nodes")
+            .and_then(Value::as_array)
+            .and_then(|nodes| nodes.iter().find(|n| n.get("id") == Some(&Value::from(id))))
+            .unwrap_or_else(|| panic!("no node `{id}`"))
+    }
+
+    #[test]
+    fn translates_objects_to_elements() {
+        let config = parse_inp(INP).unwrap();
+        assert_eq!(config.str_field("units").unwrap(), "si");
+        assert_eq!(config.f64_field("time_step").unwrap(), 30.0);
+
+        let s1 = node(&config, "S1");
+        assert_eq!(s1.str_field("type").unwrap(), "subcatchment");
+        assert_eq!(s1.str_field("rainfall").unwrap(), "RG1");
+        assert_eq!(s1.str_field("downstream").unwrap(), "J1");
+        assert_eq!(s1.f64_field("slope").unwrap(), 0.005);
+        assert!((s1.f64_field("n").unwrap() - (0.5 * 0.012 + 0.5 * 0.15)).abs() < 1e-12);
+        assert!((s1.f64_field("depression_storage").unwrap() - 3.25).abs() < 1e-12);
+        assert_eq!(s1.f64_field("loss_rate").unwrap(), 2.0);
+
+        // Nodes drain into their outgoing link, and links into their end node.
+        assert_eq!(node(&config, "J1").str_field("downstream").unwrap(), "C1");
+        assert_eq!(node(&config, "C1").str_field("downstream").unwrap(), "P1");
+        assert!(node(&config, "O1").get("downstream").is_none());
+
+        let c1 = node(&config, "C1");
+        assert_eq!(c1.str_field("type").unwrap(), "kinematic_wave");
+        assert_eq!(c1.f64_field("bottom_width").unwrap(), 2.0);
+        assert!((c1.f64_field("slope").unwrap() - 0.5 / 400.0).abs() < 1e-12);
+        // Circular conduits become a rectangle of equal full-flow area.
+        let c2 = node(&config, "C2");
+        let width = c2.f64_field("bottom_width").unwrap();
+        assert!((width - std::f64::consts::FRAC_PI_4 * 0.6).abs() < 1e-12);
+
+        let p1 = node(&config, "P1");
+        assert_eq!(p1.str_field("type").unwrap(), "reservoir");
+        assert_eq!(p1.f64_field("initial_elevation").unwrap(), 10.0);
+        let storage = p1.f64_array_field("storage").unwrap();
+        assert_eq!(storage.len(), STORAGE_ROWS);
+        assert!((storage[STORAGE_ROWS - 1] - 2000.0).abs() < 1e-9);
+        let outflow = p1.f64_array_field("outflow").unwrap();
+        assert_eq!(outflow[0], 0.0);
+        assert!(outflow.windows(2).all(|w| w[1] >= w[0]));
+    }
+
+    #[test]
+    fn translated_network_runs() {
+        let config = parse_inp(INP).unwrap();
+        let mut network = Network::from_value(&config).unwrap();
+        let rain: Vec<f64> = (0..240).map(|t| if t < 60 { 20.0 } else { 0.0 }).collect();
+        let inputs = BTreeMap::from([("RG1".to_string(), rain)]);
+        let result = network.run(&inputs, 30.0).unwrap();
+        let outfall = result.outflow_of("O1").unwrap();
+        assert!(outfall.iter().all(|q| q.is_finite() && *q >= 0.0));
+        assert!(outfall.iter().any(|&q| q > 0.0));
+    }
+
+    #[test]
+    fn reports_short_and_inconsistent_rows() {
+        for (from, to, expected) in [
+            (
+                "C1     J1   P1 400    0.015     0        0",
+                "C1     J1",
+                "SWMM conduit `C1`: field 3 is missing.",
+            ),
+            (
+                "C1     J1   P1 400    0.015     0        0",
+                "C1 J1 P1 400",
+                "SWMM conduit `C1`: field 5",
+            ),
+            (
+                "C1  RECT_OPEN  1.0  2.0  0  0  1",
+                "",
+                "SWMM conduit `C1` has no [XSECTIONS] entry.",
+            ),
+            (
+                "S1     RG1      J1     4     50",
+                "S1 RG1 J1",
+                "SWMM subcatchment `S1`",
+            ),
+            (
+                "FLOW_UNITS     CMS",
+                "FLOW_UNITS LITRES",
+                "Unknown SWMM FLOW_UNITS `LITRES`",
+            ),
+            ("FUNCTIONAL  0  0  1000", "CONICAL 1 1 1", "shape `CONICAL`"),
+            (
+                "C2     P1   O1 100",
+                "C2     J1   O1 100",
+                "SWMM node `J1` has more than one outgoing link",
+            ),
+            (
+                "[OUTFALLS]\nO1  9.0  FREE",
+                "[OUTFALLS]\nO1",
+                "SWMM node `O1`",
+            ),
+        ] {
+            assert!(INP.contains(from), "{from}");
+            match parse_inp(&INP.replacen(from, to, 1)) {
+                Err(Error::Data(msg)) => assert!(msg.contains(expected), "{msg}"),
+                other => panic!("{to:?}: {other:?}"),
+            }
+        }
+        let pump = INP.replace("[XSECTIONS]", "[PUMPS]\nPU1  O1\n\n[XSECTIONS]");
+        match parse_inp(&pump) {
+            Err(Error::Data(msg)) => assert_eq!(msg, "SWMM pump `PU1`: field 3 is missing."),
+            other => panic!("{other:?}"),
+        }
+    }
+}
@@ -1,14 +1,168 @@
-use pyo3::prelude::*;
+//! High-performance hydrological routing.
+//!
+//! The numerical cores are plain Rust and can be used without Python by
+//! disabling the default `python` feature:
+//!
+//! ```toml
+//! rustflow = { version = "0.1", default-features = false }
+//! ```
+
+#[cfg(feature = "python")]
+pub mod arrow;
+pub mod calibrate;
+pub mod climate;
+pub mod config;
+pub mod datetime;
+pub mod design;
+pub mod drought;
+pub mod economics;
+pub mod ensemble;
+pub mod error;
+pub mod float;
+pub mod forecast;
+pub mod frequency;
+pub mod functional;
+pub mod groundwater;
+pub mod hydraulics;
+pub mod hydrograph;
+pub mod interp;
+pub mod io;
+pub mod kernels;
+pub mod log;
+pub mod network;
+pub mod parallel;
+pub mod precip;
+pub mod progress;
+pub mod quality;
+pub mod random;
 pub mod reach_routing;
+pub mod regionalize;
+pub mod reservoir;
+pub mod runoff;
+pub mod scenario;
+pub mod sediment;
+pub mod stochastic;
+pub mod terrain;
+#[cfg(feature = "python")]
+pub mod time;
+pub mod timeseries;
+pub mod units;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::{Error, Result};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
 
+#[cfg(feature = "python")]
 #[pymodule]
 fn rustflow(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    error::add_exceptions(m)?;
+    log::install_python(m)?;
+
     let reach_module = PyModule::new(m.py(), "reach")?;
 
-    // Expose the contents of `reach_routing/mod.rs` directly here
-    reach_routing::init_reach(&reach_module)?;
+    // Expose the contents of `reach_routing/python.rs` directly here
+    reach_routing::python::init_reach(&reach_module)?;
 
     m.add_submodule(&reach_module)?;
 
+    let hydraulics_module = PyModule::new(m.py(), "hydraulics")?;
+    hydraulics::python::init_hydraulics(&hydraulics_module)?;
+    m.add_submodule(&hydraulics_module)?;
+
+    let hydrograph_module = PyModule::new(m.py(), "hydrograph")?;
+    hydrograph::python::init_hydrograph(&hydrograph_module)?;
+    m.add_submodule(&hydrograph_module)?;
+
+    let regionalize_module = PyModule::new(m.py(), "regionalize")?;
+    regionalize::python::init_regionalize(&regionalize_module)?;
+    m.add_submodule(&regionalize_module)?;
+
+    let reservoir_module = PyModule::new(m.py(), "reservoir")?;
+    reservoir::python::init_reservoir(&reservoir_module)?;
+    m.add_submodule(&reservoir_module)?;
+
+    let io_module = PyModule::new(m.py(), "io")?;
+    io::python::init_io(&io_module)?;
+    m.add_submodule(&io_module)?;
+
+    let network_module = PyModule::new(m.py(), "network")?;
+    network::python::init_network(&network_module)?;
+    m.add_submodule(&network_module)?;
+
+    let runoff_module = PyModule::new(m.py(), "runoff")?;
+    runoff::python::init_runoff(&runoff_module)?;
+    m.add_submodule(&runoff_module)?;
+
+    let design_module = PyModule::new(m.py(), "design")?;
+    design::python::init_design(&design_module)?;
+    m.add_submodule(&design_module)?;
+
+    let drought_module = PyModule::new(m.py(), "drought")?;
+    drought::python::init_drought(&drought_module)?;
+    m.add_submodule(&drought_module)?;
+
+    let economics_module = PyModule::new(m.py(), "economics")?;
+    economics::python::init_economics(&economics_module)?;
+    m.add_submodule(&economics_module)?;
+
+    let ensemble_module = PyModule::new(m.py(), "ensemble")?;
+    ensemble::python::init_ensemble(&ensemble_module)?;
+    m.add_submodule(&ensemble_module)?;
+
+    let calibrate_module = PyModule::new(m.py(), "calibrate")?;
+    calibrate::python::init_calibrate(&calibrate_module)?;
+    m.add_submodule(&calibrate_module)?;
+
+    let climate_module = PyModule::new(m.py(), "climate")?;
+    climate::python::init_climate(&climate_module)?;
+    m.add_submodule(&climate_module)?;
+
+    let forecast_module = PyModule::new(m.py(), "forecast")?;
+    forecast::python::init_forecast(&forecast_module)?;
+    m.add_submodule(&forecast_module)?;
+
+    let frequency_module = PyModule::new(m.py(), "frequency")?;
+    frequency::python::init_frequency(&frequency_module)?;
+    m.add_submodule(&frequency_module)?;
+
+    let functional_module = PyModule::new(m.py(), "functional")?;
+    functional::python::init_functional(&functional_module)?;
+    m.add_submodule(&functional_module)?;
+
+    let groundwater_module = PyModule::new(m.py(), "groundwater")?;
+    groundwater::python::init_groundwater(&groundwater_module)?;
+    m.add_submodule(&groundwater_module)?;
+
+    let precip_module = PyModule::new(m.py(), "precip")?;
+    precip::python::init_precip(&precip_module)?;
+    m.add_submodule(&precip_module)?;
+
+    let quality_module = PyModule::new(m.py(), "quality")?;
+    quality::python::init_quality(&quality_module)?;
+    m.add_submodule(&quality_module)?;
+
+    let scenario_module = PyModule::new(m.py(), "scenario")?;
+    scenario::python::init_scenario(&scenario_module)?;
+    m.add_submodule(&scenario_module)?;
+
+    let sediment_module = PyModule::new(m.py(), "sediment")?;
+    sediment::python::init_sediment(&sediment_module)?;
+    m.add_submodule(&sediment_module)?;
+
+    let stochastic_module = PyModule::new(m.py(), "stochastic")?;
+    stochastic::python::init_stochastic(&stochastic_module)?;
+    m.add_submodule(&stochastic_module)?;
+
+    let terrain_module = PyModule::new(m.py(), "terrain")?;
+    terrain::python::init_terrain(&terrain_module)?;
+    m.add_submodule(&terrain_module)?;
+
+    let timeseries_module = PyModule::new(m.py(), "timeseries")?;
+    timeseries::python::init_timeseries(&timeseries_module)?;
+    m.add_submodule(&timeseries_module)?;
+
     Ok(())
 }
@@ -0,0 +1,191 @@
+//! Minimal logging facade for diagnostics from the numerical cores:
+//! routing coefficients, sub-stepping decisions and solver iterations.
+//!
+//! Messages go to a sink installed by the host; the Python extension
+//! forwards them to the standard `logging` module under the `rustflow`
+//! logger, one child logger per Rust module (e.g.
+//! `rustflow.reach_routing.muskingum`). Without a sink, or above the
+//! maximum level, a message costs one atomic load and is never formatted.
+//!
+//! The Python sink acquires the GIL, so code that logs from `par_map`
+//! workers must be called with the GIL released.
+//!
+//! Warnings can also be collected for run reports with [`capture_warnings`],
+//! whether or not a sink is installed.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    /// Per-step detail such as solver iteration counts.
+    Trace,
+}
+
+type Sink = Box<dyn Fn(Level, &str, &str) + Send + Sync>;
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+/// Most verbose level passed to the sink; 0 disables logging.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+/// Number of active [`capture_warnings`] calls.
+static CAPTURING: AtomicUsize = AtomicUsize::new(0);
+/// Warnings and errors collected while capturing.
+static CAPTURED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Installs the sink receiving `(level, target, message)`, where `target`
+/// is the Rust module path. Only the first sink installed is kept.
+pub fn set_sink(sink: impl Fn(Level, &str, &str) + Send + Sync + 'static) {
+    let _ = SINK.set(Box::new(sink));
+}
+
+/// Sets the most verbose level passed to the sink; `None` disables logging.
+pub fn set_max_level(level: Option<Level>) {
+    MAX_LEVEL.store(level.map_or(0, |l| l as u8), Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+        || (level <= Level::Warn && CAPTURING.load(Ordering::Relaxed) > 0)
+}
+
+/// Sends a message to the sink, and to the captured warnings while
+/// capturing. Use the [`debug!`], [`trace!`] and [`warning!`] macros,
+/// which skip formatting when the level is disabled.
+pub fn log(level: Level, target: &str, args: fmt::Arguments<'_>) {
+    let message = args.to_string();
+    if level <= Level::Warn && CAPTURING.load(Ordering::Relaxed) > 0 {
+        if let Ok(mut captured) = CAPTURED.lock() {
+            captured.push(message.clone());
+        }
+    }
+    if level as u8 <= MAX_LEVEL.load(Ordering::Relaxed) {
+        if let Some(sink) = SINK.get() {
+            sink(level, target, &message);
+        }
+    }
+}
+
+/// Runs `f` and returns its result with the warnings and errors logged
+/// while it ran, which still reach the sink as usual. Captures running at
+/// the same time on other threads see each other's warnings.
+pub fn capture_warnings<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let start = {
+        CAPTURING.fetch_add(1, Ordering::Relaxed);
+        CAPTURED.lock().map_or(0, |captured| captured.len())
+    };
+    let result = f();
+    let warnings = match CAPTURED.lock() {
+        Ok(mut captured) => {
+            let warnings = captured.get(start..).unwrap_or_default().to_vec();
+            if CAPTURING.fetch_sub(1, Ordering::Relaxed) == 1 {
+                captured.clear();
+            }
+            warnings
+        }
+        Err(_) => {
+            CAPTURING.fetch_sub(1, Ordering::Relaxed);
+            Vec::new()
+        }
+    };
+    (result, warnings)
+}
+
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level) {
+            $crate::log::log($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+macro_rules! warning {
+    ($($arg:tt)+) => { $crate::log::log_at!($crate::log::Level::Warn, $($arg)+) };
+}
+
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log::log_at!($crate::log::Level::Info, $($arg)+) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log::log_at!($crate::log::Level::Debug, $($arg)+) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log::log_at!($crate::log::Level::Trace, $($arg)+) };
+}
+
+pub(crate) use {debug, info, log_at, trace, warning};
+
+/// Python `logging` level of `level`; trace maps to 5, below `DEBUG`.
+#[cfg(feature = "python")]
+fn python_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => 5,
+    }
+}
+
+/// Sets the maximum level from the effective level of the Python
+/// `rustflow` logger.
+#[cfg(feature = "python")]
+fn sync_python_level(py: pyo3::Python<'_>) -> pyo3::PyResult<()> {
+    use pyo3::types::PyAnyMethods;
+
+    let effective: u8 = py
+        .import("logging")?
+        .call_method1("getLogger", ("rustflow",))?
+        .call_method0("getEffectiveLevel")?
+        .extract()?;
+    let level = [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+    ]
+    .into_iter()
+    .find(|&l| python_level(l) >= effective);
+    set_max_level(level);
+    Ok(())
+}
+
+/// Re-reads the level of the `rustflow` logger. The level is read when the
+/// module is imported; call this after changing it, e.g. with
+/// `logging.getLogger("rustflow").setLevel(logging.DEBUG)`.
+#[cfg(feature = "python")]
+#[pyo3::pyfunction]
+pub fn reset_logging(py: pyo3::Python<'_>) -> pyo3::PyResult<()> {
+    sync_python_level(py)
+}
+
+/// Forwards messages to Python's `logging` and registers
+/// `reset_logging` on the top-level module.
+#[cfg(feature = "python")]
+pub fn install_python(m: &pyo3::Bound<'_, pyo3::types::PyModule>) -> pyo3::PyResult<()> {
+    use pyo3::prelude::*;
+
+    set_sink(|level, target, message| {
+        Python::with_gil(|py| {
+            let name = target.replace("::", ".");
+            let emitted = py
+                .import("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (name,)))
+                .and_then(|logger| logger.call_method1("log", (python_level(level), message)));
+            // A failing handler must not abort the numerical run.
+            if let Err(err) = emitted {
+                err.print(py);
+            }
+        })
+    });
+    sync_python_level(m.py())?;
+    m.add_function(wrap_pyfunction!(reset_logging, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::config::Value;
+use crate::error::{Error, Result};
+
+/// Adaptive time stepping of a network run. Steps are the reporting
+/// interval multiplied or divided by powers of two: a step is repeated at
+/// half the size while any node's inflow or outflow changes over it by
+/// more than `tolerance` relative to its value, and the next step is
+/// doubled after a step changing them by less than half of it. Refined
+/// steps stay within one reporting interval and coarse steps start at a
+/// reporting time and cover whole intervals. Steps are not refined below
+/// `2 K x / sub_reaches` of any Muskingum node that does not allow a
+/// negative inflow coefficient `c0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveStep {
+    /// Largest relative change in any node's inflow or outflow accepted
+    /// over one step.
+    pub tolerance: f64,
+    /// Finest step is the reporting interval divided by `2^max_refinement`.
+    pub max_refinement: u32,
+    /// Coarsest step is the reporting interval times `2^max_coarsening`.
+    pub max_coarsening: u32,
+}
+
+/// Most halvings or doublings of the reporting interval.
+const MAX_LEVELS: u32 = 16;
+
+impl AdaptiveStep {
+    /// Steps fixed at the reporting interval.
+    pub const FIXED: AdaptiveStep = AdaptiveStep {
+        tolerance: f64::INFINITY,
+        max_refinement: 0,
+        max_coarsening: 0,
+    };
+
+    /// Reads the optional `adaptive_step` table of a network configuration,
+    /// with `tolerance` (default 0.05), `max_refinement` (default 4) and
+    /// `max_coarsening` (default 3) fields.
+    pub fn from_value(value: &Value) -> Result<Option<Self>> {
+        let Some(table) = value.get("adaptive_step") else {
+            return Ok(None);
+        };
+        let step = AdaptiveStep {
+            tolerance: table.opt_f64_field("tolerance")?.unwrap_or(0.05),
+            max_refinement: table.opt_f64_field("max_refinement")?.unwrap_or(4.0) as u32,
+            max_coarsening: table.opt_f64_field("max_coarsening")?.unwrap_or(3.0) as u32,
+        };
+        step.validate()?;
+        Ok(Some(step))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.tolerance.is_nan() || self.tolerance <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "The adaptive step tolerance must be positive.".into(),
+            ));
+        }
+        if self.max_refinement > MAX_LEVELS || self.max_coarsening > MAX_LEVELS {
+            return Err(Error::InvalidParameter(format!(
+                "The reporting interval may be halved or doubled at most {MAX_LEVELS} times."
+            )));
+        }
+        Ok(())
+    }
+
+    pub(super) fn is_fixed(&self) -> bool {
+        self.max_refinement == 0 && self.max_coarsening == 0
+    }
+}
@@ -0,0 +1,618 @@
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::groundwater::aquifer::{Aquifer, Drainage, StreamExchange};
+use crate::hydraulics::bridge::{Bridge, BridgeProfile};
+use crate::hydraulics::culvert::Culvert;
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::interp::interp;
+use crate::reach_routing::kinematic_wave::KinematicWaveReach;
+use crate::reach_routing::muskingum::{check_muskingum, MuskingumReach};
+use crate::reservoir::level_pool::LevelPoolReservoir;
+use crate::runoff::nonlinear_reservoir::NonlinearReservoir;
+use crate::units::UnitSystem;
+
+use super::flood_cells::FloodCells;
+use super::lateral::LateralWeir;
+use super::pump::PumpStation;
+use super::treatment::Treatment;
+
+/// External forcing applied to an element during one time step.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Forcing {
+    /// Rainfall intensity (mm/h or in/h, per the element's unit system).
+    pub rainfall: f64,
+    /// Evaporation depth over the step from a reservoir's water surface.
+    pub evaporation: f64,
+    /// Withdrawal allocated to a demand element (flow units).
+    pub withdrawal: f64,
+    /// External pollutant load entering the node (mass per second).
+    pub load: f64,
+}
+
+/// Withdrawal point, such as a water supply intake or irrigation diversion,
+/// abstracting its allocation from the flow passing through it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Demand {
+    /// Constant demand (flow units), used when `series` is not given.
+    pub demand: f64,
+    /// Name of a demand series, supplied with the inflow series.
+    pub series: Option<String>,
+    /// Allocation priority; lower numbers are served first.
+    pub priority: i64,
+    withdrawn: f64,
+}
+
+/// Confluence summing tributary outflows and local inflow, less optional
+/// channel losses.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Junction {
+    /// Constant loss (flow units).
+    pub loss: f64,
+    /// Fraction of the summed inflow lost.
+    pub loss_fraction: f64,
+    lost: f64,
+}
+
+/// Bridge crossing passing its inflow unchanged, with the water level it
+/// raises at its upstream face as the node stage.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BridgeCrossing {
+    pub bridge: Bridge,
+    /// Bed elevation below the bridge, the datum of the stage.
+    pub bed: f64,
+    pub units: UnitSystem,
+    profile: Option<BridgeProfile>,
+}
+
+impl BridgeCrossing {
+    /// Water surface elevation at the upstream face after the last step.
+    pub fn stage(&self) -> f64 {
+        self.profile.map_or(f64::NAN, |p| {
+            let drop = self.bridge.channel.bed_slope * self.bridge.length;
+            self.bed + self.units.length_from_si(drop + p.upstream)
+        })
+    }
+}
+
+/// How a bifurcation divides its inflow.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplitRule {
+    /// Fixed fraction of the inflow diverted.
+    Ratio(f64),
+    /// Diverted flow interpolated from an inflow–diverted flow table.
+    Table(Vec<f64>, Vec<f64>),
+    /// Flow above `threshold` diverted, up to an optional capacity.
+    Threshold {
+        threshold: f64,
+        capacity: Option<f64>,
+    },
+}
+
+impl SplitRule {
+    /// Diverted part of `inflow`, within `[0, inflow]`.
+    pub fn diverted(&self, inflow: f64) -> f64 {
+        let q = match self {
+            SplitRule::Ratio(fraction) => fraction * inflow,
+            SplitRule::Table(inflows, diverted) => interp(inflow, inflows, diverted),
+            SplitRule::Threshold {
+                threshold,
+                capacity,
+            } => (inflow - threshold).min(capacity.unwrap_or(f64::INFINITY)),
+        };
+        q.clamp(0.0, inflow.max(0.0))
+    }
+}
+
+/// Flow split sending part of the inflow to a second node, such as a
+/// bypass channel, side weir or braided branch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bifurcation {
+    pub rule: SplitRule,
+    /// Id of the node receiving the diverted flow.
+    pub divert_to: String,
+    diverted: f64,
+}
+
+/// A routing element placed at a network node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Element {
+    Muskingum(MuskingumReach),
+    /// Kinematic wave reach; flows in the network are in the discharge units
+    /// of the unit system (m³/s or cfs).
+    KinematicWave(KinematicWaveReach, UnitSystem),
+    Reservoir(LevelPoolReservoir),
+    /// Passes the summed inflow through, less any losses.
+    Junction(Junction),
+    /// Subcatchment producing overland runoff from rainfall forcing.
+    Subcatchment(NonlinearReservoir, UnitSystem),
+    Demand(Demand),
+    /// Splits its inflow between `downstream` and a diversion node.
+    Bifurcation(Bifurcation),
+    /// Aquifer recharged by rainfall forcing (as a recharge rate) and by
+    /// its inflow, draining baseflow to `downstream`.
+    Groundwater(Aquifer, UnitSystem),
+    /// Stormwater treatment device (bioretention, detention or
+    /// infiltration trench).
+    Treatment(Treatment),
+    /// Bridge raising the water level upstream of its opening; tailwater
+    /// is normal depth in the channel.
+    Bridge(BridgeCrossing),
+    /// Pump station emptying a wet well to `downstream`.
+    Pump(PumpStation),
+    /// Levee or side weir exchanging flow with an off-channel storage area.
+    LateralWeir(LateralWeir),
+    /// Storage cells linked to each other and to the channel by weirs and
+    /// culverts, collecting rainfall forcing on their areas.
+    FloodCells(FloodCells),
+}
+
+/// Outflow of a reservoir drained by a culvert at each pool elevation,
+/// from the culvert table's `invert` elevation and optional `tailwater`
+/// depth, in its `units`.
+fn culvert_outflow(value: &Value, elevation: &[f64]) -> Result<Vec<f64>> {
+    let units = units_field(value)?;
+    let culvert = Culvert::from_value(value, units)?;
+    let invert = value.f64_field("invert")?;
+    let tailwater = units.length_to_si(value.opt_f64_field("tailwater")?.unwrap_or(0.0));
+    let depth: Vec<f64> = elevation
+        .iter()
+        .map(|&z| units.length_to_si(z - invert))
+        .collect();
+    Ok(culvert
+        .rating(&depth, 0.0, tailwater)
+        .into_iter()
+        .map(|q| units.discharge_from_si(q))
+        .collect())
+}
+
+pub(super) fn units_field(value: &Value) -> Result<UnitSystem> {
+    match value.opt_str_field("units")? {
+        None => Ok(UnitSystem::Si),
+        Some(name) => UnitSystem::parse(name)
+            .ok_or_else(|| Error::Data(format!("Unknown unit system `{name}`."))),
+    }
+}
+
+impl Element {
+    /// Builds an element from a configuration table whose `type` field
+    /// selects the element kind.
+    pub fn from_value(value: &Value) -> Result<Self> {
+        match value.str_field("type")? {
+            "muskingum" => {
+                let k = value.f64_field("k")?;
+                let x = value.f64_field("x")?;
+                let sub_reaches = value.opt_f64_field("sub_reaches")?.unwrap_or(1.0);
+                // The time step is checked when the network is run.
+                check_muskingum(&[0.0], 1.0, k, x, sub_reaches as i64, true)?;
+                if !(0.0..=0.5).contains(&x) {
+                    return Err(Error::InvalidParameter(
+                        "`x` must be within [0, 0.5].".into(),
+                    ));
+                }
+                Ok(Element::Muskingum(MuskingumReach::new(
+                    k,
+                    x,
+                    sub_reaches as usize,
+                    value.opt_f64_field("initial_outflow")?,
+                )))
+            }
+            "kinematic_wave" => {
+                let units = units_field(value)?;
+                let channel = TrapezoidalChannel::from_units(
+                    value.f64_field("bottom_width")?,
+                    value.opt_f64_field("side_slope")?.unwrap_or(0.0),
+                    value.f64_field("n")?,
+                    value.f64_field("slope")?,
+                    units,
+                )?;
+                let segments = value.opt_f64_field("segments")?.unwrap_or(10.0) as usize;
+                Ok(Element::KinematicWave(
+                    KinematicWaveReach::new(
+                        channel,
+                        units.length_to_si(value.f64_field("length")?),
+                        segments,
+                    )?,
+                    units,
+                ))
+            }
+            "reservoir" => {
+                let elevation = value.f64_array_field("elevation")?;
+                let area = match value.get("area") {
+                    Some(_) => Some(value.f64_array_field("area")?),
+                    None => None,
+                };
+                let seepage = match value.get("seepage") {
+                    Some(Value::Array(_)) => Some(value.f64_array_field("seepage")?),
+                    Some(_) => Some(vec![value.f64_field("seepage")?; elevation.len()]),
+                    None => None,
+                };
+                let outflow = match value.get("culvert") {
+                    Some(culvert) => culvert_outflow(culvert, &elevation)?,
+                    None => value.f64_array_field("outflow")?,
+                };
+                Ok(Element::Reservoir(
+                    LevelPoolReservoir::new(
+                        elevation,
+                        value.f64_array_field("storage")?,
+                        outflow,
+                        value.f64_field("initial_elevation")?,
+                    )?
+                    .with_losses(area, seepage)?,
+                ))
+            }
+            "junction" => {
+                let loss = value.opt_f64_field("loss")?.unwrap_or(0.0);
+                let loss_fraction = value.opt_f64_field("loss_fraction")?.unwrap_or(0.0);
+                if loss < 0.0 || !(0.0..=1.0).contains(&loss_fraction) {
+                    return Err(Error::InvalidParameter(
+                        "Junction `loss` must be non-negative and `loss_fraction` within [0, 1]."
+                            .into(),
+                    ));
+                }
+                Ok(Element::Junction(Junction {
+                    loss,
+                    loss_fraction,
+                    lost: 0.0,
+                }))
+            }
+            "subcatchment" => {
+                let units = units_field(value)?;
+                Ok(Element::Subcatchment(
+                    NonlinearReservoir::new(
+                        units.land_area_to_si(value.f64_field("area")?),
+                        units.length_to_si(value.f64_field("width")?),
+                        value.f64_field("slope")?,
+                        value.f64_field("n")?,
+                        units.small_depth_to_si(
+                            value.opt_f64_field("depression_storage")?.unwrap_or(0.0),
+                        ),
+                        units.rate_to_si(value.opt_f64_field("loss_rate")?.unwrap_or(0.0)),
+                    )?,
+                    units,
+                ))
+            }
+            "demand" => {
+                let (demand, series) = match value.get("demand") {
+                    Some(Value::Number(q)) => (*q, None),
+                    Some(Value::String(name)) => (0.0, Some(name.clone())),
+                    _ => {
+                        return Err(Error::Data(
+                            "Field `demand` must be a number or the name of a demand series."
+                                .into(),
+                        ))
+                    }
+                };
+                Ok(Element::Demand(Demand {
+                    demand,
+                    series,
+                    priority: value.opt_f64_field("priority")?.unwrap_or(1.0) as i64,
+                    withdrawn: 0.0,
+                }))
+            }
+            "bifurcation" => {
+                let rule = if let Some(fraction) = value.opt_f64_field("ratio")? {
+                    if !(0.0..=1.0).contains(&fraction) {
+                        return Err(Error::InvalidParameter(
+                            "Bifurcation `ratio` must be within [0, 1].".into(),
+                        ));
+                    }
+                    SplitRule::Ratio(fraction)
+                } else if let Some(threshold) = value.opt_f64_field("threshold")? {
+                    SplitRule::Threshold {
+                        threshold,
+                        capacity: value.opt_f64_field("capacity")?,
+                    }
+                } else if value.get("table_inflow").is_some() {
+                    let inflows = value.f64_array_field("table_inflow")?;
+                    let diverted = value.f64_array_field("table_diverted")?;
+                    if inflows.len() != diverted.len() || inflows.len() < 2 {
+                        return Err(Error::InvalidParameter(
+                            "`table_inflow` and `table_diverted` need at least two values of equal length."
+                                .into(),
+                        ));
+                    }
+                    if inflows.windows(2).any(|w| w[1] <= w[0]) {
+                        return Err(Error::InvalidParameter(
+                            "`table_inflow` must be strictly increasing.".into(),
+                        ));
+                    }
+                    SplitRule::Table(inflows, diverted)
+                } else {
+                    return Err(Error::Data(
+                        "A bifurcation needs `ratio`, `threshold` or `table_inflow`/`table_diverted`."
+                            .into(),
+                    ));
+                };
+                Ok(Element::Bifurcation(Bifurcation {
+                    rule,
+                    divert_to: value.str_field("divert_to")?.to_string(),
+                    diverted: 0.0,
+                }))
+            }
+            "groundwater" => {
+                let units = units_field(value)?;
+                let drainage = match value.opt_f64_field("k")? {
+                    Some(k) => Drainage::Linear { k },
+                    None => Drainage::nonlinear_from_units(
+                        value.f64_field("coefficient")?,
+                        value.opt_f64_field("exponent")?.unwrap_or(1.0),
+                        units,
+                    ),
+                };
+                let bottom = units.length_to_si(value.opt_f64_field("bottom")?.unwrap_or(0.0));
+                let exchange = match value.opt_f64_field("conductance")? {
+                    None => None,
+                    Some(c) => Some(StreamExchange {
+                        conductance: units.area_to_si(c),
+                        bed: value
+                            .opt_f64_field("stream_bed")?
+                            .map_or(bottom, |b| units.length_to_si(b)),
+                    }),
+                };
+                Ok(Element::Groundwater(
+                    Aquifer::new(
+                        units.land_area_to_si(value.f64_field("area")?),
+                        value.f64_field("specific_yield")?,
+                        bottom,
+                        drainage,
+                        exchange,
+                        value
+                            .opt_f64_field("initial_head")?
+                            .map(|h| units.length_to_si(h)),
+                    )?,
+                    units,
+                ))
+            }
+            "treatment" => Ok(Element::Treatment(Treatment::from_value(
+                value,
+                units_field(value)?,
+            )?)),
+            "bridge" => {
+                let units = units_field(value)?;
+                Ok(Element::Bridge(BridgeCrossing {
+                    bridge: Bridge::from_value(value, units)?,
+                    bed: value.opt_f64_field("bed")?.unwrap_or(0.0),
+                    units,
+                    profile: None,
+                }))
+            }
+            "pump" => Ok(Element::Pump(PumpStation::from_value(
+                value,
+                units_field(value)?,
+            )?)),
+            "lateral_weir" => Ok(Element::LateralWeir(LateralWeir::from_value(
+                value,
+                units_field(value)?,
+            )?)),
+            "flood_cells" => Ok(Element::FloodCells(FloodCells::from_value(
+                value,
+                units_field(value)?,
+            )?)),
+            other => Err(Error::Data(format!("Unknown element type `{other}`."))),
+        }
+    }
+
+    /// Advances the element by `dt` seconds and returns its outflow.
+    pub fn step(&mut self, inflow: f64, forcing: &Forcing, dt: f64) -> f64 {
+        match self {
+            Element::Muskingum(reach) => reach.step(inflow, dt),
+            Element::KinematicWave(reach, units) => {
+                units.discharge_from_si(reach.step(units.discharge_to_si(inflow), dt))
+            }
+            Element::Reservoir(reservoir) => {
+                reservoir.step_with_evaporation(inflow, forcing.evaporation / dt, dt)
+            }
+            Element::Junction(junction) => {
+                junction.lost =
+                    (junction.loss + junction.loss_fraction * inflow).clamp(0.0, inflow.max(0.0));
+                inflow - junction.lost
+            }
+            Element::Subcatchment(surface, units) => {
+                let runoff = surface.step(units.rate_to_si(forcing.rainfall), dt);
+                inflow + units.discharge_from_si(runoff)
+            }
+            Element::Demand(demand) => {
+                demand.withdrawn = forcing.withdrawal.clamp(0.0, inflow.max(0.0));
+                inflow - demand.withdrawn
+            }
+            Element::Bifurcation(split) => {
+                split.diverted = split.rule.diverted(inflow);
+                inflow - split.diverted
+            }
+            Element::Groundwater(aquifer, units) => {
+                let recharge = units.rate_to_si(forcing.rainfall)
+                    + units.discharge_to_si(inflow) / aquifer.area;
+                units.discharge_from_si(aquifer.drain(recharge, dt))
+            }
+            Element::Treatment(device) => {
+                let units = device.units;
+                units.discharge_from_si(device.step(units.discharge_to_si(inflow), dt))
+            }
+            Element::Bridge(crossing) => {
+                let q = crossing.units.discharge_to_si(inflow);
+                crossing.profile = Some(crossing.bridge.profile(q, None));
+                inflow
+            }
+            Element::Pump(station) => {
+                let units = station.units;
+                units.discharge_from_si(station.step(units.discharge_to_si(inflow), dt))
+            }
+            Element::LateralWeir(weir) => {
+                let units = weir.units;
+                units.discharge_from_si(weir.step(units.discharge_to_si(inflow), dt))
+            }
+            Element::FloodCells(cells) => {
+                let units = cells.units;
+                let q = cells.step(
+                    units.discharge_to_si(inflow),
+                    units.rate_to_si(forcing.rainfall),
+                    dt,
+                );
+                units.discharge_from_si(q)
+            }
+        }
+    }
+
+    /// Routes the pollutant `load` (mass per second) that entered with
+    /// `inflow` during the last step and returns `(load to downstream,
+    /// load to the diversion node, load removed by treatment)`. Loads
+    /// leave other elements in proportion to their diverted, withdrawn and
+    /// lost flows; pollutant storage is only modelled by treatment devices.
+    pub fn route_load(&mut self, load: f64, inflow: f64, dt: f64) -> (f64, f64, f64) {
+        if let Element::Treatment(device) = self {
+            let (out, removed) = device.route_load(load, dt);
+            return (out, 0.0, removed);
+        }
+        let share = |q: f64| {
+            if inflow > 0.0 {
+                (q / inflow).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        // Evaporation leaves the load behind.
+        let lost = match self {
+            Element::Reservoir(reservoir) => reservoir.seepage_loss(),
+            _ => self.loss(),
+        };
+        let diverted = load * share(self.diverted());
+        let gone = load * share(self.withdrawal() + lost);
+        ((load - diverted - gone).max(0.0), diverted, 0.0)
+    }
+
+    /// Inflow captured by a treatment device during the last step rather
+    /// than bypassed (zero for other elements).
+    pub fn captured(&self) -> f64 {
+        match self {
+            Element::Treatment(device) => device.units.discharge_from_si(device.captured()),
+            _ => 0.0,
+        }
+    }
+
+    /// Exchanges water between a groundwater element and the stream it
+    /// drains to, whose water surface is `depth` (m) above the streambed,
+    /// and returns the flow to the stream. Losses from the stream are
+    /// limited to `available`. Zero for other elements.
+    pub fn exchange_with_stream(&mut self, depth: f64, available: f64, dt: f64) -> f64 {
+        match self {
+            Element::Groundwater(aquifer, units) => {
+                let Some(link) = aquifer.exchange else {
+                    return 0.0;
+                };
+                units.discharge_from_si(aquifer.exchange_with(
+                    link.bed + depth,
+                    units.discharge_to_si(available),
+                    dt,
+                ))
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Flow exchanged with the stream during the last step (zero except
+    /// for groundwater elements, lateral weirs and flood cells).
+    pub fn exchange(&self) -> f64 {
+        match self {
+            Element::Groundwater(aquifer, units) => units.discharge_from_si(aquifer.exchanged()),
+            Element::LateralWeir(weir) => weir.units.discharge_from_si(weir.returned()),
+            Element::FloodCells(cells) => cells.units.discharge_from_si(cells.returned()),
+            _ => 0.0,
+        }
+    }
+
+    /// Flow depth at the outlet (m) of channel elements, used as the stream
+    /// level for groundwater exchange; zero for other elements.
+    pub fn flow_depth(&self) -> f64 {
+        match self {
+            Element::KinematicWave(reach, _) => reach.outlet_depth(),
+            _ => 0.0,
+        }
+    }
+
+    /// Flow sent to the diversion node during the last step (zero except
+    /// for bifurcations).
+    pub fn diverted(&self) -> f64 {
+        match self {
+            Element::Bifurcation(split) => split.diverted,
+            _ => 0.0,
+        }
+    }
+
+    /// Flow lost from the channel during the last step, including
+    /// infiltration from treatment devices and evaporation and seepage from
+    /// reservoirs (zero for elements without losses).
+    pub fn loss(&self) -> f64 {
+        match self {
+            Element::Junction(junction) => junction.lost,
+            Element::Reservoir(reservoir) => {
+                reservoir.evaporation_loss() + reservoir.seepage_loss()
+            }
+            Element::Treatment(device) => device.units.discharge_from_si(device.infiltrated()),
+            _ => 0.0,
+        }
+    }
+
+    /// Flow withdrawn during the last step (zero except for demands).
+    pub fn withdrawal(&self) -> f64 {
+        match self {
+            Element::Demand(demand) => demand.withdrawn,
+            _ => 0.0,
+        }
+    }
+
+    /// Volume currently stored in the element, in flow units × seconds.
+    pub fn storage(&self) -> f64 {
+        match self {
+            Element::Muskingum(reach) => reach.storage(),
+            Element::KinematicWave(reach, units) => units.volume_from_si(reach.storage()),
+            Element::Reservoir(reservoir) => reservoir.current_storage(),
+            Element::Junction(_) | Element::Demand(_) | Element::Bifurcation(_) => 0.0,
+            Element::Subcatchment(surface, units) => units.volume_from_si(surface.storage()),
+            Element::Groundwater(aquifer, units) => units.volume_from_si(aquifer.storage()),
+            Element::Treatment(device) => device.units.volume_from_si(device.volume()),
+            Element::Bridge(_) => 0.0,
+            Element::Pump(station) => station.units.volume_from_si(station.volume()),
+            Element::LateralWeir(weir) => weir.units.volume_from_si(weir.volume()),
+            Element::FloodCells(cells) => cells.units.volume_from_si(cells.volume()),
+        }
+    }
+
+    /// Water surface elevation, or depth for channel elements, where the
+    /// element defines one (`NaN` otherwise).
+    pub fn stage(&self) -> f64 {
+        match self {
+            Element::Reservoir(reservoir) => reservoir.pool_elevation(),
+            Element::KinematicWave(reach, units) => units.length_from_si(reach.outlet_depth()),
+            Element::Subcatchment(surface, units) => units.small_depth_from_si(surface.depth()),
+            Element::Groundwater(aquifer, units) => units.length_from_si(aquifer.head()),
+            Element::Bridge(crossing) => crossing.stage(),
+            Element::Pump(station) => station.units.length_from_si(station.level()),
+            Element::LateralWeir(weir) => weir.units.length_from_si(weir.level()),
+            Element::Muskingum(_)
+            | Element::Junction(_)
+            | Element::Demand(_)
+            | Element::Bifurcation(_)
+            | Element::Treatment(_)
+            | Element::FloodCells(_) => f64::NAN,
+        }
+    }
+
+    /// Clears the routing state.
+    pub fn reset(&mut self) {
+        match self {
+            Element::Muskingum(reach) => reach.reset(),
+            Element::KinematicWave(reach, _) => reach.reset(),
+            Element::Reservoir(reservoir) => reservoir.reset(),
+            Element::Junction(junction) => junction.lost = 0.0,
+            Element::Subcatchment(surface, _) => surface.reset(),
+            Element::Demand(demand) => demand.withdrawn = 0.0,
+            Element::Bifurcation(split) => split.diverted = 0.0,
+            Element::Groundwater(aquifer, _) => aquifer.reset(),
+            Element::Treatment(device) => device.reset(),
+            Element::Bridge(crossing) => crossing.profile = None,
+            Element::Pump(station) => station.reset(),
+            Element::LateralWeir(weir) => weir.reset(),
+            Element::FloodCells(cells) => cells.reset(),
+        }
+    }
+}
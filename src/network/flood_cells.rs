@@ -0,0 +1,438 @@
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::hydraulics::culvert::Culvert;
+use crate::hydraulics::weir::{weir_flow, BROAD_CRESTED};
+use crate::interp::interp;
+use crate::units::UnitSystem;
+
+use super::lateral::{si_array, strictly_increasing};
+
+/// Longest sub-step (s) of the implicit cell balance.
+const MAX_SUBSTEP: f64 = 300.0;
+const MAX_ITERATIONS: usize = 50;
+/// Level change (m) below which the Newton iteration has converged.
+const TOLERANCE: f64 = 1e-6;
+/// Head difference (m) below which link flows are linear in the head
+/// difference, as the submerged-flow curves are vertical at equal levels.
+const LINEAR_HEAD: f64 = 0.01;
+
+/// Storage area: a level pool, optionally with a catchment area collecting
+/// rainfall.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    pub id: String,
+    /// Elevations (m) and storage (m³), both strictly increasing.
+    pub elevation: Vec<f64>,
+    pub storage: Vec<f64>,
+    pub initial_elevation: f64,
+    /// Area (m²) over which rainfall runs into the cell.
+    pub area: f64,
+}
+
+impl Cell {
+    fn volume_at(&self, level: f64) -> f64 {
+        interp(level, &self.elevation, &self.storage)
+    }
+
+    fn level_at(&self, volume: f64) -> f64 {
+        interp(volume, &self.storage, &self.elevation)
+    }
+}
+
+/// Structure through which a link passes flow.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LinkKind {
+    /// Weir of `crest` elevation (m), `length` (m) and `coefficient`
+    /// (m^0.5/s), submerged by the lower side.
+    Weir {
+        crest: f64,
+        length: f64,
+        coefficient: f64,
+    },
+    /// Culvert with its inlet invert at `invert` (m) on the `from` side.
+    Culvert { culvert: Culvert, invert: f64 },
+}
+
+/// Connection between two cells, or a cell and the river (`None`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    pub from: Option<usize>,
+    pub to: Option<usize>,
+    pub kind: LinkKind,
+    /// Flap gate closing the link to flow from `to` to `from`.
+    pub flap_gate: bool,
+}
+
+impl Link {
+    /// Flow (m³/s) from `from` at level `upper` to `to` at level `lower`,
+    /// negative when it runs the other way.
+    fn flow(&self, upper: f64, lower: f64) -> f64 {
+        let difference = upper - lower;
+        let q = if difference.abs() < LINEAR_HEAD {
+            let mid = 0.5 * (upper + lower);
+            let full = self.structure_flow(mid + 0.5 * LINEAR_HEAD, mid - 0.5 * LINEAR_HEAD);
+            full * difference / LINEAR_HEAD
+        } else {
+            self.structure_flow(upper, lower)
+        };
+        if self.flap_gate {
+            q.max(0.0)
+        } else {
+            q
+        }
+    }
+
+    fn structure_flow(&self, upper: f64, lower: f64) -> f64 {
+        match &self.kind {
+            LinkKind::Weir {
+                crest,
+                length,
+                coefficient,
+            } => weir_flow(*coefficient, *length, *crest, upper, lower),
+            LinkKind::Culvert { culvert, invert } => {
+                let outlet = invert - culvert.length * culvert.slope;
+                if upper >= lower {
+                    culvert.discharge(upper - invert, lower - outlet)
+                } else {
+                    -culvert.discharge(lower - outlet, upper - invert)
+                }
+            }
+        }
+    }
+}
+
+/// Interconnected storage areas, such as floodplain compartments or the
+/// interior drainage behind a levee, exchanging flow with each other and
+/// with the river through weir and culvert links, in SI units.
+///
+/// The river level is read from a stage–discharge rating at the flow
+/// continuing downstream, which is the inflow plus the net flow returned by
+/// the cells. Cell levels and that flow are solved together by backward
+/// Euler with Newton iteration, so links between small cells stay stable
+/// over long steps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloodCells {
+    pub cells: Vec<Cell>,
+    pub links: Vec<Link>,
+    /// Discharges (m³/s) of the river rating, strictly increasing.
+    pub channel_discharge: Vec<f64>,
+    /// River levels (m) at each discharge.
+    pub channel_stage: Vec<f64>,
+    pub units: UnitSystem,
+    /// Stored volume (m³) of each cell.
+    volume: Vec<f64>,
+    /// Mean flow (m³/s) from the cells to the river over the last step.
+    returned: f64,
+}
+
+/// Solves the dense system `a x = b` by Gaussian elimination with partial
+/// pivoting.
+fn solve_dense(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&r, &s| a[r][col].abs().total_cmp(&a[s][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..n {
+            let f = a[row][col] / a[col][col];
+            let pivot_row = a[col].clone();
+            for (v, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *v -= f * p;
+            }
+            b[row] -= f * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let s: f64 = (row + 1..n).map(|c| a[row][c] * x[c]).sum();
+        x[row] = (b[row] - s) / a[row][row];
+    }
+    Some(x)
+}
+
+impl FloodCells {
+    /// Builds the cells from a table with the river rating
+    /// (`channel_discharge` and `channel_stage`), a `cells` list (each with
+    /// an `id`, `elevation` and `storage` arrays, optional
+    /// `initial_elevation`, default the lowest, and optional rainfall
+    /// `area` in m² or ft²) and a `links` list. Each link names its `from`
+    /// and `to` cells, `"river"` for the channel, and is a `"weir"`
+    /// (`crest`, `length`, optional `coefficient`) or a `"culvert"` (the
+    /// keys of [`Culvert::from_value`] and the `invert` of its `from` end),
+    /// with an optional `flap_gate` stopping reverse flow.
+    pub fn from_value(value: &Value, units: UnitSystem) -> Result<Self> {
+        let channel_discharge = si_array(value, "channel_discharge", |q| units.discharge_to_si(q))?;
+        let channel_stage = si_array(value, "channel_stage", |z| units.length_to_si(z))?;
+        if channel_discharge.len() < 2
+            || channel_stage.len() != channel_discharge.len()
+            || !strictly_increasing(&channel_discharge)
+        {
+            return Err(Error::InvalidParameter(
+                "The channel rating needs at least two rows of strictly increasing \
+                 `channel_discharge`."
+                    .into(),
+            ));
+        }
+        let list = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_array)
+                .ok_or_else(|| Error::Data(format!("Field `{key}` must be an array of tables.")))
+        };
+        let mut cells = Vec::new();
+        for cell in list("cells")? {
+            let elevation = si_array(cell, "elevation", |z| units.length_to_si(z))?;
+            let storage = si_array(cell, "storage", |v| units.volume_to_si(v))?;
+            if elevation.len() < 2
+                || storage.len() != elevation.len()
+                || !strictly_increasing(&elevation)
+                || !strictly_increasing(&storage)
+            {
+                return Err(Error::InvalidParameter(
+                    "Each cell needs `elevation` and `storage` tables of at least two rows, both \
+                     strictly increasing."
+                        .into(),
+                ));
+            }
+            let initial_elevation = match cell.opt_f64_field("initial_elevation")? {
+                Some(z) => units.length_to_si(z),
+                None => elevation[0],
+            };
+            cells.push(Cell {
+                id: cell.str_field("id")?.to_owned(),
+                elevation,
+                storage,
+                initial_elevation,
+                area: units.area_to_si(cell.opt_f64_field("area")?.unwrap_or(0.0)),
+            });
+        }
+        if cells.is_empty() {
+            return Err(Error::Data(
+                "A flood cell node needs at least one cell.".into(),
+            ));
+        }
+        let find = |link: &Value, key: &str| -> Result<Option<usize>> {
+            match link.str_field(key)? {
+                "river" => Ok(None),
+                id => cells
+                    .iter()
+                    .position(|c| c.id == id)
+                    .map(Some)
+                    .ok_or_else(|| Error::Data(format!("Link refers to unknown cell `{id}`."))),
+            }
+        };
+        let mut links = Vec::new();
+        for link in list("links")? {
+            let (from, to) = (find(link, "from")?, find(link, "to")?);
+            if from == to {
+                return Err(Error::Data(
+                    "A link must join two different cells or a cell and the river.".into(),
+                ));
+            }
+            let kind = match link.str_field("type")? {
+                "weir" => {
+                    let coefficient = match link.opt_f64_field("coefficient")? {
+                        Some(c) => units.weir_coefficient_to_si(c),
+                        None => BROAD_CRESTED,
+                    };
+                    let length = units.length_to_si(link.f64_field("length")?);
+                    if !(length > 0.0 && coefficient > 0.0) {
+                        return Err(Error::InvalidParameter(
+                            "Weir links need a positive `length` and `coefficient`.".into(),
+                        ));
+                    }
+                    LinkKind::Weir {
+                        crest: units.length_to_si(link.f64_field("crest")?),
+                        length,
+                        coefficient,
+                    }
+                }
+                "culvert" => LinkKind::Culvert {
+                    culvert: Culvert::from_value(link, units)?,
+                    invert: units.length_to_si(link.f64_field("invert")?),
+                },
+                other => {
+                    return Err(Error::Data(format!(
+                        "Unknown link type `{other}`; expected `weir` or `culvert`."
+                    )))
+                }
+            };
+            let flap_gate = link
+                .get("flap_gate")
+                .map(|v| {
+                    v.as_bool()
+                        .ok_or_else(|| Error::Data("Field `flap_gate` must be a boolean.".into()))
+                })
+                .transpose()?
+                .unwrap_or(false);
+            links.push(Link {
+                from,
+                to,
+                kind,
+                flap_gate,
+            });
+        }
+        let mut network = FloodCells {
+            cells,
+            links,
+            channel_discharge,
+            channel_stage,
+            units,
+            volume: Vec::new(),
+            returned: 0.0,
+        };
+        network.reset();
+        Ok(network)
+    }
+
+    pub fn reset(&mut self) {
+        self.volume = self
+            .cells
+            .iter()
+            .map(|c| c.volume_at(c.initial_elevation).max(0.0))
+            .collect();
+        self.returned = 0.0;
+    }
+
+    /// Level (m) of each cell.
+    pub fn levels(&self) -> Vec<f64> {
+        self.cells
+            .iter()
+            .zip(&self.volume)
+            .map(|(c, &v)| c.level_at(v))
+            .collect()
+    }
+
+    /// Total stored volume (m³).
+    pub fn volume(&self) -> f64 {
+        self.volume.iter().sum()
+    }
+
+    /// Stored volume (m³) of each cell.
+    pub fn volumes(&self) -> &[f64] {
+        &self.volume
+    }
+
+    /// Sets the stored volume (m³) of each cell, e.g. to resume a saved
+    /// simulation. Ignored unless it has one entry per cell.
+    pub fn set_volumes(&mut self, volumes: Vec<f64>) {
+        if volumes.len() == self.cells.len() {
+            self.volume = volumes.into_iter().map(|v| v.max(0.0)).collect();
+        }
+    }
+
+    /// Mean flow (m³/s) from the cells to the river over the last step;
+    /// negative while they fill.
+    pub fn returned(&self) -> f64 {
+        self.returned
+    }
+
+    fn river_level(&self, discharge: f64) -> f64 {
+        interp(
+            discharge.max(0.0),
+            &self.channel_discharge,
+            &self.channel_stage,
+        )
+    }
+
+    /// Backward-Euler residuals (m³) of the cell volumes and of the river
+    /// flow at the unknowns `x` (cell levels, then the flow continuing
+    /// downstream), over a sub-step `h` from volumes `old`.
+    fn residuals(&self, x: &[f64], old: &[f64], inflow: f64, rain: &[f64], h: f64) -> Vec<f64> {
+        let n = self.cells.len();
+        let river = self.river_level(x[n]);
+        let level = |node: Option<usize>| node.map_or(river, |i| x[i]);
+        let mut net = rain.to_vec();
+        let mut to_river = 0.0;
+        for link in &self.links {
+            let q = link.flow(level(link.from), level(link.to));
+            match link.from {
+                Some(i) => net[i] -= q,
+                None => to_river -= q,
+            }
+            match link.to {
+                Some(i) => net[i] += q,
+                None => to_river += q,
+            }
+        }
+        let mut r: Vec<f64> = (0..n)
+            .map(|i| self.cells[i].volume_at(x[i]) - old[i] - h * net[i])
+            .collect();
+        r.push(h * (x[n] - inflow - to_river));
+        r
+    }
+
+    /// Advances the cells by `dt` seconds with `inflow` (m³/s) in the river
+    /// and `rainfall` (m/s) on the cell areas, and returns the flow (m³/s)
+    /// continuing downstream.
+    pub fn step(&mut self, inflow: f64, rainfall: f64, dt: f64) -> f64 {
+        let n = self.cells.len();
+        let substeps = (dt / MAX_SUBSTEP).ceil().max(1.0) as usize;
+        let h = dt / substeps as f64;
+        let rain: Vec<f64> = self
+            .cells
+            .iter()
+            .map(|c| rainfall.max(0.0) * c.area)
+            .collect();
+        let mut returned = 0.0;
+        let mut x = self.levels();
+        x.push(inflow.max(0.0));
+        for _ in 0..substeps {
+            let old = self.volume.clone();
+            for _ in 0..MAX_ITERATIONS {
+                let r = self.residuals(&x, &old, inflow, &rain, h);
+                // Finite-difference Jacobian, by columns.
+                let mut jacobian = vec![vec![0.0; n + 1]; n + 1];
+                for j in 0..=n {
+                    let delta = if j < n {
+                        1e-5
+                    } else {
+                        1e-5 * x[n].abs().max(1.0)
+                    };
+                    let mut shifted = x.clone();
+                    shifted[j] += delta;
+                    let rs = self.residuals(&shifted, &old, inflow, &rain, h);
+                    for (row, (a, b)) in jacobian.iter_mut().zip(rs.iter().zip(&r)) {
+                        row[j] = (a - b) / delta;
+                    }
+                }
+                let Some(dx) = solve_dense(jacobian, r.iter().map(|v| -v).collect()) else {
+                    break;
+                };
+                // Halve the step until the residuals shrink.
+                let norm = |r: &[f64]| r.iter().map(|v| v * v).sum::<f64>();
+                let current = norm(&r);
+                let mut scale = 1.0;
+                let mut next = x.clone();
+                for _ in 0..20 {
+                    next = x.iter().zip(&dx).map(|(a, d)| a + scale * d).collect();
+                    if norm(&self.residuals(&next, &old, inflow, &rain, h)) < current {
+                        break;
+                    }
+                    scale *= 0.5;
+                }
+                let change = dx
+                    .iter()
+                    .take(n)
+                    .fold(0.0_f64, |m, d| m.max((scale * d).abs()));
+                x = next;
+                if change < TOLERANCE {
+                    break;
+                }
+            }
+            // The return flow balances the change in storage, so that
+            // volume is conserved to round-off whatever the residual left.
+            let before: f64 = self.volume.iter().sum();
+            for (i, cell) in self.cells.iter().enumerate() {
+                self.volume[i] = cell.volume_at(x[i]).max(0.0);
+            }
+            let gained = self.volume.iter().sum::<f64>() - before;
+            returned += rain.iter().sum::<f64>() * h - gained;
+        }
+        self.returned = returned / dt;
+        inflow + self.returned
+    }
+}
@@ -0,0 +1,183 @@
+//! Topology of a network: its links as a graph, upstream and downstream
+//! tracing, and export to Graphviz DOT for inspection.
+
+use super::{Network, Node};
+use crate::config::{json, Value};
+use crate::error::{Error, Result};
+
+/// Kind of link between two nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkType {
+    /// A node's outflow draining to its `downstream` node.
+    Downstream,
+    /// Flow diverted by a bifurcation to its `divert_to` node.
+    Diversion,
+}
+
+impl LinkType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkType::Downstream => "downstream",
+            LinkType::Diversion => "diversion",
+        }
+    }
+}
+
+/// Graphviz shape of each element type; other types are drawn as boxes.
+const SHAPES: [(&str, &str); 8] = [
+    ("reservoir", "invtrapezium"),
+    ("junction", "circle"),
+    ("subcatchment", "house"),
+    ("demand", "invhouse"),
+    ("bifurcation", "diamond"),
+    ("groundwater", "cylinder"),
+    ("treatment", "octagon"),
+    ("pump", "cds"),
+];
+
+impl Network {
+    /// Nodes in network order, each before the nodes it drains or diverts
+    /// to.
+    pub fn ordered_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.order.iter().map(|&i| &self.nodes[i])
+    }
+
+    /// Links between nodes as `(from, to, type)`, in network order.
+    pub fn links(&self) -> Vec<(&str, &str, LinkType)> {
+        let mut links = Vec::new();
+        for &i in &self.order {
+            let from = self.nodes[i].id.as_str();
+            if let Some(d) = self.downstream[i] {
+                links.push((from, self.nodes[d].id.as_str(), LinkType::Downstream));
+            }
+            if let Some(d) = self.diversion[i] {
+                links.push((from, self.nodes[d].id.as_str(), LinkType::Diversion));
+            }
+        }
+        links
+    }
+
+    /// Ids of the nodes whose water reaches node `id` through downstream or
+    /// diversion links, in network order.
+    pub fn upstream_of(&self, id: &str) -> Result<Vec<&str>> {
+        let target = self.index_of(id)?;
+        let mut reaches = vec![false; self.nodes.len()];
+        reaches[target] = true;
+        // Network order lists every node before the nodes it drains to, so
+        // walking it backwards sees each node's receivers first.
+        for &i in self.order.iter().rev() {
+            reaches[i] |= [self.downstream[i], self.diversion[i]]
+                .into_iter()
+                .flatten()
+                .any(|d| reaches[d]);
+        }
+        reaches[target] = false;
+        Ok(self.ids_where(&reaches))
+    }
+
+    /// Ids of the nodes that water leaving node `id` passes through, in
+    /// network order.
+    pub fn downstream_of(&self, id: &str) -> Result<Vec<&str>> {
+        let source = self.index_of(id)?;
+        let mut reached = vec![false; self.nodes.len()];
+        reached[source] = true;
+        for &i in &self.order {
+            if reached[i] {
+                for d in [self.downstream[i], self.diversion[i]]
+                    .into_iter()
+                    .flatten()
+                {
+                    reached[d] = true;
+                }
+            }
+        }
+        reached[source] = false;
+        Ok(self.ids_where(&reached))
+    }
+
+    /// Ids of the nodes without a downstream node, where water leaves the
+    /// network.
+    pub fn outlets(&self) -> Vec<&str> {
+        let outlet: Vec<bool> = self.downstream.iter().map(Option::is_none).collect();
+        self.ids_where(&outlet)
+    }
+
+    /// Graph in Graphviz DOT format, laid out from top to bottom. Nodes are
+    /// labelled with their id and element type and shaped by type, with
+    /// their configuration as a tooltip; diversions are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from(
+            "digraph network {\n    rankdir=TB;\n    node [style=filled, fillcolor=\"#eef3fb\"];\n",
+        );
+        for node in self.ordered_nodes() {
+            let kind = element_type(node);
+            let shape = SHAPES
+                .iter()
+                .find(|(t, _)| *t == kind)
+                .map_or("box", |(_, shape)| shape);
+            let tooltip = json::to_string(&node_attributes(node));
+            dot.push_str(&format!(
+                "    {} [label={}, shape={shape}, tooltip={}];\n",
+                quote(&node.id),
+                quote(&format!("{}\n{kind}", node.id)),
+                quote(&tooltip),
+            ));
+        }
+        for (from, to, link) in self.links() {
+            let style = match link {
+                LinkType::Downstream => "",
+                LinkType::Diversion => " [style=dashed, label=\"diversion\"]",
+            };
+            dot.push_str(&format!("    {} -> {}{style};\n", quote(from), quote(to)));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn index_of(&self, id: &str) -> Result<usize> {
+        self.nodes
+            .iter()
+            .position(|n| n.id == id)
+            .ok_or_else(|| Error::Data(format!("Unknown node `{id}`.")))
+    }
+
+    fn ids_where(&self, selected: &[bool]) -> Vec<&str> {
+        self.order
+            .iter()
+            .filter(|&&i| selected[i])
+            .map(|&i| self.nodes[i].id.as_str())
+            .collect()
+    }
+}
+
+/// Element type of a node, as given by its `type` field.
+pub fn element_type(node: &Node) -> &str {
+    node.config
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+}
+
+/// Metadata of a node: its configuration without the `id` and
+/// `downstream` fields, which the graph holds as the node and its links.
+pub fn node_attributes(node: &Node) -> Value {
+    match &node.config {
+        Value::Object(entries) => Value::Object(
+            entries
+                .iter()
+                .filter(|(k, _)| k != "id" && k != "downstream")
+                .cloned()
+                .collect(),
+        ),
+        _ => Value::Object(Vec::new()),
+    }
+}
+
+/// DOT quoted string.
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
@@ -0,0 +1,913 @@
+//! Routing networks: elements connected in a directed acyclic graph,
+//! simulated with a shared time step. Each node drains to at most one
+//! downstream node; bifurcations additionally divert part of their flow to
+//! a second node. Series systems, such as cascades of dams and reaches,
+//! are built with [`Network::series`]; reservoir outlets may be rated
+//! against the tailwater, which between closely spaced dams is the pool of
+//! the dam below. Runs may also adapt their time step to the flows (see
+//! [`AdaptiveStep`]). The topology can be traced upstream and downstream
+//! of a node and exported to Graphviz DOT, and the state of a simulation
+//! saved and restored to continue it later (see [`Network::save_state`]).
+
+mod adaptive;
+mod element;
+mod flood_cells;
+mod graph;
+mod lateral;
+mod pump;
+#[cfg(feature = "python")]
+pub mod python;
+mod state;
+mod tailwater;
+#[cfg(test)]
+mod tests;
+mod treatment;
+
+pub use adaptive::AdaptiveStep;
+pub use element::{Bifurcation, BridgeCrossing, Demand, Element, Forcing, Junction, SplitRule};
+pub use flood_cells::{Cell, FloodCells, Link, LinkKind};
+pub use graph::{element_type, node_attributes, LinkType};
+pub use lateral::LateralWeir;
+pub use pump::{Pump, PumpStation};
+pub use state::Checkpoint;
+pub use tailwater::{TailwaterOutlet, TailwaterSource};
+pub use treatment::{Treatment, TreatmentKind};
+
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::interp::interp;
+use crate::log::{trace, warning};
+use crate::progress::{Progress, Silent, Tracker};
+use crate::reach_routing::muskingum::check_muskingum;
+use crate::reach_routing::transmission_loss::TransmissionLoss;
+use crate::units::UnitSystem;
+use std::collections::{BTreeMap, HashMap};
+
+/// A network node: a routing element, the node its outflow drains to, the
+/// names of optional external inflow, rainfall, evaporation and pollutant
+/// load series,
+/// an optional minimum instream flow, an optional channel transmission
+/// loss and, for reservoirs, an optional tailwater-dependent outlet rating.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub id: String,
+    pub element: Element,
+    pub downstream: Option<String>,
+    pub inflow: Option<String>,
+    pub rainfall: Option<String>,
+    /// Name of a series of evaporation depths per step from a reservoir's
+    /// water surface, in the length unit of its area table.
+    pub evaporation: Option<String>,
+    /// Name of a pollutant load series (mass per second) entering the node.
+    pub load: Option<String>,
+    /// Outflow that demand withdrawals may not draw the node below. Minimum
+    /// flows take precedence over all demands.
+    pub min_flow: Option<f64>,
+    /// Loss removed from the element outflow each step, with the unit
+    /// system of the node's flows.
+    pub transmission_loss: Option<(TransmissionLoss, UnitSystem)>,
+    /// Outlet rating replacing a reservoir's outflow table according to the
+    /// tailwater level.
+    pub tailwater: Option<TailwaterOutlet>,
+    /// Configuration the node was built from, written back out by
+    /// [`Network::to_value`].
+    pub config: Value,
+}
+
+impl Node {
+    /// Builds a node from a configuration table with `id`, `type`, optional
+    /// `downstream`, `inflow`, `rainfall`, `evaporation`, `load`, `min_flow`,
+    /// `transmission_loss` (see [`TransmissionLoss::from_value`]) and
+    /// `tailwater` (see [`TailwaterOutlet::from_value`]) fields, plus the
+    /// element parameters.
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let units = element::units_field(value)?;
+        let id = value.str_field("id")?;
+        Ok(Node {
+            id: id.to_string(),
+            element: Element::from_value(value).map_err(|err| match err {
+                Error::InvalidParameter(msg) => {
+                    Error::InvalidParameter(format!("Node `{id}`: {msg}"))
+                }
+                err => err,
+            })?,
+            downstream: value.opt_str_field("downstream")?.map(str::to_string),
+            inflow: value.opt_str_field("inflow")?.map(str::to_string),
+            rainfall: value.opt_str_field("rainfall")?.map(str::to_string),
+            evaporation: value.opt_str_field("evaporation")?.map(str::to_string),
+            load: value.opt_str_field("load")?.map(str::to_string),
+            min_flow: value.opt_f64_field("min_flow")?,
+            transmission_loss: TransmissionLoss::from_value(value, units)?
+                .map(|loss| (loss, units)),
+            tailwater: TailwaterOutlet::from_value(value)?,
+            config: value.clone(),
+        })
+    }
+}
+
+/// Simulated series for every node, in network order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkResult {
+    pub node_ids: Vec<String>,
+    pub inflow: Vec<Vec<f64>>,
+    pub outflow: Vec<Vec<f64>>,
+    /// Element storage at the end of each step (flow units × seconds).
+    pub storage: Vec<Vec<f64>>,
+    /// Water surface elevation at the end of each step (`NaN` for elements
+    /// without a stage).
+    pub stage: Vec<Vec<f64>>,
+    /// Flow withdrawn by demand nodes (zero for other nodes).
+    pub withdrawal: Vec<Vec<f64>>,
+    /// Flow sent to the diversion node by bifurcations (zero for other
+    /// nodes); `outflow` is the flow remaining for `downstream`.
+    pub diversion: Vec<Vec<f64>>,
+    /// Flow from groundwater nodes to the stream they drain to through the
+    /// streambed (negative when the stream loses water), and from the
+    /// storage areas of lateral weirs and flood cells back to the channel
+    /// (negative while they fill); included in `outflow`.
+    pub exchange: Vec<Vec<f64>>,
+    /// Flow lost from the channel (junction and transmission losses, and
+    /// infiltration from treatment devices).
+    pub loss: Vec<Vec<f64>>,
+    /// Inflow captured by treatment devices rather than bypassed (zero for
+    /// other nodes).
+    pub captured: Vec<Vec<f64>>,
+    /// Pollutant load leaving each node towards `downstream` (mass per
+    /// second).
+    pub load: Vec<Vec<f64>>,
+    /// Pollutant load removed by treatment devices (mass per second).
+    pub load_removed: Vec<Vec<f64>>,
+    /// Tailwater level below reservoirs with a tailwater-dependent outlet
+    /// (`NaN` for other nodes).
+    pub tailwater: Vec<Vec<f64>>,
+}
+
+impl NetworkResult {
+    /// Outflow series of node `id`.
+    pub fn outflow_of(&self, id: &str) -> Option<&[f64]> {
+        let i = self.node_ids.iter().position(|n| n == id)?;
+        Some(&self.outflow[i])
+    }
+
+    /// Every recorded series, `[node][step]`, with its field name.
+    pub fn series(&self) -> [(&'static str, &Vec<Vec<f64>>); 12] {
+        [
+            ("inflow", &self.inflow),
+            ("outflow", &self.outflow),
+            ("storage", &self.storage),
+            ("stage", &self.stage),
+            ("withdrawal", &self.withdrawal),
+            ("diversion", &self.diversion),
+            ("exchange", &self.exchange),
+            ("loss", &self.loss),
+            ("captured", &self.captured),
+            ("load", &self.load),
+            ("load_removed", &self.load_removed),
+            ("tailwater", &self.tailwater),
+        ]
+    }
+
+    /// Appends one step of values from [`Network::record`].
+    fn push(&mut self, record: &[[f64; 12]]) {
+        let fields = [
+            &mut self.inflow,
+            &mut self.outflow,
+            &mut self.storage,
+            &mut self.stage,
+            &mut self.withdrawal,
+            &mut self.diversion,
+            &mut self.exchange,
+            &mut self.loss,
+            &mut self.captured,
+            &mut self.load,
+            &mut self.load_removed,
+            &mut self.tailwater,
+        ];
+        for (f, field) in fields.into_iter().enumerate() {
+            for (values, node) in field.iter_mut().zip(record) {
+                values.push(node[f]);
+            }
+        }
+    }
+}
+
+/// Largest change from `before` to `after` relative to the larger of the
+/// two values, zero where both are zero.
+fn relative_change(before: &[f64], after: &[f64]) -> f64 {
+    before
+        .iter()
+        .zip(after)
+        .map(|(a, b)| {
+            let scale = a.abs().max(b.abs());
+            if scale > 0.0 {
+                (b - a).abs() / scale
+            } else {
+                0.0
+            }
+        })
+        .fold(0.0, f64::max)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+    nodes: Vec<Node>,
+    /// Node indices in upstream-to-downstream order.
+    order: Vec<usize>,
+    /// Index of each node's downstream node.
+    downstream: Vec<Option<usize>>,
+    /// Index of the node receiving each bifurcation's diverted flow.
+    diversion: Vec<Option<usize>>,
+    /// Groundwater nodes exchanging water with each node.
+    aquifers: Vec<Vec<usize>>,
+    /// Transmission loss of each node during the last step.
+    transmission_lost: Vec<f64>,
+    /// Pollutant load `(entering, leaving downstream, removed)` of each node
+    /// during the last step.
+    loads: Vec<(f64, f64, f64)>,
+    /// Tailwater level of each node with a tailwater outlet (`NaN`
+    /// otherwise).
+    tailwater_level: Vec<f64>,
+    /// Whether the next run continues from a restored state.
+    resume: bool,
+    /// Time step (s) of the last run (`NaN` before the first) and the
+    /// reporting intervals completed since the simulation started.
+    time_step: f64,
+    completed: usize,
+    checkpoint: Option<Checkpoint>,
+}
+
+/// Most iterations between coupled pools in one step.
+const MAX_TAILWATER_ITERATIONS: usize = 50;
+/// Change in tailwater level at which the coupling has converged.
+const TAILWATER_TOLERANCE: f64 = 1e-6;
+
+impl Network {
+    /// Builds a network, checking that ids are unique, downstream and
+    /// diversion links resolve and the links contain no cycles.
+    pub fn new(nodes: Vec<Node>) -> Result<Self> {
+        let mut index = HashMap::new();
+        for (i, node) in nodes.iter().enumerate() {
+            if index.insert(node.id.as_str(), i).is_some() {
+                return Err(Error::Data(format!("Duplicate node id `{}`.", node.id)));
+            }
+        }
+        let downstream = nodes
+            .iter()
+            .map(|node| match &node.downstream {
+                None => Ok(None),
+                Some(id) => index.get(id.as_str()).copied().map(Some).ok_or_else(|| {
+                    Error::Data(format!("Node `{}` drains to unknown node `{id}`.", node.id))
+                }),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let diversion = nodes
+            .iter()
+            .map(|node| match &node.element {
+                Element::Bifurcation(split) => index
+                    .get(split.divert_to.as_str())
+                    .copied()
+                    .map(Some)
+                    .ok_or_else(|| {
+                        Error::Data(format!(
+                            "Node `{}` diverts to unknown node `{}`.",
+                            node.id, split.divert_to
+                        ))
+                    }),
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (i, node) in nodes.iter().enumerate() {
+            let Some(outlet) = &node.tailwater else {
+                continue;
+            };
+            let Element::Reservoir(reservoir) = &node.element else {
+                return Err(Error::Data(format!(
+                    "Node `{}` has a tailwater outlet but is not a reservoir.",
+                    node.id
+                )));
+            };
+            outlet.validate(reservoir.elevation.len())?;
+            if outlet.source == TailwaterSource::Downstream
+                && !matches!(
+                    downstream[i].map(|d| &nodes[d].element),
+                    Some(Element::Reservoir(_))
+                )
+            {
+                return Err(Error::Data(format!(
+                    "Node `{}` takes its tailwater from the downstream pool but does not drain \
+                     to a reservoir; give a `tailwater_discharge`/`tailwater_stage` rating.",
+                    node.id
+                )));
+            }
+        }
+
+        let mut aquifers = vec![Vec::new(); nodes.len()];
+        for (i, node) in nodes.iter().enumerate() {
+            if let (Element::Groundwater(..), Some(d)) = (&node.element, downstream[i]) {
+                aquifers[d].push(i);
+            }
+        }
+
+        let mut upstream_count = vec![0usize; nodes.len()];
+        for &d in downstream.iter().chain(&diversion).flatten() {
+            upstream_count[d] += 1;
+        }
+        let mut ready: Vec<usize> = (0..nodes.len())
+            .filter(|&i| upstream_count[i] == 0)
+            .collect();
+        ready.reverse();
+        let mut order = Vec::with_capacity(nodes.len());
+        while let Some(i) = ready.pop() {
+            order.push(i);
+            for d in [downstream[i], diversion[i]].into_iter().flatten() {
+                upstream_count[d] -= 1;
+                if upstream_count[d] == 0 {
+                    ready.push(d);
+                }
+            }
+        }
+        if order.len() != nodes.len() {
+            return Err(Error::Data("Network links contain a cycle.".into()));
+        }
+        Ok(Network {
+            order,
+            downstream,
+            diversion,
+            aquifers,
+            transmission_lost: vec![0.0; nodes.len()],
+            loads: vec![(0.0, 0.0, 0.0); nodes.len()],
+            tailwater_level: vec![f64::NAN; nodes.len()],
+            resume: false,
+            time_step: f64::NAN,
+            completed: 0,
+            checkpoint: None,
+            nodes,
+        })
+    }
+
+    /// Builds a series system, such as a cascade of reservoirs and reaches,
+    /// in which each node drains to the next and the last node is the
+    /// outlet. The nodes must not set `downstream` themselves.
+    pub fn series(mut nodes: Vec<Node>) -> Result<Self> {
+        if let Some(node) = nodes.iter().find(|n| n.downstream.is_some()) {
+            return Err(Error::Data(format!(
+                "Node `{}` sets `downstream`, but series nodes are linked in order.",
+                node.id
+            )));
+        }
+        let next: Vec<String> = nodes.iter().skip(1).map(|n| n.id.clone()).collect();
+        for (node, id) in nodes.iter_mut().zip(next) {
+            node.downstream = Some(id);
+        }
+        Network::new(nodes)
+    }
+
+    /// Builds a network from a configuration with a `nodes` array, or a
+    /// series system from a `series` array listed upstream to downstream.
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let nodes = |key: &str| {
+            value.get(key).and_then(Value::as_array).map(|items| {
+                items
+                    .iter()
+                    .map(Node::from_value)
+                    .collect::<Result<Vec<_>>>()
+            })
+        };
+        match (nodes("series"), nodes("nodes")) {
+            (Some(series), None) => Network::series(series?),
+            (None, Some(nodes)) => Network::new(nodes?),
+            _ => Err(Error::Data(
+                "Configuration must contain either a `nodes` or a `series` array.".into(),
+            )),
+        }
+    }
+
+    /// Configuration that rebuilds this network with
+    /// [`Network::from_value`]: a `nodes` array holding each node's
+    /// configuration, in network order, with its `downstream` link. Keys
+    /// keep the order they were given in, so the same network always
+    /// serialises to the same text.
+    pub fn to_value(&self) -> Value {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let mut config = node.config.clone();
+                if let Some(id) = &node.downstream {
+                    config.insert("downstream", id.as_str().into());
+                }
+                config
+            })
+            .collect();
+        Value::Object(vec![("nodes".to_string(), Value::Array(nodes))])
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Simulates the network with time step `dt` (seconds). `series` maps
+    /// the names referenced by node `inflow`, `rainfall`, `evaporation` and
+    /// `load` fields
+    /// and by demand series to equal-length series; unreferenced series are
+    /// ignored.
+    ///
+    /// Demands are allocated every step in priority order (lower first,
+    /// then network order). Each demand receives the largest withdrawal, up
+    /// to its demand, that leaves every more senior demand fully served and
+    /// does not draw any node below its `min_flow`.
+    ///
+    /// Elements start from their initial conditions, or from a state
+    /// restored with [`Network::set_state`], and keep their final state,
+    /// which [`Network::save_state`] can save for a later run.
+    pub fn run(&mut self, series: &BTreeMap<String, Vec<f64>>, dt: f64) -> Result<NetworkResult> {
+        self.run_with_progress(series, dt, &Silent)
+    }
+
+    /// Runs the network as [`Network::run`] does, reporting each completed
+    /// time step to `progress`.
+    pub fn run_with_progress(
+        &mut self,
+        series: &BTreeMap<String, Vec<f64>>,
+        dt: f64,
+        progress: &dyn Progress,
+    ) -> Result<NetworkResult> {
+        self.run_adaptive(series, dt, &AdaptiveStep::FIXED, progress)
+    }
+
+    /// Runs the network with time steps adapted to the flows by `step`,
+    /// refining them on rapidly rising limbs and coarsening them on slow
+    /// recessions. `dt` is the interval of the input series and of the
+    /// results. External inflows are values at the end of each interval and
+    /// are interpolated linearly within it; the other series hold over
+    /// their interval and are averaged over coarse steps. Results are
+    /// reported at the end of each interval, interpolated linearly within
+    /// coarse steps.
+    pub fn run_adaptive(
+        &mut self,
+        series: &BTreeMap<String, Vec<f64>>,
+        dt: f64,
+        step: &AdaptiveStep,
+        progress: &dyn Progress,
+    ) -> Result<NetworkResult> {
+        step.validate()?;
+        let shortest = self.check_time_step(dt)?;
+        let lookup = |node: &Node, name: Option<&String>| match name {
+            None => Ok(None),
+            Some(name) => series.get(name).map(|s| Some(s.as_slice())).ok_or_else(|| {
+                Error::Data(format!(
+                    "Series `{name}` for node `{}` was not provided.",
+                    node.id
+                ))
+            }),
+        };
+        let external = self
+            .nodes
+            .iter()
+            .map(|node| lookup(node, node.inflow.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        let rainfall = self
+            .nodes
+            .iter()
+            .map(|node| lookup(node, node.rainfall.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        let evaporation = self
+            .nodes
+            .iter()
+            .map(|node| lookup(node, node.evaporation.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        let load_series = self
+            .nodes
+            .iter()
+            .map(|node| lookup(node, node.load.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        let demand_series = self
+            .nodes
+            .iter()
+            .map(|node| match &node.element {
+                Element::Demand(demand) => lookup(node, demand.series.as_ref()),
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let forcing_series = || {
+            external
+                .iter()
+                .chain(rainfall.iter())
+                .chain(evaporation.iter())
+                .chain(load_series.iter())
+                .chain(demand_series.iter())
+                .flatten()
+        };
+        let n_steps = forcing_series().map(|s| s.len()).max().ok_or_else(|| {
+            Error::Data("No node references an external inflow or rainfall series.".into())
+        })?;
+        if forcing_series().any(|s| s.len() != n_steps) {
+            return Err(Error::Data("Input series must have equal lengths.".into()));
+        }
+
+        let mut demands: Vec<(i64, usize)> = self
+            .order
+            .iter()
+            .filter_map(|&i| match &self.nodes[i].element {
+                Element::Demand(demand) => Some((demand.priority, i)),
+                _ => None,
+            })
+            .collect();
+        demands.sort_by_key(|&(priority, _)| priority);
+
+        let n_nodes = self.nodes.len();
+        let mut result = NetworkResult {
+            node_ids: self.nodes.iter().map(|n| n.id.clone()).collect(),
+            inflow: vec![Vec::with_capacity(n_steps); n_nodes],
+            outflow: vec![Vec::with_capacity(n_steps); n_nodes],
+            storage: vec![Vec::with_capacity(n_steps); n_nodes],
+            stage: vec![Vec::with_capacity(n_steps); n_nodes],
+            withdrawal: vec![Vec::with_capacity(n_steps); n_nodes],
+            diversion: vec![Vec::with_capacity(n_steps); n_nodes],
+            exchange: vec![Vec::with_capacity(n_steps); n_nodes],
+            loss: vec![Vec::with_capacity(n_steps); n_nodes],
+            captured: vec![Vec::with_capacity(n_steps); n_nodes],
+            load: vec![Vec::with_capacity(n_steps); n_nodes],
+            load_removed: vec![Vec::with_capacity(n_steps); n_nodes],
+            tailwater: vec![Vec::with_capacity(n_steps); n_nodes],
+        };
+        self.start_run(dt);
+        let mut forcing = vec![Forcing::default(); n_nodes];
+        let mut inflow = vec![0.0; n_nodes];
+        let mut outflow = vec![0.0; n_nodes];
+        let tracker = Tracker::new(progress, n_steps);
+        // Time is counted in ticks of the finest step.
+        let fine = 1usize << step.max_refinement;
+        let total = n_steps * fine;
+        let coarsest = fine << step.max_coarsening;
+        let (mut size, mut position, mut steps, mut rejected) = (fine, 0, 0usize, 0usize);
+        let mut previous: Option<(Vec<f64>, Vec<f64>)> = None;
+        let mut reported: Option<Vec<[f64; 12]>> = None;
+        while position < total {
+            let t = position / fine;
+            let intervals = (size / fine).max(1);
+            let fraction = size as f64 / fine as f64;
+            let h = dt * fraction;
+            let mean = |s: &[f64]| {
+                if intervals == 1 {
+                    s[t]
+                } else {
+                    s[t..t + intervals].iter().sum::<f64>() / intervals as f64
+                }
+            };
+            let saved = (!step.is_fixed()).then(|| {
+                let elements: Vec<Element> = self.nodes.iter().map(|n| n.element.clone()).collect();
+                (elements, self.tailwater_level.clone())
+            });
+            for i in 0..n_nodes {
+                forcing[i] = Forcing {
+                    rainfall: rainfall[i].map_or(0.0, mean),
+                    evaporation: evaporation[i].map_or(0.0, |s| mean(s) * fraction),
+                    withdrawal: 0.0,
+                    load: load_series[i].map_or(0.0, mean),
+                };
+            }
+            // Inflows are values at the end of each interval, so they are
+            // interpolated at the end of refined steps.
+            let end = (position + size) as f64 / fine as f64;
+            let at_end = |s: &[f64]| {
+                if size >= fine || t == 0 {
+                    s[t + intervals - 1]
+                } else {
+                    s[t - 1] + (s[t] - s[t - 1]) * (end - t as f64)
+                }
+            };
+            let local: Vec<f64> = external.iter().map(|s| s.map_or(0.0, at_end)).collect();
+            if !demands.is_empty() {
+                let targets: Vec<(usize, f64)> = demands
+                    .iter()
+                    .map(|&(_, i)| {
+                        let target = match (&self.nodes[i].element, demand_series[i]) {
+                            (_, Some(s)) => mean(s),
+                            (Element::Demand(demand), None) => demand.demand,
+                            _ => 0.0,
+                        };
+                        (i, target.max(0.0))
+                    })
+                    .collect();
+                self.allocate(&targets, &local, &mut forcing, h);
+            }
+            self.advance_coupled(&local, &forcing, h, &mut inflow, &mut outflow);
+
+            let change = previous.as_ref().map_or(0.0, |(last_in, last_out)| {
+                relative_change(last_in, &inflow).max(relative_change(last_out, &outflow))
+            });
+            if let Some((elements, levels)) = saved {
+                // Refinement stops at the shortest step with non-negative
+                // Muskingum coefficients.
+                let refinable = size > 1 && dt * (size / 2) as f64 / fine as f64 >= shortest;
+                if change > step.tolerance && refinable {
+                    for (node, element) in self.nodes.iter_mut().zip(elements) {
+                        node.element = element;
+                    }
+                    self.tailwater_level = levels;
+                    size /= 2;
+                    rejected += 1;
+                    continue;
+                }
+            }
+            position += size;
+            steps += 1;
+            if position.is_multiple_of(fine) {
+                let record = self.record(&inflow, &outflow);
+                match &reported {
+                    Some(last) if intervals > 1 => {
+                        for k in 1..=intervals {
+                            let w = k as f64 / intervals as f64;
+                            let values: Vec<[f64; 12]> = last
+                                .iter()
+                                .zip(&record)
+                                .map(|(a, b)| std::array::from_fn(|f| a[f] + (b[f] - a[f]) * w))
+                                .collect();
+                            result.push(&values);
+                            tracker.advance();
+                        }
+                    }
+                    _ => {
+                        result.push(&record);
+                        tracker.advance();
+                    }
+                }
+                reported = Some(record);
+                self.complete_intervals(intervals)?;
+            }
+            tracker.check()?;
+            if !step.is_fixed() {
+                previous = Some((inflow.clone(), outflow.clone()));
+            }
+            if change <= 0.5 * step.tolerance
+                && 2 * size <= coarsest
+                && position.is_multiple_of(2 * size)
+                && position + 2 * size <= total
+            {
+                size *= 2;
+            }
+            while position + size > total {
+                size /= 2;
+            }
+        }
+        if !step.is_fixed() {
+            trace!("Adaptive run took {steps} steps ({rejected} rejected) for {n_steps} intervals");
+        }
+        Ok(result)
+    }
+
+    /// Rejects a time step `dt` (seconds) for which the inflow coefficient
+    /// `c0` of a Muskingum node's sub-reaches is negative, unless the node
+    /// sets `allow_negative_c0 = true`, and returns the shortest step that
+    /// keeps `c0` of the other nodes non-negative (`2 K x / sub_reaches`).
+    fn check_time_step(&self, dt: f64) -> Result<f64> {
+        let mut shortest: f64 = 0.0;
+        for node in &self.nodes {
+            let Element::Muskingum(reach) = &node.element else {
+                continue;
+            };
+            let allow_negative_c0 = node
+                .config
+                .get("allow_negative_c0")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            check_muskingum(
+                &[0.0],
+                dt,
+                reach.k,
+                reach.x,
+                reach.sub_reaches as i64,
+                allow_negative_c0,
+            )
+            .map_err(|err| Error::InvalidParameter(format!("Node `{}`: {err}", node.id)))?;
+            if !allow_negative_c0 {
+                shortest = shortest.max(2.0 * reach.k * reach.x / reach.sub_reaches as f64);
+            }
+        }
+        Ok(shortest)
+    }
+
+    /// Every recorded value of each node at the end of the last step, in
+    /// the order of [`NetworkResult::series`].
+    fn record(&self, inflow: &[f64], outflow: &[f64]) -> Vec<[f64; 12]> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                [
+                    inflow[i],
+                    outflow[i],
+                    node.element.storage(),
+                    node.element.stage(),
+                    node.element.withdrawal(),
+                    node.element.diverted(),
+                    node.element.exchange(),
+                    node.element.loss() + self.transmission_lost[i],
+                    node.element.captured(),
+                    self.loads[i].1,
+                    self.loads[i].2,
+                    self.tailwater_level[i],
+                ]
+            })
+            .collect()
+    }
+
+    /// Sets the tailwater levels at the start of a run from the initial
+    /// pools, with the outlets' free-flow ratings.
+    fn start_tailwater(&mut self) {
+        for i in 0..self.nodes.len() {
+            let node = &self.nodes[i];
+            let (Some(outlet), Element::Reservoir(reservoir)) = (&node.tailwater, &node.element)
+            else {
+                continue;
+            };
+            let free = interp(
+                reservoir.pool_elevation(),
+                &reservoir.elevation,
+                &outlet.outflow[0],
+            );
+            let pool = self.downstream[i].map_or(f64::NAN, |d| self.nodes[d].element.stage());
+            self.tailwater_level[i] = outlet.level(free.max(0.0), pool);
+        }
+    }
+
+    /// Recomputes the tailwater levels from the pools and outflows at the
+    /// end of the step and returns the largest change.
+    fn update_tailwater(&mut self, outflow: &[f64]) -> f64 {
+        let mut change: f64 = 0.0;
+        for (i, &q) in outflow.iter().enumerate() {
+            let Some(outlet) = &self.nodes[i].tailwater else {
+                continue;
+            };
+            let pool = self.downstream[i].map_or(f64::NAN, |d| self.nodes[d].element.stage());
+            let level = outlet.level(q, pool);
+            change = change.max((level - self.tailwater_level[i]).abs());
+            self.tailwater_level[i] = level;
+        }
+        change
+    }
+
+    /// Advances one step, repeating it from the same state until the
+    /// tailwater levels of coupled outlets agree with the end-of-step pools
+    /// and outflows.
+    fn advance_coupled(
+        &mut self,
+        local: &[f64],
+        forcing: &[Forcing],
+        dt: f64,
+        inflow: &mut [f64],
+        outflow: &mut [f64],
+    ) {
+        if self.nodes.iter().all(|n| n.tailwater.is_none()) {
+            self.advance(local, forcing, dt, inflow, outflow);
+            return;
+        }
+        let saved: Vec<Element> = self.nodes.iter().map(|n| n.element.clone()).collect();
+        for iteration in 0..MAX_TAILWATER_ITERATIONS {
+            if iteration > 0 {
+                for (node, element) in self.nodes.iter_mut().zip(&saved) {
+                    node.element.clone_from(element);
+                }
+            }
+            self.advance(local, forcing, dt, inflow, outflow);
+            let change = self.update_tailwater(outflow);
+            if change <= TAILWATER_TOLERANCE {
+                trace!(
+                    "Tailwater coupling converged after {} iteration(s)",
+                    iteration + 1
+                );
+                return;
+            }
+        }
+        warning!(
+            "Tailwater coupling did not converge in {MAX_TAILWATER_ITERATIONS} iterations; \
+             continuing with the last levels"
+        );
+    }
+
+    /// Advances every element by one step in upstream-to-downstream order.
+    fn advance(
+        &mut self,
+        local: &[f64],
+        forcing: &[Forcing],
+        dt: f64,
+        inflow: &mut [f64],
+        outflow: &mut [f64],
+    ) {
+        inflow.copy_from_slice(local);
+        for (load, f) in self.loads.iter_mut().zip(forcing) {
+            *load = (f.load, 0.0, 0.0);
+        }
+        for &i in &self.order {
+            // Aquifers draining here exchange with this node's stream level
+            // at the start of the step, once all upstream flow has arrived.
+            let depth = self.nodes[i].element.flow_depth();
+            for &g in &self.aquifers[i] {
+                let exchange = self.nodes[g]
+                    .element
+                    .exchange_with_stream(depth, inflow[i], dt);
+                outflow[g] += exchange;
+                inflow[i] += exchange;
+            }
+            let node = &mut self.nodes[i];
+            if let (Some(outlet), Element::Reservoir(reservoir)) =
+                (&node.tailwater, &mut node.element)
+            {
+                reservoir.outflow = outlet.outflow_at(self.tailwater_level[i]);
+            }
+            outflow[i] = node.element.step(inflow[i], &forcing[i], dt);
+            self.transmission_lost[i] = match &node.transmission_loss {
+                None => 0.0,
+                Some((loss, units)) => {
+                    units.discharge_from_si(loss.loss(units.discharge_to_si(outflow[i]), dt))
+                }
+            };
+            let (load, diverted_load, removed) =
+                node.element.route_load(self.loads[i].0, inflow[i], dt);
+            // Transmission losses carry load at the outflow concentration.
+            let load = if outflow[i] > 0.0 {
+                load * (1.0 - self.transmission_lost[i] / outflow[i]).max(0.0)
+            } else {
+                load
+            };
+            outflow[i] -= self.transmission_lost[i];
+            self.loads[i].1 = load;
+            self.loads[i].2 = removed;
+            if let Some(d) = self.downstream[i] {
+                inflow[d] += outflow[i];
+                self.loads[d].0 += load;
+            }
+            if let Some(d) = self.diversion[i] {
+                inflow[d] += self.nodes[i].element.diverted();
+                self.loads[d].0 += diverted_load;
+            }
+        }
+    }
+
+    /// Sets the withdrawal of each demand in `targets` (in priority order)
+    /// by trial steps from the current state, which is left unchanged.
+    fn allocate(
+        &mut self,
+        targets: &[(usize, f64)],
+        local: &[f64],
+        forcing: &mut [Forcing],
+        dt: f64,
+    ) {
+        let n = self.nodes.len();
+        let saved: Vec<Element> = self.nodes.iter().map(|n| n.element.clone()).collect();
+        let (mut inflow, mut outflow) = (vec![0.0; n], vec![0.0; n]);
+        let mut trial = |network: &mut Network, forcing: &[Forcing]| {
+            for (node, element) in network.nodes.iter_mut().zip(&saved) {
+                node.element.clone_from(element);
+            }
+            network.advance(local, forcing, dt, &mut inflow, &mut outflow);
+            (
+                outflow.clone(),
+                network
+                    .nodes
+                    .iter()
+                    .map(|n| n.element.withdrawal())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        // Flows without withdrawals bound the minimum flows that can be met.
+        let (natural, _) = trial(self, forcing);
+        let floor: Vec<Option<f64>> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| node.min_flow.map(|q| q.min(natural[i])))
+            .collect();
+        let tolerance = |q: f64| 1e-9 * q.abs().max(1.0);
+        for (j, &(node, target)) in targets.iter().enumerate() {
+            let mut feasible = |network: &mut Network, forcing: &mut [Forcing], w: f64| {
+                forcing[node].withdrawal = w;
+                let (outflow, withdrawn) = trial(network, forcing);
+                targets[..=j].iter().all(|&(k, _)| {
+                    withdrawn[k] >= forcing[k].withdrawal - tolerance(forcing[k].withdrawal)
+                }) && floor
+                    .iter()
+                    .zip(&outflow)
+                    .all(|(f, q)| f.is_none_or(|f| *q >= f - tolerance(f)))
+            };
+            if target == 0.0 || feasible(self, forcing, target) {
+                continue;
+            }
+            let (mut low, mut high) = (0.0, target);
+            for _ in 0..50 {
+                let mid = 0.5 * (low + high);
+                if feasible(self, forcing, mid) {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            forcing[node].withdrawal = low;
+        }
+        for (node, element) in self.nodes.iter_mut().zip(saved) {
+            node.element = element;
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::interp::interp;
+use crate::units::UnitSystem;
+
+/// Longest sub-step (s) of the wet-well balance, so that pumps switch
+/// close to their start and stop levels within long network steps.
+const MAX_SUBSTEP: f64 = 60.0;
+
+/// Pump switched on and off by the wet-well level, in SI units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pump {
+    /// Total dynamic heads (m) of the full-speed pump curve, strictly
+    /// increasing.
+    pub head: Vec<f64>,
+    /// Flow (m³/s) at each head, non-increasing.
+    pub flow: Vec<f64>,
+    /// Wet-well elevations (m) at which the pump starts and stops.
+    pub start: f64,
+    pub stop: f64,
+    /// Speed ratio of a variable-speed pump at the stop level, rising
+    /// linearly to 1 at `full_speed`; 1 for a fixed-speed pump.
+    pub min_speed: f64,
+    /// Wet-well elevation (m) from which a variable-speed pump runs at
+    /// full speed.
+    pub full_speed: f64,
+    /// Shortest time (s) the pump runs once started.
+    pub min_on: f64,
+    /// Shortest time (s) the pump rests once stopped.
+    pub min_off: f64,
+    running: bool,
+    /// Time (s) since the pump last switched.
+    elapsed: f64,
+}
+
+impl Pump {
+    fn from_value(value: &Value, units: UnitSystem) -> Result<Self> {
+        let lengths = |key: &str| -> Result<Vec<f64>> {
+            Ok(value
+                .f64_array_field(key)?
+                .into_iter()
+                .map(|v| units.length_to_si(v))
+                .collect())
+        };
+        let head = lengths("head")?;
+        let flow: Vec<f64> = value
+            .f64_array_field("flow")?
+            .into_iter()
+            .map(|q| units.discharge_to_si(q))
+            .collect();
+        if head.len() < 2 || head.len() != flow.len() || head.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(Error::InvalidParameter(
+                "A pump curve needs at least two points of strictly increasing `head`.".into(),
+            ));
+        }
+        if flow.iter().any(|q| q.is_nan() || *q < 0.0) || flow.windows(2).any(|w| w[1] > w[0]) {
+            return Err(Error::InvalidParameter(
+                "Pump curve flows must be non-negative and must not increase with head.".into(),
+            ));
+        }
+        let start = units.length_to_si(value.f64_field("start")?);
+        let stop = units.length_to_si(value.f64_field("stop")?);
+        let min_speed = value.opt_f64_field("min_speed")?.unwrap_or(1.0);
+        let full_speed = match value.opt_f64_field("full_speed")? {
+            Some(z) => units.length_to_si(z),
+            None => start,
+        };
+        let min_on = value.opt_f64_field("min_on")?.unwrap_or(0.0);
+        let min_off = value.opt_f64_field("min_off")?.unwrap_or(0.0);
+        // Written so that NaN fails every comparison.
+        let valid = start > stop
+            && min_speed > 0.0
+            && min_speed <= 1.0
+            && (min_speed == 1.0 || full_speed > stop)
+            && min_on >= 0.0
+            && min_off >= 0.0;
+        if !valid {
+            return Err(Error::InvalidParameter(
+                "A pump must start above its stop level, with `min_speed` within (0, 1], \
+                 `full_speed` above the stop level and non-negative `min_on` and `min_off`."
+                    .into(),
+            ));
+        }
+        Ok(Pump {
+            head,
+            flow,
+            start,
+            stop,
+            min_speed,
+            full_speed,
+            min_on,
+            min_off,
+            running: false,
+            elapsed: f64::INFINITY,
+        })
+    }
+
+    /// Speed ratio at wet-well `level`.
+    fn speed(&self, level: f64) -> f64 {
+        if self.min_speed >= 1.0 || level >= self.full_speed {
+            return 1.0;
+        }
+        let fraction = ((level - self.stop) / (self.full_speed - self.stop)).clamp(0.0, 1.0);
+        self.min_speed + (1.0 - self.min_speed) * fraction
+    }
+
+    /// Flow (m³/s) against `head` (m) at speed ratio `speed`, scaled from
+    /// the full-speed curve by the affinity laws (`Q ∝ N`, `H ∝ N²`); zero
+    /// beyond the shut-off head.
+    fn flow_at(&self, head: f64, speed: f64) -> f64 {
+        let full_head = head / (speed * speed);
+        let last = self.head.len() - 1;
+        if full_head > self.head[last] {
+            return 0.0;
+        }
+        let h = full_head.max(self.head[0]);
+        speed * interp(h, &self.head, &self.flow).max(0.0)
+    }
+
+    /// Applies the start and stop levels after `dt` seconds, respecting the
+    /// shortest run and rest times.
+    fn switch(&mut self, level: f64, dt: f64) {
+        self.elapsed += dt;
+        let (limit, change) = if self.running {
+            (self.min_on, level <= self.stop)
+        } else {
+            (self.min_off, level >= self.start)
+        };
+        if change && self.elapsed >= limit {
+            self.running = !self.running;
+            self.elapsed = 0.0;
+        }
+    }
+}
+
+/// Pump station lifting the water collected in a wet well to its outlet,
+/// as for interior drainage behind levees. Outflow is the pumped flow; the
+/// wet well stores the rest, its level extrapolated above the table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PumpStation {
+    /// Wet-well elevations (m), strictly increasing.
+    pub elevation: Vec<f64>,
+    /// Wet-well storage (m³) at each elevation, strictly increasing.
+    pub storage: Vec<f64>,
+    pub pumps: Vec<Pump>,
+    /// Elevation (m) of the discharge outlet, against which the pumps
+    /// lift.
+    pub outlet: f64,
+    pub initial_elevation: f64,
+    pub units: UnitSystem,
+    /// Stored volume (m³).
+    volume: f64,
+}
+
+impl PumpStation {
+    /// Builds a pump station from a table with the wet well's `elevation`
+    /// and `storage` arrays, `initial_elevation` (default the lowest),
+    /// discharge `outlet` elevation and `pumps`, each with a pump curve
+    /// (`head` and `flow` arrays), `start` and `stop` elevations, and
+    /// optional `min_speed` and `full_speed` for variable speed and
+    /// `min_on` and `min_off` times (s). Lengths are in m (ft), storage in
+    /// m³ (ft³) and flows in m³/s (cfs).
+    pub fn from_value(value: &Value, units: UnitSystem) -> Result<Self> {
+        let elevation: Vec<f64> = value
+            .f64_array_field("elevation")?
+            .into_iter()
+            .map(|z| units.length_to_si(z))
+            .collect();
+        let storage: Vec<f64> = value
+            .f64_array_field("storage")?
+            .into_iter()
+            .map(|v| units.volume_to_si(v))
+            .collect();
+        if elevation.len() < 2
+            || storage.len() != elevation.len()
+            || elevation.windows(2).any(|w| w[1] <= w[0])
+            || storage.windows(2).any(|w| w[1] <= w[0])
+        {
+            return Err(Error::InvalidParameter(
+                "The wet-well `elevation` and `storage` tables need at least two rows, both \
+                 strictly increasing."
+                    .into(),
+            ));
+        }
+        let pumps = value
+            .get("pumps")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::Data("Field `pumps` must be an array of pump tables.".into()))?
+            .iter()
+            .map(|pump| Pump::from_value(pump, units))
+            .collect::<Result<Vec<_>>>()?;
+        let initial_elevation = match value.opt_f64_field("initial_elevation")? {
+            Some(z) => units.length_to_si(z),
+            None => elevation[0],
+        };
+        let mut station = PumpStation {
+            elevation,
+            storage,
+            pumps,
+            outlet: units.length_to_si(value.f64_field("outlet")?),
+            initial_elevation,
+            units,
+            volume: 0.0,
+        };
+        station.reset();
+        Ok(station)
+    }
+
+    pub fn reset(&mut self) {
+        self.volume = interp(self.initial_elevation, &self.elevation, &self.storage).max(0.0);
+        for pump in &mut self.pumps {
+            pump.running = false;
+            pump.elapsed = f64::INFINITY;
+        }
+    }
+
+    /// Wet-well level (m).
+    pub fn level(&self) -> f64 {
+        interp(self.volume, &self.storage, &self.elevation)
+    }
+
+    /// Stored volume (m³).
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+
+    /// Number of pumps running at the end of the last step.
+    pub fn running(&self) -> usize {
+        self.pumps.iter().filter(|p| p.running).count()
+    }
+
+    /// Whether each pump is running and the time (s) since it last started
+    /// or stopped (infinite before it first does).
+    pub fn pump_states(&self) -> Vec<(bool, f64)> {
+        self.pumps.iter().map(|p| (p.running, p.elapsed)).collect()
+    }
+
+    /// Sets the wet-well volume (m³) and the pump states returned by
+    /// [`PumpStation::pump_states`], e.g. to resume a saved simulation.
+    /// Pump states are ignored unless there is one per pump.
+    pub fn set_state(&mut self, volume: f64, pumps: &[(bool, f64)]) {
+        self.volume = volume.max(0.0);
+        if pumps.len() == self.pumps.len() {
+            for (pump, &(running, elapsed)) in self.pumps.iter_mut().zip(pumps) {
+                pump.running = running;
+                pump.elapsed = elapsed;
+            }
+        }
+    }
+
+    /// Advances the wet well by `dt` seconds with `inflow` (m³/s) and
+    /// returns the mean pumped flow (m³/s).
+    pub fn step(&mut self, inflow: f64, dt: f64) -> f64 {
+        let substeps = (dt / MAX_SUBSTEP).ceil().max(1.0) as usize;
+        let h = dt / substeps as f64;
+        let mut pumped = 0.0;
+        for _ in 0..substeps {
+            let level = self.level();
+            let head = self.outlet - level;
+            let mut flow = 0.0;
+            for pump in &mut self.pumps {
+                pump.switch(level, h);
+                if pump.running {
+                    flow += pump.flow_at(head, pump.speed(level));
+                }
+            }
+            // The pumps cannot lift more than the well holds.
+            let available = self.volume / h + inflow;
+            let flow = flow.min(available.max(0.0));
+            self.volume = (self.volume + (inflow - flow) * h).max(0.0);
+            pumped += flow * h;
+        }
+        pumped / dt
+    }
+}
@@ -0,0 +1,930 @@
+use pyo3::exceptions::{PyImportError, PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDict, PyType};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::{node_attributes, AdaptiveStep, Checkpoint, Network, NetworkResult, Node};
+use crate::arrow::FloatSeries;
+use crate::config::python::value_to_py;
+use crate::config::{json, read_config, Value};
+use crate::error::Error;
+use crate::io::netcdf::network_result_to_netcdf;
+use crate::io::report::RunReport;
+use crate::log::capture_warnings;
+use crate::progress::run_with_progress;
+use crate::time::{datetime_to_epoch, timedelta_to_seconds};
+use crate::units::UnitSystem;
+
+/// A routing network of elements connected upstream to downstream.
+///
+/// Args:
+///     config (dict): Network configuration with a `nodes` list. Each node
+///         has an `id`, a `type` (`"muskingum"`, `"kinematic_wave"`,
+///         `"reservoir"`, `"junction"`, `"subcatchment"`, `"demand"`,
+///         `"bifurcation"`, `"groundwater"`, `"treatment"`, `"bridge"`,
+///         `"pump"`, `"lateral_weir"` or `"flood_cells"`), the element
+///         parameters, and optional `downstream` (id of the node it drains
+///         to), `inflow` (name of an external inflow series), `rainfall`
+///         (name of a rainfall series), `load` (name of a pollutant load
+///         series, mass per second) and `min_flow` (minimum instream flow
+///         protected from demand withdrawals).
+///
+/// Muskingum nodes take the storage constant `k` in seconds, `x` within
+/// [0, 0.5] and optional `sub_reaches`. Runs reject a time step shorter
+/// than 2 K x / `sub_reaches`, for which the routing coefficient c0 is
+/// negative, unless the node sets `allow_negative_c0` to true.
+///
+/// Junction nodes sum the outflow of every node draining to them plus their
+/// own `inflow` series, less an optional constant `loss` (flow units) and
+/// `loss_fraction` of the summed flow.
+///
+/// Bifurcation nodes send part of their inflow to the node named by
+/// `divert_to` and the rest `downstream`. The diverted flow is set by one
+/// of `ratio` (fraction of inflow), `threshold` (flow above it, up to an
+/// optional `capacity`) or a `table_inflow`/`table_diverted` rating table.
+///
+/// Groundwater nodes are aquifers (`area`, `specific_yield`, `bottom`,
+/// `initial_head`, and a recession constant `k` in seconds or a nonlinear
+/// `coefficient`/`exponent`) recharged by their `rainfall` series, read as
+/// a recharge rate, and by their inflow. They drain baseflow downstream
+/// and, given a streambed `conductance` and `stream_bed` elevation, gain
+/// from or lose to the downstream stream according to the head difference.
+///
+/// Treatment nodes are stormwater devices selected by `device`: a
+/// `"bioretention"` cell (`area`, `ponding_depth`, `filtration_rate` and
+/// the `removal` fraction of filtered load), a `"detention"` basin
+/// (`capacity`, drawdown constant `k` in seconds and pollutant `decay` in
+/// 1/day) or an `"infiltration_trench"` (`area`, `depth`, `porosity` and
+/// `infiltration_rate`). They capture the first flush of their inflow up to
+/// their storage and bypass the rest. Pollutant loads are carried with the
+/// flow between nodes and reduced only by treatment devices and by flow
+/// leaving the network.
+///
+/// Reservoir nodes lose water from the pool to evaporation, given a
+/// water surface `area` table over their `elevation` table and the name of
+/// an `evaporation` series of depths per step, and to `seepage`, a constant
+/// flow or a table over `elevation`. Both are reported as node losses.
+///
+/// Bridge nodes pass their inflow unchanged and report as their stage the
+/// water level at the upstream face of the opening, from the arguments of
+/// `hydraulics.bridge_backwater` (the channel's `bottom_width`,
+/// `side_slope`, `n` and `slope`, the bridge's `opening_width`,
+/// `low_chord`, `length`, `pier_width`, `pier_drag`, `abutment_slope`,
+/// `contraction`, `expansion` and `method`) with the tailwater at normal
+/// depth and the stage above the `bed` elevation below the bridge.
+///
+/// Pump nodes collect their inflow in a wet well (`elevation` and `storage`
+/// tables, `initial_elevation`) and lift it to a discharge `outlet`
+/// elevation with the `pumps` listed, each with a pump curve (`head` and
+/// `flow` arrays), `start` and `stop` wet-well elevations, optional
+/// `min_speed` and `full_speed` elevation for a variable-speed pump whose
+/// speed rises linearly from the stop level, and `min_on` and `min_off`
+/// times in seconds limiting how often it cycles. The node stage is the
+/// wet-well level and its outflow the pumped flow.
+///
+/// Lateral weir nodes connect the reach to an off-channel storage area
+/// over a levee or side weir (`crest`, `length`, optional weir
+/// `coefficient`, default 1.7 m^0.5/s). The river level is read from a
+/// `channel_discharge`/`channel_stage` rating and the storage area is a
+/// level pool (`elevation` and `storage` tables, `initial_elevation`). Flow
+/// crosses the weir in either direction, reduced for submergence, and is
+/// reported in `exchange`; the node stage is the storage-area level.
+///
+/// Flood cell nodes attach interconnected storage areas, such as floodplain
+/// compartments or interior drainage, to the reach. The river level is read
+/// from a `channel_discharge`/`channel_stage` rating; each of the `cells`
+/// has an `id`, `elevation` and `storage` tables, `initial_elevation` and an
+/// optional `area` collecting the node's rainfall. `links` join two cells,
+/// or a cell and `"river"`, by `from` and `to`: a `"weir"` link has a
+/// `crest`, `length` and optional `coefficient`, a `"culvert"` link the
+/// keys of `hydraulics.Culvert` and the `invert` of its `from` end, and
+/// either may have a `flap_gate` stopping flow from `to` to `from`. Cell
+/// levels and the river flow are solved implicitly each step; the net flow
+/// returned to the river is reported in `exchange`, the node storage is the
+/// total in the cells and it has no stage.
+///
+/// A reservoir drained by a culvert may give a `culvert` table instead of
+/// `outflow`, with the keys of `hydraulics.Culvert` (`shape`, `inlet`, `n`,
+/// `length`, `slope`, `diameter` or `span` and `rise`, `barrels`,
+/// `entrance_loss`, `units`), the `invert` elevation of its inlet and an
+/// optional constant `tailwater` depth; the outflow at each elevation is
+/// read from the culvert's headwater–discharge curve.
+///
+/// Reservoir nodes may rate their outlet against the tailwater with
+/// `tailwater` levels and a `tailwater_outflow` table holding one outflow
+/// row per level over the reservoir `elevation` table. The tailwater is the
+/// pool of the downstream reservoir, coupling closely spaced dams within
+/// each step, or is read from a `tailwater_discharge`/`tailwater_stage`
+/// rating of the channel below the dam.
+///
+/// A `series` list may be given instead of `nodes` to build a series system
+/// such as a cascade of reservoirs and reaches (see `Network.series`).
+#[pyclass(name = "Network", module = "rustflow.network")]
+pub struct PyNetwork {
+    pub(crate) inner: Network,
+}
+
+#[pymethods]
+impl PyNetwork {
+    #[new]
+    fn new(config: Value) -> PyResult<Self> {
+        Ok(PyNetwork {
+            inner: Network::from_value(&config)?,
+        })
+    }
+
+    /// Builds a network from a `.toml` or `.json` configuration file.
+    ///
+    /// Args:
+    ///     path (str | PathLike): Configuration file.
+    ///
+    /// Returns:
+    ///     Network: The network.
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        Ok(PyNetwork {
+            inner: Network::from_value(&read_config(&path)?)?,
+        })
+    }
+
+    /// Builds a network from a JSON configuration written by `to_json`.
+    ///
+    /// Args:
+    ///     text (str): The JSON text.
+    ///
+    /// Returns:
+    ///     Network: The network.
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        Ok(PyNetwork {
+            inner: Network::from_value(&json::parse(text)?)?,
+        })
+    }
+
+    /// Configuration that rebuilds this network: a `nodes` list holding
+    /// every node's fields, including its `downstream` link.
+    ///
+    /// Returns:
+    ///     dict: The configuration, accepted by `Network(config)`.
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        value_to_py(py, &self.inner.to_value())
+    }
+
+    /// Serialises the network configuration as JSON.
+    ///
+    /// The output depends only on the configuration, with keys in the
+    /// order they were given, so it can be kept under version control and
+    /// compared between runs. `Network.from_json` rebuilds the network.
+    ///
+    /// Args:
+    ///     indent (bool, optional): If True, indent nested values by two
+    ///         spaces per level; otherwise write a single line. Defaults to
+    ///         True.
+    ///
+    /// Returns:
+    ///     str: The JSON text.
+    #[pyo3(signature = (indent=true))]
+    fn to_json(&self, indent: bool) -> String {
+        let value = self.inner.to_value();
+        if indent {
+            json::to_string_pretty(&value)
+        } else {
+            json::to_string(&value)
+        }
+    }
+
+    /// Builds a series system in which each node drains to the next.
+    ///
+    /// Args:
+    ///     nodes (list[dict]): Node configurations listed upstream to
+    ///         downstream, without `downstream` fields; the last node is the
+    ///         outlet. All nodes are simulated with the shared time step of
+    ///         `run`.
+    ///
+    /// Returns:
+    ///     Network: The series system.
+    #[staticmethod]
+    fn series(nodes: Vec<Value>) -> PyResult<Self> {
+        let nodes = nodes
+            .iter()
+            .map(Node::from_value)
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        Ok(PyNetwork {
+            inner: Network::series(nodes)?,
+        })
+    }
+
+    /// list[str]: Node ids in configuration order.
+    #[getter]
+    fn node_ids(&self) -> Vec<String> {
+        self.inner.nodes().iter().map(|n| n.id.clone()).collect()
+    }
+
+    /// Simulates the network.
+    ///
+    /// Args:
+    ///     inflows (dict[str, list[float]]): External inflow, rainfall and
+    ///         demand series keyed by the names used in node `inflow`,
+    ///         `rainfall` and `demand` fields.
+    ///     time_step (timedelta): Interval between consecutive values.
+    ///     progress_callback (Callable[[int, int], None], optional): Called
+    ///         with the completed and total number of time steps as the run
+    ///         proceeds, at most 20 times a second. Raising an exception
+    ///         from it cancels the run. Ctrl-C also interrupts the run.
+    ///     adaptive_tolerance (float, optional): Adapts the time step to the
+    ///         flows when given: a step is repeated at half the size while
+    ///         any node's inflow or outflow changes over it by more than
+    ///         this share of its value, and doubled after a step changing
+    ///         them by less than half of it. Results are still reported
+    ///         every `time_step`, interpolated linearly within coarse steps.
+    ///         Defaults to None, a fixed step.
+    ///     max_refinement (int, optional): Finest adaptive step is
+    ///         `time_step / 2**max_refinement`, but no shorter than
+    ///         2 K x / `sub_reaches` of any Muskingum node not allowing a
+    ///         negative c0. Defaults to 4.
+    ///     max_coarsening (int, optional): Coarsest adaptive step is
+    ///         `time_step * 2**max_coarsening`. Defaults to 3.
+    ///     checkpoint (str | PathLike, optional): File the network state is
+    ///         saved to as the run proceeds, replacing the previous
+    ///         checkpoint, so that a run interrupted by a crash can be
+    ///         restarted with `load_state`. Defaults to None.
+    ///     checkpoint_every (int, optional): Steps between checkpoints.
+    ///         Defaults to 1.
+    ///
+    /// Demand nodes (`type = "demand"`, with a constant `demand` or the name
+    /// of a demand series, and an integer `priority`, lower first) are
+    /// allocated every step: each receives the largest withdrawal that
+    /// keeps all more senior demands fully served and all nodes at or above
+    /// their `min_flow`.
+    ///
+    /// Elements start from their initial conditions, or continue from a
+    /// state restored with `load_state` or `set_state`, and keep their
+    /// state at the end of the run for `save_state`.
+    ///
+    /// Returns:
+    ///     NetworkResult: Inflow, outflow, storage, stage, withdrawal,
+    ///     diversion, exchange, loss, captured flow and pollutant loads of
+    ///     every node.
+    #[pyo3(signature = (inflows, time_step, progress_callback=None, adaptive_tolerance=None, max_refinement=4, max_coarsening=3, checkpoint=None, checkpoint_every=1))]
+    #[allow(clippy::too_many_arguments)]
+    fn run(
+        &mut self,
+        py: Python<'_>,
+        inflows: BTreeMap<String, FloatSeries>,
+        time_step: Bound<'_, PyDelta>,
+        progress_callback: Option<Bound<'_, PyAny>>,
+        adaptive_tolerance: Option<f64>,
+        max_refinement: u32,
+        max_coarsening: u32,
+        checkpoint: Option<PathBuf>,
+        checkpoint_every: usize,
+    ) -> PyResult<PyNetworkResult> {
+        let dt = timedelta_to_seconds(&time_step);
+        let inflows: BTreeMap<String, Vec<f64>> =
+            inflows.into_iter().map(|(k, v)| (k, v.0)).collect();
+        let step = match adaptive_tolerance {
+            Some(tolerance) => AdaptiveStep {
+                tolerance,
+                max_refinement,
+                max_coarsening,
+            },
+            None => AdaptiveStep::FIXED,
+        };
+        let network = &mut self.inner;
+        network.set_checkpoint(checkpoint.map(|path| Checkpoint {
+            path,
+            every: checkpoint_every,
+        }))?;
+        let run = run_with_progress(py, progress_callback.as_ref(), |progress| {
+            let (result, warnings) =
+                capture_warnings(|| network.run_adaptive(&inflows, dt, &step, progress));
+            result.map(|result| (result, warnings))
+        });
+        network.set_checkpoint(None)?;
+        let (result, warnings) = run?;
+        let parameters = match adaptive_tolerance {
+            Some(tolerance) => vec![
+                ("adaptive_tolerance".to_string(), tolerance.into()),
+                (
+                    "max_refinement".to_string(),
+                    f64::from(max_refinement).into(),
+                ),
+                (
+                    "max_coarsening".to_string(),
+                    f64::from(max_coarsening).into(),
+                ),
+            ],
+            None => Vec::new(),
+        };
+        Ok(PyNetworkResult {
+            inner: result,
+            warnings,
+            parameters,
+        })
+    }
+
+    /// Builds the run report of a simulation of this network.
+    ///
+    /// The report holds the configuration and its SHA-256 hash, the run
+    /// parameters, the mass balance of every node, a table of peak flows,
+    /// stages and storages, and the warnings logged during the run.
+    /// Balances cover the period from the end of the first step to the end
+    /// of the last, with trapezoidal volumes in flow units times seconds;
+    /// `unaccounted` volume is rainfall, evaporation and streambed exchange
+    /// plus any continuity error.
+    ///
+    /// Args:
+    ///     result (NetworkResult): Result of `run` on this network.
+    ///     time_step (timedelta): Time step the run used.
+    ///     start (datetime, optional): Time of the first result, used to
+    ///         time the peaks. Defaults to None, giving step numbers.
+    ///     title (str, optional): Report title.
+    ///     parameters (dict, optional): Further run parameters to record,
+    ///         such as a scenario name or the source of the inflows.
+    ///
+    /// Returns:
+    ///     dict: The report, in the layout of the JSON written by
+    ///     `write_report`.
+    #[pyo3(signature = (result, time_step, start=None, title="rustflow network simulation", parameters=None))]
+    fn report(
+        &self,
+        py: Python<'_>,
+        result: &PyNetworkResult,
+        time_step: Bound<'_, PyDelta>,
+        start: Option<Bound<'_, PyDateTime>>,
+        title: &str,
+        parameters: Option<BTreeMap<String, Value>>,
+    ) -> PyResult<PyObject> {
+        let report = self.run_report(result, &time_step, start.as_ref(), title, parameters);
+        value_to_py(py, &report.to_value())
+    }
+
+    /// Writes the run report of a simulation of this network (see
+    /// `report`) as a self-contained HTML page if `path` ends in `.html`
+    /// or `.htm`, and as JSON otherwise.
+    ///
+    /// Args:
+    ///     path (str | PathLike): File to create.
+    ///     result (NetworkResult): Result of `run` on this network.
+    ///     time_step (timedelta): Time step the run used.
+    ///     start (datetime, optional): Time of the first result. Defaults to
+    ///         None.
+    ///     title (str, optional): Report title.
+    ///     parameters (dict, optional): Further run parameters to record.
+    #[pyo3(signature = (path, result, time_step, start=None, title="rustflow network simulation", parameters=None))]
+    fn write_report(
+        &self,
+        path: PathBuf,
+        result: &PyNetworkResult,
+        time_step: Bound<'_, PyDelta>,
+        start: Option<Bound<'_, PyDateTime>>,
+        title: &str,
+        parameters: Option<BTreeMap<String, Value>>,
+    ) -> PyResult<()> {
+        let report = self.run_report(result, &time_step, start.as_ref(), title, parameters);
+        report.write(&path)?;
+        Ok(())
+    }
+
+    /// State of the network at the end of the last run, or as last
+    /// restored: the state of every node's element (in SI units) and
+    /// tailwater level, keyed by node id, with the `time_step` (seconds)
+    /// and number of `steps` completed since the simulation started,
+    /// counting those before any restart.
+    ///
+    /// Returns:
+    ///     dict: The state, accepted by `set_state`.
+    fn state(&self, py: Python<'_>) -> PyResult<PyObject> {
+        value_to_py(py, &self.inner.state())
+    }
+
+    /// Restores a state returned by `state`, e.g. to run several forecasts
+    /// from the same initial conditions. The next run continues from it
+    /// instead of starting from the initial conditions.
+    ///
+    /// Args:
+    ///     state (dict): State of a network with the same nodes.
+    fn set_state(&mut self, state: Value) -> PyResult<()> {
+        self.inner.set_state(&state)?;
+        Ok(())
+    }
+
+    /// Saves the state of the network at the end of the last run as JSON,
+    /// for a later run to continue from (a hot start).
+    ///
+    /// Args:
+    ///     path (str | PathLike): File to write.
+    fn save_state(&self, path: PathBuf) -> PyResult<()> {
+        self.inner.save_state(&path)?;
+        Ok(())
+    }
+
+    /// Restores a state saved by `save_state` or written as a `run`
+    /// checkpoint. The next run continues from it instead of starting from
+    /// the initial conditions.
+    ///
+    /// Args:
+    ///     path (str | PathLike): File to read.
+    ///
+    /// Returns:
+    ///     int: Number of steps of the simulation completed at the saved
+    ///     state, counting those before any earlier restart.
+    ///     To restart an interrupted run, pass it the inflows from this
+    ///     index on.
+    fn load_state(&mut self, path: PathBuf) -> PyResult<usize> {
+        Ok(self.inner.load_state(&path)?)
+    }
+
+    /// Links between nodes, in network order.
+    ///
+    /// Returns:
+    ///     list[tuple[str, str, str]]: `(from, to, type)` triples, with type
+    ///     `"downstream"` for a node draining to its downstream node and
+    ///     `"diversion"` for the flow a bifurcation diverts.
+    fn links(&self) -> Vec<(String, String, &'static str)> {
+        self.inner
+            .links()
+            .into_iter()
+            .map(|(from, to, link)| (from.to_string(), to.to_string(), link.as_str()))
+            .collect()
+    }
+
+    /// Traces the nodes upstream of a node.
+    ///
+    /// Args:
+    ///     node_id (str): Node to trace from.
+    ///
+    /// Returns:
+    ///     list[str]: Ids of the nodes whose water reaches `node_id`
+    ///     through downstream or diversion links, in network order.
+    ///
+    /// Raises:
+    ///     KeyError: If the network has no node `node_id`.
+    fn upstream(&self, node_id: &str) -> PyResult<Vec<String>> {
+        let ids = self
+            .inner
+            .upstream_of(node_id)
+            .map_err(|_| PyKeyError::new_err(node_id.to_string()))?;
+        Ok(ids.into_iter().map(str::to_string).collect())
+    }
+
+    /// Traces the nodes downstream of a node.
+    ///
+    /// Args:
+    ///     node_id (str): Node to trace from.
+    ///
+    /// Returns:
+    ///     list[str]: Ids of the nodes that water leaving `node_id` passes
+    ///     through, including diverted flow, in network order.
+    ///
+    /// Raises:
+    ///     KeyError: If the network has no node `node_id`.
+    fn downstream(&self, node_id: &str) -> PyResult<Vec<String>> {
+        let ids = self
+            .inner
+            .downstream_of(node_id)
+            .map_err(|_| PyKeyError::new_err(node_id.to_string()))?;
+        Ok(ids.into_iter().map(str::to_string).collect())
+    }
+
+    /// list[str]: Ids of the nodes without a downstream node, where water
+    /// leaves the network.
+    #[getter]
+    fn outlets(&self) -> Vec<String> {
+        self.inner
+            .outlets()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Exports the network as a Graphviz DOT graph.
+    ///
+    /// Nodes are labelled with their id and element type, shaped by type
+    /// and carry their configuration as a tooltip; diversions are dashed.
+    /// Render it with e.g. `dot -Tsvg network.dot -o network.svg`.
+    ///
+    /// Args:
+    ///     path (str | PathLike, optional): File to write the graph to.
+    ///         Defaults to None, only returning it.
+    ///
+    /// Returns:
+    ///     str: The DOT text.
+    #[pyo3(signature = (path=None))]
+    fn to_dot(&self, path: Option<PathBuf>) -> PyResult<String> {
+        let dot = self.inner.to_dot();
+        if let Some(path) = path {
+            std::fs::write(&path, &dot)
+                .map_err(|e| Error::Io(format!("{}: {e}", path.display())))?;
+        }
+        Ok(dot)
+    }
+
+    /// Exports the network as a NetworkX directed graph.
+    ///
+    /// Each graph node is a network node id with its configuration fields
+    /// (other than `id` and `downstream`) as attributes, including `type`,
+    /// and its position in network order as `order`. Each edge has a
+    /// `type` attribute, `"downstream"` or `"diversion"`.
+    ///
+    /// Returns:
+    ///     networkx.DiGraph: The graph.
+    ///
+    /// Raises:
+    ///     ImportError: If NetworkX is not installed.
+    fn to_networkx<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let networkx = py.import("networkx").map_err(|_| {
+            PyImportError::new_err(
+                "Graph export needs NetworkX; install it with `pip install networkx` \
+                 or `pip install rustflow[graph]`.",
+            )
+        })?;
+        let graph = networkx.call_method0("DiGraph")?;
+        for (order, node) in self.inner.ordered_nodes().enumerate() {
+            let attributes = value_to_py(py, &node_attributes(node))?;
+            let attributes = attributes.downcast_bound::<PyDict>(py)?;
+            attributes.set_item("order", order)?;
+            graph.call_method("add_node", (node.id.as_str(),), Some(attributes))?;
+        }
+        for (from, to, link) in self.inner.links() {
+            let attributes = PyDict::new(py);
+            attributes.set_item("type", link.as_str())?;
+            graph.call_method("add_edge", (from, to), Some(&attributes))?;
+        }
+        Ok(graph)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Network(nodes={:?})", self.node_ids())
+    }
+
+    /// Pickles the network as its JSON configuration, with its state,
+    /// whether the next run continues from it, and its checkpoints.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(
+        Bound<'py, PyAny>,
+        (String,),
+        (PyObject, bool, Option<(PathBuf, usize)>),
+    )> {
+        let this = slf.borrow();
+        let network = &this.inner;
+        let text = json::to_string(&network.to_value());
+        let checkpoint = network
+            .checkpoint
+            .as_ref()
+            .map(|checkpoint| (checkpoint.path.clone(), checkpoint.every));
+        Ok((
+            slf.get_type().getattr("from_json")?,
+            (text,),
+            (
+                value_to_py(slf.py(), &network.state())?,
+                network.resume,
+                checkpoint,
+            ),
+        ))
+    }
+
+    fn __setstate__(&mut self, state: NetworkPickle) -> PyResult<()> {
+        let (state, resume, checkpoint) = state;
+        let network = &mut self.inner;
+        network.set_state(&state)?;
+        network.resume = resume;
+        network.set_checkpoint(checkpoint.map(|(path, every)| Checkpoint { path, every }))?;
+        Ok(())
+    }
+}
+
+impl PyNetwork {
+    fn run_report(
+        &self,
+        result: &PyNetworkResult,
+        time_step: &Bound<'_, PyDelta>,
+        start: Option<&Bound<'_, PyDateTime>>,
+        title: &str,
+        parameters: Option<BTreeMap<String, Value>>,
+    ) -> RunReport {
+        let mut report = RunReport::new(
+            title,
+            &self.inner.to_value(),
+            &result.inner,
+            timedelta_to_seconds(time_step),
+            result.warnings.clone(),
+        );
+        report.start = start.map(datetime_to_epoch);
+        report.parameters = result.parameters.clone();
+        report.parameters.extend(parameters.into_iter().flatten());
+        report
+    }
+}
+
+/// Pickled state of a `Network` beyond its configuration: the element
+/// states, whether the next run resumes from them, and the checkpoint path
+/// and interval.
+type NetworkPickle = (Value, bool, Option<(PathBuf, usize)>);
+
+/// Simulated series of every node of a `Network`.
+#[pyclass(name = "NetworkResult", module = "rustflow.network")]
+pub struct PyNetworkResult {
+    pub(crate) inner: NetworkResult,
+    /// Warnings logged during the run.
+    pub(crate) warnings: Vec<String>,
+    /// Settings of the run recorded in its report.
+    pub(crate) parameters: Vec<(String, Value)>,
+}
+
+fn series_dict<'py>(
+    py: Python<'py>,
+    ids: &[String],
+    series: &[Vec<f64>],
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (id, values) in ids.iter().zip(series) {
+        dict.set_item(id, values.clone())?;
+    }
+    Ok(dict)
+}
+
+#[pymethods]
+impl PyNetworkResult {
+    /// list[str]: Node ids in network order.
+    #[getter]
+    fn node_ids(&self) -> Vec<String> {
+        self.inner.node_ids.clone()
+    }
+
+    /// dict[str, list[float]]: Inflow to each node, keyed by node id.
+    #[getter]
+    fn inflow<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.inflow)
+    }
+
+    /// dict[str, list[float]]: Outflow from each node, keyed by node id.
+    #[getter]
+    fn outflow<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.outflow)
+    }
+
+    /// dict[str, list[float]]: Storage of each node element (flow units ×
+    /// seconds), keyed by node id.
+    #[getter]
+    fn storage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.storage)
+    }
+
+    /// dict[str, list[float]]: Water surface elevation of each node element
+    /// (`nan` where not defined), keyed by node id.
+    #[getter]
+    fn stage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.stage)
+    }
+
+    /// dict[str, list[float]]: Flow withdrawn by each demand node (zero for
+    /// other nodes), keyed by node id.
+    #[getter]
+    fn withdrawal<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.withdrawal)
+    }
+
+    /// dict[str, list[float]]: Flow sent to the diversion node by each
+    /// bifurcation (zero for other nodes), keyed by node id.
+    #[getter]
+    fn diversion<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.diversion)
+    }
+
+    /// dict[str, list[float]]: Flow from each groundwater node to its stream
+    /// through the streambed (negative when the stream loses water; zero for
+    /// other nodes), keyed by node id. Included in the node outflow.
+    #[getter]
+    fn exchange<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.exchange)
+    }
+
+    /// dict[str, list[float]]: Flow lost from the channel at each node (zero
+    /// for nodes without losses), keyed by node id.
+    #[getter]
+    fn loss<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.loss)
+    }
+
+    /// dict[str, list[float]]: Inflow captured by each treatment node rather
+    /// than bypassed (zero for other nodes), keyed by node id.
+    #[getter]
+    fn captured<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.captured)
+    }
+
+    /// dict[str, list[float]]: Pollutant load leaving each node downstream
+    /// (mass per second), keyed by node id.
+    #[getter]
+    fn load<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.load)
+    }
+
+    /// dict[str, list[float]]: Pollutant load removed by each treatment node
+    /// (mass per second; zero for other nodes), keyed by node id.
+    #[getter]
+    fn load_removed<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.load_removed)
+    }
+
+    /// dict[str, list[float]]: Tailwater level below each reservoir with a
+    /// tailwater-dependent outlet (`nan` for other nodes), keyed by node id.
+    #[getter]
+    fn tailwater<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        series_dict(py, &self.inner.node_ids, &self.inner.tailwater)
+    }
+
+    /// list[str]: Warnings logged during the run, such as unstable routing
+    /// coefficients or unconverged iterations.
+    #[getter]
+    fn warnings(&self) -> Vec<String> {
+        self.warnings.clone()
+    }
+
+    /// Writes the results to a CF-1.8 compliant NetCDF file.
+    ///
+    /// Args:
+    ///     path (str | PathLike): File to create.
+    ///     times (list[datetime]): Timestamp of each simulated step.
+    ///     units (Literal["si", "us"], optional): Unit system of the flows and
+    ///         stages, used for the `units` attributes. Defaults to `"si"`.
+    ///     title (str, optional): Dataset title.
+    ///
+    /// Raises:
+    ///     DataError: If the result has no steps, or `times` does not have
+    ///         one timestamp per step.
+    #[pyo3(signature = (path, times, units=UnitSystem::Si, title="rustflow network simulation"))]
+    fn to_netcdf(
+        &self,
+        path: PathBuf,
+        times: Vec<Bound<'_, PyDateTime>>,
+        units: UnitSystem,
+        title: &str,
+    ) -> PyResult<()> {
+        let times: Vec<f64> = times.iter().map(datetime_to_epoch).collect();
+        network_result_to_netcdf(&self.inner, &times, units, title)?.write(&path)?;
+        Ok(())
+    }
+
+    /// Builds a `pyarrow.Table` of one recorded series of every node.
+    ///
+    /// Args:
+    ///     times (list[datetime], optional): Timestamp of each step, written
+    ///         as a `time` column. Defaults to None, no time column.
+    ///     variable (str, optional): Series to export, one of the result
+    ///         attributes such as `"outflow"`, `"storage"` or `"stage"`.
+    ///         Defaults to `"outflow"`.
+    ///
+    /// Returns:
+    ///     pyarrow.Table: An optional `time` column followed by one float64
+    ///     column per node, named by node id.
+    ///
+    /// Raises:
+    ///     ImportError: If `pyarrow` is not installed.
+    ///     ValueError: If `variable` is not a recorded series, or `times`
+    ///         does not have one timestamp per step.
+    #[pyo3(signature = (times=None, variable="outflow"))]
+    fn to_arrow<'py>(
+        slf: &Bound<'py, Self>,
+        times: Option<Vec<Bound<'py, PyDateTime>>>,
+        variable: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let result = &slf.borrow().inner;
+        if !result.series().iter().any(|(name, _)| *name == variable) {
+            return Err(PyValueError::new_err(format!(
+                "`{variable}` is not a recorded series."
+            )));
+        }
+        let steps = result.outflow.first().map_or(0, Vec::len);
+        if times.as_ref().is_some_and(|t| t.len() != steps) {
+            return Err(PyValueError::new_err(format!(
+                "{} times were given for {steps} simulated steps.",
+                times.as_ref().map_or(0, Vec::len)
+            )));
+        }
+        slf.py()
+            .import("rustflow.io")?
+            .call_method1("_network_result_to_arrow", (slf, times, variable))
+    }
+
+    /// Plots the hydrographs of network nodes with matplotlib (see
+    /// `rustflow.plot.hydrograph`).
+    ///
+    /// Args:
+    ///     nodes (str | list[str], optional): Node or nodes to plot.
+    ///         Defaults to None, the last node.
+    ///     times (list, optional): Time of each step, e.g. datetimes.
+    ///         Defaults to None, plotting against the step number.
+    ///     inflow (bool, optional): If True, overlay each node's inflow
+    ///         (dashed) on its outflow. Defaults to True.
+    ///     precipitation (list[float], optional): Precipitation at each step,
+    ///         drawn as bars on an inverted axis at the top. Defaults to
+    ///         None.
+    ///     events (list[tuple], optional): `(start, end)` times to shade.
+    ///         Defaults to None.
+    ///     ax (matplotlib.axes.Axes, optional): Axes to draw on. Defaults to
+    ///         None, creating a new figure.
+    ///     **kwargs: Further arguments of `rustflow.plot.hydrograph`, e.g.
+    ///         `title` or `flow_label`.
+    ///
+    /// Returns:
+    ///     matplotlib.axes.Axes: The flow axes.
+    ///
+    /// Raises:
+    ///     ImportError: If matplotlib is not installed.
+    ///     KeyError: If a node is not in the network.
+    #[pyo3(signature = (nodes=None, times=None, inflow=true, precipitation=None, events=None, ax=None, **kwargs))]
+    #[allow(clippy::too_many_arguments)]
+    fn plot<'py>(
+        slf: &Bound<'py, Self>,
+        nodes: Option<Bound<'py, PyAny>>,
+        times: Option<Bound<'py, PyAny>>,
+        inflow: bool,
+        precipitation: Option<Bound<'py, PyAny>>,
+        events: Option<Bound<'py, PyAny>>,
+        ax: Option<Bound<'py, PyAny>>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let kwargs = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(py),
+        };
+        kwargs.set_item("precipitation", precipitation)?;
+        kwargs.set_item("events", events)?;
+        kwargs.set_item("ax", ax)?;
+        kwargs.set_item("nodes", nodes)?;
+        kwargs.set_item("times", times)?;
+        kwargs.set_item("inflow", inflow)?;
+        py.import("rustflow.plot")?
+            .call_method("_network_result", (slf,), Some(&kwargs))
+    }
+
+    /// Pickles the result as a dict of node ids and series.
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyDict>,))> {
+        let result = &slf.borrow().inner;
+        let state = PyDict::new(slf.py());
+        state.set_item("node_ids", &result.node_ids)?;
+        for (name, series) in result.series() {
+            state.set_item(name, series)?;
+        }
+        state.set_item("warnings", &slf.borrow().warnings)?;
+        let parameters = Value::Object(slf.borrow().parameters.clone());
+        state.set_item("parameters", value_to_py(slf.py(), &parameters)?)?;
+        Ok((slf.get_type().getattr("_from_state")?, (state,)))
+    }
+
+    #[classmethod]
+    fn _from_state(_cls: &Bound<'_, PyType>, state: Bound<'_, PyDict>) -> PyResult<Self> {
+        let series = |name: &str| -> PyResult<Vec<Vec<f64>>> {
+            state
+                .get_item(name)?
+                .ok_or_else(|| PyKeyError::new_err(name.to_string()))?
+                .extract()
+        };
+        Ok(PyNetworkResult {
+            inner: NetworkResult {
+                node_ids: state
+                    .get_item("node_ids")?
+                    .ok_or_else(|| PyKeyError::new_err("node_ids"))?
+                    .extract()?,
+                inflow: series("inflow")?,
+                outflow: series("outflow")?,
+                storage: series("storage")?,
+                stage: series("stage")?,
+                withdrawal: series("withdrawal")?,
+                diversion: series("diversion")?,
+                exchange: series("exchange")?,
+                loss: series("loss")?,
+                captured: series("captured")?,
+                load: series("load")?,
+                load_removed: series("load_removed")?,
+                tailwater: series("tailwater")?,
+            },
+            warnings: match state.get_item("warnings")? {
+                Some(warnings) => warnings.extract()?,
+                None => Vec::new(),
+            },
+            parameters: match state.get_item("parameters")? {
+                Some(parameters) => match parameters.extract()? {
+                    Value::Object(entries) => entries,
+                    _ => Vec::new(),
+                },
+                None => Vec::new(),
+            },
+        })
+    }
+}
+
+#[pymodule]
+pub fn init_network(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyNetwork>()?;
+    m.add_class::<PyNetworkResult>()?;
+    Ok(())
+}
@@ -0,0 +1,341 @@
+//! Checkpoints of network simulations: the state every element carries
+//! from one step to the next, saved as JSON so that a later run continues
+//! from it. Forecast cycles hot-start from the state at the end of the
+//! previous cycle, and long runs write checkpoints as they go so they can
+//! be restarted after a crash.
+
+use std::path::{Path, PathBuf};
+
+use super::{Element, Network};
+use crate::config::{json, read_config, Value};
+use crate::error::{Error, Result};
+use crate::log::warning;
+use crate::reservoir::level_pool::PoolState;
+
+/// Version of the saved state format.
+const STATE_VERSION: f64 = 1.0;
+
+/// Writing of checkpoints during a run: the network state is saved to
+/// `path` after every `every` reporting intervals, replacing the previous
+/// checkpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    pub path: PathBuf,
+    pub every: usize,
+}
+
+impl Checkpoint {
+    /// Reads the optional `checkpoint` table of a network configuration,
+    /// with `path` and `every` (default 1) fields.
+    pub fn from_value(value: &Value) -> Result<Option<Self>> {
+        let Some(table) = value.get("checkpoint") else {
+            return Ok(None);
+        };
+        let checkpoint = Checkpoint {
+            path: PathBuf::from(table.str_field("path")?),
+            every: table.opt_f64_field("every")?.unwrap_or(1.0) as usize,
+        };
+        checkpoint.validate()?;
+        Ok(Some(checkpoint))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.every == 0 {
+            return Err(Error::InvalidParameter(
+                "Checkpoints must be written every one or more steps.".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn table(entries: Vec<(&str, Value)>) -> Value {
+    Value::Object(
+        entries
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+    )
+}
+
+/// Checks that a saved series has `expected` values.
+fn check_len(key: &str, values: &[f64], expected: usize) -> Result<()> {
+    if values.len() != expected {
+        return Err(Error::Data(format!(
+            "Saved `{key}` has {} values for {expected}.",
+            values.len()
+        )));
+    }
+    Ok(())
+}
+
+impl Element {
+    /// State carried from one step to the next, as a table of named
+    /// values in the element's internal (SI) units; `Null` for elements
+    /// without one. Junctions, demands, bifurcations and bridges only hold
+    /// values of the last step.
+    pub fn state(&self) -> Value {
+        match self {
+            Element::Muskingum(reach) => {
+                let (inflow, outflow): (Vec<f64>, Vec<f64>) = reach.state().iter().copied().unzip();
+                table(vec![
+                    ("inflow", inflow.as_slice().into()),
+                    ("outflow", outflow.as_slice().into()),
+                ])
+            }
+            Element::KinematicWave(reach, _) => table(vec![("areas", reach.areas().into())]),
+            Element::Reservoir(reservoir) => match reservoir.state() {
+                None => Value::Null,
+                Some(pool) => table(vec![
+                    ("elevation", pool.elevation.into()),
+                    ("storage", pool.storage.into()),
+                    ("inflow", pool.inflow.into()),
+                    ("outflow", pool.outflow.into()),
+                    ("evaporation", pool.evaporation.into()),
+                    ("seepage", pool.seepage.into()),
+                ]),
+            },
+            Element::Subcatchment(surface, _) => table(vec![("depth", surface.depth().into())]),
+            Element::Groundwater(aquifer, _) => table(vec![("storage", aquifer.storage().into())]),
+            Element::Treatment(device) => table(vec![
+                ("volume", device.volume().into()),
+                ("mass", device.mass().into()),
+            ]),
+            Element::Pump(station) => {
+                let (running, elapsed): (Vec<bool>, Vec<f64>) =
+                    station.pump_states().into_iter().unzip();
+                table(vec![
+                    ("volume", station.volume().into()),
+                    (
+                        "running",
+                        Value::Array(running.into_iter().map(Value::from).collect()),
+                    ),
+                    // Pumps that never switched have an infinite elapsed
+                    // time, written as null.
+                    ("elapsed", elapsed.as_slice().into()),
+                ])
+            }
+            Element::LateralWeir(weir) => table(vec![("volume", weir.volume().into())]),
+            Element::FloodCells(cells) => table(vec![("volumes", cells.volumes().into())]),
+            Element::Junction(_) | Element::Demand(_) | Element::Bifurcation(_) => Value::Null,
+            Element::Bridge(_) => Value::Null,
+        }
+    }
+
+    /// Restores a state returned by [`Element::state`] for an element of
+    /// the same kind and size.
+    pub fn set_state(&mut self, state: &Value) -> Result<()> {
+        match self {
+            Element::Muskingum(reach) => {
+                let inflow = state.f64_array_field("inflow")?;
+                let outflow = state.f64_array_field("outflow")?;
+                check_len("outflow", &outflow, inflow.len())?;
+                if !inflow.is_empty() {
+                    check_len("inflow", &inflow, reach.sub_reaches)?;
+                }
+                reach.set_state(inflow.into_iter().zip(outflow).collect());
+            }
+            Element::KinematicWave(reach, _) => {
+                let areas = state.f64_array_field("areas")?;
+                if !areas.is_empty() {
+                    check_len("areas", &areas, reach.segments)?;
+                }
+                reach.set_areas(areas);
+            }
+            Element::Reservoir(reservoir) => {
+                let pool = match state {
+                    Value::Null => None,
+                    _ => Some(PoolState {
+                        elevation: state.f64_field("elevation")?,
+                        storage: state.f64_field("storage")?,
+                        inflow: state.f64_field("inflow")?,
+                        outflow: state.f64_field("outflow")?,
+                        evaporation: state.f64_field("evaporation")?,
+                        seepage: state.f64_field("seepage")?,
+                    }),
+                };
+                reservoir.set_state(pool);
+            }
+            Element::Subcatchment(surface, _) => surface.set_depth(state.f64_field("depth")?),
+            Element::Groundwater(aquifer, _) => aquifer.set_storage(state.f64_field("storage")?),
+            Element::Treatment(device) => {
+                device.set_storage(state.f64_field("volume")?, state.f64_field("mass")?)
+            }
+            Element::Pump(station) => {
+                let running = state
+                    .get("running")
+                    .and_then(Value::as_array)
+                    .and_then(|items| items.iter().map(Value::as_bool).collect::<Option<Vec<_>>>())
+                    .ok_or_else(|| {
+                        Error::Data("Field `running` must be an array of booleans.".into())
+                    })?;
+                let elapsed = state
+                    .get("elapsed")
+                    .and_then(Value::as_array)
+                    .and_then(|items| {
+                        items
+                            .iter()
+                            .map(|item| match item {
+                                Value::Null => Some(f64::INFINITY),
+                                _ => item.as_f64(),
+                            })
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .ok_or_else(|| {
+                        Error::Data("Field `elapsed` must be an array of numbers.".into())
+                    })?;
+                check_len("elapsed", &elapsed, station.pumps.len())?;
+                if running.len() != station.pumps.len() {
+                    return Err(Error::Data(format!(
+                        "Saved `running` has {} values for {}.",
+                        running.len(),
+                        station.pumps.len()
+                    )));
+                }
+                let pumps: Vec<(bool, f64)> = running.into_iter().zip(elapsed).collect();
+                station.set_state(state.f64_field("volume")?, &pumps);
+            }
+            Element::LateralWeir(weir) => weir.set_volume(state.f64_field("volume")?),
+            Element::FloodCells(cells) => {
+                let volumes = state.f64_array_field("volumes")?;
+                check_len("volumes", &volumes, cells.cells.len())?;
+                cells.set_volumes(volumes);
+            }
+            Element::Junction(_) | Element::Demand(_) | Element::Bifurcation(_) => {}
+            Element::Bridge(_) => {}
+        }
+        Ok(())
+    }
+}
+
+impl Network {
+    /// State of the network at the end of the last step: the state of
+    /// every node's element and tailwater level, keyed by node id, with
+    /// the `time_step` (s) and number of reporting intervals (`steps`)
+    /// completed since the simulation started, counting those before any
+    /// restart.
+    pub fn state(&self) -> Value {
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let mut entry = table(vec![("element", node.element.state())]);
+                if node.tailwater.is_some() {
+                    entry.insert("tailwater", self.tailwater_level[i].into());
+                }
+                (node.id.clone(), entry)
+            })
+            .collect();
+        table(vec![
+            ("version", STATE_VERSION.into()),
+            ("time_step", self.time_step.into()),
+            ("steps", (self.completed as f64).into()),
+            ("nodes", Value::Object(nodes)),
+        ])
+    }
+
+    /// Restores a state returned by [`Network::state`] for a network with
+    /// the same nodes. The next run continues from it instead of starting
+    /// from the initial conditions, and returns the results of the steps
+    /// after it; to restart an interrupted run, pass it the input series
+    /// from index `steps` on.
+    pub fn set_state(&mut self, state: &Value) -> Result<()> {
+        if state.opt_f64_field("version")? != Some(STATE_VERSION) {
+            return Err(Error::Data(
+                "Unsupported or missing network state `version`.".into(),
+            ));
+        }
+        let nodes = state
+            .get("nodes")
+            .ok_or_else(|| Error::Data("Network state has no `nodes` table.".into()))?;
+        // Validate every node before changing any of them.
+        let mut elements = Vec::with_capacity(self.nodes.len());
+        let mut levels = self.tailwater_level.clone();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let entry = nodes
+                .get(&node.id)
+                .ok_or_else(|| Error::Data(format!("Network state has no node `{}`.", node.id)))?;
+            let mut element = node.element.clone();
+            element
+                .set_state(entry.get("element").unwrap_or(&Value::Null))
+                .map_err(|e| Error::Data(format!("State of node `{}`: {e}", node.id)))?;
+            elements.push(element);
+            if node.tailwater.is_some() {
+                levels[i] = entry.opt_f64_field("tailwater")?.unwrap_or(f64::NAN);
+            }
+        }
+        for (node, element) in self.nodes.iter_mut().zip(elements) {
+            node.element = element;
+        }
+        self.tailwater_level = levels;
+        self.time_step = state.opt_f64_field("time_step")?.unwrap_or(f64::NAN);
+        self.completed = state.opt_f64_field("steps")?.unwrap_or(0.0) as usize;
+        self.resume = true;
+        Ok(())
+    }
+
+    /// Writes [`Network::state`] as JSON to `path`. The file is written
+    /// next to it first and then renamed, so an interrupted write leaves
+    /// the previous state intact.
+    pub fn save_state(&self, path: &Path) -> Result<()> {
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        std::fs::write(&partial, json::to_string(&self.state()))
+            .and_then(|()| std::fs::rename(&partial, path))
+            .map_err(|e| Error::Io(format!("{}: {e}", path.display())))
+    }
+
+    /// Restores the state saved by [`Network::save_state`] to `path` (see
+    /// [`Network::set_state`]) and returns the number of reporting
+    /// intervals of the simulation completed at it.
+    pub fn load_state(&mut self, path: &Path) -> Result<usize> {
+        self.set_state(&read_config(path)?)?;
+        Ok(self.completed)
+    }
+
+    /// Sets the checkpoints written during later runs, or stops writing
+    /// them.
+    pub fn set_checkpoint(&mut self, checkpoint: Option<Checkpoint>) -> Result<()> {
+        if let Some(checkpoint) = &checkpoint {
+            checkpoint.validate()?;
+        }
+        self.checkpoint = checkpoint;
+        Ok(())
+    }
+
+    /// Prepares the network for a run with time step `dt`: resets every
+    /// element to its initial conditions, or continues from a restored
+    /// state and its count of completed intervals.
+    pub(super) fn start_run(&mut self, dt: f64) {
+        if std::mem::take(&mut self.resume) {
+            if self.time_step.is_finite() && self.time_step != dt {
+                warning!(
+                    "Resuming from a state saved with a time step of {} s in a run with {dt} s",
+                    self.time_step
+                );
+            }
+        } else {
+            for node in self.nodes.iter_mut() {
+                node.element.reset();
+            }
+            self.start_tailwater();
+            self.completed = 0;
+        }
+        self.time_step = dt;
+    }
+
+    /// Counts `intervals` more completed reporting intervals, writing a
+    /// checkpoint when they reach a multiple of its interval.
+    pub(super) fn complete_intervals(&mut self, intervals: usize) -> Result<()> {
+        let before = self.completed;
+        self.completed += intervals;
+        match &self.checkpoint {
+            Some(checkpoint) if before / checkpoint.every != self.completed / checkpoint.every => {
+                self.save_state(&checkpoint.path)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::interp::interp;
+
+/// Where the tailwater level below a reservoir outlet comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TailwaterSource {
+    /// Pool elevation of the downstream reservoir, for closely spaced dams
+    /// whose lower pool backs up against the upper dam.
+    Downstream,
+    /// Stage–discharge rating of the channel below the dam, evaluated at
+    /// the reservoir outflow: `(discharge, stage)`.
+    Rating(Vec<f64>, Vec<f64>),
+}
+
+/// Reservoir outlet rating that depends on the tailwater level as well as
+/// the pool elevation, such as a spillway or gate that becomes submerged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TailwaterOutlet {
+    /// Tailwater levels of the table rows, strictly increasing.
+    pub levels: Vec<f64>,
+    /// Outflow at each tailwater level (rows) and each elevation of the
+    /// reservoir table (columns).
+    pub outflow: Vec<Vec<f64>>,
+    pub source: TailwaterSource,
+}
+
+impl TailwaterOutlet {
+    /// Reads the optional `tailwater` (levels) and `tailwater_outflow` (one
+    /// outflow row per level) fields of a reservoir node, with the source
+    /// given by `tailwater_discharge`/`tailwater_stage` rating arrays or,
+    /// when these are absent, the downstream reservoir's pool.
+    pub fn from_value(value: &Value) -> Result<Option<Self>> {
+        if value.get("tailwater").is_none() {
+            return Ok(None);
+        }
+        let levels = value.f64_array_field("tailwater")?;
+        let outflow = value
+            .get("tailwater_outflow")
+            .and_then(Value::as_array)
+            .and_then(|rows| {
+                rows.iter()
+                    .map(|row| row.as_array()?.iter().map(Value::as_f64).collect())
+                    .collect::<Option<Vec<Vec<f64>>>>()
+            })
+            .ok_or_else(|| {
+                Error::Data(
+                    "Field `tailwater_outflow` must be an array of arrays of numbers.".into(),
+                )
+            })?;
+        let source = if value.get("tailwater_discharge").is_some() {
+            let discharge = value.f64_array_field("tailwater_discharge")?;
+            let stage = value.f64_array_field("tailwater_stage")?;
+            if discharge.len() != stage.len() || discharge.len() < 2 {
+                return Err(Error::InvalidParameter(
+                    "`tailwater_discharge` and `tailwater_stage` need at least two values of equal length."
+                        .into(),
+                ));
+            }
+            if discharge.windows(2).any(|w| w[1] <= w[0]) {
+                return Err(Error::InvalidParameter(
+                    "`tailwater_discharge` must be strictly increasing.".into(),
+                ));
+            }
+            TailwaterSource::Rating(discharge, stage)
+        } else {
+            TailwaterSource::Downstream
+        };
+        Ok(Some(TailwaterOutlet {
+            levels,
+            outflow,
+            source,
+        }))
+    }
+
+    /// Checks the table against a reservoir table with `n` elevations.
+    pub fn validate(&self, n: usize) -> Result<()> {
+        if self.levels.is_empty() || self.outflow.len() != self.levels.len() {
+            return Err(Error::InvalidParameter(
+                "`tailwater_outflow` needs one row for each `tailwater` level.".into(),
+            ));
+        }
+        if self.levels.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(Error::InvalidParameter(
+                "`tailwater` levels must be strictly increasing.".into(),
+            ));
+        }
+        if self.outflow.iter().any(|row| row.len() != n) {
+            return Err(Error::InvalidParameter(format!(
+                "Each `tailwater_outflow` row needs {n} values, one per reservoir elevation."
+            )));
+        }
+        if self
+            .outflow
+            .iter()
+            .any(|row| row.windows(2).any(|w| w[1] < w[0]))
+        {
+            return Err(Error::InvalidParameter(
+                "`tailwater_outflow` rows must be non-decreasing with elevation.".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Outflow table at tailwater `level`, interpolated between rows and
+    /// held at the first and last rows outside them.
+    pub fn outflow_at(&self, level: f64) -> Vec<f64> {
+        let last = self.levels.len() - 1;
+        if last == 0 || level.is_nan() || level <= self.levels[0] {
+            return self.outflow[0].clone();
+        }
+        if level >= self.levels[last] {
+            return self.outflow[last].clone();
+        }
+        let i = self.levels.partition_point(|&l| l <= level) - 1;
+        let f = (level - self.levels[i]) / (self.levels[i + 1] - self.levels[i]);
+        self.outflow[i]
+            .iter()
+            .zip(&self.outflow[i + 1])
+            .map(|(a, b)| a + f * (b - a))
+            .collect()
+    }
+
+    /// Tailwater level for reservoir `outflow` and downstream pool
+    /// `downstream_pool`.
+    pub fn level(&self, outflow: f64, downstream_pool: f64) -> f64 {
+        match &self.source {
+            TailwaterSource::Downstream => downstream_pool,
+            TailwaterSource::Rating(discharge, stage) => interp(outflow, discharge, stage),
+        }
+    }
+}
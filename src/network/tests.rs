@@ -0,0 +1,1097 @@
+use super::*;
+use crate::config::toml;
+use crate::reach_routing::muskingum::muskingum_cascade_rs;
+
+const DT: f64 = 1800.0;
+
+fn network(text: &str) -> Result<Network> {
+    Network::from_value(&toml::parse(text)?)
+}
+
+fn series(entries: &[(&str, Vec<f64>)]) -> BTreeMap<String, Vec<f64>> {
+    entries
+        .iter()
+        .map(|(name, values)| (name.to_string(), values.clone()))
+        .collect()
+}
+
+/// Triangular flood of `n` steps rising from `base` to `peak` at step
+/// `rise` and receding over three times as long.
+fn flood(n: usize, base: f64, peak: f64, rise: usize) -> Vec<f64> {
+    (0..n)
+        .map(|t| {
+            let t = t as f64;
+            let r = rise as f64;
+            let shape = if t <= r {
+                t / r
+            } else {
+                (1.0 - (t - r) / (3.0 * r)).max(0.0)
+            };
+            base + (peak - base) * shape
+        })
+        .collect()
+}
+
+fn index(result: &NetworkResult, id: &str) -> usize {
+    result.node_ids.iter().position(|n| n == id).unwrap()
+}
+
+/// Asserts that two results hold bit-identical series (`NaN` included).
+fn assert_same(a: &NetworkResult, b: &NetworkResult) {
+    assert_eq!(a.node_ids, b.node_ids);
+    for ((name, x), (_, y)) in a.series().into_iter().zip(b.series()) {
+        let bits = |s: &Vec<Vec<f64>>| -> Vec<Vec<u64>> {
+            s.iter()
+                .map(|v| v.iter().map(|f| f.to_bits()).collect())
+                .collect()
+        };
+        assert_eq!(bits(x), bits(y), "{name}");
+    }
+}
+
+/// Largest difference, relative to the total inflow volume, between the
+/// change in storage of node `i` and its trapezoidal net inflow volume
+/// over each step after the first.
+fn storage_residual(result: &NetworkResult, i: usize, dt: f64) -> f64 {
+    let (inflow, outflow, storage) = (&result.inflow[i], &result.outflow[i], &result.storage[i]);
+    let volume: f64 = inflow.iter().sum::<f64>() * dt;
+    (1..inflow.len())
+        .map(|t| {
+            let net = 0.5 * (inflow[t] + inflow[t - 1] - outflow[t] - outflow[t - 1]) * dt;
+            (storage[t] - storage[t - 1] - net).abs() / volume
+        })
+        .fold(0.0, f64::max)
+}
+
+const CONFLUENCE: &str = r#"
+[[nodes]]
+id = "upper"
+type = "muskingum"
+k = 7200.0
+x = 0.2
+sub_reaches = 2
+inflow = "upper"
+downstream = "confluence"
+
+[[nodes]]
+id = "tributary"
+type = "muskingum"
+k = 3600.0
+x = 0.1
+inflow = "tributary"
+downstream = "confluence"
+
+[[nodes]]
+id = "confluence"
+type = "junction"
+downstream = "dam"
+
+[[nodes]]
+id = "dam"
+type = "reservoir"
+elevation = [100.0, 101.0, 102.0, 103.0]
+storage = [0.0, 100000.0, 250000.0, 500000.0]
+outflow = [0.0, 5.0, 20.0, 50.0]
+initial_elevation = 100.5
+"#;
+
+fn confluence_inputs() -> BTreeMap<String, Vec<f64>> {
+    series(&[
+        ("upper", flood(48, 5.0, 40.0, 6)),
+        ("tributary", flood(48, 2.0, 15.0, 4)),
+    ])
+}
+
+#[test]
+fn muskingum_node_matches_the_cascade() {
+    let mut net = network(CONFLUENCE).unwrap();
+    let inputs = confluence_inputs();
+    let result = net.run(&inputs, DT).unwrap();
+    let expected = muskingum_cascade_rs(&inputs["upper"], DT, 7200.0, 0.2, 2, None);
+    let outflow = result.outflow_of("upper").unwrap();
+    for (a, b) in outflow.iter().zip(&expected) {
+        assert!((a - b).abs() < 1e-12, "{a} != {b}");
+    }
+    assert_eq!(result.node_ids, ["upper", "tributary", "confluence", "dam"]);
+    for (name, values) in result.series() {
+        assert!(values.iter().all(|v| v.len() == 48), "{name}");
+    }
+}
+
+#[test]
+fn confluence_adds_upstream_outflows() {
+    let mut net = network(CONFLUENCE).unwrap();
+    let result = net.run(&confluence_inputs(), DT).unwrap();
+    let (upper, tributary, confluence) = (
+        index(&result, "upper"),
+        index(&result, "tributary"),
+        index(&result, "confluence"),
+    );
+    for t in 0..48 {
+        let sum = result.outflow[upper][t] + result.outflow[tributary][t];
+        assert!((result.inflow[confluence][t] - sum).abs() < 1e-12);
+        assert_eq!(result.outflow[confluence][t], result.inflow[confluence][t]);
+    }
+    let dam = index(&result, "dam");
+    assert_eq!(result.inflow[dam], result.outflow[confluence]);
+}
+
+#[test]
+fn routing_elements_conserve_mass() {
+    let mut net = network(CONFLUENCE).unwrap();
+    let result = net.run(&confluence_inputs(), DT).unwrap();
+    for id in ["upper", "tributary", "dam"] {
+        let residual = storage_residual(&result, index(&result, id), DT);
+        assert!(residual < 1e-12, "{id}: {residual}");
+    }
+    // The reservoir attenuates the peak and releases it later.
+    let dam = index(&result, "dam");
+    let peak = |s: &[f64]| s.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    assert!(peak(&result.outflow[dam]) < peak(&result.inflow[dam]));
+    assert!(result.stage[dam].iter().all(|h| (100.0..103.0).contains(h)));
+}
+
+#[test]
+fn runs_are_repeatable() {
+    let mut net = network(CONFLUENCE).unwrap();
+    let inputs = confluence_inputs();
+    let first = net.run(&inputs, DT).unwrap();
+    assert_same(&net.run(&inputs, DT).unwrap(), &first);
+}
+
+#[test]
+fn configuration_round_trips() {
+    let net = network(CONFLUENCE).unwrap();
+    let rebuilt = Network::from_value(&net.to_value()).unwrap();
+    assert_eq!(rebuilt.nodes(), net.nodes());
+    let json = crate::config::json::to_string(&net.to_value());
+    let rebuilt = Network::from_value(&crate::config::json::parse(&json).unwrap()).unwrap();
+    assert_eq!(rebuilt.nodes(), net.nodes());
+}
+
+#[test]
+fn rejects_negative_c0_unless_allowed() {
+    let mut net = network(CONFLUENCE).unwrap();
+    match net.run(&confluence_inputs(), 600.0) {
+        Err(Error::InvalidParameter(msg)) => {
+            assert!(msg.starts_with("Node `upper`:"), "{msg}");
+            assert!(msg.contains("c0"), "{msg}");
+        }
+        other => panic!("expected a c0 error, got {other:?}"),
+    }
+    let allowed = CONFLUENCE.replace(
+        "sub_reaches = 2\n",
+        "sub_reaches = 2\nallow_negative_c0 = true\n",
+    );
+    let allowed = allowed.replace("x = 0.1\n", "x = 0.1\nallow_negative_c0 = true\n");
+    let mut net = network(&allowed).unwrap();
+    assert!(net.run(&confluence_inputs(), 600.0).is_ok());
+}
+
+#[test]
+fn rejects_invalid_muskingum_parameters_with_the_node_id() {
+    for (field, value) in [
+        ("k = 7200.0", "k = -7200.0"),
+        ("x = 0.2", "x = 0.7"),
+        ("x = 0.2", "x = nan"),
+        ("sub_reaches = 2", "sub_reaches = 0"),
+        ("sub_reaches = 2", "sub_reaches = -2"),
+    ] {
+        match network(&CONFLUENCE.replacen(field, value, 1)) {
+            Err(Error::InvalidParameter(msg)) => {
+                assert!(msg.starts_with("Node `upper`:"), "{value}: {msg}")
+            }
+            other => panic!("{value}: expected a parameter error, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn rejects_malformed_networks() {
+    let reach = |id: &str, downstream: &str| {
+        format!(
+            "[[nodes]]\nid = \"{id}\"\ntype = \"muskingum\"\nk = 3600.0\nx = 0.1\n\
+             inflow = \"q\"\n{downstream}\n"
+        )
+    };
+    for (text, expected) in [
+        (reach("a", "") + &reach("a", ""), "Duplicate node id `a`"),
+        (
+            reach("a", "downstream = \"b\""),
+            "drains to unknown node `b`",
+        ),
+        (
+            reach("a", "downstream = \"b\"") + &reach("b", "downstream = \"a\""),
+            "cycle",
+        ),
+        (
+            reach("a", "").replace("muskingum", "weir"),
+            "Unknown element type `weir`",
+        ),
+        (reach("a", "").replace("k = 3600.0\n", ""), "field `k`"),
+        ("name = \"empty\"\n".to_string(), "`nodes` or a `series`"),
+        (
+            reach("a", "").replace("[[nodes]]", "[[series]]") + &reach("b", ""),
+            "`nodes` or a `series`",
+        ),
+        (
+            reach("a", "downstream = \"b\"").replace("[[nodes]]", "[[series]]"),
+            "sets `downstream`",
+        ),
+    ] {
+        match network(&text) {
+            Err(Error::Data(msg)) => assert!(msg.contains(expected), "{msg}"),
+            other => panic!("expected `{expected}`, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn rejects_missing_and_unequal_series() {
+    let mut net = network(CONFLUENCE).unwrap();
+    let missing = series(&[("upper", flood(48, 5.0, 40.0, 6))]);
+    match net.run(&missing, DT) {
+        Err(Error::Data(msg)) => assert!(msg.contains("`tributary`"), "{msg}"),
+        other => panic!("{other:?}"),
+    }
+    let unequal = series(&[
+        ("upper", flood(48, 5.0, 40.0, 6)),
+        ("tributary", flood(47, 2.0, 15.0, 4)),
+    ]);
+    assert!(matches!(net.run(&unequal, DT), Err(Error::Data(_))));
+}
+
+const DEMANDS: &str = r#"
+[[nodes]]
+id = "river"
+type = "junction"
+inflow = "q"
+downstream = "city"
+
+[[nodes]]
+id = "city"
+type = "demand"
+demand = 8.0
+priority = 2
+downstream = "farm"
+
+[[nodes]]
+id = "farm"
+type = "demand"
+demand = "irrigation"
+priority = 1
+downstream = "outlet"
+
+[[nodes]]
+id = "outlet"
+type = "junction"
+min_flow = 5.0
+"#;
+
+#[test]
+fn demands_are_served_by_priority_above_minimum_flows() {
+    let mut net = network(DEMANDS).unwrap();
+    let inputs = series(&[
+        ("q", vec![20.0, 12.0, 6.0, 3.0]),
+        ("irrigation", vec![4.0; 4]),
+    ]);
+    let result = net.run(&inputs, DT).unwrap();
+    let (city, farm, outlet) = (
+        index(&result, "city"),
+        index(&result, "farm"),
+        index(&result, "outlet"),
+    );
+    // Senior farm demand first; the junior city demand takes what is left
+    // above the outlet's minimum flow, which is capped by the natural flow.
+    let expected = [
+        (8.0, 4.0, 8.0),
+        (3.0, 4.0, 5.0),
+        (0.0, 1.0, 5.0),
+        (0.0, 0.0, 3.0),
+    ];
+    for (t, &(c, f, o)) in expected.iter().enumerate() {
+        assert!((result.withdrawal[city][t] - c).abs() < 1e-7, "city {t}");
+        assert!((result.withdrawal[farm][t] - f).abs() < 1e-7, "farm {t}");
+        assert!((result.outflow[outlet][t] - o).abs() < 1e-7, "outlet {t}");
+        let withdrawn = result.withdrawal[city][t] + result.withdrawal[farm][t];
+        assert!((inputs["q"][t] - withdrawn - result.outflow[outlet][t]).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn demands_without_minimum_flows_are_limited_by_the_flow() {
+    let mut net = network(&DEMANDS.replace("min_flow = 5.0\n", "")).unwrap();
+    let inputs = series(&[("q", vec![10.0, 6.0]), ("irrigation", vec![4.0; 2])]);
+    let result = net.run(&inputs, DT).unwrap();
+    let (city, farm) = (index(&result, "city"), index(&result, "farm"));
+    assert!((result.withdrawal[farm][0] - 4.0).abs() < 1e-7);
+    assert!((result.withdrawal[city][0] - 6.0).abs() < 1e-7);
+    assert!((result.withdrawal[farm][1] - 4.0).abs() < 1e-7);
+    assert!((result.withdrawal[city][1] - 2.0).abs() < 1e-7);
+}
+
+#[test]
+fn rejects_invalid_demands() {
+    match network(&DEMANDS.replace("demand = 8.0", "demand = true")) {
+        Err(Error::Data(msg)) => assert!(msg.contains("`demand`"), "{msg}"),
+        other => panic!("{other:?}"),
+    }
+    let mut net = network(DEMANDS).unwrap();
+    match net.run(&series(&[("q", vec![1.0])]), DT) {
+        Err(Error::Data(msg)) => assert!(msg.contains("`irrigation`"), "{msg}"),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn split_rules_divert_within_the_inflow() {
+    assert_eq!(SplitRule::Ratio(0.25).diverted(8.0), 2.0);
+    let threshold = SplitRule::Threshold {
+        threshold: 10.0,
+        capacity: Some(5.0),
+    };
+    assert_eq!(threshold.diverted(8.0), 0.0);
+    assert_eq!(threshold.diverted(12.0), 2.0);
+    assert_eq!(threshold.diverted(30.0), 5.0);
+    let table = SplitRule::Table(vec![0.0, 10.0, 20.0], vec![0.0, 2.0, 12.0]);
+    assert_eq!(table.diverted(5.0), 1.0);
+    assert_eq!(table.diverted(15.0), 7.0);
+    // Tables never divert more than the inflow.
+    let steep = SplitRule::Table(vec![0.0, 1.0], vec![0.0, 5.0]);
+    assert_eq!(steep.diverted(0.5), 0.5);
+    assert_eq!(SplitRule::Ratio(0.5).diverted(-1.0), 0.0);
+}
+
+const BIFURCATION: &str = r#"
+[[nodes]]
+id = "split"
+type = "bifurcation"
+threshold = 10.0
+capacity = 6.0
+divert_to = "bypass"
+inflow = "q"
+downstream = "main"
+
+[[nodes]]
+id = "main"
+type = "muskingum"
+k = 3600.0
+x = 0.2
+downstream = "outlet"
+
+[[nodes]]
+id = "bypass"
+type = "junction"
+downstream = "outlet"
+
+[[nodes]]
+id = "outlet"
+type = "junction"
+"#;
+
+#[test]
+fn bifurcations_divert_to_a_second_node() {
+    let mut net = network(BIFURCATION).unwrap();
+    let q = flood(40, 4.0, 30.0, 5);
+    let result = net.run(&series(&[("q", q.clone())]), DT).unwrap();
+    let (split, main, bypass, outlet) = (
+        index(&result, "split"),
+        index(&result, "main"),
+        index(&result, "bypass"),
+        index(&result, "outlet"),
+    );
+    for (t, &q) in q.iter().enumerate() {
+        let diverted = (q - 10.0).clamp(0.0, 6.0);
+        assert!((result.diversion[split][t] - diverted).abs() < 1e-12);
+        assert!((result.outflow[split][t] - (q - diverted)).abs() < 1e-12);
+        assert_eq!(result.inflow[bypass][t], result.diversion[split][t]);
+        assert_eq!(result.inflow[main][t], result.outflow[split][t]);
+        let arriving = result.outflow[main][t] + result.outflow[bypass][t];
+        assert!((result.inflow[outlet][t] - arriving).abs() < 1e-12);
+    }
+    assert!(storage_residual(&result, main, DT) < 1e-12);
+}
+
+#[test]
+fn rejects_invalid_bifurcations() {
+    for (from, to, expected) in [
+        (
+            "divert_to = \"bypass\"",
+            "divert_to = \"canal\"",
+            "diverts to unknown node `canal`",
+        ),
+        (
+            "threshold = 10.0\ncapacity = 6.0",
+            "ratio = 1.5",
+            "`ratio` must be within [0, 1]",
+        ),
+        (
+            "threshold = 10.0\ncapacity = 6.0",
+            "table_inflow = [0.0, 0.0]\ntable_diverted = [0.0, 1.0]",
+            "strictly increasing",
+        ),
+        (
+            "threshold = 10.0\ncapacity = 6.0",
+            "table_inflow = [0.0, 5.0]\ntable_diverted = [0.0]",
+            "at least two values",
+        ),
+        (
+            "threshold = 10.0\ncapacity = 6.0",
+            "",
+            "needs `ratio`, `threshold`",
+        ),
+        ("divert_to = \"bypass\"\n", "", "field `divert_to`"),
+    ] {
+        assert!(BIFURCATION.contains(from), "{from}");
+        match network(&BIFURCATION.replacen(from, to, 1)) {
+            Err(Error::InvalidParameter(msg) | Error::Data(msg)) => {
+                assert!(msg.contains(expected), "{msg}")
+            }
+            other => panic!("{to:?}: {other:?}"),
+        }
+    }
+    // A diversion back upstream closes a loop.
+    let looped = BIFURCATION.replace("divert_to = \"bypass\"", "divert_to = \"split\"");
+    assert!(matches!(network(&looped), Err(Error::Data(msg)) if msg.contains("cycle")));
+}
+
+const LOSING: &str = r#"
+[[nodes]]
+id = "sink"
+type = "junction"
+loss = 1.5
+loss_fraction = 0.1
+inflow = "q"
+downstream = "outlet"
+
+[[nodes]]
+id = "outlet"
+type = "junction"
+"#;
+
+#[test]
+fn junction_losses_are_limited_by_the_inflow() {
+    let mut net = network(LOSING).unwrap();
+    let q = vec![0.0, 1.0, 10.0, 40.0, -2.0];
+    let result = net.run(&series(&[("q", q.clone())]), DT).unwrap();
+    let (sink, outlet) = (index(&result, "sink"), index(&result, "outlet"));
+    for (t, &q) in q.iter().enumerate() {
+        let lost = (1.5 + 0.1 * q).clamp(0.0, q.max(0.0));
+        assert!((result.loss[sink][t] - lost).abs() < 1e-12, "{t}");
+        assert!((result.outflow[sink][t] - (q - lost)).abs() < 1e-12, "{t}");
+        assert_eq!(result.inflow[outlet][t], result.outflow[sink][t]);
+        assert_eq!(result.loss[outlet][t], 0.0);
+    }
+    // A small flow is lost entirely.
+    assert_eq!(result.outflow[sink][1], 0.0);
+}
+
+#[test]
+fn rejects_invalid_junction_losses() {
+    for (from, to) in [
+        ("loss = 1.5", "loss = -1.0"),
+        ("loss_fraction = 0.1", "loss_fraction = 1.2"),
+        ("loss_fraction = 0.1", "loss_fraction = -0.1"),
+    ] {
+        match network(&LOSING.replace(from, to)) {
+            Err(Error::InvalidParameter(msg) | Error::Data(msg)) => {
+                assert!(msg.contains("`loss_fraction` within [0, 1]"), "{msg}")
+            }
+            other => panic!("{to}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn transmission_losses_leave_the_routed_flow() {
+    let text = r#"
+[[nodes]]
+id = "wash"
+type = "muskingum"
+k = 3600.0
+x = 0.2
+transmission_loss = "fraction"
+loss_fraction = 0.3
+inflow = "q"
+downstream = "outlet"
+
+[[nodes]]
+id = "outlet"
+type = "junction"
+"#;
+    let mut net = network(text).unwrap();
+    let q = flood(30, 1.0, 12.0, 4);
+    let result = net.run(&series(&[("q", q.clone())]), DT).unwrap();
+    let routed = muskingum_cascade_rs(&q, DT, 3600.0, 0.2, 1, None);
+    let (wash, outlet) = (index(&result, "wash"), index(&result, "outlet"));
+    for (t, &routed) in routed.iter().enumerate() {
+        assert!((result.loss[wash][t] - 0.3 * routed).abs() < 1e-12);
+        assert!((result.outflow[wash][t] - 0.7 * routed).abs() < 1e-12);
+        assert_eq!(result.inflow[outlet][t], result.outflow[wash][t]);
+    }
+    let unknown = text.replace("\"fraction\"", "\"wick\"");
+    assert!(matches!(network(&unknown), Err(Error::Data(msg)) if msg.contains("`wick`")));
+}
+
+#[test]
+fn series_link_nodes_in_order() {
+    let listed = CONFLUENCE
+        .replace("[[nodes]]", "[[series]]")
+        .replace("downstream = \"confluence\"\n", "")
+        .replace("downstream = \"dam\"\n", "");
+    let mut net = network(&listed).unwrap();
+    let ids: Vec<_> = net.nodes().iter().map(|n| n.downstream.clone()).collect();
+    assert_eq!(
+        ids,
+        [
+            Some("tributary".to_string()),
+            Some("confluence".to_string()),
+            Some("dam".to_string()),
+            None
+        ]
+    );
+    // The same chain written with explicit links runs identically.
+    let chain = CONFLUENCE.replacen(
+        "downstream = \"confluence\"",
+        "downstream = \"tributary\"",
+        1,
+    );
+    let mut chained = network(&chain).unwrap();
+    let inputs = confluence_inputs();
+    assert_same(
+        &net.run(&inputs, DT).unwrap(),
+        &chained.run(&inputs, DT).unwrap(),
+    );
+
+    let with_link = CONFLUENCE.replace("[[nodes]]", "[[series]]");
+    assert!(
+        matches!(network(&with_link), Err(Error::Data(msg)) if msg.contains("linked in order"))
+    );
+    let both = format!("{CONFLUENCE}\n[[series]]\nid = \"extra\"\ntype = \"junction\"\n");
+    assert!(matches!(network(&both), Err(Error::Data(msg)) if msg.contains("either")));
+}
+
+const DAMS: &str = r#"
+[[series]]
+id = "upper"
+type = "reservoir"
+elevation = [100.0, 101.0, 102.0, 103.0]
+storage = [0.0, 100000.0, 250000.0, 500000.0]
+outflow = [0.0, 5.0, 20.0, 50.0]
+initial_elevation = 100.5
+inflow = "q"
+tailwater = [90.0, 92.0]
+tailwater_outflow = [[0.0, 5.0, 20.0, 50.0], [0.0, 2.0, 8.0, 20.0]]
+tailwater_discharge = [0.0, 50.0]
+tailwater_stage = [90.0, 92.0]
+
+[[series]]
+id = "lower"
+type = "reservoir"
+elevation = [88.0, 90.0, 92.0, 94.0]
+storage = [0.0, 200000.0, 500000.0, 900000.0]
+outflow = [0.0, 2.0, 10.0, 40.0]
+initial_elevation = 89.0
+"#;
+
+#[test]
+fn tailwater_ratings_throttle_the_outlet() {
+    let q = flood(60, 2.0, 40.0, 6);
+    let inputs = series(&[("q", q)]);
+    let mut net = network(DAMS).unwrap();
+    let result = net.run(&inputs, DT).unwrap();
+    let (upper, lower) = (index(&result, "upper"), index(&result, "lower"));
+    for t in 0..60 {
+        // Converged levels follow the rating at the released flow.
+        let level = interp(result.outflow[upper][t], &[0.0, 50.0], &[90.0, 92.0]);
+        assert!((result.tailwater[upper][t] - level).abs() < 1e-5, "{t}");
+        assert!(result.tailwater[lower][t].is_nan());
+    }
+    assert!(storage_residual(&result, upper, DT) < 1e-9);
+    assert!(storage_residual(&result, lower, DT) < 1e-9);
+
+    // A single row is the plain outflow table.
+    let free = DAMS
+        .replace(", [0.0, 2.0, 8.0, 20.0]", "")
+        .replace("tailwater = [90.0, 92.0]", "tailwater = [90.0]");
+    let plain = DAMS.replace(
+        "tailwater = [90.0, 92.0]\ntailwater_outflow = [[0.0, 5.0, 20.0, 50.0], [0.0, 2.0, 8.0, 20.0]]\ntailwater_discharge = [0.0, 50.0]\ntailwater_stage = [90.0, 92.0]\n",
+        "",
+    );
+    let free = network(&free).unwrap().run(&inputs, DT).unwrap();
+    let plain = network(&plain).unwrap().run(&inputs, DT).unwrap();
+    assert_eq!(free.outflow, plain.outflow);
+    let peak = |s: &[f64]| s.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    assert!(peak(&result.outflow[upper]) < peak(&free.outflow[upper]));
+    assert!(peak(&result.stage[upper]) > peak(&free.stage[upper]));
+}
+
+#[test]
+fn tailwater_follows_the_downstream_pool() {
+    let coupled = DAMS.replace(
+        "tailwater_discharge = [0.0, 50.0]\ntailwater_stage = [90.0, 92.0]\n",
+        "",
+    );
+    let mut net = network(&coupled).unwrap();
+    let result = net
+        .run(&series(&[("q", flood(60, 2.0, 40.0, 6))]), DT)
+        .unwrap();
+    let (upper, lower) = (index(&result, "upper"), index(&result, "lower"));
+    for t in 0..60 {
+        let pool = result.stage[lower][t];
+        assert!((result.tailwater[upper][t] - pool).abs() < 1e-5, "{t}");
+    }
+    assert!(storage_residual(&result, upper, DT) < 1e-9);
+    assert!(storage_residual(&result, lower, DT) < 1e-9);
+}
+
+#[test]
+fn rejects_invalid_tailwater_outlets() {
+    let rating = "tailwater_discharge = [0.0, 50.0]\ntailwater_stage = [90.0, 92.0]\n";
+    for (from, to, expected) in [
+        (
+            "tailwater = [90.0, 92.0]",
+            "tailwater = [92.0, 90.0]",
+            "strictly increasing",
+        ),
+        (
+            "tailwater = [90.0, 92.0]",
+            "tailwater = [90.0, 91.0, 92.0]",
+            "one row for each",
+        ),
+        (
+            "[0.0, 2.0, 8.0, 20.0]]",
+            "[0.0, 2.0, 8.0]]",
+            "needs 4 values",
+        ),
+        (
+            "[0.0, 2.0, 8.0, 20.0]]",
+            "[0.0, 2.0, 8.0, 1.0]]",
+            "non-decreasing",
+        ),
+        (
+            "tailwater_outflow = [[0.0, 5.0, 20.0, 50.0], [0.0, 2.0, 8.0, 20.0]]",
+            "tailwater_outflow = [1.0, 2.0]",
+            "array of arrays",
+        ),
+        (
+            "tailwater_stage = [90.0, 92.0]",
+            "tailwater_stage = [90.0]",
+            "equal length",
+        ),
+        (
+            "tailwater_discharge = [0.0, 50.0]",
+            "tailwater_discharge = [50.0, 0.0]",
+            "strictly increasing",
+        ),
+    ] {
+        assert!(DAMS.contains(from), "{from}");
+        match network(&DAMS.replacen(from, to, 1)) {
+            Err(Error::InvalidParameter(msg) | Error::Data(msg)) => {
+                assert!(msg.contains(expected), "{msg}")
+            }
+            other => panic!("{to:?}: {other:?}"),
+        }
+    }
+    // Without a rating the tailwater is the pool below, which must exist.
+    let channel = DAMS.replace(
+        "id = \"lower\"\ntype = \"reservoir\"",
+        "id = \"lower\"\ntype = \"junction\"",
+    );
+    assert!(network(&channel).is_ok());
+    assert!(matches!(
+        network(&channel.replace(rating, "")),
+        Err(Error::Data(msg)) if msg.contains("does not drain to a reservoir")
+    ));
+    let junction = format!(
+        "[[series]]\nid = \"j\"\ntype = \"junction\"\ntailwater = [1.0]\n\
+         tailwater_outflow = [[0.0]]\n{rating}"
+    );
+    assert!(matches!(network(&junction), Err(Error::Data(msg)) if msg.contains("not a reservoir")));
+}
+
+const STEEP: &str = r#"
+[[nodes]]
+id = "reach"
+type = "muskingum"
+k = 3600.0
+x = 0.0
+inflow = "q"
+"#;
+
+fn adaptive(tolerance: f64, max_refinement: u32, max_coarsening: u32) -> AdaptiveStep {
+    AdaptiveStep {
+        tolerance,
+        max_refinement,
+        max_coarsening,
+    }
+}
+
+#[test]
+fn fixed_steps_match_plain_runs() {
+    let inputs = confluence_inputs();
+    let plain = network(CONFLUENCE).unwrap().run(&inputs, DT).unwrap();
+    for step in [AdaptiveStep::FIXED, adaptive(0.01, 0, 0)] {
+        let mut net = network(CONFLUENCE).unwrap();
+        assert_same(
+            &net.run_adaptive(&inputs, DT, &step, &Silent).unwrap(),
+            &plain,
+        );
+    }
+}
+
+#[test]
+fn refined_steps_approach_a_fine_run() {
+    let q = flood(24, 2.0, 60.0, 2);
+    let inputs = series(&[("q", q.clone())]);
+    let refined = network(STEEP)
+        .unwrap()
+        .run_adaptive(&inputs, 3600.0, &adaptive(0.02, 3, 0), &Silent)
+        .unwrap();
+    let coarse = network(STEEP).unwrap().run(&inputs, 3600.0).unwrap();
+    // Inflows are interpolated linearly within each interval.
+    let fine_q: Vec<f64> = (0..24 * 8)
+        .map(|j| {
+            let (t, k) = (j / 8, (j % 8 + 1) as f64 / 8.0);
+            if t == 0 {
+                q[0]
+            } else {
+                q[t - 1] + (q[t] - q[t - 1]) * k
+            }
+        })
+        .collect();
+    let fine = network(STEEP)
+        .unwrap()
+        .run(&series(&[("q", fine_q)]), 450.0)
+        .unwrap();
+    let error = |result: &NetworkResult| {
+        (0..24)
+            .map(|t| (result.outflow[0][t] - fine.outflow[0][8 * t + 7]).abs())
+            .fold(0.0, f64::max)
+    };
+    assert_eq!(refined.outflow[0].len(), 24);
+    assert!(error(&refined) < 0.01 * error(&coarse));
+}
+
+#[test]
+fn coarse_steps_keep_steady_flows() {
+    let inputs = series(&[("q", vec![7.0; 37])]);
+    let result = network(STEEP)
+        .unwrap()
+        .run_adaptive(&inputs, 3600.0, &adaptive(0.05, 2, 3), &Silent)
+        .unwrap();
+    for (name, values) in result.series() {
+        assert!(values.iter().all(|v| v.len() == 37), "{name}");
+    }
+    assert!(result.outflow[0].iter().all(|&q| (q - 7.0).abs() < 1e-9));
+}
+
+#[test]
+fn steps_are_not_refined_below_the_c0_limit() {
+    // `2 K x` is 2880 s, so halving the 3600 s interval would make `c0`
+    // negative.
+    let text = STEEP
+        .replace("x = 0.0", "x = 0.2")
+        .replace("3600.0", "7200.0");
+    let inputs = series(&[("q", flood(24, 2.0, 60.0, 2))]);
+    let fixed = network(&text).unwrap().run(&inputs, 3600.0).unwrap();
+    let limited = network(&text)
+        .unwrap()
+        .run_adaptive(&inputs, 3600.0, &adaptive(1e-6, 4, 0), &Silent)
+        .unwrap();
+    assert_same(&limited, &fixed);
+    // Nodes that allow a negative `c0` do not limit refinement.
+    let allowed = format!("{text}allow_negative_c0 = true\n");
+    let refined = network(&allowed)
+        .unwrap()
+        .run_adaptive(&inputs, 3600.0, &adaptive(1e-6, 4, 0), &Silent)
+        .unwrap();
+    assert_ne!(refined.outflow, fixed.outflow);
+}
+
+#[test]
+fn rejects_invalid_adaptive_steps() {
+    let inputs = confluence_inputs();
+    for step in [
+        adaptive(0.0, 2, 2),
+        adaptive(f64::NAN, 2, 2),
+        adaptive(0.1, 17, 0),
+        adaptive(0.1, 0, 17),
+    ] {
+        let mut net = network(CONFLUENCE).unwrap();
+        assert!(matches!(
+            net.run_adaptive(&inputs, DT, &step, &Silent),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+    let table = |text: &str| AdaptiveStep::from_value(&toml::parse(text).unwrap());
+    assert_eq!(table("x = 1").unwrap(), None);
+    assert_eq!(
+        table("[adaptive_step]\nmax_coarsening = 1").unwrap(),
+        Some(adaptive(0.05, 4, 1))
+    );
+    assert!(table("[adaptive_step]\ntolerance = -0.1").is_err());
+    assert!(table("[adaptive_step]\ntolerance = \"tight\"").is_err());
+}
+
+/// Steps `steps` of every input series, such as those after a restart.
+fn window(
+    inputs: &BTreeMap<String, Vec<f64>>,
+    steps: std::ops::Range<usize>,
+) -> BTreeMap<String, Vec<f64>> {
+    inputs
+        .iter()
+        .map(|(name, values)| (name.clone(), values[steps.clone()].to_vec()))
+        .collect()
+}
+
+/// Asserts that `restarted` holds bit-identical values to the steps of
+/// `full` from `from` on.
+fn assert_continues(full: &NetworkResult, restarted: &NetworkResult, from: usize) {
+    assert_eq!(full.node_ids, restarted.node_ids);
+    for ((name, x), (_, y)) in full.series().into_iter().zip(restarted.series()) {
+        for (a, b) in x.iter().zip(y) {
+            let bits = |s: &[f64]| s.iter().map(|f| f.to_bits()).collect::<Vec<_>>();
+            assert_eq!(bits(&a[from..]), bits(b), "{name}");
+        }
+    }
+}
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("rustflow-{name}-{}.json", std::process::id()))
+}
+
+#[test]
+fn restarts_continue_the_run() {
+    let dams = DAMS.replace(
+        "tailwater_discharge = [0.0, 50.0]\ntailwater_stage = [90.0, 92.0]\n",
+        "",
+    );
+    for (text, inputs) in [
+        (CONFLUENCE, confluence_inputs()),
+        (dams.as_str(), series(&[("q", flood(48, 2.0, 40.0, 6))])),
+    ] {
+        let full = network(text).unwrap().run(&inputs, DT).unwrap();
+        let mut first = network(text).unwrap();
+        first.run(&window(&inputs, 0..20), DT).unwrap();
+        let state = first.state();
+        assert_eq!(state.get("steps").and_then(Value::as_f64), Some(20.0));
+
+        let mut second = network(text).unwrap();
+        second.set_state(&state).unwrap();
+        let restarted = second.run(&window(&inputs, 20..48), DT).unwrap();
+        assert_continues(&full, &restarted, 20);
+        // The count of completed steps includes those before the restart.
+        assert_eq!(
+            second.state().get("steps").and_then(Value::as_f64),
+            Some(48.0)
+        );
+        // Without a restored state the next run starts afresh.
+        let again = second.run(&inputs, DT).unwrap();
+        assert_same(&again, &full);
+    }
+}
+
+#[test]
+fn saved_states_and_checkpoints_restart_runs() {
+    let inputs = confluence_inputs();
+    let full = network(CONFLUENCE).unwrap().run(&inputs, DT).unwrap();
+    let path = temp_path("checkpoint");
+    let mut net = network(CONFLUENCE).unwrap();
+    net.set_checkpoint(Some(Checkpoint {
+        path: path.clone(),
+        every: 7,
+    }))
+    .unwrap();
+    net.run(&inputs, DT).unwrap();
+
+    // The last checkpoint was written after 42 of the 48 steps.
+    let mut restarted = network(CONFLUENCE).unwrap();
+    assert_eq!(restarted.load_state(&path).unwrap(), 42);
+    let rest = restarted.run(&window(&inputs, 42..48), DT).unwrap();
+    assert_continues(&full, &rest, 42);
+
+    net.save_state(&path).unwrap();
+    let mut finished = network(CONFLUENCE).unwrap();
+    assert_eq!(finished.load_state(&path).unwrap(), 48);
+    assert_eq!(finished.state().get("nodes"), net.state().get("nodes"));
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(finished.load_state(&path), Err(Error::Io(_))));
+
+    assert!(net
+        .set_checkpoint(Some(Checkpoint {
+            path: path.clone(),
+            every: 0,
+        }))
+        .is_err());
+    let config = |text: &str| Checkpoint::from_value(&toml::parse(text).unwrap());
+    assert_eq!(config("x = 1").unwrap(), None);
+    assert_eq!(
+        config("[checkpoint]\npath = \"run.json\"").unwrap(),
+        Some(Checkpoint {
+            path: "run.json".into(),
+            every: 1,
+        })
+    );
+    assert!(config("[checkpoint]\nevery = 2").is_err());
+}
+
+#[test]
+fn rejects_mismatched_states() {
+    let inputs = confluence_inputs();
+    let mut net = network(CONFLUENCE).unwrap();
+    net.run(&window(&inputs, 0..10), DT).unwrap();
+    let state = net.state();
+
+    let mut version = state.clone();
+    version.insert("version", 2.0.into());
+    let mut missing = state.clone();
+    let mut nodes = state.get("nodes").unwrap().clone();
+    if let Value::Object(entries) = &mut nodes {
+        entries.retain(|(id, _)| id != "dam");
+    }
+    missing.insert("nodes", nodes);
+    let mut sizes = state.clone();
+    let mut nodes = state.get("nodes").unwrap().clone();
+    let mut upper = nodes.get("upper").unwrap().clone();
+    let mut element = upper.get("element").unwrap().clone();
+    element.insert("inflow", [1.0, 2.0, 3.0].as_slice().into());
+    element.insert("outflow", [1.0, 2.0, 3.0].as_slice().into());
+    upper.insert("element", element);
+    nodes.insert("upper", upper);
+    sizes.insert("nodes", nodes);
+
+    let fresh = network(CONFLUENCE).unwrap().run(&inputs, DT).unwrap();
+    for (state, expected) in [
+        (version, "`version`"),
+        (missing, "no node `dam`"),
+        (sizes, "State of node `upper`"),
+        (Value::Null, "`version`"),
+    ] {
+        let mut other = network(CONFLUENCE).unwrap();
+        match other.set_state(&state) {
+            Err(Error::Data(msg)) => assert!(msg.contains(expected), "{msg}"),
+            result => panic!("{expected}: {result:?}"),
+        }
+        // A rejected state leaves the network untouched.
+        assert_same(&other.run(&inputs, DT).unwrap(), &fresh);
+    }
+}
+
+const CELLS: &str = r#"
+[[nodes]]
+id = "floodplain"
+type = "flood_cells"
+inflow = "q"
+channel_discharge = [0.0, 100.0]
+channel_stage = [8.0, 9.0]
+cells = [
+    { id = "north", elevation = [10.0, 12.0], storage = [0.0, 200000.0], initial_elevation = 11.5 },
+    { id = "south", elevation = [10.0, 12.0], storage = [0.0, 100000.0], initial_elevation = 11.5 },
+]
+links = [
+    { from = "north", to = "south", type = "weir", crest = 10.2, length = 5.0 },
+    { from = "north", to = "river", type = "weir", crest = 13.0, length = 20.0 },
+]
+"#;
+
+/// Stored volume (m³) of a cell of [`CELLS`] with `capacity` at 12 m.
+fn cell_volume(capacity: f64, level: f64) -> f64 {
+    capacity * (level - 10.0) / 2.0
+}
+
+#[test]
+fn cells_at_equal_levels_exchange_no_flow() {
+    let mut net = network(CELLS).unwrap();
+    let result = net.run(&series(&[("q", vec![20.0; 24])]), DT).unwrap();
+    let initial = cell_volume(200000.0, 11.5) + cell_volume(100000.0, 11.5);
+    for t in 0..24 {
+        assert_eq!(result.outflow[0][t], 20.0);
+        assert!((result.storage[0][t] - initial).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn flow_between_cells_conserves_mass() {
+    let text = CELLS.replacen("initial_elevation = 11.5", "initial_elevation = 11.8", 1);
+    let mut net = network(&text).unwrap();
+    let result = net.run(&series(&[("q", vec![20.0; 48])]), DT).unwrap();
+    let initial = cell_volume(200000.0, 11.8) + cell_volume(100000.0, 11.5);
+    // Nothing reaches the river, so the volume only moves between cells.
+    for t in 0..48 {
+        assert!(
+            (result.storage[0][t] - initial).abs() < 1e-6 * initial,
+            "{t}"
+        );
+        assert!((result.outflow[0][t] - 20.0).abs() < 1e-9);
+    }
+    // The levels meet where both cells share the volume.
+    let level = 10.0 + 2.0 * initial / 300000.0;
+    let Element::FloodCells(cells) = &net.nodes()[0].element else {
+        unreachable!()
+    };
+    for z in cells.levels() {
+        assert!((z - level).abs() < 1e-3, "{z} != {level}");
+    }
+}
+
+#[test]
+fn a_single_cell_drains_to_its_weir_crest() {
+    let text = r#"
+[[nodes]]
+id = "pond"
+type = "flood_cells"
+inflow = "q"
+channel_discharge = [0.0, 100.0]
+channel_stage = [8.0, 9.0]
+cells = [{ id = "pond", elevation = [10.0, 12.0], storage = [0.0, 200000.0], initial_elevation = 11.0 }]
+links = [{ from = "pond", to = "river", type = "weir", crest = 10.5, length = 10.0 }]
+"#;
+    let mut net = network(text).unwrap();
+    let n = 480;
+    let result = net.run(&series(&[("q", vec![5.0; n])]), DT).unwrap();
+    let crest = cell_volume(200000.0, 10.5);
+    let mut released = 0.0;
+    let mut last = cell_volume(200000.0, 11.0);
+    for t in 0..n {
+        let storage = result.storage[0][t];
+        assert!(storage <= last && storage >= crest - 1e-6, "{t}: {storage}");
+        released += (result.outflow[0][t] - 5.0) * DT;
+        assert!((last - storage - (result.outflow[0][t] - 5.0) * DT).abs() < 1e-6);
+        last = storage;
+    }
+    assert!(last - crest < 0.01 * crest, "{last} vs {crest}");
+    assert!((released - (cell_volume(200000.0, 11.0) - last)).abs() < 1e-6);
+}
+
+#[test]
+fn rejects_invalid_flood_cells() {
+    for (from, to, expected) in [
+        ("to = \"south\"", "to = \"east\"", "unknown cell `east`"),
+        ("to = \"south\"", "to = \"north\"", "two different cells"),
+        (
+            "type = \"weir\", crest = 10.2",
+            "type = \"gate\", crest = 10.2",
+            "`gate`",
+        ),
+        ("length = 5.0", "length = 0.0", "positive `length`"),
+        (
+            "channel_stage = [8.0, 9.0]",
+            "channel_stage = [8.0]",
+            "channel rating",
+        ),
+        (
+            "storage = [0.0, 100000.0]",
+            "storage = [0.0, 0.0]",
+            "strictly increasing",
+        ),
+    ] {
+        assert!(CELLS.contains(from), "{from}");
+        match network(&CELLS.replacen(from, to, 1)) {
+            Err(Error::InvalidParameter(msg) | Error::Data(msg)) => {
+                assert!(msg.contains(expected), "{msg}")
+            }
+            other => panic!("{to}: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Scoped-thread parallel map used by the batch routines.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+/// Number of worker threads: `RUSTFLOW_NUM_THREADS` if set, otherwise the
+/// available parallelism.
+pub fn num_threads() -> usize {
+    std::env::var("RUSTFLOW_NUM_THREADS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+}
+
+/// Applies `f` to every item on a pool of scoped threads, returning results
+/// in input order. Items are split into contiguous chunks, one per thread.
+/// Each result depends only on its item, so the output is the same for any
+/// number of threads.
+pub fn par_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = num_threads().min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let chunk = items.len().div_ceil(threads);
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk)
+            .map(|part| scope.spawn(move || part.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
@@ -0,0 +1,208 @@
+//! Basin-average rainfall from point gauges.
+//!
+//! Thiessen and inverse-distance weights are computed by sampling the basin
+//! polygon on a regular grid: each sample point is assigned to its nearest
+//! gauge (Thiessen) or shared among all gauges in proportion to
+//! `1 / distance^power` (IDW), and a gauge's weight is its share of the
+//! sample points. When gauges are missing (NaN) at a step, the weights are
+//! recomputed over the gauges that reported. Coordinates are planar (e.g.
+//! projected metres).
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+
+/// How gauge weights are obtained.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArealMethod {
+    /// Nearest-gauge (Voronoi) areas within the basin.
+    Thiessen,
+    /// Inverse-distance weighting averaged over the basin.
+    InverseDistance { power: f64 },
+    /// Fixed user weights, one per gauge, renormalised over the gauges
+    /// that reported.
+    Weights(Vec<f64>),
+}
+
+impl ArealMethod {
+    pub fn parse(name: &str, power: f64, weights: Option<Vec<f64>>) -> Result<Self> {
+        match (name.to_ascii_lowercase().as_str(), weights) {
+            ("thiessen", None) => Ok(ArealMethod::Thiessen),
+            ("idw", None) => Ok(ArealMethod::InverseDistance { power }),
+            ("weights", Some(weights)) => Ok(ArealMethod::Weights(weights)),
+            ("weights", None) => Err(Error::InvalidParameter(
+                "Method `weights` requires gauge weights.".into(),
+            )),
+            (_, Some(_)) => Err(Error::InvalidParameter(
+                "Gauge weights are only used by method `weights`.".into(),
+            )),
+            (other, None) => Err(Error::InvalidParameter(format!(
+                "Unknown areal method `{other}`; expected `thiessen`, `idw` or `weights`."
+            ))),
+        }
+    }
+}
+
+/// Whether `(x, y)` lies inside `polygon` (even–odd rule).
+pub fn contains(polygon: &[(f64, f64)], (x, y): (f64, f64)) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let ((xi, yi), (xj, yj)) = (polygon[i], polygon[j]);
+        if (yi > y) != (yj > y) && x < xi + (y - yi) * (xj - xi) / (yj - yi) {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Centres of a regular grid of about `resolution` cells along the longer
+/// side of the polygon's bounding box that fall inside the polygon.
+pub fn sample_points(polygon: &[(f64, f64)], resolution: usize) -> Result<Vec<(f64, f64)>> {
+    if polygon.len() < 3 || resolution == 0 {
+        return Err(Error::InvalidParameter(
+            "The basin polygon needs at least three vertices and a positive resolution.".into(),
+        ));
+    }
+    let (mut x0, mut y0, mut x1, mut y1) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for &(x, y) in polygon {
+        (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x), y1.max(y));
+    }
+    let cell = (x1 - x0).max(y1 - y0) / resolution as f64;
+    if cell <= 0.0 || !cell.is_finite() {
+        return Err(Error::InvalidParameter(
+            "The basin polygon has no extent.".into(),
+        ));
+    }
+    let (nx, ny) = (
+        ((x1 - x0) / cell).ceil() as usize,
+        ((y1 - y0) / cell).ceil() as usize,
+    );
+    let points: Vec<(f64, f64)> = (0..ny)
+        .flat_map(|j| (0..nx).map(move |i| (i, j)))
+        .map(|(i, j)| (x0 + (i as f64 + 0.5) * cell, y0 + (j as f64 + 0.5) * cell))
+        .filter(|&p| contains(polygon, p))
+        .collect();
+    if points.is_empty() {
+        return Err(Error::InvalidParameter(
+            "No sample points fall inside the basin polygon; increase the resolution.".into(),
+        ));
+    }
+    Ok(points)
+}
+
+/// Basin weights of the gauges at `gauges` flagged in `available`, summing
+/// to one (zero for unavailable gauges).
+pub fn gauge_weights(
+    method: &ArealMethod,
+    gauges: &[(f64, f64)],
+    available: &[bool],
+    points: &[(f64, f64)],
+) -> Vec<f64> {
+    let mut weights = vec![0.0; gauges.len()];
+    let active: Vec<usize> = (0..gauges.len()).filter(|&g| available[g]).collect();
+    if active.is_empty() {
+        return weights;
+    }
+    let distance2 = |g: usize, (x, y): (f64, f64)| {
+        let (gx, gy) = gauges[g];
+        (gx - x).powi(2) + (gy - y).powi(2)
+    };
+    match method {
+        ArealMethod::Thiessen => {
+            for &p in points {
+                let nearest = active
+                    .iter()
+                    .copied()
+                    .min_by(|&a, &b| distance2(a, p).total_cmp(&distance2(b, p)))
+                    .unwrap_or(active[0]);
+                weights[nearest] += 1.0;
+            }
+        }
+        ArealMethod::InverseDistance { power } => {
+            for &p in points {
+                // A sample point on a gauge takes that gauge's value.
+                if let Some(&g) = active.iter().find(|&&g| distance2(g, p) == 0.0) {
+                    weights[g] += 1.0;
+                    continue;
+                }
+                let inverse: Vec<f64> = active
+                    .iter()
+                    .map(|&g| distance2(g, p).powf(-0.5 * power))
+                    .collect();
+                let total: f64 = inverse.iter().sum();
+                for (&g, w) in active.iter().zip(inverse) {
+                    weights[g] += w / total;
+                }
+            }
+        }
+        ArealMethod::Weights(user) => {
+            for &g in &active {
+                weights[g] = user[g];
+            }
+        }
+    }
+    let total: f64 = weights.iter().sum();
+    if total > 0.0 {
+        weights.iter_mut().for_each(|w| *w /= total);
+    }
+    weights
+}
+
+/// Basin-average series of the gauge `series` at `gauges` within `basin`,
+/// and the weights with every gauge reporting. Steps where no gauge
+/// reported are NaN.
+pub fn areal_average_rs(
+    series: &[Vec<f64>],
+    gauges: &[(f64, f64)],
+    basin: &[(f64, f64)],
+    method: &ArealMethod,
+    resolution: usize,
+) -> Result<(Vec<f64>, Vec<f64>)> {
+    let n_steps = series.first().map_or(0, Vec::len);
+    if series.len() != gauges.len() || series.iter().any(|s| s.len() != n_steps) {
+        return Err(Error::Data(
+            "Every gauge needs a location and series of equal length.".into(),
+        ));
+    }
+    match method {
+        ArealMethod::InverseDistance { power } if *power <= 0.0 => {
+            return Err(Error::InvalidParameter(
+                "The IDW power must be positive.".into(),
+            ))
+        }
+        ArealMethod::Weights(w) if w.len() != gauges.len() || w.iter().any(|&w| w < 0.0) => {
+            return Err(Error::InvalidParameter(
+                "Give one non-negative weight per gauge.".into(),
+            ))
+        }
+        _ => {}
+    }
+    let points = match method {
+        ArealMethod::Weights(_) => Vec::new(),
+        _ => sample_points(basin, resolution)?,
+    };
+    let all = vec![true; gauges.len()];
+    let full = gauge_weights(method, gauges, &all, &points);
+    let mut cache: HashMap<Vec<bool>, Vec<f64>> = HashMap::new();
+    cache.insert(all, full.clone());
+    let average = (0..n_steps)
+        .map(|t| {
+            let available: Vec<bool> = series.iter().map(|s| !s[t].is_nan()).collect();
+            if !available.contains(&true) {
+                return f64::NAN;
+            }
+            let weights = cache
+                .entry(available)
+                .or_insert_with_key(|available| gauge_weights(method, gauges, available, &points));
+            series
+                .iter()
+                .zip(weights.iter())
+                .filter(|(_, &w)| w > 0.0)
+                .map(|(s, w)| s[t] * w)
+                .sum()
+        })
+        .collect();
+    Ok((average, full))
+}
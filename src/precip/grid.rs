@@ -0,0 +1,246 @@
+//! Catchment-average forcing from gridded fields.
+//!
+//! Each catchment polygon is overlaid on the grid cells and every cell is
+//! weighted by the area of its overlap with the polygon, found by clipping
+//! the polygon's rings to the cell. The lumped series is then the
+//! weighted mean of the cells at each step, over the cells that are not
+//! NaN at that step. Steps are processed in parallel.
+//!
+//! Polygons follow the GeoJSON layout: a catchment is a list of parts,
+//! each a list of rings, the first ring the exterior and the others holes.
+//! On a geographic (longitude–latitude) grid, overlap areas are scaled by
+//! the cosine of the cell latitude and reported in square metres.
+
+use crate::error::{Error, Result};
+use crate::parallel::{num_threads, par_map};
+
+/// Mean Earth radius (m).
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// A polygon ring of `(x, y)` vertices; the closing vertex may be repeated.
+pub type Ring = Vec<(f64, f64)>;
+
+/// A catchment: parts, each an exterior ring followed by its holes.
+pub type MultiPolygon = Vec<Vec<Ring>>;
+
+/// Cell-centre coordinates of a regular or irregular rectilinear grid.
+/// Values are indexed `[y][x]`; either axis may decrease.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAxes {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Coordinates are longitude and latitude in degrees.
+    pub geographic: bool,
+}
+
+/// Cells overlapping a catchment and their overlap areas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellWeights {
+    /// Row-major cell indices, `y * nx + x`.
+    pub cells: Vec<usize>,
+    /// Overlap area of each cell.
+    pub areas: Vec<f64>,
+}
+
+impl CellWeights {
+    /// Area of the catchment covered by the grid.
+    pub fn area(&self) -> f64 {
+        self.areas.iter().fold(0.0, |a, b| a + b)
+    }
+}
+
+/// Cell edges of strictly monotonic centres, midway between centres and
+/// extrapolated by half a cell at the ends.
+fn edges(centres: &[f64], axis: &str) -> Result<Vec<f64>> {
+    let increasing = centres.windows(2).all(|w| w[1] > w[0]);
+    let decreasing = centres.windows(2).all(|w| w[1] < w[0]);
+    if centres.len() < 2 || !(increasing || decreasing) {
+        return Err(Error::InvalidParameter(format!(
+            "The {axis} coordinates must hold at least two strictly monotonic cell centres."
+        )));
+    }
+    let n = centres.len();
+    let mut edges = Vec::with_capacity(n + 1);
+    edges.push(centres[0] - 0.5 * (centres[1] - centres[0]));
+    edges.extend(centres.windows(2).map(|w| 0.5 * (w[0] + w[1])));
+    edges.push(centres[n - 1] + 0.5 * (centres[n - 1] - centres[n - 2]));
+    Ok(edges)
+}
+
+/// Indices of the cells whose extent along one axis overlaps `[lo, hi]`,
+/// with each cell's `(min, max)` extent.
+fn overlapping(edges: &[f64], lo: f64, hi: f64) -> Vec<(usize, f64, f64)> {
+    edges
+        .windows(2)
+        .enumerate()
+        .map(|(i, w)| (i, w[0].min(w[1]), w[0].max(w[1])))
+        .filter(|&(_, a, b)| b > lo && a < hi)
+        .collect()
+}
+
+/// Clips `ring` to the rectangle `[x0, x1] × [y0, y1]`
+/// (Sutherland–Hodgman). Concave rings give extra edges along the
+/// rectangle but the right area.
+fn clip(ring: &[(f64, f64)], x0: f64, x1: f64, y0: f64, y1: f64) -> Vec<(f64, f64)> {
+    let mut points = ring.to_vec();
+    // Each boundary: its axis, its value and whether the inside lies above.
+    for (axis, value, above) in [(0, x0, true), (0, x1, false), (1, y0, true), (1, y1, false)] {
+        if points.is_empty() {
+            break;
+        }
+        let inside = |p: (f64, f64)| {
+            let v = if axis == 0 { p.0 } else { p.1 };
+            if above {
+                v >= value
+            } else {
+                v <= value
+            }
+        };
+        let crossing = |a: (f64, f64), b: (f64, f64)| {
+            if axis == 0 {
+                let t = (value - a.0) / (b.0 - a.0);
+                (value, a.1 + t * (b.1 - a.1))
+            } else {
+                let t = (value - a.1) / (b.1 - a.1);
+                (a.0 + t * (b.0 - a.0), value)
+            }
+        };
+        let input = std::mem::take(&mut points);
+        let mut previous = input[input.len() - 1];
+        for &current in &input {
+            match (inside(current), inside(previous)) {
+                (true, true) => points.push(current),
+                (true, false) => {
+                    points.push(crossing(previous, current));
+                    points.push(current);
+                }
+                (false, true) => points.push(crossing(previous, current)),
+                (false, false) => {}
+            }
+            previous = current;
+        }
+    }
+    points
+}
+
+/// Unsigned area of a ring (shoelace formula).
+fn ring_area(ring: &[(f64, f64)]) -> f64 {
+    if ring.len() < 3 {
+        return 0.0;
+    }
+    let mut twice = 0.0;
+    let mut previous = ring[ring.len() - 1];
+    for &p in ring {
+        twice += previous.0 * p.1 - p.0 * previous.1;
+        previous = p;
+    }
+    0.5 * twice.abs()
+}
+
+/// Overlap areas of `polygon` with the cells of `axes`.
+pub fn cell_weights(axes: &GridAxes, polygon: &MultiPolygon) -> Result<CellWeights> {
+    let x_edges = edges(&axes.x, "x")?;
+    let y_edges = edges(&axes.y, "y")?;
+    let nx = axes.x.len();
+    let degree = EARTH_RADIUS * std::f64::consts::PI / 180.0;
+    let mut overlap = std::collections::BTreeMap::<usize, f64>::new();
+    for part in polygon {
+        for (r, ring) in part.iter().enumerate() {
+            if ring.len() < 3 {
+                return Err(Error::InvalidParameter(
+                    "Every polygon ring needs at least three vertices.".into(),
+                ));
+            }
+            let sign = if r == 0 { 1.0 } else { -1.0 };
+            let fold = |f: fn(f64, f64) -> f64, start: f64, axis: fn(&(f64, f64)) -> f64| {
+                ring.iter().map(axis).fold(start, f)
+            };
+            let (xmin, xmax) = (
+                fold(f64::min, f64::INFINITY, |p| p.0),
+                fold(f64::max, f64::NEG_INFINITY, |p| p.0),
+            );
+            let (ymin, ymax) = (
+                fold(f64::min, f64::INFINITY, |p| p.1),
+                fold(f64::max, f64::NEG_INFINITY, |p| p.1),
+            );
+            let columns = overlapping(&x_edges, xmin, xmax);
+            for (iy, y0, y1) in overlapping(&y_edges, ymin, ymax) {
+                let scale = if axes.geographic {
+                    degree * degree * axes.y[iy].to_radians().cos()
+                } else {
+                    1.0
+                };
+                for &(ix, x0, x1) in &columns {
+                    let area = ring_area(&clip(ring, x0, x1, y0, y1));
+                    if area > 0.0 {
+                        *overlap.entry(iy * nx + ix).or_insert(0.0) += sign * area * scale;
+                    }
+                }
+            }
+        }
+    }
+    let (cells, areas) = overlap.into_iter().filter(|&(_, a)| a > 0.0).unzip();
+    Ok(CellWeights { cells, areas })
+}
+
+/// Catchment means of `values`, laid out `[step][y][x]` with `cells` values
+/// per step, for each catchment of `weights`: `result[c][t]`. A catchment
+/// whose cells are all NaN at a step, or that misses the grid, gives NaN.
+pub fn aggregate<T>(values: &[T], cells: usize, weights: &[CellWeights]) -> Vec<Vec<f64>>
+where
+    T: Copy + Into<f64> + Sync,
+{
+    let steps = values.len().checked_div(cells).unwrap_or(0);
+    let chunk = steps.div_ceil(4 * num_threads()).max(1);
+    let starts: Vec<usize> = (0..steps).step_by(chunk).collect();
+    let blocks = par_map(&starts, |&start| {
+        let end = (start + chunk).min(steps);
+        let mut block = vec![Vec::with_capacity(end - start); weights.len()];
+        for t in start..end {
+            let field = &values[t * cells..(t + 1) * cells];
+            for (series, w) in block.iter_mut().zip(weights) {
+                let (mut sum, mut total) = (0.0, 0.0);
+                for (&c, &a) in w.cells.iter().zip(&w.areas) {
+                    let v: f64 = field[c].into();
+                    if !v.is_nan() {
+                        sum += a * v;
+                        total += a;
+                    }
+                }
+                series.push(if total > 0.0 { sum / total } else { f64::NAN });
+            }
+        }
+        block
+    });
+    let mut result = vec![Vec::with_capacity(steps); weights.len()];
+    for block in blocks {
+        for (series, part) in result.iter_mut().zip(block) {
+            series.extend(part);
+        }
+    }
+    result
+}
+
+/// Catchment-average series of a gridded field `values[step][y][x]`.
+pub fn grid_to_catchments<T>(
+    values: &[T],
+    axes: &GridAxes,
+    catchments: &[MultiPolygon],
+) -> Result<(Vec<Vec<f64>>, Vec<CellWeights>)>
+where
+    T: Copy + Into<f64> + Sync,
+{
+    let cells = axes.x.len() * axes.y.len();
+    if cells == 0 || !values.len().is_multiple_of(cells) {
+        return Err(Error::Data(format!(
+            "The field holds {} values, not a whole number of {} × {} grids.",
+            values.len(),
+            axes.y.len(),
+            axes.x.len()
+        )));
+    }
+    let weights = par_map(catchments, |polygon| cell_weights(axes, polygon))
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    Ok((aggregate(values, cells, &weights), weights))
+}
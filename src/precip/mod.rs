@@ -0,0 +1,9 @@
+//! Precipitation pre-processing: basin-average rainfall from gauges and
+//! from gridded fields, areal reduction factors and depth–area–duration
+//! analysis of gridded storms.
+
+pub mod areal;
+pub mod dad;
+pub mod grid;
+#[cfg(feature = "python")]
+pub mod python;
@@ -0,0 +1,209 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+use std::collections::BTreeMap;
+
+use super::areal::{areal_average_rs, ArealMethod};
+use super::dad::{areal_reduction_rs, dad_rs, ArfMethod};
+use super::grid::{GridAxes, MultiPolygon};
+use crate::arrow::{FloatBuffer, FloatSeries, FloatSlice};
+use crate::time::timedelta_to_seconds;
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (series, gauges, basin, method="thiessen", power=2.0, weights=None, resolution=200))]
+#[allow(clippy::too_many_arguments)]
+pub fn areal_average<'py>(
+    py: Python<'py>,
+    series: BTreeMap<String, FloatSeries>,
+    gauges: BTreeMap<String, (f64, f64)>,
+    basin: Vec<(f64, f64)>,
+    method: &str,
+    power: f64,
+    weights: Option<BTreeMap<String, f64>>,
+    resolution: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let names: Vec<&String> = series.keys().collect();
+    let lookup = |name: &String| {
+        gauges
+            .get(name)
+            .copied()
+            .ok_or_else(|| PyValueError::new_err(format!("Gauge `{name}` has no location.")))
+    };
+    let locations = names
+        .iter()
+        .map(|n| lookup(n))
+        .collect::<PyResult<Vec<_>>>()?;
+    let weights = weights
+        .map(|w| {
+            names
+                .iter()
+                .map(|n| {
+                    w.get(*n)
+                        .copied()
+                        .ok_or_else(|| PyValueError::new_err(format!("Gauge `{n}` has no weight.")))
+                })
+                .collect::<PyResult<Vec<_>>>()
+        })
+        .transpose()?;
+    let method = ArealMethod::parse(method, power, weights)?;
+    let values: Vec<Vec<f64>> = series.values().map(|s| s.0.clone()).collect();
+    let (average, full) =
+        py.allow_threads(|| areal_average_rs(&values, &locations, &basin, &method, resolution))?;
+    let result = PyDict::new(py);
+    result.set_item("average", average)?;
+    let by_gauge = PyDict::new(py);
+    for (name, w) in names.iter().zip(full) {
+        by_gauge.set_item(name, w)?;
+    }
+    result.set_item("weights", by_gauge)?;
+    Ok(result)
+}
+
+fn hours(durations: &[Bound<'_, PyDelta>]) -> Vec<f64> {
+    durations
+        .iter()
+        .map(|d| timedelta_to_seconds(d) / 3600.0)
+        .collect()
+}
+
+/// Areal reduction method from its name or a table with `areas`,
+/// `durations` (timedeltas) and `factors`.
+fn arf_method(
+    method: &str,
+    table: Option<&Bound<'_, PyDict>>,
+    units: UnitSystem,
+) -> PyResult<ArfMethod> {
+    let Some(table) = table else {
+        return Ok(ArfMethod::parse(method)?);
+    };
+    let field = |name: &str| {
+        table
+            .get_item(name)?
+            .ok_or_else(|| PyValueError::new_err(format!("Areal reduction table has no `{name}`.")))
+    };
+    let areas: Vec<f64> = field("areas")?.extract()?;
+    let durations: Vec<Bound<'_, PyDelta>> = field("durations")?.extract()?;
+    Ok(ArfMethod::table(
+        areas
+            .iter()
+            .map(|&a| units.basin_area_to_si(a) / 1e6)
+            .collect(),
+        hours(&durations),
+        field("factors")?.extract()?,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (depths, durations, area, method="fsr", table=None, units=UnitSystem::Si))]
+pub fn areal_reduction(
+    depths: Vec<f64>,
+    durations: Vec<Bound<'_, PyDelta>>,
+    area: f64,
+    method: &str,
+    table: Option<Bound<'_, PyDict>>,
+    units: UnitSystem,
+) -> PyResult<Vec<f64>> {
+    let method = arf_method(method, table.as_ref(), units)?;
+    let area = units.basin_area_to_si(area) / 1e6;
+    Ok(areal_reduction_rs(
+        &method,
+        &depths,
+        &hours(&durations),
+        area,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (durations, area, method="fsr", table=None, units=UnitSystem::Si))]
+pub fn areal_reduction_factors(
+    durations: Vec<Bound<'_, PyDelta>>,
+    area: f64,
+    method: &str,
+    table: Option<Bound<'_, PyDict>>,
+    units: UnitSystem,
+) -> PyResult<Vec<f64>> {
+    let method = arf_method(method, table.as_ref(), units)?;
+    let area = units.basin_area_to_si(area) / 1e6;
+    Ok(hours(&durations)
+        .iter()
+        .map(|&d| method.factor(area, d))
+        .collect::<crate::error::Result<_>>()?)
+}
+
+#[pyfunction]
+pub fn depth_area_duration<'py>(
+    py: Python<'py>,
+    rainfall: Vec<FloatSeries>,
+    time_step: Bound<'py, PyDelta>,
+    cell_area: f64,
+    durations: Vec<Bound<'py, PyDelta>>,
+    areas: Vec<f64>,
+) -> PyResult<Vec<Vec<f64>>> {
+    let dt = timedelta_to_seconds(&time_step);
+    if dt <= 0.0 {
+        return Err(PyValueError::new_err("The time step must be positive."));
+    }
+    let steps = durations
+        .iter()
+        .map(|d| {
+            let n = timedelta_to_seconds(d) / dt;
+            if n >= 0.5 && (n - n.round()).abs() < 1e-9 * n.max(1.0) {
+                Ok(n.round() as usize)
+            } else {
+                Err(PyValueError::new_err(
+                    "Each duration must be a positive multiple of the time step.",
+                ))
+            }
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let rainfall: Vec<Vec<f64>> = rainfall.into_iter().map(|s| s.0).collect();
+    Ok(py.allow_threads(|| dad_rs(&rainfall, cell_area, &steps, &areas))?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, x, y, catchments, geographic=false))]
+pub fn grid_to_catchments<'py>(
+    py: Python<'py>,
+    values: FloatBuffer,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    catchments: Vec<MultiPolygon>,
+    geographic: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let shape = values.shape();
+    let expected = [y.len(), x.len()];
+    if !(shape.len() == 2 || shape.len() == 3) || shape[shape.len() - 2..] != expected {
+        return Err(PyValueError::new_err(format!(
+            "The values must have shape (steps, {}, {}) or ({}, {}) to match the coordinates, \
+             not {shape:?}.",
+            y.len(),
+            x.len(),
+            y.len(),
+            x.len()
+        )));
+    }
+    let axes = GridAxes { x, y, geographic };
+    let (series, weights) = py.allow_threads(|| match values.values() {
+        FloatSlice::F32(v) => super::grid::grid_to_catchments(v, &axes, &catchments),
+        FloatSlice::F64(v) => super::grid::grid_to_catchments(v, &axes, &catchments),
+    })?;
+    let result = PyDict::new(py);
+    result.set_item("series", series)?;
+    result.set_item(
+        "cells",
+        weights.iter().map(|w| w.cells.len()).collect::<Vec<_>>(),
+    )?;
+    result.set_item("area", weights.iter().map(|w| w.area()).collect::<Vec<_>>())?;
+    Ok(result)
+}
+
+#[pymodule]
+pub fn init_precip(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(areal_average, m)?)?;
+    m.add_function(wrap_pyfunction!(areal_reduction, m)?)?;
+    m.add_function(wrap_pyfunction!(areal_reduction_factors, m)?)?;
+    m.add_function(wrap_pyfunction!(depth_area_duration, m)?)?;
+    m.add_function(wrap_pyfunction!(grid_to_catchments, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Progress reporting and cooperative cancellation for long runs.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::error::{Error, Result};
+
+/// Receives the number of completed and total work units (time steps,
+/// model runs or ensemble members) of a long run. Returning `false` cancels
+/// the run, which then fails with [`Error::Cancelled`].
+pub trait Progress: Sync {
+    fn update(&self, completed: usize, total: usize) -> bool;
+}
+
+impl<F> Progress for F
+where
+    F: Fn(usize, usize) -> bool + Sync,
+{
+    fn update(&self, completed: usize, total: usize) -> bool {
+        self(completed, total)
+    }
+}
+
+/// Reports nothing and never cancels.
+pub struct Silent;
+
+impl Progress for Silent {
+    fn update(&self, _completed: usize, _total: usize) -> bool {
+        true
+    }
+}
+
+/// Counts work units completed by parallel workers and forwards the count
+/// to a [`Progress`].
+pub struct Tracker<'a> {
+    progress: &'a dyn Progress,
+    total: usize,
+    completed: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl<'a> Tracker<'a> {
+    pub fn new(progress: &'a dyn Progress, total: usize) -> Self {
+        Tracker {
+            progress,
+            total,
+            completed: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether the run has been cancelled; workers skip their remaining
+    /// units once it has.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Records one completed unit. Returns `false` once the run is
+    /// cancelled.
+    pub fn advance(&self) -> bool {
+        if self.is_cancelled() {
+            return false;
+        }
+        let completed = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+        if !self.progress.update(completed, self.total) {
+            self.cancelled.store(true, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    /// Fails with [`Error::Cancelled`] if the run was cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled(format!(
+                "Cancelled after {} of {} units.",
+                self.completed.load(Ordering::Relaxed),
+                self.total
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Interval at which a Python caller polls for signals and reports
+/// progress while the run proceeds on a worker thread.
+#[cfg(feature = "python")]
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Latest counts reported by a run on a worker thread.
+#[cfg(feature = "python")]
+#[derive(Default)]
+struct Shared {
+    completed: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+#[cfg(feature = "python")]
+impl Progress for Shared {
+    fn update(&self, completed: usize, total: usize) -> bool {
+        self.completed.store(completed, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+        !self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs `run` on a worker thread with the GIL released while the calling
+/// thread checks for signals (Ctrl-C) and calls `callback(completed,
+/// total)` as the run progresses, at most every [`POLL_INTERVAL`] and once
+/// at the end. A signal or an exception raised by the callback cancels the
+/// run and is re-raised.
+#[cfg(feature = "python")]
+pub fn run_with_progress<T, F>(
+    py: pyo3::Python<'_>,
+    callback: Option<&pyo3::Bound<'_, pyo3::PyAny>>,
+    run: F,
+) -> pyo3::PyResult<T>
+where
+    T: Send,
+    F: FnOnce(&dyn Progress) -> Result<T> + Send,
+{
+    use pyo3::types::PyAnyMethods;
+
+    let shared = Shared::default();
+    let mut reported = (0, 0);
+    let mut error: Option<pyo3::PyErr> = None;
+    let mut poll = |finished: bool| {
+        if error.is_some() {
+            return;
+        }
+        let outcome = py.check_signals().and_then(|()| {
+            let counts = (
+                shared.completed.load(Ordering::Relaxed),
+                shared.total.load(Ordering::Relaxed),
+            );
+            match callback {
+                Some(callback) if counts != reported && (finished || counts.0 < counts.1) => {
+                    reported = counts;
+                    callback.call1(counts).map(drop)
+                }
+                _ => Ok(()),
+            }
+        });
+        if let Err(err) = outcome {
+            shared.cancelled.store(true, Ordering::Relaxed);
+            error = Some(err);
+        }
+    };
+    let result = std::thread::scope(|scope| {
+        let worker = scope.spawn(|| run(&shared));
+        while !worker.is_finished() {
+            py.allow_threads(|| std::thread::sleep(POLL_INTERVAL));
+            poll(false);
+        }
+        worker.join().expect("worker thread panicked")
+    });
+    poll(true);
+    match (result, error) {
+        (Err(Error::Cancelled(_)), Some(err)) => Err(err),
+        (result, _) => result.map_err(Into::into),
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::{Error, Result};
+use crate::units::UnitSystem;
+
+/// Runoff rate per unit area (m/s, about 0.001 in/h) below which a step is
+/// dry, as in SWMM.
+const MIN_RUNOFF: f64 = 7.0e-9;
+
+/// Pollutant buildup on a catchment surface during dry weather, in SI
+/// units (kg/m², 1/s).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Buildup {
+    /// No buildup; washoff is not limited by surface mass.
+    None,
+    /// Exponential approach `B = max (1 - e^(-rate t))` to `max`.
+    Exponential { max: f64, rate: f64 },
+}
+
+impl Buildup {
+    /// Exponential buildup from a maximum in kg/ha (lb/acre) and a rate
+    /// constant in 1/day.
+    pub fn exponential_from_units(max: f64, rate: f64, units: UnitSystem) -> Self {
+        Buildup::Exponential {
+            max: units.mass_to_si(max) / units.land_area_to_si(1.0),
+            rate: rate / 86_400.0,
+        }
+    }
+}
+
+/// SWMM washoff function, in SI units (kg/s, m³/s).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Washoff {
+    /// Mass rate `coefficient · q^exponent · B`, with `q` the runoff rate
+    /// per unit area (m/s) and `B` the surface mass (kg).
+    Exponential { coefficient: f64, exponent: f64 },
+    /// Mass rate `coefficient · Q^exponent` with runoff `Q` (m³/s).
+    RatingCurve { coefficient: f64, exponent: f64 },
+    /// Event mean concentration (kg/m³) times runoff.
+    Emc(f64),
+}
+
+impl Washoff {
+    /// Builds a washoff function from SWMM-style user units:
+    ///
+    /// - `"exponential"`: `coefficient` in 1/h per (mm/h)^`exponent`
+    ///   (in/h for US units);
+    /// - `"rating_curve"`: mass rate in kg/s (lb/s) for runoff in m³/s
+    ///   (cfs);
+    /// - `"emc"`: `coefficient` is a concentration in mg/L.
+    pub fn from_units(
+        kind: &str,
+        coefficient: f64,
+        exponent: f64,
+        units: UnitSystem,
+    ) -> Result<Self> {
+        if coefficient < 0.0 {
+            return Err(Error::InvalidParameter(
+                "Washoff coefficient must not be negative.".into(),
+            ));
+        }
+        match kind {
+            "exponential" => Ok(Washoff::Exponential {
+                coefficient: coefficient / 3600.0 * units.rate_from_si(1.0).powf(exponent),
+                exponent,
+            }),
+            "rating_curve" => Ok(Washoff::RatingCurve {
+                coefficient: units.mass_to_si(coefficient)
+                    * units.discharge_from_si(1.0).powf(exponent),
+                exponent,
+            }),
+            "emc" => Ok(Washoff::Emc(coefficient / 1000.0)),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown washoff function `{other}`; expected `exponential`, `rating_curve` or `emc`."
+            ))),
+        }
+    }
+}
+
+/// Pollutant load generated by a catchment: surface mass builds up in dry
+/// weather and is washed off by runoff, as in SWMM.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollutantLoading {
+    /// Catchment area (m²).
+    pub area: f64,
+    pub buildup: Buildup,
+    pub washoff: Washoff,
+    /// Surface mass at the start of a simulation (kg).
+    pub initial_mass: f64,
+    mass: f64,
+}
+
+impl PollutantLoading {
+    pub fn new(area: f64, buildup: Buildup, washoff: Washoff, initial_mass: f64) -> Result<Self> {
+        if area <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "Catchment area must be positive.".into(),
+            ));
+        }
+        if matches!(washoff, Washoff::Exponential { .. }) && buildup == Buildup::None {
+            return Err(Error::InvalidParameter(
+                "Exponential washoff requires a buildup function.".into(),
+            ));
+        }
+        Ok(PollutantLoading {
+            area,
+            buildup,
+            washoff,
+            initial_mass,
+            mass: initial_mass,
+        })
+    }
+
+    /// Surface mass (kg).
+    pub fn mass(&self) -> f64 {
+        self.mass
+    }
+
+    pub fn reset(&mut self) {
+        self.mass = self.initial_mass;
+    }
+
+    /// Advances by `dt` seconds with catchment `runoff` (m³/s) and returns
+    /// the washed-off load (kg/s). Buildup proceeds only in dry steps, with
+    /// negligible runoff; washoff is limited to the surface mass when
+    /// buildup is modelled.
+    pub fn step(&mut self, runoff: f64, dt: f64) -> f64 {
+        if runoff / self.area < MIN_RUNOFF {
+            if let Buildup::Exponential { max, rate } = self.buildup {
+                let limit = max * self.area;
+                self.mass = limit - (limit - self.mass) * (-rate * dt).exp();
+            }
+            return 0.0;
+        }
+        let washed = match self.washoff {
+            Washoff::Exponential {
+                coefficient,
+                exponent,
+            } => {
+                let q = runoff / self.area;
+                self.mass * (1.0 - (-coefficient * q.powf(exponent) * dt).exp())
+            }
+            Washoff::RatingCurve {
+                coefficient,
+                exponent,
+            } => coefficient * runoff.powf(exponent) * dt,
+            Washoff::Emc(concentration) => concentration * runoff * dt,
+        };
+        let washed = match self.buildup {
+            Buildup::None => washed,
+            Buildup::Exponential { .. } => {
+                let washed = washed.min(self.mass);
+                self.mass -= washed;
+                washed
+            }
+        };
+        washed / dt
+    }
+}
+
+/// Pollutant load (kg/s) and surface mass (kg) at the end of each step for
+/// a `runoff` series (m³/s), e.g. from [`crate::runoff`], with time step
+/// `dt` (s).
+pub fn pollutant_load_rs(
+    loading: &mut PollutantLoading,
+    runoff: &[f64],
+    dt: f64,
+) -> Vec<(f64, f64)> {
+    loading.reset();
+    runoff
+        .iter()
+        .map(|&q| (loading.step(q, dt), loading.mass()))
+        .collect()
+}
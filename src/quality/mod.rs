@@ -0,0 +1,8 @@
+//! Water quality: catchment pollutant loading, and constituent transport
+//! and stream temperature along reaches.
+
+pub mod buildup_washoff;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod temperature;
+pub mod transport;
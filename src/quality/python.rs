@@ -0,0 +1,230 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+
+use super::buildup_washoff::{pollutant_load_rs, Buildup, PollutantLoading, Washoff};
+use super::temperature::{stream_temperature_rs, HeatMethod, MetForcing, TemperatureReach};
+use super::transport::{advection_dispersion_rs, AdvectionDispersion, Spill};
+use crate::arrow::FloatSeries;
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::time::timedelta_to_seconds;
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (discharge, time_step, length, bottom_width, side_slope, n, slope, dispersion, decay=0.0, upstream_concentration=None, initial_concentration=0.0, spill_mass=None, spill_position=0.0, spill_step=0, stations=None, cells=100, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn advection_dispersion(
+    py: Python<'_>,
+    discharge: FloatSeries,
+    time_step: Bound<'_, PyDelta>,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    dispersion: f64,
+    decay: f64,
+    upstream_concentration: Option<FloatSeries>,
+    initial_concentration: f64,
+    spill_mass: Option<f64>,
+    spill_position: f64,
+    spill_step: usize,
+    stations: Option<Vec<f64>>,
+    cells: usize,
+    units: UnitSystem,
+) -> PyResult<Vec<(f64, Vec<f64>)>> {
+    let n_steps = discharge.0.len();
+    let upstream = match upstream_concentration {
+        Some(series) if series.0.len() != n_steps => {
+            return Err(PyValueError::new_err(
+                "`upstream_concentration` must have the same length as `discharge`.",
+            ))
+        }
+        Some(series) => series.0,
+        None => vec![0.0; n_steps],
+    };
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let (velocity, area): (Vec<f64>, Vec<f64>) = discharge
+        .0
+        .iter()
+        .map(|&q| {
+            let depth = channel.normal_depth(units.discharge_to_si(q));
+            (channel.velocity(depth), channel.area(depth))
+        })
+        .unzip();
+    let mut reach = AdvectionDispersion::new(
+        units.length_to_si(length),
+        cells,
+        units.area_to_si(dispersion),
+        decay / 86_400.0,
+        initial_concentration,
+    )?;
+    let spill = spill_mass.map(|mass| Spill {
+        // Grams, so that concentrations are in mg/L.
+        mass: 1000.0 * units.mass_to_si(mass),
+        position: units.length_to_si(spill_position),
+        step: spill_step,
+    });
+    let stations = stations.unwrap_or_else(|| vec![length]);
+    let stations_si: Vec<f64> = stations.iter().map(|&x| units.length_to_si(x)).collect();
+    let dt = timedelta_to_seconds(&time_step);
+    let series = py.allow_threads(|| {
+        advection_dispersion_rs(
+            &mut reach,
+            &velocity,
+            &area,
+            &upstream,
+            spill,
+            &stations_si,
+            dt,
+        )
+    });
+    Ok(stations.into_iter().zip(series).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (discharge, inflow_temperature, air_temperature, relative_humidity, wind_speed, solar_radiation, time_step, length, bottom_width, side_slope, n, slope, cloud_cover=None, shade=0.0, method="energy_balance", initial_temperature=None, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn stream_temperature<'py>(
+    py: Python<'py>,
+    discharge: FloatSeries,
+    inflow_temperature: FloatSeries,
+    air_temperature: FloatSeries,
+    relative_humidity: FloatSeries,
+    wind_speed: FloatSeries,
+    solar_radiation: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    cloud_cover: Option<FloatSeries>,
+    shade: f64,
+    method: &str,
+    initial_temperature: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let n_steps = discharge.0.len();
+    let cloud_cover = cloud_cover.map_or_else(|| vec![0.0; n_steps], |c| c.0);
+    let forcing = [
+        &inflow_temperature.0,
+        &air_temperature.0,
+        &relative_humidity.0,
+        &wind_speed.0,
+        &solar_radiation.0,
+        &cloud_cover,
+    ];
+    if forcing.iter().any(|s| s.len() != n_steps) {
+        return Err(PyValueError::new_err(
+            "Flow, inflow temperature and meteorological series must have equal lengths.",
+        ));
+    }
+    let met: Vec<MetForcing> = (0..n_steps)
+        .map(|t| MetForcing {
+            air_temperature: air_temperature.0[t],
+            relative_humidity: relative_humidity.0[t],
+            wind_speed: wind_speed.0[t],
+            solar_radiation: solar_radiation.0[t],
+            cloud_cover: cloud_cover[t],
+        })
+        .collect();
+    let mut reach = TemperatureReach::new(
+        TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?,
+        units.length_to_si(length),
+        shade,
+        HeatMethod::parse(method)?,
+        initial_temperature,
+    )?;
+    let discharge_si: Vec<f64> = discharge
+        .0
+        .iter()
+        .map(|&q| units.discharge_to_si(q))
+        .collect();
+    let dt = timedelta_to_seconds(&time_step);
+    let steps = py.allow_threads(|| {
+        stream_temperature_rs(&mut reach, &discharge_si, &inflow_temperature.0, &met, dt)
+    });
+    let result = PyDict::new(py);
+    result.set_item(
+        "temperature",
+        steps.iter().map(|s| s.temperature).collect::<Vec<_>>(),
+    )?;
+    result.set_item(
+        "equilibrium_temperature",
+        steps.iter().map(|s| s.equilibrium).collect::<Vec<_>>(),
+    )?;
+    result.set_item(
+        "heat_flux",
+        steps.iter().map(|s| s.heat_flux).collect::<Vec<_>>(),
+    )?;
+    Ok(result)
+}
+
+#[pyfunction]
+#[pyo3(signature = (runoff, time_step, area, washoff="exponential", coefficient=0.0, exponent=1.0, buildup_max=None, buildup_rate=0.0, initial_buildup=None, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn pollutant_load<'py>(
+    py: Python<'py>,
+    runoff: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    area: f64,
+    washoff: &str,
+    coefficient: f64,
+    exponent: f64,
+    buildup_max: Option<f64>,
+    buildup_rate: f64,
+    initial_buildup: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let area_si = units.land_area_to_si(area);
+    let buildup = match buildup_max {
+        None => Buildup::None,
+        Some(max) => Buildup::exponential_from_units(max, buildup_rate, units),
+    };
+    // Initial surface mass per unit area; defaults to the buildup limit.
+    let initial = match (initial_buildup, buildup) {
+        (Some(b), _) => units.mass_to_si(b) / units.land_area_to_si(1.0),
+        (None, Buildup::Exponential { max, .. }) => max,
+        (None, Buildup::None) => 0.0,
+    };
+    let mut loading = PollutantLoading::new(
+        area_si,
+        buildup,
+        Washoff::from_units(washoff, coefficient, exponent, units)?,
+        initial * area_si,
+    )?;
+    let dt = timedelta_to_seconds(&time_step);
+    let runoff_si: Vec<f64> = runoff.0.iter().map(|&q| units.discharge_to_si(q)).collect();
+    let steps = py.allow_threads(|| pollutant_load_rs(&mut loading, &runoff_si, dt));
+    let concentration: Vec<f64> = steps
+        .iter()
+        .zip(&runoff_si)
+        .map(|(&(load, _), &q)| if q > 0.0 { 1000.0 * load / q } else { 0.0 })
+        .collect();
+    let result = PyDict::new(py);
+    result.set_item(
+        "load",
+        steps
+            .iter()
+            .map(|&(load, _)| units.mass_from_si(load))
+            .collect::<Vec<_>>(),
+    )?;
+    result.set_item("concentration", concentration)?;
+    result.set_item(
+        "buildup",
+        steps
+            .iter()
+            .map(|&(_, mass)| units.mass_from_si(mass))
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(result)
+}
+
+#[pymodule]
+pub fn init_quality(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(advection_dispersion, m)?)?;
+    m.add_function(wrap_pyfunction!(stream_temperature, m)?)?;
+    m.add_function(wrap_pyfunction!(pollutant_load, m)?)?;
+    Ok(())
+}
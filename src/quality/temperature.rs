@@ -0,0 +1,225 @@
+use crate::error::{Error, Result};
+use crate::hydraulics::manning::TrapezoidalChannel;
+
+/// Stefan–Boltzmann constant (W/m²/K⁴).
+const STEFAN_BOLTZMANN: f64 = 5.67e-8;
+/// Volumetric heat capacity of water (J/m³/°C).
+const HEAT_CAPACITY: f64 = 1000.0 * 4186.0;
+/// Emissivity of water.
+const WATER_EMISSIVITY: f64 = 0.97;
+/// Shortwave albedo of a water surface.
+const ALBEDO: f64 = 0.06;
+/// Bowen ratio coefficient at standard pressure (hPa/°C).
+const BOWEN: f64 = 0.61;
+
+/// Meteorological forcing for one time step.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MetForcing {
+    /// Air temperature (°C).
+    pub air_temperature: f64,
+    /// Relative humidity (0–1).
+    pub relative_humidity: f64,
+    /// Wind speed at about 2 m (m/s).
+    pub wind_speed: f64,
+    /// Incoming shortwave radiation (W/m²).
+    pub solar_radiation: f64,
+    /// Cloud cover fraction (0–1).
+    pub cloud_cover: f64,
+}
+
+/// Saturation vapour pressure (hPa) at `temperature` (°C).
+fn saturation_vapour_pressure(temperature: f64) -> f64 {
+    6.108 * (17.27 * temperature / (temperature + 237.3)).exp()
+}
+
+/// Net surface heat flux into the water (W/m²) at water temperature
+/// `water` (°C), from absorbed shortwave (reduced by `shade`), atmospheric
+/// longwave (Brutsaert emissivity with a cloud correction), back radiation,
+/// evaporation (Edinger–Brady wind function) and conduction (Bowen ratio).
+pub fn surface_heat_flux(met: &MetForcing, shade: f64, water: f64) -> f64 {
+    let air_k = met.air_temperature + 273.15;
+    let vapour =
+        met.relative_humidity.clamp(0.0, 1.0) * saturation_vapour_pressure(met.air_temperature);
+    let emissivity =
+        1.24 * (vapour / air_k).powf(1.0 / 7.0) * (1.0 + 0.17 * met.cloud_cover.powi(2));
+    let shortwave = (1.0 - ALBEDO) * (1.0 - shade) * met.solar_radiation.max(0.0);
+    let longwave = WATER_EMISSIVITY * emissivity.min(1.0) * STEFAN_BOLTZMANN * air_k.powi(4);
+    let back = WATER_EMISSIVITY * STEFAN_BOLTZMANN * (water + 273.15).powi(4);
+    // Wind function in W/m²/mmHg, converted to W/m²/hPa.
+    let wind = (9.2 + 0.46 * met.wind_speed * met.wind_speed) / 1.333;
+    let evaporation = wind * (saturation_vapour_pressure(water) - vapour);
+    let conduction = wind * BOWEN * (water - met.air_temperature);
+    shortwave + longwave - back - evaporation - conduction
+}
+
+/// Water temperature (°C) at which the net surface heat flux vanishes.
+pub fn equilibrium_temperature(met: &MetForcing, shade: f64) -> f64 {
+    let (mut low, mut high) = (-30.0, 60.0);
+    for _ in 0..60 {
+        let mid = 0.5 * (low + high);
+        if surface_heat_flux(met, shade, mid) > 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// How the surface heat flux is evaluated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeatMethod {
+    /// Full energy balance, solved implicitly.
+    EnergyBalance,
+    /// Edinger et al. (1968) linearisation `K (Te - T)` about the
+    /// equilibrium temperature `Te`.
+    Equilibrium,
+}
+
+impl HeatMethod {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "energy_balance" => Ok(HeatMethod::EnergyBalance),
+            "equilibrium" => Ok(HeatMethod::Equilibrium),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown temperature method `{name}`; expected `energy_balance` or `equilibrium`."
+            ))),
+        }
+    }
+}
+
+/// Well-mixed stream reach exchanging heat with the atmosphere, in SI
+/// units. The reach volume and surface area follow normal flow in the
+/// channel at the current discharge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemperatureReach {
+    pub channel: TrapezoidalChannel,
+    /// Reach length (m).
+    pub length: f64,
+    /// Fraction of shortwave radiation blocked by riparian shade (0–1).
+    pub shade: f64,
+    pub method: HeatMethod,
+    /// Water temperature at the start; the first inflow temperature if
+    /// `None`.
+    pub initial_temperature: Option<f64>,
+    temperature: Option<f64>,
+}
+
+/// Temperature and surface heat budget of one step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TemperatureStep {
+    /// Outflow temperature (°C).
+    pub temperature: f64,
+    /// Equilibrium temperature (°C).
+    pub equilibrium: f64,
+    /// Net surface heat flux at the end of the step (W/m²).
+    pub heat_flux: f64,
+}
+
+impl TemperatureReach {
+    pub fn new(
+        channel: TrapezoidalChannel,
+        length: f64,
+        shade: f64,
+        method: HeatMethod,
+        initial_temperature: Option<f64>,
+    ) -> Result<Self> {
+        if length <= 0.0 || !(0.0..=1.0).contains(&shade) {
+            return Err(Error::InvalidParameter(
+                "Reach length must be positive and shade within [0, 1].".into(),
+            ));
+        }
+        Ok(TemperatureReach {
+            channel,
+            length,
+            shade,
+            method,
+            initial_temperature,
+            temperature: None,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.temperature = None;
+    }
+
+    /// Advances by `dt` seconds with flow `discharge` (m³/s) entering at
+    /// `inflow_temperature` (°C).
+    pub fn step(
+        &mut self,
+        discharge: f64,
+        inflow_temperature: f64,
+        met: &MetForcing,
+        dt: f64,
+    ) -> TemperatureStep {
+        let previous = *self
+            .temperature
+            .get_or_insert(self.initial_temperature.unwrap_or(inflow_temperature));
+        let depth = self.channel.normal_depth(discharge.max(0.0));
+        // Keep a thin film of water so that a dry reach tracks equilibrium.
+        let depth = depth.max(0.01);
+        let volume = self.channel.area(depth) * self.length;
+        let surface = self.channel.top_width(depth) * self.length;
+        let exchange = discharge.max(0.0) * dt / volume;
+        let heating = surface * dt / (HEAT_CAPACITY * volume);
+        let equilibrium = equilibrium_temperature(met, self.shade);
+        let temperature = match self.method {
+            HeatMethod::EnergyBalance => {
+                // Backward Euler; the residual decreases monotonically in T.
+                let residual = |t: f64| {
+                    previous
+                        + exchange * (inflow_temperature - t)
+                        + heating * surface_heat_flux(met, self.shade, t)
+                        - t
+                };
+                let (mut low, mut high) = (-30.0, 60.0);
+                for _ in 0..60 {
+                    let mid = 0.5 * (low + high);
+                    if residual(mid) > 0.0 {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+                0.5 * (low + high)
+            }
+            HeatMethod::Equilibrium => {
+                let flux = surface_heat_flux(met, self.shade, previous);
+                let coefficient = if (equilibrium - previous).abs() > 1e-6 {
+                    flux / (equilibrium - previous)
+                } else {
+                    (surface_heat_flux(met, self.shade, previous - 0.5)
+                        - surface_heat_flux(met, self.shade, previous + 0.5))
+                    .max(0.0)
+                };
+                let k = heating * coefficient.max(0.0);
+                (previous + exchange * inflow_temperature + k * equilibrium) / (1.0 + exchange + k)
+            }
+        }
+        .max(0.0);
+        self.temperature = Some(temperature);
+        TemperatureStep {
+            temperature,
+            equilibrium,
+            heat_flux: surface_heat_flux(met, self.shade, temperature),
+        }
+    }
+}
+
+/// Simulates `reach` for `discharge` (m³/s) and `inflow_temperature` (°C)
+/// series with one [`MetForcing`] per step.
+pub fn stream_temperature_rs(
+    reach: &mut TemperatureReach,
+    discharge: &[f64],
+    inflow_temperature: &[f64],
+    met: &[MetForcing],
+    dt: f64,
+) -> Vec<TemperatureStep> {
+    reach.reset();
+    discharge
+        .iter()
+        .zip(inflow_temperature)
+        .zip(met)
+        .map(|((&q, &t), m)| reach.step(q, t, m, dt))
+        .collect()
+}
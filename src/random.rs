@@ -0,0 +1,448 @@
+//! Deterministic pseudo-random numbers for the sampling routines
+//! (xoshiro256** seeded through SplitMix64).
+//!
+//! Seeded results are reproducible bit for bit:
+//! - Draws never depend on the number of threads. Routines draw their
+//!   samples on one thread before evaluating them in parallel, and
+//!   parallel tasks that draw their own numbers get independent streams
+//!   from [`task_seeds`].
+//! - Draws never depend on the machine. The samplers use the portable
+//!   [`ln`], [`exp`] and [`powf`] here rather than the platform's math
+//!   library, whose last bits differ between systems.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::log::info;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+fn splitmix64(x: &mut u64) -> u64 {
+    *x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seed of a run: `seed` if given, otherwise the `RUSTFLOW_SEED`
+/// environment variable if set, otherwise one from the system clock. A
+/// clock seed is logged at the info level so that the run can be repeated.
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    if let Some(seed) = seed {
+        return seed;
+    }
+    if let Some(seed) = std::env::var("RUSTFLOW_SEED")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+    {
+        return seed;
+    }
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    info!("Random seed {seed} taken from the clock; pass it as `seed` to repeat this run");
+    seed
+}
+
+/// Seeds of `n` independent streams for parallel tasks, drawn from one
+/// base seed (see [`resolve_seed`]). Task `i` gets the same stream whatever
+/// the number of tasks or threads, so results do not depend on how tasks
+/// are spread over threads.
+pub fn task_seeds(seed: Option<u64>, n: usize) -> Vec<u64> {
+    let mut rng = Rng::from_seed(seed);
+    (0..n).map(|_| rng.next_u64()).collect()
+}
+
+impl Rng {
+    pub fn seed_from_u64(seed: u64) -> Self {
+        let mut x = seed;
+        Rng {
+            state: [
+                splitmix64(&mut x),
+                splitmix64(&mut x),
+                splitmix64(&mut x),
+                splitmix64(&mut x),
+            ],
+        }
+    }
+
+    /// Seeds from `seed` if given, otherwise as [`resolve_seed`] does.
+    pub fn from_seed(seed: Option<u64>) -> Self {
+        Rng::seed_from_u64(resolve_seed(seed))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Uniform sample in `[0, 1)`.
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Standard normal sample (Box–Muller).
+    pub fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        (-2.0 * ln(u1)).sqrt() * cos_turns(u2)
+    }
+
+    /// Gamma sample with unit scale (Marsaglia & Tsang, 2000).
+    pub fn gamma(&mut self, shape: f64) -> f64 {
+        if shape < 1.0 {
+            let u = 1.0 - self.uniform();
+            return self.gamma(shape + 1.0) * powf(u, 1.0 / shape);
+        }
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let z = self.normal();
+            let w = 1.0 + c * z;
+            let v = w * w * w;
+            if v <= 0.0 {
+                continue;
+            }
+            let u = 1.0 - self.uniform();
+            if ln(u) < 0.5 * z * z + d - d * v + d * ln(v) {
+                return d * v;
+            }
+        }
+    }
+
+    /// Uniform integer in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.uniform() * n as f64) as usize % n.max(1)
+    }
+
+    /// Shuffles `items` in place (Fisher–Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// The portable functions below follow FreeBSD's msun (fdlibm), using only
+// IEEE 754 arithmetic, which gives the same bits on every platform.
+
+const LN2_HI: f64 = 0.6931471803691238;
+const LN2_LO: f64 = 1.9082149292705877e-10;
+
+/// Natural logarithm, accurate to within one unit in the last place and
+/// identical on every platform.
+pub fn ln(x: f64) -> f64 {
+    const LG1: f64 = 0.6666666666666735;
+    const LG2: f64 = 0.3999999999940942;
+    const LG3: f64 = 0.2857142874366239;
+    const LG4: f64 = 0.22222198432149784;
+    const LG5: f64 = 0.1818357216161805;
+    const LG6: f64 = 0.15313837699209373;
+    const LG7: f64 = 0.14798198605116586;
+
+    let mut bits = x.to_bits();
+    let mut hx = (bits >> 32) as u32;
+    let mut k: i32 = 0;
+    if hx < 0x0010_0000 || hx >> 31 != 0 {
+        if bits << 1 == 0 {
+            return f64::NEG_INFINITY;
+        }
+        if hx >> 31 != 0 {
+            return f64::NAN;
+        }
+        // Subnormal: scale up.
+        k -= 54;
+        bits = (x * f64::from_bits(0x4350_0000_0000_0000)).to_bits();
+        hx = (bits >> 32) as u32;
+    } else if hx >= 0x7ff0_0000 {
+        return x;
+    } else if hx == 0x3ff0_0000 && bits << 32 == 0 {
+        return 0.0;
+    }
+    // Reduce x into [sqrt(2)/2, sqrt(2)].
+    hx += 0x3ff0_0000 - 0x3fe6_a09e;
+    k += (hx >> 20) as i32 - 0x3ff;
+    hx = (hx & 0x000f_ffff) + 0x3fe6_a09e;
+    let x = f64::from_bits((u64::from(hx) << 32) | (bits & 0xffff_ffff));
+
+    let f = x - 1.0;
+    let hfsq = 0.5 * f * f;
+    let s = f / (2.0 + f);
+    let z = s * s;
+    let w = z * z;
+    let t1 = w * (LG2 + w * (LG4 + w * LG6));
+    let t2 = z * (LG1 + w * (LG3 + w * (LG5 + w * LG7)));
+    let r = t2 + t1;
+    let dk = f64::from(k);
+    s * (hfsq + r) + dk * LN2_LO - hfsq + f + dk * LN2_HI
+}
+
+/// Exponential, accurate to within one unit in the last place of the
+/// result and identical on every platform. Results below `f64::MIN_POSITIVE`
+/// (`x` below about -708.4) are subnormal, with a last place of `2^-1074`,
+/// so their relative error grows to `2^-1074 / exp(x)`, about `2e-13` at
+/// `x = -715`.
+pub fn exp(x: f64) -> f64 {
+    const P1: f64 = 0.16666666666666602;
+    const P2: f64 = -0.0027777777777015593;
+    const P3: f64 = 6.613756321437934e-05;
+    const P4: f64 = -1.6533902205465252e-06;
+    const P5: f64 = 4.1381367970572385e-08;
+
+    if x.is_nan() {
+        return x;
+    }
+    if x > 709.782712893384 {
+        return f64::INFINITY;
+    }
+    if x < -745.1332191019411 {
+        return 0.0;
+    }
+    let hx = (x.to_bits() >> 32) as u32 & 0x7fff_ffff;
+    let (hi, lo, k) = if hx > 0x3fd6_2e42 {
+        // |x| > ln(2) / 2: reduce to x - k ln(2).
+        let k = if hx >= 0x3ff0_a2b2 {
+            (std::f64::consts::LOG2_E * x + 0.5f64.copysign(x)) as i32
+        } else if x < 0.0 {
+            -1
+        } else {
+            1
+        };
+        let hi = x - f64::from(k) * LN2_HI;
+        (hi, f64::from(k) * LN2_LO, k)
+    } else if hx > 0x3e30_0000 {
+        (x, 0.0, 0)
+    } else {
+        return 1.0 + x;
+    };
+    let x = hi - lo;
+    let xx = x * x;
+    let c = x - xx * (P1 + xx * (P2 + xx * (P3 + xx * (P4 + xx * P5))));
+    let y = 1.0 + (x * c / (2.0 - c) - lo + hi);
+    if k == 0 {
+        y
+    } else {
+        scale(y, k)
+    }
+}
+
+/// `x` raised to the power `y` for positive `x`, as `exp(y ln(x))`;
+/// identical on every platform.
+pub fn powf(x: f64, y: f64) -> f64 {
+    if x == 0.0 {
+        return if y > 0.0 { 0.0 } else { f64::INFINITY };
+    }
+    exp(y * ln(x))
+}
+
+/// `y · 2^k`.
+fn scale(mut y: f64, mut k: i32) -> f64 {
+    let two_to = |k: i32| f64::from_bits(((0x3ff + k) as u64) << 52);
+    if k > 1023 {
+        y *= two_to(1023);
+        k -= 1023;
+    } else if k < -1022 {
+        // Scale in two steps to round subnormal results only once.
+        y *= two_to(-1022 + 53);
+        k += 1022 - 53;
+    }
+    y * two_to(k.clamp(-1022, 1023))
+}
+
+/// Cosine of `2π u` for `u` in `[0, 1)`, identical on every platform. The
+/// quadrant is taken from `u` exactly, leaving an angle of at most π/4.
+fn cos_turns(u: f64) -> f64 {
+    let t = 4.0 * u;
+    let quadrant = t.floor();
+    let r = t - quadrant;
+    // cos and sin of r π/2 from the kernel on [0, π/4].
+    let (cos, sin) = if r <= 0.5 {
+        let x = r * std::f64::consts::FRAC_PI_2;
+        (kernel_cos(x), kernel_sin(x))
+    } else {
+        let x = (1.0 - r) * std::f64::consts::FRAC_PI_2;
+        (kernel_sin(x), kernel_cos(x))
+    };
+    match quadrant as i32 & 3 {
+        0 => cos,
+        1 => -sin,
+        2 => -cos,
+        _ => sin,
+    }
+}
+
+/// Sine on `[-π/4, π/4]`.
+fn kernel_sin(x: f64) -> f64 {
+    const S1: f64 = -0.16666666666666632;
+    const S2: f64 = 0.00833333333332249;
+    const S3: f64 = -0.0001984126982985795;
+    const S4: f64 = 2.7557313707070068e-06;
+    const S5: f64 = -2.5050760253406863e-08;
+    const S6: f64 = 1.58969099521155e-10;
+    let z = x * x;
+    let v = z * x;
+    let r = S2 + z * (S3 + z * (S4 + z * (S5 + z * S6)));
+    x + v * (S1 + z * r)
+}
+
+/// Cosine on `[-π/4, π/4]`.
+fn kernel_cos(x: f64) -> f64 {
+    const C1: f64 = 0.0416666666666666;
+    const C2: f64 = -0.001388888888887411;
+    const C3: f64 = 2.480158728947673e-05;
+    const C4: f64 = -2.7557314351390663e-07;
+    const C5: f64 = 2.087572321298175e-09;
+    const C6: f64 = -1.1359647557788195e-11;
+    let z = x * x;
+    let w = z * z;
+    let r = z * (C1 + z * (C2 + z * C3)) + w * w * (C4 + z * (C5 + z * C6));
+    let hz = 0.5 * z;
+    let w = 1.0 - hz;
+    w + (((1.0 - w) - hz) + z * r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Distance in units in the last place between two finite values of
+    /// the same sign.
+    fn ulps(a: f64, b: f64) -> u64 {
+        (a.to_bits() as i64 - b.to_bits() as i64).unsigned_abs()
+    }
+
+    #[test]
+    fn matches_reference_sequences() {
+        // Test vectors of the reference SplitMix64 and xoshiro256**.
+        let mut x = 1234567;
+        let seeds: Vec<u64> = (0..3).map(|_| splitmix64(&mut x)).collect();
+        assert_eq!(
+            seeds,
+            [
+                6457827717110365317,
+                3203168211198807973,
+                9817491932198370423
+            ]
+        );
+        let mut rng = Rng {
+            state: [1, 2, 3, 4],
+        };
+        let draws: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        assert_eq!(draws, [11520, 0, 1509978240, 1215971899390074240]);
+        let mut rng = Rng::seed_from_u64(0);
+        assert_eq!(rng.state[0], 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 11091344671253066420);
+        assert_eq!(rng.next_u64(), 13793997310169335082);
+    }
+
+    #[test]
+    fn ln_and_exp_match_the_platform() {
+        // Both are within an ulp of the exact value, so within two of each
+        // other on any platform.
+        let mut rng = Rng::seed_from_u64(42);
+        for _ in 0..20000 {
+            // Magnitudes across the whole normal range.
+            let x = powf(2.0, rng.uniform() * 2040.0 - 1020.0) * (1.0 + rng.uniform());
+            assert!(ulps(ln(x), x.ln()) <= 2, "ln({x:e})");
+            let y = rng.uniform() * 1400.0 - 700.0;
+            assert!(ulps(exp(y), y.exp()) <= 2, "exp({y})");
+            let small = (rng.uniform() - 0.5) * 1e-6;
+            assert!(ulps(exp(small), small.exp()) <= 2, "exp({small:e})");
+        }
+    }
+
+    #[test]
+    fn handles_subnormals_and_special_values() {
+        let mut rng = Rng::seed_from_u64(3);
+        for _ in 0..5000 {
+            let tiny = f64::from_bits(1 + rng.next_u64() % 0x000f_ffff_ffff_ffff);
+            assert!(ulps(ln(tiny), tiny.ln()) <= 2, "ln({tiny:e})");
+            // Subnormal results are within a unit of 2^-1074 of the exact value.
+            let y = -708.4 - rng.uniform() * 36.7;
+            assert!(ulps(exp(y), y.exp()) <= 2, "exp({y})");
+        }
+        assert_eq!(ln(0.0), f64::NEG_INFINITY);
+        assert_eq!(ln(-0.0), f64::NEG_INFINITY);
+        assert!(ln(-1.0).is_nan() && ln(f64::NAN).is_nan());
+        assert_eq!(ln(f64::INFINITY), f64::INFINITY);
+        assert_eq!(ln(1.0), 0.0);
+        assert_eq!(exp(0.0), 1.0);
+        assert_eq!(exp(710.0), f64::INFINITY);
+        assert_eq!(exp(-746.0), 0.0);
+        assert_eq!(exp(f64::NEG_INFINITY), 0.0);
+        assert!(exp(f64::NAN).is_nan());
+        assert_eq!(powf(0.0, 2.0), 0.0);
+        assert_eq!(powf(0.0, -1.0), f64::INFINITY);
+        assert!(ulps(powf(2.0, 10.0), 1024.0) <= 2);
+        assert!(ulps(powf(10.0, -0.5), 10f64.powf(-0.5)) <= 2);
+    }
+
+    #[test]
+    fn samples_follow_their_distributions() {
+        let mut rng = Rng::seed_from_u64(9);
+        let n = 200_000;
+        let mean = |f: &mut dyn FnMut() -> f64| (0..n).map(|_| f()).sum::<f64>() / n as f64;
+        let uniform = mean(&mut || {
+            let u = rng.uniform();
+            assert!((0.0..1.0).contains(&u));
+            u
+        });
+        assert!((uniform - 0.5).abs() < 0.005);
+        assert!(mean(&mut || rng.normal()).abs() < 0.01);
+        assert!((mean(&mut || rng.normal().powi(2)) - 1.0).abs() < 0.02);
+        for shape in [0.5, 3.0] {
+            let gamma = mean(&mut || rng.gamma(shape));
+            assert!(
+                (gamma - shape).abs() < 0.02 * shape.max(1.0),
+                "{shape}: {gamma}"
+            );
+        }
+        for u in [0.0, 0.1, 0.25, 0.4, 0.5, 0.7, 0.75, 0.99] {
+            let expected = (2.0 * std::f64::consts::PI * u).cos();
+            assert!((cos_turns(u) - expected).abs() < 1e-15, "{u}");
+        }
+    }
+
+    #[test]
+    fn draws_integers_and_permutations_in_range() {
+        let mut rng = Rng::seed_from_u64(5);
+        let mut seen = [0usize; 7];
+        for _ in 0..7000 {
+            seen[rng.below(7)] += 1;
+        }
+        assert!(seen.iter().all(|&c| c > 800), "{seen:?}");
+        assert_eq!(rng.below(1), 0);
+        let mut items: Vec<usize> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn seeds_are_reproducible() {
+        assert_eq!(resolve_seed(Some(17)), 17);
+        let mut a = Rng::from_seed(Some(17));
+        let mut b = Rng::seed_from_u64(17);
+        assert!((0..10).all(|_| a.next_u64() == b.next_u64()));
+        // Task streams do not depend on the number of tasks.
+        let seeds = task_seeds(Some(17), 10);
+        assert_eq!(task_seeds(Some(17), 3), seeds[..3]);
+        let mut unique = seeds.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), 10);
+        assert_ne!(task_seeds(Some(18), 3), seeds[..3]);
+    }
+}
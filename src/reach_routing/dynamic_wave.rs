@@ -0,0 +1,538 @@
+use crate::error::{Error, Result};
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::log::{trace, warning};
+
+const GRAVITY: f64 = 9.81;
+/// Smallest depth kept during the Newton iterations (m).
+const MIN_DEPTH: f64 = 1e-3;
+const MAX_ITERATIONS: usize = 30;
+
+/// One-dimensional unsteady flow (Saint-Venant equations) in a prismatic
+/// channel, solved with the implicit four-point Preissmann scheme, in SI
+/// units.
+///
+/// The upstream boundary is a discharge hydrograph and the downstream
+/// boundary a stage hydrograph, so backwater from a downstream lake, tide
+/// or confluence is represented. Flow must be subcritical. Each time step
+/// is solved by Newton iteration on the discharge and depth of every node,
+/// with a banded Jacobian. The initial state is the steady gradually varied
+/// profile for the first boundary values.
+///
+/// Discharges are signed: a rising tide may drive the flow upstream
+/// (negative) through part or all of the reach, with friction opposing it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicWaveReach {
+    pub channel: TrapezoidalChannel,
+    /// Reach length (m).
+    pub length: f64,
+    /// Number of computational segments.
+    pub segments: usize,
+    /// Time weighting of the scheme, between 0.5 and 1; values slightly
+    /// above 0.5 damp spurious oscillations.
+    pub theta: f64,
+    /// Bed elevation at the downstream end (m), the datum of the stages.
+    pub outlet_bed: f64,
+}
+
+/// Results of a dynamic wave simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicWaveResult {
+    /// Distance of each node from the upstream end (m).
+    pub stations: Vec<f64>,
+    /// Discharge at the downstream end at each step (m³/s).
+    pub outflow: Vec<f64>,
+    /// Water-surface elevation at the upstream end at each step (m).
+    pub upstream_stage: Vec<f64>,
+    /// Highest water-surface elevation reached at each node (m).
+    pub max_stage: Vec<f64>,
+    /// Lowest discharge at each node (m³/s), negative where the flow
+    /// reversed.
+    pub min_discharge: Vec<f64>,
+}
+
+/// Quasi-steady backwater profiles: the steady gradually varied profile
+/// for the discharge and downstream stage of each step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackwaterResult {
+    /// Distance of each node from the upstream end (m).
+    pub stations: Vec<f64>,
+    /// Water-surface elevation (m) at each node, for each step.
+    pub stage: Vec<Vec<f64>>,
+}
+
+/// Discharge and depth of every node.
+#[derive(Clone, Debug, PartialEq)]
+struct State {
+    discharge: Vec<f64>,
+    depth: Vec<f64>,
+}
+
+impl DynamicWaveReach {
+    pub fn new(
+        channel: TrapezoidalChannel,
+        length: f64,
+        segments: usize,
+        theta: f64,
+        outlet_bed: f64,
+    ) -> Result<Self> {
+        if length.is_nan() || length <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "Reach length must be positive.".into(),
+            ));
+        }
+        if !(0.5..=1.0).contains(&theta) {
+            return Err(Error::InvalidParameter(format!(
+                "Preissmann weight {theta} is outside [0.5, 1]."
+            )));
+        }
+        Ok(DynamicWaveReach {
+            channel,
+            length,
+            segments: segments.max(1),
+            theta,
+            outlet_bed,
+        })
+    }
+
+    fn dx(&self) -> f64 {
+        self.length / self.segments as f64
+    }
+
+    /// Distance of each node from the upstream end (m).
+    fn stations(&self) -> Vec<f64> {
+        (0..=self.segments).map(|i| i as f64 * self.dx()).collect()
+    }
+
+    /// Depth (m) at the downstream end for the water-surface `stage` (m).
+    fn outlet_depth(&self, stage: f64) -> Result<f64> {
+        let bed = self.outlet_bed;
+        if stage.is_nan() || stage - bed < MIN_DEPTH {
+            return Err(Error::InvalidParameter(format!(
+                "Downstream stage {stage} is at or below the channel bed ({bed})."
+            )));
+        }
+        Ok(stage - bed)
+    }
+
+    /// Bed elevation of node `i` (m).
+    fn bed(&self, i: usize) -> f64 {
+        self.outlet_bed + self.channel.bed_slope * (self.segments - i) as f64 * self.dx()
+    }
+
+    fn friction_slope(&self, discharge: f64, depth: f64) -> f64 {
+        let area = self.channel.area(depth);
+        let radius = self.channel.hydraulic_radius(depth);
+        let n = self.channel.manning_n;
+        discharge * discharge.abs() * n * n / (area * area * radius.powf(4.0 / 3.0))
+    }
+
+    /// Steady gradually varied profile carrying `discharge` with depth
+    /// `outlet_depth` at the downstream end, integrated upstream by
+    /// fourth-order Runge–Kutta. Where the flow would be critical or
+    /// supercritical, normal depth is used instead. A negative discharge
+    /// flows upstream, so the water surface falls in that direction.
+    fn steady_profile(&self, discharge: f64, outlet_depth: f64) -> State {
+        let n = self.segments + 1;
+        let normal = self.channel.normal_depth(discharge.abs()).max(MIN_DEPTH);
+        // dh/dx along the flow; integrated with negative steps upstream.
+        let slope = |h: f64| {
+            let area = self.channel.area(h);
+            let froude2 =
+                discharge * discharge * self.channel.top_width(h) / (GRAVITY * area.powi(3));
+            if froude2 >= 0.95 {
+                return None;
+            }
+            Some((self.channel.bed_slope - self.friction_slope(discharge, h)) / (1.0 - froude2))
+        };
+        let mut depth = vec![0.0; n];
+        depth[n - 1] = outlet_depth.max(MIN_DEPTH);
+        const SUBSTEPS: usize = 10;
+        let h_step = -self.dx() / SUBSTEPS as f64;
+        for i in (0..n - 1).rev() {
+            let mut h = depth[i + 1];
+            for _ in 0..SUBSTEPS {
+                let next = (|| {
+                    let k1 = slope(h)?;
+                    let k2 = slope((h + 0.5 * h_step * k1).max(MIN_DEPTH))?;
+                    let k3 = slope((h + 0.5 * h_step * k2).max(MIN_DEPTH))?;
+                    let k4 = slope((h + h_step * k3).max(MIN_DEPTH))?;
+                    Some(h + h_step / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4))
+                })();
+                h = next.unwrap_or(normal).max(MIN_DEPTH);
+            }
+            depth[i] = h;
+        }
+        State {
+            discharge: vec![discharge; n],
+            depth,
+        }
+    }
+
+    /// Continuity and momentum residuals of the cell between nodes `j` and
+    /// `j + 1`, from the old state and the new values
+    /// `[q_j, h_j, q_j+1, h_j+1]`.
+    fn cell_residuals(&self, old: &State, j: usize, new: [f64; 4], dt: f64) -> [f64; 2] {
+        let (theta, dx) = (self.theta, self.dx());
+        let c = &self.channel;
+        let [q0, h0, q1, h1] = new;
+        let (p0, g0) = (old.discharge[j], old.depth[j]);
+        let (p1, g1) = (old.discharge[j + 1], old.depth[j + 1]);
+        let (a0, a1, b0, b1) = (c.area(h0), c.area(h1), c.area(g0), c.area(g1));
+        let weighted = |new: f64, old: f64| theta * new + (1.0 - theta) * old;
+
+        let continuity = (a0 - b0 + a1 - b1) / (2.0 * dt) + weighted(q1 - q0, p1 - p0) / dx;
+
+        let area = weighted(0.5 * (a0 + a1), 0.5 * (b0 + b1));
+        let convection = weighted(q1 * q1 / a1 - q0 * q0 / a0, p1 * p1 / b1 - p0 * p0 / b0) / dx;
+        let pressure = weighted(h1 - h0, g1 - g0) / dx;
+        let friction = weighted(
+            0.5 * (self.friction_slope(q0, h0) + self.friction_slope(q1, h1)),
+            0.5 * (self.friction_slope(p0, g0) + self.friction_slope(p1, g1)),
+        );
+        let momentum = (q0 - p0 + q1 - p1) / (2.0 * dt)
+            + convection
+            + GRAVITY * area * (pressure + friction - c.bed_slope);
+        [continuity, momentum]
+    }
+
+    /// Advances `state` by `dt` seconds to the boundary values `inflow`
+    /// (m³/s) and `outlet_depth` (m).
+    fn advance(&self, old: &State, inflow: f64, outlet_depth: f64, dt: f64) -> Result<State> {
+        let n = self.segments + 1;
+        let size = 2 * n;
+        let mut new = old.clone();
+        for iteration in 1..=MAX_ITERATIONS {
+            let mut matrix = BandMatrix::new(size);
+            let mut rhs = vec![0.0; size];
+            // Unknowns are ordered q0, h0, q1, h1, ...; row 0 is the
+            // upstream boundary, rows 2j+1 and 2j+2 the equations of cell j
+            // and the last row the downstream boundary.
+            matrix.set(0, 0, 1.0);
+            rhs[0] = -(new.discharge[0] - inflow);
+            for j in 0..n - 1 {
+                let values = [
+                    new.discharge[j],
+                    new.depth[j],
+                    new.discharge[j + 1],
+                    new.depth[j + 1],
+                ];
+                let residual = self.cell_residuals(old, j, values, dt);
+                for (v, value) in values.iter().enumerate() {
+                    let step = 1e-7 * value.abs().max(1.0);
+                    let mut shifted = values;
+                    shifted[v] += step;
+                    let perturbed = self.cell_residuals(old, j, shifted, dt);
+                    for e in 0..2 {
+                        matrix.set(
+                            2 * j + 1 + e,
+                            2 * j + v,
+                            (perturbed[e] - residual[e]) / step,
+                        );
+                    }
+                }
+                rhs[2 * j + 1] = -residual[0];
+                rhs[2 * j + 2] = -residual[1];
+            }
+            matrix.set(size - 1, size - 1, 1.0);
+            rhs[size - 1] = -(new.depth[n - 1] - outlet_depth);
+
+            let delta = matrix
+                .solve(rhs)
+                .ok_or_else(|| Error::Data("The dynamic wave equations became singular.".into()))?;
+            let mut change: f64 = 0.0;
+            for i in 0..n {
+                new.discharge[i] += delta[2 * i];
+                new.depth[i] = (new.depth[i] + delta[2 * i + 1]).max(MIN_DEPTH);
+                let scale = old.discharge[i].abs().max(1.0);
+                change = change
+                    .max(delta[2 * i].abs() / scale)
+                    .max(delta[2 * i + 1].abs());
+            }
+            if !change.is_finite() {
+                break;
+            }
+            trace!("Dynamic wave: Newton iteration {iteration}, largest change {change:.3e}");
+            if change < 1e-8 {
+                return Ok(new);
+            }
+        }
+        warning!("Dynamic wave: no convergence after {MAX_ITERATIONS} Newton iterations");
+        Err(Error::Convergence(
+            "The dynamic wave solution did not converge; reduce the time step or \
+             check that the flow is subcritical."
+                .into(),
+        ))
+    }
+}
+
+/// Routes `inflow` (m³/s) through `reach` against the downstream
+/// water-surface elevations `downstream_stage` (m, same length), with time
+/// step `dt` (s).
+pub fn dynamic_wave_rs(
+    reach: &DynamicWaveReach,
+    inflow: &[f64],
+    downstream_stage: &[f64],
+    dt: f64,
+) -> Result<DynamicWaveResult> {
+    if inflow.len() != downstream_stage.len() {
+        return Err(Error::InvalidParameter(format!(
+            "Got {} inflows for {} downstream stages.",
+            inflow.len(),
+            downstream_stage.len()
+        )));
+    }
+    if dt.is_nan() || dt <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The time step must be positive.".into(),
+        ));
+    }
+    let n = reach.segments + 1;
+    let mut result = DynamicWaveResult {
+        stations: reach.stations(),
+        outflow: Vec::with_capacity(inflow.len()),
+        upstream_stage: Vec::with_capacity(inflow.len()),
+        max_stage: vec![f64::NEG_INFINITY; n],
+        min_discharge: vec![f64::INFINITY; n],
+    };
+    let mut state: Option<State> = None;
+    for (&q, &stage) in inflow.iter().zip(downstream_stage) {
+        let outlet_depth = reach.outlet_depth(stage)?;
+        let next = match &state {
+            None => reach.steady_profile(q, outlet_depth),
+            Some(old) => reach.advance(old, q, outlet_depth, dt)?,
+        };
+        result.outflow.push(next.discharge[n - 1]);
+        result.upstream_stage.push(reach.bed(0) + next.depth[0]);
+        for (i, (max, h)) in result.max_stage.iter_mut().zip(&next.depth).enumerate() {
+            *max = max.max(reach.bed(i) + h);
+        }
+        for (min, q) in result.min_discharge.iter_mut().zip(&next.discharge) {
+            *min = min.min(*q);
+        }
+        state = Some(next);
+    }
+    Ok(result)
+}
+
+/// Steady backwater profiles through `reach` for each `discharge` (m³/s,
+/// negative for flow driven upstream) against the matching
+/// `downstream_stage` (m), such as a tide slow enough for the reach to
+/// stay in equilibrium.
+pub fn backwater_rs(
+    reach: &DynamicWaveReach,
+    discharge: &[f64],
+    downstream_stage: &[f64],
+) -> Result<BackwaterResult> {
+    if discharge.len() != downstream_stage.len() {
+        return Err(Error::InvalidParameter(format!(
+            "Got {} discharges for {} downstream stages.",
+            discharge.len(),
+            downstream_stage.len()
+        )));
+    }
+    let stage = discharge
+        .iter()
+        .zip(downstream_stage)
+        .map(|(&q, &stage)| {
+            let profile = reach.steady_profile(q, reach.outlet_depth(stage)?);
+            Ok(profile
+                .depth
+                .iter()
+                .enumerate()
+                .map(|(i, h)| reach.bed(i) + h)
+                .collect())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(BackwaterResult {
+        stations: reach.stations(),
+        stage,
+    })
+}
+
+/// Square matrix with two sub- and two super-diagonals, stored by columns
+/// with room for the fill-in of partial pivoting (as LAPACK's `gbtrf`).
+struct BandMatrix {
+    size: usize,
+    columns: Vec<[f64; 7]>,
+}
+
+impl BandMatrix {
+    const LOWER: usize = 2;
+    const UPPER: usize = 2;
+
+    fn new(size: usize) -> Self {
+        BandMatrix {
+            size,
+            columns: vec![[0.0; 7]; size],
+        }
+    }
+
+    fn index(i: usize, j: usize) -> usize {
+        (Self::LOWER + Self::UPPER + i) - j
+    }
+
+    fn get(&self, i: usize, j: usize) -> f64 {
+        self.columns[j][Self::index(i, j)]
+    }
+
+    fn set(&mut self, i: usize, j: usize, value: f64) {
+        self.columns[j][Self::index(i, j)] = value;
+    }
+
+    /// Solves `A x = b` by Gaussian elimination with partial pivoting.
+    fn solve(mut self, mut b: Vec<f64>) -> Option<Vec<f64>> {
+        let n = self.size;
+        let reach = Self::LOWER + Self::UPPER;
+        for k in 0..n {
+            let last_row = (k + Self::LOWER).min(n - 1);
+            let pivot = (k..=last_row)
+                .max_by(|&r, &s| self.get(r, k).abs().total_cmp(&self.get(s, k).abs()))?;
+            if self.get(pivot, k).abs() < 1e-300 {
+                return None;
+            }
+            let last_column = (k + reach).min(n - 1);
+            if pivot != k {
+                for j in k..=last_column {
+                    let (a, c) = (self.get(k, j), self.get(pivot, j));
+                    self.set(k, j, c);
+                    self.set(pivot, j, a);
+                }
+                b.swap(k, pivot);
+            }
+            for i in k + 1..=last_row {
+                let factor = self.get(i, k) / self.get(k, k);
+                if factor == 0.0 {
+                    continue;
+                }
+                for j in k..=last_column {
+                    let value = self.get(i, j) - factor * self.get(k, j);
+                    self.set(i, j, value);
+                }
+                b[i] -= factor * b[k];
+            }
+        }
+        let mut x = vec![0.0; n];
+        for k in (0..n).rev() {
+            let last_column = (k + reach).min(n - 1);
+            let sum: f64 = (k + 1..=last_column).map(|j| self.get(k, j) * x[j]).sum();
+            x[k] = (b[k] - sum) / self.get(k, k);
+        }
+        Some(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel() -> TrapezoidalChannel {
+        TrapezoidalChannel {
+            bottom_width: 10.0,
+            side_slope: 2.0,
+            manning_n: 0.03,
+            bed_slope: 0.0005,
+        }
+    }
+
+    fn reach() -> DynamicWaveReach {
+        DynamicWaveReach::new(channel(), 5000.0, 20, 0.6, 100.0).unwrap()
+    }
+
+    /// Volume held in the reach (m³), with the cell areas of the scheme.
+    fn volume(reach: &DynamicWaveReach, state: &State) -> f64 {
+        let c = &reach.channel;
+        state
+            .depth
+            .windows(2)
+            .map(|h| 0.5 * (c.area(h[0]) + c.area(h[1])) * reach.dx())
+            .sum()
+    }
+
+    #[test]
+    fn uniform_inflow_stays_at_normal_depth() {
+        let reach = reach();
+        let normal = channel().normal_depth(25.0);
+        let n = 48;
+        let result =
+            dynamic_wave_rs(&reach, &vec![25.0; n], &vec![100.0 + normal; n], 600.0).unwrap();
+        assert_eq!(result.stations.len(), 21);
+        assert_eq!(result.stations[20], 5000.0);
+        for t in 0..n {
+            assert!((result.outflow[t] - 25.0).abs() < 1e-6, "{t}");
+            let depth = result.upstream_stage[t] - reach.bed(0);
+            assert!((depth - normal).abs() < 1e-6, "{t}: {depth} != {normal}");
+        }
+        for (i, stage) in result.max_stage.iter().enumerate() {
+            assert!((stage - reach.bed(i) - normal).abs() < 1e-6, "{i}");
+        }
+        assert!(result.min_discharge.iter().all(|q| (q - 25.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn backwater_rises_towards_a_high_downstream_stage() {
+        let reach = reach();
+        let normal = channel().normal_depth(25.0);
+        let profile = backwater_rs(&reach, &[25.0], &[100.0 + normal + 2.0]).unwrap();
+        let depths: Vec<f64> = profile.stage[0]
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s - reach.bed(i))
+            .collect();
+        // An M1 profile: deeper than normal, deepening downstream.
+        assert!(depths.windows(2).all(|w| w[0] < w[1]));
+        assert!(depths[0] > normal && depths[0] < normal + 2.0);
+    }
+
+    #[test]
+    fn storage_closes_against_boundary_flows() {
+        let reach = reach();
+        let dt = 300.0;
+        let normal = channel().normal_depth(10.0);
+        let mut state = reach.steady_profile(10.0, normal);
+        let theta = reach.theta;
+        for t in 1..60 {
+            let inflow = 10.0 + 40.0 * (-((t as f64 - 15.0) / 6.0).powi(2)).exp();
+            let next = reach.advance(&state, inflow, normal, dt).unwrap();
+            let net = |s: &State| s.discharge[0] - s.discharge[reach.segments];
+            let expected = dt * (theta * net(&next) + (1.0 - theta) * net(&state));
+            let change = volume(&reach, &next) - volume(&reach, &state);
+            assert!(
+                (change - expected).abs() < 1e-6 * volume(&reach, &state),
+                "{t}: {change} != {expected}"
+            );
+            state = next;
+        }
+    }
+
+    #[test]
+    fn non_convergent_steps_are_reported() {
+        // A steep channel whose flood turns supercritical.
+        let steep = TrapezoidalChannel {
+            bed_slope: 0.05,
+            ..channel()
+        };
+        let reach = DynamicWaveReach::new(steep, 5000.0, 20, 0.55, 100.0).unwrap();
+        let inflow = [1.0, 500.0, 2000.0];
+        match dynamic_wave_rs(&reach, &inflow, &[100.5; 3], 3600.0) {
+            Err(Error::Convergence(msg)) => assert!(msg.contains("subcritical"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_setups() {
+        assert!(DynamicWaveReach::new(channel(), 0.0, 10, 0.6, 0.0).is_err());
+        assert!(DynamicWaveReach::new(channel(), 100.0, 10, 0.4, 0.0).is_err());
+        let reach = reach();
+        for (inflow, stage, dt) in [
+            (vec![1.0, 2.0], vec![101.0], 60.0),
+            (vec![1.0], vec![101.0], 0.0),
+            (vec![1.0], vec![100.0], 60.0),
+        ] {
+            assert!(matches!(
+                dynamic_wave_rs(&reach, &inflow, &stage, dt),
+                Err(Error::InvalidParameter(_))
+            ));
+        }
+    }
+}
@@ -1,9 +1,8 @@
-use pyo3::prelude::*;
-
+pub mod dynamic_wave;
+pub mod kinematic_wave;
 pub mod muskingum;
-
-#[pymodule]
-pub fn init_reach(m: &Bound<'_, PyModule>) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(muskingum::muskingum_routing, m)?)?;
-    Ok(())
-}
+#[cfg(feature = "python")]
+pub mod python;
+pub mod result;
+pub mod tide;
+pub mod transmission_loss;
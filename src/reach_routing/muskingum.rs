@@ -1,62 +1,481 @@
-use pyo3::prelude::*;
-use pyo3::types::PyDelta;
-use std::time::Duration;
-
-#[pyfunction]
-pub fn muskingum_routing(
-    py: Python,
-    inflow: Vec<f64>,
-    k: Py<PyDelta>,
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::log::{debug, warning};
+
+/// Routes `inflow` through a reach divided into `sub_reaches` identical
+/// Muskingum sub-reaches.
+///
+/// `k` is the storage constant of the whole reach in seconds; each sub-reach
+/// uses `k / sub_reaches`. `initial_outflow` is the outflow of every
+/// sub-reach at the first time step and defaults to the first inflow value.
+/// The outflow is stored in the precision of the inflow.
+pub fn muskingum_cascade_rs<T: Float>(
+    inflow: &[T],
+    dt: f64,
+    k: f64,
     x: f64,
-    time_step: Py<PyDelta>,
-    sub_reaches: i64,
-    initial_outflow: f64,
-) -> PyResult<Vec<f64>> {
-    if !(0.0..=0.5).contains(&x) {
-        py.import("warnings")?.call_method1(
-            "warn",
-            ("`x` is outside the recommended range [0.0, 0.5].",),
-        )?;
+    sub_reaches: usize,
+    initial_outflow: Option<f64>,
+) -> Vec<T> {
+    let k_sub = k / sub_reaches as f64;
+    log_coefficients(dt, k_sub, x, sub_reaches);
+    let mut outflow = muskingum_routing_rs(inflow, dt, k_sub, x, initial_outflow);
+    for _ in 1..sub_reaches {
+        outflow = muskingum_routing_rs(&outflow, dt, k_sub, x, None)
     }
-    let time_step_duration: Duration = time_step.extract(py)?;
-    let dt_s: f64 = time_step_duration.as_secs_f64();
+    outflow
+}
 
-    let k_duration: Duration = k.extract(py)?;
-    let k_s: f64 = k_duration.as_secs_f64() / sub_reaches as f64;
+/// Checks Muskingum inputs for physical sense: a non-empty inflow, a
+/// positive time step, a non-negative `k` (s) and at least one sub-reach.
+/// Unless `allow_negative_c0` is set, also rejects a time step shorter than
+/// `2 K x` for each sub-reach, for which the inflow coefficient `c0` is
+/// negative and the outflow dips below its initial value as the inflow
+/// rises.
+pub fn check_muskingum<T>(
+    inflow: &[T],
+    dt: f64,
+    k: f64,
+    x: f64,
+    sub_reaches: i64,
+    allow_negative_c0: bool,
+) -> Result<()> {
+    if inflow.is_empty() {
+        return Err(Error::InvalidParameter(
+            "The inflow series must not be empty.".into(),
+        ));
+    }
+    if dt.is_nan() || dt <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The time step must be positive.".into(),
+        ));
+    }
+    if k.is_nan() || k < 0.0 {
+        return Err(Error::InvalidParameter("`k` must not be negative.".into()));
+    }
+    if x.is_nan() {
+        return Err(Error::InvalidParameter("`x` must be a number.".into()));
+    }
+    if sub_reaches < 1 {
+        return Err(Error::InvalidParameter(
+            "`sub_reaches` must be at least 1.".into(),
+        ));
+    }
+    let (c0, _, _) = muskingum_coefficients(dt, k / sub_reaches as f64, x);
+    if c0 < 0.0 && !allow_negative_c0 {
+        return Err(Error::InvalidParameter(format!(
+            "The routing coefficient c0 = {c0:.4} is negative: the time step must be at least \
+             2 K x / sub_reaches ({:.1} s). Use more sub-reaches or a longer time step, or \
+             pass `allow_negative_c0=True`.",
+            2.0 * k * x / sub_reaches as f64
+        )));
+    }
+    Ok(())
+}
 
-    let mut outflow = muskingum_routing_rs(inflow, dt_s, k_s, x, Some(initial_outflow));
-    for _ in 1..sub_reaches {
-        outflow = muskingum_routing_rs(outflow, dt_s, k_s, x, None)
+/// Routes `inflow` through `sub_reaches` identical Muskingum sub-reaches
+/// as [`muskingum_cascade_rs`] does and returns the outflow of each
+/// sub-reach, `[sub_reach][step]`. The last series is the reach outflow.
+pub fn muskingum_sub_reach_outflows_rs(
+    inflow: &[f64],
+    dt: f64,
+    k: f64,
+    x: f64,
+    sub_reaches: usize,
+    initial_outflow: Option<f64>,
+) -> Vec<Vec<f64>> {
+    let k_sub = k / sub_reaches as f64;
+    log_coefficients(dt, k_sub, x, sub_reaches);
+    let mut outflows = Vec::with_capacity(sub_reaches);
+    outflows.push(muskingum_routing_rs(inflow, dt, k_sub, x, initial_outflow));
+    for i in 1..sub_reaches {
+        outflows.push(muskingum_routing_rs(&outflows[i - 1], dt, k_sub, x, None));
     }
+    outflows
+}
 
-    Ok(outflow)
+/// Outflow and storage of a Muskingum reach at each time step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MuskingumStates {
+    pub outflow: Vec<f64>,
+    /// Reach storage `K [x I + (1 - x) O]` summed over sub-reaches, in flow
+    /// units × seconds.
+    pub storage: Vec<f64>,
 }
 
-fn muskingum_routing_rs(
-    q_in: Vec<f64>,
+/// Routes `inflow` as [`muskingum_cascade_rs`] does and also records the
+/// reach storage at each step.
+pub fn muskingum_states_rs(
+    inflow: &[f64],
     dt: f64,
     k: f64,
     x: f64,
+    sub_reaches: usize,
     initial_outflow: Option<f64>,
-) -> Vec<f64> {
-    let initial_outflow = initial_outflow.unwrap_or(q_in[0]);
+) -> MuskingumStates {
+    let mut reach = MuskingumReach::new(k, x, sub_reaches, initial_outflow);
+    let mut states = MuskingumStates {
+        outflow: Vec::with_capacity(inflow.len()),
+        storage: Vec::with_capacity(inflow.len()),
+    };
+    for &q in inflow {
+        states.outflow.push(reach.step(q, dt));
+        states.storage.push(reach.storage());
+    }
+    states
+}
+
+/// Logs the coefficients of `sub_reaches` sub-reaches with storage
+/// constant `k_sub`, warning when one is negative.
+fn log_coefficients(dt: f64, k_sub: f64, x: f64, sub_reaches: usize) {
+    let (c0, c1, c2) = muskingum_coefficients(dt, k_sub, x);
+    debug!(
+        "Muskingum: {sub_reaches} sub-reach(es) with K = {k_sub} s, x = {x}, dt = {dt} s: \
+         c0 = {c0:.4}, c1 = {c1:.4}, c2 = {c2:.4}"
+    );
+    if c0 < 0.0 || c2 < 0.0 {
+        warning!(
+            "Muskingum: negative coefficient (c0 = {c0:.4}, c2 = {c2:.4}); dt = {dt} s is outside \
+             [2Kx, 2K(1 - x)] = [{:.1}, {:.1}] s per sub-reach and the outflow may dip or oscillate.",
+            2.0 * k_sub * x,
+            2.0 * k_sub * (1.0 - x)
+        );
+    }
+}
+
+/// Muskingum routing coefficients `(c0, c1, c2)` for a single reach.
+pub fn muskingum_coefficients(dt: f64, k: f64, x: f64) -> (f64, f64, f64) {
     let den: f64 = 2.0 * k * (1.0 - x) + dt;
     let c0 = (dt - 2.0 * k * x) / den;
     let c1 = (dt + 2.0 * k * x) / den;
     let c2 = (2.0 * k * (1.0 - x) - dt) / den;
+    (c0, c1, c2)
+}
 
-    let mut outflow: Vec<f64> = Vec::with_capacity(q_in.len());
+/// Routes `q_in` through a single Muskingum reach with storage constant `k`
+/// and time step `dt` (both in seconds).
+pub fn muskingum_routing_rs<T: Float>(
+    q_in: &[T],
+    dt: f64,
+    k: f64,
+    x: f64,
+    initial_outflow: Option<f64>,
+) -> Vec<T> {
+    if q_in.is_empty() {
+        return Vec::new();
+    }
+    let initial_outflow = initial_outflow.unwrap_or(q_in[0].to_f64());
+    let (c0, c1, c2) = muskingum_coefficients(dt, k, x);
+
+    let mut outflow: Vec<T> = Vec::with_capacity(q_in.len());
     let mut previous_outflow: f64 = initial_outflow;
-    let mut previous_inflow: f64 = q_in[0];
+    let mut previous_inflow: f64 = q_in[0].to_f64();
 
-    outflow.push(initial_outflow);
+    outflow.push(T::from_f64(initial_outflow));
 
-    for &current_inflow in q_in.iter().skip(1) {
+    for current_inflow in q_in.iter().skip(1).map(|q| q.to_f64()) {
         let current_outflow = c0 * current_inflow + c1 * previous_inflow + c2 * previous_outflow;
-        outflow.push(current_outflow);
+        outflow.push(T::from_f64(current_outflow));
         previous_outflow = current_outflow;
         previous_inflow = current_inflow;
     }
 
     outflow
 }
+
+/// Stateful Muskingum reach advanced one time step at a time, for use in
+/// routing networks. Produces the same results as [`muskingum_cascade_rs`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MuskingumReach {
+    /// Storage constant of the whole reach (s).
+    pub k: f64,
+    /// Weighting factor.
+    pub x: f64,
+    /// Number of identical sub-reaches.
+    pub sub_reaches: usize,
+    /// Outflow at the first time step; defaults to the first inflow.
+    pub initial_outflow: Option<f64>,
+    /// `(previous inflow, previous outflow)` of each sub-reach.
+    state: Vec<(f64, f64)>,
+}
+
+impl MuskingumReach {
+    pub fn new(k: f64, x: f64, sub_reaches: usize, initial_outflow: Option<f64>) -> Self {
+        MuskingumReach {
+            k,
+            x,
+            sub_reaches: sub_reaches.max(1),
+            initial_outflow,
+            state: Vec::new(),
+        }
+    }
+
+    /// Clears the routing state so the next step starts a new simulation.
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+
+    /// Advances the reach by `dt` seconds and returns the outflow at the end
+    /// of the step. The first call initialises the state.
+    pub fn step(&mut self, inflow: f64, dt: f64) -> f64 {
+        if self.state.is_empty() {
+            log_coefficients(
+                dt,
+                self.k / self.sub_reaches as f64,
+                self.x,
+                self.sub_reaches,
+            );
+            let q0 = self.initial_outflow.unwrap_or(inflow);
+            self.state = (0..self.sub_reaches)
+                .map(|i| (if i == 0 { inflow } else { q0 }, q0))
+                .collect();
+            return q0;
+        }
+        let (c0, c1, c2) = muskingum_coefficients(dt, self.k / self.sub_reaches as f64, self.x);
+        let mut q = inflow;
+        for (previous_inflow, previous_outflow) in self.state.iter_mut() {
+            let outflow = c0 * q + c1 * *previous_inflow + c2 * *previous_outflow;
+            *previous_inflow = q;
+            *previous_outflow = outflow;
+            q = outflow;
+        }
+        q
+    }
+
+    /// Routes a block of `inflow` continuing from the current state and
+    /// returns the outflow at each step, so a long record can be routed
+    /// block by block with the same result as in one pass.
+    pub fn route(&mut self, inflow: &[f64], dt: f64) -> Vec<f64> {
+        inflow.iter().map(|&q| self.step(q, dt)).collect()
+    }
+
+    /// `(previous inflow, previous outflow)` of each sub-reach (empty
+    /// before the first step).
+    pub fn state(&self) -> &[(f64, f64)] {
+        &self.state
+    }
+
+    /// Restores a state returned by [`MuskingumReach::state`]. Ignored
+    /// unless it has one entry per sub-reach, or is empty.
+    pub fn set_state(&mut self, state: Vec<(f64, f64)>) {
+        if state.is_empty() || state.len() == self.sub_reaches {
+            self.state = state;
+        }
+    }
+
+    /// Outflow of each sub-reach at the end of the last step (empty before
+    /// the first step). The last value is the reach outflow.
+    pub fn outflows(&self) -> Vec<f64> {
+        self.state.iter().map(|&(_, o)| o).collect()
+    }
+
+    /// Overwrites the sub-reach outflows, e.g. after a data assimilation
+    /// update. The inflow of each sub-reach below the first follows the
+    /// outflow of the one above. Ignored before the first step.
+    pub fn set_outflows(&mut self, outflows: &[f64]) {
+        if self.state.len() != outflows.len() {
+            return;
+        }
+        for i in 0..outflows.len() {
+            self.state[i].1 = outflows[i];
+            if i + 1 < outflows.len() {
+                self.state[i + 1].0 = outflows[i];
+            }
+        }
+    }
+
+    /// Total storage `K [x I + (1 - x) O]` summed over sub-reaches, in
+    /// flow units × seconds.
+    pub fn storage(&self) -> f64 {
+        let k_sub = self.k / self.sub_reaches as f64;
+        self.state
+            .iter()
+            .map(|&(i, o)| k_sub * (self.x * i + (1.0 - self.x) * o))
+            .sum()
+    }
+}
+
+/// Muskingum `k` (s) and `x` of a prismatic reach of `length` (m) by the
+/// Muskingum–Cunge method: the numerical diffusion of the scheme matches
+/// the physical diffusion of the flood wave at `reference_flow` (m³/s),
+/// giving `k = L / c` and `x = ½ (1 − q0 / (B S c L))` for celerity `c`
+/// and top width `B` at the normal depth of `q0`. `x` is negative for
+/// reaches too long for the flow and is returned unclamped.
+pub fn muskingum_cunge_rs(
+    channel: &TrapezoidalChannel,
+    length: f64,
+    reference_flow: f64,
+) -> Result<(f64, f64)> {
+    if !(reference_flow > 0.0 && length > 0.0) {
+        return Err(Error::InvalidParameter(
+            "Muskingum–Cunge needs a positive reference flow and length.".into(),
+        ));
+    }
+    let depth = channel.normal_depth(reference_flow);
+    let celerity = channel.celerity(depth);
+    let spread = channel.top_width(depth) * channel.bed_slope * celerity * length;
+    Ok((length / celerity, 0.5 * (1.0 - reference_flow / spread)))
+}
+
+/// Division of a Muskingum reach into sub-reaches and of each time step
+/// into sub-steps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MuskingumDiscretization {
+    pub sub_reaches: usize,
+    pub sub_steps: usize,
+    /// Storage constant of each sub-reach and length of each sub-step (s).
+    pub k_sub: f64,
+    pub dt_sub: f64,
+    /// `dt_sub / k_sub`, the Courant number of the equivalent
+    /// Muskingum–Cunge scheme (infinite for `k = 0`).
+    pub courant: f64,
+    pub c0: f64,
+    pub c1: f64,
+    pub c2: f64,
+}
+
+impl MuskingumDiscretization {
+    /// Whether all routing coefficients are non-negative, so the outflow
+    /// neither dips nor oscillates. A reach with `k = 0` passes the inflow
+    /// through unchanged and is always accurate.
+    pub fn is_accurate(&self) -> bool {
+        self.k_sub == 0.0 || (self.c0 >= 0.0 && self.c2 >= 0.0)
+    }
+}
+
+/// Chooses the number of sub-reaches and sub-steps, each at most
+/// `max_divisions`, that bring the Courant number `dt_sub / k_sub` closest
+/// to one, using the fewest divisions among equally good choices. The
+/// routing coefficients are non-negative, and so the outflow free of dips
+/// and oscillations, whenever the Courant number lies in
+/// `[2 x, 2 (1 - x)]`; a warning is logged if no choice within
+/// `max_divisions` achieves this.
+pub fn muskingum_discretization_rs(
+    dt: f64,
+    k: f64,
+    x: f64,
+    max_divisions: usize,
+) -> Result<MuskingumDiscretization> {
+    check_muskingum(&[0.0], dt, k, x, 1, true)?;
+    if !(0.0..=0.5).contains(&x) {
+        return Err(Error::InvalidParameter(
+            "`x` must be between 0.0 and 0.5.".into(),
+        ));
+    }
+    if max_divisions == 0 {
+        return Err(Error::InvalidParameter(
+            "`max_divisions` must be at least 1.".into(),
+        ));
+    }
+    let (mut sub_reaches, mut sub_steps) = (1, 1);
+    if k > 0.0 {
+        let mut best = (f64::INFINITY, usize::MAX);
+        for n in 1..=max_divisions {
+            for m in 1..=max_divisions {
+                let miss = (n as f64 * dt / (m as f64 * k)).ln().abs();
+                // Round so that float noise does not favour more divisions.
+                let candidate = ((miss * 1e9).round(), n * m);
+                if candidate.0 < best.0 || (candidate.0 == best.0 && candidate.1 < best.1) {
+                    best = candidate;
+                    (sub_reaches, sub_steps) = (n, m);
+                }
+            }
+        }
+    }
+    let k_sub = k / sub_reaches as f64;
+    let dt_sub = dt / sub_steps as f64;
+    let (c0, c1, c2) = muskingum_coefficients(dt_sub, k_sub, x);
+    let discretization = MuskingumDiscretization {
+        sub_reaches,
+        sub_steps,
+        k_sub,
+        dt_sub,
+        courant: dt_sub / k_sub,
+        c0,
+        c1,
+        c2,
+    };
+    if !discretization.is_accurate() {
+        warning!(
+            "Muskingum: no discretization with at most {max_divisions} divisions keeps the \
+             coefficients non-negative (best: {sub_reaches} sub-reach(es), {sub_steps} \
+             sub-step(s), c0 = {c0:.4}, c2 = {c2:.4})."
+        );
+    }
+    Ok(discretization)
+}
+
+/// Routes `inflow` as [`muskingum_states_rs`] does, but advances each time
+/// step `dt` in `sub_steps` equal sub-steps with the inflow interpolated
+/// linearly in between. Outflow and storage are reported at the end of
+/// each full step.
+pub fn muskingum_sub_stepped_rs(
+    inflow: &[f64],
+    dt: f64,
+    k: f64,
+    x: f64,
+    sub_reaches: usize,
+    sub_steps: usize,
+    initial_outflow: Option<f64>,
+) -> MuskingumStates {
+    let sub_steps = sub_steps.max(1);
+    let dt_sub = dt / sub_steps as f64;
+    let mut reach = MuskingumReach::new(k, x, sub_reaches, initial_outflow);
+    let mut states = MuskingumStates {
+        outflow: Vec::with_capacity(inflow.len()),
+        storage: Vec::with_capacity(inflow.len()),
+    };
+    let mut previous = inflow.first().copied().unwrap_or(0.0);
+    for (t, &q) in inflow.iter().enumerate() {
+        let outflow = if t == 0 {
+            reach.step(q, dt_sub)
+        } else {
+            let mut outflow = 0.0;
+            for j in 1..=sub_steps {
+                let fraction = j as f64 / sub_steps as f64;
+                outflow = reach.step(previous + fraction * (q - previous), dt_sub);
+            }
+            outflow
+        };
+        states.outflow.push(outflow);
+        states.storage.push(reach.storage());
+        previous = q;
+    }
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::{UnitSystem, FT_TO_M};
+
+    #[test]
+    fn cunge_parameters_match_a_wide_channel() {
+        // Wide rectangular channel: c = 5/3 v and B S c L = 5/3 q0 L / h.
+        let channel =
+            TrapezoidalChannel::from_units(200.0, 0.0, 0.03, 0.0005, UnitSystem::Si).unwrap();
+        let (k, x) = muskingum_cunge_rs(&channel, 10_000.0, 150.0).unwrap();
+        let depth = channel.normal_depth(150.0);
+        let celerity = channel.celerity(depth);
+        assert!((k - 10_000.0 / celerity).abs() < 1e-9);
+        let wide = 0.5 * (1.0 - 150.0 / (200.0 * 0.0005 * celerity * 10_000.0));
+        assert!((x - wide).abs() < 1e-12);
+        assert!(x > 0.0 && x < 0.5);
+    }
+
+    #[test]
+    fn cunge_parameters_do_not_depend_on_the_unit_system() {
+        let si = TrapezoidalChannel::from_units(20.0, 2.0, 0.035, 0.001, UnitSystem::Si).unwrap();
+        let us = TrapezoidalChannel::from_units(20.0 / FT_TO_M, 2.0, 0.035, 0.001, UnitSystem::Us)
+            .unwrap();
+        let (k_si, x_si) = muskingum_cunge_rs(&si, 5_000.0, 40.0).unwrap();
+        let (k_us, x_us) = muskingum_cunge_rs(&us, 5_000.0, 40.0).unwrap();
+        assert!((k_si - k_us).abs() < 1e-6 * k_si);
+        assert!((x_si - x_us).abs() < 1e-9);
+        match muskingum_cunge_rs(&si, 5_000.0, 0.0) {
+            Err(Error::InvalidParameter(msg)) => assert!(msg.contains("reference flow"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+    }
+}
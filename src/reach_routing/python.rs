@@ -0,0 +1,951 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDict, PyType};
+
+use super::dynamic_wave::{backwater_rs, dynamic_wave_rs, DynamicWaveReach};
+use super::kinematic_wave::{
+    kinematic_wave_routing_rs, kinematic_wave_states_rs, KinematicWaveReach,
+};
+use super::muskingum::{
+    check_muskingum, muskingum_cascade_rs, muskingum_cunge_rs, muskingum_discretization_rs,
+    muskingum_states_rs, muskingum_sub_reach_outflows_rs, muskingum_sub_stepped_rs, MuskingumReach,
+};
+use super::result::RoutingResult;
+use super::tide::{tide_stage_rs, Constituent};
+use super::transmission_loss::{transmission_loss_rs, TransmissionLoss};
+use crate::arrow::{f32_array, FloatArray, FloatArrays, FloatSeries};
+use crate::float::Float;
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::parallel::par_map;
+use crate::time::{
+    datetime_to_epoch, epoch_to_datetime, seconds_to_timedelta, timedelta_to_seconds,
+};
+use crate::units::UnitSystem;
+
+/// Routed hydrograph with its inputs, parameters and diagnostics.
+#[pyclass(name = "RoutingResult", module = "rustflow.reach")]
+pub struct PyRoutingResult {
+    inner: RoutingResult,
+    /// Time of the first value (seconds since the Unix epoch).
+    start: Option<f64>,
+    units: Option<UnitSystem>,
+}
+
+impl PyRoutingResult {
+    fn time<'py>(&self, py: Python<'py>, step: usize) -> PyResult<Bound<'py, PyAny>> {
+        let offset = step as f64 * self.inner.dt;
+        Ok(match self.start {
+            Some(start) => epoch_to_datetime(py, start + offset)?.into_any(),
+            None => seconds_to_timedelta(py, offset)?.into_any(),
+        })
+    }
+}
+
+#[pymethods]
+impl PyRoutingResult {
+    /// str: Routing method, `"muskingum"` or `"kinematic_wave"`.
+    #[getter]
+    fn method(&self) -> String {
+        self.inner.method.clone()
+    }
+
+    /// list[float]: Inflow at each step.
+    #[getter]
+    fn inflow(&self) -> Vec<f64> {
+        self.inner.inflow.clone()
+    }
+
+    /// list[float]: Routed outflow at each step.
+    #[getter]
+    fn outflow(&self) -> Vec<f64> {
+        self.inner.outflow.clone()
+    }
+
+    /// timedelta: Interval between consecutive values.
+    #[getter]
+    fn time_step<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDelta>> {
+        seconds_to_timedelta(py, self.inner.dt)
+    }
+
+    /// list[datetime] | list[timedelta]: Time of each value: datetimes when
+    /// a start was given, otherwise offsets from the first value.
+    #[getter]
+    fn times<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        (0..self.inner.outflow.len())
+            .map(|i| self.time(py, i))
+            .collect()
+    }
+
+    /// dict[str, Any]: Parameters the reach was routed with, in the units
+    /// they were given; durations are timedeltas.
+    #[getter]
+    fn parameters<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, value) in &self.inner.parameters {
+            match name.as_str() {
+                "k" => dict.set_item(name, seconds_to_timedelta(py, *value)?)?,
+                "sub_reaches" | "segments" => dict.set_item(name, *value as usize)?,
+                _ => dict.set_item(name, value)?,
+            }
+        }
+        if let Some(units) = self.units {
+            dict.set_item("units", units.name())?;
+        }
+        Ok(dict)
+    }
+
+    /// dict[str, list[float]]: Series recorded at each step besides the
+    /// outflow, e.g. `storage`.
+    #[getter]
+    fn states<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, values) in &self.inner.states {
+            dict.set_item(name, values)?;
+        }
+        Ok(dict)
+    }
+
+    /// list[list[float]]: Outflow of each sub-reach, `[sub_reach][step]`;
+    /// empty unless requested with `intermediate=True`.
+    #[getter]
+    fn sub_reach_outflow(&self) -> Vec<Vec<f64>> {
+        self.inner.sub_reach_outflow.clone()
+    }
+
+    /// dict[str, Any]: Peak attenuation, peak lag (timedelta),
+    /// outflow-to-inflow volume ratio and, where storage is recorded, the
+    /// relative continuity error.
+    #[getter]
+    fn diagnostics<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, value) in self.inner.diagnostics() {
+            if name == "peak_lag" {
+                dict.set_item(name, seconds_to_timedelta(py, value)?)?;
+            } else {
+                dict.set_item(name, value)?;
+            }
+        }
+        Ok(dict)
+    }
+
+    /// Largest outflow.
+    ///
+    /// Returns:
+    ///     Optional[float]: The peak, or `None` for an empty result.
+    fn peak(&self) -> Option<f64> {
+        self.inner.peak().map(|(_, q)| q)
+    }
+
+    /// Time of the largest outflow.
+    ///
+    /// Returns:
+    ///     Optional[Union[datetime, timedelta]]: A datetime when a start was
+    ///     given, otherwise the offset from the first value; `None` for an
+    ///     empty result.
+    fn peak_time<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.inner.peak().map(|(i, _)| self.time(py, i)).transpose()
+    }
+
+    /// Outflow volume: the sum of the outflows times the time step.
+    ///
+    /// Returns:
+    ///     float: The volume, in flow units × seconds.
+    fn volume(&self) -> f64 {
+        self.inner.volume()
+    }
+
+    /// Outflow as a NumPy array.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: The outflow series.
+    fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        py.import("numpy")?
+            .call_method1("asarray", (self.inner.outflow.clone(),))
+    }
+
+    /// Plain dict of times, inflow, outflow, states, parameters and
+    /// diagnostics.
+    ///
+    /// Returns:
+    ///     dict[str, Any]: The result as built-in Python objects.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("method", &self.inner.method)?;
+        dict.set_item("time", self.times(py)?)?;
+        dict.set_item("inflow", &self.inner.inflow)?;
+        dict.set_item("outflow", &self.inner.outflow)?;
+        dict.set_item("states", self.states(py)?)?;
+        if !self.inner.sub_reach_outflow.is_empty() {
+            dict.set_item("sub_reach_outflow", &self.inner.sub_reach_outflow)?;
+        }
+        dict.set_item("time_step", self.time_step(py)?)?;
+        dict.set_item("parameters", self.parameters(py)?)?;
+        dict.set_item("diagnostics", self.diagnostics(py)?)?;
+        Ok(dict)
+    }
+
+    /// Plots the inflow and outflow hydrographs with matplotlib (see
+    /// `rustflow.plot.hydrograph`).
+    ///
+    /// Args:
+    ///     precipitation (list[float], optional): Precipitation at each step,
+    ///         drawn as bars on an inverted axis at the top. Defaults to
+    ///         None.
+    ///     events (list[tuple], optional): `(start, end)` times to shade.
+    ///         Defaults to None.
+    ///     ax (matplotlib.axes.Axes, optional): Axes to draw on. Defaults to
+    ///         None, creating a new figure.
+    ///     **kwargs: Further arguments of `rustflow.plot.hydrograph`, e.g.
+    ///         `title` or `flow_label`.
+    ///
+    /// Returns:
+    ///     matplotlib.axes.Axes: The flow axes.
+    ///
+    /// Raises:
+    ///     ImportError: If matplotlib is not installed.
+    #[pyo3(signature = (precipitation=None, events=None, ax=None, **kwargs))]
+    fn plot<'py>(
+        slf: &Bound<'py, Self>,
+        precipitation: Option<Bound<'py, PyAny>>,
+        events: Option<Bound<'py, PyAny>>,
+        ax: Option<Bound<'py, PyAny>>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let kwargs = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(py),
+        };
+        kwargs.set_item("precipitation", precipitation)?;
+        kwargs.set_item("events", events)?;
+        kwargs.set_item("ax", ax)?;
+        py.import("rustflow.plot")?
+            .call_method("_routing_result", (slf,), Some(&kwargs))
+    }
+
+    /// Builds a `pyarrow.Table` of the routed series.
+    ///
+    /// Returns:
+    ///     pyarrow.Table: `time` (timestamps, or offsets from the first
+    ///     value without a start time), `inflow`, `outflow` and one column
+    ///     per recorded state, such as `storage`.
+    ///
+    /// Raises:
+    ///     ImportError: If `pyarrow` is not installed.
+    fn to_arrow<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.py()
+            .import("rustflow.io")?
+            .call_method1("_routing_result_to_arrow", (slf,))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.outflow.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RoutingResult(method={:?}, steps={})",
+            self.inner.method,
+            self.inner.outflow.len()
+        )
+    }
+
+    /// Pickles the result by value.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (RoutingState,))> {
+        let this = slf.borrow();
+        let r = &this.inner;
+        let state = (
+            r.method.clone(),
+            r.inflow.clone(),
+            r.outflow.clone(),
+            r.dt,
+            r.parameters.clone(),
+            r.states.clone(),
+            r.sub_reach_outflow.clone(),
+            this.start,
+            this.units.map(|units| units.name().to_string()),
+        );
+        Ok((slf.get_type().getattr("_from_state")?, (state,)))
+    }
+
+    #[classmethod]
+    fn _from_state(_cls: &Bound<'_, PyType>, state: RoutingState) -> Self {
+        let (method, inflow, outflow, dt, parameters, states, sub_reach_outflow, start, units) =
+            state;
+        PyRoutingResult {
+            inner: RoutingResult {
+                method,
+                inflow,
+                outflow,
+                dt,
+                parameters,
+                states,
+                sub_reach_outflow,
+            },
+            start,
+            units: units.as_deref().and_then(UnitSystem::parse),
+        }
+    }
+}
+
+/// Fields of a `RoutingResult` as pickled: the result's fields, then the
+/// start time and the unit system name.
+type RoutingState = (
+    String,
+    Vec<f64>,
+    Vec<f64>,
+    f64,
+    Vec<(String, f64)>,
+    Vec<(String, Vec<f64>)>,
+    Vec<Vec<f64>>,
+    Option<f64>,
+    Option<String>,
+);
+
+/// Returns `routing` as a `RoutingResult`, or as a dict of the outflow and
+/// state series.
+fn routing_output(
+    py: Python<'_>,
+    routing: RoutingResult,
+    result: bool,
+    start: Option<&Bound<'_, PyDateTime>>,
+    units: Option<UnitSystem>,
+) -> PyResult<PyObject> {
+    if result {
+        let result = PyRoutingResult {
+            inner: routing,
+            start: start.map(datetime_to_epoch),
+            units,
+        };
+        return Ok(Py::new(py, result)?.into_any());
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("outflow", routing.outflow)?;
+    for (name, values) in routing.states {
+        dict.set_item(name, values)?;
+    }
+    if !routing.sub_reach_outflow.is_empty() {
+        dict.set_item("sub_reach_outflow", routing.sub_reach_outflow)?;
+    }
+    Ok(dict.into_any().unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, k, x, time_step, sub_reaches=1, initial_outflow=None, states=false, result=false, start=None, intermediate=false, allow_negative_c0=false, sub_steps=1))]
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_routing(
+    py: Python,
+    inflow: FloatSeries,
+    k: Bound<'_, PyDelta>,
+    x: f64,
+    time_step: Bound<'_, PyDelta>,
+    sub_reaches: i64,
+    initial_outflow: Option<f64>,
+    states: bool,
+    result: bool,
+    start: Option<Bound<'_, PyDateTime>>,
+    intermediate: bool,
+    allow_negative_c0: bool,
+    sub_steps: usize,
+) -> PyResult<PyObject> {
+    if !(0.0..=0.5).contains(&x) {
+        py.import("warnings")?.call_method1(
+            "warn",
+            ("`x` is outside the recommended range [0.0, 0.5].",),
+        )?;
+    }
+    let dt_s: f64 = timedelta_to_seconds(&time_step);
+    let k_s: f64 = timedelta_to_seconds(&k);
+    if sub_steps == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "`sub_steps` must be at least 1.",
+        ));
+    }
+    if sub_steps > 1 && intermediate {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Sub-reach outflows are not available with sub-steps.",
+        ));
+    }
+    let dt_sub = dt_s / sub_steps as f64;
+    check_muskingum(&inflow.0, dt_sub, k_s, x, sub_reaches, allow_negative_c0)?;
+    let sub_reaches = sub_reaches as usize;
+
+    let sub_reach_outflow = if intermediate {
+        muskingum_sub_reach_outflows_rs(&inflow.0, dt_s, k_s, x, sub_reaches, initial_outflow)
+    } else {
+        Vec::new()
+    };
+    if !(states || result) {
+        if intermediate {
+            return Ok(sub_reach_outflow.into_pyobject(py)?.into_any().unbind());
+        }
+        if sub_steps > 1 {
+            let routed = muskingum_sub_stepped_rs(
+                &inflow.0,
+                dt_s,
+                k_s,
+                x,
+                sub_reaches,
+                sub_steps,
+                initial_outflow,
+            );
+            return Ok(routed.outflow.into_pyobject(py)?.into_any().unbind());
+        }
+        let outflow = muskingum_cascade_rs(&inflow.0, dt_s, k_s, x, sub_reaches, initial_outflow);
+        return Ok(outflow.into_pyobject(py)?.into_any().unbind());
+    }
+    let routed = if sub_steps > 1 {
+        muskingum_sub_stepped_rs(
+            &inflow.0,
+            dt_s,
+            k_s,
+            x,
+            sub_reaches,
+            sub_steps,
+            initial_outflow,
+        )
+    } else {
+        muskingum_states_rs(&inflow.0, dt_s, k_s, x, sub_reaches, initial_outflow)
+    };
+    let mut parameters = vec![
+        ("k".to_string(), k_s),
+        ("x".to_string(), x),
+        ("sub_reaches".to_string(), sub_reaches as f64),
+    ];
+    if sub_steps > 1 {
+        parameters.push(("sub_steps".to_string(), sub_steps as f64));
+    }
+    if let Some(q0) = initial_outflow {
+        parameters.push(("initial_outflow".to_string(), q0));
+    }
+    let routing = RoutingResult {
+        method: "muskingum".into(),
+        inflow: inflow.0,
+        outflow: routed.outflow,
+        dt: dt_s,
+        parameters,
+        states: vec![("storage".to_string(), routed.storage)],
+        sub_reach_outflow,
+    };
+    routing_output(py, routing, result, start.as_ref(), None)
+}
+
+/// Routes several inflow series, each through its own Muskingum reach, in
+/// parallel. If every series is a `float32` array, the outflows are
+/// `float32` arrays; otherwise they are lists of floats. `allow_negative_c0`
+/// holds one flag per reach and defaults to rejecting a negative c0 for
+/// every reach.
+#[pyfunction]
+#[pyo3(signature = (inflows, k, x, time_step, sub_reaches, initial_outflow, allow_negative_c0=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_routing_many(
+    py: Python,
+    inflows: Vec<FloatArray>,
+    k: Vec<Bound<'_, PyDelta>>,
+    x: Vec<f64>,
+    time_step: Bound<'_, PyDelta>,
+    sub_reaches: Vec<i64>,
+    initial_outflow: Vec<Option<f64>>,
+    allow_negative_c0: Option<Vec<bool>>,
+) -> PyResult<PyObject> {
+    let n = inflows.len();
+    let allow_negative_c0 = allow_negative_c0.unwrap_or_else(|| vec![false; n]);
+    if k.len() != n
+        || x.len() != n
+        || sub_reaches.len() != n
+        || initial_outflow.len() != n
+        || allow_negative_c0.len() != n
+    {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "One set of reach parameters is required per inflow series.",
+        ));
+    }
+    if x.iter().any(|x| !(0.0..=0.5).contains(x)) {
+        py.import("warnings")?.call_method1(
+            "warn",
+            ("`x` is outside the recommended range [0.0, 0.5].",),
+        )?;
+    }
+    let dt_s = timedelta_to_seconds(&time_step);
+    let reaches = k
+        .iter()
+        .zip(&x)
+        .zip(&sub_reaches)
+        .zip(&initial_outflow)
+        .map(|(((k, &x), &sub_reaches), &initial_outflow)| {
+            (timedelta_to_seconds(k), x, sub_reaches, initial_outflow)
+        })
+        .collect::<Vec<_>>();
+    match FloatArrays::from(inflows) {
+        FloatArrays::F32(inflows) => {
+            let outflows = route_many(py, &inflows, &reaches, dt_s, &allow_negative_c0)?;
+            let arrays = outflows
+                .iter()
+                .map(|outflow| f32_array(py, outflow))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(arrays.into_pyobject(py)?.into_any().unbind())
+        }
+        FloatArrays::F64(inflows) => {
+            let outflows = route_many(py, &inflows, &reaches, dt_s, &allow_negative_c0)?;
+            Ok(outflows.into_pyobject(py)?.into_any().unbind())
+        }
+    }
+}
+
+/// Checks and routes each inflow through its reach `(k, x, sub_reaches,
+/// initial_outflow)` in parallel, keeping the precision of the inflows.
+fn route_many<T: Float>(
+    py: Python,
+    inflows: &[Vec<T>],
+    reaches: &[(f64, f64, i64, Option<f64>)],
+    dt: f64,
+    allow_negative_c0: &[bool],
+) -> PyResult<Vec<Vec<T>>> {
+    for ((inflow, &(k, x, sub_reaches, _)), &allow) in
+        inflows.iter().zip(reaches).zip(allow_negative_c0)
+    {
+        check_muskingum(inflow, dt, k, x, sub_reaches, allow)?;
+    }
+    let jobs: Vec<_> = inflows.iter().zip(reaches).collect();
+    Ok(py.allow_threads(|| {
+        par_map(&jobs, |&(inflow, &(k, x, sub_reaches, initial_outflow))| {
+            muskingum_cascade_rs(inflow, dt, k, x, sub_reaches as usize, initial_outflow)
+        })
+    }))
+}
+
+/// Sub-reaches and sub-steps that bring the Courant number of a Muskingum
+/// reach closest to one, with the resulting coefficients.
+#[pyfunction]
+#[pyo3(signature = (k, x, time_step, max_divisions=100))]
+pub fn muskingum_discretization<'py>(
+    py: Python<'py>,
+    k: Bound<'py, PyDelta>,
+    x: f64,
+    time_step: Bound<'py, PyDelta>,
+    max_divisions: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let d = muskingum_discretization_rs(
+        timedelta_to_seconds(&time_step),
+        timedelta_to_seconds(&k),
+        x,
+        max_divisions,
+    )?;
+    let dict = PyDict::new(py);
+    dict.set_item("sub_reaches", d.sub_reaches)?;
+    dict.set_item("sub_steps", d.sub_steps)?;
+    dict.set_item("sub_reach_k", seconds_to_timedelta(py, d.k_sub)?)?;
+    dict.set_item("sub_time_step", seconds_to_timedelta(py, d.dt_sub)?)?;
+    dict.set_item("courant", d.courant)?;
+    dict.set_item("c0", d.c0)?;
+    dict.set_item("c1", d.c1)?;
+    dict.set_item("c2", d.c2)?;
+    dict.set_item("accurate", d.is_accurate())?;
+    Ok(dict)
+}
+
+/// Muskingum `k` and `x` of a prismatic reach by the Muskingum–Cunge
+/// method, with the geometry and flow in `units`.
+#[pyfunction]
+#[pyo3(signature = (reference_flow, length, bottom_width, side_slope, n, slope, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn muskingum_cunge<'py>(
+    py: Python<'py>,
+    reference_flow: f64,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let (k, x) = muskingum_cunge_rs(
+        &channel,
+        units.length_to_si(length),
+        units.discharge_to_si(reference_flow),
+    )?;
+    let dict = PyDict::new(py);
+    dict.set_item("k", seconds_to_timedelta(py, k)?)?;
+    dict.set_item("x", x)?;
+    Ok(dict)
+}
+
+/// Muskingum reach routing a long record block by block. The routing state
+/// carries over between blocks, so the outflow is the same as routing the
+/// whole record at once with `muskingum_routing`.
+///
+/// Args:
+///     k (timedelta): Storage time constant of the whole reach.
+///     x (float): Weighting factor, typically 0.0-0.5.
+///     time_step (timedelta): Interval between consecutive values.
+///     sub_reaches (int, optional): Number of sub-reaches. Defaults to 1.
+///     initial_outflow (float, optional): Outflow at the first step.
+///         Defaults to the first inflow.
+///     allow_negative_c0 (bool, optional): Accept a time step shorter than
+///         `2 K x / sub_reaches`. Defaults to False.
+#[pyclass(name = "MuskingumReach", module = "rustflow.reach")]
+pub struct PyMuskingumReach {
+    inner: MuskingumReach,
+    dt: f64,
+}
+
+#[pymethods]
+impl PyMuskingumReach {
+    #[new]
+    #[pyo3(signature = (k, x, time_step, sub_reaches=1, initial_outflow=None, allow_negative_c0=false))]
+    fn new(
+        k: Bound<'_, PyDelta>,
+        x: f64,
+        time_step: Bound<'_, PyDelta>,
+        sub_reaches: i64,
+        initial_outflow: Option<f64>,
+        allow_negative_c0: bool,
+    ) -> PyResult<Self> {
+        let dt = timedelta_to_seconds(&time_step);
+        let k = timedelta_to_seconds(&k);
+        // No inflow yet: check the parameters against a single value.
+        check_muskingum(&[0.0], dt, k, x, sub_reaches, allow_negative_c0)?;
+        Ok(PyMuskingumReach {
+            inner: MuskingumReach::new(k, x, sub_reaches as usize, initial_outflow),
+            dt,
+        })
+    }
+
+    /// Routes the next block of inflow.
+    ///
+    /// Args:
+    ///     inflow (Sequence[float]): Consecutive inflow values.
+    ///
+    /// Returns:
+    ///     list[float]: Outflow at each step of the block.
+    fn route(&mut self, py: Python<'_>, inflow: FloatSeries) -> Vec<f64> {
+        py.allow_threads(|| self.inner.route(&inflow.0, self.dt))
+    }
+
+    /// Clears the routing state so the next block starts a new record.
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    /// list[float]: Outflow of each sub-reach at the end of the last block;
+    /// empty before the first.
+    #[getter]
+    fn outflows(&self) -> Vec<f64> {
+        self.inner.outflows()
+    }
+
+    /// float: Reach storage at the end of the last block, in flow units ×
+    /// seconds.
+    #[getter]
+    fn storage(&self) -> f64 {
+        self.inner.storage()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MuskingumReach(k={} s, x={}, sub_reaches={})",
+            self.inner.k, self.inner.x, self.inner.sub_reaches
+        )
+    }
+
+    /// Pickles the parameters and the routing state, so a run can resume
+    /// in another process.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(
+        Bound<'py, PyAny>,
+        (f64, f64, f64, usize, Option<f64>, Vec<(f64, f64)>),
+    )> {
+        let this = slf.borrow();
+        let r = &this.inner;
+        Ok((
+            slf.get_type().getattr("_from_state")?,
+            (
+                r.k,
+                r.x,
+                this.dt,
+                r.sub_reaches,
+                r.initial_outflow,
+                r.state().to_vec(),
+            ),
+        ))
+    }
+
+    #[classmethod]
+    fn _from_state(
+        _cls: &Bound<'_, PyType>,
+        k: f64,
+        x: f64,
+        dt: f64,
+        sub_reaches: usize,
+        initial_outflow: Option<f64>,
+        state: Vec<(f64, f64)>,
+    ) -> Self {
+        let mut inner = MuskingumReach::new(k, x, sub_reaches, initial_outflow);
+        inner.set_state(state);
+        PyMuskingumReach { inner, dt }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, time_step, length, bottom_width, side_slope, n, slope, segments=10, units=UnitSystem::Si, states=false, result=false, start=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn kinematic_wave_routing(
+    py: Python,
+    inflow: FloatSeries,
+    time_step: Bound<'_, PyDelta>,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    segments: usize,
+    units: UnitSystem,
+    states: bool,
+    result: bool,
+    start: Option<Bound<'_, PyDateTime>>,
+) -> PyResult<PyObject> {
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let mut reach = KinematicWaveReach::new(channel, units.length_to_si(length), segments)?;
+    let dt_s = timedelta_to_seconds(&time_step);
+    let inflow_si: Vec<f64> = inflow.0.iter().map(|&q| units.discharge_to_si(q)).collect();
+    let convert = |values: &[f64], f: fn(UnitSystem, f64) -> f64| -> Vec<f64> {
+        values.iter().map(|&v| f(units, v)).collect()
+    };
+    if !(states || result) {
+        let outflow = py.allow_threads(|| kinematic_wave_routing_rs(&inflow_si, dt_s, &mut reach));
+        let outflow = convert(&outflow, UnitSystem::discharge_from_si);
+        return Ok(outflow.into_pyobject(py)?.into_any().unbind());
+    }
+    let routed = py.allow_threads(|| kinematic_wave_states_rs(&inflow_si, dt_s, &mut reach));
+    let routing = RoutingResult {
+        method: "kinematic_wave".into(),
+        inflow: inflow.0,
+        outflow: convert(&routed.outflow, UnitSystem::discharge_from_si),
+        dt: dt_s,
+        parameters: vec![
+            ("length".to_string(), length),
+            ("bottom_width".to_string(), bottom_width),
+            ("side_slope".to_string(), side_slope),
+            ("n".to_string(), n),
+            ("slope".to_string(), slope),
+            ("segments".to_string(), reach.segments as f64),
+        ],
+        states: vec![
+            (
+                "storage".to_string(),
+                convert(&routed.storage, UnitSystem::volume_from_si),
+            ),
+            (
+                "depth".to_string(),
+                convert(&routed.depth, UnitSystem::length_from_si),
+            ),
+            (
+                "velocity".to_string(),
+                convert(&routed.velocity, UnitSystem::velocity_from_si),
+            ),
+        ],
+        sub_reach_outflow: Vec::new(),
+    };
+    routing_output(py, routing, result, start.as_ref(), Some(units))
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, downstream_stage, time_step, length, bottom_width, side_slope, n, slope, segments=20, theta=0.6, outlet_bed=0.0, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn dynamic_wave<'py>(
+    py: Python<'py>,
+    inflow: FloatSeries,
+    downstream_stage: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    segments: usize,
+    theta: f64,
+    outlet_bed: f64,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let reach = DynamicWaveReach::new(
+        channel,
+        units.length_to_si(length),
+        segments,
+        theta,
+        units.length_to_si(outlet_bed),
+    )?;
+    let dt_s = timedelta_to_seconds(&time_step);
+    let inflow_si: Vec<f64> = inflow.0.iter().map(|&q| units.discharge_to_si(q)).collect();
+    let stage_si: Vec<f64> = downstream_stage
+        .0
+        .iter()
+        .map(|&z| units.length_to_si(z))
+        .collect();
+    let result = py.allow_threads(|| dynamic_wave_rs(&reach, &inflow_si, &stage_si, dt_s))?;
+    let length =
+        |values: &[f64]| -> Vec<f64> { values.iter().map(|&z| units.length_from_si(z)).collect() };
+    let dict = PyDict::new(py);
+    dict.set_item(
+        "outflow",
+        result
+            .outflow
+            .iter()
+            .map(|&q| units.discharge_from_si(q))
+            .collect::<Vec<_>>(),
+    )?;
+    dict.set_item("upstream_stage", length(&result.upstream_stage))?;
+    dict.set_item("stations", length(&result.stations))?;
+    dict.set_item("max_stage", length(&result.max_stage))?;
+    dict.set_item(
+        "min_discharge",
+        result
+            .min_discharge
+            .iter()
+            .map(|&q| units.discharge_from_si(q))
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (discharge, downstream_stage, length, bottom_width, side_slope, n, slope, segments=20, outlet_bed=0.0, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn backwater_profile<'py>(
+    py: Python<'py>,
+    discharge: FloatSeries,
+    downstream_stage: FloatSeries,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    segments: usize,
+    outlet_bed: f64,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let reach = DynamicWaveReach::new(
+        channel,
+        units.length_to_si(length),
+        segments,
+        0.5,
+        units.length_to_si(outlet_bed),
+    )?;
+    let discharge_si: Vec<f64> = discharge
+        .0
+        .iter()
+        .map(|&q| units.discharge_to_si(q))
+        .collect();
+    let stage_si: Vec<f64> = downstream_stage
+        .0
+        .iter()
+        .map(|&z| units.length_to_si(z))
+        .collect();
+    let result = py.allow_threads(|| backwater_rs(&reach, &discharge_si, &stage_si))?;
+    let length =
+        |values: &[f64]| -> Vec<f64> { values.iter().map(|&z| units.length_from_si(z)).collect() };
+    let dict = PyDict::new(py);
+    dict.set_item("stations", length(&result.stations))?;
+    dict.set_item(
+        "stage",
+        result.stage.iter().map(|s| length(s)).collect::<Vec<_>>(),
+    )?;
+    dict.set_item(
+        "upstream_stage",
+        result
+            .stage
+            .iter()
+            .map(|s| units.length_from_si(s[0]))
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (steps, time_step, constituents, mean_level=0.0, units=UnitSystem::Si))]
+pub fn tide_stage(
+    steps: usize,
+    time_step: Bound<'_, PyDelta>,
+    constituents: Vec<(String, f64, f64)>,
+    mean_level: f64,
+    units: UnitSystem,
+) -> PyResult<Vec<f64>> {
+    let constituents = constituents
+        .iter()
+        .map(|(name, amplitude, phase)| {
+            Constituent::named(name, units.length_to_si(*amplitude), *phase)
+        })
+        .collect::<crate::error::Result<Vec<_>>>()?;
+    let stage = tide_stage_rs(
+        units.length_to_si(mean_level),
+        &constituents,
+        steps,
+        timedelta_to_seconds(&time_step),
+    )?;
+    Ok(stage.into_iter().map(|z| units.length_from_si(z)).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (flow, time_step, method, rate=None, width=None, length=None, fraction=None, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn transmission_loss(
+    py: Python,
+    flow: FloatSeries,
+    time_step: Bound<'_, PyDelta>,
+    method: &str,
+    rate: Option<f64>,
+    width: Option<f64>,
+    length: Option<f64>,
+    fraction: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let required = |value: Option<f64>, name: &str| {
+        value.ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("Method `{method}` requires `{name}`."))
+        })
+    };
+    let loss = match method {
+        "seepage" => TransmissionLoss::Seepage {
+            rate: units.rate_to_si(required(rate, "rate")?),
+            width: units.length_to_si(required(width, "width")?),
+            length: units.length_to_si(required(length, "length")?),
+        },
+        "lane" => TransmissionLoss::Lane {
+            conductivity: units.rate_to_si(required(rate, "rate")?),
+            width: units.length_to_si(required(width, "width")?),
+            length: units.length_to_si(required(length, "length")?),
+        },
+        "fraction" => TransmissionLoss::Fraction(required(fraction, "fraction")?),
+        other => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown transmission loss method `{other}`."
+            )))
+        }
+    };
+    loss.validate()?;
+    let dt_s = timedelta_to_seconds(&time_step);
+    let flow_si: Vec<f64> = flow.0.iter().map(|&q| units.discharge_to_si(q)).collect();
+    let (remaining, lost) = py.allow_threads(|| transmission_loss_rs(&flow_si, dt_s, &loss));
+    let from_si =
+        |v: Vec<f64>| -> Vec<f64> { v.into_iter().map(|q| units.discharge_from_si(q)).collect() };
+    Ok((from_si(remaining), from_si(lost)))
+}
+
+#[pymodule]
+pub fn init_reach(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(muskingum_routing, m)?)?;
+    m.add_function(wrap_pyfunction!(muskingum_routing_many, m)?)?;
+    m.add_function(wrap_pyfunction!(muskingum_discretization, m)?)?;
+    m.add_function(wrap_pyfunction!(muskingum_cunge, m)?)?;
+    m.add_function(wrap_pyfunction!(kinematic_wave_routing, m)?)?;
+    m.add_function(wrap_pyfunction!(dynamic_wave, m)?)?;
+    m.add_function(wrap_pyfunction!(backwater_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(tide_stage, m)?)?;
+    m.add_function(wrap_pyfunction!(transmission_loss, m)?)?;
+    m.add_class::<PyMuskingumReach>()?;
+    m.add_class::<PyRoutingResult>()?;
+    Ok(())
+}
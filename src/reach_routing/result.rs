@@ -0,0 +1,83 @@
+/// Routed hydrograph with the inputs and settings that produced it, for
+/// reproducible reporting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoutingResult {
+    /// Routing method, e.g. `"muskingum"`.
+    pub method: String,
+    pub inflow: Vec<f64>,
+    pub outflow: Vec<f64>,
+    /// Time step (s).
+    pub dt: f64,
+    /// Numeric parameters of the method, in the order they were given.
+    pub parameters: Vec<(String, f64)>,
+    /// Additional series recorded at each step, such as `storage`.
+    pub states: Vec<(String, Vec<f64>)>,
+    /// Outflow of each sub-reach, `[sub_reach][step]`, when recorded.
+    pub sub_reach_outflow: Vec<Vec<f64>>,
+}
+
+impl RoutingResult {
+    /// Recorded state series `name`, if any.
+    pub fn state(&self, name: &str) -> Option<&[f64]> {
+        self.states
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, values)| values.as_slice())
+    }
+
+    /// Step and value of the largest outflow (the first, if tied).
+    pub fn peak(&self) -> Option<(usize, f64)> {
+        peak(&self.outflow)
+    }
+
+    /// Outflow volume: the sum of the outflows times the time step.
+    pub fn volume(&self) -> f64 {
+        self.outflow.iter().sum::<f64>() * self.dt
+    }
+
+    /// Summary checks of the routed hydrograph:
+    ///
+    /// - `peak_attenuation`: inflow peak less outflow peak;
+    /// - `peak_lag`: time from the inflow peak to the outflow peak (s);
+    /// - `volume_ratio`: outflow over inflow volume;
+    /// - `continuity_error`: with a `storage` state, the inflow volume less
+    ///   the outflow volume and the storage change, by the trapezoidal rule,
+    ///   relative to the inflow volume.
+    pub fn diagnostics(&self) -> Vec<(&'static str, f64)> {
+        let mut diagnostics = Vec::new();
+        if let (Some((i_in, q_in)), Some((i_out, q_out))) = (peak(&self.inflow), self.peak()) {
+            diagnostics.push(("peak_attenuation", q_in - q_out));
+            diagnostics.push(("peak_lag", (i_out as f64 - i_in as f64) * self.dt));
+        }
+        let inflow_volume = self.inflow.iter().sum::<f64>() * self.dt;
+        if inflow_volume != 0.0 {
+            diagnostics.push(("volume_ratio", self.volume() / inflow_volume));
+        }
+        if let Some(storage) = self.state("storage") {
+            let trapezoid = |series: &[f64]| {
+                series.windows(2).map(|w| 0.5 * (w[0] + w[1])).sum::<f64>() * self.dt
+            };
+            let inflow_volume = trapezoid(&self.inflow);
+            if storage.len() > 1 && inflow_volume != 0.0 {
+                let change = storage[storage.len() - 1] - storage[0];
+                diagnostics.push((
+                    "continuity_error",
+                    (inflow_volume - trapezoid(&self.outflow) - change) / inflow_volume,
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+fn peak(series: &[f64]) -> Option<(usize, f64)> {
+    series
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, q)| !q.is_nan())
+        .fold(None, |best, (i, q)| match best {
+            Some((_, b)) if b >= q => best,
+            _ => Some((i, q)),
+        })
+}
@@ -0,0 +1,78 @@
+//! Harmonic tide series for the downstream stage boundary of coastal
+//! reaches.
+
+use std::f64::consts::PI;
+
+use crate::error::{Error, Result};
+
+/// Tidal constituent: a cosine of `amplitude` (m), `period` (h) and
+/// `phase` lag (degrees).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constituent {
+    pub amplitude: f64,
+    pub period: f64,
+    pub phase: f64,
+}
+
+impl Constituent {
+    /// Constituent named by its Darwin symbol (`M2`, `S2`, `N2`, `K2`,
+    /// `K1`, `O1`, `P1` or `M4`, case-insensitive), with its standard
+    /// period.
+    pub fn named(name: &str, amplitude: f64, phase: f64) -> Result<Self> {
+        let period = match name.to_ascii_uppercase().as_str() {
+            "M2" => 12.420_601,
+            "S2" => 12.0,
+            "N2" => 12.658_348,
+            "K2" => 11.967_235,
+            "K1" => 23.934_470,
+            "O1" => 25.819_342,
+            "P1" => 24.065_890,
+            "M4" => 6.210_300,
+            _ => {
+                return Err(Error::InvalidParameter(format!(
+                    "Unknown tidal constituent `{name}`."
+                )))
+            }
+        };
+        Ok(Constituent {
+            amplitude,
+            period,
+            phase,
+        })
+    }
+
+    /// Elevation (m) about the mean level `hours` after the time origin.
+    pub fn elevation(&self, hours: f64) -> f64 {
+        let angle = 2.0 * PI * hours / self.period - self.phase.to_radians();
+        self.amplitude * angle.cos()
+    }
+}
+
+/// Stage (m) at `steps` times `dt` seconds apart, starting at the time
+/// origin of the phases: `mean_level` plus the sum of the `constituents`.
+pub fn tide_stage_rs(
+    mean_level: f64,
+    constituents: &[Constituent],
+    steps: usize,
+    dt: f64,
+) -> Result<Vec<f64>> {
+    if dt.is_nan() || dt <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The time step must be positive.".into(),
+        ));
+    }
+    if constituents
+        .iter()
+        .any(|c| c.period.is_nan() || c.period <= 0.0)
+    {
+        return Err(Error::InvalidParameter(
+            "Tidal constituent periods must be positive.".into(),
+        ));
+    }
+    Ok((0..steps)
+        .map(|i| {
+            let hours = i as f64 * dt / 3600.0;
+            mean_level + constituents.iter().map(|c| c.elevation(hours)).sum::<f64>()
+        })
+        .collect())
+}
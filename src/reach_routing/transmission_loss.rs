@@ -0,0 +1,303 @@
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::units::UnitSystem;
+
+/// Channel transmission loss for ephemeral streams, where flow infiltrates
+/// through the bed into the alluvium. Parameters are in SI units.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransmissionLoss {
+    /// Constant seepage `rate` (m/s) over a bed of `width` × `length` (m).
+    Seepage { rate: f64, width: f64, length: f64 },
+    /// Lane's (1983) regression method, as used in SWAT, for a channel with
+    /// effective bed hydraulic `conductivity` (m/s), `width` and `length`
+    /// (m). Each time step is treated as a flow event lasting one step.
+    Lane {
+        conductivity: f64,
+        width: f64,
+        length: f64,
+    },
+    /// Fixed fraction of the flow lost.
+    Fraction(f64),
+}
+
+impl TransmissionLoss {
+    /// Builds a loss model from the `transmission_loss` field of an element
+    /// table (`"seepage"`, `"lane"` or `"fraction"`), or `None` if absent.
+    ///
+    /// Seepage reads `seepage_rate` (mm/h or in/h), Lane's method reads
+    /// `conductivity` (mm/h or in/h), and both read `loss_width` and
+    /// `loss_length` (m or ft), defaulting to the element's `bottom_width`
+    /// and `length`. The fraction method reads `loss_fraction`.
+    pub fn from_value(value: &Value, units: UnitSystem) -> Result<Option<Self>> {
+        let Some(method) = value.opt_str_field("transmission_loss")? else {
+            return Ok(None);
+        };
+        let bed = |value: &Value| -> Result<(f64, f64)> {
+            let width = value
+                .opt_f64_field("loss_width")?
+                .or(value.opt_f64_field("bottom_width")?)
+                .ok_or_else(|| Error::Data("Transmission loss needs `loss_width`.".into()))?;
+            let length = value
+                .opt_f64_field("loss_length")?
+                .or(value.opt_f64_field("length")?)
+                .ok_or_else(|| Error::Data("Transmission loss needs `loss_length`.".into()))?;
+            Ok((units.length_to_si(width), units.length_to_si(length)))
+        };
+        let loss = match method {
+            "seepage" => {
+                let (width, length) = bed(value)?;
+                TransmissionLoss::Seepage {
+                    rate: units.rate_to_si(value.f64_field("seepage_rate")?),
+                    width,
+                    length,
+                }
+            }
+            "lane" => {
+                let (width, length) = bed(value)?;
+                TransmissionLoss::Lane {
+                    conductivity: units.rate_to_si(value.f64_field("conductivity")?),
+                    width,
+                    length,
+                }
+            }
+            "fraction" => TransmissionLoss::Fraction(value.f64_field("loss_fraction")?),
+            other => {
+                return Err(Error::Data(format!(
+                    "Unknown transmission loss method `{other}`."
+                )))
+            }
+        };
+        loss.validate()?;
+        Ok(Some(loss))
+    }
+
+    /// Checks that the parameters are physically meaningful.
+    pub fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            TransmissionLoss::Seepage {
+                rate,
+                width,
+                length,
+            } => rate >= 0.0 && width > 0.0 && length > 0.0,
+            TransmissionLoss::Lane {
+                conductivity,
+                width,
+                length,
+            } => conductivity >= 0.0 && width > 0.0 && length > 0.0,
+            TransmissionLoss::Fraction(fraction) => (0.0..=1.0).contains(&fraction),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidParameter(
+                "Transmission loss rates must be non-negative, bed dimensions positive and \
+                 `loss_fraction` within [0, 1]."
+                    .into(),
+            ))
+        }
+    }
+
+    /// Flow lost (m³/s) from flow `q` (m³/s) during a step of `dt` seconds,
+    /// within `[0, q]`.
+    pub fn loss(&self, q: f64, dt: f64) -> f64 {
+        if q <= 0.0 {
+            return 0.0;
+        }
+        let lost = match *self {
+            TransmissionLoss::Seepage {
+                rate,
+                width,
+                length,
+            } => rate * width * length,
+            TransmissionLoss::Lane {
+                conductivity,
+                width,
+                length,
+            } => {
+                // Lane's unit-channel regression in its customary units:
+                // conductivity mm/h, duration h, volume m³, length km, width m.
+                let volume = q * dt;
+                let k_dur = conductivity * 3.6e6 * dt / 3600.0;
+                let a_x = -0.2258 * k_dur;
+                let b_x = (-0.4905 * k_dur / volume).exp();
+                let decay = -2.22 * b_x.ln();
+                let b = (-decay * length / 1000.0 * width).exp();
+                let a = if b_x < 1.0 {
+                    a_x / (1.0 - b_x) * (1.0 - b)
+                } else {
+                    0.0
+                };
+                (volume - (a + b * volume).max(0.0)) / dt
+            }
+            TransmissionLoss::Fraction(fraction) => fraction * q,
+        };
+        lost.clamp(0.0, q)
+    }
+}
+
+/// Applies `loss` to each value of `flow` (m³/s) and returns the remaining
+/// flow and the flow lost. The lost volume of each step is the lost flow
+/// times `dt`.
+pub fn transmission_loss_rs(
+    flow: &[f64],
+    dt: f64,
+    loss: &TransmissionLoss,
+) -> (Vec<f64>, Vec<f64>) {
+    flow.iter()
+        .map(|&q| {
+            let lost = loss.loss(q, dt);
+            (q - lost, lost)
+        })
+        .unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::toml;
+
+    fn parse(text: &str, units: UnitSystem) -> Result<Option<TransmissionLoss>> {
+        TransmissionLoss::from_value(&toml::parse(text)?, units)
+    }
+
+    #[test]
+    fn seepage_and_fraction_are_limited_by_the_flow() {
+        let seepage = TransmissionLoss::Seepage {
+            rate: 1e-5,
+            width: 4.0,
+            length: 1000.0,
+        };
+        assert!((seepage.loss(2.0, 3600.0) - 0.04).abs() < 1e-15);
+        assert_eq!(seepage.loss(0.01, 3600.0), 0.01);
+        assert_eq!(seepage.loss(0.0, 3600.0), 0.0);
+        let fraction = TransmissionLoss::Fraction(0.25);
+        assert_eq!(fraction.loss(8.0, 3600.0), 2.0);
+        assert_eq!(fraction.loss(-1.0, 3600.0), 0.0);
+    }
+
+    #[test]
+    fn lane_method_matches_a_hand_calculation() {
+        let lane = |length| TransmissionLoss::Lane {
+            conductivity: 1e-6,
+            width: 5.0,
+            length,
+        };
+        let lost = lane(2000.0).loss(1.0, 3600.0);
+        assert!((lost - 0.015_816_817_738_547_62).abs() < 1e-12, "{lost}");
+        // Longer channels lose more, but never more than the flow.
+        assert!(lane(20000.0).loss(1.0, 3600.0) > lost);
+        assert!(lane(1e7).loss(1.0, 3600.0) <= 1.0);
+        let dry = TransmissionLoss::Lane {
+            conductivity: 0.0,
+            width: 5.0,
+            length: 2000.0,
+        };
+        assert_eq!(dry.loss(1.0, 3600.0), 0.0);
+    }
+
+    #[test]
+    fn routed_series_conserve_the_flow() {
+        let flow = [0.0, 0.02, 1.0, 3.0];
+        let loss = TransmissionLoss::Seepage {
+            rate: 1e-5,
+            width: 4.0,
+            length: 1000.0,
+        };
+        let (remaining, lost) = transmission_loss_rs(&flow, 600.0, &loss);
+        for ((q, r), l) in flow.iter().zip(&remaining).zip(&lost) {
+            assert!((r + l - q).abs() < 1e-15);
+            assert!(*r >= 0.0);
+        }
+    }
+
+    #[test]
+    fn reads_methods_in_the_element_units() {
+        assert_eq!(parse("k = 1.0", UnitSystem::Si).unwrap(), None);
+        let seepage = parse(
+            "transmission_loss = \"seepage\"\nseepage_rate = 36.0\nbottom_width = 4.0\n\
+             length = 1000.0",
+            UnitSystem::Si,
+        )
+        .unwrap()
+        .unwrap();
+        match seepage {
+            TransmissionLoss::Seepage {
+                rate,
+                width,
+                length,
+            } => {
+                assert!((rate - 1e-5).abs() < 1e-18);
+                assert_eq!((width, length), (4.0, 1000.0));
+            }
+            other => panic!("{other:?}"),
+        }
+        let lane = parse(
+            "transmission_loss = \"lane\"\nconductivity = 1.0\nloss_width = 10.0\n\
+             loss_length = 100.0\nbottom_width = 2.0",
+            UnitSystem::Us,
+        )
+        .unwrap()
+        .unwrap();
+        match lane {
+            TransmissionLoss::Lane {
+                conductivity,
+                width,
+                length,
+            } => {
+                assert!((conductivity - 0.0254 / 3600.0).abs() < 1e-15);
+                assert!((width - 3.048).abs() < 1e-12);
+                assert!((length - 30.48).abs() < 1e-12);
+            }
+            other => panic!("{other:?}"),
+        }
+        let fraction = parse(
+            "transmission_loss = \"fraction\"\nloss_fraction = 0.2",
+            UnitSystem::Si,
+        );
+        assert_eq!(fraction.unwrap(), Some(TransmissionLoss::Fraction(0.2)));
+    }
+
+    #[test]
+    fn rejects_invalid_losses() {
+        for (text, expected) in [
+            (
+                "transmission_loss = \"wick\"",
+                "Unknown transmission loss method `wick`",
+            ),
+            (
+                "transmission_loss = \"seepage\"\nseepage_rate = 1.0\nlength = 10.0",
+                "needs `loss_width`",
+            ),
+            (
+                "transmission_loss = \"lane\"\nconductivity = 1.0\nloss_width = 1.0",
+                "needs `loss_length`",
+            ),
+            (
+                "transmission_loss = \"seepage\"\nloss_width = 1.0\nlength = 1.0",
+                "seepage_rate",
+            ),
+            (
+                "transmission_loss = \"seepage\"\nseepage_rate = -1.0\nloss_width = 1.0\n\
+                 length = 1.0",
+                "non-negative",
+            ),
+            (
+                "transmission_loss = \"lane\"\nconductivity = 1.0\nloss_width = 0.0\n\
+                 length = 1.0",
+                "bed dimensions positive",
+            ),
+            (
+                "transmission_loss = \"fraction\"\nloss_fraction = 1.5",
+                "within [0, 1]",
+            ),
+            ("transmission_loss = 3", "transmission_loss"),
+        ] {
+            match parse(text, UnitSystem::Si) {
+                Err(Error::InvalidParameter(msg) | Error::Data(msg)) => {
+                    assert!(msg.contains(expected), "{msg}")
+                }
+                other => panic!("{text}: {other:?}"),
+            }
+        }
+    }
+}
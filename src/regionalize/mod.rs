@@ -0,0 +1,260 @@
+//! Transfer of calibrated parameters to ungauged catchments.
+//!
+//! Donor catchments with calibrated parameter sets are ranked by their
+//! distance to the target, either in space (spatial proximity) or in the
+//! space of standardised catchment attributes (physical similarity). The
+//! nearest donors are combined by inverse-distance weighting, and the
+//! weighted spread of their parameters gives the uncertainty range
+//! (Oudin et al., 2008).
+
+#[cfg(feature = "python")]
+pub mod python;
+
+use crate::calibrate::glue::weighted_quantile;
+use crate::error::{Error, Result};
+
+/// Mean Earth radius (m) for great-circle distances.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// How donors are ranked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    /// Distance between catchment locations.
+    SpatialProximity,
+    /// Euclidean distance between standardised attributes.
+    PhysicalSimilarity,
+}
+
+impl Method {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "spatial_proximity" | "spatial" => Ok(Method::SpatialProximity),
+            "physical_similarity" | "physical" => Ok(Method::PhysicalSimilarity),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown method `{name}`; expected `spatial_proximity` or `physical_similarity`."
+            ))),
+        }
+    }
+}
+
+/// A gauged catchment with calibrated parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Donor {
+    /// Outlet or centroid: projected `(x, y)`, or `(longitude, latitude)`
+    /// in degrees when distances are geographic.
+    pub location: Option<(f64, f64)>,
+    /// Catchment attributes, in the order of the target's.
+    pub attributes: Vec<f64>,
+    pub parameters: Vec<f64>,
+    /// Calibration score, higher is better.
+    pub score: Option<f64>,
+}
+
+/// The ungauged catchment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub location: Option<(f64, f64)>,
+    pub attributes: Vec<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferOptions {
+    pub method: Method,
+    /// Number of donors combined.
+    pub donors: usize,
+    /// Exponent of the inverse-distance weights; 0 weights donors equally.
+    pub power: f64,
+    /// Locations are longitude and latitude in degrees.
+    pub geographic: bool,
+    /// Relative weight of each attribute in the similarity distance.
+    pub attribute_weights: Option<Vec<f64>>,
+    /// Donors scoring below this are not used.
+    pub min_score: Option<f64>,
+    /// Lower and upper probabilities of the uncertainty range.
+    pub bounds: (f64, f64),
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        TransferOptions {
+            method: Method::SpatialProximity,
+            donors: 5,
+            power: 2.0,
+            geographic: false,
+            attribute_weights: None,
+            min_score: None,
+            bounds: (0.05, 0.95),
+        }
+    }
+}
+
+/// Transferred parameters and the donors they came from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    /// Weighted mean of the donors' parameters.
+    pub parameters: Vec<f64>,
+    /// Weighted quantiles of the donors' parameters at the bounds.
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+    /// Indices of the donors used, nearest first.
+    pub donors: Vec<usize>,
+    /// Distance of each donor used: metres (geographic), location units,
+    /// or standard deviations of the attributes.
+    pub distances: Vec<f64>,
+    /// Weight of each donor used, summing to one.
+    pub weights: Vec<f64>,
+}
+
+/// Great-circle distance (m) between two `(longitude, latitude)` points in
+/// degrees.
+pub fn great_circle_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lon1, lat1) = (a.0.to_radians(), a.1.to_radians());
+    let (lon2, lat2) = (b.0.to_radians(), b.1.to_radians());
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+}
+
+/// Distance of every donor to `target` under `options`.
+fn distances(target: &Target, donors: &[Donor], options: &TransferOptions) -> Result<Vec<f64>> {
+    match options.method {
+        Method::SpatialProximity => {
+            let target = target.location.ok_or_else(|| {
+                Error::InvalidParameter("Spatial proximity needs the target location.".into())
+            })?;
+            donors
+                .iter()
+                .enumerate()
+                .map(|(i, d)| {
+                    let location = d.location.ok_or_else(|| {
+                        Error::InvalidParameter(format!("Donor {i} has no location."))
+                    })?;
+                    Ok(if options.geographic {
+                        great_circle_distance(target, location)
+                    } else {
+                        (target.0 - location.0).hypot(target.1 - location.1)
+                    })
+                })
+                .collect()
+        }
+        Method::PhysicalSimilarity => {
+            let m = target.attributes.len();
+            if m == 0 {
+                return Err(Error::InvalidParameter(
+                    "Physical similarity needs catchment attributes.".into(),
+                ));
+            }
+            if let Some(i) = donors.iter().position(|d| d.attributes.len() != m) {
+                return Err(Error::InvalidParameter(format!(
+                    "Donor {i} has {} attributes; the target has {m}.",
+                    donors[i].attributes.len()
+                )));
+            }
+            let weights = options.attribute_weights.clone().unwrap_or(vec![1.0; m]);
+            if weights.len() != m || weights.iter().any(|w| !(w.is_finite() && *w >= 0.0)) {
+                return Err(Error::InvalidParameter(
+                    "There must be one non-negative weight per attribute.".into(),
+                ));
+            }
+            // Standardise by the spread among the donors, so attributes in
+            // different units weigh alike.
+            let n = donors.len() as f64;
+            let scale: Vec<f64> = (0..m)
+                .map(|j| {
+                    let mean = donors.iter().map(|d| d.attributes[j]).sum::<f64>() / n;
+                    let variance = donors
+                        .iter()
+                        .map(|d| (d.attributes[j] - mean).powi(2))
+                        .sum::<f64>()
+                        / n;
+                    if variance > 0.0 {
+                        variance.sqrt()
+                    } else {
+                        1.0
+                    }
+                })
+                .collect();
+            Ok(donors
+                .iter()
+                .map(|d| {
+                    (0..m)
+                        .map(|j| {
+                            weights[j]
+                                * ((d.attributes[j] - target.attributes[j]) / scale[j]).powi(2)
+                        })
+                        .sum::<f64>()
+                        .sqrt()
+                })
+                .collect())
+        }
+    }
+}
+
+/// Estimates the parameters of `target` from the nearest `donors`.
+pub fn transfer_parameters(
+    target: &Target,
+    donors: &[Donor],
+    options: &TransferOptions,
+) -> Result<Transfer> {
+    let p = donors.first().map_or(0, |d| d.parameters.len());
+    if p == 0 {
+        return Err(Error::InvalidParameter(
+            "At least one donor with parameters is needed.".into(),
+        ));
+    }
+    if let Some(i) = donors.iter().position(|d| d.parameters.len() != p) {
+        return Err(Error::InvalidParameter(format!(
+            "Donor {i} has {} parameters; the first has {p}.",
+            donors[i].parameters.len()
+        )));
+    }
+    if options.donors == 0 || options.power.is_nan() || options.power < 0.0 {
+        return Err(Error::InvalidParameter(
+            "`donors` must be positive and `power` non-negative.".into(),
+        ));
+    }
+    let distance = distances(target, donors, options)?;
+    let mut ranked: Vec<usize> = (0..donors.len())
+        .filter(|&i| {
+            distance[i].is_finite()
+                && options
+                    .min_score
+                    .is_none_or(|min| donors[i].score.is_some_and(|s| s >= min))
+        })
+        .collect();
+    if ranked.is_empty() {
+        return Err(Error::Data(
+            "No donor meets the minimum score and has a finite distance.".into(),
+        ));
+    }
+    ranked.sort_by(|&a, &b| distance[a].total_cmp(&distance[b]));
+    ranked.truncate(options.donors);
+
+    let distances: Vec<f64> = ranked.iter().map(|&i| distance[i]).collect();
+    // A donor at zero distance takes all the weight.
+    let raw: Vec<f64> = if distances[0] == 0.0 {
+        distances.iter().map(|&d| f64::from(d == 0.0)).collect()
+    } else {
+        distances.iter().map(|&d| d.powf(-options.power)).collect()
+    };
+    let total: f64 = raw.iter().sum();
+    let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+
+    let mut parameters = Vec::with_capacity(p);
+    let mut lower = Vec::with_capacity(p);
+    let mut upper = Vec::with_capacity(p);
+    for j in 0..p {
+        let values: Vec<f64> = ranked.iter().map(|&i| donors[i].parameters[j]).collect();
+        parameters.push(values.iter().zip(&weights).map(|(v, w)| v * w).sum());
+        lower.push(weighted_quantile(&values, &weights, options.bounds.0));
+        upper.push(weighted_quantile(&values, &weights, options.bounds.1));
+    }
+    Ok(Transfer {
+        parameters,
+        lower,
+        upper,
+        donors: ranked,
+        distances,
+        weights,
+    })
+}
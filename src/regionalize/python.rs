@@ -0,0 +1,75 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::{
+    transfer_parameters as transfer_parameters_rs, Donor, Method, Target, TransferOptions,
+};
+
+#[pyfunction]
+#[pyo3(signature = (target_location, target_attributes, donor_locations, donor_attributes, donor_parameters, donor_scores=None, method="spatial_proximity", donors=5, power=2.0, geographic=false, attribute_weights=None, min_score=None, bounds=(0.05, 0.95)))]
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_parameters<'py>(
+    py: Python<'py>,
+    target_location: Option<(f64, f64)>,
+    target_attributes: Vec<f64>,
+    donor_locations: Vec<Option<(f64, f64)>>,
+    donor_attributes: Vec<Vec<f64>>,
+    donor_parameters: Vec<Vec<f64>>,
+    donor_scores: Option<Vec<Option<f64>>>,
+    method: &str,
+    donors: usize,
+    power: f64,
+    geographic: bool,
+    attribute_weights: Option<Vec<f64>>,
+    min_score: Option<f64>,
+    bounds: (f64, f64),
+) -> PyResult<Bound<'py, PyDict>> {
+    let n = donor_parameters.len();
+    if donor_locations.len() != n || donor_attributes.len() != n {
+        return Err(crate::error::Error::InvalidParameter(
+            "Donor locations, attributes and parameters must have one entry per donor.".into(),
+        )
+        .into());
+    }
+    let scores = donor_scores.unwrap_or_else(|| vec![None; n]);
+    let donor_list: Vec<Donor> = donor_locations
+        .into_iter()
+        .zip(donor_attributes)
+        .zip(donor_parameters)
+        .zip(scores)
+        .map(|(((location, attributes), parameters), score)| Donor {
+            location,
+            attributes,
+            parameters,
+            score,
+        })
+        .collect();
+    let target = Target {
+        location: target_location,
+        attributes: target_attributes,
+    };
+    let options = TransferOptions {
+        method: Method::parse(method)?,
+        donors,
+        power,
+        geographic,
+        attribute_weights,
+        min_score,
+        bounds,
+    };
+    let result = transfer_parameters_rs(&target, &donor_list, &options)?;
+    let dict = PyDict::new(py);
+    dict.set_item("parameters", result.parameters)?;
+    dict.set_item("lower", result.lower)?;
+    dict.set_item("upper", result.upper)?;
+    dict.set_item("donors", result.donors)?;
+    dict.set_item("distances", result.distances)?;
+    dict.set_item("weights", result.weights)?;
+    Ok(dict)
+}
+
+#[pymodule]
+pub fn init_regionalize(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(transfer_parameters, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,595 @@
+//! Forecast-informed release scheduling for a reservoir flood pool.
+//!
+//! A single release schedule is sought for all members of an inflow
+//! forecast ensemble. Releases are held constant over each step, between
+//! `min_release` and `max_release`, and may change by at most `max_ramp`
+//! from one step to the next. Member `m` then holds storage
+//! `S(m, t) = S0 + dt Σ (I(m) - R)` at the end of step `t`, so:
+//!
+//! - the peak pool over the ensemble is that of the wettest cumulative
+//!   inflow, `max_m Σ I(m)`;
+//! - the pool stays above the minimum in every member if it does under the
+//!   driest cumulative inflow;
+//! - the downstream exceedance `max(0, R + L(m) - capacity)` of each step
+//!   depends only on that step's release.
+//!
+//! The schedule is therefore found by dynamic programming over the
+//! cumulative release volume and the previous release, whatever the number
+//! of members. Releases are taken from `release_levels` evenly spaced
+//! values and the cumulative release is discretised on `storage_levels`
+//! points per step, with the value function interpolated between them; the
+//! schedule is rebuilt forwards from the exact cumulative release.
+
+use crate::error::{Error, Result};
+use crate::interp::interp;
+use crate::log::{debug, warning};
+use crate::parallel::par_map;
+
+/// What the release schedule minimises.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawdownObjective {
+    /// The highest pool reached by any member; ties are broken by the
+    /// downstream exceedance.
+    PeakPool,
+    /// The ensemble-mean volume passed downstream above the channel
+    /// capacity, with the pool kept below `max_elevation`.
+    Exceedance,
+}
+
+impl DrawdownObjective {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "peak_pool" => Ok(DrawdownObjective::PeakPool),
+            "exceedance" => Ok(DrawdownObjective::Exceedance),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown objective `{name}`; expected `peak_pool` or `exceedance`."
+            ))),
+        }
+    }
+}
+
+/// Reservoir, forecast and operating constraints of a drawdown problem, in
+/// consistent units (flows in volume per second).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawdownProblem {
+    /// Forecast inflow members, `[member][step]`, each value the mean
+    /// inflow over its step.
+    pub inflow: Vec<Vec<f64>>,
+    /// Local inflow joining below the dam, shaped as `inflow`, or empty.
+    pub local_inflow: Vec<Vec<f64>>,
+    /// Time step (s).
+    pub dt: f64,
+    /// Pool elevations of the reservoir table, strictly increasing.
+    pub elevation: Vec<f64>,
+    /// Storage at each elevation, strictly increasing.
+    pub storage: Vec<f64>,
+    pub initial_elevation: f64,
+    pub min_release: f64,
+    pub max_release: f64,
+    /// Largest change in release between consecutive steps.
+    pub max_ramp: Option<f64>,
+    /// Release before the first step, from which the first change is
+    /// ramped; unconstrained when `None`.
+    pub initial_release: Option<f64>,
+    /// Lowest pool allowed in any member; the bottom of the table if `None`.
+    pub min_elevation: Option<f64>,
+    /// Highest pool allowed in any member.
+    pub max_elevation: Option<f64>,
+    /// Pool to be reached, or passed below, at the end of the horizon
+    /// under the ensemble-mean inflow.
+    pub target_elevation: Option<f64>,
+    /// Downstream flow above which releases count as exceedance.
+    pub channel_capacity: Option<f64>,
+    pub objective: DrawdownObjective,
+    pub release_levels: usize,
+    pub storage_levels: usize,
+}
+
+/// Optimal release schedule and its outcome in each member.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawdownSchedule {
+    /// Release over each step.
+    pub release: Vec<f64>,
+    /// Storage at the end of each step, `[member][step]`.
+    pub storage: Vec<Vec<f64>>,
+    /// Pool elevation at the end of each step, `[member][step]`.
+    pub elevation: Vec<Vec<f64>>,
+    /// Release plus local inflow, `[member][step]`.
+    pub downstream: Vec<Vec<f64>>,
+    /// Highest pool elevation reached by any member.
+    pub peak_elevation: f64,
+    /// Ensemble-mean volume passed downstream above the channel capacity.
+    pub exceedance_volume: f64,
+    /// Fraction of members above the channel capacity at each step.
+    pub exceedance_probability: Vec<f64>,
+}
+
+/// Cost to go: the highest storage of the wettest member, the exceedance
+/// volume and, when ramping is limited, the total change in release, which
+/// breaks ties in favour of smooth schedules.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cost {
+    peak: f64,
+    total: f64,
+    change: f64,
+}
+
+impl Cost {
+    const INFEASIBLE: Cost = Cost {
+        peak: f64::INFINITY,
+        total: f64::INFINITY,
+        change: f64::INFINITY,
+    };
+    const ZERO: Cost = Cost {
+        peak: f64::NEG_INFINITY,
+        total: 0.0,
+        change: 0.0,
+    };
+
+    fn feasible(self) -> bool {
+        self.total.is_finite()
+    }
+
+    /// Whether `self` is better than `other` under `objective`, peaks
+    /// within `resolution` of each other counting as equal.
+    fn better(self, other: Cost, objective: DrawdownObjective, resolution: f64) -> bool {
+        let key = |c: Cost| match objective {
+            DrawdownObjective::PeakPool => [(c.peak, resolution), (c.total, 0.0), (c.change, 0.0)],
+            DrawdownObjective::Exceedance => {
+                [(c.total, 0.0), (c.peak, resolution), (c.change, 0.0)]
+            }
+        };
+        for ((a, resolution), (b, _)) in key(self).into_iter().zip(key(other)) {
+            let tolerance = resolution.max(1e-9 * a.abs().max(b.abs()).max(1.0));
+            if !a.is_finite() || !b.is_finite() || (a - b).abs() > tolerance {
+                return a < b;
+            }
+        }
+        false
+    }
+
+    fn lerp(self, other: Cost, fraction: f64) -> Cost {
+        let mix = |a: f64, b: f64| {
+            if a == b {
+                a
+            } else {
+                a + fraction * (b - a)
+            }
+        };
+        Cost {
+            peak: mix(self.peak, other.peak),
+            total: mix(self.total, other.total),
+            change: mix(self.change, other.change),
+        }
+    }
+}
+
+impl DrawdownProblem {
+    fn validate(&self) -> Result<()> {
+        let steps = self.inflow.first().map_or(0, Vec::len);
+        if steps == 0 || self.inflow.iter().any(|m| m.len() != steps) {
+            return Err(Error::InvalidParameter(
+                "The inflow forecast needs at least one member, all of the same non-zero length."
+                    .into(),
+            ));
+        }
+        if self.inflow.iter().flatten().any(|q| !q.is_finite()) {
+            return Err(Error::Data("Forecast inflows must be finite.".into()));
+        }
+        if !self.local_inflow.is_empty()
+            && (self.local_inflow.len() != self.inflow.len()
+                || self.local_inflow.iter().any(|m| m.len() != steps)
+                || self.local_inflow.iter().flatten().any(|q| !q.is_finite()))
+        {
+            return Err(Error::InvalidParameter(
+                "Local inflows must be finite and shaped like the inflow forecast.".into(),
+            ));
+        }
+        if self.dt.is_nan() || self.dt <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "The time step must be positive.".into(),
+            ));
+        }
+        if self.elevation.len() < 2
+            || self.storage.len() != self.elevation.len()
+            || self
+                .elevation
+                .windows(2)
+                .any(|w| w[1] <= w[0] || w[1].is_nan())
+            || self
+                .storage
+                .windows(2)
+                .any(|w| w[1] <= w[0] || w[1].is_nan())
+        {
+            return Err(Error::InvalidParameter(
+                "Elevation and storage tables must be of equal length (at least 2) and strictly increasing."
+                    .into(),
+            ));
+        }
+        if !(self.min_release >= 0.0 && self.max_release >= self.min_release) {
+            return Err(Error::InvalidParameter(
+                "Releases must satisfy 0 <= min_release <= max_release.".into(),
+            ));
+        }
+        if self.max_ramp.is_some_and(|r| r.is_nan() || r <= 0.0) {
+            return Err(Error::InvalidParameter(
+                "The ramping limit must be positive.".into(),
+            ));
+        }
+        if self.release_levels < 2 || self.storage_levels < 2 {
+            return Err(Error::InvalidParameter(
+                "At least two release and two storage levels are needed.".into(),
+            ));
+        }
+        if self.objective == DrawdownObjective::Exceedance
+            && (self.channel_capacity.is_none() || self.max_elevation.is_none())
+        {
+            return Err(Error::InvalidParameter(
+                "The `exceedance` objective needs a channel capacity and a maximum pool elevation."
+                    .into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn storage_at(&self, elevation: f64) -> f64 {
+        interp(elevation, &self.elevation, &self.storage)
+    }
+
+    fn elevation_at(&self, storage: f64) -> f64 {
+        interp(storage, &self.storage, &self.elevation)
+    }
+}
+
+/// Finds the release schedule minimising `problem.objective`.
+pub fn optimize_drawdown(problem: &DrawdownProblem) -> Result<DrawdownSchedule> {
+    problem.validate()?;
+    let p = problem;
+    let steps = p.inflow[0].len();
+    let members = p.inflow.len();
+    let dt = p.dt;
+    let s0 = p.storage_at(p.initial_elevation);
+    let s_min = p.storage_at(p.min_elevation.unwrap_or(p.elevation[0]));
+    let s_max = p.max_elevation.map(|z| p.storage_at(z));
+    let s_target = p.target_elevation.map(|z| p.storage_at(z));
+
+    // Wettest, driest and mean cumulative inflow volume at the end of each
+    // step.
+    let mut cumulative = vec![0.0; members];
+    let mut wet = Vec::with_capacity(steps);
+    let mut dry = Vec::with_capacity(steps);
+    let mut mean = Vec::with_capacity(steps);
+    for t in 0..steps {
+        for (c, member) in cumulative.iter_mut().zip(&p.inflow) {
+            *c += member[t] * dt;
+        }
+        wet.push(cumulative.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+        dry.push(cumulative.iter().copied().fold(f64::INFINITY, f64::min));
+        mean.push(cumulative.iter().sum::<f64>() / members as f64);
+    }
+
+    let levels = p.release_levels;
+    let releases: Vec<f64> = (0..levels)
+        .map(|j| p.min_release + (p.max_release - p.min_release) * j as f64 / (levels - 1) as f64)
+        .collect();
+    // Exceedance volume of each release level at each step, averaged over
+    // members.
+    let exceedance: Vec<Vec<f64>> = (0..steps)
+        .map(|t| {
+            releases
+                .iter()
+                .map(|&r| match p.channel_capacity {
+                    None => 0.0,
+                    Some(capacity) => {
+                        let excess = |m: usize| {
+                            let local = p.local_inflow.get(m).map_or(0.0, |l| l[t]);
+                            (r + local - capacity).max(0.0)
+                        };
+                        (0..members).map(excess).sum::<f64>() * dt / members as f64
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    // Bounds on the cumulative release volume at the end of each step.
+    let mut bounds = Vec::with_capacity(steps);
+    for t in 0..steps {
+        let elapsed = (t + 1) as f64 * dt;
+        let mut lo = elapsed * p.min_release;
+        let hi = (elapsed * p.max_release).min(s0 + dry[t] - s_min);
+        if let Some(s_max) = s_max {
+            lo = lo.max(s0 + wet[t] - s_max);
+        }
+        if t + 1 == steps {
+            if let Some(s_target) = s_target {
+                lo = lo.max(s0 + mean[t] - s_target);
+            }
+        }
+        if lo > hi + 1e-9 * hi.abs().max(1.0) {
+            return Err(Error::InvalidParameter(format!(
+                "No release schedule meets the constraints at step {}: the pool limits, target \
+                 and release range cannot all be satisfied. Raise `max_release` or \
+                 `max_elevation`, or relax the target.",
+                t + 1
+            )));
+        }
+        bounds.push((lo, hi.max(lo)));
+    }
+
+    // Release classes of the state: the previous release level when ramping
+    // is limited, otherwise a single class.
+    let classes = if p.max_ramp.is_some() { levels } else { 1 };
+    let class_of = |j: usize| if classes == 1 { 0 } else { j };
+    // Whether level `j` may follow `previous` (the release before the
+    // first step is `initial_release`).
+    let allowed = |previous: Option<f64>, j: usize| match (p.max_ramp, previous) {
+        (Some(ramp), Some(r)) => (releases[j] - r).abs() <= ramp * (1.0 + 1e-12),
+        _ => true,
+    };
+
+    let n = p.storage_levels;
+    let grid = |t: usize, s: usize| {
+        let (lo, hi) = bounds[t];
+        lo + (hi - lo) * s as f64 / (n - 1) as f64
+    };
+    // Half the grid spacing of step `t`, within which interpolated peaks
+    // are not told apart.
+    let resolution = |t: usize| {
+        let (lo, hi) = bounds[t];
+        0.5 * (hi - lo) / (n - 1) as f64
+    };
+    // Cost to go after step `t` from cumulative release `v` in `class`.
+    let lookup = |values: &[Cost], t: usize, v: f64, class: usize| -> Cost {
+        let (lo, hi) = bounds[t];
+        let tolerance = 1e-9 * hi.abs().max(1.0);
+        if v < lo - tolerance || v > hi + tolerance {
+            return Cost::INFEASIBLE;
+        }
+        let row = &values[class * n..(class + 1) * n];
+        if hi - lo <= tolerance {
+            return row[0];
+        }
+        let x = ((v - lo) / (hi - lo) * (n - 1) as f64).clamp(0.0, (n - 1) as f64);
+        let i = (x.floor() as usize).min(n - 2);
+        let fraction = x - i as f64;
+        let (a, b) = (row[i], row[i + 1]);
+        match (a.feasible(), b.feasible()) {
+            (true, true) => a.lerp(b, fraction),
+            (true, false) if fraction <= 0.5 => a,
+            (false, true) if fraction >= 0.5 => b,
+            _ => Cost::INFEASIBLE,
+        }
+    };
+    // Cost of releasing level `j` over step `t` from cumulative release `v`
+    // (before the step) after releasing `previous`, and the cumulative
+    // release after it.
+    let step_cost = |values: &[Cost], t: usize, v: f64, previous: Option<f64>, j: usize| {
+        let v_next = v + releases[j] * dt;
+        let next = lookup(values, t, v_next, class_of(j));
+        if !next.feasible() {
+            return (Cost::INFEASIBLE, v_next);
+        }
+        let cost = Cost {
+            peak: next.peak.max(s0 + wet[t] - v_next),
+            total: next.total + exceedance[t][j],
+            change: next.change + previous.map_or(0.0, |r| (releases[j] - r).abs()),
+        };
+        (cost, v_next)
+    };
+
+    // Backward recursion: `value[t]` is the cost to go after step `t`,
+    // indexed by `class * n + grid point`.
+    let mut value: Vec<Vec<Cost>> = vec![Vec::new(); steps];
+    value[steps - 1] = vec![Cost::ZERO; classes * n];
+    let states: Vec<usize> = (0..classes * n).collect();
+    for t in (0..steps - 1).rev() {
+        let next = &value[t + 1];
+        let row = par_map(&states, |&state| {
+            let (class, s) = (state / n, state % n);
+            let v = grid(t, s);
+            let previous = (classes > 1).then(|| releases[class]);
+            let mut best = Cost::INFEASIBLE;
+            for j in (0..levels).filter(|&j| allowed(previous, j)) {
+                let (cost, _) = step_cost(next, t + 1, v, previous, j);
+                if cost.better(best, p.objective, resolution(t + 1)) {
+                    best = cost;
+                }
+            }
+            best
+        });
+        value[t] = row;
+    }
+
+    // Forward pass from the exact cumulative release.
+    let mut release = Vec::with_capacity(steps);
+    let mut v = 0.0;
+    let mut previous = p.initial_release;
+    for t in 0..steps {
+        let mut best: Option<(Cost, usize, f64)> = None;
+        for j in (0..levels).filter(|&j| allowed(previous, j)) {
+            let ramped = previous.filter(|_| classes > 1);
+            let (cost, v_next) = step_cost(&value[t], t, v, ramped, j);
+            if cost.feasible()
+                && best.is_none_or(|(b, _, _)| cost.better(b, p.objective, resolution(t)))
+            {
+                best = Some((cost, j, v_next));
+            }
+        }
+        let (j, v_next) = match best {
+            Some((_, j, v_next)) => (j, v_next),
+            None => {
+                // Discretisation left no feasible level: keep as close to
+                // the feasible range as the ramp allows.
+                let (lo, hi) = bounds[t];
+                let j = (0..levels)
+                    .filter(|&j| allowed(previous, j))
+                    .min_by(|&a, &b| {
+                        let gap = |j: usize| {
+                            let w = v + releases[j] * dt;
+                            (lo - w).max(w - hi).max(0.0)
+                        };
+                        gap(a).total_cmp(&gap(b))
+                    })
+                    .unwrap_or(0);
+                warning!(
+                    "Drawdown: no feasible release level at step {}; the schedule may \
+                     slightly break a pool limit. Use more release or storage levels.",
+                    t + 1
+                );
+                (j, v + releases[j] * dt)
+            }
+        };
+        release.push(releases[j]);
+        v = v_next;
+        previous = Some(releases[j]);
+    }
+
+    // Outcome in each member.
+    let mut storage = Vec::with_capacity(members);
+    let mut downstream = Vec::with_capacity(members);
+    for (m, member) in p.inflow.iter().enumerate() {
+        let mut s = s0;
+        storage.push(
+            member
+                .iter()
+                .zip(&release)
+                .map(|(i, r)| {
+                    s += (i - r) * dt;
+                    s
+                })
+                .collect::<Vec<f64>>(),
+        );
+        downstream.push(
+            release
+                .iter()
+                .enumerate()
+                .map(|(t, r)| r + p.local_inflow.get(m).map_or(0.0, |l| l[t]))
+                .collect::<Vec<f64>>(),
+        );
+    }
+    let elevation: Vec<Vec<f64>> = storage
+        .iter()
+        .map(|member| member.iter().map(|&s| p.elevation_at(s)).collect())
+        .collect();
+    let peak_elevation = elevation
+        .iter()
+        .flatten()
+        .copied()
+        .fold(p.initial_elevation, f64::max);
+    let capacity = p.channel_capacity.unwrap_or(f64::INFINITY);
+    let exceedance_volume = downstream
+        .iter()
+        .flatten()
+        .map(|q| (q - capacity).max(0.0) * dt)
+        .sum::<f64>()
+        / members as f64;
+    let exceedance_probability = (0..steps)
+        .map(|t| downstream.iter().filter(|d| d[t] > capacity).count() as f64 / members as f64)
+        .collect();
+    debug!(
+        "Drawdown: {members} member(s), {steps} step(s); peak pool {peak_elevation:.3}, \
+         mean exceedance volume {exceedance_volume:.1}"
+    );
+    Ok(DrawdownSchedule {
+        release,
+        storage,
+        elevation,
+        downstream,
+        peak_elevation,
+        exceedance_volume,
+        exceedance_probability,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three forecast members of a day-long flood into a 1 km² pool at
+    /// 104 m, with the flood pool topped at 108 m.
+    fn problem(objective: DrawdownObjective) -> DrawdownProblem {
+        let member = |peak: f64| {
+            (0..24)
+                .map(|t| 20.0 + (peak - 20.0) * (1.0 - (t as f64 - 8.0).abs() / 10.0).max(0.0))
+                .collect::<Vec<f64>>()
+        };
+        DrawdownProblem {
+            inflow: vec![member(120.0), member(170.0), member(220.0)],
+            local_inflow: vec![vec![20.0; 24]; 3],
+            dt: 3600.0,
+            elevation: vec![100.0, 110.0],
+            storage: vec![0.0, 1.0e7],
+            initial_elevation: 104.0,
+            min_release: 0.0,
+            max_release: 300.0,
+            max_ramp: None,
+            initial_release: None,
+            min_elevation: None,
+            max_elevation: Some(108.0),
+            target_elevation: None,
+            channel_capacity: Some(100.0),
+            objective,
+            release_levels: 31,
+            storage_levels: 41,
+        }
+    }
+
+    #[test]
+    fn releases_respect_the_channel_capacity() {
+        let problem = problem(DrawdownObjective::Exceedance);
+        let schedule = optimize_drawdown(&problem).unwrap();
+        // The flood pool holds what the channel cannot pass, so nothing
+        // above the capacity goes downstream.
+        assert_eq!(schedule.exceedance_volume, 0.0);
+        assert!(schedule.exceedance_probability.iter().all(|&p| p == 0.0));
+        for member in &schedule.downstream {
+            assert!(member.iter().all(|&q| q <= 100.0), "{member:?}");
+        }
+        assert!(schedule.peak_elevation <= 108.0 + 1e-9);
+    }
+
+    #[test]
+    fn the_flood_pool_is_not_exceeded_when_releases_suffice() {
+        let mut problem = problem(DrawdownObjective::PeakPool);
+        problem.max_ramp = Some(40.0);
+        problem.initial_release = Some(20.0);
+        let schedule = optimize_drawdown(&problem).unwrap();
+        for (member, inflow) in schedule.storage.iter().zip(&problem.inflow) {
+            // Storage follows the water balance of the shared schedule.
+            let mut s = 4.0e6;
+            for t in 0..24 {
+                s += (inflow[t] - schedule.release[t]) * 3600.0;
+                assert!((member[t] - s).abs() < 1e-6);
+            }
+        }
+        for member in &schedule.elevation {
+            assert!(member.iter().all(|&z| z <= 108.0 + 1e-9), "{member:?}");
+        }
+        let mut previous = 20.0;
+        for &r in &schedule.release {
+            assert!((0.0..=300.0).contains(&r));
+            assert!((r - previous).abs() <= 40.0 + 1e-9);
+            previous = r;
+        }
+        // Minimising the peak draws the pool down further than holding the
+        // channel capacity does.
+        let held = optimize_drawdown(&self::problem(DrawdownObjective::Exceedance)).unwrap();
+        assert!(schedule.peak_elevation < held.peak_elevation);
+    }
+
+    #[test]
+    fn reports_infeasible_flood_pools() {
+        let mut problem = problem(DrawdownObjective::PeakPool);
+        problem.max_release = 20.0;
+        match optimize_drawdown(&problem) {
+            Err(Error::InvalidParameter(msg)) => assert!(msg.contains("max_elevation"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+        problem.max_release = 300.0;
+        problem.channel_capacity = None;
+        problem.objective = DrawdownObjective::Exceedance;
+        assert!(optimize_drawdown(&problem).is_err());
+        assert!(DrawdownObjective::parse("volume").is_err());
+    }
+}
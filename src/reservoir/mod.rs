@@ -0,0 +1,7 @@
+pub mod dam_breach;
+pub mod drawdown;
+pub mod hydropower;
+pub mod level_pool;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod yield_analysis;
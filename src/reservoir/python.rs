@@ -0,0 +1,452 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+
+use super::dam_breach::{dam_breach_rs, BreachDam, BreachMethod, BreachMode};
+use super::drawdown::{
+    optimize_drawdown as optimize_drawdown_rs, DrawdownObjective, DrawdownProblem,
+};
+use super::hydropower::{hydropower_rs, Efficiency, Powerhouse};
+use super::level_pool::{level_pool_routing_with_evaporation_rs, LevelPoolReservoir};
+use super::yield_analysis::{self, WaterBalance};
+use crate::arrow::FloatSeries;
+use crate::time::timedelta_to_seconds;
+use crate::units::UnitSystem;
+
+/// Seepage given as one flow or as a value per elevation.
+#[derive(FromPyObject)]
+pub enum SeepageInput {
+    Constant(f64),
+    Table(Vec<f64>),
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, elevation, storage, outflow, time_step, initial_elevation, area=None, evaporation=None, seepage=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn level_pool_routing(
+    inflow: FloatSeries,
+    elevation: Vec<f64>,
+    storage: Vec<f64>,
+    outflow: Vec<f64>,
+    time_step: Bound<'_, PyDelta>,
+    initial_elevation: f64,
+    area: Option<Vec<f64>>,
+    evaporation: Option<FloatSeries>,
+    seepage: Option<SeepageInput>,
+) -> PyResult<Vec<f64>> {
+    let seepage = seepage.map(|s| match s {
+        SeepageInput::Constant(q) => vec![q; elevation.len()],
+        SeepageInput::Table(table) => table,
+    });
+    if evaporation.is_some() && area.is_none() {
+        return Err(PyValueError::new_err(
+            "Evaporation requires an `area` table.",
+        ));
+    }
+    let mut reservoir = LevelPoolReservoir::new(elevation, storage, outflow, initial_elevation)?
+        .with_losses(area, seepage)?;
+    let dt_s = timedelta_to_seconds(&time_step);
+    Ok(level_pool_routing_with_evaporation_rs(
+        &inflow.0,
+        &evaporation.map(|e| e.0).unwrap_or_default(),
+        dt_s,
+        &mut reservoir,
+    )?)
+}
+
+fn water_balance(
+    demand_pattern: Option<Vec<f64>>,
+    evaporation: Option<Vec<f64>>,
+    storage_area: Option<(Vec<f64>, Vec<f64>)>,
+) -> WaterBalance {
+    WaterBalance {
+        demand_pattern: demand_pattern.unwrap_or_default(),
+        evaporation: evaporation.unwrap_or_default(),
+        storage_area,
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, demand, time_step, demand_pattern=None))]
+pub fn sequent_peak(
+    inflow: FloatSeries,
+    demand: f64,
+    time_step: Bound<'_, PyDelta>,
+    demand_pattern: Option<Vec<f64>>,
+) -> f64 {
+    let balance = water_balance(demand_pattern, None, None);
+    yield_analysis::sequent_peak(
+        &inflow.0,
+        demand,
+        timedelta_to_seconds(&time_step),
+        &balance,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, capacity, demand, time_step, initial_storage=None, demand_pattern=None, evaporation=None, storage_area=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_yield<'py>(
+    py: Python<'py>,
+    inflow: FloatSeries,
+    capacity: f64,
+    demand: f64,
+    time_step: Bound<'py, PyDelta>,
+    initial_storage: Option<f64>,
+    demand_pattern: Option<Vec<f64>>,
+    evaporation: Option<Vec<f64>>,
+    storage_area: Option<(Vec<f64>, Vec<f64>)>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let balance = water_balance(demand_pattern, evaporation, storage_area);
+    let result = yield_analysis::simulate(
+        &inflow.0,
+        capacity,
+        demand,
+        timedelta_to_seconds(&time_step),
+        initial_storage.unwrap_or(capacity),
+        &balance,
+    )?;
+    let dict = PyDict::new(py);
+    dict.set_item("storage", result.storage)?;
+    dict.set_item("release", result.release)?;
+    dict.set_item("spill", result.spill)?;
+    dict.set_item("reliability", result.reliability)?;
+    dict.set_item("volumetric_reliability", result.volumetric_reliability)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (traces, capacities, reliability, time_step, demand_pattern=None, evaporation=None, storage_area=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn storage_yield_reliability(
+    py: Python<'_>,
+    traces: Vec<FloatSeries>,
+    capacities: Vec<f64>,
+    reliability: f64,
+    time_step: Bound<'_, PyDelta>,
+    demand_pattern: Option<Vec<f64>>,
+    evaporation: Option<Vec<f64>>,
+    storage_area: Option<(Vec<f64>, Vec<f64>)>,
+) -> PyResult<Vec<f64>> {
+    let balance = water_balance(demand_pattern, evaporation, storage_area);
+    let traces: Vec<Vec<f64>> = traces.into_iter().map(|t| t.0).collect();
+    let dt = timedelta_to_seconds(&time_step);
+    Ok(py.allow_threads(|| {
+        yield_analysis::storage_yield_reliability(&traces, &capacities, reliability, dt, &balance)
+    })?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, time_step, elevation, storage, outflow, initial_elevation, crest_elevation, bottom_elevation, mode="overtopping", piping_elevation=None, method="froehlich", crest_width=None, upstream_slope=None, downstream_slope=None, bottom_width=None, side_slope=None, formation_time=None, trigger_elevation=None, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn dam_breach<'py>(
+    py: Python<'py>,
+    inflow: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    elevation: Vec<f64>,
+    storage: Vec<f64>,
+    outflow: Vec<f64>,
+    initial_elevation: f64,
+    crest_elevation: f64,
+    bottom_elevation: f64,
+    mode: &str,
+    piping_elevation: Option<f64>,
+    method: &str,
+    crest_width: Option<f64>,
+    upstream_slope: Option<f64>,
+    downstream_slope: Option<f64>,
+    bottom_width: Option<f64>,
+    side_slope: Option<f64>,
+    formation_time: Option<Bound<'py, PyDelta>>,
+    trigger_elevation: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mode = match (mode.to_ascii_lowercase().as_str(), piping_elevation) {
+        ("overtopping", _) => BreachMode::Overtopping,
+        ("piping", Some(z)) => BreachMode::Piping {
+            elevation: units.length_to_si(z),
+        },
+        ("piping", None) => {
+            return Err(PyValueError::new_err(
+                "Breach mode `piping` requires `piping_elevation`.",
+            ))
+        }
+        (other, _) => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown breach mode `{other}`; expected `overtopping` or `piping`."
+            )))
+        }
+    };
+    let missing = |name: &str| PyValueError::new_err(format!("Breach method requires `{name}`."));
+    let method = match method.to_ascii_lowercase().as_str() {
+        "froehlich" => BreachMethod::Froehlich,
+        "macdonald" => BreachMethod::MacDonald {
+            crest_width: units.length_to_si(crest_width.ok_or_else(|| missing("crest_width"))?),
+            upstream_slope: upstream_slope.ok_or_else(|| missing("upstream_slope"))?,
+            downstream_slope: downstream_slope.ok_or_else(|| missing("downstream_slope"))?,
+        },
+        "user" => BreachMethod::User {
+            bottom_width: units.length_to_si(bottom_width.ok_or_else(|| missing("bottom_width"))?),
+            side_slope: side_slope.ok_or_else(|| missing("side_slope"))?,
+            formation_time: timedelta_to_seconds(
+                &formation_time.ok_or_else(|| missing("formation_time"))?,
+            ),
+        },
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown breach method `{other}`; expected `froehlich`, `macdonald` or `user`."
+            )))
+        }
+    };
+    let dam = BreachDam {
+        elevation: elevation.iter().map(|&z| units.length_to_si(z)).collect(),
+        storage: storage.iter().map(|&s| units.volume_to_si(s)).collect(),
+        outflow: outflow.iter().map(|&q| units.discharge_to_si(q)).collect(),
+        crest_elevation: units.length_to_si(crest_elevation),
+        bottom_elevation: units.length_to_si(bottom_elevation),
+        mode,
+        method,
+        trigger_elevation: trigger_elevation.map(|z| units.length_to_si(z)),
+    };
+    let dt = timedelta_to_seconds(&time_step);
+    let inflow: Vec<f64> = inflow.0.iter().map(|&q| units.discharge_to_si(q)).collect();
+    let initial = units.length_to_si(initial_elevation);
+    let result = py.allow_threads(|| dam_breach_rs(&inflow, dt, initial, &dam))?;
+
+    let discharge = |values: &[f64]| -> Vec<f64> {
+        values.iter().map(|&q| units.discharge_from_si(q)).collect()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("outflow", discharge(&result.outflow))?;
+    dict.set_item("breach_outflow", discharge(&result.breach_outflow))?;
+    dict.set_item(
+        "elevation",
+        result
+            .elevation
+            .iter()
+            .map(|&z| units.length_from_si(z))
+            .collect::<Vec<_>>(),
+    )?;
+    let seconds = |s: f64| {
+        let whole = s.floor();
+        PyDelta::new(
+            py,
+            0,
+            whole as i32,
+            ((s - whole) * 1e6).round() as i32,
+            true,
+        )
+    };
+    match result.initiation {
+        Some(t) => dict.set_item("initiation", seconds(t)?)?,
+        None => dict.set_item("initiation", py.None())?,
+    }
+    match result.parameters {
+        Some(p) => {
+            let parameters = PyDict::new(py);
+            parameters.set_item("bottom_width", units.length_from_si(p.bottom_width))?;
+            parameters.set_item("side_slope", p.side_slope)?;
+            parameters.set_item("formation_time", seconds(p.formation_time)?)?;
+            parameters.set_item("height", units.length_from_si(p.height))?;
+            parameters.set_item("volume", units.volume_from_si(p.volume))?;
+            dict.set_item("parameters", parameters)?;
+        }
+        None => dict.set_item("parameters", py.None())?,
+    }
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, time_step, elevation, storage, outflow, initial_elevation, tailwater_discharge, tailwater_elevation, turbine_capacity, efficiency=0.9, efficiency_curve=None, power_capacity=None, min_head=0.0, loss_coefficient=0.0, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn hydropower<'py>(
+    py: Python<'py>,
+    inflow: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    elevation: Vec<f64>,
+    storage: Vec<f64>,
+    outflow: Vec<f64>,
+    initial_elevation: f64,
+    tailwater_discharge: Vec<f64>,
+    tailwater_elevation: Vec<f64>,
+    turbine_capacity: f64,
+    efficiency: f64,
+    efficiency_curve: Option<(Vec<f64>, Vec<f64>)>,
+    power_capacity: Option<f64>,
+    min_head: f64,
+    loss_coefficient: f64,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let discharge_to_si =
+        |values: &[f64]| -> Vec<f64> { values.iter().map(|&q| units.discharge_to_si(q)).collect() };
+    let length_to_si =
+        |values: &[f64]| -> Vec<f64> { values.iter().map(|&z| units.length_to_si(z)).collect() };
+    let efficiency = match efficiency_curve {
+        None => Efficiency::Constant(efficiency),
+        Some((flows, values)) => Efficiency::Curve(discharge_to_si(&flows), values),
+    };
+    let powerhouse = Powerhouse {
+        tailwater: (
+            discharge_to_si(&tailwater_discharge),
+            length_to_si(&tailwater_elevation),
+        ),
+        efficiency,
+        turbine_capacity: units.discharge_to_si(turbine_capacity),
+        power_capacity: power_capacity.map(|p| p * 1e6),
+        min_head: units.length_to_si(min_head),
+        loss_coefficient: units.length_to_si(loss_coefficient) / units.discharge_to_si(1.0).powi(2),
+    };
+    let mut reservoir = LevelPoolReservoir::new(
+        length_to_si(&elevation),
+        storage.iter().map(|&s| units.volume_to_si(s)).collect(),
+        discharge_to_si(&outflow),
+        units.length_to_si(initial_elevation),
+    )?;
+    let dt = timedelta_to_seconds(&time_step);
+    let inflow = discharge_to_si(&inflow.0);
+    let result = py.allow_threads(|| hydropower_rs(&inflow, dt, &mut reservoir, &powerhouse))?;
+
+    let discharge = |f: &dyn Fn(usize) -> f64| -> Vec<f64> {
+        (0..result.outflow.len())
+            .map(|i| units.discharge_from_si(f(i)))
+            .collect()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("outflow", discharge(&|i| result.outflow[i]))?;
+    dict.set_item(
+        "turbine_flow",
+        discharge(&|i| result.generation[i].turbine_flow),
+    )?;
+    dict.set_item("spill", discharge(&|i| result.generation[i].spill))?;
+    dict.set_item(
+        "elevation",
+        result
+            .elevation
+            .iter()
+            .map(|&z| units.length_from_si(z))
+            .collect::<Vec<_>>(),
+    )?;
+    dict.set_item(
+        "head",
+        result
+            .generation
+            .iter()
+            .map(|g| units.length_from_si(g.head))
+            .collect::<Vec<_>>(),
+    )?;
+    dict.set_item(
+        "power",
+        result
+            .generation
+            .iter()
+            .map(|g| g.power / 1e6)
+            .collect::<Vec<_>>(),
+    )?;
+    dict.set_item(
+        "energy",
+        result.energy.iter().map(|e| e / 3.6e9).collect::<Vec<_>>(),
+    )?;
+    dict.set_item("total_energy", result.total_energy() / 3.6e9)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (inflow, time_step, elevation, storage, initial_elevation, max_release, min_release=0.0, max_ramp=None, initial_release=None, min_elevation=None, max_elevation=None, target_elevation=None, channel_capacity=None, local_inflow=None, objective="peak_pool", release_levels=51, storage_levels=101, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn optimize_drawdown<'py>(
+    py: Python<'py>,
+    inflow: Vec<FloatSeries>,
+    time_step: Bound<'py, PyDelta>,
+    elevation: Vec<f64>,
+    storage: Vec<f64>,
+    initial_elevation: f64,
+    max_release: f64,
+    min_release: f64,
+    max_ramp: Option<f64>,
+    initial_release: Option<f64>,
+    min_elevation: Option<f64>,
+    max_elevation: Option<f64>,
+    target_elevation: Option<f64>,
+    channel_capacity: Option<f64>,
+    local_inflow: Option<Vec<FloatSeries>>,
+    objective: &str,
+    release_levels: usize,
+    storage_levels: usize,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let discharge_to_si =
+        |values: &[f64]| -> Vec<f64> { values.iter().map(|&q| units.discharge_to_si(q)).collect() };
+    let problem = DrawdownProblem {
+        inflow: inflow.iter().map(|m| discharge_to_si(&m.0)).collect(),
+        local_inflow: local_inflow
+            .unwrap_or_default()
+            .iter()
+            .map(|m| discharge_to_si(&m.0))
+            .collect(),
+        dt: timedelta_to_seconds(&time_step),
+        elevation: elevation.iter().map(|&z| units.length_to_si(z)).collect(),
+        storage: storage.iter().map(|&s| units.volume_to_si(s)).collect(),
+        initial_elevation: units.length_to_si(initial_elevation),
+        min_release: units.discharge_to_si(min_release),
+        max_release: units.discharge_to_si(max_release),
+        max_ramp: max_ramp.map(|r| units.discharge_to_si(r)),
+        initial_release: initial_release.map(|r| units.discharge_to_si(r)),
+        min_elevation: min_elevation.map(|z| units.length_to_si(z)),
+        max_elevation: max_elevation.map(|z| units.length_to_si(z)),
+        target_elevation: target_elevation.map(|z| units.length_to_si(z)),
+        channel_capacity: channel_capacity.map(|q| units.discharge_to_si(q)),
+        objective: DrawdownObjective::parse(objective)?,
+        release_levels,
+        storage_levels,
+    };
+    let result = py.allow_threads(|| optimize_drawdown_rs(&problem))?;
+
+    let members = |rows: &[Vec<f64>], f: &dyn Fn(f64) -> f64| -> Vec<Vec<f64>> {
+        rows.iter()
+            .map(|row| row.iter().map(|&v| f(v)).collect())
+            .collect()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item(
+        "release",
+        result
+            .release
+            .iter()
+            .map(|&q| units.discharge_from_si(q))
+            .collect::<Vec<_>>(),
+    )?;
+    dict.set_item(
+        "elevation",
+        members(&result.elevation, &|z| units.length_from_si(z)),
+    )?;
+    dict.set_item(
+        "storage",
+        members(&result.storage, &|s| units.volume_from_si(s)),
+    )?;
+    dict.set_item(
+        "downstream",
+        members(&result.downstream, &|q| units.discharge_from_si(q)),
+    )?;
+    dict.set_item(
+        "peak_elevation",
+        units.length_from_si(result.peak_elevation),
+    )?;
+    dict.set_item(
+        "exceedance_volume",
+        units.volume_from_si(result.exceedance_volume),
+    )?;
+    dict.set_item("exceedance_probability", result.exceedance_probability)?;
+    Ok(dict)
+}
+
+#[pymodule]
+pub fn init_reservoir(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(dam_breach, m)?)?;
+    m.add_function(wrap_pyfunction!(hydropower, m)?)?;
+    m.add_function(wrap_pyfunction!(level_pool_routing, m)?)?;
+    m.add_function(wrap_pyfunction!(optimize_drawdown, m)?)?;
+    m.add_function(wrap_pyfunction!(sequent_peak, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_yield, m)?)?;
+    m.add_function(wrap_pyfunction!(storage_yield_reliability, m)?)?;
+    Ok(())
+}
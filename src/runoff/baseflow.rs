@@ -0,0 +1,153 @@
+//! Baseflow added to direct runoff, as in the HEC-HMS baseflow methods, in
+//! SI units.
+
+use crate::datetime::civil_from_days;
+use crate::error::{Error, Result};
+
+/// Baseflow method of a subbasin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Baseflow {
+    /// The same baseflow (m³/s) throughout.
+    Constant(f64),
+    /// A baseflow (m³/s) for each calendar month, January first, applied
+    /// by the month of each step's time.
+    MonthlyConstant([f64; 12]),
+    /// Baseflow receding from `initial` (m³/s) by the factor `recession`
+    /// per day. Once the total flow of an event falls below `threshold`,
+    /// the total flow itself recedes by the same factor, until direct
+    /// runoff of a new event lifts it again.
+    Recession {
+        initial: f64,
+        recession: f64,
+        threshold: Threshold,
+    },
+}
+
+/// Flow below which the falling limb follows the recession.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Threshold {
+    /// A share of the event's peak total flow.
+    RatioToPeak(f64),
+    /// A fixed flow (m³/s).
+    Flow(f64),
+}
+
+/// Baseflow and total flow (direct runoff plus baseflow) at each step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BaseflowResult {
+    pub baseflow: Vec<f64>,
+    pub total: Vec<f64>,
+}
+
+impl Baseflow {
+    pub fn validate(&self) -> Result<()> {
+        let values: &[f64] = match self {
+            Baseflow::Constant(q) => std::slice::from_ref(q),
+            Baseflow::MonthlyConstant(q) => q,
+            Baseflow::Recession {
+                initial,
+                recession,
+                threshold,
+            } => {
+                let threshold_valid = match *threshold {
+                    Threshold::RatioToPeak(r) => r > 0.0 && r <= 1.0,
+                    Threshold::Flow(q) => q >= 0.0,
+                };
+                if !(*recession > 0.0 && *recession <= 1.0 && threshold_valid) {
+                    return Err(Error::InvalidParameter(
+                        "The recession constant and the ratio to peak must be within (0, 1], \
+                         and the threshold flow must not be negative."
+                            .into(),
+                    ));
+                }
+                std::slice::from_ref(initial)
+            }
+        };
+        if values.iter().any(|q| q.is_nan() || *q < 0.0) {
+            return Err(Error::InvalidParameter(
+                "Baseflow must not be negative.".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Adds baseflow to `direct` runoff (m³/s) whose first value is at
+    /// `start` (s since 1970-01-01) and whose values are `dt` s apart.
+    pub fn apply(&self, direct: &[f64], start: f64, dt: f64) -> Result<BaseflowResult> {
+        self.validate()?;
+        if dt.is_nan() || dt <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "The time step must be positive.".into(),
+            ));
+        }
+        let baseflow: Vec<f64> = match self {
+            Baseflow::Constant(q) => vec![*q; direct.len()],
+            Baseflow::MonthlyConstant(monthly) => (0..direct.len())
+                .map(|i| {
+                    let day = ((start + i as f64 * dt) / 86_400.0).floor() as i64;
+                    let (_, month, _) = civil_from_days(day);
+                    monthly[month as usize - 1]
+                })
+                .collect(),
+            Baseflow::Recession {
+                initial,
+                recession,
+                threshold,
+            } => recession_baseflow(direct, dt, *initial, *recession, *threshold),
+        };
+        let total = direct.iter().zip(&baseflow).map(|(d, b)| d + b).collect();
+        Ok(BaseflowResult { baseflow, total })
+    }
+}
+
+/// Baseflow of the recession method: the share of the total flow that is
+/// not direct runoff.
+fn recession_baseflow(
+    direct: &[f64],
+    dt: f64,
+    initial: f64,
+    recession: f64,
+    threshold: Threshold,
+) -> Vec<f64> {
+    let factor = recession.powf(dt / 86_400.0);
+    let mut base = initial;
+    // Receding total flow once the threshold has been passed.
+    let mut receding: Option<f64> = None;
+    // Peak total flow of the current event, once direct runoff has begun.
+    let mut peak: Option<f64> = None;
+    let mut baseflow = Vec::with_capacity(direct.len());
+    for (t, &d) in direct.iter().enumerate() {
+        if t > 0 {
+            base *= factor;
+            receding = receding.map(|r| r * factor);
+        }
+        let mut total = d + base;
+        match receding {
+            // Rising direct runoff starts a new event on top of the
+            // receding flow, which continues as its baseflow.
+            Some(r) if t > 0 && d > direct[t - 1] => {
+                base = base.max(r - d);
+                total = d + base;
+                receding = None;
+                peak = Some(total);
+            }
+            Some(r) => total = r,
+            None => {
+                if d > 0.0 || peak.is_some() {
+                    let event_peak = peak.map_or(total, |p: f64| p.max(total));
+                    peak = Some(event_peak);
+                    let limit = match threshold {
+                        Threshold::RatioToPeak(ratio) => ratio * event_peak,
+                        Threshold::Flow(q) => q,
+                    };
+                    if total < event_peak && total <= limit {
+                        receding = Some(limit.min(event_peak));
+                        total = limit.min(event_peak);
+                    }
+                }
+            }
+        }
+        baseflow.push(total - d);
+    }
+    baseflow
+}
@@ -0,0 +1,10 @@
+//! Rainfall–runoff transformations: losses, unit hydrographs, baseflow, the
+//! SWMM nonlinear reservoir and the UK ReFH model.
+
+pub mod baseflow;
+pub mod loss;
+pub mod nonlinear_reservoir;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod refh;
+pub mod unit_hydrograph;
@@ -0,0 +1,438 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDict, PyType};
+
+use super::baseflow::{Baseflow, Threshold};
+use super::loss::LossMethod;
+use super::nonlinear_reservoir::{nonlinear_reservoir_runoff_rs, NonlinearReservoir};
+use super::refh::{CatchmentDescriptors, Refh};
+use super::unit_hydrograph::clark_unit_hydrograph as clark_unit_hydrograph_rs;
+use crate::arrow::FloatSeries;
+use crate::time::{datetime_to_epoch, seconds_to_timedelta, timedelta_to_seconds};
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (rainfall, time_step, area, width, slope, n, depression_storage=0.0, loss_rate=0.0, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn nonlinear_reservoir_runoff(
+    py: Python<'_>,
+    rainfall: FloatSeries,
+    time_step: Bound<'_, PyDelta>,
+    area: f64,
+    width: f64,
+    slope: f64,
+    n: f64,
+    depression_storage: f64,
+    loss_rate: f64,
+    units: UnitSystem,
+) -> PyResult<Vec<f64>> {
+    let mut reservoir = NonlinearReservoir::new(
+        units.land_area_to_si(area),
+        units.length_to_si(width),
+        slope,
+        n,
+        units.small_depth_to_si(depression_storage),
+        units.rate_to_si(loss_rate),
+    )?;
+    let dt = timedelta_to_seconds(&time_step);
+    let rain_si: Vec<f64> = rainfall.0.iter().map(|&i| units.rate_to_si(i)).collect();
+    let runoff = py.allow_threads(|| nonlinear_reservoir_runoff_rs(&rain_si, dt, &mut reservoir));
+    Ok(runoff
+        .into_iter()
+        .map(|q| units.discharge_from_si(q))
+        .collect())
+}
+
+/// SWMM-style nonlinear reservoir run over a long rainfall record block by
+/// block. The ponded depth carries over between blocks, so the runoff is
+/// the same as from `nonlinear_reservoir_runoff` over the whole record.
+///
+/// Args:
+///     time_step (timedelta): Interval between consecutive values.
+///     area (float): Subcatchment area (ha or acres).
+///     width (float): Overland flow width (m or ft).
+///     slope (float): Surface slope (m/m).
+///     n (float): Manning's roughness for overland flow.
+///     depression_storage (float, optional): Depression storage (mm or
+///         in). Defaults to 0.0.
+///     loss_rate (float, optional): Constant loss rate (mm/h or in/h).
+///         Defaults to 0.0.
+///     units (Literal["si", "us"], optional): Unit system. Defaults to
+///         `"si"`.
+#[pyclass(name = "NonlinearReservoir", module = "rustflow.runoff")]
+pub struct PyNonlinearReservoir {
+    inner: NonlinearReservoir,
+    dt: f64,
+    units: UnitSystem,
+}
+
+#[pymethods]
+impl PyNonlinearReservoir {
+    #[new]
+    #[pyo3(signature = (time_step, area, width, slope, n, depression_storage=0.0, loss_rate=0.0, units=UnitSystem::Si))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        time_step: Bound<'_, PyDelta>,
+        area: f64,
+        width: f64,
+        slope: f64,
+        n: f64,
+        depression_storage: f64,
+        loss_rate: f64,
+        units: UnitSystem,
+    ) -> PyResult<Self> {
+        let inner = NonlinearReservoir::new(
+            units.land_area_to_si(area),
+            units.length_to_si(width),
+            slope,
+            n,
+            units.small_depth_to_si(depression_storage),
+            units.rate_to_si(loss_rate),
+        )?;
+        Ok(PyNonlinearReservoir {
+            inner,
+            dt: timedelta_to_seconds(&time_step),
+            units,
+        })
+    }
+
+    /// Runoff for the next block of rainfall.
+    ///
+    /// Args:
+    ///     rainfall (Sequence[float]): Rainfall intensity (mm/h or in/h).
+    ///
+    /// Returns:
+    ///     list[float]: Runoff at each step (m³/s or cfs).
+    fn runoff(&mut self, py: Python<'_>, rainfall: FloatSeries) -> Vec<f64> {
+        let units = self.units;
+        let rain_si: Vec<f64> = rainfall.0.iter().map(|&i| units.rate_to_si(i)).collect();
+        py.allow_threads(|| self.inner.runoff(&rain_si, self.dt))
+            .into_iter()
+            .map(|q| units.discharge_from_si(q))
+            .collect()
+    }
+
+    /// Empties the surface so the next block starts a new record.
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    /// float: Ponded depth at the end of the last block (mm or in).
+    #[getter]
+    fn depth(&self) -> f64 {
+        self.units.small_depth_from_si(self.inner.depth())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NonlinearReservoir(area={} m², width={} m, slope={}, n={})",
+            self.inner.area, self.inner.width, self.inner.slope, self.inner.manning_n
+        )
+    }
+
+    /// Pickles the parameters and the ponded depth, so a run can resume in
+    /// another process.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, ([f64; 6], f64, &'static str, f64))> {
+        let this = slf.borrow();
+        let r = &this.inner;
+        Ok((
+            slf.get_type().getattr("_from_state")?,
+            (
+                [
+                    r.area,
+                    r.width,
+                    r.slope,
+                    r.manning_n,
+                    r.depression_storage,
+                    r.loss_rate,
+                ],
+                this.dt,
+                this.units.name(),
+                r.depth(),
+            ),
+        ))
+    }
+
+    #[classmethod]
+    fn _from_state(
+        _cls: &Bound<'_, PyType>,
+        parameters: [f64; 6],
+        dt: f64,
+        units: UnitSystem,
+        depth: f64,
+    ) -> PyResult<Self> {
+        let [area, width, slope, n, depression_storage, loss_rate] = parameters;
+        let mut inner =
+            NonlinearReservoir::new(area, width, slope, n, depression_storage, loss_rate)?;
+        inner.set_depth(depth);
+        Ok(PyNonlinearReservoir { inner, dt, units })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (time_area, storage_coefficient, time_step, units=UnitSystem::Si))]
+pub fn clark_unit_hydrograph(
+    time_area: Vec<f64>,
+    storage_coefficient: Bound<'_, PyDelta>,
+    time_step: Bound<'_, PyDelta>,
+    units: UnitSystem,
+) -> PyResult<Vec<f64>> {
+    let time_area: Vec<f64> = time_area
+        .iter()
+        .map(|&a| units.basin_area_to_si(a))
+        .collect();
+    let ordinates = clark_unit_hydrograph_rs(
+        &time_area,
+        timedelta_to_seconds(&storage_coefficient),
+        timedelta_to_seconds(&time_step),
+    )?;
+    // Per mm or inch of excess.
+    let per_depth = units.small_depth_to_si(1.0);
+    Ok(ordinates
+        .into_iter()
+        .map(|u| units.discharge_from_si(u * per_depth))
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (rainfall, time_step, method, initial_loss=0.0, loss_rate=0.0, curve_number=None, initial_abstraction_ratio=0.2, initial_index=0.0, decay=0.9, capacity=None, exponent=1.0, max_deficit=None, initial_deficit=0.0, recovery_rate=0.0, impervious_fraction=0.0, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn rainfall_excess(
+    py: Python<'_>,
+    rainfall: FloatSeries,
+    time_step: Bound<'_, PyDelta>,
+    method: &str,
+    initial_loss: f64,
+    loss_rate: f64,
+    curve_number: Option<f64>,
+    initial_abstraction_ratio: f64,
+    initial_index: f64,
+    decay: f64,
+    capacity: Option<f64>,
+    exponent: f64,
+    max_deficit: Option<f64>,
+    initial_deficit: f64,
+    recovery_rate: f64,
+    impervious_fraction: f64,
+    units: UnitSystem,
+) -> PyResult<Vec<f64>> {
+    let required = |value: Option<f64>, name: &str| {
+        value.ok_or_else(|| {
+            PyValueError::new_err(format!("Loss method `{method}` requires `{name}`."))
+        })
+    };
+    let depth = |d: f64| units.small_depth_to_si(d);
+    let loss = match method.to_ascii_lowercase().as_str() {
+        "initial_constant" => LossMethod::InitialConstant {
+            initial: depth(initial_loss),
+            rate: units.rate_to_si(loss_rate),
+        },
+        "curve_number" | "scs" => LossMethod::CurveNumber {
+            curve_number: required(curve_number, "curve_number")?,
+            ratio: initial_abstraction_ratio,
+        },
+        "api" | "antecedent_precipitation" => LossMethod::AntecedentPrecipitation {
+            initial: depth(initial_index),
+            decay,
+            capacity: depth(required(capacity, "capacity")?),
+            exponent,
+        },
+        "deficit_constant" | "sma" => LossMethod::DeficitConstant {
+            max_deficit: depth(required(max_deficit, "max_deficit")?),
+            initial: depth(initial_deficit),
+            rate: units.rate_to_si(loss_rate),
+            recovery: units.rate_to_si(recovery_rate),
+        },
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown loss method `{other}`; expected `initial_constant`, `curve_number`, \
+                 `api` or `deficit_constant`."
+            )))
+        }
+    };
+    let dt = timedelta_to_seconds(&time_step);
+    let rain: Vec<f64> = rainfall.0.iter().map(|&p| depth(p)).collect();
+    let excess =
+        py.allow_threads(|| loss.excess_with_impervious(&rain, dt, impervious_fraction))?;
+    Ok(excess
+        .into_iter()
+        .map(|e| units.small_depth_from_si(e))
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (direct_runoff, time_step, method="constant", flow=0.0, monthly=None, start=None, recession_constant=None, ratio_to_peak=None, threshold_flow=None, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn baseflow<'py>(
+    py: Python<'py>,
+    direct_runoff: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    method: &str,
+    flow: f64,
+    monthly: Option<Vec<f64>>,
+    start: Option<Bound<'py, PyDateTime>>,
+    recession_constant: Option<f64>,
+    ratio_to_peak: Option<f64>,
+    threshold_flow: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let method = match method.to_ascii_lowercase().as_str() {
+        "constant" => Baseflow::Constant(units.discharge_to_si(flow)),
+        "monthly_constant" | "monthly" => {
+            let monthly: [f64; 12] = monthly
+                .ok_or_else(|| {
+                    PyValueError::new_err("Baseflow method `monthly_constant` requires `monthly`.")
+                })?
+                .iter()
+                .map(|&q| units.discharge_to_si(q))
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| PyValueError::new_err("`monthly` must have 12 values."))?;
+            if start.is_none() {
+                return Err(PyValueError::new_err(
+                    "Baseflow method `monthly_constant` requires `start`.",
+                ));
+            }
+            Baseflow::MonthlyConstant(monthly)
+        }
+        "recession" => {
+            let threshold = match (ratio_to_peak, threshold_flow) {
+                (Some(ratio), None) => Threshold::RatioToPeak(ratio),
+                (None, Some(q)) => Threshold::Flow(units.discharge_to_si(q)),
+                _ => {
+                    return Err(PyValueError::new_err(
+                        "Baseflow method `recession` requires one of `ratio_to_peak` and \
+                         `threshold_flow`.",
+                    ))
+                }
+            };
+            Baseflow::Recession {
+                initial: units.discharge_to_si(flow),
+                recession: recession_constant.ok_or_else(|| {
+                    PyValueError::new_err(
+                        "Baseflow method `recession` requires `recession_constant`.",
+                    )
+                })?,
+                threshold,
+            }
+        }
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown baseflow method `{other}`; expected `constant`, `monthly_constant` or \
+                 `recession`."
+            )))
+        }
+    };
+    let dt = timedelta_to_seconds(&time_step);
+    let start = start.as_ref().map_or(0.0, datetime_to_epoch);
+    let direct: Vec<f64> = direct_runoff
+        .0
+        .iter()
+        .map(|&q| units.discharge_to_si(q))
+        .collect();
+    let result = method.apply(&direct, start, dt)?;
+    let discharge = |values: &[f64]| -> Vec<f64> {
+        values.iter().map(|&q| units.discharge_from_si(q)).collect()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("baseflow", discharge(&result.baseflow))?;
+    dict.set_item("total", discharge(&result.total))?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (area, propwet, dplbar, dpsbar, bfihost, urbext=0.0))]
+pub fn refh_parameters(
+    py: Python<'_>,
+    area: f64,
+    propwet: f64,
+    dplbar: f64,
+    dpsbar: f64,
+    bfihost: f64,
+    urbext: f64,
+) -> PyResult<Bound<'_, PyDict>> {
+    let descriptors = CatchmentDescriptors {
+        area,
+        propwet,
+        dplbar,
+        dpsbar,
+        bfihost,
+        urbext,
+    };
+    let refh = Refh::from_descriptors(&descriptors, 0.0, 0.0)?;
+    let dict = PyDict::new(py);
+    dict.set_item("time_to_peak", seconds_to_timedelta(py, refh.tp)?)?;
+    dict.set_item("max_moisture", refh.cmax * 1000.0)?;
+    dict.set_item("baseflow_lag", seconds_to_timedelta(py, refh.bl)?)?;
+    dict.set_item("baseflow_recharge", refh.br)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (rainfall, time_step, area, time_to_peak, max_moisture, baseflow_lag, baseflow_recharge, initial_moisture=0.0, initial_baseflow=0.0, peak=Refh::UP, kink=Refh::UK, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn refh<'py>(
+    py: Python<'py>,
+    rainfall: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    area: f64,
+    time_to_peak: Bound<'py, PyDelta>,
+    max_moisture: f64,
+    baseflow_lag: Bound<'py, PyDelta>,
+    baseflow_recharge: f64,
+    initial_moisture: f64,
+    initial_baseflow: f64,
+    peak: f64,
+    kink: f64,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    let model = Refh {
+        area: units.basin_area_to_si(area),
+        tp: timedelta_to_seconds(&time_to_peak),
+        up: peak,
+        uk: kink,
+        cmax: units.small_depth_to_si(max_moisture),
+        cini: units.small_depth_to_si(initial_moisture),
+        bl: timedelta_to_seconds(&baseflow_lag),
+        br: baseflow_recharge,
+        bf0: units.discharge_to_si(initial_baseflow),
+    };
+    let dt = timedelta_to_seconds(&time_step);
+    let rain: Vec<f64> = rainfall
+        .0
+        .iter()
+        .map(|&p| units.small_depth_to_si(p))
+        .collect();
+    let result = py.allow_threads(|| model.simulate(&rain, dt))?;
+    let discharge = |values: &[f64]| -> Vec<f64> {
+        values.iter().map(|&q| units.discharge_from_si(q)).collect()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item(
+        "excess",
+        result
+            .excess
+            .iter()
+            .map(|&e| units.small_depth_from_si(e))
+            .collect::<Vec<f64>>(),
+    )?;
+    dict.set_item("direct", discharge(&result.direct))?;
+    dict.set_item("baseflow", discharge(&result.baseflow))?;
+    dict.set_item("total", discharge(&result.total))?;
+    Ok(dict)
+}
+
+#[pymodule]
+pub fn init_runoff(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(baseflow, m)?)?;
+    m.add_function(wrap_pyfunction!(clark_unit_hydrograph, m)?)?;
+    m.add_function(wrap_pyfunction!(nonlinear_reservoir_runoff, m)?)?;
+    m.add_function(wrap_pyfunction!(rainfall_excess, m)?)?;
+    m.add_function(wrap_pyfunction!(refh, m)?)?;
+    m.add_function(wrap_pyfunction!(refh_parameters, m)?)?;
+    m.add_class::<PyNonlinearReservoir>()?;
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! Unit hydrographs and their convolution with rainfall excess, in SI
+//! units. Ordinates are discharges (m³/s) per metre of excess in one step.
+
+use crate::error::{Error, Result};
+use crate::interp::interp;
+use crate::kernels;
+
+/// NRCS dimensionless unit hydrograph: `t / tp` and `q / qp` (National
+/// Engineering Handbook, Part 630, Chapter 16, Table 16-1).
+const SCS_TIME: [f64; 33] = [
+    0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5, 1.6, 1.7, 1.8,
+    1.9, 2.0, 2.2, 2.4, 2.6, 2.8, 3.0, 3.2, 3.4, 3.6, 3.8, 4.0, 4.5, 5.0,
+];
+const SCS_DISCHARGE: [f64; 33] = [
+    0.0, 0.030, 0.100, 0.190, 0.310, 0.470, 0.660, 0.820, 0.930, 0.990, 1.000, 0.990, 0.930, 0.860,
+    0.780, 0.680, 0.560, 0.460, 0.390, 0.330, 0.280, 0.207, 0.147, 0.107, 0.077, 0.055, 0.040,
+    0.029, 0.021, 0.015, 0.011, 0.005, 0.0,
+];
+
+/// NRCS unit hydrograph of a basin of `area` (m²) with lag `lag` (s) for
+/// excess in steps of `dt` (s). Time to peak is `dt / 2 + lag`, and the
+/// ordinates are scaled so that they carry exactly one metre over the
+/// basin.
+pub fn scs_unit_hydrograph(area: f64, lag: f64, dt: f64) -> Result<Vec<f64>> {
+    if !(area > 0.0 && lag > 0.0 && dt > 0.0) {
+        return Err(Error::InvalidParameter(
+            "Basin area, lag and time step must be positive.".into(),
+        ));
+    }
+    let peak_time = 0.5 * dt + lag;
+    let steps = (5.0 * peak_time / dt).ceil() as usize;
+    let shape: Vec<f64> = (0..=steps)
+        .map(|k| interp(k as f64 * dt / peak_time, &SCS_TIME, &SCS_DISCHARGE).max(0.0))
+        .collect();
+    let total: f64 = shape.iter().sum::<f64>() * dt;
+    Ok(shape.into_iter().map(|q| q * area / total).collect())
+}
+
+/// Clark unit hydrograph for excess in steps of `dt` (s): `time_area`
+/// (m²) is the catchment area reaching the outlet within each step, as
+/// from [`crate::terrain::travel_time::TravelTime::time_area`], translated
+/// to the outlet and routed through a linear reservoir with storage
+/// coefficient `storage` (s). The ordinates are mean outflows over each
+/// step, carried until the reservoir has released all but a
+/// millionth of the excess, and scaled so that they carry exactly one
+/// metre over the basin.
+pub fn clark_unit_hydrograph(time_area: &[f64], storage: f64, dt: f64) -> Result<Vec<f64>> {
+    if storage.is_nan() || storage < 0.0 || dt.is_nan() || dt <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The storage coefficient must not be negative and the time step must be positive."
+                .into(),
+        ));
+    }
+    if time_area.iter().any(|a| a.is_nan() || *a < 0.0) {
+        return Err(Error::InvalidParameter(
+            "Time–area increments must not be negative.".into(),
+        ));
+    }
+    let area: f64 = time_area.iter().sum();
+    if area <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The time–area histogram must have a positive total area.".into(),
+        ));
+    }
+    if storage == 0.0 {
+        return Ok(time_area.iter().map(|a| a / dt).collect());
+    }
+    // Exact linear reservoir over each step with constant inflow.
+    let decay = (-dt / storage).exp();
+    let mut volume = 0.0;
+    let mut ordinates = Vec::new();
+    let mut k = 0;
+    while k < time_area.len() || volume > 1e-6 * area {
+        let inflow = time_area.get(k).map_or(0.0, |a| a / dt);
+        let end = volume * decay + inflow * storage * (1.0 - decay);
+        ordinates.push(inflow - (end - volume) / dt);
+        volume = end;
+        k += 1;
+    }
+    let total: f64 = ordinates.iter().sum::<f64>() * dt;
+    Ok(ordinates.into_iter().map(|q| q * area / total).collect())
+}
+
+/// Compresses a unit hydrograph in time by `factor` (> 0): the time to
+/// peak is divided by `factor` and the peak rises by about the same ratio,
+/// while the volume is kept. Used for the peaking adjustments of probable
+/// maximum flood studies.
+pub fn adjust_peak(ordinates: &[f64], dt: f64, factor: f64) -> Result<Vec<f64>> {
+    if factor.is_nan() || factor <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The peaking factor must be positive.".into(),
+        ));
+    }
+    let volume: f64 = ordinates.iter().sum::<f64>() * dt;
+    if factor == 1.0 || volume <= 0.0 {
+        return Ok(ordinates.to_vec());
+    }
+    let times: Vec<f64> = (0..ordinates.len()).map(|k| k as f64).collect();
+    let last = (ordinates.len() - 1) as f64;
+    let steps = (last / factor).ceil() as usize;
+    let shape: Vec<f64> = (0..=steps)
+        .map(|k| {
+            let t = k as f64 * factor;
+            if t > last {
+                0.0
+            } else {
+                interp(t, &times, ordinates)
+            }
+        })
+        .collect();
+    let total: f64 = shape.iter().sum::<f64>() * dt;
+    Ok(shape.into_iter().map(|q| q * volume / total).collect())
+}
+
+/// Direct runoff (m³/s) from `excess` (m per step) and `ordinates`; the
+/// result has `excess.len() + ordinates.len() - 1` values.
+pub fn convolve(excess: &[f64], ordinates: &[f64]) -> Vec<f64> {
+    kernels::convolve(excess, ordinates)
+}
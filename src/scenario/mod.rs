@@ -0,0 +1,286 @@
+//! Scenario manager for batch what-if runs: a base network configuration
+//! plus a list of parameter, structure and inflow overrides, run in
+//! parallel and compared by peak, volume and timing at selected nodes.
+
+#[cfg(feature = "python")]
+pub mod python;
+
+use crate::config::Value;
+use crate::error::{Error, Result};
+use crate::network::Network;
+use crate::parallel::par_map;
+use std::collections::BTreeMap;
+
+/// Changes applied to the base network and inputs for one scenario.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    pub name: String,
+    /// Fields set on existing nodes, keyed by node id.
+    pub overrides: Vec<(String, Value)>,
+    /// Node configurations added to the network, such as new detention.
+    pub added: Vec<Value>,
+    /// Ids of nodes removed from the network.
+    pub removed: Vec<String>,
+    /// Factor applied to every input series without its own factor.
+    pub scale: f64,
+    /// Factors applied to individual input series, such as climate-scaled
+    /// inflows.
+    pub series_scale: BTreeMap<String, f64>,
+}
+
+impl Scenario {
+    /// Builds a scenario from a table with a `name` and optional `nodes`
+    /// (node id → fields to set), `add` (node configurations), `remove`
+    /// (node ids) and `scale` (a factor for all series, or a table of
+    /// factors by series name) fields.
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let name = value.str_field("name")?.to_string();
+        let overrides = match value.get("nodes") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Object(entries)) => entries.clone(),
+            Some(_) => {
+                return Err(Error::Data(format!(
+                    "Field `nodes` of scenario `{name}` must map node ids to fields."
+                )))
+            }
+        };
+        let added = match value.get("add") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(items) => items
+                .as_array()
+                .ok_or_else(|| {
+                    Error::Data(format!(
+                        "Field `add` of scenario `{name}` must be an array of nodes."
+                    ))
+                })?
+                .to_vec(),
+        };
+        let removed = match value.get("remove") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(items) => items
+                .as_array()
+                .and_then(|ids| {
+                    ids.iter()
+                        .map(|id| id.as_str().map(str::to_string))
+                        .collect()
+                })
+                .ok_or_else(|| {
+                    Error::Data(format!(
+                        "Field `remove` of scenario `{name}` must be an array of node ids."
+                    ))
+                })?,
+        };
+        let (scale, series_scale) = match value.get("scale") {
+            None | Some(Value::Null) => (1.0, BTreeMap::new()),
+            Some(Value::Number(factor)) => (*factor, BTreeMap::new()),
+            Some(Value::Object(entries)) => (
+                1.0,
+                entries
+                    .iter()
+                    .map(|(series, factor)| {
+                        factor.as_f64().map(|f| (series.clone(), f)).ok_or_else(|| {
+                            Error::Data(format!(
+                                "Scale factor of series `{series}` in scenario `{name}` must be a number."
+                            ))
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            Some(_) => {
+                return Err(Error::Data(format!(
+                    "Field `scale` of scenario `{name}` must be a number or a table of factors."
+                )))
+            }
+        };
+        Ok(Scenario {
+            name,
+            overrides,
+            added,
+            removed,
+            scale,
+            series_scale,
+        })
+    }
+
+    /// Applies the structural and parameter changes to a network
+    /// configuration with a `nodes` or `series` array. Added nodes are
+    /// appended, so in a series system they drain from its last node.
+    pub fn apply(&self, base: &Value) -> Result<Value> {
+        let key = if base.get("series").is_some() {
+            "series"
+        } else {
+            "nodes"
+        };
+        let mut nodes = base
+            .get(key)
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                Error::Data(
+                    "Configuration must contain either a `nodes` or a `series` array.".into(),
+                )
+            })?
+            .to_vec();
+        let id_of = |node: &Value| node.get("id").and_then(Value::as_str).map(str::to_string);
+        for id in &self.removed {
+            let before = nodes.len();
+            nodes.retain(|node| id_of(node).as_deref() != Some(id.as_str()));
+            if nodes.len() == before {
+                return Err(self.unknown_node(id));
+            }
+        }
+        for (id, fields) in &self.overrides {
+            let node = nodes
+                .iter_mut()
+                .find(|node| id_of(node).as_deref() == Some(id.as_str()))
+                .ok_or_else(|| self.unknown_node(id))?;
+            let Value::Object(fields) = fields else {
+                return Err(Error::Data(format!(
+                    "Overrides of node `{id}` in scenario `{}` must be a table of fields.",
+                    self.name
+                )));
+            };
+            for (field, value) in fields {
+                node.insert(field, value.clone());
+            }
+        }
+        nodes.extend(self.added.iter().cloned());
+        let mut config = base.clone();
+        config.insert(key, Value::Array(nodes));
+        Ok(config)
+    }
+
+    /// Input series multiplied by the scenario's factors.
+    pub fn scale_series(&self, series: &BTreeMap<String, Vec<f64>>) -> BTreeMap<String, Vec<f64>> {
+        series
+            .iter()
+            .map(|(name, values)| {
+                let factor = self.series_scale.get(name).copied().unwrap_or(self.scale);
+                (name.clone(), values.iter().map(|v| v * factor).collect())
+            })
+            .collect()
+    }
+
+    fn unknown_node(&self, id: &str) -> Error {
+        Error::Data(format!(
+            "Scenario `{}` refers to unknown node `{id}`.",
+            self.name
+        ))
+    }
+}
+
+/// Outflow summary of one node in one scenario.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioRow {
+    pub scenario: String,
+    pub node: String,
+    /// Peak outflow (flow units); `NaN` if the node is not in the scenario.
+    pub peak: f64,
+    /// Time of the peak from the start of the run (s).
+    pub peak_time: f64,
+    /// Outflow volume over the run (flow units × seconds).
+    pub volume: f64,
+    /// Peak less the baseline scenario's peak at the node (`NaN` without a
+    /// baseline).
+    pub peak_change: f64,
+    /// Volume less the baseline scenario's volume at the node.
+    pub volume_change: f64,
+}
+
+impl Scenario {
+    /// The unchanged base network and inputs.
+    pub fn base(name: &str) -> Self {
+        Scenario {
+            name: name.to_string(),
+            overrides: Vec::new(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            scale: 1.0,
+            series_scale: BTreeMap::new(),
+        }
+    }
+}
+
+/// Runs every scenario on the `base` network configuration in parallel and
+/// summarises the outflow of `nodes` (every node of each scenario when
+/// empty), one row per scenario and node in input order. Changes are
+/// relative to the scenario named `baseline`, if given.
+pub fn run_scenarios_rs(
+    base: &Value,
+    series: &BTreeMap<String, Vec<f64>>,
+    dt: f64,
+    scenarios: &[Scenario],
+    nodes: &[String],
+    baseline: Option<&str>,
+) -> Result<Vec<ScenarioRow>> {
+    let mut names = std::collections::HashSet::new();
+    if let Some(scenario) = scenarios.iter().find(|s| !names.insert(s.name.as_str())) {
+        return Err(Error::Data(format!(
+            "Duplicate scenario name `{}`.",
+            scenario.name
+        )));
+    }
+    let tables = par_map(scenarios, |scenario| -> Result<Vec<ScenarioRow>> {
+        let mut network = Network::from_value(&scenario.apply(base)?)?;
+        let result = network.run(&scenario.scale_series(series), dt)?;
+        let selected: Vec<&String> = if nodes.is_empty() {
+            result.node_ids.iter().collect()
+        } else {
+            nodes.iter().collect()
+        };
+        Ok(selected
+            .into_iter()
+            .map(|node| {
+                let (peak, peak_time, volume) = match result.outflow_of(node) {
+                    None => (f64::NAN, f64::NAN, f64::NAN),
+                    Some(outflow) => {
+                        let (step, peak) = outflow.iter().copied().enumerate().fold(
+                            (0, f64::NEG_INFINITY),
+                            |best, (t, q)| {
+                                if q > best.1 {
+                                    (t, q)
+                                } else {
+                                    best
+                                }
+                            },
+                        );
+                        (peak, step as f64 * dt, outflow.iter().sum::<f64>() * dt)
+                    }
+                };
+                ScenarioRow {
+                    scenario: scenario.name.clone(),
+                    node: node.clone(),
+                    peak,
+                    peak_time,
+                    volume,
+                    peak_change: f64::NAN,
+                    volume_change: f64::NAN,
+                }
+            })
+            .collect())
+    });
+    let mut rows: Vec<ScenarioRow> = tables
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+    if let Some(baseline) = baseline {
+        if !names.contains(baseline) {
+            return Err(Error::Data(format!(
+                "Baseline scenario `{baseline}` is not among the scenarios."
+            )));
+        }
+        let reference: BTreeMap<String, (f64, f64)> = rows
+            .iter()
+            .filter(|row| row.scenario == baseline)
+            .map(|row| (row.node.clone(), (row.peak, row.volume)))
+            .collect();
+        for row in &mut rows {
+            if let Some((peak, volume)) = reference.get(&row.node) {
+                row.peak_change = row.peak - peak;
+                row.volume_change = row.volume - volume;
+            }
+        }
+    }
+    Ok(rows)
+}
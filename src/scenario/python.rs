@@ -0,0 +1,77 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+use std::collections::BTreeMap;
+
+use super::{run_scenarios_rs, Scenario};
+use crate::arrow::FloatSeries;
+use crate::config::Value;
+use crate::time::timedelta_to_seconds;
+
+#[pyfunction]
+#[pyo3(signature = (base, inflows, time_step, scenarios, nodes=None, include_base=true))]
+pub fn run<'py>(
+    py: Python<'py>,
+    base: Value,
+    inflows: BTreeMap<String, FloatSeries>,
+    time_step: Bound<'py, PyDelta>,
+    scenarios: Vec<Value>,
+    nodes: Option<Vec<String>>,
+    include_base: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut runs = Vec::with_capacity(scenarios.len() + 1);
+    if include_base {
+        runs.push(Scenario::base("base"));
+    }
+    for scenario in &scenarios {
+        runs.push(Scenario::from_value(scenario)?);
+    }
+    let series: BTreeMap<String, Vec<f64>> = inflows.into_iter().map(|(k, v)| (k, v.0)).collect();
+    let dt = timedelta_to_seconds(&time_step);
+    let nodes = nodes.unwrap_or_default();
+    let baseline = include_base.then_some("base");
+    let rows =
+        py.allow_threads(|| run_scenarios_rs(&base, &series, dt, &runs, &nodes, baseline))?;
+
+    let column =
+        |f: &dyn Fn(&super::ScenarioRow) -> f64| -> Vec<f64> { rows.iter().map(f).collect() };
+    let table = PyDict::new(py);
+    table.set_item(
+        "scenario",
+        rows.iter().map(|r| r.scenario.clone()).collect::<Vec<_>>(),
+    )?;
+    table.set_item(
+        "node",
+        rows.iter().map(|r| r.node.clone()).collect::<Vec<_>>(),
+    )?;
+    table.set_item("peak", column(&|r| r.peak))?;
+    table.set_item(
+        "peak_time",
+        rows.iter()
+            .map(|r| {
+                if r.peak_time.is_nan() {
+                    Ok(None)
+                } else {
+                    let whole = r.peak_time.floor();
+                    PyDelta::new(
+                        py,
+                        0,
+                        whole as i32,
+                        ((r.peak_time - whole) * 1e6).round() as i32,
+                        true,
+                    )
+                    .map(Some)
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?,
+    )?;
+    table.set_item("volume", column(&|r| r.volume))?;
+    table.set_item("peak_change", column(&|r| r.peak_change))?;
+    table.set_item("volume_change", column(&|r| r.volume_change))?;
+    Ok(table)
+}
+
+#[pymodule]
+pub fn init_scenario(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use crate::error::{Error, Result};
+use crate::hydraulics::manning::TrapezoidalChannel;
+
+/// Gravitational acceleration (m/s²).
+const G: f64 = 9.81;
+/// Density of water (kg/m³).
+const WATER_DENSITY: f64 = 1000.0;
+/// Kinematic viscosity of water at about 20 °C (m²/s).
+const VISCOSITY: f64 = 1.0e-6;
+
+/// Sediment transport formula.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Formula {
+    /// Engelund–Hansen (1967) total load, for sand beds.
+    EngelundHansen,
+    /// Yang (1973) unit stream power total load, for sand beds.
+    Yang,
+    /// Meyer-Peter–Müller (1948) bedload, for gravel beds.
+    MeyerPeterMuller,
+}
+
+impl Formula {
+    /// Parses `"engelund_hansen"`, `"yang"` or `"meyer_peter_muller"`
+    /// (case-insensitive).
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "engelund_hansen" => Ok(Formula::EngelundHansen),
+            "yang" => Ok(Formula::Yang),
+            "meyer_peter_muller" | "mpm" => Ok(Formula::MeyerPeterMuller),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown transport formula `{name}`; expected `engelund_hansen`, `yang` or \
+                 `meyer_peter_muller`."
+            ))),
+        }
+    }
+}
+
+/// Uniform bed material, in SI units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BedMaterial {
+    /// Median grain diameter (m).
+    pub d50: f64,
+    /// Specific gravity of the grains.
+    pub specific_gravity: f64,
+}
+
+impl BedMaterial {
+    pub fn new(d50: f64, specific_gravity: f64) -> Result<Self> {
+        if d50 <= 0.0 || specific_gravity <= 1.0 {
+            return Err(Error::InvalidParameter(
+                "Grain size must be positive and specific gravity greater than 1.".into(),
+            ));
+        }
+        Ok(BedMaterial {
+            d50,
+            specific_gravity,
+        })
+    }
+
+    /// Settling velocity (m/s) from Ferguson and Church (2004) for natural
+    /// grains.
+    pub fn fall_velocity(&self) -> f64 {
+        let r = self.specific_gravity - 1.0;
+        let d = self.d50;
+        r * G * d * d / (18.0 * VISCOSITY + (0.75 * 1.0 * r * G * d.powi(3)).sqrt())
+    }
+
+    /// Shields parameter for a bed shear stress `ρ g R S`.
+    fn shields(&self, hydraulic_radius: f64, slope: f64) -> f64 {
+        hydraulic_radius * slope / ((self.specific_gravity - 1.0) * self.d50)
+    }
+}
+
+/// Sediment transport capacity (kg/s) of `channel` carrying `discharge`
+/// (m³/s) at normal depth.
+pub fn transport_capacity(
+    formula: Formula,
+    material: &BedMaterial,
+    channel: &TrapezoidalChannel,
+    discharge: f64,
+) -> f64 {
+    if discharge <= 0.0 {
+        return 0.0;
+    }
+    let depth = channel.normal_depth(discharge);
+    let velocity = channel.velocity(depth);
+    let radius = channel.hydraulic_radius(depth);
+    let slope = channel.bed_slope;
+    let s = material.specific_gravity;
+    let d = material.d50;
+    let grain_density = s * WATER_DENSITY;
+    let capacity = match formula {
+        Formula::EngelundHansen => {
+            let theta = material.shields(radius, slope);
+            let per_width =
+                0.05 * velocity * velocity * (d / (G * (s - 1.0))).sqrt() * theta.powf(1.5);
+            per_width * channel.top_width(depth) * grain_density
+        }
+        Formula::MeyerPeterMuller => {
+            let excess = material.shields(radius, slope) - 0.047;
+            if excess <= 0.0 {
+                return 0.0;
+            }
+            let per_width = 8.0 * excess.powf(1.5) * ((s - 1.0) * G * d.powi(3)).sqrt();
+            // Bedload moves over the bed, not the banks.
+            let width = if channel.bottom_width > 0.0 {
+                channel.bottom_width
+            } else {
+                channel.top_width(depth)
+            };
+            per_width * width * grain_density
+        }
+        Formula::Yang => {
+            let w = material.fall_velocity();
+            let shear_velocity = (G * radius * slope).sqrt();
+            let reynolds = shear_velocity * d / VISCOSITY;
+            let critical = if reynolds < 70.0 {
+                w * (2.5 / ((reynolds.max(1.2)).log10() - 0.06) + 0.66)
+            } else {
+                2.05 * w
+            };
+            let excess = (velocity - critical) * slope / w;
+            if excess <= 0.0 {
+                return 0.0;
+            }
+            let a = (w * d / VISCOSITY).log10();
+            let b = (shear_velocity / w).log10();
+            let log_ppm =
+                5.435 - 0.286 * a - 0.457 * b + (1.799 - 0.409 * a - 0.314 * b) * excess.log10();
+            // Concentration by weight, applied to the mass flow of water.
+            10f64.powf(log_ppm) * 1e-6 * discharge * WATER_DENSITY
+        }
+    };
+    capacity.max(0.0)
+}
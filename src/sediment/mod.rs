@@ -0,0 +1,7 @@
+//! Sediment transport capacity and reach sediment budgets driven by routed
+//! flows.
+
+pub mod capacity;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod routing;
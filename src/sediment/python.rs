@@ -0,0 +1,93 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict};
+
+use super::capacity::{transport_capacity as capacity_rs, BedMaterial, Formula};
+use super::routing::{route_sediment_rs, SedimentReach};
+use crate::arrow::FloatSeries;
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::time::timedelta_to_seconds;
+use crate::units::UnitSystem;
+
+#[pyfunction]
+#[pyo3(signature = (discharge, bottom_width, side_slope, n, slope, d50, formula="engelund_hansen", specific_gravity=2.65, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn transport_capacity(
+    py: Python<'_>,
+    discharge: FloatSeries,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    d50: f64,
+    formula: &str,
+    specific_gravity: f64,
+    units: UnitSystem,
+) -> PyResult<Vec<f64>> {
+    let formula = Formula::parse(formula)?;
+    let channel = TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?;
+    let material = BedMaterial::new(units.small_depth_to_si(d50), specific_gravity)?;
+    Ok(py.allow_threads(|| {
+        discharge
+            .0
+            .iter()
+            .map(|&q| {
+                let capacity = capacity_rs(formula, &material, &channel, units.discharge_to_si(q));
+                units.mass_from_si(capacity)
+            })
+            .collect()
+    }))
+}
+
+#[pyfunction]
+#[pyo3(signature = (discharge, load, time_step, bottom_width, side_slope, n, slope, d50, formula="engelund_hansen", specific_gravity=2.65, bed_storage=None, units=UnitSystem::Si))]
+#[allow(clippy::too_many_arguments)]
+pub fn route_sediment<'py>(
+    py: Python<'py>,
+    discharge: FloatSeries,
+    load: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    d50: f64,
+    formula: &str,
+    specific_gravity: f64,
+    bed_storage: Option<f64>,
+    units: UnitSystem,
+) -> PyResult<Bound<'py, PyDict>> {
+    if discharge.0.len() != load.0.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "`discharge` and `load` must have equal lengths.",
+        ));
+    }
+    let mut reach = SedimentReach::new(
+        TrapezoidalChannel::from_units(bottom_width, side_slope, n, slope, units)?,
+        BedMaterial::new(units.small_depth_to_si(d50), specific_gravity)?,
+        Formula::parse(formula)?,
+        bed_storage.map_or(f64::INFINITY, |m| units.mass_to_si(m.max(0.0))),
+    );
+    let dt = timedelta_to_seconds(&time_step);
+    let discharge_si: Vec<f64> = discharge
+        .0
+        .iter()
+        .map(|&q| units.discharge_to_si(q))
+        .collect();
+    let load_si: Vec<f64> = load.0.iter().map(|&l| units.mass_to_si(l)).collect();
+    let routed = py.allow_threads(|| route_sediment_rs(&discharge_si, &load_si, dt, &mut reach));
+    let convert = |values: Vec<f64>| -> Vec<f64> {
+        values.into_iter().map(|v| units.mass_from_si(v)).collect()
+    };
+    let result = PyDict::new(py);
+    result.set_item("load", convert(routed.load))?;
+    result.set_item("capacity", convert(routed.capacity))?;
+    result.set_item("bed", convert(routed.bed))?;
+    Ok(result)
+}
+
+#[pymodule]
+pub fn init_sediment(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(transport_capacity, m)?)?;
+    m.add_function(wrap_pyfunction!(route_sediment, m)?)?;
+    Ok(())
+}
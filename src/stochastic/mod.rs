@@ -0,0 +1,14 @@
+//! Stochastic generation of synthetic hydrological series.
+
+#[cfg(feature = "python")]
+pub mod python;
+pub mod rainfall;
+pub mod streamflow;
+
+use crate::random::task_seeds;
+
+/// Independent seeds for `n` realisations drawn from one base seed, so the
+/// output does not depend on how realisations are spread over threads.
+pub fn realization_seeds(seed: Option<u64>, n: usize) -> Vec<u64> {
+    task_seeds(seed, n)
+}
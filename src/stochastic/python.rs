@@ -0,0 +1,128 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::rainfall::MarkovRainfall;
+use super::realization_seeds;
+use super::streamflow::{arma11, disaggregate_fragments, index_sequential, periodic_ar1};
+use crate::arrow::FloatSeries;
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+use crate::random::Rng;
+
+#[pyfunction]
+#[pyo3(signature = (observed, threshold=0.0, period=0, seasons=12))]
+pub fn fit_markov_rainfall<'py>(
+    py: Python<'py>,
+    observed: FloatSeries,
+    threshold: f64,
+    period: usize,
+    seasons: usize,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let model = MarkovRainfall::fit(&observed.0, threshold, period, seasons)?;
+    model
+        .seasons
+        .iter()
+        .map(|s| {
+            let dict = PyDict::new(py);
+            dict.set_item("p_wet_after_dry", s.p_wet_after_dry)?;
+            dict.set_item("p_wet_after_wet", s.p_wet_after_wet)?;
+            dict.set_item("shape", s.shape)?;
+            dict.set_item("scale", s.scale)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (observed, n_steps, threshold=0.0, period=0, seasons=12, realizations=1, start=0, seed=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn rainfall_generator(
+    py: Python<'_>,
+    observed: FloatSeries,
+    n_steps: usize,
+    threshold: f64,
+    period: usize,
+    seasons: usize,
+    realizations: usize,
+    start: usize,
+    seed: Option<u64>,
+) -> PyResult<Vec<Vec<f64>>> {
+    let model = MarkovRainfall::fit(&observed.0, threshold, period, seasons)?;
+    let seeds = realization_seeds(seed, realizations);
+    Ok(py.allow_threads(|| {
+        par_map(&seeds, |&seed| {
+            model.generate(n_steps, start, &mut Rng::seed_from_u64(seed))
+        })
+    }))
+}
+
+#[pyfunction]
+#[pyo3(signature = (observed, n_steps, method="ar1", period=12, realizations=None, log=true, start=0, seed=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn streamflow_generator(
+    py: Python<'_>,
+    observed: FloatSeries,
+    n_steps: usize,
+    method: &str,
+    period: usize,
+    realizations: Option<usize>,
+    log: bool,
+    start: usize,
+    seed: Option<u64>,
+) -> PyResult<Vec<Vec<f64>>> {
+    let observed = observed.0;
+    if method == "index_sequential" {
+        let traces = realizations.unwrap_or(observed.len() / period.max(1));
+        return Ok(index_sequential(&observed, period, n_steps, traces)?);
+    }
+    let generate = match method {
+        "ar1" => periodic_ar1,
+        "arma" => arma11,
+        _ => {
+            return Err(Error::InvalidParameter(format!(
+                "Unknown method `{method}`; expected \"ar1\", \"arma\" or \"index_sequential\"."
+            ))
+            .into())
+        }
+    };
+    let seeds = realization_seeds(seed, realizations.unwrap_or(1));
+    let traces = py.allow_threads(|| {
+        par_map(&seeds, |&seed| {
+            generate(
+                &observed,
+                period,
+                log,
+                n_steps,
+                start,
+                &mut Rng::seed_from_u64(seed),
+            )
+        })
+    });
+    Ok(traces.into_iter().collect::<Result<Vec<_>>>()?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (synthetic, fragments, period=12, start=0))]
+pub fn disaggregate(
+    synthetic: FloatSeries,
+    fragments: Vec<FloatSeries>,
+    period: usize,
+    start: usize,
+) -> PyResult<Vec<f64>> {
+    let fragments: Vec<Vec<f64>> = fragments.into_iter().map(|f| f.0).collect();
+    Ok(disaggregate_fragments(
+        &synthetic.0,
+        &fragments,
+        period,
+        start,
+    )?)
+}
+
+#[pymodule]
+pub fn init_stochastic(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(fit_markov_rainfall, m)?)?;
+    m.add_function(wrap_pyfunction!(rainfall_generator, m)?)?;
+    m.add_function(wrap_pyfunction!(streamflow_generator, m)?)?;
+    m.add_function(wrap_pyfunction!(disaggregate, m)?)?;
+    Ok(())
+}
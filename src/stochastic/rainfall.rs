@@ -0,0 +1,128 @@
+//! First-order two-state Markov chain rainfall generator with gamma
+//! distributed wet-step depths (Richardson, 1981), fitted per season.
+
+use crate::error::{Error, Result};
+use crate::random::Rng;
+
+/// Parameters of one season.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeasonParameters {
+    /// Probability that a dry step is followed by a wet step.
+    pub p_wet_after_dry: f64,
+    /// Probability that a wet step is followed by a wet step.
+    pub p_wet_after_wet: f64,
+    /// Gamma shape of the wet-step depth in excess of the threshold.
+    pub shape: f64,
+    /// Gamma scale of the wet-step depth in excess of the threshold.
+    pub scale: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkovRainfall {
+    /// Depth above which a step counts as wet.
+    pub threshold: f64,
+    /// Steps per seasonal cycle (e.g. 8766 for hourly data over a year);
+    /// 0 for a single, non-seasonal parameter set.
+    pub period: usize,
+    pub seasons: Vec<SeasonParameters>,
+}
+
+impl MarkovRainfall {
+    /// Season of step `t`, where step 0 is the start of the cycle.
+    pub fn season_of(&self, t: usize) -> usize {
+        match self.period {
+            0 => 0,
+            period => (t % period) * self.seasons.len() / period,
+        }
+    }
+
+    /// Fits the model to an observed series whose first value is at the
+    /// start of the seasonal cycle. NaN values are skipped.
+    pub fn fit(observed: &[f64], threshold: f64, period: usize, seasons: usize) -> Result<Self> {
+        let n_seasons = if period == 0 { 1 } else { seasons.max(1) };
+        let mut model = MarkovRainfall {
+            threshold,
+            period,
+            seasons: Vec::with_capacity(n_seasons),
+        };
+        // Per season: [dry->dry, dry->wet, wet->dry, wet->wet] and excess depths.
+        let mut transitions = vec![[0usize; 4]; n_seasons];
+        let mut excess = vec![Vec::new(); n_seasons];
+        model.seasons = vec![
+            SeasonParameters {
+                p_wet_after_dry: 0.0,
+                p_wet_after_wet: 0.0,
+                shape: 1.0,
+                scale: 0.0,
+            };
+            n_seasons
+        ];
+        for (t, &depth) in observed.iter().enumerate() {
+            if depth.is_nan() {
+                continue;
+            }
+            let season = model.season_of(t);
+            let wet = depth > threshold;
+            if wet {
+                excess[season].push(depth - threshold);
+            }
+            if let Some(&previous) = t.checked_sub(1).and_then(|p| observed.get(p)) {
+                if !previous.is_nan() {
+                    let from_wet = previous > threshold;
+                    transitions[season][2 * from_wet as usize + wet as usize] += 1;
+                }
+            }
+        }
+        for (season, params) in model.seasons.iter_mut().enumerate() {
+            let [dd, dw, wd, ww] = transitions[season];
+            if dd + dw == 0 {
+                return Err(Error::Data(format!(
+                    "Season {season} has no observed dry steps; use fewer seasons or a longer record."
+                )));
+            }
+            params.p_wet_after_dry = dw as f64 / (dd + dw) as f64;
+            params.p_wet_after_wet = if wd + ww == 0 {
+                0.0
+            } else {
+                ww as f64 / (wd + ww) as f64
+            };
+            let depths = &excess[season];
+            if !depths.is_empty() {
+                let mean = depths.iter().sum::<f64>() / depths.len() as f64;
+                let variance =
+                    depths.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / depths.len() as f64;
+                // Method of moments; an exponential when the variance is degenerate.
+                let shape = if variance > 0.0 {
+                    mean * mean / variance
+                } else {
+                    1.0
+                };
+                params.shape = shape;
+                params.scale = mean / shape;
+            }
+        }
+        Ok(model)
+    }
+
+    /// Generates `n_steps` values starting at step `start` of the seasonal
+    /// cycle.
+    pub fn generate(&self, n_steps: usize, start: usize, rng: &mut Rng) -> Vec<f64> {
+        let mut wet = false;
+        (0..n_steps)
+            .map(|t| {
+                let params = &self.seasons[self.season_of(start + t)];
+                let p = if wet {
+                    params.p_wet_after_wet
+                } else {
+                    params.p_wet_after_dry
+                };
+                wet = rng.uniform() < p;
+                if wet {
+                    self.threshold + params.scale * rng.gamma(params.shape)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,255 @@
+//! Synthetic streamflow: periodic lag-1 autoregression (Thomas–Fiering),
+//! ARMA(1,1) on deseasonalised flows, the index-sequential method, and
+//! disaggregation to shorter steps by the method of fragments.
+
+use crate::error::{Error, Result};
+use crate::random::{self, Rng};
+
+/// Steps discarded before the output so the generated series forgets its
+/// initial state; a whole number of cycles keeps the seasons aligned.
+const WARM_UP_CYCLES: usize = 10;
+
+/// Per-season moments of a (possibly log-transformed) flow series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeasonalMoments {
+    pub mean: Vec<f64>,
+    pub std: Vec<f64>,
+    /// Correlation between season `m - 1` and season `m`.
+    pub lag1: Vec<f64>,
+}
+
+fn transform(observed: &[f64], log: bool) -> Result<Vec<f64>> {
+    if log {
+        if let Some(q) = observed.iter().find(|q| **q <= 0.0) {
+            return Err(Error::Data(format!(
+                "Log-space generation requires positive flows; found {q}."
+            )));
+        }
+        Ok(observed.iter().map(|&q| random::ln(q)).collect())
+    } else {
+        Ok(observed.to_vec())
+    }
+}
+
+fn back_transform(value: f64, log: bool) -> f64 {
+    if log {
+        random::exp(value)
+    } else {
+        value.max(0.0)
+    }
+}
+
+/// Seasonal moments of `series` with `period` seasons per cycle.
+pub fn seasonal_moments(series: &[f64], period: usize) -> Result<SeasonalMoments> {
+    let period = period.max(1);
+    if series.len() < 2 * period {
+        return Err(Error::Data(
+            "At least two full cycles of observations are required.".into(),
+        ));
+    }
+    let mut moments = SeasonalMoments {
+        mean: vec![0.0; period],
+        std: vec![0.0; period],
+        lag1: vec![0.0; period],
+    };
+    for m in 0..period {
+        let values: Vec<f64> = series.iter().skip(m).step_by(period).copied().collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        moments.mean[m] = mean;
+        moments.std[m] = var.sqrt();
+    }
+    for m in 0..period {
+        let previous = (m + period - 1) % period;
+        let (mut cov, mut n) = (0.0, 0usize);
+        for t in (m..series.len()).step_by(period).filter(|&t| t >= 1) {
+            cov += (series[t] - moments.mean[m]) * (series[t - 1] - moments.mean[previous]);
+            n += 1;
+        }
+        let denom = moments.std[m] * moments.std[previous];
+        moments.lag1[m] = if n == 0 || denom == 0.0 {
+            0.0
+        } else {
+            (cov / n as f64 / denom).clamp(-0.99, 0.99)
+        };
+    }
+    Ok(moments)
+}
+
+/// Thomas–Fiering periodic AR(1) generator. The first output value is in
+/// season `start`.
+pub fn periodic_ar1(
+    observed: &[f64],
+    period: usize,
+    log: bool,
+    n_steps: usize,
+    start: usize,
+    rng: &mut Rng,
+) -> Result<Vec<f64>> {
+    let period = period.max(1);
+    let m = seasonal_moments(&transform(observed, log)?, period)?;
+    let warm_up = WARM_UP_CYCLES * period;
+    // Start from the mean of the season preceding the first step.
+    let mut value = m.mean[(start + period - 1) % period];
+    let mut output = Vec::with_capacity(n_steps);
+    for t in 0..warm_up + n_steps {
+        let season = (start + t) % period;
+        let previous = (season + period - 1) % period;
+        let r = m.lag1[season];
+        let ratio = if m.std[previous] > 0.0 {
+            m.std[season] / m.std[previous]
+        } else {
+            0.0
+        };
+        value = m.mean[season]
+            + r * ratio * (value - m.mean[previous])
+            + rng.normal() * m.std[season] * (1.0 - r * r).sqrt();
+        if t >= warm_up {
+            output.push(back_transform(value, log));
+        }
+    }
+    Ok(output)
+}
+
+/// ARMA(1,1) parameters `(phi, theta, innovation std)` of a standardised
+/// series, by the method of moments.
+pub fn fit_arma11(series: &[f64]) -> Result<(f64, f64, f64)> {
+    let n = series.len();
+    if n < 3 {
+        return Err(Error::Data("Too few values to fit ARMA(1,1).".into()));
+    }
+    let mean = series.iter().sum::<f64>() / n as f64;
+    let gamma = |lag: usize| {
+        (lag..n)
+            .map(|t| (series[t] - mean) * (series[t - lag] - mean))
+            .sum::<f64>()
+            / n as f64
+    };
+    let (g0, g1, g2) = (gamma(0), gamma(1), gamma(2));
+    if g0 == 0.0 {
+        return Err(Error::Data("The series has zero variance.".into()));
+    }
+    let (r1, r2) = (g1 / g0, g2 / g0);
+    let phi = if r1.abs() > 1e-9 {
+        (r2 / r1).clamp(-0.99, 0.99)
+    } else {
+        0.0
+    };
+    // r1 = (1 - phi θ)(phi - θ) / (1 + θ² - 2 phi θ), rearranged as a
+    // quadratic in θ; take the invertible root.
+    let a = phi - r1;
+    let b = 1.0 + phi * phi - 2.0 * r1 * phi;
+    let c = phi - r1;
+    let theta = if a.abs() < 1e-12 {
+        0.0
+    } else {
+        let disc = (b * b - 4.0 * a * c).max(0.0).sqrt();
+        let roots = [(b - disc) / (2.0 * a), (b + disc) / (2.0 * a)];
+        roots.into_iter().find(|t| t.abs() < 1.0).unwrap_or(0.0)
+    };
+    let variance = g0 * (1.0 - phi * phi) / (1.0 + theta * theta - 2.0 * phi * theta);
+    Ok((phi, theta, variance.max(0.0).sqrt()))
+}
+
+/// ARMA(1,1) generator on flows standardised by season.
+pub fn arma11(
+    observed: &[f64],
+    period: usize,
+    log: bool,
+    n_steps: usize,
+    start: usize,
+    rng: &mut Rng,
+) -> Result<Vec<f64>> {
+    let period = period.max(1);
+    let series = transform(observed, log)?;
+    let m = seasonal_moments(&series, period)?;
+    let standardised: Vec<f64> = series
+        .iter()
+        .enumerate()
+        .map(|(t, v)| {
+            let s = t % period;
+            if m.std[s] > 0.0 {
+                (v - m.mean[s]) / m.std[s]
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let (phi, theta, sigma) = fit_arma11(&standardised)?;
+    let warm_up = WARM_UP_CYCLES * period;
+    let (mut z, mut a_prev) = (0.0, 0.0);
+    let mut output = Vec::with_capacity(n_steps);
+    for t in 0..warm_up + n_steps {
+        let a = sigma * rng.normal();
+        z = phi * z + a - theta * a_prev;
+        a_prev = a;
+        if t >= warm_up {
+            let s = (start + t - warm_up) % period;
+            output.push(back_transform(m.mean[s] + m.std[s] * z, log));
+        }
+    }
+    Ok(output)
+}
+
+/// Index-sequential traces: trace `r` replays the record from cycle
+/// `r mod n_cycles`, wrapping to the beginning at the end of the record.
+pub fn index_sequential(
+    observed: &[f64],
+    period: usize,
+    n_steps: usize,
+    traces: usize,
+) -> Result<Vec<Vec<f64>>> {
+    let period = period.max(1);
+    let cycles = observed.len() / period;
+    if cycles == 0 {
+        return Err(Error::Data("The record is shorter than one cycle.".into()));
+    }
+    let record = &observed[..cycles * period];
+    Ok((0..traces)
+        .map(|r| {
+            let offset = (r % cycles) * period;
+            (0..n_steps)
+                .map(|t| record[(offset + t) % record.len()])
+                .collect()
+        })
+        .collect())
+}
+
+/// Disaggregates a coarse synthetic series (e.g. monthly mean flows) with
+/// the method of fragments. `fragments[i]` holds the observed fine-step
+/// flows within coarse step `i` of the record, whose season is
+/// `i mod period`. Each synthetic value takes the pattern of the observed
+/// step of the same season with the closest mean, scaled to the synthetic
+/// mean. `start` is the season of the first synthetic value.
+pub fn disaggregate_fragments(
+    synthetic: &[f64],
+    fragments: &[Vec<f64>],
+    period: usize,
+    start: usize,
+) -> Result<Vec<f64>> {
+    let period = period.max(1);
+    let means: Vec<f64> = fragments
+        .iter()
+        .map(|f| f.iter().sum::<f64>() / f.len() as f64)
+        .collect();
+    let mut output = Vec::new();
+    for (i, &value) in synthetic.iter().enumerate() {
+        let season = (start + i) % period;
+        let best = (season..fragments.len())
+            .step_by(period)
+            .filter(|&k| means[k].is_finite() && means[k] > 0.0)
+            .min_by(|&a, &b| {
+                (means[a] - value)
+                    .abs()
+                    .total_cmp(&(means[b] - value).abs())
+            })
+            .ok_or_else(|| {
+                Error::Data(format!(
+                    "No observed fragments with a positive mean for season {season}."
+                ))
+            })?;
+        let scale = value / means[best];
+        output.extend(fragments[best].iter().map(|q| q * scale));
+    }
+    Ok(output)
+}
@@ -0,0 +1,199 @@
+//! Longest flow path and basin characteristics of the catchment of an
+//! outlet cell, as used by Snyder and NRCS lag formulas and regional
+//! regression equations.
+//!
+//! Lengths and areas are in map units and elevations in the units of the
+//! DEM; slopes are ratios of the two.
+
+use super::d8::FlowGrid;
+use crate::error::{Error, Result};
+use crate::interp::interp;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BasinCharacteristics {
+    /// Outlet cell.
+    pub outlet: usize,
+    /// Cells of the catchment.
+    pub cells: usize,
+    pub area: f64,
+    /// Length of the catchment boundary along cell edges.
+    pub perimeter: f64,
+    /// Centre of mass of the catchment cells.
+    pub centroid: (f64, f64),
+    /// Cells of the longest flow path, from the most remote cell to the
+    /// outlet.
+    pub flow_path: Vec<usize>,
+    /// Length of the longest flow path, `L`.
+    pub flow_path_length: f64,
+    /// Length along the longest flow path from the outlet to the point
+    /// nearest the centroid, Snyder's `Lc`.
+    pub centroid_length: f64,
+    /// Drop along the longest flow path divided by its length.
+    pub flow_path_slope: f64,
+    /// Slope between 10 % and 85 % of the longest flow path length from
+    /// the outlet (Benson, 1962).
+    pub slope_10_85: f64,
+    /// Mean of the cell slopes by Horn's (1981) method.
+    pub mean_slope: f64,
+    pub min_elevation: f64,
+    pub mean_elevation: f64,
+    pub max_elevation: f64,
+    /// Highest elevation less the outlet elevation.
+    pub relief: f64,
+    /// Relief divided by the longest flow path length.
+    pub relief_ratio: f64,
+    /// `(mean − min) / (max − min)` elevation.
+    pub hypsometric_integral: f64,
+    /// Area divided by the squared flow path length (Horton).
+    pub form_factor: f64,
+    /// Diameter of the circle of equal area divided by the flow path
+    /// length (Schumm).
+    pub elongation_ratio: f64,
+    /// Area divided by that of the circle of equal perimeter (Miller).
+    pub circularity_ratio: f64,
+    /// Perimeter divided by that of the circle of equal area (Gravelius).
+    pub compactness: f64,
+}
+
+/// Slope of `cell` from the elevations of its eight neighbours (Horn,
+/// 1981), using the cell's own elevation for neighbours outside the
+/// domain.
+fn horn_slope(grid: &FlowGrid, cell: usize) -> f64 {
+    let (r, c) = ((cell / grid.cols) as isize, (cell % grid.cols) as isize);
+    let z0 = grid.elevation[cell];
+    let z = |dr: isize, dc: isize| {
+        let (rr, cc) = (r + dr, c + dc);
+        if rr < 0 || cc < 0 || rr >= grid.rows as isize || cc >= grid.cols as isize {
+            return z0;
+        }
+        let v = grid.elevation[rr as usize * grid.cols + cc as usize];
+        if v.is_nan() {
+            z0
+        } else {
+            v
+        }
+    };
+    let dzdx = ((z(-1, 1) + 2.0 * z(0, 1) + z(1, 1)) - (z(-1, -1) + 2.0 * z(0, -1) + z(1, -1)))
+        / (8.0 * grid.dx);
+    let dzdy = ((z(1, -1) + 2.0 * z(1, 0) + z(1, 1)) - (z(-1, -1) + 2.0 * z(-1, 0) + z(-1, 1)))
+        / (8.0 * grid.dy);
+    dzdx.hypot(dzdy)
+}
+
+/// Characteristics of the catchment draining to `outlet`.
+pub fn basin_characteristics(grid: &FlowGrid, outlet: usize) -> Result<BasinCharacteristics> {
+    let labels = grid.catchments(&[outlet])?;
+    let inside = |cell: usize| labels[cell] == 0;
+
+    // Flow distance to the outlet, filled from the outlet upwards.
+    let mut distance = vec![f64::NAN; grid.len()];
+    distance[outlet] = 0.0;
+    let mut remote = outlet;
+    for &cell in grid.order().iter().rev() {
+        if cell == outlet || !inside(cell) {
+            continue;
+        }
+        if let Some(down) = grid.downstream(cell) {
+            distance[cell] = distance[down] + grid.flow_length(cell);
+            if distance[cell] > distance[remote] {
+                remote = cell;
+            }
+        }
+    }
+    let mut flow_path = vec![remote];
+    while let Some(down) = grid.downstream(flow_path[flow_path.len() - 1]) {
+        if !inside(down) {
+            break;
+        }
+        flow_path.push(down);
+    }
+    if flow_path.len() < 2 {
+        return Err(Error::Data(
+            "The outlet drains a single cell; choose a cell on a stream or snap the outlet.".into(),
+        ));
+    }
+    let length = distance[remote];
+
+    let members: Vec<usize> = (0..grid.len()).filter(|&c| inside(c)).collect();
+    let n = members.len() as f64;
+    let area = n * grid.cell_area();
+    let mut perimeter = 0.0;
+    let (mut sx, mut sy, mut sz, mut slope) = (0.0, 0.0, 0.0, 0.0);
+    let (mut min_z, mut max_z) = (f64::INFINITY, f64::NEG_INFINITY);
+    for &cell in &members {
+        let (r, c) = (cell / grid.cols, cell % grid.cols);
+        let edge = |rr: Option<usize>, cc: Option<usize>| match (rr, cc) {
+            (Some(rr), Some(cc)) if rr < grid.rows && cc < grid.cols => {
+                !inside(rr * grid.cols + cc)
+            }
+            _ => true,
+        };
+        for (rr, cc, side) in [
+            (r.checked_sub(1), Some(c), grid.dx),
+            (Some(r + 1), Some(c), grid.dx),
+            (Some(r), c.checked_sub(1), grid.dy),
+            (Some(r), Some(c + 1), grid.dy),
+        ] {
+            if edge(rr, cc) {
+                perimeter += side;
+            }
+        }
+        let (x, y) = grid.centre(cell);
+        let z = grid.elevation[cell];
+        sx += x;
+        sy += y;
+        sz += z;
+        slope += horn_slope(grid, cell);
+        min_z = min_z.min(z);
+        max_z = max_z.max(z);
+    }
+    let centroid = (sx / n, sy / n);
+    let mean_z = sz / n;
+
+    let near_centroid = flow_path
+        .iter()
+        .copied()
+        .min_by(|&a, &b| {
+            let d = |cell: usize| {
+                let (x, y) = grid.centre(cell);
+                (x - centroid.0).hypot(y - centroid.1)
+            };
+            d(a).total_cmp(&d(b))
+        })
+        .unwrap_or(outlet);
+    // Elevation along the path against distance from the outlet.
+    let along: Vec<f64> = flow_path.iter().rev().map(|&c| distance[c]).collect();
+    let profile: Vec<f64> = flow_path.iter().rev().map(|&c| grid.elevation[c]).collect();
+    let z_at = |fraction: f64| interp(fraction * length, &along, &profile);
+    let z_outlet = grid.elevation[outlet];
+    let relief = max_z - z_outlet;
+    let equal_circle = 2.0 * (area / std::f64::consts::PI).sqrt();
+
+    Ok(BasinCharacteristics {
+        outlet,
+        cells: members.len(),
+        area,
+        perimeter,
+        centroid,
+        flow_path_length: length,
+        centroid_length: distance[near_centroid],
+        flow_path_slope: (grid.elevation[remote] - z_outlet) / length,
+        slope_10_85: (z_at(0.85) - z_at(0.10)) / (0.75 * length),
+        mean_slope: slope / n,
+        min_elevation: min_z,
+        mean_elevation: mean_z,
+        max_elevation: max_z,
+        relief,
+        relief_ratio: relief / length,
+        hypsometric_integral: if max_z > min_z {
+            (mean_z - min_z) / (max_z - min_z)
+        } else {
+            f64::NAN
+        },
+        form_factor: area / (length * length),
+        elongation_ratio: equal_circle / length,
+        circularity_ratio: 4.0 * std::f64::consts::PI * area / (perimeter * perimeter),
+        compactness: perimeter / (std::f64::consts::PI * equal_circle),
+        flow_path,
+    })
+}
@@ -0,0 +1,539 @@
+//! D8 flow directions, flow accumulation and catchments.
+
+use crate::error::{Error, Result};
+use crate::parallel::{num_threads, par_map};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// D8 direction codes (as used by ArcGIS) and the row and column offset of
+/// the neighbour each points to: east, south-east, south, south-west,
+/// west, north-west, north, north-east.
+pub const DIRECTIONS: [(u8, isize, isize); 8] = [
+    (1, 0, 1),
+    (2, 1, 1),
+    (4, 1, 0),
+    (8, 1, -1),
+    (16, 0, -1),
+    (32, -1, -1),
+    (64, -1, 0),
+    (128, -1, 1),
+];
+
+/// A cell waiting in the priority-flood queue, lowest elevation first.
+#[derive(PartialEq)]
+struct Lowest(f64, usize);
+
+impl Eq for Lowest {}
+
+impl Ord for Lowest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Lowest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// D8 flow directions of a DEM on a regular grid.
+///
+/// Rows run north to south and columns west to east. NaN elevations mark
+/// cells outside the domain, which neither send nor receive flow. Cells on
+/// the edge of the domain with no lower neighbour are outlets, draining
+/// off the grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowGrid {
+    pub rows: usize,
+    pub cols: usize,
+    /// Cell width (west–east).
+    pub dx: f64,
+    /// Cell height (north–south).
+    pub dy: f64,
+    /// Coordinates of the north-west corner of the grid.
+    pub origin: (f64, f64),
+    /// Elevations the directions were derived from, after any filling;
+    /// NaN outside the domain.
+    pub elevation: Vec<f64>,
+    /// Direction code of each cell, or 0 for outlets and cells outside the
+    /// domain.
+    pub directions: Vec<u8>,
+    /// Cells in the domain, each after every cell draining to it.
+    order: Vec<usize>,
+}
+
+impl FlowGrid {
+    /// Derives flow directions from the row-major elevations `dem`. With
+    /// `fill`, depressions and flats are first filled by priority flood
+    /// with the smallest representable gradient (Barnes et al., 2014), so
+    /// every cell drains to the edge of the domain; otherwise cells with no
+    /// lower neighbour are sinks.
+    pub fn from_dem(
+        dem: &[f64],
+        rows: usize,
+        cols: usize,
+        cell_size: (f64, f64),
+        origin: (f64, f64),
+        fill: bool,
+    ) -> Result<Self> {
+        let (dx, dy) = cell_size;
+        if dx.is_nan() || dx <= 0.0 || dy.is_nan() || dy <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "The cell size must be positive.".into(),
+            ));
+        }
+        if rows == 0 || cols == 0 || dem.len() != rows * cols {
+            return Err(Error::Data(format!(
+                "The DEM holds {} values, not {rows} × {cols}.",
+                dem.len()
+            )));
+        }
+        if dem.iter().all(|z| z.is_nan()) {
+            return Err(Error::Data("The DEM has no valid cells.".into()));
+        }
+        let mut grid = FlowGrid {
+            rows,
+            cols,
+            dx,
+            dy,
+            origin,
+            elevation: dem
+                .iter()
+                .map(|z| if z.is_finite() { *z } else { f64::NAN })
+                .collect(),
+            directions: Vec::new(),
+            order: Vec::new(),
+        };
+        if fill {
+            grid.fill_depressions();
+        }
+        grid.directions = grid.steepest_descent();
+        grid.order = grid.topological_order()?;
+        Ok(grid)
+    }
+
+    /// Rebuilds a grid from its directions, as when unpickling. The
+    /// directions must drain without cycles.
+    pub fn from_parts(
+        rows: usize,
+        cols: usize,
+        cell_size: (f64, f64),
+        origin: (f64, f64),
+        elevation: Vec<f64>,
+        directions: Vec<u8>,
+    ) -> Result<Self> {
+        if elevation.len() != rows * cols || directions.len() != rows * cols {
+            return Err(Error::Data(
+                "The elevations and directions do not match the grid shape.".into(),
+            ));
+        }
+        let mut grid = FlowGrid {
+            rows,
+            cols,
+            dx: cell_size.0,
+            dy: cell_size.1,
+            origin,
+            elevation,
+            directions,
+            order: Vec::new(),
+        };
+        grid.order = grid.topological_order()?;
+        Ok(grid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn cell_area(&self) -> f64 {
+        self.dx * self.dy
+    }
+
+    pub fn is_valid(&self, cell: usize) -> bool {
+        !self.elevation[cell].is_nan()
+    }
+
+    /// Map coordinates of the centre of `cell`.
+    pub fn centre(&self, cell: usize) -> (f64, f64) {
+        let (r, c) = (cell / self.cols, cell % self.cols);
+        (
+            self.origin.0 + (c as f64 + 0.5) * self.dx,
+            self.origin.1 - (r as f64 + 0.5) * self.dy,
+        )
+    }
+
+    /// The cell containing map point `(x, y)`, if on the grid.
+    pub fn cell_at(&self, x: f64, y: f64) -> Option<usize> {
+        let c = ((x - self.origin.0) / self.dx).floor();
+        let r = ((self.origin.1 - y) / self.dy).floor();
+        (c >= 0.0 && r >= 0.0 && (c as usize) < self.cols && (r as usize) < self.rows)
+            .then(|| r as usize * self.cols + c as usize)
+    }
+
+    /// Neighbour of `cell` at offset `(dr, dc)`, if on the grid.
+    fn neighbour(&self, cell: usize, dr: isize, dc: isize) -> Option<usize> {
+        let r = (cell / self.cols).checked_add_signed(dr)?;
+        let c = (cell % self.cols).checked_add_signed(dc)?;
+        (r < self.rows && c < self.cols).then(|| r * self.cols + c)
+    }
+
+    /// Distance between the centres of neighbouring cells.
+    pub fn step_length(&self, dr: isize, dc: isize) -> f64 {
+        (dr as f64 * self.dy).hypot(dc as f64 * self.dx)
+    }
+
+    /// The cell `cell` drains to.
+    pub fn downstream(&self, cell: usize) -> Option<usize> {
+        let code = self.directions[cell];
+        let &(_, dr, dc) = DIRECTIONS.iter().find(|d| d.0 == code)?;
+        self.neighbour(cell, dr, dc)
+    }
+
+    /// Distance from the centre of `cell` to the centre of the cell it
+    /// drains to, or half a cell width for outlets.
+    pub fn flow_length(&self, cell: usize) -> f64 {
+        let code = self.directions[cell];
+        match DIRECTIONS.iter().find(|d| d.0 == code) {
+            Some(&(_, dr, dc)) => self.step_length(dr, dc),
+            None => 0.5 * self.dx.min(self.dy),
+        }
+    }
+
+    /// Cells in the domain, each after every cell draining to it.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Raises cells in depressions and flats to just above the lowest
+    /// spill point, flooding inwards from the domain edge.
+    fn fill_depressions(&mut self) {
+        let n = self.len();
+        let mut closed = vec![false; n];
+        let mut open = BinaryHeap::new();
+        let mut pit = VecDeque::new();
+        for (cell, closed) in closed.iter_mut().enumerate() {
+            if !self.is_valid(cell) {
+                continue;
+            }
+            let edge = DIRECTIONS.iter().any(|&(_, dr, dc)| {
+                self.neighbour(cell, dr, dc)
+                    .is_none_or(|other| !self.is_valid(other))
+            });
+            if edge {
+                *closed = true;
+                open.push(Lowest(self.elevation[cell], cell));
+            }
+        }
+        while let Some(cell) = pit
+            .pop_front()
+            .or_else(|| open.pop().map(|Lowest(_, cell)| cell))
+        {
+            let spill = self.elevation[cell].next_up();
+            for &(_, dr, dc) in &DIRECTIONS {
+                let Some(other) = self.neighbour(cell, dr, dc) else {
+                    continue;
+                };
+                if closed[other] || !self.is_valid(other) {
+                    continue;
+                }
+                closed[other] = true;
+                if self.elevation[other] <= spill {
+                    self.elevation[other] = spill;
+                    pit.push_back(other);
+                } else {
+                    open.push(Lowest(self.elevation[other], other));
+                }
+            }
+        }
+    }
+
+    /// Direction of steepest descent of each cell, computed by rows in
+    /// parallel.
+    fn steepest_descent(&self) -> Vec<u8> {
+        let rows: Vec<usize> = (0..self.rows).collect();
+        let chunk = self.rows.div_ceil(4 * num_threads()).max(1);
+        let blocks: Vec<&[usize]> = rows.chunks(chunk).collect();
+        par_map(&blocks, |block| {
+            let mut codes = Vec::with_capacity(block.len() * self.cols);
+            for &r in *block {
+                for c in 0..self.cols {
+                    let cell = r * self.cols + c;
+                    let z = self.elevation[cell];
+                    let mut best = (0u8, 0.0);
+                    if !z.is_nan() {
+                        for &(code, dr, dc) in &DIRECTIONS {
+                            let Some(other) = self.neighbour(cell, dr, dc) else {
+                                continue;
+                            };
+                            let drop = (z - self.elevation[other]) / self.step_length(dr, dc);
+                            if drop > best.1 {
+                                best = (code, drop);
+                            }
+                        }
+                    }
+                    codes.push(best.0);
+                }
+            }
+            codes
+        })
+        .concat()
+    }
+
+    /// Cells of the domain ordered from the ridges down (Kahn's algorithm).
+    fn topological_order(&self) -> Result<Vec<usize>> {
+        let n = self.len();
+        let mut inflows = vec![0u8; n];
+        for cell in 0..n {
+            if let Some(down) = self.downstream(cell) {
+                inflows[down] += 1;
+            }
+        }
+        let mut order: Vec<usize> = (0..n)
+            .filter(|&cell| self.is_valid(cell) && inflows[cell] == 0)
+            .collect();
+        let mut i = 0;
+        while i < order.len() {
+            if let Some(down) = self.downstream(order[i]) {
+                inflows[down] -= 1;
+                if inflows[down] == 0 {
+                    order.push(down);
+                }
+            }
+            i += 1;
+        }
+        if order.len() != (0..n).filter(|&cell| self.is_valid(cell)).count() {
+            return Err(Error::Data(
+                "The flow directions contain a cycle or leave the domain.".into(),
+            ));
+        }
+        Ok(order)
+    }
+
+    /// Number of cells draining through each cell, including itself, or
+    /// with `weights`, the sum of the weights of those cells (NaN weights
+    /// count as zero). NaN outside the domain.
+    pub fn accumulation(&self, weights: Option<&[f64]>) -> Result<Vec<f64>> {
+        if weights.is_some_and(|w| w.len() != self.len()) {
+            return Err(Error::Data(
+                "The weights do not match the grid shape.".into(),
+            ));
+        }
+        let mut total = vec![f64::NAN; self.len()];
+        for &cell in &self.order {
+            total[cell] = 0.0;
+        }
+        for &cell in &self.order {
+            let own = weights.map_or(1.0, |w| if w[cell].is_nan() { 0.0 } else { w[cell] });
+            total[cell] += own;
+            if let Some(down) = self.downstream(cell) {
+                total[down] += total[cell];
+            }
+        }
+        Ok(total)
+    }
+
+    /// Moves each of `cells` to the cell of largest `accumulation` within
+    /// `radius` cells, so that pour points digitised near a stream land on
+    /// it.
+    pub fn snap(&self, cells: &[usize], accumulation: &[f64], radius: usize) -> Vec<usize> {
+        let r = radius as isize;
+        cells
+            .iter()
+            .map(|&cell| {
+                let mut best = cell;
+                for dr in -r..=r {
+                    for dc in -r..=r {
+                        if let Some(other) = self.neighbour(cell, dr, dc) {
+                            if accumulation[other] > accumulation[best]
+                                || accumulation[best].is_nan()
+                            {
+                                best = other;
+                            }
+                        }
+                    }
+                }
+                best
+            })
+            .collect()
+    }
+
+    /// Catchment of each pour point: the index in `pour_points` of the
+    /// first pour point each cell drains through, or -1. Nested pour points
+    /// split the catchment of the one downstream.
+    pub fn catchments(&self, pour_points: &[usize]) -> Result<Vec<i32>> {
+        let mut labels = vec![-1; self.len()];
+        for (i, &cell) in pour_points.iter().enumerate() {
+            if cell >= self.len() || !self.is_valid(cell) {
+                return Err(Error::InvalidParameter(format!(
+                    "Pour point {} is outside the domain.",
+                    i + 1
+                )));
+            }
+            labels[cell] = i as i32;
+        }
+        let outlets = labels.clone();
+        for &cell in self.order.iter().rev() {
+            if outlets[cell] < 0 {
+                if let Some(down) = self.downstream(cell) {
+                    labels[cell] = labels[down];
+                }
+            }
+        }
+        Ok(labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(dem: &[f64], rows: usize, cols: usize, fill: bool) -> FlowGrid {
+        FlowGrid::from_dem(dem, rows, cols, (10.0, 10.0), (0.0, 0.0), fill).unwrap()
+    }
+
+    /// Two valleys draining to the south-west and south-east corners,
+    /// split by a ridge along the middle column.
+    fn two_valleys() -> FlowGrid {
+        let dem: Vec<f64> = (0..4)
+            .flat_map(|r| {
+                (0..5).map(move |c: i32| (2 - (c - 2).abs()) as f64 + 0.5 * (3 - r) as f64)
+            })
+            .collect();
+        grid(&dem, 4, 5, false)
+    }
+
+    #[test]
+    fn tilted_planes_drain_downhill() {
+        // Rising eastwards: every row drains west to the edge.
+        let dem: Vec<f64> = (0..3).flat_map(|_| (0..4).map(f64::from)).collect();
+        let grid = grid(&dem, 3, 4, false);
+        for r in 0..3 {
+            assert_eq!(grid.directions[r * 4..r * 4 + 4], [0, 16, 16, 16]);
+        }
+        let accumulation = grid.accumulation(None).unwrap();
+        assert_eq!(accumulation, [4.0, 3.0, 2.0, 1.0].repeat(3));
+        let weights = vec![0.5; 12];
+        let weighted = grid.accumulation(Some(&weights)).unwrap();
+        assert_eq!(weighted[0], 2.0);
+        assert_eq!(grid.downstream(1), Some(0));
+        assert_eq!(grid.downstream(0), None);
+        assert_eq!(grid.flow_length(1), 10.0);
+        assert_eq!(grid.flow_length(0), 5.0);
+        // Every cell comes after the cells draining to it.
+        let position = |cell| grid.order().iter().position(|&c| c == cell).unwrap();
+        assert!((0..12).all(|cell| grid
+            .downstream(cell)
+            .is_none_or(|d| position(d) > position(cell))));
+    }
+
+    #[test]
+    fn diagonal_steps_are_longer() {
+        // Falling south-east: the diagonal drop per metre is the largest.
+        let dem: Vec<f64> = (0..3)
+            .flat_map(|r| (0..3).map(move |c| 10.0 - (r + c) as f64))
+            .collect();
+        let grid = grid(&dem, 3, 3, false);
+        assert_eq!(grid.directions[0], 2);
+        assert_eq!(grid.downstream(0), Some(4));
+        assert!((grid.flow_length(0) - 200f64.sqrt()).abs() < 1e-12);
+        assert_eq!(grid.accumulation(None).unwrap()[8], 9.0);
+    }
+
+    #[test]
+    fn single_cell_pits_are_filled() {
+        let mut dem = vec![5.0; 9];
+        dem[4] = 1.0;
+        let unfilled = grid(&dem, 3, 3, false);
+        assert_eq!(unfilled.directions[4], 0);
+        assert_eq!(unfilled.accumulation(None).unwrap()[4], 9.0);
+        assert_eq!(unfilled.elevation[4], 1.0);
+
+        let filled = grid(&dem, 3, 3, true);
+        // Raised to just above the rim and draining over it.
+        assert_eq!(filled.elevation[4], 5f64.next_up());
+        assert_ne!(filled.directions[4], 0);
+        let accumulation = filled.accumulation(None).unwrap();
+        assert_eq!(accumulation.iter().sum::<f64>(), 10.0);
+        assert_eq!(accumulation[filled.downstream(4).unwrap()], 2.0);
+    }
+
+    #[test]
+    fn cells_outside_the_domain_are_skipped() {
+        let dem = [f64::NAN, 2.0, 1.0, f64::INFINITY];
+        let grid = grid(&dem, 2, 2, true);
+        assert!(!grid.is_valid(0) && !grid.is_valid(3));
+        assert_eq!(grid.directions, [0, 8, 0, 0]);
+        let accumulation = grid.accumulation(None).unwrap();
+        assert!(accumulation[0].is_nan() && accumulation[3].is_nan());
+        assert_eq!(accumulation[2], 2.0);
+    }
+
+    #[test]
+    fn catchments_split_at_the_ridge() {
+        let grid = two_valleys();
+        let (west, east) = (15, 19);
+        assert_eq!(grid.directions[west], 0);
+        assert_eq!(grid.directions[east], 0);
+        let labels = grid.catchments(&[west, east]).unwrap();
+        for (cell, &label) in labels.iter().enumerate() {
+            let expected = if cell % 5 < 2 { 0 } else { 1 };
+            assert_eq!(label, expected, "cell {cell}");
+        }
+        let accumulation = grid.accumulation(None).unwrap();
+        assert_eq!((accumulation[west], accumulation[east]), (8.0, 12.0));
+        // A nested pour point takes its own part of the catchment.
+        let nested = grid.catchments(&[west, 5]).unwrap();
+        assert_eq!(nested.iter().filter(|&&l| l == 1).count(), 3);
+        assert_eq!(nested.iter().filter(|&&l| l == 0).count(), 5);
+        assert_eq!(nested[2], -1);
+        // Pour points near the stream snap onto it.
+        assert_eq!(grid.snap(&[16], &accumulation, 1), [west]);
+    }
+
+    #[test]
+    fn maps_cells_to_coordinates() {
+        let grid = FlowGrid::from_dem(&[1.0; 6], 2, 3, (10.0, 5.0), (100.0, 50.0), false).unwrap();
+        assert_eq!(grid.centre(4), (115.0, 42.5));
+        assert_eq!(grid.cell_at(115.0, 42.5), Some(4));
+        assert_eq!(grid.cell_at(99.0, 42.5), None);
+        assert_eq!(grid.cell_area(), 50.0);
+    }
+
+    #[test]
+    fn rejects_invalid_grids() {
+        let dem = [1.0; 4];
+        assert!(matches!(
+            FlowGrid::from_dem(&dem, 2, 2, (0.0, 1.0), (0.0, 0.0), false),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            FlowGrid::from_dem(&dem, 3, 2, (1.0, 1.0), (0.0, 0.0), false),
+            Err(Error::Data(_))
+        ));
+        assert!(matches!(
+            FlowGrid::from_dem(&[f64::NAN; 4], 2, 2, (1.0, 1.0), (0.0, 0.0), false),
+            Err(Error::Data(_))
+        ));
+        // Two cells pointing at each other.
+        let cycle = FlowGrid::from_parts(1, 2, (1.0, 1.0), (0.0, 0.0), vec![1.0; 2], vec![1, 16]);
+        assert!(matches!(cycle, Err(Error::Data(msg)) if msg.contains("cycle")));
+        let grid = two_valleys();
+        assert!(grid.catchments(&[20]).is_err());
+        assert!(grid.accumulation(Some(&[1.0])).is_err());
+        let rebuilt = FlowGrid::from_parts(
+            4,
+            5,
+            (10.0, 10.0),
+            (0.0, 0.0),
+            grid.elevation.clone(),
+            grid.directions.clone(),
+        )
+        .unwrap();
+        assert_eq!(rebuilt, grid);
+    }
+}
@@ -0,0 +1,12 @@
+//! Terrain analysis of gridded DEMs: D8 flow directions, flow
+//! accumulation, stream networks, catchments draining to pour points and
+//! their longest flow paths, basin characteristics and travel times, and
+//! distributed routing of gridded runoff to outlet hydrographs.
+
+pub mod basin;
+pub mod d8;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod routing;
+pub mod streams;
+pub mod travel_time;
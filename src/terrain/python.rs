@@ -0,0 +1,718 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict, PyType};
+
+use super::basin::basin_characteristics;
+use super::d8::FlowGrid;
+use super::routing::{route_grid, CellRouting, GridRoutingOptions};
+use super::streams::StreamNetwork;
+use super::travel_time::{travel_time, VelocityModel};
+use crate::arrow::{grid_array, FloatBuffer, FloatSlice};
+use crate::config::python::value_to_py;
+use crate::progress::run_with_progress;
+use crate::time::{seconds_to_timedelta, timedelta_to_seconds};
+use crate::units::UnitSystem;
+
+/// Values of a 2-D array as `f64`, with its shape.
+fn grid_values(values: &FloatBuffer, what: &str) -> PyResult<(Vec<f64>, usize, usize)> {
+    let &[rows, cols] = values.shape() else {
+        return Err(PyValueError::new_err(format!(
+            "The {what} must be a 2-D array."
+        )));
+    };
+    let values = match values.values() {
+        FloatSlice::F32(v) => v.iter().map(|&z| f64::from(z)).collect(),
+        FloatSlice::F64(v) => v.to_vec(),
+    };
+    Ok((values, rows, cols))
+}
+
+/// Cell size given as one number or as `(dx, dy)`.
+#[derive(FromPyObject)]
+pub enum CellSize {
+    Square(f64),
+    Rectangle(f64, f64),
+}
+
+impl CellSize {
+    fn pair(self) -> (f64, f64) {
+        match self {
+            CellSize::Square(d) => (d, d),
+            CellSize::Rectangle(dx, dy) => (dx, dy),
+        }
+    }
+}
+
+/// D8 flow directions of a DEM, with flow accumulation, stream and
+/// catchment delineation.
+///
+/// Rows run north to south and columns west to east, as in a GeoTIFF read
+/// with rasterio. Each cell drains to the neighbour of steepest descent.
+/// Depressions and flats are filled first by priority flood, raising cells
+/// by the smallest representable amount so that every cell drains to the
+/// edge of the DEM. NaN cells are outside the domain; cells next to them
+/// or on the grid edge with no lower neighbour are outlets.
+///
+/// Args:
+///     dem (numpy.ndarray): 2-D float32 or float64 elevations in a
+///         projected coordinate system.
+///     cell_size (float | tuple[float, float]): Cell size, or its width and
+///         height `(dx, dy)`, in map units.
+///     origin (tuple[float, float], optional): Map coordinates of the
+///         north-west corner of the grid. Defaults to `(0, 0)`.
+///     fill (bool, optional): Whether to fill depressions. Without filling,
+///         cells with no lower neighbour are sinks. Defaults to True.
+#[pyclass(name = "FlowGrid", module = "rustflow.terrain")]
+pub struct PyFlowGrid {
+    pub(crate) inner: FlowGrid,
+}
+
+#[pymethods]
+impl PyFlowGrid {
+    #[new]
+    #[pyo3(signature = (dem, cell_size, origin=(0.0, 0.0), fill=true))]
+    fn new(
+        py: Python<'_>,
+        dem: FloatBuffer,
+        cell_size: CellSize,
+        origin: (f64, f64),
+        fill: bool,
+    ) -> PyResult<Self> {
+        let (values, rows, cols) = grid_values(&dem, "DEM")?;
+        let inner = py.allow_threads(|| {
+            FlowGrid::from_dem(&values, rows, cols, cell_size.pair(), origin, fill)
+        })?;
+        Ok(PyFlowGrid { inner })
+    }
+
+    /// tuple[int, int]: Rows and columns of the grid.
+    #[getter]
+    fn shape(&self) -> (usize, usize) {
+        (self.inner.rows, self.inner.cols)
+    }
+
+    /// tuple[float, float]: Cell width and height.
+    #[getter]
+    fn cell_size(&self) -> (f64, f64) {
+        (self.inner.dx, self.inner.dy)
+    }
+
+    /// tuple[float, float]: Map coordinates of the north-west corner.
+    #[getter]
+    fn origin(&self) -> (f64, f64) {
+        self.inner.origin
+    }
+
+    /// numpy.ndarray: D8 direction codes (uint8): 1 east, 2 south-east,
+    /// 4 south, 8 south-west, 16 west, 32 north-west, 64 north, 128
+    /// north-east, and 0 for outlets and cells outside the domain.
+    #[getter]
+    fn directions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        grid_array(py, &self.inner.directions, self.inner.rows, self.inner.cols)
+    }
+
+    /// numpy.ndarray: Elevations after filling (float64), NaN outside the
+    /// domain.
+    #[getter]
+    fn elevation<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        grid_array(py, &self.inner.elevation, self.inner.rows, self.inner.cols)
+    }
+
+    /// Computes flow accumulation.
+    ///
+    /// Args:
+    ///     weights (numpy.ndarray, optional): 2-D weight of each cell, such
+    ///         as runoff depth. NaN weights count as zero. By default each
+    ///         cell weighs one.
+    ///     area (bool, optional): Whether to multiply the result by the cell
+    ///         area, giving contributing area (or, with depth weights,
+    ///         volume). Defaults to False.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: The number of cells (or summed weight) draining
+    ///         through each cell, including itself; NaN outside the domain.
+    #[pyo3(signature = (weights=None, area=false))]
+    fn accumulation<'py>(
+        &self,
+        py: Python<'py>,
+        weights: Option<FloatBuffer>,
+        area: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let weights = weights
+            .map(|w| grid_values(&w, "weights"))
+            .transpose()?
+            .map(|(w, _, _)| w);
+        let mut total = py.allow_threads(|| self.inner.accumulation(weights.as_deref()))?;
+        if area {
+            let cell = self.inner.cell_area();
+            total.iter_mut().for_each(|v| *v *= cell);
+        }
+        grid_array(py, &total, self.inner.rows, self.inner.cols)
+    }
+
+    /// The cell containing a map point.
+    ///
+    /// Args:
+    ///     x (float): Easting.
+    ///     y (float): Northing.
+    ///
+    /// Returns:
+    ///     tuple[int, int] | None: Row and column, or None off the grid.
+    fn cell_at(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        self.inner
+            .cell_at(x, y)
+            .map(|cell| (cell / self.inner.cols, cell % self.inner.cols))
+    }
+
+    /// Map coordinates of the centre of a cell.
+    ///
+    /// Args:
+    ///     row (int): Row.
+    ///     col (int): Column.
+    ///
+    /// Returns:
+    ///     tuple[float, float]: Easting and northing.
+    fn centre(&self, row: usize, col: usize) -> (f64, f64) {
+        self.inner.centre(row * self.inner.cols + col)
+    }
+
+    /// Delineates the stream network.
+    ///
+    /// Cells whose contributing area reaches `threshold` are streams. They
+    /// are ordered by Strahler's scheme and split into links at
+    /// confluences.
+    ///
+    /// Args:
+    ///     threshold (float): Contributing area that starts a stream, in
+    ///         squared map units.
+    ///
+    /// Returns:
+    ///     dict: `links` (int32 array of the link index of each cell, -1 off
+    ///         the streams), `order` (uint8 array of Strahler orders, 0 off
+    ///         the streams), `segments` (one dict per link with its `cells`
+    ///         as `(row, col)` from upstream to downstream, `downstream`
+    ///         link index or None, `order`, `length`, draining `area` and
+    ///         `slope`) and `geojson`, a FeatureCollection of the links with
+    ///         `from_node`/`to_node` attributes for
+    ///         `rustflow.io.read_geojson_network`.
+    fn streams<'py>(&self, py: Python<'py>, threshold: f64) -> PyResult<Bound<'py, PyDict>> {
+        let grid = &self.inner;
+        let network = py.allow_threads(|| StreamNetwork::delineate(grid, threshold))?;
+        let result = PyDict::new(py);
+        result.set_item(
+            "links",
+            grid_array(py, &network.link_of, grid.rows, grid.cols)?,
+        )?;
+        result.set_item(
+            "order",
+            grid_array(py, &network.order, grid.rows, grid.cols)?,
+        )?;
+        let segments = network
+            .links
+            .iter()
+            .map(|link| {
+                let segment = PyDict::new(py);
+                let cells: Vec<(usize, usize)> = link
+                    .cells
+                    .iter()
+                    .map(|&c| (c / grid.cols, c % grid.cols))
+                    .collect();
+                segment.set_item("cells", cells)?;
+                segment.set_item("downstream", link.downstream)?;
+                segment.set_item("order", link.order)?;
+                segment.set_item("length", link.length)?;
+                segment.set_item("area", link.area)?;
+                segment.set_item("slope", link.slope)?;
+                Ok(segment)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        result.set_item("segments", segments)?;
+        result.set_item("geojson", value_to_py(py, &network.to_geojson(grid))?)?;
+        Ok(result)
+    }
+
+    /// Delineates the catchments of pour points.
+    ///
+    /// Each cell belongs to the first pour point it drains through, so a
+    /// pour point upstream of another splits off its own sub-catchment.
+    ///
+    /// Args:
+    ///     pour_points (list[tuple[int, int]]): Row and column of each
+    ///         outlet.
+    ///     snap (int, optional): Radius in cells within which each pour point
+    ///         is moved to the cell of largest flow accumulation. Defaults to
+    ///         0.
+    ///
+    /// Returns:
+    ///     dict: `labels` (int32 array of the index of the pour point each
+    ///         cell drains to, -1 for none), the snapped `pour_points` and
+    ///         the `area` of each catchment in squared map units.
+    ///
+    /// Raises:
+    ///     ValueError: If a pour point is outside the domain.
+    #[pyo3(signature = (pour_points, snap=0))]
+    fn catchments<'py>(
+        &self,
+        py: Python<'py>,
+        pour_points: Vec<(usize, usize)>,
+        snap: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let grid = &self.inner;
+        let mut cells = pour_points
+            .iter()
+            .map(|&(r, c)| {
+                if r < grid.rows && c < grid.cols {
+                    Ok(r * grid.cols + c)
+                } else {
+                    Err(PyValueError::new_err(format!(
+                        "Pour point ({r}, {c}) is off the {} × {} grid.",
+                        grid.rows, grid.cols
+                    )))
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let labels = py.allow_threads(|| -> crate::error::Result<Vec<i32>> {
+            if snap > 0 {
+                cells = grid.snap(&cells, &grid.accumulation(None)?, snap);
+            }
+            grid.catchments(&cells)
+        })?;
+        let mut area = vec![0.0; cells.len()];
+        for &label in &labels {
+            if label >= 0 {
+                area[label as usize] += grid.cell_area();
+            }
+        }
+        let result = PyDict::new(py);
+        result.set_item("labels", grid_array(py, &labels, grid.rows, grid.cols)?)?;
+        result.set_item(
+            "pour_points",
+            cells
+                .iter()
+                .map(|&c| (c / grid.cols, c % grid.cols))
+                .collect::<Vec<_>>(),
+        )?;
+        result.set_item("area", area)?;
+        Ok(result)
+    }
+
+    /// Computes the longest flow path and basin characteristics of the
+    /// catchment of an outlet.
+    ///
+    /// The results are the usual inputs to Snyder and NRCS lag formulas and
+    /// regional regression equations. Lengths and areas are in map units,
+    /// elevations in DEM units, and slopes are ratios.
+    ///
+    /// Args:
+    ///     outlet (tuple[int, int]): Row and column of the outlet.
+    ///     snap (int, optional): Radius in cells within which the outlet is
+    ///         moved to the cell of largest flow accumulation. Defaults to 0.
+    ///
+    /// Returns:
+    ///     dict: `outlet` (snapped row and column), `cells`, `area`,
+    ///         `perimeter`, `centroid` (map coordinates), `flow_path` (cells
+    ///         from the most remote to the outlet), `flow_path_length` (`L`),
+    ///         `centroid_length` (`Lc`, along the flow path from the outlet
+    ///         to the point nearest the centroid), `flow_path_slope` (total
+    ///         drop over `L`), `slope_10_85` (between 10 % and 85 % of `L`
+    ///         from the outlet), `mean_slope` (mean cell slope by Horn's
+    ///         method), `min_elevation`, `mean_elevation`, `max_elevation`,
+    ///         `relief`, `relief_ratio`, `hypsometric_integral`,
+    ///         `form_factor`, `elongation_ratio`, `circularity_ratio` and
+    ///         `compactness` (Gravelius coefficient).
+    ///
+    /// Raises:
+    ///     ValueError: If the outlet is outside the domain or drains only
+    ///         itself.
+    #[pyo3(signature = (outlet, snap=0))]
+    fn basin_characteristics<'py>(
+        &self,
+        py: Python<'py>,
+        outlet: (usize, usize),
+        snap: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let grid = &self.inner;
+        let (r, c) = outlet;
+        if r >= grid.rows || c >= grid.cols {
+            return Err(PyValueError::new_err(format!(
+                "The outlet ({r}, {c}) is off the {} × {} grid.",
+                grid.rows, grid.cols
+            )));
+        }
+        let b = py.allow_threads(|| -> crate::error::Result<_> {
+            let mut cell = r * grid.cols + c;
+            if snap > 0 {
+                cell = grid.snap(&[cell], &grid.accumulation(None)?, snap)[0];
+            }
+            basin_characteristics(grid, cell)
+        })?;
+        let rc = |cell: usize| (cell / grid.cols, cell % grid.cols);
+        let result = PyDict::new(py);
+        result.set_item("outlet", rc(b.outlet))?;
+        result.set_item("cells", b.cells)?;
+        result.set_item("area", b.area)?;
+        result.set_item("perimeter", b.perimeter)?;
+        result.set_item("centroid", b.centroid)?;
+        result.set_item(
+            "flow_path",
+            b.flow_path.iter().map(|&c| rc(c)).collect::<Vec<_>>(),
+        )?;
+        result.set_item("flow_path_length", b.flow_path_length)?;
+        result.set_item("centroid_length", b.centroid_length)?;
+        result.set_item("flow_path_slope", b.flow_path_slope)?;
+        result.set_item("slope_10_85", b.slope_10_85)?;
+        result.set_item("mean_slope", b.mean_slope)?;
+        result.set_item("min_elevation", b.min_elevation)?;
+        result.set_item("mean_elevation", b.mean_elevation)?;
+        result.set_item("max_elevation", b.max_elevation)?;
+        result.set_item("relief", b.relief)?;
+        result.set_item("relief_ratio", b.relief_ratio)?;
+        result.set_item("hypsometric_integral", b.hypsometric_integral)?;
+        result.set_item("form_factor", b.form_factor)?;
+        result.set_item("elongation_ratio", b.elongation_ratio)?;
+        result.set_item("circularity_ratio", b.circularity_ratio)?;
+        result.set_item("compactness", b.compactness)?;
+        Ok(result)
+    }
+
+    /// Computes the travel time from each cell to an outlet and the
+    /// time–area histogram of its catchment.
+    ///
+    /// Velocity follows Manning's equation in each cell, on the slope of
+    /// the filled DEM to the downstream cell, with a shallow hydraulic
+    /// radius on hillslopes and a deeper one in cells whose contributing
+    /// area reaches `channel_area`. Travel times add flow length over
+    /// velocity along the flow path. The histogram is the translation
+    /// input of `rustflow.runoff.clark_unit_hydrograph` and of
+    /// `rustflow.design.pmf(time_area=...)`.
+    ///
+    /// Args:
+    ///     outlet (tuple[int, int]): Row and column of the outlet.
+    ///     time_step (timedelta): Bin width of the time–area histogram.
+    ///     channel_area (float): Contributing area in squared map units from
+    ///         which cells are channels.
+    ///     hillslope_n (float, optional): Manning's roughness of hillslope
+    ///         flow. Defaults to 0.1.
+    ///     channel_n (float, optional): Manning's roughness of channels.
+    ///         Defaults to 0.04.
+    ///     hillslope_radius (float, optional): Hydraulic radius of
+    ///         hillslope flow. Defaults to 0.03.
+    ///     channel_radius (float, optional): Hydraulic radius of channel
+    ///         flow. Defaults to 1.0.
+    ///     roughness (numpy.ndarray, optional): 2-D Manning's roughness of
+    ///         each cell, for example from land cover, used instead of
+    ///         `hillslope_n` and `channel_n` where finite and positive.
+    ///     min_slope (float, optional): Slope used where the filled DEM is
+    ///         flatter. Defaults to 1e-4.
+    ///     min_velocity (float, optional): Lower bound on the velocity.
+    ///         Defaults to 0.01.
+    ///     max_velocity (float, optional): Upper bound on the velocity.
+    ///         Defaults to 5.0.
+    ///     snap (int, optional): Radius in cells within which the outlet is
+    ///         moved to the cell of largest flow accumulation. Defaults to 0.
+    ///     units (str, optional): `"si"` for map units, radii and
+    ///         velocities in metres and areas in km², or `"us"` for feet and
+    ///         mi². Defaults to `"si"`.
+    ///
+    /// Returns:
+    ///     dict: `outlet` (snapped row and column), `travel_time` (array of
+    ///         hours to the outlet, NaN outside the catchment), `velocity`
+    ///         (array), `time_of_concentration` (timedelta) and `time_area`
+    ///         (area reaching the outlet within each time step).
+    ///
+    /// Raises:
+    ///     ValueError: If the outlet is outside the domain, the roughness
+    ///         does not match the grid, or a parameter is not positive.
+    #[pyo3(signature = (
+        outlet,
+        time_step,
+        channel_area,
+        hillslope_n=0.1,
+        channel_n=0.04,
+        hillslope_radius=0.03,
+        channel_radius=1.0,
+        roughness=None,
+        min_slope=1e-4,
+        min_velocity=0.01,
+        max_velocity=5.0,
+        snap=0,
+        units=UnitSystem::Si
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn travel_time<'py>(
+        &self,
+        py: Python<'py>,
+        outlet: (usize, usize),
+        time_step: Bound<'_, PyDelta>,
+        channel_area: f64,
+        hillslope_n: f64,
+        channel_n: f64,
+        hillslope_radius: f64,
+        channel_radius: f64,
+        roughness: Option<FloatBuffer>,
+        min_slope: f64,
+        min_velocity: f64,
+        max_velocity: f64,
+        snap: usize,
+        units: UnitSystem,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let grid = &self.inner;
+        let (r, c) = outlet;
+        if r >= grid.rows || c >= grid.cols {
+            return Err(PyValueError::new_err(format!(
+                "The outlet ({r}, {c}) is off the {} × {} grid.",
+                grid.rows, grid.cols
+            )));
+        }
+        let roughness = roughness
+            .map(|n| grid_values(&n, "roughness"))
+            .transpose()?
+            .map(|(n, _, _)| n);
+        let model = VelocityModel {
+            hillslope_n,
+            channel_n,
+            hillslope_radius: units.length_to_si(hillslope_radius),
+            channel_radius: units.length_to_si(channel_radius),
+            channel_area: units.area_to_si(channel_area),
+            min_slope,
+            min_velocity: units.velocity_to_si(min_velocity),
+            max_velocity: units.velocity_to_si(max_velocity),
+            length_scale: units.length_factor(),
+        };
+        let dt = timedelta_to_seconds(&time_step);
+        let (times, time_area) = py.allow_threads(|| -> crate::error::Result<_> {
+            let mut cell = r * grid.cols + c;
+            if snap > 0 {
+                cell = grid.snap(&[cell], &grid.accumulation(None)?, snap)[0];
+            }
+            let times = travel_time(grid, cell, &model, roughness.as_deref())?;
+            let time_area = times.time_area(dt)?;
+            Ok((times, time_area))
+        })?;
+        let hours: Vec<f64> = times.time.iter().map(|t| t / 3600.0).collect();
+        let velocity: Vec<f64> = times
+            .velocity
+            .iter()
+            .map(|&v| units.velocity_from_si(v))
+            .collect();
+        let result = PyDict::new(py);
+        result.set_item(
+            "outlet",
+            (times.outlet / grid.cols, times.outlet % grid.cols),
+        )?;
+        result.set_item("travel_time", grid_array(py, &hours, grid.rows, grid.cols)?)?;
+        result.set_item("velocity", grid_array(py, &velocity, grid.rows, grid.cols)?)?;
+        result.set_item(
+            "time_of_concentration",
+            seconds_to_timedelta(py, times.time_of_concentration)?,
+        )?;
+        result.set_item(
+            "time_area",
+            time_area
+                .iter()
+                .map(|&a| units.basin_area_from_si(a))
+                .collect::<Vec<_>>(),
+        )?;
+        Ok(result)
+    }
+
+    /// Routes gridded runoff from cell to cell along the flow directions to
+    /// outlet hydrographs.
+    ///
+    /// Each cell is a store receiving the runoff falling on it and the
+    /// outflow of the cells draining to it. Cells whose contributing area
+    /// reaches `channel_area` are channel cells, the rest hillslope cells.
+    /// With `"linear_reservoir"`, each cell releases its storage divided by
+    /// its flow length over the hillslope or channel velocity. With
+    /// `"kinematic_wave"`, it releases the Manning flow of the depth of its
+    /// storage spread over its flow length, as sheet flow across the cell
+    /// on hillslopes and in a wide channel of `channel_width` in channel
+    /// cells, on the slope of the filled DEM to the downstream cell.
+    ///
+    /// Args:
+    ///     runoff (numpy.ndarray): 3-D float32 or float64 runoff depth of
+    ///         each step and cell, shaped `(steps, rows, cols)`, in mm (SI)
+    ///         or inches (US). NaN counts as no runoff.
+    ///     time_step (timedelta): Interval between consecutive steps.
+    ///     outlets (list[tuple[int, int]]): Row and column of the cells
+    ///         whose outflow is returned.
+    ///     channel_area (float): Contributing area in squared map units from
+    ///         which cells are channels.
+    ///     method (str, optional): `"linear_reservoir"` or
+    ///         `"kinematic_wave"`. Defaults to `"linear_reservoir"`.
+    ///     hillslope_velocity (float, optional): Linear reservoir velocity
+    ///         of hillslope cells. Defaults to 0.1.
+    ///     channel_velocity (float, optional): Linear reservoir velocity of
+    ///         channel cells. Defaults to 1.0.
+    ///     hillslope_n (float, optional): Manning's roughness of hillslope
+    ///         sheet flow. Defaults to 0.2.
+    ///     channel_n (float, optional): Manning's roughness of channels.
+    ///         Defaults to 0.04.
+    ///     channel_width (float, optional): Width of channels. Defaults to
+    ///         5.0.
+    ///     min_slope (float, optional): Slope used where the filled DEM is
+    ///         flatter. Defaults to 1e-4.
+    ///     units (str, optional): `"si"` for map units, widths and velocities
+    ///         in metres and flows in m³/s, or `"us"` for feet and cfs.
+    ///         Defaults to `"si"`.
+    ///     progress_callback (Callable[[int, int], None], optional): Called
+    ///         with the completed and total number of time steps as the run
+    ///         proceeds, at most 20 times a second. Raising an exception
+    ///         from it cancels the run. Ctrl-C also interrupts the run.
+    ///
+    /// Returns:
+    ///     dict: `outlets` (mean outflow of each outlet over each step),
+    ///         `peak_flow` (array of the largest outflow of each cell, NaN
+    ///         outside the domain), `runoff_volume` (volume of runoff on the
+    ///         domain) and `storage` (volume left in the cells at the end),
+    ///         in m³ or ft³.
+    ///
+    /// Raises:
+    ///     ValueError: If the runoff does not match the grid, an outlet is
+    ///         outside the domain, or a parameter is not positive.
+    #[pyo3(signature = (
+        runoff,
+        time_step,
+        outlets,
+        channel_area,
+        method="linear_reservoir",
+        hillslope_velocity=0.1,
+        channel_velocity=1.0,
+        hillslope_n=0.2,
+        channel_n=0.04,
+        channel_width=5.0,
+        min_slope=1e-4,
+        units=UnitSystem::Si,
+        progress_callback=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn route_runoff<'py>(
+        &self,
+        py: Python<'py>,
+        runoff: FloatBuffer,
+        time_step: Bound<'_, PyDelta>,
+        outlets: Vec<(usize, usize)>,
+        channel_area: f64,
+        method: &str,
+        hillslope_velocity: f64,
+        channel_velocity: f64,
+        hillslope_n: f64,
+        channel_n: f64,
+        channel_width: f64,
+        min_slope: f64,
+        units: UnitSystem,
+        progress_callback: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let grid = &self.inner;
+        let &[_, rows, cols] = runoff.shape() else {
+            return Err(PyValueError::new_err(
+                "The runoff must be a 3-D array of steps, rows and columns.",
+            ));
+        };
+        if (rows, cols) != (grid.rows, grid.cols) {
+            return Err(PyValueError::new_err(format!(
+                "The runoff grids are {rows} × {cols}, not {} × {}.",
+                grid.rows, grid.cols
+            )));
+        }
+        let cells = outlets
+            .iter()
+            .map(|&(r, c)| {
+                if r < grid.rows && c < grid.cols {
+                    Ok(r * grid.cols + c)
+                } else {
+                    Err(PyValueError::new_err(format!(
+                        "Outlet ({r}, {c}) is off the {} × {} grid.",
+                        grid.rows, grid.cols
+                    )))
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let method = match method {
+            "linear_reservoir" => CellRouting::LinearReservoir {
+                hillslope_velocity: units.velocity_to_si(hillslope_velocity),
+                channel_velocity: units.velocity_to_si(channel_velocity),
+            },
+            "kinematic_wave" => CellRouting::KinematicWave {
+                hillslope_n,
+                channel_n,
+                channel_width: units.length_to_si(channel_width),
+                min_slope,
+            },
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown routing method `{other}`; expected \"linear_reservoir\" or \"kinematic_wave\"."
+                )))
+            }
+        };
+        let options = GridRoutingOptions {
+            method,
+            channel_area: units.area_to_si(channel_area),
+            length_scale: units.length_factor(),
+            depth_scale: units.small_depth_to_si(1.0),
+        };
+        let dt = timedelta_to_seconds(&time_step);
+        let result = run_with_progress(py, progress_callback.as_ref(), |progress| {
+            match runoff.values() {
+                FloatSlice::F32(v) => route_grid(grid, v, dt, &cells, &options, progress),
+                FloatSlice::F64(v) => route_grid(grid, v, dt, &cells, &options, progress),
+            }
+        })?;
+        let to_flow = |q: &[f64]| {
+            q.iter()
+                .map(|&q| units.discharge_from_si(q))
+                .collect::<Vec<_>>()
+        };
+        let dict = PyDict::new(py);
+        dict.set_item(
+            "outlets",
+            result
+                .outlets
+                .iter()
+                .map(|s| to_flow(s))
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "peak_flow",
+            grid_array(py, &to_flow(&result.peak), grid.rows, grid.cols)?,
+        )?;
+        dict.set_item("runoff_volume", units.volume_from_si(result.runoff_volume))?;
+        dict.set_item("storage", units.volume_from_si(result.storage))?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FlowGrid(shape=({}, {}), cell_size=({}, {}))",
+            self.inner.rows, self.inner.cols, self.inner.dx, self.inner.dy
+        )
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (FlowGridState,))> {
+        let g = &slf.borrow().inner;
+        let state = (
+            (g.rows, g.cols),
+            (g.dx, g.dy),
+            g.origin,
+            g.elevation.clone(),
+            g.directions.clone(),
+        );
+        Ok((slf.get_type().getattr("_from_state")?, (state,)))
+    }
+
+    #[classmethod]
+    fn _from_state(_cls: &Bound<'_, PyType>, state: FlowGridState) -> PyResult<Self> {
+        let ((rows, cols), cell_size, origin, elevation, directions) = state;
+        Ok(PyFlowGrid {
+            inner: FlowGrid::from_parts(rows, cols, cell_size, origin, elevation, directions)?,
+        })
+    }
+}
+
+type FlowGridState = ((usize, usize), (f64, f64), (f64, f64), Vec<f64>, Vec<u8>);
+
+#[pymodule]
+pub fn init_terrain(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyFlowGrid>()?;
+    Ok(())
+}
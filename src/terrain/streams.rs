@@ -0,0 +1,192 @@
+//! Stream networks delineated from flow accumulation.
+
+use super::d8::FlowGrid;
+use crate::config::Value;
+use crate::error::{Error, Result};
+
+/// A stream link: the cells between a source or confluence and the next
+/// confluence or outlet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    /// Cells from upstream to downstream.
+    pub cells: Vec<usize>,
+    /// Index of the link the last cell drains to.
+    pub downstream: Option<usize>,
+    /// Cell the last cell drains to, if on the grid.
+    pub outlet: Option<usize>,
+    /// Strahler order.
+    pub order: u8,
+    /// Length from the centre of the first cell to the centre of the
+    /// outlet cell, or to the grid edge.
+    pub length: f64,
+    /// Area draining through the last cell.
+    pub area: f64,
+    /// Elevation drop over `length`, divided by `length`; for a link with
+    /// no drop, such as a single cell at the grid edge, the slope from the
+    /// largest cell draining into it.
+    pub slope: f64,
+}
+
+/// Stream cells, their Strahler order and the links they form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamNetwork {
+    /// Link index of each cell, or -1 off the streams.
+    pub link_of: Vec<i32>,
+    /// Strahler order of each cell, or 0 off the streams.
+    pub order: Vec<u8>,
+    pub links: Vec<Link>,
+}
+
+impl StreamNetwork {
+    /// Streams of the cells whose contributing area reaches `threshold`
+    /// (in the units of `dx * dy`).
+    pub fn delineate(grid: &FlowGrid, threshold: f64) -> Result<Self> {
+        if threshold.is_nan() || threshold <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "The stream threshold must be a positive area.".into(),
+            ));
+        }
+        let n = grid.len();
+        let area = grid.cell_area();
+        let count = grid.accumulation(None)?;
+        let stream: Vec<bool> = count.iter().map(|&c| c * area >= threshold).collect();
+
+        // Strahler order, passed down from the ridges, and the number of
+        // stream cells draining into each cell.
+        let mut order = vec![0u8; n];
+        let mut highest = vec![(0u8, 0u8); n];
+        let mut tributaries = vec![0u8; n];
+        let mut main_inflow: Vec<Option<usize>> = vec![None; n];
+        for &cell in grid.order() {
+            if let Some(down) = grid.downstream(cell) {
+                if main_inflow[down].is_none_or(|m| count[cell] > count[m]) {
+                    main_inflow[down] = Some(cell);
+                }
+            }
+            if !stream[cell] {
+                continue;
+            }
+            let (top, ties) = highest[cell];
+            order[cell] = match (top, ties) {
+                (0, _) => 1,
+                (top, ties) if ties >= 2 => top + 1,
+                (top, _) => top,
+            };
+            if let Some(down) = grid.downstream(cell) {
+                tributaries[down] += 1;
+                let entry = &mut highest[down];
+                match order[cell].cmp(&entry.0) {
+                    std::cmp::Ordering::Greater => *entry = (order[cell], 1),
+                    std::cmp::Ordering::Equal => entry.1 += 1,
+                    std::cmp::Ordering::Less => {}
+                }
+            }
+        }
+
+        // Links start at sources and confluences.
+        let mut link_of = vec![-1; n];
+        let mut links = Vec::new();
+        for head in (0..n).filter(|&c| stream[c] && tributaries[c] != 1) {
+            let id = links.len() as i32;
+            let mut cells = vec![head];
+            link_of[head] = id;
+            let mut length = grid.flow_length(head);
+            let mut cell = head;
+            let outlet = loop {
+                match grid.downstream(cell) {
+                    Some(down) if tributaries[down] == 1 => {
+                        cell = down;
+                        cells.push(cell);
+                        link_of[cell] = id;
+                        length += grid.flow_length(cell);
+                    }
+                    other => break other,
+                }
+            };
+            let end = outlet.unwrap_or(cell);
+            let drop = grid.elevation[head] - grid.elevation[end];
+            let slope = match main_inflow[head] {
+                _ if drop > 0.0 => drop / length,
+                Some(up) => (grid.elevation[up] - grid.elevation[head]) / grid.flow_length(up),
+                None => 0.0,
+            };
+            links.push(Link {
+                cells,
+                downstream: None,
+                outlet,
+                order: order[head],
+                length,
+                area: count[cell] * area,
+                slope,
+            });
+        }
+        for link in &mut links {
+            link.downstream = link
+                .outlet
+                .map(|cell| link_of[cell])
+                .filter(|&l| l >= 0)
+                .map(|l| l as usize);
+        }
+        Ok(StreamNetwork {
+            link_of,
+            order,
+            links,
+        })
+    }
+
+    /// The links as a GeoJSON FeatureCollection of line strings through the
+    /// cell centres, with `id`, `from_node`, `to_node`, `order`, `length`,
+    /// `area` and `slope` properties, ready for
+    /// [`crate::io::geojson::network_from_geojson`]. Node ids are `n`
+    /// followed by the cell index, and outlets off the grid are `o`
+    /// followed by the index of the last cell.
+    pub fn to_geojson(&self, grid: &FlowGrid) -> Value {
+        let features = self
+            .links
+            .iter()
+            .enumerate()
+            .map(|(i, link)| {
+                let last = link.cells[link.cells.len() - 1];
+                let mut points: Vec<(f64, f64)> =
+                    link.cells.iter().map(|&c| grid.centre(c)).collect();
+                let to = match link.outlet {
+                    Some(cell) => {
+                        points.push(grid.centre(cell));
+                        format!("n{cell}")
+                    }
+                    None => format!("o{last}"),
+                };
+                if points.len() == 1 {
+                    // A single-cell link at the grid edge: extend it to
+                    // the edge so that it has a length.
+                    let (x, y) = points[0];
+                    points.push((x, y - 0.5 * grid.dy));
+                }
+                let coordinates = points
+                    .iter()
+                    .map(|&(x, y)| Value::from([x, y].as_slice()))
+                    .collect();
+                let mut properties = Value::Object(Vec::new());
+                properties.insert("id", format!("link{}", i + 1).into());
+                properties.insert("from_node", format!("n{}", link.cells[0]).into());
+                properties.insert("to_node", to.into());
+                properties.insert("order", f64::from(link.order).into());
+                properties.insert("length", link.length.into());
+                properties.insert("area", link.area.into());
+                properties.insert("slope", link.slope.into());
+                let mut geometry = Value::Object(Vec::new());
+                geometry.insert("type", "LineString".into());
+                geometry.insert("coordinates", Value::Array(coordinates));
+                let mut feature = Value::Object(Vec::new());
+                feature.insert("type", "Feature".into());
+                feature.insert("properties", properties);
+                feature.insert("geometry", geometry);
+                feature
+            })
+            .collect();
+        let mut collection = Value::Object(Vec::new());
+        collection.insert("type", "FeatureCollection".into());
+        collection.insert("features", Value::Array(features));
+        collection
+    }
+}
@@ -0,0 +1,151 @@
+//! Travel time from each cell to an outlet and the time–area histogram of
+//! its catchment, in SI units.
+//!
+//! Flow velocity follows Manning's equation in every cell, with a shallow
+//! hydraulic radius on hillslopes and a deeper one in channel cells, on the
+//! slope of the filled DEM to the downstream cell. Travel times accumulate
+//! flow length over velocity from the outlet upwards, and the histogram of
+//! catchment area against travel time is the translation input of the
+//! Clark unit hydrograph
+//! ([`crate::runoff::unit_hydrograph::clark_unit_hydrograph`]).
+
+use super::d8::FlowGrid;
+use crate::error::{Error, Result};
+
+/// Manning velocity of hillslope and channel cells.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VelocityModel {
+    pub hillslope_n: f64,
+    pub channel_n: f64,
+    /// Hydraulic radius (m) of hillslope flow.
+    pub hillslope_radius: f64,
+    /// Hydraulic radius (m) of channel flow.
+    pub channel_radius: f64,
+    /// Contributing area (m²) from which cells are channels.
+    pub channel_area: f64,
+    /// Slope used where the filled DEM is flatter.
+    pub min_slope: f64,
+    /// Bounds (m/s) on the velocity of any cell.
+    pub min_velocity: f64,
+    pub max_velocity: f64,
+    /// Metres per map unit of the grid.
+    pub length_scale: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TravelTime {
+    pub outlet: usize,
+    /// Time (s) from each cell to the outlet; NaN outside its catchment.
+    pub time: Vec<f64>,
+    /// Flow velocity (m/s) of each cell; NaN outside the catchment.
+    pub velocity: Vec<f64>,
+    /// Longest travel time (s).
+    pub time_of_concentration: f64,
+    /// Area (m²) of one cell.
+    pub cell_area: f64,
+}
+
+impl TravelTime {
+    /// Catchment area (m²) reaching the outlet within each step of `dt`
+    /// (s): entry `k` holds the cells with travel times in
+    /// `(k dt, (k + 1) dt]`, and the outlet cell itself in the first.
+    pub fn time_area(&self, dt: f64) -> Result<Vec<f64>> {
+        if dt.is_nan() || dt <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "The time step must be positive.".into(),
+            ));
+        }
+        let steps = ((self.time_of_concentration / dt).ceil() as usize).max(1);
+        let mut area = vec![0.0; steps];
+        for &t in self.time.iter().filter(|t| !t.is_nan()) {
+            let k = ((t / dt).ceil() as usize).saturating_sub(1).min(steps - 1);
+            area[k] += self.cell_area;
+        }
+        Ok(area)
+    }
+}
+
+/// Travel time from every cell of the catchment of `outlet` to it, with
+/// Manning's roughness taken from `roughness` where given, finite and
+/// positive.
+pub fn travel_time(
+    grid: &FlowGrid,
+    outlet: usize,
+    model: &VelocityModel,
+    roughness: Option<&[f64]>,
+) -> Result<TravelTime> {
+    let positive = |v: f64| v.is_finite() && v > 0.0;
+    if ![
+        model.hillslope_n,
+        model.channel_n,
+        model.hillslope_radius,
+        model.channel_radius,
+        model.min_slope,
+        model.min_velocity,
+        model.max_velocity,
+        model.length_scale,
+    ]
+    .into_iter()
+    .all(positive)
+        || model.min_velocity > model.max_velocity
+    {
+        return Err(Error::InvalidParameter(
+            "Roughness, hydraulic radii, minimum slope and velocity bounds must be positive, \
+             with the minimum velocity below the maximum."
+                .into(),
+        ));
+    }
+    if roughness.is_some_and(|n| n.len() != grid.len()) {
+        return Err(Error::Data(format!(
+            "The roughness grid must be {} × {}.",
+            grid.rows, grid.cols
+        )));
+    }
+    let labels = grid.catchments(&[outlet])?;
+    let scale = model.length_scale;
+    let cell_area = grid.cell_area() * scale * scale;
+    let contributing = grid.accumulation(None)?;
+
+    let mut velocity = vec![f64::NAN; grid.len()];
+    let mut time = vec![f64::NAN; grid.len()];
+    time[outlet] = 0.0;
+    let mut longest: f64 = 0.0;
+    // From the outlet upwards, so that each cell's downstream time is known.
+    for &cell in grid.order().iter().rev() {
+        if labels[cell] != 0 {
+            continue;
+        }
+        let length = grid.flow_length(cell) * scale;
+        let down = grid.downstream(cell);
+        let slope = down
+            .map(|d| (grid.elevation[cell] - grid.elevation[d]) * scale / length)
+            .unwrap_or(0.0)
+            .max(model.min_slope);
+        let channel = contributing[cell] * cell_area >= model.channel_area;
+        let (default_n, radius) = if channel {
+            (model.channel_n, model.channel_radius)
+        } else {
+            (model.hillslope_n, model.hillslope_radius)
+        };
+        let n = roughness
+            .map(|r| r[cell])
+            .filter(|n| positive(*n))
+            .unwrap_or(default_n);
+        let v = (radius.powf(2.0 / 3.0) * slope.sqrt() / n)
+            .clamp(model.min_velocity, model.max_velocity);
+        velocity[cell] = v;
+        if cell != outlet {
+            if let Some(d) = down {
+                time[cell] = time[d] + length / v;
+                longest = longest.max(time[cell]);
+            }
+        }
+    }
+    Ok(TravelTime {
+        outlet,
+        time,
+        velocity,
+        time_of_concentration: longest,
+        cell_area,
+    })
+}
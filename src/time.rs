@@ -0,0 +1,57 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDateAccess, PyDateTime, PyDelta, PyDeltaAccess, PyTimeAccess};
+
+use crate::datetime::DateTime;
+
+/// Converts a Python `timedelta` to seconds as `f64`.
+///
+/// The microsecond component is preserved, so sub-second time steps such as
+/// `timedelta(milliseconds=500)` are not truncated to zero.
+pub fn timedelta_to_seconds(delta: &Bound<'_, PyDelta>) -> f64 {
+    delta.get_days() as f64 * 86_400.0
+        + delta.get_seconds() as f64
+        + delta.get_microseconds() as f64 * 1e-6
+}
+
+/// Converts a naive Python `datetime` to seconds since the Unix epoch. Any
+/// `tzinfo` is ignored.
+pub fn datetime_to_epoch(datetime: &Bound<'_, PyDateTime>) -> f64 {
+    DateTime {
+        year: datetime.get_year() as i64,
+        month: datetime.get_month() as u32,
+        day: datetime.get_day() as u32,
+        hour: datetime.get_hour() as u32,
+        minute: datetime.get_minute() as u32,
+        second: datetime.get_second() as u32,
+        microsecond: datetime.get_microsecond(),
+    }
+    .to_epoch()
+}
+
+/// Converts seconds since the Unix epoch to a naive Python `datetime`.
+pub fn epoch_to_datetime(py: Python<'_>, seconds: f64) -> PyResult<Bound<'_, PyDateTime>> {
+    let dt = DateTime::from_epoch(seconds);
+    PyDateTime::new(
+        py,
+        dt.year as i32,
+        dt.month as u8,
+        dt.day as u8,
+        dt.hour as u8,
+        dt.minute as u8,
+        dt.second as u8,
+        dt.microsecond,
+        None,
+    )
+}
+
+/// Converts seconds to a Python `timedelta`, keeping microseconds.
+pub fn seconds_to_timedelta(py: Python<'_>, seconds: f64) -> PyResult<Bound<'_, PyDelta>> {
+    let whole = seconds.floor();
+    PyDelta::new(
+        py,
+        0,
+        whole as i32,
+        ((seconds - whole) * 1e6).round() as i32,
+        true,
+    )
+}
@@ -0,0 +1,322 @@
+//! Block-by-block processing of records too long to hold in memory.
+//!
+//! Each processor is fed consecutive blocks of a series and carries its
+//! state from one block to the next, so the output is the same however the
+//! record is split.
+
+use std::collections::VecDeque;
+
+use crate::error::{Error, Result};
+use crate::kernels::sum;
+
+/// Count, mean, variance and extremes of a series, updated block by block
+/// with Welford's algorithm. NaN values are counted as missing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunningStats {
+    /// Number of values seen, excluding NaN.
+    pub count: u64,
+    /// Number of NaN values seen.
+    pub missing: u64,
+    /// Mean of the values seen (NaN before the first).
+    pub mean: f64,
+    /// Sum of squared deviations from the mean.
+    pub m2: f64,
+    /// Smallest value seen (NaN before the first).
+    pub min: f64,
+    /// Largest value seen (NaN before the first).
+    pub max: f64,
+}
+
+impl Default for RunningStats {
+    fn default() -> Self {
+        RunningStats {
+            count: 0,
+            missing: 0,
+            mean: f64::NAN,
+            m2: 0.0,
+            min: f64::NAN,
+            max: f64::NAN,
+        }
+    }
+}
+
+impl RunningStats {
+    pub fn new() -> Self {
+        RunningStats::default()
+    }
+
+    /// Adds the values of `block`.
+    pub fn update(&mut self, block: &[f64]) {
+        for &v in block {
+            if v.is_nan() {
+                self.missing += 1;
+                continue;
+            }
+            self.count += 1;
+            if self.count == 1 {
+                (self.mean, self.min, self.max) = (v, v, v);
+                continue;
+            }
+            let delta = v - self.mean;
+            self.mean += delta / self.count as f64;
+            self.m2 += delta * (v - self.mean);
+            self.min = self.min.min(v);
+            self.max = self.max.max(v);
+        }
+    }
+
+    /// Sample variance (divisor `count - 1`); NaN for fewer than two values.
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            f64::NAN
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+
+    /// Sum of the values seen.
+    pub fn sum(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.mean * self.count as f64
+        }
+    }
+}
+
+/// Trailing moving mean over `window` steps. The first `window - 1` values
+/// of the record, and any window holding a NaN, give NaN.
+///
+/// The window sum is updated incrementally and recomputed from the window
+/// every `window` steps of the record, so rounding does not accumulate over
+/// long records; a window of zeros gives exactly zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovingMean {
+    pub window: usize,
+    /// Last `window` values of the record.
+    values: VecDeque<f64>,
+    /// Number of values seen.
+    seen: u64,
+    sum: f64,
+    missing: usize,
+    zeros: usize,
+}
+
+impl MovingMean {
+    pub fn new(window: usize) -> Result<Self> {
+        if window == 0 {
+            return Err(Error::InvalidParameter(
+                "The moving window must hold at least one step.".into(),
+            ));
+        }
+        Ok(MovingMean {
+            window,
+            values: VecDeque::with_capacity(window + 1),
+            seen: 0,
+            sum: 0.0,
+            missing: 0,
+            zeros: 0,
+        })
+    }
+
+    /// Rebuilds a moving mean from its window length, the last values of
+    /// the record (at most `window`), the number of values seen and the
+    /// running sum, as returned by [`MovingMean::values`],
+    /// [`MovingMean::seen`] and [`MovingMean::sum`].
+    pub fn from_state(window: usize, values: Vec<f64>, seen: u64, sum: f64) -> Result<Self> {
+        let mut mean = MovingMean::new(window)?;
+        if values.len() > window || (values.len() as u64) > seen {
+            return Err(Error::InvalidParameter(
+                "The saved window is longer than the window or the record.".into(),
+            ));
+        }
+        mean.values = values.into();
+        mean.seen = seen;
+        mean.recount();
+        // Restore the incremental sum exactly, so the output matches an
+        // uninterrupted run.
+        mean.sum = sum;
+        Ok(mean)
+    }
+
+    /// Last values of the record held in the window, oldest first.
+    pub fn values(&self) -> Vec<f64> {
+        self.values.iter().copied().collect()
+    }
+
+    /// Number of values seen.
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// Running sum of the values in the window, excluding NaN.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Means of the windows ending at each value of `block`.
+    pub fn process(&mut self, block: &[f64]) -> Vec<f64> {
+        block.iter().map(|&q| self.push(q)).collect()
+    }
+
+    fn push(&mut self, q: f64) -> f64 {
+        self.add(q, 1);
+        self.values.push_back(q);
+        if self.values.len() > self.window {
+            let old = self.values.pop_front().unwrap_or(0.0);
+            self.add(old, -1);
+        }
+        self.seen += 1;
+        if self.seen.is_multiple_of(self.window as u64) {
+            self.recount();
+        }
+        if self.values.len() < self.window || self.missing > 0 {
+            f64::NAN
+        } else if self.zeros == self.window {
+            0.0
+        } else {
+            self.sum / self.window as f64
+        }
+    }
+
+    fn add(&mut self, q: f64, sign: i8) {
+        if q.is_nan() {
+            self.missing = self.missing.wrapping_add_signed(sign.into());
+        } else {
+            self.sum += f64::from(sign) * q;
+            if q == 0.0 {
+                self.zeros = self.zeros.wrapping_add_signed(sign.into());
+            }
+        }
+    }
+
+    /// Recomputes the sum and counts from the values in the window.
+    fn recount(&mut self) {
+        let values = self.values.make_contiguous();
+        self.missing = values.iter().filter(|q| q.is_nan()).count();
+        self.zeros = values.iter().filter(|&&q| q == 0.0).count();
+        self.sum = if self.missing > 0 {
+            values.iter().filter(|q| !q.is_nan()).sum()
+        } else {
+            sum(values)
+        };
+    }
+}
+
+/// How the values of each group are combined by a [`Resampler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Mean,
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregation {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "mean" => Ok(Aggregation::Mean),
+            "sum" => Ok(Aggregation::Sum),
+            "min" => Ok(Aggregation::Min),
+            "max" => Ok(Aggregation::Max),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown aggregation `{name}`; expected `mean`, `sum`, `min` or `max`."
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aggregation::Mean => "mean",
+            Aggregation::Sum => "sum",
+            Aggregation::Min => "min",
+            Aggregation::Max => "max",
+        }
+    }
+
+    /// Combines `values`; NaN if any value is NaN.
+    fn apply(self, values: &[f64]) -> f64 {
+        if values.iter().any(|v| v.is_nan()) {
+            return f64::NAN;
+        }
+        match self {
+            Aggregation::Mean => sum(values) / values.len() as f64,
+            Aggregation::Sum => sum(values),
+            Aggregation::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregation::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Aggregates a series to a coarser step: every `factor` consecutive values
+/// become one. Values left over at the end of a block wait for the next.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resampler {
+    pub factor: usize,
+    pub aggregation: Aggregation,
+    /// Values of the incomplete group, fewer than `factor`.
+    pending: Vec<f64>,
+}
+
+impl Resampler {
+    pub fn new(factor: usize, aggregation: Aggregation) -> Result<Self> {
+        if factor == 0 {
+            return Err(Error::InvalidParameter(
+                "The resampling factor must be at least 1.".into(),
+            ));
+        }
+        Ok(Resampler {
+            factor,
+            aggregation,
+            pending: Vec::with_capacity(factor),
+        })
+    }
+
+    /// Rebuilds a resampler holding the values `pending` of an incomplete
+    /// group.
+    pub fn from_state(factor: usize, aggregation: Aggregation, pending: Vec<f64>) -> Result<Self> {
+        let mut resampler = Resampler::new(factor, aggregation)?;
+        if pending.len() >= factor {
+            return Err(Error::InvalidParameter(
+                "An incomplete group must hold fewer than `factor` values.".into(),
+            ));
+        }
+        resampler.pending = pending;
+        Ok(resampler)
+    }
+
+    /// Values of the incomplete group.
+    pub fn pending(&self) -> &[f64] {
+        &self.pending
+    }
+
+    /// Aggregates of the groups completed by `block`.
+    pub fn process(&mut self, block: &[f64]) -> Vec<f64> {
+        let mut out = Vec::with_capacity((self.pending.len() + block.len()) / self.factor);
+        let mut rest = block;
+        if !self.pending.is_empty() {
+            let take = (self.factor - self.pending.len()).min(rest.len());
+            self.pending.extend_from_slice(&rest[..take]);
+            rest = &rest[take..];
+            if self.pending.len() < self.factor {
+                return out;
+            }
+            out.push(self.aggregation.apply(&self.pending));
+            self.pending.clear();
+        }
+        let groups = rest.chunks_exact(self.factor);
+        self.pending.extend_from_slice(groups.remainder());
+        out.extend(groups.map(|group| self.aggregation.apply(group)));
+        out
+    }
+
+    /// Aggregate of the incomplete group at the end of the record, if any.
+    pub fn finish(&mut self) -> Option<f64> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let value = self.aggregation.apply(&self.pending);
+        self.pending.clear();
+        Some(value)
+    }
+}
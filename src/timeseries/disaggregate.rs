@@ -0,0 +1,151 @@
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+
+/// Method used to spread each coarse value over its sub-steps.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Disaggregation {
+    /// Monotone cubic (Fritsch–Carlson) interpolation of cumulative volume
+    /// through the period boundaries, differenced at the sub-step
+    /// boundaries. Sub-step flows are smooth, never negative for
+    /// non-negative input, and average exactly to each period's value.
+    Spline,
+    /// Method of fragments with the nearest-neighbour period of a fine
+    /// reference record (Nowak et al., 2010): the reference period whose
+    /// mean flow and those of its neighbours are closest to the target's
+    /// lends its within-period pattern.
+    Pattern(Vec<f64>),
+}
+
+fn check_series(series: &[f64], name: &str) -> Result<()> {
+    if series.iter().any(|q| !q.is_finite() || *q < 0.0) {
+        return Err(Error::Data(format!(
+            "The {name} series must be finite and not negative."
+        )));
+    }
+    Ok(())
+}
+
+/// Disaggregates `series` (mean flow of each period) into `steps` values
+/// per period whose mean equals the period's value.
+pub fn disaggregate_rs(series: &[f64], steps: usize, method: &Disaggregation) -> Result<Vec<f64>> {
+    if steps == 0 {
+        return Err(Error::InvalidParameter(
+            "The number of sub-steps per period must be positive.".into(),
+        ));
+    }
+    check_series(series, "input")?;
+    match method {
+        Disaggregation::Spline => Ok(spline(series, steps)),
+        Disaggregation::Pattern(reference) => pattern(series, steps, reference),
+    }
+}
+
+fn spline(series: &[f64], steps: usize) -> Vec<f64> {
+    let n = series.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    // Cumulative volume (in period-mean units) at the period boundaries.
+    let mut cumulative = Vec::with_capacity(n + 1);
+    cumulative.push(0.0);
+    for &q in series {
+        cumulative.push(cumulative[cumulative.len() - 1] + q);
+    }
+    // Fritsch–Carlson slopes: harmonic means of adjacent secants, zero at
+    // local extrema, one-sided at the ends.
+    let mut slope = vec![0.0; n + 1];
+    slope[0] = series[0];
+    slope[n] = series[n - 1];
+    for i in 1..n {
+        let (a, b) = (series[i - 1], series[i]);
+        slope[i] = if a > 0.0 && b > 0.0 {
+            2.0 * a * b / (a + b)
+        } else {
+            0.0
+        };
+    }
+    let hermite = |i: usize, t: f64| {
+        let (t2, t3) = (t * t, t * t * t);
+        (2.0 * t3 - 3.0 * t2 + 1.0) * cumulative[i]
+            + (t3 - 2.0 * t2 + t) * slope[i]
+            + (-2.0 * t3 + 3.0 * t2) * cumulative[i + 1]
+            + (t3 - t2) * slope[i + 1]
+    };
+    let mut out = Vec::with_capacity(n * steps);
+    for i in 0..n {
+        let mut previous = cumulative[i];
+        for k in 1..=steps {
+            let current = if k == steps {
+                cumulative[i + 1]
+            } else {
+                hermite(i, k as f64 / steps as f64)
+            };
+            out.push(((current - previous) * steps as f64).max(0.0));
+            previous = current;
+        }
+    }
+    out
+}
+
+fn pattern(series: &[f64], steps: usize, reference: &[f64]) -> Result<Vec<f64>> {
+    check_series(reference, "reference")?;
+    if reference.is_empty() || !reference.len().is_multiple_of(steps) {
+        return Err(Error::Data(format!(
+            "The reference record must cover whole periods of {steps} sub-steps."
+        )));
+    }
+    let means: Vec<f64> = reference
+        .chunks(steps)
+        .map(|c| c.iter().sum::<f64>() / steps as f64)
+        .collect();
+    let candidates: Vec<usize> = (0..means.len()).filter(|&j| means[j] > 0.0).collect();
+    if candidates.is_empty() {
+        return Err(Error::Data(
+            "The reference record has no flow to take patterns from.".into(),
+        ));
+    }
+    // Features: the period's mean and its neighbours', scaled by the spread
+    // of the reference means.
+    let features = |values: &[f64], i: usize| {
+        let last = values.len() - 1;
+        [
+            values[i.saturating_sub(1)],
+            values[i],
+            values[(i + 1).min(last)],
+        ]
+    };
+    let count = means.len() as f64;
+    let average = means.iter().sum::<f64>() / count;
+    let spread = (means.iter().map(|m| (m - average).powi(2)).sum::<f64>() / count)
+        .sqrt()
+        .max(f64::MIN_POSITIVE);
+    const WEIGHTS: [f64; 3] = [0.5, 1.0, 0.5];
+
+    let periods: Vec<usize> = (0..series.len()).collect();
+    let fragments = par_map(&periods, |&i| {
+        let q = series[i];
+        if q == 0.0 {
+            return vec![0.0; steps];
+        }
+        let target = features(series, i);
+        let distance = |j: usize| {
+            features(&means, j)
+                .iter()
+                .zip(&target)
+                .zip(WEIGHTS)
+                .map(|((x, y), w)| w * ((x - y) / spread).powi(2))
+                .sum::<f64>()
+        };
+        let nearest = candidates
+            .iter()
+            .map(|&j| (distance(j), j))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(candidates[0], |(_, j)| j);
+        let scale = q / means[nearest];
+        reference[nearest * steps..(nearest + 1) * steps]
+            .iter()
+            .map(|r| r * scale)
+            .collect::<Vec<f64>>()
+    });
+    Ok(fragments.concat())
+}
@@ -0,0 +1,130 @@
+use crate::error::{Error, Result};
+
+/// Double-mass curve of a series against a reference (e.g. the mean of
+/// neighbouring gauges), with a single break in slope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoubleMass {
+    /// Cumulative reference values, over steps where both are present.
+    pub cumulative_reference: Vec<f64>,
+    /// Cumulative series values over the same steps.
+    pub cumulative_series: Vec<f64>,
+    /// Index (into the cumulative curves) of the last point before the
+    /// break.
+    pub break_index: usize,
+    /// Slope of the curve up to the break.
+    pub slope_before: f64,
+    /// Slope of the curve after the break.
+    pub slope_after: f64,
+}
+
+impl DoubleMass {
+    /// Factor that brings the values after the break in line with those
+    /// before it.
+    pub fn adjustment(&self) -> f64 {
+        self.slope_before / self.slope_after
+    }
+}
+
+/// Sums of a suffix or prefix of the curve used by the least-squares fits.
+#[derive(Clone, Copy, Default)]
+struct Sums {
+    n: f64,
+    x: f64,
+    y: f64,
+    xx: f64,
+    yy: f64,
+    xy: f64,
+}
+
+impl Sums {
+    fn add(&mut self, x: f64, y: f64) {
+        self.n += 1.0;
+        self.x += x;
+        self.y += y;
+        self.xx += x * x;
+        self.yy += y * y;
+        self.xy += x * y;
+    }
+
+    fn minus(&self, other: &Sums) -> Sums {
+        Sums {
+            n: self.n - other.n,
+            x: self.x - other.x,
+            y: self.y - other.y,
+            xx: self.xx - other.xx,
+            yy: self.yy - other.yy,
+            xy: self.xy - other.xy,
+        }
+    }
+}
+
+/// Builds the double-mass curve of `series` against `reference`, skipping
+/// steps where either is NaN, and fits a continuous two-segment line
+/// through the origin with its break after `break_index`, or at the index
+/// that minimises the squared error if `None`.
+pub fn double_mass_rs(
+    series: &[f64],
+    reference: &[f64],
+    break_index: Option<usize>,
+) -> Result<DoubleMass> {
+    if series.len() != reference.len() {
+        return Err(Error::Data(
+            "The series and the reference must have the same length.".into(),
+        ));
+    }
+    let (mut x, mut y) = (Vec::new(), Vec::new());
+    let (mut sx, mut sy) = (0.0, 0.0);
+    for (&s, &r) in series.iter().zip(reference) {
+        if s.is_nan() || r.is_nan() {
+            continue;
+        }
+        sx += r;
+        sy += s;
+        x.push(sx);
+        y.push(sy);
+    }
+    let n = x.len();
+    if n < 4 {
+        return Err(Error::Data(
+            "At least four steps with both values are required.".into(),
+        ));
+    }
+    let mut prefix = vec![Sums::default(); n + 1];
+    for i in 0..n {
+        prefix[i + 1] = prefix[i];
+        prefix[i + 1].add(x[i], y[i]);
+    }
+    // Fit with the break after point k: y = a x up to k, then
+    // y = a x_k + b (x - x_k).
+    let fit = |k: usize| {
+        let head = prefix[k + 1];
+        let a = head.xy / head.xx;
+        let sse_head = head.yy - head.xy * a;
+        let tail = prefix[n].minus(&head);
+        let (xk, yk) = (x[k], a * x[k]);
+        let uu = tail.xx - 2.0 * xk * tail.x + tail.n * xk * xk;
+        let uv = tail.xy - yk * tail.x - xk * tail.y + tail.n * xk * yk;
+        let vv = tail.yy - 2.0 * yk * tail.y + tail.n * yk * yk;
+        let b = uv / uu;
+        (a, b, sse_head + vv - uv * b)
+    };
+    let k = match break_index {
+        Some(k) if k >= 1 && k + 2 < n => k,
+        Some(k) => {
+            return Err(Error::InvalidParameter(format!(
+                "Break index {k} must leave at least two points on each side."
+            )))
+        }
+        None => (1..n - 2)
+            .min_by(|&i, &j| fit(i).2.total_cmp(&fit(j).2))
+            .unwrap_or(1),
+    };
+    let (slope_before, slope_after, _) = fit(k);
+    Ok(DoubleMass {
+        cumulative_reference: x,
+        cumulative_series: y,
+        break_index: k,
+        slope_before,
+        slope_after,
+    })
+}
@@ -0,0 +1,14 @@
+//! Consistency and change detection for long gauge records (double-mass
+//! curves and Mann–Kendall / Sen's slope trend tests), disaggregation of
+//! daily flows to sub-daily steps, spectral and wavelet analysis, rolling
+//! window statistics, and block-by-block statistics of records too long to
+//! hold in memory.
+
+pub mod chunked;
+pub mod disaggregate;
+pub mod double_mass;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod rolling;
+pub mod spectral;
+pub mod trend;
@@ -0,0 +1,453 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDelta, PyDict, PyType};
+
+use super::chunked::{Aggregation, MovingMean, Resampler, RunningStats};
+use super::disaggregate::{disaggregate_rs, Disaggregation};
+use super::double_mass::double_mass_rs;
+use super::rolling::{rolling_many_rs, rolling_rs, Rolling};
+use super::spectral::{morlet_wavelet_rs, periodogram_rs, Window};
+use super::trend::{mann_kendall_rs, sens_slope_rs};
+use crate::arrow::{grid_array, FloatSeries};
+use crate::time::timedelta_to_seconds;
+
+/// Seconds per day; spectra are reported in cycles per day.
+const DAY: f64 = 86_400.0;
+
+#[pyfunction]
+#[pyo3(signature = (series, steps=24, method="spline", reference=None))]
+pub fn disaggregate(
+    py: Python<'_>,
+    series: FloatSeries,
+    steps: usize,
+    method: &str,
+    reference: Option<FloatSeries>,
+) -> PyResult<Vec<f64>> {
+    let method = match (method.to_ascii_lowercase().as_str(), reference) {
+        ("spline", _) => Disaggregation::Spline,
+        ("pattern", Some(reference)) => Disaggregation::Pattern(reference.0),
+        ("pattern", None) => {
+            return Err(PyValueError::new_err(
+                "Method `pattern` requires a `reference` record.",
+            ))
+        }
+        (other, _) => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown disaggregation method `{other}`; expected `spline` or `pattern`."
+            )))
+        }
+    };
+    Ok(py.allow_threads(|| disaggregate_rs(&series.0, steps, &method))?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (series, reference, break_index=None))]
+pub fn double_mass<'py>(
+    py: Python<'py>,
+    series: FloatSeries,
+    reference: FloatSeries,
+    break_index: Option<usize>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let curve = py.allow_threads(|| double_mass_rs(&series.0, &reference.0, break_index))?;
+    let result = PyDict::new(py);
+    result.set_item("cumulative_reference", &curve.cumulative_reference)?;
+    result.set_item("cumulative_series", &curve.cumulative_series)?;
+    result.set_item("break_index", curve.break_index)?;
+    result.set_item("slope_before", curve.slope_before)?;
+    result.set_item("slope_after", curve.slope_after)?;
+    result.set_item("adjustment", curve.adjustment())?;
+    Ok(result)
+}
+
+#[pyfunction]
+pub fn mann_kendall<'py>(py: Python<'py>, series: FloatSeries) -> PyResult<Bound<'py, PyDict>> {
+    let test = py.allow_threads(|| mann_kendall_rs(&series.0))?;
+    let result = PyDict::new(py);
+    result.set_item("s", test.s)?;
+    result.set_item("variance", test.variance)?;
+    result.set_item("z", test.z)?;
+    result.set_item("p_value", test.p_value)?;
+    result.set_item("tau", test.tau)?;
+    Ok(result)
+}
+
+#[pyfunction]
+#[pyo3(signature = (series, confidence=0.95))]
+pub fn sens_slope<'py>(
+    py: Python<'py>,
+    series: FloatSeries,
+    confidence: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let estimate = py.allow_threads(|| sens_slope_rs(&series.0, confidence))?;
+    let result = PyDict::new(py);
+    result.set_item("slope", estimate.slope)?;
+    result.set_item("intercept", estimate.intercept)?;
+    result.set_item("lower", estimate.lower)?;
+    result.set_item("upper", estimate.upper)?;
+    Ok(result)
+}
+
+/// Count, mean, variance and extremes of a record fed block by block.
+/// NaN values are counted as missing.
+#[pyclass(name = "RunningStats", module = "rustflow.timeseries")]
+pub struct PyRunningStats {
+    inner: RunningStats,
+}
+
+#[pymethods]
+impl PyRunningStats {
+    #[new]
+    fn new() -> Self {
+        PyRunningStats {
+            inner: RunningStats::new(),
+        }
+    }
+
+    /// Adds the values of the next block.
+    ///
+    /// Args:
+    ///     block (Sequence[float]): Consecutive values of the record.
+    fn update(&mut self, py: Python<'_>, block: FloatSeries) {
+        py.allow_threads(|| self.inner.update(&block.0))
+    }
+
+    /// int: Number of values seen, excluding NaN.
+    #[getter]
+    fn count(&self) -> u64 {
+        self.inner.count
+    }
+
+    /// int: Number of NaN values seen.
+    #[getter]
+    fn missing(&self) -> u64 {
+        self.inner.missing
+    }
+
+    /// float: Mean of the values seen; NaN before the first.
+    #[getter]
+    fn mean(&self) -> f64 {
+        self.inner.mean
+    }
+
+    /// float: Sample variance; NaN for fewer than two values.
+    #[getter]
+    fn variance(&self) -> f64 {
+        self.inner.variance()
+    }
+
+    /// float: Sample standard deviation; NaN for fewer than two values.
+    #[getter]
+    fn std(&self) -> f64 {
+        self.inner.variance().sqrt()
+    }
+
+    /// float: Sum of the values seen.
+    #[getter]
+    fn sum(&self) -> f64 {
+        self.inner.sum()
+    }
+
+    /// float: Smallest value seen; NaN before the first.
+    #[getter]
+    fn min(&self) -> f64 {
+        self.inner.min
+    }
+
+    /// float: Largest value seen; NaN before the first.
+    #[getter]
+    fn max(&self) -> f64 {
+        self.inner.max
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RunningStats(count={}, mean={:.6}, min={:.6}, max={:.6})",
+            self.inner.count, self.inner.mean, self.inner.min, self.inner.max
+        )
+    }
+
+    /// Pickles the accumulated statistics.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (u64, u64, f64, f64, f64, f64))> {
+        let s = &slf.borrow().inner;
+        Ok((
+            slf.get_type().getattr("_from_state")?,
+            (s.count, s.missing, s.mean, s.m2, s.min, s.max),
+        ))
+    }
+
+    #[classmethod]
+    fn _from_state(
+        _cls: &Bound<'_, PyType>,
+        count: u64,
+        missing: u64,
+        mean: f64,
+        m2: f64,
+        min: f64,
+        max: f64,
+    ) -> Self {
+        PyRunningStats {
+            inner: RunningStats {
+                count,
+                missing,
+                mean,
+                m2,
+                min,
+                max,
+            },
+        }
+    }
+}
+
+/// Trailing moving mean of a record fed block by block. The window carries
+/// over between blocks, so the output does not depend on how the record is
+/// split. The first `window - 1` values of the record, and windows holding
+/// a NaN, give NaN.
+///
+/// Args:
+///     window (int): Number of steps averaged, at least 1.
+#[pyclass(name = "MovingMean", module = "rustflow.timeseries")]
+pub struct PyMovingMean {
+    inner: MovingMean,
+}
+
+#[pymethods]
+impl PyMovingMean {
+    #[new]
+    fn new(window: usize) -> PyResult<Self> {
+        Ok(PyMovingMean {
+            inner: MovingMean::new(window)?,
+        })
+    }
+
+    /// Moving means ending at each value of the next block.
+    ///
+    /// Args:
+    ///     block (Sequence[float]): Consecutive values of the record.
+    ///
+    /// Returns:
+    ///     list[float]: One mean per value of `block`.
+    fn process(&mut self, py: Python<'_>, block: FloatSeries) -> Vec<f64> {
+        py.allow_threads(|| self.inner.process(&block.0))
+    }
+
+    /// int: Number of steps averaged.
+    #[getter]
+    fn window(&self) -> usize {
+        self.inner.window
+    }
+
+    fn __repr__(&self) -> String {
+        format!("MovingMean(window={})", self.inner.window)
+    }
+
+    /// Pickles the window length, the values in the window, the number of
+    /// values seen and the running sum.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (usize, Vec<f64>, u64, f64))> {
+        let m = &slf.borrow().inner;
+        Ok((
+            slf.get_type().getattr("_from_state")?,
+            (m.window, m.values(), m.seen(), m.sum()),
+        ))
+    }
+
+    #[classmethod]
+    fn _from_state(
+        _cls: &Bound<'_, PyType>,
+        window: usize,
+        values: Vec<f64>,
+        seen: u64,
+        sum: f64,
+    ) -> PyResult<Self> {
+        Ok(PyMovingMean {
+            inner: MovingMean::from_state(window, values, seen, sum)?,
+        })
+    }
+}
+
+/// Aggregates a record fed block by block to a coarser step, combining
+/// every `factor` consecutive values into one. Values left over at the end
+/// of a block wait for the next; `finish` aggregates the final incomplete
+/// group.
+///
+/// Args:
+///     factor (int): Number of values per aggregated value, e.g. 60 for
+///         minutes to hours.
+///     how (Literal["mean", "sum", "min", "max"], optional): How values are
+///         combined. Defaults to `"mean"`.
+#[pyclass(name = "Resampler", module = "rustflow.timeseries")]
+pub struct PyResampler {
+    inner: Resampler,
+}
+
+#[pymethods]
+impl PyResampler {
+    #[new]
+    #[pyo3(signature = (factor, how="mean"))]
+    fn new(factor: usize, how: &str) -> PyResult<Self> {
+        Ok(PyResampler {
+            inner: Resampler::new(factor, Aggregation::parse(how)?)?,
+        })
+    }
+
+    /// Aggregates of the groups completed by the next block.
+    ///
+    /// Args:
+    ///     block (Sequence[float]): Consecutive values of the record.
+    ///
+    /// Returns:
+    ///     list[float]: One value per completed group; NaN for a group
+    ///         holding a NaN.
+    fn process(&mut self, py: Python<'_>, block: FloatSeries) -> Vec<f64> {
+        py.allow_threads(|| self.inner.process(&block.0))
+    }
+
+    /// Aggregate of the incomplete group at the end of the record.
+    ///
+    /// Returns:
+    ///     Optional[float]: The aggregate, or None if no values are pending.
+    fn finish(&mut self) -> Option<f64> {
+        self.inner.finish()
+    }
+
+    /// int: Number of values per aggregated value.
+    #[getter]
+    fn factor(&self) -> usize {
+        self.inner.factor
+    }
+
+    /// Literal["mean", "sum", "min", "max"]: How values are combined.
+    #[getter]
+    fn how(&self) -> &'static str {
+        self.inner.aggregation.name()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Resampler(factor={}, how='{}')",
+            self.inner.factor,
+            self.inner.aggregation.name()
+        )
+    }
+
+    /// Pickles the factor, aggregation and values of the incomplete group.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (usize, &'static str, Vec<f64>))> {
+        let r = &slf.borrow().inner;
+        Ok((
+            slf.get_type().getattr("_from_state")?,
+            (r.factor, r.aggregation.name(), r.pending().to_vec()),
+        ))
+    }
+
+    #[classmethod]
+    fn _from_state(
+        _cls: &Bound<'_, PyType>,
+        factor: usize,
+        how: &str,
+        pending: Vec<f64>,
+    ) -> PyResult<Self> {
+        Ok(PyResampler {
+            inner: Resampler::from_state(factor, Aggregation::parse(how)?, pending)?,
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (series, time_step, segment_length=None, window="hann"))]
+pub fn periodogram<'py>(
+    py: Python<'py>,
+    series: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    segment_length: Option<usize>,
+    window: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dt = timedelta_to_seconds(&time_step);
+    let window = Window::parse(window)?;
+    let result = py.allow_threads(|| periodogram_rs(&series.0, dt, segment_length, window))?;
+    let dict = PyDict::new(py);
+    let frequency: Vec<f64> = result.frequency.iter().map(|f| f * DAY).collect();
+    let period: Vec<f64> = frequency.iter().map(|f| 1.0 / f).collect();
+    let power: Vec<f64> = result.power.iter().map(|p| p / DAY).collect();
+    dict.set_item("frequency", frequency)?;
+    dict.set_item("period", period)?;
+    dict.set_item("power", power)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (series, time_step, voices=8, min_period=None, max_period=None))]
+pub fn wavelet<'py>(
+    py: Python<'py>,
+    series: FloatSeries,
+    time_step: Bound<'py, PyDelta>,
+    voices: usize,
+    min_period: Option<Bound<'py, PyDelta>>,
+    max_period: Option<Bound<'py, PyDelta>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dt = timedelta_to_seconds(&time_step);
+    let min_period = min_period.map(|p| timedelta_to_seconds(&p));
+    let max_period = max_period.map(|p| timedelta_to_seconds(&p));
+    let result =
+        py.allow_threads(|| morlet_wavelet_rs(&series.0, dt, voices, min_period, max_period))?;
+    let dict = PyDict::new(py);
+    let days = |values: &[f64]| values.iter().map(|v| v / DAY).collect::<Vec<f64>>();
+    dict.set_item("period", days(&result.period))?;
+    dict.set_item(
+        "power",
+        grid_array(
+            py,
+            &result.power.concat(),
+            result.power.len(),
+            series.0.len(),
+        )?,
+    )?;
+    dict.set_item("global_power", result.global)?;
+    dict.set_item("cone_of_influence", days(&result.cone_of_influence))?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (series, window, statistic="mean", quantile=0.5, min_values=None, center=false))]
+pub fn rolling<'py>(
+    py: Python<'py>,
+    series: FloatSeries,
+    window: Bound<'py, PyAny>,
+    statistic: &str,
+    quantile: f64,
+    min_values: Option<usize>,
+    center: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let statistic = Rolling::parse(statistic, quantile)?;
+    if let Ok(window) = window.extract::<usize>() {
+        let values =
+            py.allow_threads(|| rolling_rs(&series.0, window, statistic, min_values, center))?;
+        return values.into_pyobject(py).map(|v| v.into_any());
+    }
+    let windows: Vec<usize> = window.extract()?;
+    let values =
+        py.allow_threads(|| rolling_many_rs(&series.0, &windows, statistic, min_values, center))?;
+    values.into_pyobject(py).map(|v| v.into_any())
+}
+
+#[pymodule]
+pub fn init_timeseries(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(disaggregate, m)?)?;
+    m.add_function(wrap_pyfunction!(double_mass, m)?)?;
+    m.add_function(wrap_pyfunction!(mann_kendall, m)?)?;
+    m.add_function(wrap_pyfunction!(periodogram, m)?)?;
+    m.add_function(wrap_pyfunction!(rolling, m)?)?;
+    m.add_function(wrap_pyfunction!(sens_slope, m)?)?;
+    m.add_function(wrap_pyfunction!(wavelet, m)?)?;
+    m.add_class::<PyMovingMean>()?;
+    m.add_class::<PyResampler>()?;
+    m.add_class::<PyRunningStats>()?;
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! Rolling-window statistics over long series: sums, means, standard
+//! deviations, extremes and quantiles of every window in a single pass.
+//!
+//! Extremes use a monotonic queue and quantiles a sorted window, so each
+//! step costs O(1) and O(window) respectively rather than a sort of the
+//! whole window. NaN values are skipped; a window with fewer than
+//! `min_values` valid values gives NaN.
+
+use std::collections::VecDeque;
+
+use crate::ensemble::quantile_sorted;
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+
+/// Statistic of each window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rolling {
+    Sum,
+    Mean,
+    /// Sample standard deviation.
+    Std,
+    Min,
+    Max,
+    /// Quantile at a probability in [0, 1], interpolated linearly.
+    Quantile(f64),
+}
+
+impl Rolling {
+    /// Parses `sum`, `mean`, `std`, `min`, `max`, `median` or `quantile`
+    /// (at `probability`).
+    pub fn parse(name: &str, probability: f64) -> Result<Self> {
+        let statistic = match name.to_ascii_lowercase().as_str() {
+            "sum" => Rolling::Sum,
+            "mean" => Rolling::Mean,
+            "std" => Rolling::Std,
+            "min" => Rolling::Min,
+            "max" => Rolling::Max,
+            "median" => Rolling::Quantile(0.5),
+            "quantile" | "percentile" => Rolling::Quantile(probability),
+            _ => {
+                return Err(Error::InvalidParameter(format!(
+                    "Unknown rolling statistic `{name}`; expected `sum`, `mean`, `std`, `min`, \
+                     `max`, `median` or `quantile`."
+                )))
+            }
+        };
+        if let Rolling::Quantile(p) = statistic {
+            if !(0.0..=1.0).contains(&p) {
+                return Err(Error::InvalidParameter(format!(
+                    "The quantile probability {p} is outside [0, 1]."
+                )));
+            }
+        }
+        Ok(statistic)
+    }
+}
+
+/// `statistic` of the window of `window` values ending at each value of
+/// `series` (or centred on it with `center`, the extra value of an even
+/// window falling before it). Windows overhanging the ends, or with fewer
+/// than `min_values` valid values (default `window`), give NaN.
+pub fn rolling_rs(
+    series: &[f64],
+    window: usize,
+    statistic: Rolling,
+    min_values: Option<usize>,
+    center: bool,
+) -> Result<Vec<f64>> {
+    if window == 0 {
+        return Err(Error::InvalidParameter(
+            "The rolling window must hold at least one step.".into(),
+        ));
+    }
+    let min_values = min_values.unwrap_or(window).clamp(1, window);
+    let mut values = match statistic {
+        Rolling::Sum | Rolling::Mean | Rolling::Std => moments(series, window, statistic),
+        Rolling::Min => extremes(series, window, |new, old| new <= old),
+        Rolling::Max => extremes(series, window, |new, old| new >= old),
+        Rolling::Quantile(p) => quantiles(series, window, p),
+    };
+    let mut valid = 0;
+    for t in 0..series.len() {
+        valid += usize::from(!series[t].is_nan());
+        if t >= window {
+            valid -= usize::from(!series[t - window].is_nan());
+        }
+        let complete = t + 1 >= window;
+        let enough = valid >= min_values && !(statistic == Rolling::Std && valid < 2);
+        if !(complete && enough) {
+            values[t] = f64::NAN;
+        }
+    }
+    if center {
+        // Shift so each window is labelled by its middle value.
+        let shift = window / 2;
+        values.drain(..shift.min(values.len()));
+        values.resize(series.len(), f64::NAN);
+    }
+    Ok(values)
+}
+
+/// Rolling `statistic` for each of `windows`, computed in parallel.
+pub fn rolling_many_rs(
+    series: &[f64],
+    windows: &[usize],
+    statistic: Rolling,
+    min_values: Option<usize>,
+    center: bool,
+) -> Result<Vec<Vec<f64>>> {
+    par_map(windows, |&window| {
+        rolling_rs(series, window, statistic, min_values, center)
+    })
+    .into_iter()
+    .collect()
+}
+
+/// Sums, means or standard deviations from running sums of the values and
+/// their squares, taken about the first valid value to limit cancellation
+/// and recomputed from the window every `window` steps so that rounding
+/// does not accumulate. A window of zeros gives exactly zero.
+fn moments(series: &[f64], window: usize, statistic: Rolling) -> Vec<f64> {
+    let shift = series.iter().copied().find(|v| !v.is_nan()).unwrap_or(0.0);
+    let (mut sum, mut squares, mut count, mut nonzero) = (0.0, 0.0, 0usize, 0usize);
+    let mut out = Vec::with_capacity(series.len());
+    for t in 0..series.len() {
+        if (t + 1).is_multiple_of(window) {
+            let valid: Vec<f64> = series[t + 1 - window..=t]
+                .iter()
+                .copied()
+                .filter(|v| !v.is_nan())
+                .collect();
+            sum = valid.iter().map(|v| v - shift).sum();
+            squares = valid.iter().map(|v| (v - shift).powi(2)).sum();
+            count = valid.len();
+            nonzero = valid.iter().filter(|&&v| v != 0.0).count();
+        } else {
+            let q = series[t];
+            if !q.is_nan() {
+                sum += q - shift;
+                squares += (q - shift).powi(2);
+                count += 1;
+                nonzero += usize::from(q != 0.0);
+            }
+            if t >= window {
+                let old = series[t - window];
+                if !old.is_nan() {
+                    sum -= old - shift;
+                    squares -= (old - shift).powi(2);
+                    count -= 1;
+                    nonzero -= usize::from(old != 0.0);
+                }
+            }
+        }
+        let n = count as f64;
+        out.push(match statistic {
+            _ if nonzero == 0 => 0.0,
+            Rolling::Sum => sum + n * shift,
+            Rolling::Mean => sum / n + shift,
+            _ => ((squares - sum * sum / n).max(0.0) / (n - 1.0)).sqrt(),
+        });
+    }
+    out
+}
+
+/// Window extremes with a monotonic queue of indices; `dominates(new, old)`
+/// is true when `new` makes `old` irrelevant.
+fn extremes(series: &[f64], window: usize, dominates: impl Fn(f64, f64) -> bool) -> Vec<f64> {
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    let mut out = Vec::with_capacity(series.len());
+    for (t, &q) in series.iter().enumerate() {
+        if !q.is_nan() {
+            while queue.back().is_some_and(|&i| dominates(q, series[i])) {
+                queue.pop_back();
+            }
+            queue.push_back(t);
+        }
+        while queue.front().is_some_and(|&i| i + window <= t) {
+            queue.pop_front();
+        }
+        out.push(queue.front().map_or(f64::NAN, |&i| series[i]));
+    }
+    out
+}
+
+/// Window quantiles from a sorted copy of the window updated by binary
+/// search.
+fn quantiles(series: &[f64], window: usize, p: f64) -> Vec<f64> {
+    let mut sorted: Vec<f64> = Vec::with_capacity(window);
+    let mut out = Vec::with_capacity(series.len());
+    for (t, &q) in series.iter().enumerate() {
+        if !q.is_nan() {
+            let at = sorted.partition_point(|v| v.total_cmp(&q).is_lt());
+            sorted.insert(at, q);
+        }
+        if t >= window {
+            let old = series[t - window];
+            if !old.is_nan() {
+                let at = sorted.partition_point(|v| v.total_cmp(&old).is_lt());
+                sorted.remove(at);
+            }
+        }
+        out.push(quantile_sorted(&sorted, p));
+    }
+    out
+}
@@ -0,0 +1,450 @@
+//! Spectral analysis of long flow records: FFT periodograms (with Welch
+//! averaging) for periodic signals such as diurnal and seasonal cycles, and
+//! the continuous Morlet wavelet transform (Torrence and Compo, 1998) for
+//! signals whose strength changes over time.
+
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+use crate::error::{Error, Result};
+use crate::parallel::par_map;
+
+/// Non-dimensional frequency of the Morlet wavelet.
+const MORLET_OMEGA: f64 = 6.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    fn from_angle(angle: f64) -> Self {
+        Complex::new(angle.cos(), angle.sin())
+    }
+
+    fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    fn scale(self, factor: f64) -> Self {
+        Complex::new(self.re * factor, self.im * factor)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+/// In-place iterative radix-2 FFT of a power-of-two length. The inverse is
+/// not scaled.
+fn radix2(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+    while length <= n {
+        let step = Complex::from_angle(sign * 2.0 * PI / length as f64);
+        for start in (0..n).step_by(length) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..length / 2 {
+                let even = data[start + k];
+                let odd = data[start + k + length / 2] * w;
+                data[start + k] = even + odd;
+                data[start + k + length / 2] = even - odd;
+                w = w * step;
+            }
+        }
+        length <<= 1;
+    }
+}
+
+/// Forward DFT of any length: radix-2 for powers of two, otherwise
+/// Bluestein's chirp-z algorithm.
+fn fft(values: &[Complex]) -> Vec<Complex> {
+    let n = values.len();
+    if n.is_power_of_two() || n == 0 {
+        let mut data = values.to_vec();
+        radix2(&mut data, false);
+        return data;
+    }
+    let m = (2 * n - 1).next_power_of_two();
+    // The chirp angle uses k² mod 2n to keep it accurate for long series.
+    let chirp: Vec<Complex> = (0..n)
+        .map(|k| {
+            let k2 = (k as u128 * k as u128 % (2 * n as u128)) as f64;
+            Complex::from_angle(-PI * k2 / n as f64)
+        })
+        .collect();
+    let mut a = vec![Complex::default(); m];
+    for k in 0..n {
+        a[k] = values[k] * chirp[k];
+    }
+    let mut b = vec![Complex::default(); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (x, y) in a.iter_mut().zip(&b) {
+        *x = *x * *y;
+    }
+    radix2(&mut a, true);
+    (0..n)
+        .map(|k| a[k].scale(1.0 / m as f64) * chirp[k])
+        .collect()
+}
+
+/// Taper applied to each segment before its transform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+}
+
+impl Window {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rectangular" | "boxcar" | "none" => Ok(Window::Rectangular),
+            "hann" | "hanning" => Ok(Window::Hann),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unknown window `{name}`; expected `hann` or `rectangular`."
+            ))),
+        }
+    }
+
+    fn weights(self, n: usize) -> Vec<f64> {
+        match self {
+            Window::Rectangular => vec![1.0; n],
+            Window::Hann => (0..n)
+                .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / n as f64).cos())
+                .collect(),
+        }
+    }
+}
+
+/// One-sided power spectral density.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Periodogram {
+    /// Frequencies (cycles per second), from the lowest resolved to the
+    /// Nyquist frequency.
+    pub frequency: Vec<f64>,
+    /// Power density (squared series units per cycle per second).
+    pub power: Vec<f64>,
+}
+
+fn check_series(series: &[f64], dt: f64, minimum: usize) -> Result<()> {
+    if series.len() < minimum {
+        return Err(Error::Data(format!(
+            "Spectral analysis needs at least {minimum} values."
+        )));
+    }
+    if series.iter().any(|v| !v.is_finite()) {
+        return Err(Error::Data(
+            "Spectral analysis needs a gap-free series; fill the gaps first.".into(),
+        ));
+    }
+    if dt.is_nan() || dt <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "The time step must be positive.".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Periodogram of `series` sampled every `dt` seconds. With `segment`, the
+/// series is split into segments of that length overlapping by half and
+/// their periodograms are averaged (Welch's method), trading frequency
+/// resolution for a steadier estimate. Each segment has its mean removed
+/// and is tapered by `window`.
+pub fn periodogram_rs(
+    series: &[f64],
+    dt: f64,
+    segment: Option<usize>,
+    window: Window,
+) -> Result<Periodogram> {
+    check_series(series, dt, 4)?;
+    let length = segment.unwrap_or(series.len());
+    if length < 4 || length > series.len() {
+        return Err(Error::InvalidParameter(format!(
+            "The segment length must be between 4 and the series length ({}).",
+            series.len()
+        )));
+    }
+    let hop = (length / 2).max(1);
+    let starts: Vec<usize> = (0..=series.len() - length).step_by(hop).collect();
+    let weights = window.weights(length);
+    let energy: f64 = weights.iter().map(|w| w * w).sum();
+    let bins = length / 2;
+    let spectra = par_map(&starts, |&start| {
+        let values = &series[start..start + length];
+        let mean = values.iter().sum::<f64>() / length as f64;
+        let tapered: Vec<Complex> = values
+            .iter()
+            .zip(&weights)
+            .map(|(v, w)| Complex::new((v - mean) * w, 0.0))
+            .collect();
+        let transform = fft(&tapered);
+        (1..=bins)
+            .map(|k| {
+                // Every bin but the Nyquist one folds in its negative
+                // frequency.
+                let fold = if 2 * k == length { 1.0 } else { 2.0 };
+                fold * transform[k].norm_sqr() * dt / energy
+            })
+            .collect::<Vec<f64>>()
+    });
+    let mut power = vec![0.0; bins];
+    for spectrum in &spectra {
+        for (p, s) in power.iter_mut().zip(spectrum) {
+            *p += s / spectra.len() as f64;
+        }
+    }
+    Ok(Periodogram {
+        frequency: (1..=bins)
+            .map(|k| k as f64 / (length as f64 * dt))
+            .collect(),
+        power,
+    })
+}
+
+/// Continuous Morlet wavelet transform.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaveletTransform {
+    /// Equivalent Fourier period (s) of each scale, increasing.
+    pub period: Vec<f64>,
+    /// Wavelet power `|W|²` (squared series units), `[scale][step]`.
+    pub power: Vec<Vec<f64>>,
+    /// Longest period (s) at each step unaffected by the ends of the
+    /// series; power at longer periods is unreliable.
+    pub cone_of_influence: Vec<f64>,
+    /// Time-averaged power at each scale.
+    pub global: Vec<f64>,
+}
+
+/// Morlet wavelet transform of `series` sampled every `dt` seconds, at
+/// `voices` scales per octave between `min_period` (default two steps) and
+/// `max_period` (default the record length), computed scale by scale in
+/// parallel through the FFT with the series padded with zeros.
+pub fn morlet_wavelet_rs(
+    series: &[f64],
+    dt: f64,
+    voices: usize,
+    min_period: Option<f64>,
+    max_period: Option<f64>,
+) -> Result<WaveletTransform> {
+    check_series(series, dt, 4)?;
+    if voices == 0 {
+        return Err(Error::InvalidParameter(
+            "At least one voice per octave is needed.".into(),
+        ));
+    }
+    let n = series.len();
+    let fourier_factor = 4.0 * PI / (MORLET_OMEGA + (2.0 + MORLET_OMEGA * MORLET_OMEGA).sqrt());
+    let min_period = min_period.unwrap_or(2.0 * dt);
+    let max_period = max_period.unwrap_or(n as f64 * dt);
+    if min_period.is_nan() || min_period <= 0.0 || max_period.is_nan() || max_period < min_period {
+        return Err(Error::InvalidParameter(
+            "The periods must be positive with `min_period` no longer than `max_period`.".into(),
+        ));
+    }
+    let octaves = (max_period / min_period).log2();
+    let scales: Vec<f64> = (0..=(octaves * voices as f64).floor() as usize)
+        .map(|j| min_period / fourier_factor * 2f64.powf(j as f64 / voices as f64))
+        .collect();
+
+    let mean = series.iter().sum::<f64>() / n as f64;
+    let padded_length = (2 * n - 1).next_power_of_two();
+    let mut padded = vec![Complex::default(); padded_length];
+    for (p, v) in padded.iter_mut().zip(series) {
+        *p = Complex::new(v - mean, 0.0);
+    }
+    radix2(&mut padded, false);
+    let angular: Vec<f64> = (0..padded_length)
+        .map(|k| {
+            let k = if k <= padded_length / 2 {
+                k as f64
+            } else {
+                k as f64 - padded_length as f64
+            };
+            2.0 * PI * k / (padded_length as f64 * dt)
+        })
+        .collect();
+    let power = par_map(&scales, |&scale| {
+        let norm = (2.0 * PI * scale / dt).sqrt() * PI.powf(-0.25);
+        let mut product: Vec<Complex> = padded
+            .iter()
+            .zip(&angular)
+            .map(|(x, &w)| {
+                if w > 0.0 {
+                    x.scale(norm * (-0.5 * (scale * w - MORLET_OMEGA).powi(2)).exp())
+                } else {
+                    Complex::default()
+                }
+            })
+            .collect();
+        radix2(&mut product, true);
+        product[..n]
+            .iter()
+            .map(|c| c.norm_sqr() / (padded_length * padded_length) as f64)
+            .collect::<Vec<f64>>()
+    });
+    let global = power
+        .iter()
+        .map(|row| row.iter().sum::<f64>() / n as f64)
+        .collect();
+    let cone_of_influence = (0..n)
+        .map(|i| fourier_factor / 2f64.sqrt() * dt * (i + 1).min(n - i) as f64)
+        .collect();
+    Ok(WaveletTransform {
+        period: scales.iter().map(|s| s * fourier_factor).collect(),
+        power,
+        cone_of_influence,
+        global,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 3600.0;
+
+    /// A daily cycle of amplitude 3 about 10, sampled hourly for `days`.
+    fn daily_cycle(days: usize) -> Vec<f64> {
+        (0..24 * days)
+            .map(|t| 10.0 + 3.0 * (2.0 * PI * t as f64 / 24.0).sin())
+            .collect()
+    }
+
+    fn peak(values: &[f64]) -> usize {
+        (0..values.len())
+            .max_by(|&a, &b| values[a].total_cmp(&values[b]))
+            .unwrap()
+    }
+
+    #[test]
+    fn ffts_match_the_direct_transform() {
+        for n in [1, 7, 8, 12, 30] {
+            let values: Vec<Complex> = (0..n)
+                .map(|t| Complex::new((t as f64 * 0.7).sin(), (t as f64).cos() - 0.2))
+                .collect();
+            let transform = fft(&values);
+            for (k, x) in transform.iter().enumerate() {
+                let direct = values
+                    .iter()
+                    .enumerate()
+                    .map(|(t, v)| *v * Complex::from_angle(-2.0 * PI * (k * t) as f64 / n as f64))
+                    .fold(Complex::default(), |a, b| a + b);
+                assert!((*x - direct).norm_sqr().sqrt() < 1e-12, "{n} {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn sinusoids_peak_at_their_period() {
+        // 45 days is not a power of two, so this also goes through the
+        // chirp-z transform.
+        let series = daily_cycle(45);
+        for window in [Window::Rectangular, Window::Hann] {
+            let spectrum = periodogram_rs(&series, DT, None, window).unwrap();
+            let k = peak(&spectrum.power);
+            assert!(
+                (1.0 / spectrum.frequency[k] / DT - 24.0).abs() < 1e-9,
+                "{window:?}"
+            );
+        }
+        // With a rectangular window the power integrates to the variance,
+        // 3² / 2.
+        let spectrum = periodogram_rs(&series, DT, None, Window::Rectangular).unwrap();
+        let df = spectrum.frequency[0];
+        let variance: f64 = spectrum.power.iter().sum::<f64>() * df;
+        assert!((variance - 4.5).abs() < 1e-9, "{variance}");
+        // Welch segments of four days keep the peak at 24 hours.
+        let welch = periodogram_rs(&series, DT, Some(96), Window::Hann).unwrap();
+        assert_eq!(welch.frequency.len(), 48);
+        assert!((1.0 / welch.frequency[peak(&welch.power)] / DT - 24.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wavelet_power_peaks_at_the_same_period() {
+        let series = daily_cycle(32);
+        let voices = 12;
+        let wavelet = morlet_wavelet_rs(&series, DT, voices, None, Some(10.0 * 86400.0)).unwrap();
+        assert_eq!(wavelet.power.len(), wavelet.period.len());
+        assert!(wavelet.period.windows(2).all(|w| w[1] > w[0]));
+        let period = wavelet.period[peak(&wavelet.global)] / DT;
+        // Within one voice of the daily period.
+        assert!(
+            (period / 24.0).log2().abs() <= 1.0 / voices as f64,
+            "{period}"
+        );
+        // Mid-record, where the cone of influence is far away, the local
+        // power peaks at the same scale.
+        let middle = series.len() / 2;
+        let local: Vec<f64> = wavelet.power.iter().map(|row| row[middle]).collect();
+        assert_eq!(peak(&local), peak(&wavelet.global));
+        assert!(wavelet.cone_of_influence[middle] > 10.0 * 86400.0);
+    }
+
+    #[test]
+    fn rejects_invalid_series() {
+        let series = daily_cycle(4);
+        assert!(matches!(
+            periodogram_rs(&series[..3], DT, None, Window::Hann),
+            Err(Error::Data(_))
+        ));
+        let mut gappy = series.clone();
+        gappy[5] = f64::NAN;
+        assert!(matches!(
+            periodogram_rs(&gappy, DT, None, Window::Hann),
+            Err(Error::Data(_))
+        ));
+        assert!(periodogram_rs(&series, DT, Some(200), Window::Hann).is_err());
+        assert!(periodogram_rs(&series, 0.0, None, Window::Hann).is_err());
+        assert!(morlet_wavelet_rs(&series, DT, 0, None, None).is_err());
+        assert!(morlet_wavelet_rs(&series, DT, 4, Some(10.0 * DT), Some(DT)).is_err());
+        assert!(Window::parse("hamming").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Non-parametric trend tests: Mann–Kendall with tie correction and Sen's
+//! slope with Gilbert's (1987) confidence interval. Missing (NaN) values
+//! are skipped, keeping the time index of the others. Both are `O(n²)` in
+//! the series length, so long daily records are best aggregated first.
+
+use crate::ensemble::quantile_sorted;
+use crate::error::{Error, Result};
+use crate::frequency::distributions::{normal_cdf, normal_quantile};
+
+/// Result of a Mann–Kendall test.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MannKendall {
+    /// Kendall's S statistic.
+    pub s: f64,
+    /// Variance of S under the null hypothesis, corrected for ties.
+    pub variance: f64,
+    /// Standard normal test statistic, with continuity correction.
+    pub z: f64,
+    /// Two-sided p-value.
+    pub p_value: f64,
+    /// Kendall's tau.
+    pub tau: f64,
+}
+
+/// Indexed non-NaN values of `series`.
+fn present(series: &[f64]) -> Vec<(f64, f64)> {
+    series
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| !v.is_nan())
+        .map(|(t, &v)| (t as f64, v))
+        .collect()
+}
+
+/// Variance of S for the values of `points`, corrected for tied groups.
+fn s_variance(points: &[(f64, f64)]) -> f64 {
+    let mut values: Vec<f64> = points.iter().map(|p| p.1).collect();
+    values.sort_by(f64::total_cmp);
+    let n = values.len() as f64;
+    let mut ties = 0.0;
+    for group in values.chunk_by(|a, b| a == b) {
+        let t = group.len() as f64;
+        ties += t * (t - 1.0) * (2.0 * t + 5.0);
+    }
+    (n * (n - 1.0) * (2.0 * n + 5.0) - ties) / 18.0
+}
+
+/// Mann–Kendall test for a monotonic trend in `series`.
+pub fn mann_kendall_rs(series: &[f64]) -> Result<MannKendall> {
+    let points = present(series);
+    let n = points.len();
+    if n < 3 {
+        return Err(Error::Data(
+            "At least three values are required for a trend test.".into(),
+        ));
+    }
+    let mut s = 0.0;
+    for i in 0..n {
+        for j in i + 1..n {
+            let difference = points[j].1 - points[i].1;
+            if difference > 0.0 {
+                s += 1.0;
+            } else if difference < 0.0 {
+                s -= 1.0;
+            }
+        }
+    }
+    let variance = s_variance(&points);
+    let z = if variance <= 0.0 {
+        0.0
+    } else if s > 0.0 {
+        (s - 1.0) / variance.sqrt()
+    } else if s < 0.0 {
+        (s + 1.0) / variance.sqrt()
+    } else {
+        0.0
+    };
+    let pairs = (n * (n - 1)) as f64 / 2.0;
+    Ok(MannKendall {
+        s,
+        variance,
+        z,
+        p_value: 2.0 * normal_cdf(-z.abs()),
+        tau: s / pairs,
+    })
+}
+
+/// Sen's slope estimate with its confidence interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SensSlope {
+    /// Median of the pairwise slopes, per time step.
+    pub slope: f64,
+    /// Median of `y - slope · t`, so that the line is `intercept + slope t`
+    /// with `t` the step index.
+    pub intercept: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Sen's slope of `series` with a two-sided interval of coverage
+/// `confidence`.
+pub fn sens_slope_rs(series: &[f64], confidence: f64) -> Result<SensSlope> {
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err(Error::InvalidParameter(
+            "Confidence must be within (0, 1).".into(),
+        ));
+    }
+    let points = present(series);
+    let n = points.len();
+    if n < 3 {
+        return Err(Error::Data(
+            "At least three values are required for a trend test.".into(),
+        ));
+    }
+    let mut slopes = Vec::with_capacity(n * (n - 1) / 2);
+    for i in 0..n {
+        for j in i + 1..n {
+            slopes.push((points[j].1 - points[i].1) / (points[j].0 - points[i].0));
+        }
+    }
+    slopes.sort_by(f64::total_cmp);
+    let slope = quantile_sorted(&slopes, 0.5);
+    let m = slopes.len() as f64;
+    let c = normal_quantile(0.5 + 0.5 * confidence) * s_variance(&points).sqrt();
+    // Ranks (1-based) of the lower and upper limits among the ordered
+    // slopes, interpolated between neighbours.
+    let at_rank = |rank: f64| quantile_sorted(&slopes, ((rank - 1.0) / (m - 1.0)).clamp(0.0, 1.0));
+    let mut residuals: Vec<f64> = points.iter().map(|(t, y)| y - slope * t).collect();
+    residuals.sort_by(f64::total_cmp);
+    Ok(SensSlope {
+        slope,
+        intercept: quantile_sorted(&residuals, 0.5),
+        lower: at_rank((m - c) / 2.0),
+        upper: at_rank((m + c) / 2.0 + 1.0),
+    })
+}
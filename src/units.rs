@@ -0,0 +1,191 @@
+/// Feet to metres.
+pub const FT_TO_M: f64 = 0.3048;
+/// Acres to square metres.
+pub const ACRE_TO_M2: f64 = 4046.8564224;
+/// Square miles to square metres.
+pub const MI2_TO_M2: f64 = 2_589_988.110336;
+/// Pounds to kilograms.
+pub const LB_TO_KG: f64 = 0.45359237;
+
+/// Unit system used for the inputs and outputs of geometry-dependent
+/// functions. All internal computations are carried out in SI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnitSystem {
+    /// Metres, square metres, cubic metres per second.
+    #[default]
+    Si,
+    /// Feet, square feet, cubic feet per second.
+    Us,
+}
+
+impl UnitSystem {
+    /// Parses `"si"` or `"us"` (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "si" | "metric" => Some(UnitSystem::Si),
+            "us" | "imperial" | "english" => Some(UnitSystem::Us),
+            _ => None,
+        }
+    }
+
+    /// Name accepted by [`UnitSystem::parse`]: `"si"` or `"us"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            UnitSystem::Si => "si",
+            UnitSystem::Us => "us",
+        }
+    }
+
+    /// Multiplier converting a length in this unit system to metres.
+    pub fn length_factor(self) -> f64 {
+        match self {
+            UnitSystem::Si => 1.0,
+            UnitSystem::Us => FT_TO_M,
+        }
+    }
+
+    pub fn length_to_si(self, value: f64) -> f64 {
+        value * self.length_factor()
+    }
+
+    pub fn length_from_si(self, value: f64) -> f64 {
+        value / self.length_factor()
+    }
+
+    pub fn area_to_si(self, value: f64) -> f64 {
+        value * self.length_factor().powi(2)
+    }
+
+    pub fn area_from_si(self, value: f64) -> f64 {
+        value / self.length_factor().powi(2)
+    }
+
+    pub fn volume_to_si(self, value: f64) -> f64 {
+        value * self.length_factor().powi(3)
+    }
+
+    pub fn volume_from_si(self, value: f64) -> f64 {
+        value / self.length_factor().powi(3)
+    }
+
+    /// Weir coefficient `C` of `Q = C L H^1.5` (m^0.5/s or ft^0.5/s) to
+    /// m^0.5/s.
+    pub fn weir_coefficient_to_si(self, value: f64) -> f64 {
+        value * self.length_factor().sqrt()
+    }
+
+    /// Weir coefficient from m^0.5/s to m^0.5/s or ft^0.5/s.
+    pub fn weir_coefficient_from_si(self, value: f64) -> f64 {
+        value / self.length_factor().sqrt()
+    }
+
+    /// Catchment area (ha or acres) to m².
+    pub fn land_area_to_si(self, value: f64) -> f64 {
+        match self {
+            UnitSystem::Si => value * 1e4,
+            UnitSystem::Us => value * ACRE_TO_M2,
+        }
+    }
+
+    /// Catchment area from m² to ha or acres.
+    pub fn land_area_from_si(self, value: f64) -> f64 {
+        match self {
+            UnitSystem::Si => value / 1e4,
+            UnitSystem::Us => value / ACRE_TO_M2,
+        }
+    }
+
+    /// Basin area (km² or mi²) to m².
+    pub fn basin_area_to_si(self, value: f64) -> f64 {
+        match self {
+            UnitSystem::Si => value * 1e6,
+            UnitSystem::Us => value * MI2_TO_M2,
+        }
+    }
+
+    /// Basin area from m² to km² or mi².
+    pub fn basin_area_from_si(self, value: f64) -> f64 {
+        match self {
+            UnitSystem::Si => value / 1e6,
+            UnitSystem::Us => value / MI2_TO_M2,
+        }
+    }
+
+    /// Multiplier converting a small depth (mm or in) to metres.
+    pub fn small_depth_factor(self) -> f64 {
+        match self {
+            UnitSystem::Si => 1e-3,
+            UnitSystem::Us => 0.0254,
+        }
+    }
+
+    /// Small depth (rainfall, depression storage: mm or in) to metres.
+    pub fn small_depth_to_si(self, value: f64) -> f64 {
+        value * self.small_depth_factor()
+    }
+
+    /// Small depth from metres to mm or in.
+    pub fn small_depth_from_si(self, value: f64) -> f64 {
+        value / self.small_depth_factor()
+    }
+
+    /// Rainfall or loss rate (mm/h or in/h) to m/s.
+    pub fn rate_to_si(self, value: f64) -> f64 {
+        self.small_depth_to_si(value) / 3600.0
+    }
+
+    /// Rainfall or loss rate from m/s to mm/h or in/h.
+    pub fn rate_from_si(self, value: f64) -> f64 {
+        self.small_depth_from_si(value * 3600.0)
+    }
+
+    /// Discharge (volume per second) to m³/s.
+    pub fn discharge_to_si(self, value: f64) -> f64 {
+        self.volume_to_si(value)
+    }
+
+    /// Discharge from m³/s to this unit system.
+    pub fn discharge_from_si(self, value: f64) -> f64 {
+        self.volume_from_si(value)
+    }
+
+    /// Velocity (length per second) to m/s.
+    pub fn velocity_to_si(self, value: f64) -> f64 {
+        self.length_to_si(value)
+    }
+
+    /// Velocity from m/s to this unit system.
+    pub fn velocity_from_si(self, value: f64) -> f64 {
+        self.length_from_si(value)
+    }
+
+    /// Mass (kg or lb) to kg; also applies to mass rates per second.
+    pub fn mass_to_si(self, value: f64) -> f64 {
+        match self {
+            UnitSystem::Si => value,
+            UnitSystem::Us => value * LB_TO_KG,
+        }
+    }
+
+    /// Mass from kg to kg or lb.
+    pub fn mass_from_si(self, value: f64) -> f64 {
+        match self {
+            UnitSystem::Si => value,
+            UnitSystem::Us => value / LB_TO_KG,
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> pyo3::FromPyObject<'py> for UnitSystem {
+    fn extract_bound(ob: &pyo3::Bound<'py, pyo3::PyAny>) -> pyo3::PyResult<Self> {
+        use pyo3::types::PyAnyMethods;
+
+        let name: String = ob.extract()?;
+        UnitSystem::parse(&name).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown unit system `{name}`; expected \"si\" or \"us\"."
+            ))
+        })
+    }
+}
@@ -0,0 +1,265 @@
+//! WebAssembly exports through `wasm-bindgen`.
+//!
+//! Series cross the boundary as `Float64Array`s (`&[f64]` in, `Vec<f64>`
+//! out) and errors are thrown as JavaScript `Error`s carrying the message of
+//! the corresponding [`Error`](crate::error::Error).
+
+use wasm_bindgen::prelude::*;
+
+use crate::hydraulics::manning::TrapezoidalChannel;
+use crate::hydrograph::{design, lag, signatures};
+use crate::reach_routing::kinematic_wave::{kinematic_wave_routing_rs, KinematicWaveReach};
+use crate::reach_routing::muskingum::{check_muskingum, muskingum_cascade_rs};
+use crate::units::UnitSystem;
+
+fn unit_system(units: Option<String>) -> Result<UnitSystem, JsError> {
+    match units {
+        None => Ok(UnitSystem::Si),
+        Some(name) => UnitSystem::parse(&name)
+            .ok_or_else(|| JsError::new(&format!("Unknown unit system `{name}`."))),
+    }
+}
+
+fn channel(
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    units: UnitSystem,
+) -> Result<TrapezoidalChannel, JsError> {
+    Ok(TrapezoidalChannel::from_units(
+        bottom_width,
+        side_slope,
+        n,
+        slope,
+        units,
+    )?)
+}
+
+/// Muskingum routing through `sub_reaches` equal sub-reaches.
+///
+/// `dt` and `k` are in seconds. Without `initial_outflow` the routing starts
+/// from the first inflow value.
+#[wasm_bindgen]
+pub fn muskingum(
+    inflow: &[f64],
+    dt: f64,
+    k: f64,
+    x: f64,
+    sub_reaches: Option<u32>,
+    initial_outflow: Option<f64>,
+    allow_negative_c0: Option<bool>,
+) -> Result<Vec<f64>, JsError> {
+    let sub_reaches = sub_reaches.unwrap_or(1);
+    check_muskingum(
+        inflow,
+        dt,
+        k,
+        x,
+        sub_reaches as i64,
+        allow_negative_c0.unwrap_or(false),
+    )?;
+    Ok(muskingum_cascade_rs(
+        inflow,
+        dt,
+        k,
+        x,
+        sub_reaches as usize,
+        initial_outflow,
+    ))
+}
+
+/// Kinematic wave routing of `inflow` through a trapezoidal channel of
+/// `length`, starting dry.
+///
+/// Flows, lengths and widths are in `units` (`"si"` by default, or `"us"`);
+/// `dt` is in seconds.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn kinematic_wave(
+    inflow: &[f64],
+    dt: f64,
+    length: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    segments: Option<u32>,
+    units: Option<String>,
+) -> Result<Vec<f64>, JsError> {
+    let units = unit_system(units)?;
+    if dt.is_nan() || dt <= 0.0 {
+        return Err(JsError::new("The time step must be positive."));
+    }
+    let channel = channel(bottom_width, side_slope, n, slope, units)?;
+    let mut reach = KinematicWaveReach::new(
+        channel,
+        units.length_to_si(length),
+        segments.unwrap_or(10) as usize,
+    )?;
+    let inflow: Vec<f64> = inflow.iter().map(|&q| units.discharge_to_si(q)).collect();
+    Ok(kinematic_wave_routing_rs(&inflow, dt, &mut reach)
+        .into_iter()
+        .map(|q| units.discharge_from_si(q))
+        .collect())
+}
+
+/// Manning normal-flow discharge of a trapezoidal channel at `depth`.
+#[wasm_bindgen]
+pub fn manning_discharge(
+    depth: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    units: Option<String>,
+) -> Result<f64, JsError> {
+    let units = unit_system(units)?;
+    let channel = channel(bottom_width, side_slope, n, slope, units)?;
+    Ok(units.discharge_from_si(channel.discharge(units.length_to_si(depth))))
+}
+
+/// Manning normal depth of a trapezoidal channel carrying `discharge`.
+#[wasm_bindgen]
+pub fn normal_depth(
+    discharge: f64,
+    bottom_width: f64,
+    side_slope: f64,
+    n: f64,
+    slope: f64,
+    units: Option<String>,
+) -> Result<f64, JsError> {
+    let units = unit_system(units)?;
+    let channel = channel(bottom_width, side_slope, n, slope, units)?;
+    Ok(units.length_from_si(channel.normal_depth(units.discharge_to_si(discharge))))
+}
+
+/// Names of the values returned by [`flow_signatures`], in order.
+#[wasm_bindgen]
+pub fn signature_names() -> Vec<String> {
+    signatures::SIGNATURES
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Hydrologic signatures of a flow series with steps of `dt` seconds, in
+/// the order of [`signature_names`].
+#[wasm_bindgen]
+pub fn flow_signatures(
+    flows: &[f64],
+    dt: f64,
+    precipitation: Option<Vec<f64>>,
+) -> Result<Vec<f64>, JsError> {
+    Ok(signatures::signatures_rs(
+        flows,
+        precipitation.as_deref(),
+        dt,
+    )?)
+}
+
+/// Base flow separated with the Lyne–Hollick filter.
+#[wasm_bindgen]
+pub fn baseflow(flows: &[f64]) -> Vec<f64> {
+    signatures::lyne_hollick(flows)
+}
+
+/// Cross-correlation between an upstream and a downstream gauge.
+#[wasm_bindgen]
+pub struct LagCorrelation {
+    inner: lag::LagCorrelation,
+}
+
+#[wasm_bindgen]
+impl LagCorrelation {
+    /// Lags in steps, from `-max_lag` to `max_lag`.
+    #[wasm_bindgen(getter)]
+    pub fn lags(&self) -> Vec<f64> {
+        self.inner.lags.iter().map(|&l| l as f64).collect()
+    }
+
+    /// Pearson correlation at each lag.
+    #[wasm_bindgen(getter)]
+    pub fn correlation(&self) -> Vec<f64> {
+        self.inner.correlation.clone()
+    }
+
+    /// Lag of the highest correlation.
+    #[wasm_bindgen(getter)]
+    pub fn best_lag(&self) -> f64 {
+        self.inner.best_lag as f64
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn best_correlation(&self) -> f64 {
+        self.inner.best_correlation
+    }
+
+    /// Best lag refined to fractional steps.
+    #[wasm_bindgen(getter)]
+    pub fn refined_lag(&self) -> f64 {
+        self.inner.refined_lag
+    }
+}
+
+/// Correlates `downstream[t + lag]` with `upstream[t]` for every lag up to
+/// `max_lag` steps either way.
+#[wasm_bindgen]
+pub fn lag_correlation(
+    upstream: &[f64],
+    downstream: &[f64],
+    max_lag: u32,
+) -> Result<LagCorrelation, JsError> {
+    Ok(LagCorrelation {
+        inner: lag::lag_correlation_rs(upstream, downstream, max_lag as usize)?,
+    })
+}
+
+/// Scales the flow above `baseflow` so that the peak equals `peak`.
+#[wasm_bindgen]
+pub fn scale_to_peak(
+    hydrograph: &[f64],
+    peak: f64,
+    baseflow: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    Ok(design::scale_to_peak(
+        hydrograph,
+        peak,
+        baseflow.unwrap_or(0.0),
+    )?)
+}
+
+/// Scales the flow above `baseflow` so that the volume above it equals
+/// `volume` with steps of `dt` seconds.
+#[wasm_bindgen]
+pub fn scale_to_volume(
+    hydrograph: &[f64],
+    volume: f64,
+    dt: f64,
+    baseflow: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    Ok(design::scale_to_volume(
+        hydrograph,
+        volume,
+        dt,
+        baseflow.unwrap_or(0.0),
+    )?)
+}
+
+/// Balanced design hydrograph whose maximum mean flow over each of
+/// `durations` (in steps) equals the matching value of `flows`.
+#[wasm_bindgen]
+pub fn balanced_hydrograph(
+    durations: &[u32],
+    flows: &[f64],
+    pattern: Option<Vec<f64>>,
+    peak_position: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    let durations: Vec<usize> = durations.iter().map(|&d| d as usize).collect();
+    Ok(design::balanced_hydrograph_rs(
+        &durations,
+        flows,
+        pattern.as_deref(),
+        peak_position.unwrap_or(0.5),
+    )?)
+}